@@ -0,0 +1,51 @@
+uplink = Uplink
+    .unknown = غير معروف
+    .home = الرئيسية
+    .add = إضافة
+    .added = تمت الإضافة!
+    .add-members = إضافة أعضاء
+    .current-members = الأعضاء الحاليون
+    .call = اتصال
+    .users = المستخدمون
+    .groups = المجموعات
+    .members = الأعضاء
+    .members-count = الأعضاء ({ $num })
+    .reset-account = إعادة تعيين الحساب (لا يمكن التراجع!)
+    .coming-soon = قريبًا
+    .chat = محادثة
+    .chats = المحادثات
+    .send = إرسال
+    .remove = إزالة
+    .cancel = إلغاء
+    .clear-unreads = تعليم الكل كمقروء
+    .unread-badge = { $count } رسائل غير مقروءة
+    .delete-group-chat = حذف المجموعة
+    .leave-group = مغادرة المجموعة
+    .delete-conversation = حذف المحادثة
+    .hide-chat = إخفاء المحادثة
+    .search-placeholder = بحث...
+    .nothing-here = لا يوجد شيء هنا...
+    .end = إنهاء
+    .username = اسم المستخدم
+    .status = رسالة الحالة
+    .delete = حذف
+    .dismiss = تجاهل
+    .check-for-updates = التحقق من وجود تحديثات
+    .download-update = تنزيل التحديث
+    .date-time-format = %d/%m/%Y %I:%M %p
+    .copy-text = نسخ النص
+    .copy = نسخ
+    .paste = لصق
+    .go-back = رجوع
+    .upload-queue = قائمة الرفع
+    .download-queue = قائمة التنزيل
+    .copy-seed = نسخ إلى الحافظة
+    .copied-seed = تم النسخ إلى الحافظة
+
+accessibility = إمكانية الوصول
+    .new-message = { $user }: { $text }
+    .incoming-call = مكالمة واردة من { $user }
+    .call-cancelled = أُلغيت المكالمة.
+    .joined-call = انضم { $user } إلى المكالمة.
+    .left-call = غادر { $user } المكالمة.
+    .call-ended = انتهت المكالمة.
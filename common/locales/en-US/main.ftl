@@ -10,6 +10,7 @@ uplink = Uplink
     .groups = Groups
     .members = Members
     .members-count = Members ({ $num })
+    .messages = Messages
     .reset-account = Reset Account (Irreversible!)
     .coming-soon = Coming soon
     .chat = Chat
@@ -18,6 +19,7 @@ uplink = Uplink
     .remove = Remove
     .cancel = Cancel
     .clear-unreads = Clear Unreads
+    .unread-badge = { $count } unread messages
     .delete-group-chat = Delete Group
     .leave-group = Leave Group
     .delete-conversation = Delete Chat
@@ -34,6 +36,8 @@ uplink = Uplink
     .update-available = Update available: { $version }
     .update-downloading = Downloading update: { $progress }%
     .update-downloaded = Update downloaded. Click to install
+    .update-installer = Uplink update
+    .update-failed = The update could not be downloaded or verified.
     .update-menu-dismiss = Dismiss
     .update-menu-download = Download
     .update-menu-install = Install
@@ -46,6 +50,7 @@ uplink = Uplink
     .copy = Copy
     .paste = Paste
     .go-back = Go Back
+    .continue = Continue
     .upload-queue = Upload queue
     .download-queue = Download Queue
     .copy-seed = Copy to Clipboard
@@ -64,6 +69,15 @@ updates = Updates
     .instruction4 = 4. Open Uplink and start chatting again!
     .instruction5 = *We are going to streamline this process in a future update.
     .download-label = Download Update
+    .install-title = Install Uplink { $version }?
+    .install-description = Uplink will close and the installer will open. Your account and messages are kept.
+    .install-later = Later
+
+safe-mode = Safe Mode
+    .title = Safe Mode
+    .active = Uplink is running in safe mode. Extensions, custom themes and animations are disabled. Restart Uplink to leave safe mode.
+    .offer-title = Uplink failed to start
+    .offer-description = Uplink didn't start correctly the last few times. Do you want to start in safe mode? Extensions, custom themes and animations will be disabled until the next restart.
 
 warning-messages = Warning Messages
     .please-enter-at-least-one = Please enter at least 1 character.
@@ -84,6 +98,24 @@ messages = Messages
     .say-something-placeholder = Say Something...
     .user-sent-message = { $user } sent you a message.
     .unknown-sent-message = someone sent you a message.
+    .new-room-mention = New Group Mention
+    .gifs = GIFs
+    .search-gifs = Search GIFs
+    .no-gifs = No GIFs found.
+    .gifs-disabled = Choose a GIF provider in the settings to search GIFs.
+    .gif-search-failed = Searching GIFs failed. Check the GIF provider in the settings.
+    .gif-download-failed = The GIF could not be downloaded.
+    .no-spelling-suggestions = No suggestions
+    .add-to-dictionary = Add to Dictionary
+    .expand-editor = Expand editor
+    .collapse-editor = Collapse editor
+    .expanded-editor-hint = { $keys } to send, Esc to go back to the chatbar.
+    .call-started-by-you = You started a call
+    .call-started = { $user } started a call
+    .call-ended = Call ended after { $duration }
+    .call-missed = Missed call from { $user }
+    .call-declined = You declined a call from { $user }
+    .user-mentioned-room = { $user } mentioned { $mention }.
     .msg-banner = Messages are secured by end-to-end encryption and sent over a peer-to-peer network.
     .create-group-chat = Create Group Chat
     .group-name = Group Name
@@ -93,11 +125,109 @@ messages = Messages
     .group-name-invalid = Group Name Invalid
     .fetching = Fetching more messages...
     .group-creator-label = Group Creator
+    .group-admin = Admin
+    .group-moderator = Moderator
+    .group-member = Member
+    .mute-member = Mute
+    .unmute-member = Unmute
+    .not-allowed = You don't have permission to do this in this group.
+    .invite-links = Invite Links
+    .invite-link = Invite or Handoff Link
+    .create-invite = Create Link
+    .invite-copied = Invite link copied!
+    .invite-uses = Used { $num }
+    .invite-max-uses = { $num } uses
+    .invite-unlimited-uses = No limit
+    .invite-expires-never = Never expires
+    .invite-expires-hour = Expires in 1 hour
+    .invite-expires-day = Expires in 1 day
+    .invite-expires-week = Expires in 7 days
+    .join-group = Join via Link
+    .join-request-sent = Join request sent. You'll be added once the inviter is online.
+    .invalid-invite = This invite link is invalid.
+    .invite-inviter-not-found = The creator of this invite link could not be found.
+    .invite-inviter-blocked = You blocked the creator of this invite link.
+    .join-request-failed = The join request could not be sent. Try again later.
+    .new-message = New Message
+    .recipient-did = Recipient DID
+    .new-message-description = You don't have to be friends first. Whether they receive your messages depends on their privacy settings.
+    .start-conversation = Start Conversation
+    .recipient-not-found = No one with this DID could be found.
+    .start-conversation-failed = The conversation could not be started.
+    .handoff = Continue on Another Device
+    .handoff-description = Scan this code or paste the link in "Join via Link" on another device of your account. The chat opens where you left it.
+    .handoff-copied = Link copied!
+    .handoff-not-found = This conversation isn't on this device yet. Try again once it synced.
+    .edit-title = Edit Title & Topic
+    .chat-title = Title (only visible to you)
+    .language-auto = Auto detect
+    .language-auto-detected = Auto detect ({ $language })
+    .chat-topic = Topic
+    .show-more-members = Show { $num } more
+    .notification-settings = Notification Settings
+    .appearance = Appearance
+    .chat-background = Background
+    .chat-wallpaper = Wallpaper
+    .pick-wallpaper = Choose Image
+    .wallpaper-too-large = The image is too large for a wallpaper, the limit is 8 MB.
+    .chat-bubble = Bubble Color
+    .theme-color = Use the theme color
+    .bubble-low-contrast = This bubble color is too close to the background and is not used.
+    .reset-appearance = Reset Appearance
+    .webhooks = Webhooks
+    .incoming-webhooks = Bots
+    .incoming-webhooks-description = Scripts and services post into this chat through the address of a bot. Messages are sent from your account under the name of the bot. Anyone who knows the address can post, so keep it private.
+    .bot-name = Bot name
+    .add-bot = Add Bot
+    .copy-bot-url = Copy Address
+    .bot-url-copied = Address copied to the clipboard.
+    .bot-port-taken = Port { $taken } was taken by another program, so bots now post to port { $port }. Copy their addresses again. If you don't trust that program, remove the bots and add them again, since it may have seen their addresses.
+    .bots-failed = Bots can't post, the address for them couldn't be opened: { $error }
+    .bots-try-again = Try Again
+    .outgoing-webhook = Forward New Messages
+    .outgoing-webhook-description = New messages in this chat are posted as JSON to this address on your device or local network, signed like the webhooks in the notification settings. Leave it empty to stop.
+    .reset-zoom = Reset the text size of this chat
+    .new-folder = New Folder
+    .rename-folder = Rename Folder
+    .delete-folder = Delete Folder
+    .folder-name = Folder name
+    .save-folder = Save
+    .move-to-folder = Move to { $folder }
+    .remove-from-folder = Remove from Folder
+    .open-in-split-view = Open in Split View
+    .drop-to-split = Drop here to open it next to this chat
+    .split-swap = Make this the main chat
+    .split-close = Close Split View
+    .mute-conversation = Mute notifications
+    .not-muted = Not muted
+    .mute-hour = For 1 hour
+    .mute-eight-hours = For 8 hours
+    .mute-forever = Until I turn it back on
+    .muted-until = Until { $time }
+    .mentions-only = Only notify me when I'm mentioned
+    .notification-sound = Notification sound
+    .sound-default = Default
+    .sound-flip = Flip
+    .sound-interaction = Interaction
+    .sound-on = On
+    .sound-off = Off
+    .export-chat = Export Chat
+    .export-format = Format
+    .export-json = JSON
+    .export-plaintext = Plain Text
+    .export-html = HTML
+    .export-attachments = Include attachments
+    .export = Export
+    .export-progress = Exported { $num } of { $total } messages
+    .export-finished = Exported { $num } messages to { $file }
+    .export-failed = Failed to export the conversation.
     .user-typing = { $user } is typing
     .users-typing = { $users } are typing
     .users-multiple-typing = Multiple users are typing
     .maximum-amount-files-per-message = You reached { $amount } files per message limit
     .scroll-bottom = Scroll to bottom
+    .scroll-bottom-new = Jump to latest ({ $count } new)
+    .unread-divider = New Messages
     .pin = Pin Message
     .unpin = Unpin Message
     .pin-view = Pinned Messages
@@ -105,20 +235,52 @@ messages = Messages
     .pin-button-unpin = Unpin
     .pinned-none = There are no pinned messages in this chat
     .pinned-max = You reached the maximum amount of pinned messages for this chat
+    .read-by = Read By
+    .read-by-none = Nobody has read this message yet.
+    .forward = Forward
+    .forwarded-from = Forwarded from { $user }:
+    .forwarded = Message forwarded to { $num } chats.
+    .not-sent-to-chats = Not sent to { $num } of the chats: { $error }
+    .forward-failed = The message wasn't forwarded, an attachment couldn't be downloaded: { $error }
     .missing-emoji-picker = Emoji extension is disabled
     .unknown-identity = User not found:
     .emoji-suggestion = Suggested Emoji
     .username-suggestion = Suggested Users
+    .command-suggestion = Commands
     .control-group = More
     .no-chats = No chats available
     .attachments-fail = Upload Failed!
     .attachments-fail-msg = Upload failed: { $reason }
     .attachments-fail-no-storage = No more storage left!
+    .copy-code = Copy
+    .code-copied = Copied!
+    .copy-code-failed = Press Ctrl+C to copy
+    .queued = Waiting to send
+    .queued-failed = Couldn't send
+    .cancel-queued = Don't Send
+    .retry-queued = Try Again
     
+slash-commands = Commands
+    .shrug = Appends ¯\_(ツ)_/¯ to your message
+    .me = Describes what you are doing
+    .giphy = Searches for a GIF
+    .file = Attaches a file from your computer, or from your storage
+
+conversation-panel = Conversation Details
+    .show = Show Details
+    .hide = Hide Details
+    .media = Shared Media
+    .media-none = No files shared in the loaded messages
+    .tasks = Tasks
+    .tasks-none = Start a line with "- [ ]" to add a task
+    .members = Members
+
 favorites = Favorites
     .favorites = Favorites
     .add = Add to Favorites
     .remove = Remove from Favorites
+    .pin = Pin to Sidebar
+    .unpin = Unpin from Sidebar
 
 friends = Friends
     .friends = Friends
@@ -135,6 +297,9 @@ friends = Friends
     .view-group = View Group
     .cannot-add-self = Can't add yourself, silly!
     .incoming_requests = Incoming Requests
+    .accept-all = Accept All
+    .deny-all = Deny All
+    .bulk-progress = { $current } of { $total }
     .outgoing_requests = Outgoing Requests
     .cancel = Cancel Request
     .requested = Requested 
@@ -159,6 +324,7 @@ friends = Friends
     .already-friends = Already Friends
     .nothing-to-see-here = Nothing to see here
     .share = Share DID
+    .notification-sound = Notification Sound
     .select-chat = Select Chat
     .share-to-chat = Share
     .search = Search Friends
@@ -166,11 +332,14 @@ friends = Friends
 
 files = Files
     .file = File
+    .delete-items = Delete Items
+    .delete-items-confirm = Delete the { $num } selected items? This can't be undone.
     .files = Files
     .new-folder = New Folder
     .upload = Upload
     .storage-max-size = Max Size:
     .storage-current-size = Used Space:
+    .folder-size = This Folder:
     .rename = Rename
     .download = Download
     .thumbnail-uploaded = Thumbnail Uploaded
@@ -180,6 +349,13 @@ files = Files
     .no-thumbnail-preview = No Thumbnail available for preview
     .not-possible-to-preview-file = This file type is currently not supported in preview
     .file-already-opened = File already opened
+    .compare = Compare
+    .move-up = Move up
+    .start-next = Start next
+    .compare-with = Compare with...
+    .show-hidden-folders = Show all folders
+    .copy-path = Copy path
+    .path-copied = Path copied to clipboard
     .directory-already-with-name = There is already a directory with this name
     .no-size-available = No size available for file: { $file }
     .file-already-with-name = There is already a file with this name
@@ -200,21 +376,81 @@ files = Files
     .download-failed = Failed to download file { $file }
     .download-success = Downloaded file { $file }
     .transfer-start = Starting...
+    .transfer-queued = Waiting for the other uploads...
     .transfer-progress-upload = Upload: { $progress }% ({ $size } / { $total })
     .transfer-progress-download = Download: { $progress }% ({ $size } / { $total })
     .transfer-paused = Paused: { $progress }% ({ $size } / { $total })
     .transfer-finishing = Finishing...
     .transfer-cancelling = Cancelling...
     .transfer-error = { $error }
+    .transfer-verification-failed = The file doesn't match the one that was uploaded
+    .announce-queued = { $file } is waiting for the other uploads
+    .announce-upload-started = Uploading { $file }
+    .announce-download-started = Downloading { $file }
+    .announce-progress = { $file }: { $percent }%
+    .announce-finishing = { $file }: finishing
+    .announce-paused = { $file }: paused at { $percent }%
+    .announce-resumed = { $file }: resumed
+    .announce-cancelling = { $file }: cancelling
+    .announce-cancelled = { $file }: cancelled
+    .announce-failed = { $file }: failed
+    .announce-verification-failed = { $file }: doesn't match the uploaded file
+    .history = Transfer History
+    .history-search = Search by file name
+    .history-all = All
+    .history-uploads = Uploads
+    .history-downloads = Downloads
+    .history-any-outcome = Any Result
+    .history-completed = Completed
+    .history-failed = Failed
+    .history-empty = No transfers yet.
+    .history-conversation = In { $name }
+    .history-clear = Clear History
+    .announce-upload-done = { $file } was uploaded
+    .announce-download-done = { $file } was downloaded
     .direct-message-name = DM: { $with }
 
+activity = Activity
+    .activity = Activity
+    .all = All
+    .messages = Muted Chats
+    .reactions = Reactions
+    .friends = Friends
+    .transfers = Transfers
+    .message = { $name } wrote in { $chat }
+    .reaction = { $name } reacted { $emoji } to your message in { $chat }
+    .friend-accepted = { $name } accepted your friend request
+    .transfer = { $file } finished transferring
+    .transfer-in = { $file } finished transferring in { $chat }
+    .empty = Nothing has happened yet.
+    .clear = Clear Activity
+    .conversation-not-found = This conversation no longer exists.
+
+calls = Calls
+    .calls = Calls
+    .all = All
+    .missed = Missed
+    .incoming = Incoming
+    .outgoing = Outgoing
+    .incoming-call = Incoming call
+    .outgoing-call = Outgoing call
+    .missed-call = Missed call
+    .declined-call = Declined call
+    .ongoing = In progress
+    .duration = Lasted { $duration }
+    .from = From { $user }
+    .call-back = Call Back
+    .empty = No calls yet.
+    .clear = Clear Call History
+    .conversation-not-found = This conversation no longer exists.
+
 settings = Settings
     .settings = Settings
     .profile = Profile
     .general = General 
     .messages = Messages
     .privacy = Privacy
-    .audio = Sounds & Audio
+    .audio = Audio & Video
     .files = Files
     .extensions = Extensions
     .keybinds = Keyboard Shortcuts
@@ -223,10 +459,29 @@ settings = Settings
     .developer = Developer
     .about = About
     .licenses = Licenses
+    .network = Network
+    .devices = Linked Devices
+    .storage = Storage
     .search-placeholder = Search Settings...
     .please-select-area-you-want-to-crop = Please select the area you want to crop
     .no-extensions-installed = No extensions installed
 
+settings-devices = Linked Devices
+    .link = Link a Device
+    .link-description = Share this account with another device on the same network. The other device chooses "Link to a Device" when creating an account.
+    .pairing-instructions = Enter this pairing code on the new device. It is valid for 5 minutes and can only be used once.
+    .confirm = { $name } wants to use your account. Only allow devices you own.
+    .allow = Allow
+    .deny = Deny
+    .sending = Sending your account to { $name }...
+    .linked = Device linked.
+    .link-failed = The device could not be linked.
+    .none = No devices are linked yet.
+    .linked-at = Linked on { $date }
+    .revoke = Revoke
+    .revoked = Device revoked. It deletes the account the next time it logs in and has received the change.
+    .revoke-failed = The device could not be revoked.
+
 settings-profile = Profile Settings
     .failed = Failed to update profile
     .change-banner = Change banner
@@ -240,11 +495,30 @@ settings-profile = Profile Settings
     .copy-id = Copy ID
     .copy-did = Copy DID Key
     .online-status = Online Status
-    .online-status-description = Set the appearance of your online status
+    .online-status-description = Set the appearance of your online status. Busy mutes notification sounds, Invisible appears offline to others while you still receive messages.
+    .pronouns = Pronouns
+    .pronouns-placeholder = e.g. they/them
+    .about = About
+    .about-placeholder = A few words about yourself
+    .links = Links
+    .links-placeholder = Up to { $max } web addresses, separated by spaces
+    .status-expiry = Clear Status After
+    .status-expiry-description = Your status is cleared automatically after this time. Changing it sets your status again.
+    .status-expires-at = Your status is cleared at { $time }.
+    .expiry-never = Don't clear
+    .expiry-30m = 30 minutes
+    .expiry-1h = 1 hour
+    .expiry-today = Today
     .status-online = Online
     .status-idle = Idle
     .status-do-not-disturb = Do not disturb
     .status-offline = Offline
+    .status-invisible = Invisible
+    .auto-away = Away When Inactive
+    .auto-away-description = Set your status to Idle when you haven't used the keyboard or mouse for a while, and back to Online when you return. Busy and Invisible are left as they are.
+    .away-after = Inactive For
+    .away-after-description = How long without input before your status changes to Idle.
+    .away-after-minutes = { $minutes } minutes
     .recovery-seed = Recovery Seed
     .recovery-seed-description = This seed represents the "master key" for your account. Keep this safe and secure somewhere in order to maintain proper control and security over your Uplink account.
     .reveal-recovery-seed = Reveal Recovery Seed
@@ -252,32 +526,112 @@ settings-profile = Profile Settings
     .store-on-account = Store recovery seed on account (disable this for increased security)
     .remove-recovery-seed = Remove Seed Phrase
     .remove-recovery-seed-description = Removing the seed phrase from storage can increase security on your account. However, this action is irreversable and if you have not backed up your seed phrase yet you should select 'Cancel' below.
+    .backup = Backup
+    .backup-description = Export an encrypted backup of your account, friends, conversations and settings. It can be restored from the account creation screen.
+    .export-backup = Export Backup
+    .backup-passphrase = Passphrase
+    .backup-passphrase-confirm = Confirm Passphrase
+    .backup-passphrase-description = The backup is encrypted with this passphrase. You will need it and your current PIN to restore the backup.
+    .backup-passphrase-short = The passphrase needs at least 8 characters.
+    .backup-passphrase-mismatch = The passphrases don't match.
+    .backup-exported = Backup exported.
+    .backup-export-failed = The backup could not be exported.
 
 settings-general = General Settings
     .overlay = Uplink Overlay
     .overlay-description = Enable the on screen Uplink overlay. This will show active call information, as well as allow you to add custom widgets to your screen.
     .app-language = App Language
-    .change-language = Change Language.
+    .change-language = Change Language. Translations made by the community can be added to the locales folder.
+    .reload-translations = Reload the translations of the locales folder
+    .open-locales-folder = Open the locales folder
+    .translations-loaded = Community translations found: { $count }
     .theme = Theme
     .theme-description = Change the theme of the app.
+    .edit-theme = Edit theme colors
+    .import-theme = Import theme file
+    .export-theme = Export theme file
+    .theme-imported = Theme imported.
+    .theme-import-failed = This theme file could not be imported.
+    .theme-export-failed = The theme could not be exported.
+    .theme-name = Theme Name
+    .my-theme = My Theme
+    .save-theme = Save Theme
+    .theme-saved = Saved { $theme }.
+    .theme-save-failed = The theme could not be saved. Its name can't contain slashes or dots.
     .font-scaling = Font Scaling
     .font-scaling-description = Scale the font size up or down to your liking.
+    .monitor-scaling = Display Scaling
+    .monitor-scaling-description = Scales the whole interface on the display Uplink is currently on. Each display remembers its own setting.
     .font = Font
     .font-description = Change the font of the app.
+    .default-font = Default
+    .import-font = Import font file
+    .font-imported = Imported { $font }.
+    .font-import-failed = This font file could not be imported.
+    .message-font = Message Font
+    .message-font-description = Change the font used for messages.
+    .monospace-font = Monospace Font
+    .monospace-font-description = Change the font used for code blocks.
     .clear-accent = Clear accent color
+    .open-items = Open Files And Folders
+    .open-items-description = With a double click, a single click selects the item instead. Hold Ctrl (Cmd on macOS) or Shift to select several.
+    .single-click = Single click
+    .double-click = Double click
 
 settings-messages = Message Settings
     .emoji-conversion = Convert Emoji
     .emoji-conversion-description = Convert Emoji text like ':)' into an emoji symbol like '😊'.
+    .emoji-style = Emoji Style
+    .emoji-style-description = Use the emojis of your operating system, or Uplink's own emoji set so they look the same on every platform.
+    .emoji-style-native = System
+    .emoji-style-bundled = Uplink
+    .gif-provider = GIF Provider
+    .gif-provider-description = Where the GIF picker searches for GIFs. Your searches are sent to this provider.
+    .gif-provider-disabled = Disabled
+    .gif-provider-self-hosted = Self hosted
+    .gif-api-key = API Key
+    .gif-api-key-description = The API key of your account with the GIF provider.
+    .gif-endpoint = Search Endpoint
+    .gif-endpoint-description = Called with the parameters q and limit, it should return a list of GIFs with an id, url and preview_url.
+    .gif-attachment = Send GIFs As Files
+    .gif-attachment-description = Downloads the GIF and sends it as an attachment instead of sending a link to it.
     .markdown-support = Markdown support
     .markdown-support-description = Enables the support of the Markdown markup language in messaging. 
+    .merge-duplicates = Merge Duplicate Conversations
+    .merge-duplicates-description = Combines direct conversations with the same user into a single thread. Found { $num } duplicate(s).
+    .merge = Merge
+    .merged-duplicates = Merged { $num } conversation(s).
+    .dictionaries = Spellcheck Dictionaries
+    .dictionaries-description = Misspelled words are underlined using the Hunspell dictionary of the language of the conversation. Put the .aff and .dic files of a language in the dictionaries folder, named after it like en-US.aff and en-US.dic.
+    .reload-dictionaries = Reload dictionaries
+    .open-dictionaries-folder = Open dictionaries folder
+    .dictionaries-reloaded = Dictionaries reloaded.
+    .personal-dictionary = Personal Dictionary
+    .personal-dictionary-description = Words which are never marked as misspelled. Right click a misspelled word to add it.
+    .add-word = Add a word
 
 settings-privacy = Settings Privacy 
     .backup-recovery-phrase = Backup Recovery Phrase
     .backup-phrase = Backup Phrase
     .backup-phrase-description = Back this phrase up! Along with your password this represents your account. If you lose it, we can't help you get it back.
+    .read-receipts = Read Receipts
+    .read-receipts-description = Let others know when you've read their messages. If disabled, you won't see when others have read yours either.
+    .typing-indicators = Typing Indicators
+    .typing-indicators-description = Let others know when you are typing a message.
+    .keychain-unlock = Unlock with the System Keychain
+    .keychain-unlock-description = Save your pin encrypted in the keychain of your operating system the next time you unlock Uplink. Touch ID, Windows Hello or your system password is asked for instead of the pin. Not used while a duress pin is set.
+    .duress-pin = Duress PIN
+    .duress-pin-description = A second pin for when someone forces you to unlock Uplink. Entering it and pressing enter on the unlock screen triggers the action below instead of unlocking your account.
+    .duress-pin-placeholder = New duress pin
+    .duress-pin-save = Save
+    .duress-pin-remove = Remove
+    .duress-pin-saved = The duress pin was saved.
+    .duress-action = When the Duress PIN Is Entered
+    .duress-action-description = Wiping deletes the account, messages and settings of this profile from this device. The files are deleted, not securely erased, so they may still be recoverable from the disk. The decoy is an empty account which is created the first time it is opened.
+    .duress-action-wipe = Wipe this profile
+    .duress-action-decoy = Open a decoy account
 
-settings-audio = Audio & Sound Settings 
+settings-audio = Audio & Video Settings
     .input-device = Input Device
     .input-device-description = Select your input device (microphone, usually).
     .output-device = Output Device
@@ -289,6 +643,9 @@ settings-audio = Audio & Sound Settings
     .noise-suppression-description = Helps to minimize background noise and focus on your voice.
     .echo-cancellation = Echo cancellation
     .echo-cancellation-description = Helps to minimize feedback from speakers into your microphone.
+    .mic-too-quiet = Too quiet, move closer to the microphone or raise its volume.
+    .mic-good = Sounds good.
+    .mic-too-loud = Too loud, move away from the microphone or lower its volume.
     .call-timer = Call Timer
     .call-timer-description = When enabled a timer will display when you're in a call showing it's duration.
     .interface-sounds = Interface Sounds
@@ -297,8 +654,74 @@ settings-audio = Audio & Sound Settings
     .media-sounds-description = When enabled, media related events such as toggling microphone or headphones and other real time events, will play sounds.
     .message-sounds = Message Sounds
     .message-sounds-description = When enabled you will hear a notification when a new message is received.
+    .camera = Camera
+    .camera-description = The camera shown in the preview during calls. Video isn't sent to the other participants yet.
+    .camera-preview = Preview
+    .camera-stop-preview = Stop Preview
+    .pre-call-check = Pre-Call Check
+    .pre-call-check-description = When enabled, your devices and connection are checked before you join a call.
     .failed = Failed to update settings
 
+settings-storage = Storage Settings
+    .backend = Storage Location
+    .backend-description = Where the files you pin in Files are kept. Currently: { $backend }.
+    .local = This Device
+    .external-disk = External Disk
+    .remote-pinning = Pinning Service
+    .path = Folder
+    .path-description = Choose a folder on the disk. Uplink keeps the data of this profile in a folder of its own inside it and won't start while the disk isn't connected.
+    .choose-folder = Choose Folder
+    .endpoint = Service Endpoint
+    .endpoint-description = The address of a service supporting the IPFS Pinning Service API. Files are still kept on this device as well.
+    .token = Access Token
+    .token-description = Sent to the service with every request. It is kept in the keychain of your device, leave this empty to keep the saved one.
+    .apply = Apply
+    .apply-description = Your data is moved the next time Uplink starts.
+    .pending = Switching to { $backend } the next time Uplink starts.
+    .restart = Restart Uplink to move your data.
+    .select-failed = Failed to change the storage location.
+
+settings-network = Network Settings
+    .status = Status
+    .peers = Connected Peers
+    .relay = Relay
+    .relay-addresses = Relay Addresses
+    .local-address = Local Address
+    .nat = NAT
+    .discovery = Discovery
+    .transports = Transports
+    .last-checked = Last Checked
+    .peer-seen = Last Heard From a Peer
+    .relay-reachable = Reachable ({ $ms } ms)
+    .relay-unreachable = Unreachable
+    .relay-not-used = Not used
+    .nat-private = Behind NAT, port mapping enabled
+    .nat-public = Public address
+    .unknown = Unknown
+    .none = None
+    .diagnostics = Diagnostics
+    .diagnostics-description = Check the connection again, or copy these details to include them in a bug report.
+    .check-again = Check Again
+    .copy-diagnostics = Copy Diagnostics
+    .max-upload = Upload Limit
+    .max-download = Download Limit
+    .no-limit = No limit. Transfers in Files and attachments use as much of the connection as they can.
+    .limit = At most { $speed }/s, shared by all the transfers in Files and attachments.
+    .concurrent-uploads = Simultaneous Uploads
+    .concurrent-uploads-all = All the uploads start at once.
+    .concurrent-uploads-description = Uploads run at most { $count } at a time, the others wait in line.
+    .retry-corrupted = Retry Corrupted Downloads
+    .retry-corrupted-description = Downloads are checked against the file that was uploaded. When they don't match, they are downloaded again.
+    .diagnostics-copied = Diagnostics copied to the clipboard.
+    .copy-failed = Failed to copy the diagnostics.
+
+network = Network
+    .connecting = Connecting
+    .connected = Connected
+    .relay-only = Relay Only
+    .offline = Offline
+    .timed-out = No Peers Reached
+
 settings-files = Files Settings
     .local-sync = Local Sync
     .local-sync-description = When enabled, files will be synced to your local machine.
@@ -312,14 +735,36 @@ settings-keybinds = Keybind Settings
     .info = Global keybinds are disabled while on this page. Click to edit a keybind, press the keybind to highlight and find a specific shortcut.
     .increase-font-size = Increase font size within Uplink.
     .decrease-font-size = Decrease font size within Uplink.
+    .zoom-in-chat = Make the messages of the open chat bigger.
+    .zoom-out-chat = Make the messages of the open chat smaller.
+    .reset-chat-zoom = Reset the text size of the open chat.
     .toggle-mute = Mute & un-mute your microphone.
     .toggle-deafen = Toggle turning off all sounds including your microphone and headphones.
+    .answer-call = Answer an incoming call.
+    .hang-up-call = Hang up the current call, or decline an incoming one.
+    .cycle-call-participants = Move focus to the next participant of the call.
     .conflicting-keybinds = Conflicting Keybind.
     .change-keybind = Record New keybind
     .open-close-dev-tools = Open/Close Web Inspector
     .toggle-devmode = Toggle Developer Mode
+    .toggle-profiling-overlay = Toggle Profiling Overlay
     .cancel-change-keybind = Cancel Recording
     .hide-focus-uplink = Hide/Focus Uplink
+    .open-quick-switcher = Open Quick Switcher
+    .next-chat = Open the next chat in the sidebar.
+    .previous-chat = Open the previous chat in the sidebar.
+    .open-settings = Open Settings
+    .lock-app = Lock Uplink
+    .send-message = Send the message being written.
+    .focus-search = Search chats and friends.
+    .group-navigation = Navigation
+    .group-chats = Chats
+    .group-calls = Calls
+    .group-appearance = Appearance
+    .group-developer = Developer
+    .hotkey-taken = Another application is using this shortcut.
+    .system-wide = Works while Uplink is in the background. Click to only use it within Uplink.
+    .in-app = Only works within Uplink. Click to use it while Uplink is in the background too.
 
 settings-extensions = Extension Settings
     .open-extensions-folder = Open Extensions Folder
@@ -330,10 +775,25 @@ settings-extensions = Extension Settings
     .installed = Installed
     .explore = Explore
     .settings = Settings
+    .permissions = This extension asks for access to:
+    .permission-read-conversations = Your conversations and their participants
+    .permission-read-messages = The messages of your conversations
 
 settings-accessibility = Accessibility Settings
     .dyslexia = Open Dyslexic
     .dyslexia-description = Open Dyslexic may help some users who suffer from dyslexia, it's a custom font you can enable.
+    .high-contrast = High Contrast
+    .high-contrast-description = Switch to a black and white theme with bright accents which is easier to read.
+    .reduced-motion = Reduce Motion
+    .reduced-motion-description = Turn off animations and transitions. They are also turned off when your system asks for less motion.
+
+accessibility = Accessibility
+    .new-message = { $user }: { $text }
+    .incoming-call = Incoming call from { $user }
+    .call-cancelled = The call was cancelled.
+    .joined-call = { $user } joined the call.
+    .left-call = { $user } left the call.
+    .call-ended = The call ended.
 
 settings-notifications = Notifications
     .enabled = Enabled
@@ -343,6 +803,36 @@ settings-notifications = Notifications
     .friends-description = Enable notifications for friend requests.
     .messages-description = Enable notifications for new messages.
     .settings-description = Enable notifications for updates and important alerts.
+    .do-not-disturb = Do Not Disturb
+    .do-not-disturb-description = Silence notifications and their sounds until turned off. Unread badges are still counted.
+    .schedules = Quiet Hours
+    .schedules-description = Turn on Do Not Disturb automatically at these times, on the selected days. Times are entered as 22:00.
+    .add-schedule = Add Quiet Hours
+    .monday = Mon
+    .tuesday = Tue
+    .wednesday = Wed
+    .thursday = Thu
+    .friday = Fri
+    .saturday = Sat
+    .sunday = Sun
+    .turn-on-dnd = Turn On Do Not Disturb
+    .turn-off-dnd = Turn Off Do Not Disturb
+    .webhooks = Webhooks
+    .webhooks-description = Post events as JSON to a local endpoint, for scripts and home automation.
+    .webhook-url = Endpoint
+    .webhook-url-description = Only addresses on this device or in your local network are allowed.
+    .webhook-url-invalid = Enter an http(s) address on this device or in your local network.
+    .webhook-secret = Signing Secret
+    .webhook-secret-description = Every request is signed with HMAC-SHA256 using this secret, so your script can verify it came from Uplink.
+    .webhook-copy-secret = Copy Secret
+    .webhook-regenerate-secret = Regenerate
+    .webhook-secret-copied = Secret copied to the clipboard.
+    .webhook-messages-description = Post new messages, unless the conversation is muted.
+    .webhook-friends-description = Post incoming friend requests.
+    .webhook-transfers = Transfers
+    .webhook-transfers-description = Post finished uploads and downloads.
+    .webhook-test = Send Test Event
+    .webhook-test-description = Post a test event to the endpoint.
 
 settings-developer = Developer Settings 
     .developer-mode = Developer Mode
@@ -363,8 +853,48 @@ settings-developer = Developer Settings
     .clear = Clear
     .save-logs-to-file = Save logs in a file
     .save-logs-to-file-description = Enabling this option, logs will be saved in a file and will be persistent.
+    .log-viewer = Log Viewer
+    .log-viewer-description = Follow the log file without leaving Uplink. Filter by level and module, search with a regular expression or export a part of it.
+    .automation = Automation API
+    .automation-description = Lets scripts and integrations like streaming overlays send messages, list conversations, upload files and read unread counts through a local socket.
+    .automation-socket = Socket
+    .automation-socket-description = Scripts connect here and send one JSON-RPC 2.0 request per line.
+    .automation-token = Token
+    .automation-token-description = Scripts have to call auth with this token before anything else. Regenerating it disconnects them.
+    .automation-copy-token = Copy Token
+    .automation-regenerate-token = Regenerate
+    .automation-token-copied = Token copied to clipboard
+    .logs-module = Module
+    .logs-search = Search (regular expression)
+    .logs-follow = Follow
+    .logs-paused = Paused, { $num } new entries
+    .logs-empty = No matching entries.
+    .logs-not-saved = Logs are only shown here while they are saved in a file.
+    .logs-invalid-regex = The search is not a valid regular expression.
+    .logs-from = From (YYYY-MM-DD HH:MM)
+    .logs-to = To (YYYY-MM-DD HH:MM)
+    .logs-invalid-range = Times have to be written as YYYY-MM-DD HH:MM.
+    .logs-export = Export
+    .logs-exported = Exported { $num } entries.
+    .logs-export-failed = The logs could not be exported.
     .print-state = Print State 
     .print-state-description = Display State in the debug logger.
+    .check-integrity = Check Storage Integrity
+    .check-integrity-description = Scans for orphaned thumbnails, files with missing contents and inconsistent conversation indexes.
+    .check = Check
+    .repair = Repair
+    .integrity-no-issues = No issues found.
+    .integrity-issues = Found { $thumbnails } orphaned thumbnails, { $blobs } files with missing contents and { $indexes } conversation index issues.
+    .integrity-repaired = Repaired { $num } issues.
+    .command-latency = Command Latency
+    .command-latency-description = How long Uplink waits for Warp, per command. Percentiles use the last 256 runs of each command.
+    .refresh = Refresh
+    .reset = Reset
+    .no-commands = No commands were measured yet.
+    .command = Command
+    .count = Count
+    .log-latency = Log Command Latency
+    .log-latency-description = Logs how long each command took.
 
 settings-about = About Settings
     .info = About
@@ -396,6 +926,47 @@ remote-controls = Remote Controls
     .incoming-call = Incoming Transmission ...
     .outgoing-call = Outgoing Transmission ...
     .empty = Nobody is here
+    .show-captions = Show Captions
+    .hide-captions = Hide Captions
+    .captions-listening = Listening...
+    .captions-unavailable = Live captions are not available on this device.
+    .captions-notice = Only your own speech is captioned. Your voice is sent to the online speech recognition service of your system's web engine.
+    .captions-you = You
+    .transcript-consent = Allow saving the transcript
+    .save-transcript = Save Transcript
+    .transcript-saved = Transcript saved to the conversation.
+    .participants = Participants
+    .waiting = Waiting to join...
+    .camera-on = Show Camera Preview (only visible to you)
+    .camera-off = Hide Camera Preview
+    .camera-unavailable = The camera could not be started.
+    .drop-to-share = Drop to share with the call
+    .share-failed = The files could not be shared.
+
+pre-call = Pre-Call Check
+    .title = Check Before Joining
+    .checking = Checking...
+    .microphone = Microphone
+    .speaker = Speaker
+    .camera = Camera
+    .network = Connection
+    .camera-off = The camera is off, it can be turned on during the call.
+    .no-camera = No camera found.
+    .no-microphone = No microphone found.
+    .no-speaker = No speaker found.
+    .device-error = The device could not be tested.
+    .microphone-silent = No sound was picked up, check that your microphone isn't muted.
+    .speaker-silent = No sound was played, check your output device.
+    .latency = { $ms } ms to the relay
+    .no-relay = No relay is configured, the call will connect directly.
+    .relay-unreachable = The relay could not be reached.
+    .summary-running = Checking your devices and connection...
+    .summary-go = Everything looks good, you're ready to join.
+    .summary-warning = You can join, but the call may not work as expected.
+    .summary-no-go = Something is wrong, the call is unlikely to work.
+    .dont-show-again = Don't check before calls again
+    .join = Join
+    .join-anyway = Join Anyway
 
 unlock = Unlock
     .notice = (this is used to encrypt all of the data Uplink stores on your computer when you're not using it so nobody can read your data.)
@@ -409,15 +980,73 @@ unlock = Unlock
     .error-unknown-pin = An unknown error occurred.
     .help = Help (right-click)
     .logging-in = Logging in...
+    .default-profile = Default Profile
+    .add-profile = Add Profile
+    .profile-name = Profile Name
+    .profile-exists = A profile with this name already exists.
+    .use-saved-pin = Use Saved Pin
+    .saved-pin-invalid = The saved pin didn't work, please enter it again.
+    .device-revoked = This device was removed from the account by another device, so the account was deleted from it.
+
+lock-screen = Lock Screen
+    .title = Uplink is locked
+    .description = Enter your pin to continue.
+    .unlock = Unlock
+    .retry-in = Too many wrong pins. Try again in { $seconds } seconds.
+
+quick-switcher = Quick Switcher
+    .placeholder = Jump to a chat, friend, file or setting...
+    .no-results = Nothing matches.
+    .chat = Chat
+    .friend = Friend
+    .folder = Folder
+    .file = File
+    .action = Action
+    .settings = Settings
+
+tray = Tray
+    .open = Open Uplink
+    .status = Status
+    .recent = Recent Conversations
+    .lock = Lock Uplink
+
+shutdown = Shutdown
+    .finishing-up = Finishing up...
+    .unsent-messages = Sending { $num } message(s)
+    .active-transfers = Waiting for { $num } file transfer(s)
+    .quit-now = Quit Now
+    .interrupted-transfers = Some transfers were interrupted when Uplink was closed
 
 create-or-recover = Account Creation
     .create = Create New Account
     .instructions = We're going to create an account for you. On the next screen, you'll see a set of words. Screenshot this or write it down. This is the only way to backup your account.
     .recover = Import an Account
+    .restore = Restore from Backup
+    .link-device = Link to a Device
+
+link-device = Link to a Device
+    .instructions = Open Settings > Linked Devices on the device with your account and enter the pairing code it shows. Both devices need to be on the same network. You unlock the account with the PIN used on the other device.
+    .code = Pairing Code
+    .device-name = Device Name
+    .invalid-code = This is not a valid pairing code.
+    .submit = Link
+    .profile-name = Linked Account
+    .connect-failed = Couldn't receive the account. Check the code and that the other device allowed the link.
+    .restore-failed = The account was received but could not be set up.
+
+restore-backup = Restore Backup
+    .instructions = Choose a backup file and enter its passphrase. It is restored as a new profile, which you unlock with the PIN of the backed up account.
+    .no-file = No file selected
+    .choose-file = Choose File
+    .passphrase = Passphrase
+    .submit = Restore
+    .failed = The backup could not be restored. Check the passphrase and the file.
 
 copy-seed-words = Recovery Seed
     .instructions = Write these words down in the order that they appear. Having the correct order is crucial when you are recovering your account.
     .finished = I Saved It
+    .acknowledge = I stored my recovery seed somewhere safe. Without it my account can't be recovered.
+    .picture-failed = Your account was created, but its profile picture couldn't be set: { $error }. You can set it again in the profile settings.
 
 enter-seed-words = Recovery Seed
     .instructions = Type your recovery seed here. Each phrase should go into their respective box. Alternatively you can simply copy past your recovery seed in here.
@@ -429,10 +1058,45 @@ enter-seed-words = Recovery Seed
 auth = Create Account 
     .enter-username = Enter Username
     .enter-username-subtext = Time to pick your username, you can change this later at any time in settings.
+    .pick-avatar = Pick a Profile Picture
+    .change-avatar = Change Picture
+
+onboarding = Welcome
+    .notifications = Notifications
+    .notifications-description = Choose what Uplink should notify you about. You can change this later in the settings.
+    .theme = Theme
+    .theme-description = Pick how Uplink looks. More themes can be added in the settings.
+    .add-friend = Add a Friend
+    .add-friend-description = Send a request to someone you know using their DID or username.
+    .tour = Take a Tour
+    .tour-description = Want us to show you around? The tour takes less than a minute.
+    .skip = Skip
+    .next = Next
+    .finish = Finish
+    .skip-tour = No Thanks
+    .start-tour = Start the Tour
+    .end-tour = End Tour
+    .tour-progress = { $current } of { $total }
+    .tour-nav = Navigation
+    .tour-nav-description = Switch between your chats, files, friends and settings from here.
+    .tour-chats = Chats
+    .tour-chats-description = Your conversations are listed here, the most recent one first.
+    .tour-friends = Friends
+    .tour-friends-description = Add friends with their DID or username, and answer their requests.
+    .tour-files = Files
+    .tour-files-description = Store files and share them in your chats.
+    .tour-settings = Settings
+    .tour-settings-description = Change your profile, privacy, notifications and much more.
 
 sidebar = Sidebar 
     .subtext = { $user } sent multiple attachments
     .chat-new = No messages sent yet, send one!
+    .filter-all = All
+    .filter-unread = Unread
+    .filter-groups = Groups
+    .filter-direct = Direct
+    .filter-favorites = Favorites
+    .filter-empty = No conversations match this filter.
 
 quickprofile = Quick Profile
     .profile = Profile
@@ -451,3 +1115,23 @@ toast_actions = Toast Actions
     .DisplayChat = Open Chat
     .FriendListPending = Friend List
     .Dummy = Dummy Action
+    .reply = Reply
+    .reply-placeholder = Write a reply...
+    .respond = Respond
+    .accept = Accept
+    .deny = Deny
+
+profiling = Profiling
+    .title = Profiling
+    .reset = Reset
+    .close = Close
+    .memory = Memory:
+    .component = Component
+    .renders = Renders
+    .per-second = Last second
+
+status-bar = Status Bar
+    .transfers = Transfers: { $num } ({ $percent }%)
+    .no-transfers = No transfers
+    .outbox = Unsent messages: { $num }
+    .outbox-empty = All messages sent
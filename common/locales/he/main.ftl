@@ -0,0 +1,51 @@
+uplink = Uplink
+    .unknown = לא ידוע
+    .home = בית
+    .add = הוספה
+    .added = נוסף!
+    .add-members = הוספת חברים
+    .current-members = חברים נוכחיים
+    .call = שיחה
+    .users = משתמשים
+    .groups = קבוצות
+    .members = חברים
+    .members-count = חברים ({ $num })
+    .reset-account = איפוס החשבון (בלתי הפיך!)
+    .coming-soon = בקרוב
+    .chat = צ'אט
+    .chats = צ'אטים
+    .send = שליחה
+    .remove = הסרה
+    .cancel = ביטול
+    .clear-unreads = סימון הכול כנקרא
+    .unread-badge = { $count } הודעות שלא נקראו
+    .delete-group-chat = מחיקת הקבוצה
+    .leave-group = יציאה מהקבוצה
+    .delete-conversation = מחיקת הצ'אט
+    .hide-chat = הסתרת הצ'אט
+    .search-placeholder = חיפוש...
+    .nothing-here = אין כאן כלום...
+    .end = סיום
+    .username = שם משתמש
+    .status = הודעת מצב
+    .delete = מחיקה
+    .dismiss = סגירה
+    .check-for-updates = בדיקת עדכונים
+    .download-update = הורדת העדכון
+    .date-time-format = %d/%m/%Y %H:%M
+    .copy-text = העתקת הטקסט
+    .copy = העתקה
+    .paste = הדבקה
+    .go-back = חזרה
+    .upload-queue = תור העלאות
+    .download-queue = תור הורדות
+    .copy-seed = העתקה ללוח
+    .copied-seed = הועתק ללוח
+
+accessibility = נגישות
+    .new-message = { $user }: { $text }
+    .incoming-call = שיחה נכנסת מ{ $user }
+    .call-cancelled = השיחה בוטלה.
+    .joined-call = { $user } הצטרף/ה לשיחה.
+    .left-call = { $user } עזב/ה את השיחה.
+    .call-ended = השיחה הסתיימה.
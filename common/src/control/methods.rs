@@ -0,0 +1,367 @@
+//! The methods of the control socket. They are sent to the warp runner like the commands of the UI.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use futures::{channel::oneshot, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use warp::{
+    constellation::{item::Item, Progression},
+    crypto::DID,
+    raygun,
+};
+
+use super::rpc::{Notification, Request, RpcError, INTERNAL_ERROR};
+use crate::{
+    state::storage::Storage,
+    warp_runner::{
+        message_store, ui_adapter::MessageEvent, ConstellationCmd, FetchMessagesConfig,
+        MultiPassCmd, RayGunCmd, WarpCmd,
+    },
+    WARP_CMD_CH,
+};
+
+// messages returned by messages.list when no limit is given
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Deserialize)]
+struct ConversationParams {
+    conversation_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct ListMessagesParams {
+    conversation_id: Uuid,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SendMessageParams {
+    conversation_id: Uuid,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct DidParams {
+    did: String,
+}
+
+// the files methods take the directory of the storage they work in, like "photos/2023" or "/" for
+// the root. the directory shown by the UI isn't used
+#[derive(Deserialize)]
+struct DirectoryParams {
+    directory: String,
+}
+
+#[derive(Deserialize)]
+struct NameParams {
+    directory: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct UploadParams {
+    directory: String,
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct DownloadParams {
+    directory: String,
+    name: String,
+    path: PathBuf,
+}
+
+pub(super) async fn call(request: &Request) -> Result<Value, RpcError> {
+    match request.method.as_str() {
+        "identity.get" => {
+            let did = send(|rsp| WarpCmd::MultiPass(MultiPassCmd::GetOwnDid { rsp })).await??;
+            let identity =
+                send(|rsp| WarpCmd::MultiPass(MultiPassCmd::GetIdentity { did, rsp })).await??;
+            Ok(json!({
+                "did": identity.did_key().to_string(),
+                "username": identity.username(),
+                "status_message": identity.status_message(),
+            }))
+        }
+        "conversations.list" => {
+            let conv_ids =
+                send(|rsp| WarpCmd::RayGun(RayGunCmd::ListConversations { rsp })).await??;
+            let chats =
+                send(|rsp| WarpCmd::RayGun(RayGunCmd::LoadConversations { conv_ids, rsp })).await?;
+            let chats: Vec<_> = chats
+                .values()
+                .map(|chat| {
+                    json!({
+                        "id": chat.id,
+                        "name": chat.conversation_name,
+                        "type": format!("{:?}", chat.conversation_type).to_lowercase(),
+                        "participants": chat
+                            .participants
+                            .iter()
+                            .map(|did| did.to_string())
+                            .collect::<Vec<_>>(),
+                        "unread": chat.unreads(),
+                    })
+                })
+                .collect();
+            Ok(json!(chats))
+        }
+        "conversations.start" => {
+            let DidParams { did } = request.params()?;
+            let recipient = DID::from_str(&did).map_err(RpcError::invalid_params)?;
+            let conv_id =
+                send(|rsp| WarpCmd::RayGun(RayGunCmd::StartConversation { recipient, rsp }))
+                    .await??;
+            Ok(json!({ "conversation_id": conv_id }))
+        }
+        "conversations.unread" => {
+            let did = send(|rsp| WarpCmd::MultiPass(MultiPassCmd::GetOwnDid { rsp })).await??;
+            let unread: serde_json::Map<_, _> = message_store::unread(&did)
+                .await
+                .into_iter()
+                .filter(|(_, messages)| !messages.is_empty())
+                .map(|(conv_id, messages)| (conv_id.to_string(), json!(messages.len())))
+                .collect();
+            let total: u64 = unread.values().filter_map(Value::as_u64).sum();
+            Ok(json!({ "conversations": unread, "total": total }))
+        }
+        "messages.list" => {
+            let ListMessagesParams {
+                conversation_id,
+                limit,
+            } = request.params()?;
+            let config = FetchMessagesConfig::MostRecent {
+                limit: limit.unwrap_or(DEFAULT_LIMIT),
+            };
+            let response = send(|rsp| {
+                WarpCmd::RayGun(RayGunCmd::FetchMessages {
+                    conv_id: conversation_id,
+                    config,
+                    rsp,
+                })
+            })
+            .await??;
+            let messages: Vec<_> = response
+                .messages
+                .iter()
+                .map(|message| message_json(&message.inner))
+                .collect();
+            Ok(json!({ "messages": messages, "has_more": response.has_more }))
+        }
+        "messages.send" => {
+            let SendMessageParams {
+                conversation_id,
+                text,
+            } = request.params()?;
+            if text.trim().is_empty() {
+                return Err(RpcError::invalid_params("the message is empty"));
+            }
+            let (message_id, _) = send(|rsp| {
+                WarpCmd::RayGun(RayGunCmd::SendMessage {
+                    conv_id: conversation_id,
+                    msg: text.lines().map(String::from).collect(),
+                    attachments: vec![],
+                    rsp,
+                })
+            })
+            .await??;
+            Ok(json!({ "message_id": message_id }))
+        }
+        "messages.mark_read" => {
+            let ConversationParams { conversation_id } = request.params()?;
+            message_store::mark_read(conversation_id);
+            Ok(Value::Bool(true))
+        }
+        "files.list" => {
+            let DirectoryParams { directory } = request.params()?;
+            let storage = list(&directory).await?;
+            Ok(json!({
+                "directory": storage.current_dir.name(),
+                "directories": storage
+                    .directories
+                    .iter()
+                    .map(|dir| dir.name())
+                    .collect::<Vec<_>>(),
+                "files": storage
+                    .files
+                    .iter()
+                    .map(|file| json!({ "name": file.name(), "size": file.size() }))
+                    .collect::<Vec<_>>(),
+                "total_items": storage.total_items,
+            }))
+        }
+        "files.create_directory" => {
+            let NameParams { directory, name } = request.params()?;
+            send_in(&directory, |rsp| ConstellationCmd::CreateNewDirectory {
+                directory_name: name,
+                rsp,
+            })
+            .await??;
+            Ok(Value::Bool(true))
+        }
+        "files.upload" => {
+            let UploadParams { directory, paths } = request.params()?;
+            if let Some(path) = paths.iter().find(|path| !path.is_file()) {
+                return Err(RpcError::invalid_params(format!(
+                    "{} is not a file",
+                    path.display()
+                )));
+            }
+            // the upload isn't answered, so the directory is checked first
+            list(&directory).await?;
+            let queued = paths.len();
+            // the result is sent as a files.uploaded or files.upload_failed event
+            WARP_CMD_CH
+                .tx
+                .send(WarpCmd::Constellation(ConstellationCmd::InDirectory {
+                    path: directory,
+                    cmd: Box::new(ConstellationCmd::UploadFiles { files_path: paths }),
+                }))
+                .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+            Ok(json!({ "queued": queued }))
+        }
+        "files.download" => {
+            let DownloadParams {
+                directory,
+                name,
+                path,
+            } = request.params()?;
+            check_download_path(&path)?;
+            let mut progress = send_in(&directory, |rsp| ConstellationCmd::DownloadFile {
+                file_name: name,
+                local_path_to_save_file: path.clone(),
+                rsp,
+            })
+            .await??;
+            while let Some(progress) = progress.next().await {
+                match progress {
+                    Progression::ProgressComplete { .. } => break,
+                    Progression::ProgressFailed { error, .. } => return Err(error.into()),
+                    _ => {}
+                }
+            }
+            Ok(json!({ "path": path }))
+        }
+        "files.delete" => {
+            let NameParams { directory, name } = request.params()?;
+            let storage = list(&directory).await?;
+            let item = storage
+                .files
+                .iter()
+                .find(|file| file.name() == name)
+                .map(|file| Item::from(file.clone()))
+                .or_else(|| {
+                    storage
+                        .directories
+                        .iter()
+                        .find(|dir| dir.name() == name)
+                        .map(|dir| Item::from(dir.clone()))
+                })
+                .ok_or_else(|| RpcError::invalid_params(format!("{name} doesn't exist")))?;
+            send_in(&directory, |rsp| ConstellationCmd::DeleteItems {
+                item,
+                rsp,
+            })
+            .await??;
+            Ok(Value::Bool(true))
+        }
+        method => Err(RpcError::method_not_found(method)),
+    }
+}
+
+/// The event sent to subscribed clients for a message event, if they are told about it
+pub(super) fn message_notification(event: MessageEvent) -> Option<Notification> {
+    let (method, params) = match event {
+        MessageEvent::Received { message, .. } => {
+            ("message.received", message_json(&message.inner))
+        }
+        MessageEvent::Sent { message, .. } => ("message.sent", message_json(&message.inner)),
+        MessageEvent::Edited { message, .. } => ("message.edited", message_json(&message.inner)),
+        MessageEvent::Deleted {
+            conversation_id,
+            message_id,
+            ..
+        } => (
+            "message.deleted",
+            json!({ "conversation_id": conversation_id, "id": message_id }),
+        ),
+        _ => return None,
+    };
+    Some(Notification::new(method, params))
+}
+
+fn message_json(message: &raygun::Message) -> Value {
+    json!({
+        "id": message.id(),
+        "conversation_id": message.conversation_id(),
+        "sender": message.sender().to_string(),
+        "date": message.date().to_rfc3339(),
+        "lines": message.lines(),
+        "attachments": message
+            .attachments()
+            .iter()
+            .map(|file| file.name())
+            .collect::<Vec<_>>(),
+    })
+}
+
+async fn list(directory: &str) -> Result<Storage, RpcError> {
+    Ok(send_in(directory, |rsp| {
+        ConstellationCmd::GetItemsFromCurrentDirectory { rsp }
+    })
+    .await??)
+}
+
+// downloads only go to a folder which exists and never replace anything
+fn check_download_path(path: &Path) -> Result<(), RpcError> {
+    if !path.is_absolute() {
+        return Err(RpcError::invalid_params("the path has to be absolute"));
+    }
+    if path.symlink_metadata().is_ok() {
+        return Err(RpcError::invalid_params(format!(
+            "{} already exists",
+            path.display()
+        )));
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(()),
+        _ => Err(RpcError::invalid_params(format!(
+            "the folder of {} doesn't exist",
+            path.display()
+        ))),
+    }
+}
+
+// runs the command in `directory` of the storage, see `ConstellationCmd::InDirectory`
+async fn send_in<T>(
+    directory: &str,
+    cmd: impl FnOnce(oneshot::Sender<T>) -> ConstellationCmd,
+) -> Result<T, RpcError> {
+    let (tx, rx) = oneshot::channel();
+    WARP_CMD_CH
+        .tx
+        .send(WarpCmd::Constellation(ConstellationCmd::InDirectory {
+            path: directory.to_string(),
+            cmd: Box::new(cmd(tx)),
+        }))
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+    // the command is dropped if there is no such directory
+    rx.await
+        .map_err(|_| RpcError::invalid_params(format!("{directory} is not a directory")))
+}
+
+async fn send<T>(cmd: impl FnOnce(oneshot::Sender<T>) -> WarpCmd) -> Result<T, RpcError> {
+    let (tx, rx) = oneshot::channel();
+    WARP_CMD_CH
+        .tx
+        .send(cmd(tx))
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+    rx.await
+        .map_err(|_| RpcError::new(INTERNAL_ERROR, "the warp runner stopped"))
+}
@@ -0,0 +1,278 @@
+//! The control socket, used to drive Uplink without its window (see `--headless`) and by scripts
+//! while it runs with its window, if enabled in the settings. Clients send JSON-RPC 2.0 requests,
+//! one per line, and get the responses the same way. After calling `events.subscribe` they are
+//! also sent notifications, like `message.received`.
+//!
+//! Only the user running Uplink can connect: on unix the socket is created with 0600 permissions,
+//! on Windows it is a named pipe of the user and the profile which only the user can open and which
+//! doesn't accept remote clients, see `pipe`. With the window, clients
+//! also have to call `auth` with the token shown in the settings first. While the app is locked,
+//! requests are refused and no events are sent.
+
+mod methods;
+#[cfg(windows)]
+pub mod pipe;
+pub mod rpc;
+
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    sync::{broadcast, mpsc},
+};
+use tracing::log;
+
+use crate::{
+    state::configuration::Automation,
+    utils::{app_lock, webhooks},
+    warp_runner::WarpEvent,
+    WARP_EVENT_CH,
+};
+use rpc::{Notification, Request, Response, RpcError, LOCKED, UNAUTHORIZED};
+
+// events are dropped for clients which fall this far behind
+const EVENTS_CAPACITY: usize = 256;
+
+static EVENTS: Lazy<broadcast::Sender<Notification>> =
+    Lazy::new(|| broadcast::channel(EVENTS_CAPACITY).0);
+// set once the events of warp are forwarded, the socket can be served several times
+static FORWARDING: AtomicBool = AtomicBool::new(false);
+// the token clients authenticate with, None while the socket is disabled in the settings
+static TOKEN: Lazy<RwLock<Option<String>>> = Lazy::new(Default::default);
+
+#[derive(serde::Deserialize)]
+struct AuthParams {
+    token: String,
+}
+
+/// A random token, in the same format as the secret of the webhooks
+pub fn new_token() -> String {
+    webhooks::new_secret()
+}
+
+/// Applies the settings. Clients which authenticated with another token are cut off
+pub fn configure(automation: &Automation) {
+    *TOKEN.write() = automation.enabled.then(|| automation.token.clone());
+}
+
+// compares every byte so the time taken doesn't tell how much of the token was right
+fn is_current_token(token: &str) -> bool {
+    let current = TOKEN.read();
+    let Some(current) = current.as_deref() else {
+        return false;
+    };
+    current.len() == token.len()
+        && current
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Sends an event to the clients which subscribed to them
+pub fn publish(method: &str, params: Value) {
+    // fails if no client is subscribed
+    let _ = EVENTS.send(Notification::new(method, params));
+}
+
+/// Accepts clients on `path` until an error occurs. A socket left over at `path` is replaced.
+/// If `authenticate` is set, clients have to send the token of the settings first
+#[cfg(unix)]
+pub async fn serve(path: &Path, authenticate: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    if !FORWARDING.swap(true, Ordering::Relaxed) {
+        tokio::spawn(forward_warp_events());
+    }
+    log::info!("control socket listening on {}", path.display());
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(handle_client(stream, authenticate));
+    }
+}
+
+/// Accepts clients on the named pipe `path` until an error occurs. If `authenticate` is set,
+/// clients have to send the token of the settings first
+#[cfg(windows)]
+pub async fn serve(path: &Path, authenticate: bool) -> std::io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    // fails if another program created the pipe first
+    let mut security = pipe::OwnerOnly::new()?;
+    let mut server = unsafe {
+        ServerOptions::new()
+            .first_pipe_instance(true)
+            .reject_remote_clients(true)
+            .create_with_security_attributes_raw(path, security.as_ptr())?
+    };
+    if !FORWARDING.swap(true, Ordering::Relaxed) {
+        tokio::spawn(forward_warp_events());
+    }
+    log::info!("control socket listening on {}", path.display());
+
+    loop {
+        server.connect().await?;
+        // the next client connects to a new instance of the pipe
+        let next = unsafe {
+            ServerOptions::new()
+                .reject_remote_clients(true)
+                .create_with_security_attributes_raw(path, security.as_ptr())?
+        };
+        let client = std::mem::replace(&mut server, next);
+        tokio::spawn(handle_client(client, authenticate));
+    }
+}
+
+async fn forward_warp_events() {
+    let mut rx = WARP_EVENT_CH.tx.subscribe();
+    loop {
+        match rx.recv().await {
+            Ok(WarpEvent::Message(event)) => {
+                if let Some(notification) = methods::message_notification(event) {
+                    let _ = EVENTS.send(notification);
+                }
+            }
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                log::warn!("control socket skipped {skipped} warp events");
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn handle_client<S: AsyncRead + AsyncWrite + Send + 'static>(stream: S, authenticate: bool) {
+    let (reader, mut writer) = tokio::io::split(stream);
+    // responses and events are written by a single task, so their lines don't get mixed up
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn(async move {
+        while let Some(line) = rx.recv().await {
+            if writer.write_all(line.as_bytes()).await.is_err()
+                || writer.write_all(b"\n").await.is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let mut events = None;
+    // the token the client sent, checked again with every request
+    let mut token: Option<String> = None;
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match Request::parse(&line) {
+            Ok(request) => request,
+            Err(response) => {
+                write(&tx, &response);
+                continue;
+            }
+        };
+        if app_lock::is_locked() {
+            let error = RpcError::new(LOCKED, "Uplink is locked");
+            respond(&tx, request.id, Err(error));
+            continue;
+        }
+        if request.method == "auth" {
+            let result = request.params().and_then(|AuthParams { token: sent }| {
+                if !authenticate || is_current_token(&sent) {
+                    token = Some(sent);
+                    Ok(Value::Bool(true))
+                } else {
+                    Err(RpcError::new(UNAUTHORIZED, "wrong token"))
+                }
+            });
+            respond(&tx, request.id, result);
+            continue;
+        }
+        let authorized = !authenticate || token.as_deref().is_some_and(is_current_token);
+        if !authorized {
+            let error = RpcError::new(UNAUTHORIZED, "call auth with the token first");
+            respond(&tx, request.id, Err(error));
+            continue;
+        }
+        match request.method.as_str() {
+            "events.subscribe" => {
+                if events.is_none() {
+                    let token = token.clone().filter(|_| authenticate);
+                    events = Some(tokio::spawn(forward_events(tx.clone(), token)));
+                }
+                respond(&tx, request.id, Ok(Value::Bool(true)));
+            }
+            "events.unsubscribe" => {
+                if let Some(events) = events.take() {
+                    events.abort();
+                }
+                respond(&tx, request.id, Ok(Value::Bool(true)));
+            }
+            // a download doesn't hold up the other requests of the client
+            _ => {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let result = methods::call(&request).await;
+                    respond(&tx, request.id, result);
+                });
+            }
+        }
+    }
+
+    if let Some(events) = events {
+        events.abort();
+    }
+    drop(tx);
+    let _ = writer.await;
+}
+
+// `token` is the one the client authenticated with, if it had to
+async fn forward_events(tx: mpsc::UnboundedSender<String>, token: Option<String>) {
+    let mut rx = EVENTS.subscribe();
+    loop {
+        match rx.recv().await {
+            Ok(_)
+                if token
+                    .as_deref()
+                    .is_some_and(|token| !is_current_token(token)) =>
+            {
+                break
+            }
+            // what happens while the app is locked isn't shown
+            Ok(_) if app_lock::is_locked() => {}
+            Ok(notification) => write(&tx, &notification),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                log::warn!("a client of the control socket missed {skipped} events");
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+// notifications, requests without an id, aren't answered
+fn respond(tx: &mpsc::UnboundedSender<String>, id: Option<Value>, result: Result<Value, RpcError>) {
+    if let Some(id) = id {
+        write(tx, &Response::new(id, result));
+    }
+}
+
+fn write(tx: &mpsc::UnboundedSender<String>, message: &impl Serialize) {
+    match serde_json::to_string(message) {
+        Ok(line) => {
+            let _ = tx.send(line);
+        }
+        Err(e) => log::error!("failed to serialize a message of the control socket: {e}"),
+    }
+}
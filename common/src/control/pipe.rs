@@ -0,0 +1,110 @@
+//! The named pipe of the control socket on Windows. Its name has the SID of the user and a hash of
+//! the profile, so every profile of every user has its own pipe, and its DACL only lets the user
+//! open it. The links forwarded between instances of Uplink (see `utils::handoff`) use a pipe made
+//! the same way.
+
+use std::{ffi::c_void, io, iter, path::Path};
+
+use sha2::{Digest, Sha256};
+use windows::{
+    core::{PCWSTR, PWSTR},
+    Win32::{
+        Foundation::{CloseHandle, LocalFree, HANDLE, HLOCAL},
+        Security::{
+            Authorization::{
+                ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW,
+                SDDL_REVISION_1,
+            },
+            GetTokenInformation, TokenUser, PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES, TOKEN_QUERY,
+            TOKEN_USER,
+        },
+        System::Threading::{GetCurrentProcess, OpenProcessToken},
+    },
+};
+
+/// The name of the pipe of the given `kind` for the directory at `path`, the profile for the
+/// control socket
+pub fn name(kind: &str, path: &Path) -> String {
+    let hash = Sha256::digest(path.to_string_lossy().as_bytes());
+    let path: String = hash[..8].iter().map(|b| format!("{b:02x}")).collect();
+    // without the SID the DACL can't be made either, so the pipe isn't served
+    let sid = user_sid().unwrap_or_default();
+    format!(r"\\.\pipe\uplink-{kind}-{sid}-{path}")
+}
+
+/// The security attributes which only let the current user open the pipe
+pub struct OwnerOnly {
+    descriptor: PSECURITY_DESCRIPTOR,
+    attributes: SECURITY_ATTRIBUTES,
+}
+
+// the descriptor is only read by the system while the pipe is created
+unsafe impl Send for OwnerOnly {}
+
+impl OwnerOnly {
+    pub fn new() -> io::Result<Self> {
+        // protected, so nothing is inherited: the user gets full access and nobody else any
+        let sddl: Vec<u16> = format!("D:P(A;;GA;;;{})", user_sid()?)
+            .encode_utf16()
+            .chain(iter::once(0))
+            .collect();
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                PCWSTR(sddl.as_ptr()),
+                SDDL_REVISION_1,
+                &mut descriptor,
+                None,
+            )?;
+        }
+        Ok(Self {
+            descriptor,
+            attributes: SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: descriptor.0,
+                bInheritHandle: false.into(),
+            },
+        })
+    }
+
+    /// for `ServerOptions::create_with_security_attributes_raw`
+    pub fn as_ptr(&mut self) -> *mut c_void {
+        &mut self.attributes as *mut SECURITY_ATTRIBUTES as *mut c_void
+    }
+}
+
+impl Drop for OwnerOnly {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = LocalFree(HLOCAL(self.descriptor.0 as _));
+        }
+    }
+}
+
+fn user_sid() -> io::Result<String> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)?;
+        // the first call only tells the size
+        let mut len = 0;
+        let _ = GetTokenInformation(token, TokenUser, None, 0, &mut len);
+        // u64s, so the TOKEN_USER in it is aligned
+        let mut buffer = vec![0_u64; (len as usize).div_ceil(8)];
+        let result = GetTokenInformation(
+            token,
+            TokenUser,
+            Some(buffer.as_mut_ptr() as *mut c_void),
+            len,
+            &mut len,
+        );
+        let _ = CloseHandle(token);
+        result?;
+
+        let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+        let mut sid = PWSTR::null();
+        ConvertSidToStringSidW(user.User.Sid, &mut sid)?;
+        let string = sid.to_string();
+        let _ = LocalFree(HLOCAL(sid.0 as _));
+        string.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
@@ -0,0 +1,162 @@
+//! The JSON-RPC 2.0 messages of the control socket. Every line is one request, response or
+//! notification. Batches aren't supported.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+// the client didn't send the token yet, or it was changed in the settings
+pub const UNAUTHORIZED: i64 = -32001;
+// the app is locked, nothing is answered until it is unlocked
+pub const LOCKED: i64 = -32002;
+
+#[derive(Debug, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+    // None for notifications, which don't get a response
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl Request {
+    /// Reads a line sent by a client. The error is the response to send back
+    pub fn parse(line: &str) -> Result<Self, Response> {
+        let value: Value = serde_json::from_str(line)
+            .map_err(|e| Response::error(Value::Null, RpcError::new(PARSE_ERROR, e)))?;
+        let id = value.get("id").cloned().unwrap_or(Value::Null);
+        let request: Request = serde_json::from_value(value)
+            .map_err(|e| Response::error(id.clone(), RpcError::new(INVALID_REQUEST, e)))?;
+        if request.jsonrpc != VERSION {
+            return Err(Response::error(
+                id,
+                RpcError::new(INVALID_REQUEST, "only JSON-RPC 2.0 is supported"),
+            ));
+        }
+        Ok(request)
+    }
+
+    /// The params as `T`. Missing params are read as an empty object
+    pub fn params<T: serde::de::DeserializeOwned>(&self) -> Result<T, RpcError> {
+        let params = match &self.params {
+            Value::Null => Value::Object(Default::default()),
+            params => params.clone(),
+        };
+        serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+impl Response {
+    pub fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        match result {
+            Ok(result) => Self {
+                jsonrpc: VERSION,
+                id,
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => Self::error(id, error),
+        }
+    }
+
+    pub fn error(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: VERSION,
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("unknown method {method}"))
+    }
+
+    pub fn invalid_params(message: impl ToString) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+}
+
+// the warp runner is gone or warp failed
+impl From<warp::error::Error> for RpcError {
+    fn from(e: warp::error::Error) -> Self {
+        Self::new(INTERNAL_ERROR, e)
+    }
+}
+
+/// Sent to the clients which subscribed to events, without an id
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    jsonrpc: &'static str,
+    pub method: String,
+    pub params: Value,
+}
+
+impl Notification {
+    pub fn new(method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: VERSION,
+            method: method.into(),
+            params,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn answers_bad_requests_with_their_error() {
+        let response =
+            |line: &str| serde_json::to_value(Request::parse(line).unwrap_err()).unwrap();
+
+        assert_eq!(response("{")["error"]["code"], json!(PARSE_ERROR));
+        assert_eq!(response("{")["id"], Value::Null);
+        let wrong_version = response(r#"{"jsonrpc":"1.0","id":7,"method":"identity.get"}"#);
+        assert_eq!(wrong_version["error"]["code"], json!(INVALID_REQUEST));
+        assert_eq!(wrong_version["id"], json!(7));
+        assert!(wrong_version.get("result").is_none());
+
+        let request = Request::parse(r#"{"jsonrpc":"2.0","id":"a","method":"messages.list"}"#)
+            .expect("request is valid");
+        assert_eq!(request.id, Some(json!("a")));
+        // no params are read like an empty object
+        let params: std::collections::HashMap<String, Value> = request.params().unwrap();
+        assert!(params.is_empty());
+    }
+}
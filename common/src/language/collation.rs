@@ -0,0 +1,229 @@
+//! Orders names the way people expect them in a list. Numbers are compared by their value, so "file2"
+//! comes before "file10". Letters are compared without regard to case and accents first, then by
+//! their accents and then by their case, following the alphabet of the app language where it has
+//! letters of its own (ñ in Spanish, ł in Polish, č in Croatian...).
+
+use std::cmp::Ordering;
+
+use unic_langid::LanguageIdentifier;
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+use super::APP_LANG;
+
+/// Compares two names using the rules of the app language
+pub fn compare(a: &str, b: &str) -> Ordering {
+    compare_in(&APP_LANG.read(), a, b)
+}
+
+/// Compares two names using the rules of `language`
+pub fn compare_in(language: &LanguageIdentifier, a: &str, b: &str) -> Ordering {
+    let alphabet = Alphabet::of(language);
+    let a_key = SortKey::new(a, alphabet);
+    let b_key = SortKey::new(b, alphabet);
+    a_key
+        .primary
+        .cmp(&b_key.primary)
+        .then_with(|| a_key.accents.cmp(&b_key.accents))
+        .then_with(|| a_key.uppercase.cmp(&b_key.uppercase))
+        .then_with(|| a_key.leading_zeros.cmp(&b_key.leading_zeros))
+        .then_with(|| a.cmp(b))
+}
+
+/// Sorts the items by the name `f` returns for them, using the rules of the app language
+pub fn sort_by_name<T, F, S>(items: &mut [T], f: F)
+where
+    F: Fn(&T) -> S,
+    S: AsRef<str>,
+{
+    let language = APP_LANG.read().clone();
+    items.sort_by(|a, b| compare_in(&language, f(a).as_ref(), f(b).as_ref()));
+}
+
+/// The letter a name is listed under in the app language, lowercase and without accents unless
+/// they make a letter of its own. Names starting with a number are listed under '#'
+pub fn initial(name: &str) -> Option<char> {
+    let alphabet = Alphabet::of(&APP_LANG.read());
+    let key = SortKey::new(name.chars().next()?.to_string().as_str(), alphabet);
+    let initial = match key.primary.first()? {
+        Primary::Number { .. } => '#',
+        Primary::Letter(base, 0) => *base,
+        // the letter itself, like 'ñ' in Spanish
+        Primary::Letter(..) => name.chars().next()?.to_lowercase().next()?,
+    };
+    Some(initial)
+}
+
+#[derive(Clone, Copy)]
+enum Alphabet {
+    Default,
+    Spanish,
+    Polish,
+    // Croatian, Bosnian and the latin script of Serbian
+    SerboCroatian,
+}
+
+impl Alphabet {
+    fn of(language: &LanguageIdentifier) -> Self {
+        match language.language.as_str() {
+            "es" => Alphabet::Spanish,
+            "pl" => Alphabet::Polish,
+            "hr" | "bs" | "sr" => Alphabet::SerboCroatian,
+            _ => Alphabet::Default,
+        }
+    }
+
+    // letters which are sorted after the one they're based on instead of next to it
+    fn letter(&self, c: char) -> Option<(char, u8)> {
+        let letter = match (self, c) {
+            (Alphabet::Spanish, 'ñ') => ('n', 1),
+            (Alphabet::Polish, 'ą') => ('a', 1),
+            (Alphabet::Polish, 'ć') => ('c', 1),
+            (Alphabet::Polish, 'ę') => ('e', 1),
+            (Alphabet::Polish, 'ł') => ('l', 1),
+            (Alphabet::Polish, 'ń') => ('n', 1),
+            (Alphabet::Polish, 'ó') => ('o', 1),
+            (Alphabet::Polish, 'ś') => ('s', 1),
+            (Alphabet::Polish, 'ź') => ('z', 1),
+            (Alphabet::Polish, 'ż') => ('z', 2),
+            (Alphabet::SerboCroatian, 'č') => ('c', 1),
+            (Alphabet::SerboCroatian, 'ć') => ('c', 2),
+            (Alphabet::SerboCroatian, 'đ') => ('d', 1),
+            (Alphabet::SerboCroatian, 'š') => ('s', 1),
+            (Alphabet::SerboCroatian, 'ž') => ('z', 1),
+            _ => return None,
+        };
+        Some(letter)
+    }
+}
+
+// letters which don't decompose into a base letter and accents
+fn fold(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'æ' => "ae",
+        'œ' => "oe",
+        'ß' => "ss",
+        'ø' => "o",
+        'ł' => "l",
+        'đ' | 'ð' => "d",
+        'ı' => "i",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+// numbers come before letters, and shorter numbers (without leading zeros) before longer ones
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Primary {
+    Number { len: usize, digits: String },
+    Letter(char, u8),
+}
+
+#[derive(Default)]
+struct SortKey {
+    primary: Vec<Primary>,
+    // the accents of every letter, compared when the letters are the same
+    accents: Vec<String>,
+    // lowercase letters come first
+    uppercase: Vec<bool>,
+    leading_zeros: Vec<usize>,
+}
+
+impl SortKey {
+    fn new(name: &str, alphabet: Alphabet) -> Self {
+        let mut key = SortKey::default();
+        let mut chars = name.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c.is_ascii_digit() {
+                let mut digits = String::from(c);
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(d);
+                }
+                let number = digits.trim_start_matches('0');
+                // all zeros is still a number
+                let number = if number.is_empty() { "0" } else { number };
+                key.leading_zeros.push(digits.len() - number.len());
+                key.primary.push(Primary::Number {
+                    len: number.len(),
+                    digits: number.to_string(),
+                });
+                continue;
+            }
+
+            let uppercase = c.is_uppercase();
+            for lower in c.to_lowercase() {
+                key.push_letter(lower, uppercase, alphabet);
+            }
+        }
+        key
+    }
+
+    fn push_letter(&mut self, c: char, uppercase: bool, alphabet: Alphabet) {
+        if let Some((base, rank)) = alphabet.letter(c) {
+            self.primary.push(Primary::Letter(base, rank));
+            self.accents.push(String::new());
+            self.uppercase.push(uppercase);
+        } else if let Some(folded) = fold(c) {
+            for base in folded.chars() {
+                self.primary.push(Primary::Letter(base, 0));
+                self.accents.push(c.to_string());
+                self.uppercase.push(uppercase);
+            }
+        } else {
+            let mut base = None;
+            let mut accents = String::new();
+            decompose_canonical(c, |d| {
+                if base.is_none() && !is_combining_mark(d) {
+                    base = Some(d);
+                } else {
+                    accents.push(d);
+                }
+            });
+            self.primary.push(Primary::Letter(base.unwrap_or(c), 0));
+            self.accents.push(accents);
+            self.uppercase.push(uppercase);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use unic_langid::langid;
+
+    fn sorted(language: LanguageIdentifier, names: &[&str]) -> Vec<String> {
+        let mut names: Vec<String> = names.iter().map(|s| s.to_string()).collect();
+        names.sort_by(|a, b| compare_in(&language, a, b));
+        names
+    }
+
+    #[test]
+    fn numbers_by_value() {
+        assert_eq!(
+            sorted(
+                langid!("en-US"),
+                &["file10.txt", "file2.txt", "File1.txt", "file02.txt", "file"]
+            ),
+            ["file", "File1.txt", "file2.txt", "file02.txt", "file10.txt"]
+        );
+    }
+
+    #[test]
+    fn case_and_accents_come_last() {
+        assert_eq!(
+            sorted(langid!("de"), &["Zebra", "über", "Ufer", "uber", "apfel"]),
+            ["apfel", "uber", "über", "Ufer", "Zebra"]
+        );
+    }
+
+    #[test]
+    fn letters_of_the_language() {
+        let names = ["nube", "ñandú", "oso"];
+        assert_eq!(sorted(langid!("es-MX"), &names), ["nube", "ñandú", "oso"]);
+        let names = ["łoś", "lody", "mleko"];
+        assert_eq!(sorted(langid!("pl"), &names), ["lody", "łoś", "mleko"]);
+        assert_eq!(sorted(langid!("en-US"), &names), ["lody", "łoś", "mleko"]);
+        let names = ["čaj", "cvijet", "dan"];
+        assert_eq!(sorted(langid!("hr-HR"), &names), ["cvijet", "čaj", "dan"]);
+        assert_eq!(sorted(langid!("en-US"), &names), ["čaj", "cvijet", "dan"]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Translations made by the community, loaded from the `locales` folder of the app data. Every
+//! `<language id>.ftl` file there is one language, `fr-FR.ftl` for example, written like the bundled
+//! ones. Whatever it doesn't translate falls back to the bundled texts. A `language-name` message
+//! gives the name shown in the language picker, the id is shown otherwise.
+
+use std::{fs, path::Path};
+
+use fluent_templates::{
+    fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource},
+    LanguageIdentifier,
+};
+use tracing::log;
+
+const NAME_KEY: &str = "language-name";
+
+pub struct CommunityLanguage {
+    pub id: LanguageIdentifier,
+    pub name: String,
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl CommunityLanguage {
+    pub fn parse(id: LanguageIdentifier, source: String) -> Result<Self, String> {
+        let resource = FluentResource::try_new(source)
+            .map_err(|(_, errors)| format!("{} syntax errors", errors.len()))?;
+        let mut bundle = FluentBundle::new_concurrent(vec![id.clone()]);
+        // like the bundled languages
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .map_err(|errors| format!("{} duplicated messages", errors.len()))?;
+        let mut language = Self {
+            name: id.to_string(),
+            id,
+            bundle,
+        };
+        if let Some(name) = language.lookup(NAME_KEY, None) {
+            language.name = name;
+        }
+        Ok(language)
+    }
+
+    /// `key` is either a message or `message.attribute`, like for `get_local_text`
+    pub fn lookup(&self, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+        let (message, attribute) = match key.split_once('.') {
+            Some((message, attribute)) => (message, Some(attribute)),
+            None => (key, None),
+        };
+        let message = self.bundle.get_message(message)?;
+        let pattern = match attribute {
+            Some(attribute) => message.get_attribute(attribute)?.value(),
+            None => message.value()?,
+        };
+        let mut errors = vec![];
+        let text = self.bundle.format_pattern(pattern, args, &mut errors);
+        if !errors.is_empty() {
+            log::warn!("failed to format {key}: {errors:?}");
+        }
+        Some(text.into_owned())
+    }
+}
+
+/// The languages in `dir`. Files which can't be read are skipped
+pub fn load(dir: &Path) -> Vec<CommunityLanguage> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut languages: Vec<CommunityLanguage> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "ftl").unwrap_or(false))
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_str()?.parse().ok()?;
+            let source = fs::read_to_string(&path).ok()?;
+            CommunityLanguage::parse(id, source)
+                .map_err(|e| log::warn!("skipping translation {}: {e}", path.display()))
+                .ok()
+        })
+        .collect();
+    languages.sort_by(|a, b| a.name.cmp(&b.name));
+    languages
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn looks_up_messages_and_attributes() {
+        let source = "language-name = Français\nuplink = Uplink\n    .send = Envoyer\n    .members-count = Membres ({ $num })\n";
+        let language = CommunityLanguage::parse("fr-FR".parse().unwrap(), source.into()).unwrap();
+        assert_eq!(language.name, "Français");
+        assert_eq!(language.lookup("uplink", None).as_deref(), Some("Uplink"));
+        assert_eq!(
+            language.lookup("uplink.send", None).as_deref(),
+            Some("Envoyer")
+        );
+        let mut args = FluentArgs::new();
+        args.set("num", 3);
+        assert_eq!(
+            language
+                .lookup("uplink.members-count", Some(&args))
+                .as_deref(),
+            Some("Membres (3)")
+        );
+        // left to the bundled texts
+        assert_eq!(language.lookup("uplink.cancel", None), None);
+        assert!(CommunityLanguage::parse("fr-FR".parse().unwrap(), "uplink = {".into()).is_err());
+    }
+}
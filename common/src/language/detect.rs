@@ -0,0 +1,102 @@
+//! Guesses the language of a conversation from its recent messages. This only has to be good enough to
+//! pick a spellcheck dictionary, so it counts common words instead of using a language model.
+
+// (language name as used by `LANGUAGES`, common words of that language)
+const COMMON_WORDS: [(&str, &[&str]); 6] = [
+    (
+        "English (USA)",
+        &[
+            "the", "and", "is", "are", "you", "to", "of", "that", "it", "for", "with", "what",
+            "this", "have", "was", "not",
+        ],
+    ),
+    (
+        "Deutsch",
+        &[
+            "und", "der", "die", "das", "ist", "nicht", "ich", "du", "ein", "eine", "mit", "auch",
+            "was", "wie", "aber", "sie",
+        ],
+    ),
+    (
+        "Español (México)",
+        &[
+            "el", "la", "que", "y", "los", "las", "es", "por", "para", "con", "pero", "qué",
+            "está", "yo", "muy", "del",
+        ],
+    ),
+    (
+        "Português (Brasil)",
+        &[
+            "o", "que", "e", "não", "é", "um", "uma", "para", "com", "você", "mas", "está", "os",
+            "eu", "muito", "do",
+        ],
+    ),
+    (
+        "Polski (Polska)",
+        &[
+            "i", "w", "nie", "się", "to", "jest", "na", "że", "z", "co", "jak", "ale", "tak",
+            "czy", "już", "mnie",
+        ],
+    ),
+    (
+        "Croatia (Hrvatska)",
+        &[
+            "i", "je", "u", "da", "se", "na", "ne", "su", "što", "za", "ali", "sam", "smo", "kako",
+            "bi", "ovo",
+        ],
+    ),
+];
+
+// fewer matching words than this are not enough to tell the language
+const MIN_MATCHES: usize = 3;
+
+/// Returns the name of the most likely language of `texts`, or None if there is no clear winner
+pub fn detect_language<'a>(texts: impl IntoIterator<Item = &'a str>) -> Option<&'static str> {
+    let mut counts = [0usize; COMMON_WORDS.len()];
+    for text in texts {
+        for word in text
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|word| !word.is_empty())
+        {
+            let word = word.to_lowercase();
+            for (count, (_, words)) in counts.iter_mut().zip(COMMON_WORDS.iter()) {
+                if words.contains(&word.as_str()) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    let (best, best_count) = counts
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by_key(|(_, count)| *count)?;
+    let runner_up = counts
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != best)
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or_default();
+    (best_count >= MIN_MATCHES && best_count > runner_up).then_some(COMMON_WORDS[best].0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language(["what is the plan for today?", "I have no idea"]),
+            Some("English (USA)")
+        );
+        assert_eq!(
+            detect_language(["Ich weiß nicht, was das ist", "und du?"]),
+            Some("Deutsch")
+        );
+        assert_eq!(detect_language(["ok", "👍"]), None);
+        assert_eq!(detect_language([]), None);
+    }
+}
@@ -1,12 +1,21 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use fluent_templates::{
-    fluent_bundle::FluentValue, once_cell::sync::Lazy, LanguageIdentifier, Loader,
+    fluent_bundle::{FluentArgs, FluentValue},
+    once_cell::sync::Lazy,
+    LanguageIdentifier, Loader,
 };
 use parking_lot::RwLock;
-use unic_langid::langid;
+use unic_langid::{langid, CharacterDirection};
 
-use crate::LOCALES;
+use crate::{LOCALES, STATIC_ARGS};
+
+use self::community::CommunityLanguage;
+
+pub mod collation;
+mod community;
+mod detect;
+pub use detect::detect_language;
 
 pub const US_ENGLISH: (LanguageIdentifier, &str) = (langid!("en-US"), "English (USA)");
 
@@ -26,6 +35,8 @@ static LANGUAGES: Lazy<HashMap<String, (LanguageIdentifier, &'static str)>> = La
     add(&mut map, &(langid!("sr-RS"), "Serbia (Srbija)"));
     add(&mut map, &(langid!("hr-HR"), "Croatia (Hrvatska)"));
     add(&mut map, &(langid!("pl"), "Polski (Polska)"));
+    add(&mut map, &(langid!("ar"), "العربية"));
+    add(&mut map, &(langid!("he"), "עברית"));
     add(
         &mut map,
         &(
@@ -36,39 +47,105 @@ static LANGUAGES: Lazy<HashMap<String, (LanguageIdentifier, &'static str)>> = La
     map
 });
 
-static APP_LANG: Lazy<RwLock<(LanguageIdentifier, &str)>> = Lazy::new(|| RwLock::new(US_ENGLISH));
+static APP_LANG: Lazy<RwLock<LanguageIdentifier>> = Lazy::new(|| RwLock::new(US_ENGLISH.0));
+// the translations found in the locales folder, see `load_community_languages`
+static COMMUNITY_LANGUAGES: Lazy<RwLock<Vec<Arc<CommunityLanguage>>>> = Lazy::new(Default::default);
+// set when the app language is one of them. it is looked up first
+static COMMUNITY_LANG: Lazy<RwLock<Option<Arc<CommunityLanguage>>>> = Lazy::new(Default::default);
 
+/// Switches the app language right away, the next `get_local_text` already uses it. Returns the
+/// name of the new language, English if `new_language` isn't known
 pub fn change_language(new_language: String) -> String {
-    let new_language_identifier = LANGUAGES.get(&new_language);
-
-    match new_language_identifier {
+    if let Some(new_lang) = LANGUAGES.get(&new_language) {
+        *APP_LANG.write() = new_lang.0.clone();
+        *COMMUNITY_LANG.write() = None;
+        return new_lang.1.to_string();
+    }
+    let community = COMMUNITY_LANGUAGES
+        .read()
+        .iter()
+        .find(|lang| lang.name == new_language)
+        .cloned();
+    match community {
         Some(new_lang) => {
-            *APP_LANG.write() = new_lang.clone();
-            new_lang.1.to_string()
+            // what isn't translated comes from the bundled texts for the same language if there are
+            // any, and from English otherwise
+            *APP_LANG.write() = new_lang.id.clone();
+            let name = new_lang.name.clone();
+            *COMMUNITY_LANG.write() = Some(new_lang);
+            name
         }
         None => {
-            *APP_LANG.write() = US_ENGLISH;
+            *APP_LANG.write() = US_ENGLISH.0;
+            *COMMUNITY_LANG.write() = None;
             US_ENGLISH.1.to_string()
         }
     }
 }
 
+/// (Re)reads the translations in the locales folder. A community language which is in use is
+/// switched to its new version. Returns how many were found
+pub fn load_community_languages() -> usize {
+    let languages = community::load(&STATIC_ARGS.locales_path);
+    let count = languages.len();
+    *COMMUNITY_LANGUAGES.write() = languages.into_iter().map(Arc::new).collect();
+    let current = COMMUNITY_LANG.read().as_ref().map(|lang| lang.name.clone());
+    if let Some(current) = current {
+        change_language(current);
+    }
+    count
+}
+
 pub fn get_id_of(language: &str) -> String {
-    let language_identifier = LANGUAGES.get(language);
-    match language_identifier {
-        Some(lang) => lang.0.to_string(),
-        None => US_ENGLISH.0.to_string(),
+    if let Some(lang) = LANGUAGES.get(language) {
+        return lang.0.to_string();
+    }
+    COMMUNITY_LANGUAGES
+        .read()
+        .iter()
+        .find(|lang| lang.name == language)
+        .map(|lang| lang.id.to_string())
+        .unwrap_or_else(|| US_ENGLISH.0.to_string())
+}
+
+/// Whether the app language is written from right to left, which mirrors the layout
+pub fn is_rtl() -> bool {
+    is_rtl_language(&APP_LANG.read())
+}
+
+fn is_rtl_language(language: &LanguageIdentifier) -> bool {
+    language.character_direction() == CharacterDirection::RTL
+}
+
+/// The `dir` of the root of the app
+pub fn text_direction() -> &'static str {
+    if is_rtl() {
+        "rtl"
+    } else {
+        "ltr"
     }
 }
 
+/// The bundled languages followed by the ones of the community
 pub fn get_available_languages() -> Vec<String> {
     let mut v: Vec<String> = LANGUAGES.keys().cloned().collect();
     v.sort();
+    v.extend(
+        COMMUNITY_LANGUAGES
+            .read()
+            .iter()
+            .map(|lang| lang.name.clone())
+            .filter(|name| !LANGUAGES.contains_key(name)),
+    );
     v
 }
 
 pub fn get_local_text(text: &str) -> String {
-    LOCALES.lookup(&APP_LANG.read().0, text)
+    let community = COMMUNITY_LANG
+        .read()
+        .as_ref()
+        .and_then(|lang| lang.lookup(text, None));
+    community.unwrap_or_else(|| LOCALES.lookup(&APP_LANG.read(), text))
 }
 
 // Looks and formats a local text using the given args
@@ -94,5 +171,28 @@ where
         builder(&mut map);
         map
     };
-    LOCALES.lookup_with_args(&APP_LANG.read().0, text, &args)
+    let community = COMMUNITY_LANG.read().as_ref().and_then(|lang| {
+        let mut fluent_args = FluentArgs::new();
+        for (key, val) in args.iter() {
+            fluent_args.set(key.as_ref().to_string(), val.clone());
+        }
+        lang.lookup(text, Some(&fluent_args))
+    });
+    community.unwrap_or_else(|| LOCALES.lookup_with_args(&APP_LANG.read(), text, &args))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_right_to_left_languages() {
+        let rtl: Vec<&str> = LANGUAGES
+            .values()
+            .filter(|(id, _)| is_rtl_language(id))
+            .map(|(_, name)| *name)
+            .collect();
+        assert_eq!(rtl.len(), 2);
+        assert!(rtl.contains(&"العربية") && rtl.contains(&"עברית"));
+    }
 }
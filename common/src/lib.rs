@@ -1,7 +1,11 @@
+pub mod control;
 pub mod language;
 pub mod notifications;
 pub mod profile_update_channel;
+pub mod profiles;
+pub mod slash_commands;
 pub mod sounds;
+pub mod spellcheck;
 pub mod state;
 pub mod testing;
 pub mod upload_file_channel;
@@ -14,6 +18,7 @@ use clap::Parser;
 pub use icons;
 use icons::outline::Shape as Icon;
 use once_cell::sync::Lazy;
+use parking_lot::RwLock;
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
@@ -55,6 +60,16 @@ pub struct Args {
     /// configures log output
     #[clap(long, default_value_t = false)]
     pub log_to_file: bool,
+    /// starts with extensions disabled, the default theme and animations off. Used to recover from a broken extension or theme.
+    #[clap(long, default_value_t = false)]
+    pub safe_mode: bool,
+    /// runs the account without a window. Uplink is then driven through the control socket, see
+    /// `control_socket_path`. The PIN is read from UPLINK_PIN, or from the terminal if it isn't set.
+    #[clap(long, default_value_t = false)]
+    pub headless: bool,
+    /// an `uplink://` link to open, passed by the system when one is clicked. If Uplink already
+    /// runs, the link is opened there
+    pub link: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq)]
@@ -73,6 +88,25 @@ pub enum DiscoveryMode {
     Disable,
 }
 
+const DEFAULT_SHUTTLE_ADDRESS: &str =
+    "/ip4/159.65.41.31/tcp/8848/p2p/12D3KooWRF2bz3KDRPvBs1FASRDRk7BfdYc1RUcfwKsz7UBEu7mL";
+
+impl DiscoveryMode {
+    /// The multiaddresses of the nodes used for discovery and relaying, empty if none are configured
+    pub fn addresses(&self) -> Vec<String> {
+        match self {
+            DiscoveryMode::RzPoint { address } => vec![address.clone()],
+            DiscoveryMode::Shuttle => match std::env::var("SHUTTLE_ADDR_POINT") {
+                Ok(val) if !val.trim().is_empty() => {
+                    val.split(',').map(|addr| addr.trim().to_string()).collect()
+                }
+                _ => vec![DEFAULT_SHUTTLE_ADDRESS.into()],
+            },
+            DiscoveryMode::Full | DiscoveryMode::Disable => vec![],
+        }
+    }
+}
+
 impl std::str::FromStr for DiscoveryMode {
     type Err = warp::error::Error;
     fn from_str(mode: &str) -> Result<Self, Self::Err> {
@@ -88,34 +122,31 @@ impl std::str::FromStr for DiscoveryMode {
 #[derive(Debug)]
 pub struct StaticArgs {
     /// ~/.uplink
-    /// contains the following: extra (folder), extensions (folder), themes (folder), fonts (folder), .user
+    /// contains the following: extra (folder), extensions (folder), themes (folder), fonts (folder), locales (folder), dictionaries (folder), .user, profiles (folder), profiles.json
     pub dot_uplink: PathBuf,
-    /// ~/.uplink/.user
-    /// contains the following: warp (folder), state.json, debug.log
-    pub uplink_path: PathBuf,
+    /// the directory of the active profile, see `StaticArgs::uplink_path`
+    profile_path: RwLock<PathBuf>,
     /// Directory for temporary files and deleted everytime app is closed or opened
     pub temp_files: PathBuf,
     /// custom themes for the user
     pub themes_path: PathBuf,
     /// custom fonts for the user
     pub fonts_path: PathBuf,
-    /// state.json: a serialized version of State which gets saved every time state is modified
-    pub cache_path: PathBuf,
-    /// a fake tesseract_path to prevent anything from mutating the tesseract keypair after it has been created (probably not necessary)
-    pub mock_cache_path: PathBuf,
-    /// houses warp specific data
-    pub warp_path: PathBuf,
+    /// translations made by the community, one .ftl file per language
+    pub locales_path: PathBuf,
+    /// hunspell dictionaries for spellchecking
+    pub dictionaries_path: PathBuf,
     /// a debug log which is only written to when the settings are enabled. otherwise logs are only sent to stdout
+    /// shared by all profiles
     pub logger_path: PathBuf,
     /// contains the keypair used for IPFS
     pub tesseract_file: String,
-    /// the unlock and auth pages don't have access to State but need to know if they should play a notification.
-    /// part of state is serialized and saved here
-    pub login_config_path: PathBuf,
     /// path to custom plugins
     pub extensions_path: PathBuf,
     /// crash logs
     pub crash_logs: PathBuf,
+    /// number of launches since Uplink last ran long enough to be considered stable. used to detect crash loops
+    pub startup_attempts_path: PathBuf,
     /// recordings
     pub recordings: PathBuf,
     /// seconds
@@ -147,25 +178,27 @@ pub static STATIC_ARGS: Lazy<StaticArgs> = Lazy::new(|| {
         _ => dirs::home_dir().unwrap_or_default().join(".uplink"),
     };
 
-    let uplink_path = uplink_container.join(".user");
-    let warp_path = uplink_path.join("warp");
+    let profile_path = profiles::Profiles::load_from(&uplink_container)
+        .active()
+        .path(&uplink_container);
+    // app wide files stay where they were before profiles existed
+    let default_profile_path = uplink_container.join(".user");
     StaticArgs {
         dot_uplink: uplink_container.clone(),
-        uplink_path: uplink_path.clone(), // TODO: Should this be "User path" instead?
+        profile_path: RwLock::new(profile_path),
         temp_files: uplink_container.join("temp_files"),
         themes_path: uplink_container.join("themes"),
         fonts_path: uplink_container.join("fonts"),
-        cache_path: uplink_path.join("state.json"),
+        locales_path: uplink_container.join("locales"),
+        dictionaries_path: uplink_container.join("dictionaries"),
         extensions_path: uplink_container.join("extensions"),
         crash_logs: uplink_container.join("crash-logs"),
+        startup_attempts_path: default_profile_path.join("startup_attempts"),
         recordings: uplink_container.join("recordings"),
-        mock_cache_path: uplink_path.join("mock-state.json"),
-        warp_path: warp_path.clone(),
-        logger_path: uplink_path.join("debug.log"),
+        logger_path: default_profile_path.join("debug.log"),
         typing_indicator_refresh: 5,
         typing_indicator_timeout: 6,
         tesseract_file: "tesseract.json".into(),
-        login_config_path: uplink_path.join("login_config.json"),
         use_mock,
         discovery: args.discovery.unwrap_or_default(),
         disable_quic: args.disable_quic,
@@ -173,6 +206,114 @@ pub static STATIC_ARGS: Lazy<StaticArgs> = Lazy::new(|| {
     }
 });
 
+impl StaticArgs {
+    /// ~/.uplink/.user, or ~/.uplink/profiles/<id> for profiles other than the default one
+    /// contains the following: warp (folder), state (folder)
+    pub fn uplink_path(&self) -> PathBuf {
+        self.profile_path.read().clone()
+    }
+
+    /// houses warp specific data
+    pub fn warp_path(&self) -> PathBuf {
+        self.uplink_path().join("warp")
+    }
+
+    /// state: the parts of State which are persisted, one file per store. see `state::persistence`
+    pub fn state_path(&self) -> PathBuf {
+        self.uplink_path().join("state")
+    }
+
+    /// state.json: a serialized version of State, from before it was split into stores. only read to migrate it
+    pub fn cache_path(&self) -> PathBuf {
+        self.uplink_path().join("state.json")
+    }
+
+    /// a fake tesseract_path to prevent anything from mutating the tesseract keypair after it has been created (probably not necessary)
+    pub fn mock_cache_path(&self) -> PathBuf {
+        self.uplink_path().join("mock-state.json")
+    }
+
+    /// the unlock and auth pages don't have access to State but need to know if they should play a notification.
+    /// part of state is serialized and saved here
+    pub fn login_config_path(&self) -> PathBuf {
+        self.uplink_path().join("login_config.json")
+    }
+
+    /// invite links created for group conversations
+    pub fn group_invites_path(&self) -> PathBuf {
+        self.uplink_path().join("group_invites.json")
+    }
+
+    /// the uploads and downloads which finished or failed. see `state::transfer_history`
+    pub fn transfer_history_path(&self) -> PathBuf {
+        self.uplink_path().join("transfer_history.json")
+    }
+
+    /// the uploads and downloads which were running the last time the tracker changed. see `state::data_transfer`
+    pub fn interrupted_transfers_path(&self) -> PathBuf {
+        self.uplink_path().join("interrupted_transfers.json")
+    }
+
+    /// the hashes of the uploaded files, by the id of the file. see `warp_runner::checksum`
+    pub fn checksums_path(&self) -> PathBuf {
+        self.uplink_path().join("checksums.json")
+    }
+
+    /// where the data pinned by Constellation is kept. see `warp_runner::storage_backend`
+    pub fn storage_backend_path(&self) -> PathBuf {
+        self.uplink_path().join("storage_backend.json")
+    }
+
+    /// recent events shown in the activity feed. see `state::activity`
+    pub fn activity_path(&self) -> PathBuf {
+        self.uplink_path().join("activity.json")
+    }
+
+    /// the calls of every conversation. see `state::call_log`
+    pub fn call_log_path(&self) -> PathBuf {
+        self.uplink_path().join("call_log.json")
+    }
+
+    /// what was picked from the quick switcher lately. see `state::quick_switcher`
+    pub fn quick_switcher_path(&self) -> PathBuf {
+        self.uplink_path().join("quick_switcher.json")
+    }
+
+    /// the port bots post to, kept so their addresses stay the same. see `utils::incoming_webhooks`
+    pub fn incoming_webhooks_path(&self) -> PathBuf {
+        self.uplink_path().join("incoming_webhooks.json")
+    }
+
+    /// the local copy of the messages, encrypted. see `warp_runner::message_store`
+    pub fn message_store_path(&self) -> PathBuf {
+        self.uplink_path().join("messages.db")
+    }
+
+    /// control.sock: the JSON-RPC socket served in headless mode, see `control`. a named pipe of the
+    /// user and the profile on windows
+    pub fn control_socket_path(&self) -> PathBuf {
+        #[cfg(windows)]
+        return PathBuf::from(control::pipe::name("control", &self.uplink_path()));
+        #[cfg(not(windows))]
+        self.uplink_path().join("control.sock")
+    }
+
+    /// where the running Uplink listens for the links the system launches new instances with, see
+    /// `utils::handoff`. not per profile: the link is opened by whichever profile runs
+    pub fn links_socket_path(&self) -> PathBuf {
+        #[cfg(windows)]
+        return PathBuf::from(control::pipe::name("links", &self.dot_uplink));
+        #[cfg(not(windows))]
+        self.dot_uplink.join("links.sock")
+    }
+
+    /// points the profile specific paths at another profile. warp has to be restarted afterwards,
+    /// which the warp runner does when it switches profiles.
+    pub fn set_profile_path(&self, path: PathBuf) {
+        *self.profile_path.write() = path;
+    }
+}
+
 // allows the UI to send commands to Warp
 pub static WARP_CMD_CH: Lazy<WarpCmdChannels> = Lazy::new(|| {
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
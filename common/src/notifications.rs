@@ -1,13 +1,18 @@
 use std::thread;
 
 use crate::language::get_local_text;
+use crate::utils::app_lock;
+use crate::warp_runner::{MultiPassCmd, WarpCmd};
+use crate::WARP_CMD_CH;
 
 use super::sounds::{Play, Sounds};
 use derive_more::Display;
+use futures::channel::oneshot;
 use notify_rust::Notification;
 use std::sync::Arc;
 use tracing::log;
 use uuid::Uuid;
+use warp::crypto::DID;
 
 use once_cell::sync::Lazy;
 use tokio::sync::{
@@ -26,10 +31,106 @@ pub enum NotificationAction {
     DisplayChat(Uuid),
     #[display(fmt = "FriendListPending")]
     FriendListPending,
+    // the link is taken with `handoff::take_pending`
+    #[display(fmt = "OpenHandoff")]
+    OpenHandoff,
+    // the text of an inline reply, sent by the app like a message typed in the chatbar
+    #[display(fmt = "Reply")]
+    Reply(Uuid, String),
     #[display(fmt = "Dummy")]
     Dummy,
 }
 
+/// Ways to answer a notification from the notification itself, without focusing the window
+#[derive(Debug, Clone)]
+pub enum Responses {
+    // an inline reply field which sends the text to the conversation
+    Reply(Uuid),
+    // accept and deny buttons for an incoming friend request
+    FriendRequest(DID),
+}
+
+// the ids of the response actions. there are no inline replies on linux
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+const REPLY: &str = "toast_actions.reply";
+const ACCEPT: &str = "toast_actions.accept";
+const DENY: &str = "toast_actions.deny";
+
+#[derive(Debug)]
+enum Response {
+    Reply(Uuid, String),
+    Accept(DID),
+    Deny(DID),
+}
+
+impl Response {
+    // the responses without a reply text
+    fn for_action(responses: &Responses, action_id: &str) -> Option<Self> {
+        match responses {
+            Responses::FriendRequest(did) if action_id == ACCEPT => Some(Self::Accept(did.clone())),
+            Responses::FriendRequest(did) if action_id == DENY => Some(Self::Deny(did.clone())),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn reply(responses: &Responses, text: String) -> Option<Self> {
+        match responses {
+            Responses::Reply(conv_id) if !text.trim().is_empty() => {
+                Some(Self::Reply(*conv_id, text))
+            }
+            _ => None,
+        }
+    }
+
+    // called from the notification thread, so it waits for warp to be done. replies are sent by the app
+    fn dispatch(self) {
+        // the app has to be unlocked to answer
+        if app_lock::is_locked() {
+            log::debug!("not responding from a notification while locked");
+            return;
+        }
+        log::debug!("responding from a notification: {self:?}");
+        match self {
+            Self::Reply(conv_id, text) => {
+                // the app checks and sends it, and queues it in the outbox if that fails
+                if let Err(e) = NOTIFICATION_LISTENER
+                    .tx
+                    .send(NotificationAction::Reply(conv_id, text))
+                {
+                    log::error!("failed to send the reply to the app: {e}");
+                }
+            }
+            Self::Accept(did) => {
+                let (tx, rx) = oneshot::channel();
+                run_warp_cmd(
+                    WarpCmd::MultiPass(MultiPassCmd::AcceptRequest { did, rsp: tx }),
+                    rx,
+                );
+            }
+            Self::Deny(did) => {
+                let (tx, rx) = oneshot::channel();
+                run_warp_cmd(
+                    WarpCmd::MultiPass(MultiPassCmd::DenyRequest { did, rsp: tx }),
+                    rx,
+                );
+            }
+        }
+    }
+}
+
+fn run_warp_cmd<T>(cmd: WarpCmd, rx: oneshot::Receiver<Result<T, warp::error::Error>>) {
+    if let Err(e) = WARP_CMD_CH.tx.send(cmd) {
+        log::error!("failed to send warp command: {e}");
+        return;
+    }
+    match futures::executor::block_on(rx) {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => log::error!("failed to respond from a notification: {e}"),
+        Err(e) => log::error!("failed to respond from a notification: {e}"),
+    }
+}
+
 pub struct NotificationChannel {
     pub tx: broadcast::Sender<NotificationAction>,
 }
@@ -61,20 +162,55 @@ pub fn push_notification(
     notification_sound: Option<Sounds>,
     timeout: notify_rust::Timeout,
     action: NotificationAction,
+) {
+    push(title, content, notification_sound, timeout, action, None);
+}
+
+/// Like `push_notification`, with a way to answer from the notification where the OS supports it
+pub fn push_notification_with_responses(
+    title: String,
+    content: String,
+    notification_sound: Option<Sounds>,
+    timeout: notify_rust::Timeout,
+    action: NotificationAction,
+    responses: Responses,
+) {
+    push(
+        title,
+        content,
+        notification_sound,
+        timeout,
+        action,
+        Some(responses),
+    );
+}
+
+fn push(
+    title: String,
+    content: String,
+    notification_sound: Option<Sounds>,
+    timeout: notify_rust::Timeout,
+    action: NotificationAction,
+    responses: Option<Responses>,
 ) {
     let summary = format!("Uplink - {title}");
+    // no way to answer while the app is locked
+    let responses = responses.filter(|_| !app_lock::is_locked());
     thread::spawn(move || {
         let action_id = format!("toast_actions.{}", action);
-        show_with_action(
-            Notification::new()
-                .summary(summary.as_ref())
-                .body(&content)
-                .timeout(timeout)
-                .action(&action_id, &get_local_text(&action_id))
-                .finalize(),
-            action_id,
-            action,
-        );
+        let mut notification = Notification::new();
+        notification
+            .summary(summary.as_ref())
+            .body(&content)
+            .timeout(timeout)
+            .action(&action_id, &get_local_text(&action_id));
+        // the freedesktop spec has no inline replies, so only the buttons are added
+        if let Some(Responses::FriendRequest(_)) = responses {
+            notification
+                .action(ACCEPT, &get_local_text(ACCEPT))
+                .action(DENY, &get_local_text(DENY));
+        }
+        show_with_action(notification.finalize(), action_id, action, responses);
     });
 
     if let Some(sound) = notification_sound {
@@ -82,9 +218,20 @@ pub fn push_notification(
     }
 }
 
+/// The window whose taskbar button shows the badge
+#[cfg(target_os = "windows")]
+pub fn set_badge_window(hwnd: isize) {
+    taskbar::WINDOW.store(hwnd, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Shows `count` on the dock icon, the taskbar button or the launcher. 0 removes the badge
 pub fn set_badge(count: u32) -> Result<(), String> {
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     let _ = count;
+    #[cfg(target_os = "windows")]
+    taskbar::set_overlay(count)?;
+    #[cfg(target_os = "linux")]
+    launcher::update(count)?;
     #[cfg(target_os = "macos")]
     unsafe {
         use cocoa::{appkit::NSApp, base::nil, foundation::NSString};
@@ -102,7 +249,12 @@ pub fn set_badge(count: u32) -> Result<(), String> {
 }
 
 // We need to handle them all differently as there isnt a single lib that covers it for all
-fn show_with_action(notification: Notification, action_id: String, action: NotificationAction) {
+fn show_with_action(
+    notification: Notification,
+    action_id: String,
+    action: NotificationAction,
+    responses: Option<Responses>,
+) {
     #[cfg(target_os = "windows")]
     {
         // Notify-rust does not support windows actions so we use the underlying system directly
@@ -121,11 +273,33 @@ fn show_with_action(notification: Notification, action_id: String, action: Notif
         //TODO set proper app id
         let app_id = POWERSHELL_APP_ID.to_string();
         let template_binding = "ToastGeneric";
-        let actions = format!(
+        let mut actions = format!(
             r#"<action content="{}" arguments="{}"/>"#,
             &get_local_text(&action_id),
             &action_id
         );
+        // responses are handled in the background, without bringing the window up
+        match &responses {
+            Some(Responses::Reply(_)) => {
+                actions = format!(
+                    r#"<input id="{REPLY}" type="text" placeHolderContent="{}"/>
+                    <action content="{}" arguments="{REPLY}" hint-inputId="{REPLY}" activationType="background"/>
+                    {actions}"#,
+                    &get_local_text("toast_actions.reply-placeholder"),
+                    &get_local_text(REPLY),
+                );
+            }
+            Some(Responses::FriendRequest(_)) => {
+                actions = format!(
+                    r#"<action content="{}" arguments="{ACCEPT}" activationType="background"/>
+                    <action content="{}" arguments="{DENY}" activationType="background"/>
+                    {actions}"#,
+                    &get_local_text(ACCEPT),
+                    &get_local_text(DENY),
+                );
+            }
+            None => {}
+        }
 
         let toast_xml = windows::Data::Xml::Dom::XmlDocument::new().unwrap();
         if let Err(err) = toast_xml.LoadXml(&windows::core::HSTRING::from(format!(
@@ -172,9 +346,35 @@ fn show_with_action(notification: Notification, action_id: String, action: Notif
                 let event: Option<
                     windows::core::Result<windows::UI::Notifications::ToastActivatedEventArgs>,
                 > = result.as_ref().map(windows::core::Interface::cast);
-                let arguments = event
-                    .and_then(|val| val.ok())
-                    .and_then(|args| args.Arguments().ok());
+                let args = event.and_then(|val| val.ok());
+                let arguments = args.as_ref().and_then(|args| args.Arguments().ok());
+                if let (Some(val), Some(responses)) = (&arguments, &responses) {
+                    let id = val.to_string_lossy();
+                    let response = if id == REPLY {
+                        let text = args
+                            .as_ref()
+                            .and_then(|args| args.UserInput().ok())
+                            .and_then(|input| {
+                                input.Lookup(&windows::core::HSTRING::from(REPLY)).ok()
+                            })
+                            .and_then(|value| {
+                                windows::core::Interface::cast::<
+                                        windows::Foundation::IPropertyValue,
+                                    >(&value)
+                                    .ok()
+                            })
+                            .and_then(|value| value.GetString().ok())
+                            .map(|text| text.to_string_lossy())
+                            .unwrap_or_default();
+                        Response::reply(responses, text)
+                    } else {
+                        Response::for_action(responses, &id)
+                    };
+                    if let Some(response) = response {
+                        response.dispatch();
+                        return Ok(());
+                    }
+                }
                 if let Some(val) = arguments {
                     if val.to_string_lossy().eq(&action_id) {
                         log::trace!("toast action activated {:?}", val);
@@ -212,14 +412,46 @@ fn show_with_action(notification: Notification, action_id: String, action: Notif
     {
         // Notify-rust does not support macos actions but the underlying mac_notification library does
         let action_name = &get_local_text(&action_id);
+        let placeholder = get_local_text("toast_actions.reply-placeholder");
+        let respond = get_local_text("toast_actions.respond");
+        let choices = [get_local_text(ACCEPT), get_local_text(DENY)];
+        let choices = [choices[0].as_str(), choices[1].as_str()];
+        let main_button = match &responses {
+            Some(Responses::Reply(_)) => mac_notification_sys::MainButton::Response(&placeholder),
+            Some(Responses::FriendRequest(_)) => {
+                mac_notification_sys::MainButton::DropdownActions(&respond, &choices)
+            }
+            None => mac_notification_sys::MainButton::SingleAction(action_name),
+        };
         match mac_notification_sys::Notification::default()
             .title(notification.summary.as_str())
             .message(&notification.body)
             .maybe_subtitle(notification.subtitle.as_deref())
-            .main_button(mac_notification_sys::MainButton::SingleAction(action_name))
+            .main_button(main_button)
             .send()
         {
             Ok(response) => match response {
+                mac_notification_sys::NotificationResponse::Reply(text) => {
+                    if let Some(response) =
+                        responses.and_then(|responses| Response::reply(&responses, text))
+                    {
+                        response.dispatch();
+                    }
+                }
+                mac_notification_sys::NotificationResponse::ActionButton(id)
+                    if responses.is_some() =>
+                {
+                    // the dropdown reports the title of the chosen action
+                    let action_id = [ACCEPT, DENY]
+                        .into_iter()
+                        .find(|action_id| get_local_text(action_id) == id)
+                        .unwrap_or_default();
+                    if let Some(response) =
+                        responses.and_then(|responses| Response::for_action(&responses, action_id))
+                    {
+                        response.dispatch();
+                    }
+                }
                 mac_notification_sys::NotificationResponse::ActionButton(id) => {
                     if action_name.eq(&id) {
                         let tx = NOTIFICATION_LISTENER.tx.clone();
@@ -248,7 +480,12 @@ fn show_with_action(notification: Notification, action_id: String, action: Notif
     {
         match notification.show() {
             Ok(handle) => handle.wait_for_action(|id| {
-                if action_id.eq(id) {
+                if let Some(response) = responses
+                    .as_ref()
+                    .and_then(|responses| Response::for_action(responses, id))
+                {
+                    response.dispatch();
+                } else if action_id.eq(id) {
                     let tx = NOTIFICATION_LISTENER.tx.clone();
                     if let Err(e) = tx.send(action) {
                         log::error!("failed to send notification action {}", e);
@@ -263,3 +500,182 @@ fn show_with_action(notification: Notification, action_id: String, action: Notif
         }
     }
 }
+
+// the taskbar has no text badges, so the count is drawn into an overlay icon
+#[cfg(target_os = "windows")]
+mod taskbar {
+    use std::sync::atomic::{AtomicIsize, Ordering};
+
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::{COLORREF, HWND, RECT, TRUE};
+    use windows::Win32::Graphics::Gdi::{
+        CreateBitmap, CreateCompatibleBitmap, CreateCompatibleDC, CreateFontW, CreateSolidBrush,
+        DeleteDC, DeleteObject, DrawTextW, Ellipse, GetDC, GetStockObject, PatBlt, ReleaseDC,
+        SelectObject, SetBkMode, SetTextColor, BLACKNESS, BLACK_BRUSH, CLEARTYPE_QUALITY,
+        CLIP_DEFAULT_PRECIS, DEFAULT_CHARSET, DT_CENTER, DT_SINGLELINE, DT_VCENTER, FF_SWISS,
+        FW_BOLD, NULL_PEN, OUT_DEFAULT_PRECIS, TRANSPARENT, VARIABLE_PITCH, WHITENESS,
+    };
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::{ITaskbarList3, TaskbarList};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateIconIndirect, DestroyIcon, HICON, ICONINFO,
+    };
+
+    use crate::language::get_local_text_with_args;
+
+    // overlays are drawn at the size of small icons
+    const SIZE: i32 = 16;
+    // red, as 0x00BBGGRR
+    const BACKGROUND: COLORREF = COLORREF(0x0030_30E0);
+    const FOREGROUND: COLORREF = COLORREF(0x00FF_FFFF);
+
+    pub static WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+    // called from the main thread, where COM was initialized by the event loop
+    pub fn set_overlay(count: u32) -> Result<(), String> {
+        let hwnd = HWND(WINDOW.load(Ordering::Relaxed));
+        if hwnd.0 == 0 {
+            return Ok(());
+        }
+        unsafe {
+            let taskbar: ITaskbarList3 = CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| e.to_string())?;
+            taskbar.HrInit().map_err(|e| e.to_string())?;
+            if count == 0 {
+                return taskbar
+                    .SetOverlayIcon(hwnd, HICON::default(), PCWSTR::null())
+                    .map_err(|e| e.to_string());
+            }
+            let label = if count > 99 {
+                "99+".to_string()
+            } else {
+                count.to_string()
+            };
+            let icon = draw(&label)?;
+            let description: Vec<u16> =
+                get_local_text_with_args("uplink.unread-badge", vec![("count", label)])
+                    .encode_utf16()
+                    .chain([0])
+                    .collect();
+            let result = taskbar.SetOverlayIcon(hwnd, icon, PCWSTR(description.as_ptr()));
+            let _ = DestroyIcon(icon);
+            result.map_err(|e| e.to_string())
+        }
+    }
+
+    // a red circle with the label in white. the mask leaves the corners transparent
+    unsafe fn draw(label: &str) -> Result<HICON, String> {
+        let screen = GetDC(None);
+        let color = CreateCompatibleBitmap(screen, SIZE, SIZE);
+        ReleaseDC(None, screen);
+        let mask = CreateBitmap(SIZE, SIZE, 1, 1, None);
+        let dc = CreateCompatibleDC(None);
+        let pen = SelectObject(dc, GetStockObject(NULL_PEN));
+
+        let previous = SelectObject(dc, mask);
+        let _ = PatBlt(dc, 0, 0, SIZE, SIZE, WHITENESS);
+        let brush = SelectObject(dc, GetStockObject(BLACK_BRUSH));
+        let _ = Ellipse(dc, 0, 0, SIZE + 1, SIZE + 1);
+
+        SelectObject(dc, color);
+        let _ = PatBlt(dc, 0, 0, SIZE, SIZE, BLACKNESS);
+        let background = CreateSolidBrush(BACKGROUND);
+        SelectObject(dc, background);
+        let _ = Ellipse(dc, 0, 0, SIZE + 1, SIZE + 1);
+        let font = CreateFontW(
+            if label.len() > 2 { -8 } else { -11 },
+            0,
+            0,
+            0,
+            FW_BOLD.0 as i32,
+            0,
+            0,
+            0,
+            DEFAULT_CHARSET.0 as u32,
+            OUT_DEFAULT_PRECIS.0 as u32,
+            CLIP_DEFAULT_PRECIS.0 as u32,
+            CLEARTYPE_QUALITY.0 as u32,
+            (VARIABLE_PITCH.0 | FF_SWISS.0) as u32,
+            w!("Segoe UI"),
+        );
+        let previous_font = SelectObject(dc, font);
+        SetBkMode(dc, TRANSPARENT);
+        SetTextColor(dc, FOREGROUND);
+        let mut text: Vec<u16> = label.encode_utf16().collect();
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: SIZE,
+            bottom: SIZE,
+        };
+        DrawTextW(
+            dc,
+            &mut text,
+            &mut rect,
+            DT_CENTER | DT_VCENTER | DT_SINGLELINE,
+        );
+
+        SelectObject(dc, previous_font);
+        SelectObject(dc, brush);
+        SelectObject(dc, pen);
+        SelectObject(dc, previous);
+        let _ = DeleteObject(font);
+        let _ = DeleteObject(background);
+        let _ = DeleteDC(dc);
+
+        let info = ICONINFO {
+            fIcon: TRUE,
+            xHotspot: 0,
+            yHotspot: 0,
+            hbmMask: mask,
+            hbmColor: color,
+        };
+        let icon = CreateIconIndirect(&info).map_err(|e| e.to_string());
+        let _ = DeleteObject(mask);
+        let _ = DeleteObject(color);
+        icon
+    }
+}
+
+// the Unity launcher API. besides Unity, it is implemented by docks like Plank, Dash to Dock and
+// the KDE task manager
+#[cfg(target_os = "linux")]
+mod launcher {
+    use dbus::{
+        arg::{PropMap, Variant},
+        blocking::SyncConnection,
+        channel::Sender,
+        Message,
+    };
+    use once_cell::sync::Lazy;
+    use tracing::log;
+
+    // the launcher finds the entry by the name of the desktop file
+    const APP_URI: &str = "application://im.satellite.uplink.desktop";
+
+    static SESSION: Lazy<Option<SyncConnection>> = Lazy::new(|| {
+        SyncConnection::new_session()
+            .map_err(|e| log::warn!("no session bus for the launcher badge: {e}"))
+            .ok()
+    });
+
+    pub fn update(count: u32) -> Result<(), String> {
+        let Some(session) = SESSION.as_ref() else {
+            return Ok(());
+        };
+        let mut properties = PropMap::new();
+        properties.insert("count".into(), Variant(Box::new(count as i64)));
+        properties.insert("count-visible".into(), Variant(Box::new(count > 0)));
+        let signal = Message::signal(
+            &"/im/satellite/uplink".into(),
+            &"com.canonical.Unity.LauncherEntry".into(),
+            &"Update".into(),
+        )
+        .append2(APP_URI, properties);
+        session
+            .send(signal)
+            .map_err(|_| "failed to send the launcher update".to_string())?;
+        session.channel().flush();
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+//! Uplink can hold several accounts side by side. Every profile has its own tesseract, warp and
+//! state directory so switching between them never deletes anything. The profile which was used
+//! last is opened on the next launch.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::STATIC_ARGS;
+
+const PROFILES_FILE: &str = "profiles.json";
+pub(crate) const PROFILES_DIR: &str = "profiles";
+// the directory used before profiles existed. it stays the home of the default profile
+const DEFAULT_PROFILE_DIR: &str = ".user";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: Uuid,
+    // empty for the default profile, the UI shows its own name for it
+    pub name: String,
+}
+
+impl Profile {
+    pub fn is_default(&self) -> bool {
+        self.id.is_nil()
+    }
+
+    /// the directory holding the tesseract, warp data and state of the profile
+    pub fn path(&self, dot_uplink: &Path) -> PathBuf {
+        if self.is_default() {
+            dot_uplink.join(DEFAULT_PROFILE_DIR)
+        } else {
+            dot_uplink.join(PROFILES_DIR).join(self.id.to_string())
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profiles {
+    pub active: Uuid,
+    // always starts with the default profile
+    pub all: Vec<Profile>,
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            active: Uuid::nil(),
+            all: vec![Profile {
+                id: Uuid::nil(),
+                name: String::new(),
+            }],
+        }
+    }
+}
+
+impl Profiles {
+    pub fn load() -> Self {
+        Self::load_from(&STATIC_ARGS.dot_uplink)
+    }
+
+    pub(crate) fn load_from(dot_uplink: &Path) -> Self {
+        let mut profiles = std::fs::read_to_string(dot_uplink.join(PROFILES_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str::<Profiles>(&s).ok())
+            .unwrap_or_default();
+        if !profiles.all.iter().any(Profile::is_default) {
+            profiles.all.insert(0, Profiles::default().all.remove(0));
+        }
+        if profiles.get(&profiles.active).is_none() {
+            profiles.active = Uuid::nil();
+        }
+        profiles
+    }
+
+    pub fn save(&self) {
+        let path = STATIC_ARGS.dot_uplink.join(PROFILES_FILE);
+        match serde_json::to_string_pretty(self) {
+            Ok(contents) => {
+                if let Err(e) = std::fs::write(path, contents) {
+                    log::error!("failed to save profiles: {e}");
+                }
+            }
+            Err(e) => log::error!("failed to serialize profiles: {e}"),
+        }
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&Profile> {
+        self.all.iter().find(|profile| &profile.id == id)
+    }
+
+    pub fn active(&self) -> &Profile {
+        self.get(&self.active).unwrap_or(&self.all[0])
+    }
+
+    /// adds a profile without switching to it. a number is appended if the name is taken
+    pub fn add(&mut self, name: &str) -> Profile {
+        let base = name.trim();
+        let mut name = base.to_string();
+        let mut n = 2;
+        while self.all.iter().any(|p| p.name == name) {
+            name = format!("{base} ({n})");
+            n += 1;
+        }
+        let profile = Profile {
+            id: Uuid::new_v4(),
+            name,
+        };
+        self.all.push(profile.clone());
+        profile
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn profiles_keep_separate_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut profiles = Profiles::load_from(dir.path());
+        assert_eq!(profiles.active().path(dir.path()), dir.path().join(".user"));
+
+        let work = profiles.add(" work ");
+        assert_eq!(work.name, "work");
+        assert_eq!(profiles.add("work").name, "work (2)");
+        assert_eq!(
+            work.path(dir.path()),
+            dir.path().join("profiles").join(work.id.to_string())
+        );
+
+        // an unknown active profile falls back to the default one
+        profiles.active = Uuid::new_v4();
+        std::fs::write(
+            dir.path().join(PROFILES_FILE),
+            serde_json::to_string(&profiles).unwrap(),
+        )
+        .unwrap();
+        let loaded = Profiles::load_from(dir.path());
+        assert!(loaded.active().is_default());
+        assert_eq!(loaded.all.len(), 3);
+    }
+}
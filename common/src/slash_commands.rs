@@ -0,0 +1,146 @@
+//! Commands which can be typed into the chatbar, like `/shrug`. A command only turns its arguments into
+//! a `SlashCommandOutput`, the chatbar decides what to do with it.
+
+use crate::language::get_local_text;
+
+const SHRUG: &str = "¯\\\\\\_(ツ)\\_/¯";
+
+pub enum SlashCommandOutput {
+    // sends the text as a message
+    Send(String),
+    // opens the file picker
+    AttachFile,
+    // opens the files of the storage
+    SendFromStorage,
+    // opens the GIF picker with the given search
+    SearchGifs(String),
+}
+
+pub struct SlashCommandInput<'a> {
+    // everything after the name of the command, trimmed
+    pub args: &'a str,
+    pub username: &'a str,
+}
+
+pub struct SlashCommand {
+    pub name: &'static str,
+    // usage of the arguments, shown in the suggestions. empty if the command has none
+    pub args: &'static str,
+    // locale key of the description
+    pub description: &'static str,
+    pub handler: fn(SlashCommandInput) -> Option<SlashCommandOutput>,
+}
+
+impl SlashCommand {
+    pub fn description(&self) -> String {
+        get_local_text(self.description)
+    }
+
+    pub fn usage(&self) -> String {
+        if self.args.is_empty() {
+            format!("/{}", self.name)
+        } else {
+            format!("/{} {}", self.name, self.args)
+        }
+    }
+}
+
+pub struct SlashCommands {
+    commands: Vec<SlashCommand>,
+}
+
+impl Default for SlashCommands {
+    fn default() -> Self {
+        let mut commands = Self { commands: vec![] };
+        commands.register(SlashCommand {
+            name: "shrug",
+            args: "[message]",
+            description: "slash-commands.shrug",
+            handler: |input| {
+                Some(SlashCommandOutput::Send(
+                    format!("{} {SHRUG}", input.args).trim().to_string(),
+                ))
+            },
+        });
+        commands.register(SlashCommand {
+            name: "me",
+            args: "<action>",
+            description: "slash-commands.me",
+            handler: |input| {
+                (!input.args.is_empty()).then(|| {
+                    SlashCommandOutput::Send(format!("_{} {}_", input.username, input.args))
+                })
+            },
+        });
+        commands.register(SlashCommand {
+            name: "giphy",
+            args: "<search>",
+            description: "slash-commands.giphy",
+            handler: |input| Some(SlashCommandOutput::SearchGifs(input.args.to_string())),
+        });
+        commands.register(SlashCommand {
+            name: "file",
+            args: "[storage]",
+            description: "slash-commands.file",
+            handler: |input| match input.args {
+                "" => Some(SlashCommandOutput::AttachFile),
+                "storage" => Some(SlashCommandOutput::SendFromStorage),
+                _ => None,
+            },
+        });
+        commands
+    }
+}
+
+impl SlashCommands {
+    /// Adds a command. A command with the same name is replaced.
+    pub fn register(&mut self, command: SlashCommand) {
+        self.commands.retain(|c| c.name != command.name);
+        self.commands.push(command);
+    }
+
+    /// Commands whose name starts with `prefix`
+    pub fn matching<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a SlashCommand> {
+        self.commands
+            .iter()
+            .filter(move |command| command.name.starts_with(prefix))
+    }
+
+    /// Runs the command in `text`. Returns None if the text isn't a known command or the arguments are
+    /// invalid, in which case the text should be sent as it is.
+    pub fn run(&self, text: &str, username: &str) -> Option<SlashCommandOutput> {
+        let text = text.trim().strip_prefix('/')?;
+        let (name, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let command = self.commands.iter().find(|command| command.name == name)?;
+        (command.handler)(SlashCommandInput {
+            args: args.trim(),
+            username,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let commands = SlashCommands::default();
+        assert!(matches!(
+            commands.run("/me waves", "alice"),
+            Some(SlashCommandOutput::Send(text)) if text == "_alice waves_"
+        ));
+        assert!(matches!(
+            commands.run("/file storage", "alice"),
+            Some(SlashCommandOutput::SendFromStorage)
+        ));
+        assert!(matches!(
+            commands.run("/giphy cats", "alice"),
+            Some(SlashCommandOutput::SearchGifs(query)) if query == "cats"
+        ));
+        assert!(commands.run("/me", "alice").is_none());
+        assert!(commands.run("/unknown", "alice").is_none());
+        assert!(commands.run("not a command", "alice").is_none());
+        assert_eq!(commands.matching("f").count(), 1);
+    }
+}
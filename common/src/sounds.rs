@@ -1,9 +1,13 @@
+use serde::{Deserialize, Serialize};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
 };
 use tracing::log;
 
+use crate::language::get_local_text;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Sounds {
     Notification,
     Flip,
@@ -14,6 +18,28 @@ pub enum Sounds {
     Off,
 }
 
+/// The sounds which can be chosen for message notifications
+pub const NOTIFICATION_SOUNDS: [Sounds; 5] = [
+    Sounds::Notification,
+    Sounds::Flip,
+    Sounds::Interaction,
+    Sounds::On,
+    Sounds::Off,
+];
+
+impl Sounds {
+    /// Name of the sound shown in the notification sound selection
+    pub fn label(&self) -> String {
+        match self {
+            Sounds::Flip => get_local_text("messages.sound-flip"),
+            Sounds::Interaction => get_local_text("messages.sound-interaction"),
+            Sounds::On => get_local_text("messages.sound-on"),
+            Sounds::Off => get_local_text("messages.sound-off"),
+            _ => get_local_text("messages.sound-default"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ContinuousSound {
     RingTone,
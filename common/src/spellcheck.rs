@@ -0,0 +1,219 @@
+//! Spellchecking of what is typed into the chatbar, with Hunspell dictionaries. They aren't bundled:
+//! `<language id>.aff` and `<language id>.dic`, like `en-US.aff` and `en-US.dic` or `en_US.*` as
+//! most dictionaries are named, are read from the dictionaries folder of the app data the first
+//! time a chat in that language needs them.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    ops::Range,
+    sync::Arc,
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use spellbook::Dictionary;
+use tracing::log;
+
+use crate::STATIC_ARGS;
+
+const MAX_SUGGESTIONS: usize = 5;
+
+// by language id. `None` for the languages without a dictionary, so they aren't looked for again
+static DICTIONARIES: Lazy<Mutex<HashMap<String, Option<Arc<SpellChecker>>>>> =
+    Lazy::new(Default::default);
+
+/// The spellchecker for a language, if there is a dictionary for it
+pub fn for_language(language_id: &str) -> Option<Arc<SpellChecker>> {
+    DICTIONARIES
+        .lock()
+        .entry(language_id.to_string())
+        .or_insert_with(|| load(language_id).map(Arc::new))
+        .clone()
+}
+
+/// Forgets the loaded dictionaries, so added and changed ones are read again
+pub fn reload_dictionaries() {
+    DICTIONARIES.lock().clear();
+}
+
+fn load(language_id: &str) -> Option<SpellChecker> {
+    [language_id.to_string(), language_id.replace('-', "_")]
+        .iter()
+        .find_map(|name| {
+            let path = STATIC_ARGS.dictionaries_path.join(name);
+            let aff = fs::read_to_string(path.with_extension("aff")).ok()?;
+            let dic = fs::read_to_string(path.with_extension("dic")).ok()?;
+            SpellChecker::new(&aff, &dic)
+                .map_err(|e| log::error!("failed to read the dictionary {name}: {e}"))
+                .ok()
+        })
+}
+
+/// A word which isn't in the dictionary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    pub word: String,
+    /// where it is in the text
+    pub bytes: Range<usize>,
+    /// the same in UTF-16 code units, which is how the editor counts
+    pub utf16: Range<usize>,
+}
+
+pub struct SpellChecker {
+    dictionary: Dictionary,
+    // tried in suggestions, from the TRY line of the affix file
+    letters: Vec<char>,
+}
+
+impl SpellChecker {
+    pub fn new(aff: &str, dic: &str) -> Result<Self, String> {
+        let dictionary = Dictionary::new(aff, dic).map_err(|e| e.to_string())?;
+        let letters = aff
+            .lines()
+            .find_map(|line| line.strip_prefix("TRY "))
+            .map(|letters| letters.trim().chars().collect())
+            .unwrap_or_else(|| ('a'..='z').collect());
+        Ok(Self {
+            dictionary,
+            letters,
+        })
+    }
+
+    pub fn check(&self, word: &str) -> bool {
+        self.dictionary.check(word)
+    }
+
+    /// The words of `text` which are neither in the dictionary nor in `personal`. Code, links,
+    /// mentions, emoji aliases and words with digits are left alone
+    pub fn misspelled(&self, text: &str, personal: &BTreeSet<String>) -> Vec<Misspelling> {
+        words(text)
+            .filter(|(_, word)| {
+                !personal.contains(*word)
+                    && !personal.contains(&word.to_lowercase())
+                    && !self.check(word)
+            })
+            .map(|(start, word)| {
+                let utf16_start = text[..start].encode_utf16().count();
+                Misspelling {
+                    word: word.to_string(),
+                    bytes: start..start + word.len(),
+                    utf16: utf16_start..utf16_start + word.encode_utf16().count(),
+                }
+            })
+            .collect()
+    }
+
+    /// Words of the dictionary which are one typo away from `word`
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut candidates: Vec<String> = vec![];
+        let with = |f: &dyn Fn(&mut Vec<char>)| {
+            let mut chars = chars.clone();
+            f(&mut chars);
+            chars.into_iter().collect::<String>()
+        };
+        for i in 0..chars.len() {
+            if i + 1 < chars.len() {
+                candidates.push(with(&|c| c.swap(i, i + 1)));
+            }
+            for letter in &self.letters {
+                candidates.push(with(&|c| c[i] = *letter));
+            }
+            candidates.push(with(&|c| {
+                c.remove(i);
+            }));
+        }
+        for i in 0..=chars.len() {
+            for letter in &self.letters {
+                candidates.push(with(&|c| c.insert(i, *letter)));
+            }
+        }
+        // two words typed without the space between them
+        for i in 1..chars.len() {
+            let (first, second) = chars.split_at(i);
+            let (first, second): (String, String) =
+                (first.iter().collect(), second.iter().collect());
+            if self.check(&first) && self.check(&second) {
+                candidates.push(format!("{first} {second}"));
+            }
+        }
+
+        let mut suggestions: Vec<String> = vec![];
+        for candidate in candidates {
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+            if candidate != word
+                && !suggestions.contains(&candidate)
+                && (candidate.contains(' ') || self.check(&candidate))
+            {
+                suggestions.push(candidate);
+            }
+        }
+        suggestions
+    }
+}
+
+// the words to check with where they start
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    // every other part is between backticks, which is code
+    text.split('`')
+        .enumerate()
+        .flat_map(move |(idx, part)| {
+            let start = offset;
+            offset += part.len() + 1;
+            let part = if idx % 2 == 0 { part } else { "" };
+            part.split_whitespace().map(move |token| {
+                let token_start = token.as_ptr() as usize - part.as_ptr() as usize;
+                (start + token_start, token)
+            })
+        })
+        .filter(|(_, token)| {
+            !token.contains("://")
+                && !token.starts_with(['@', ':', '#'])
+                && !token.chars().any(|c| c.is_numeric())
+        })
+        .flat_map(|(start, token)| {
+            // the letters of the token, with apostrophes inside of words
+            let trimmed = token.trim_matches(|c: char| !c.is_alphabetic());
+            let trimmed_start = start + trimmed.as_ptr() as usize - token.as_ptr() as usize;
+            trimmed
+                .split(|c: char| !(c.is_alphabetic() || c == '\'' || c == '’'))
+                .filter(|word| word.chars().count() > 1)
+                .map(move |word| {
+                    (
+                        trimmed_start + word.as_ptr() as usize - trimmed.as_ptr() as usize,
+                        word,
+                    )
+                })
+        })
+        // acronyms
+        .filter(|(_, word)| !word.chars().all(|c| c.is_uppercase()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8\nTRY eolhdwr\n";
+    const DIC: &str = "3\nhello\nworld\nthe\n";
+
+    #[test]
+    fn finds_and_corrects_typos() {
+        let checker = SpellChecker::new(AFF, DIC).unwrap();
+        let text = "héllo wrold, `helo` @helo the NASA helloworld";
+        let personal = BTreeSet::from(["helloworld".to_string()]);
+        let misspelled = checker.misspelled(text, &personal);
+        let words: Vec<&str> = misspelled.iter().map(|m| m.word.as_str()).collect();
+        assert_eq!(words, ["héllo", "wrold"]);
+        assert_eq!(&text[misspelled[1].bytes.clone()], "wrold");
+        // é is one code unit but two bytes
+        assert_eq!(misspelled[1].utf16, 6..11);
+
+        assert_eq!(checker.suggest("wrold"), ["world"]);
+        assert_eq!(checker.suggest("helo"), ["hello"]);
+        assert_eq!(checker.suggest("theworld"), ["the world"]);
+    }
+}
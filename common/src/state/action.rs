@@ -5,19 +5,26 @@ use derive_more::Display;
 
 use dioxus_desktop::DesktopService;
 use dioxus_desktop::{tao::window::WindowId, DesktopContext};
-use extensions::UplinkExtension;
+use extensions::{conversations::Permission, UplinkExtension};
 use uuid::Uuid;
 use warp::crypto::DID;
 use warp::raygun::Location;
 
-use crate::warp_runner::ui_adapter;
+use crate::sounds::Sounds;
+use crate::warp_runner::{ui_adapter, GroupRole};
 
 use super::{
     call,
+    chats::{ChatAppearance, ChatWebhooks, NotificationRules},
+    configuration::{Automation, Gifs, OpenItemsOn, Presence, Transfers, Webhooks},
+    do_not_disturb::DoNotDisturb,
     identity::Identity,
+    integrity::IntegrityIssue,
     notifications::NotificationKind,
+    onboarding::OnboardingStep,
     route::To,
-    ui::{EmojiDestination, Font, Theme, ToastNotification, WindowMeta},
+    settings::LinkedDevice,
+    ui::{EmojiDestination, EmojiStyle, Font, SidebarFilter, Theme, ToastNotification, WindowMeta},
 };
 
 /// used exclusively by State::mutate
@@ -43,6 +50,9 @@ pub enum Action<'a> {
     ToggleMute,
     #[display(fmt = "SidebarHidden: {_0}")]
     SidebarHidden(bool),
+    /// shows the lock screen, or hides it once the PIN was entered
+    #[display(fmt = "SetLocked: {_0}")]
+    SetLocked(bool),
     #[display(fmt = "SetOverlay")]
     SetOverlay(bool),
     #[display(fmt = "AddToastNotification")]
@@ -51,8 +61,17 @@ pub enum Action<'a> {
     SetTheme(Option<Theme>),
     #[display(fmt = "SetFont")]
     SetFont(Option<Font>),
+    /// Sets the font used for message text. None uses the UI font
+    #[display(fmt = "SetMessageFont")]
+    SetMessageFont(Option<Font>),
+    /// Sets the font used for code blocks
+    #[display(fmt = "SetMonospaceFont")]
+    SetMonospaceFont(Option<Font>),
     #[display(fmt = "SetFontScale")]
     SetFontScale(f32),
+    /// Sets the UI scale override for the monitor the window is currently on
+    #[display(fmt = "SetMonitorScale {_0}")]
+    SetMonitorScale(f32),
     #[display(fmt = "TrackEmojiUsage")]
     TrackEmojiUsage(String),
     #[display(fmt = "SetEmojiPickerVisible")]
@@ -61,6 +80,17 @@ pub enum Action<'a> {
     SetTransformMarkdownText(bool),
     #[display(fmt = "SetTransformAsciiEmojis")]
     SetTransformAsciiEmojis(bool),
+    #[display(fmt = "SetEmojiStyle {_0:?}")]
+    SetEmojiStyle(EmojiStyle),
+    /// Shows only some of the conversations in the chat sidebar
+    #[display(fmt = "SetSidebarFilter {_0:?}")]
+    SetSidebarFilter(SidebarFilter),
+    /// Shows the conversation next to the active one, or closes the split view for `None`
+    #[display(fmt = "SetSplitChat {_0:?}")]
+    SetSplitChat(Option<Uuid>),
+    /// Goes to a step of the onboarding, or ends it for `None`
+    #[display(fmt = "SetOnboardingStep {_0:?}")]
+    SetOnboardingStep(Option<OnboardingStep>),
     // RemoveToastNotification,
     /// Sets the active call and active media id
     #[display(fmt = "AnswerCall")]
@@ -72,6 +102,16 @@ pub enum Action<'a> {
     OfferCall(call::Call),
     #[display(fmt = "EndCall")]
     EndCall,
+    #[display(fmt = "SetCaptionsEnabled")]
+    SetCaptionsEnabled(bool),
+    #[display(fmt = "SetCameraEnabled")]
+    SetCameraEnabled(bool),
+    /// Adds a phrase recognized by the live captions to the active call
+    #[display(fmt = "AddCaption")]
+    AddCaption(String),
+    /// Whether the user agrees to save the transcript of the active call to the conversation
+    #[display(fmt = "SetTranscriptConsent")]
+    SetTranscriptConsent(bool),
     // Account
     /// Sets the ID for the user.
     #[display(fmt = "SetId")]
@@ -96,7 +136,7 @@ pub enum Action<'a> {
     ClearAllPopoutWindows(DesktopContext),
     // Notifications
     #[display(fmt = "AddNotification")]
-    AddNotification(NotificationKind, u32, bool),
+    AddNotification(NotificationKind, u32),
     #[display(fmt = "RemoveNotification")]
     RemoveNotification(NotificationKind, u32),
     #[display(fmt = "ClearNotification")]
@@ -113,6 +153,18 @@ pub enum Action<'a> {
     PauseGlobalKeybinds(bool), // If provided false, keybinds will be un-paused.
     #[display(fmt = "ResetKeybinds")]
     ResetKeybinds,
+    /// Remembers a device the account was shared with
+    #[display(fmt = "AddLinkedDevice")]
+    AddLinkedDevice(LinkedDevice),
+    #[display(fmt = "RevokeLinkedDevice")]
+    RevokeLinkedDevice(Uuid),
+    #[display(fmt = "SetDoNotDisturb")]
+    SetDoNotDisturb(DoNotDisturb),
+    /// Adds a word to the personal dictionary of the spellchecker
+    #[display(fmt = "AddToDictionary")]
+    AddToDictionary(String),
+    #[display(fmt = "RemoveFromDictionary")]
+    RemoveFromDictionary(String),
     // Routes
     /// Set the active route
     #[display(fmt = "Navigate")]
@@ -157,9 +209,69 @@ pub enum Action<'a> {
     /// Removes a chat from the sidebar, also removes the active chat if the chat being removed matches
     #[display(fmt = "RemoveFromSidebar")]
     RemoveFromSidebar(Uuid),
+    /// Adds a folder to the chat sidebar with the given name
+    #[display(fmt = "CreateChatFolder")]
+    CreateChatFolder(String),
+    #[display(fmt = "RenameChatFolder")]
+    RenameChatFolder(Uuid, String),
+    /// Removes a folder, its chats stay in the sidebar
+    #[display(fmt = "DeleteChatFolder")]
+    DeleteChatFolder(Uuid),
+    /// chat, folder. None takes the chat out of its folder
+    #[display(fmt = "MoveChatToFolder")]
+    MoveChatToFolder(Uuid, Option<Uuid>),
+    /// Collapses or expands a folder
+    #[display(fmt = "ToggleChatFolder")]
+    ToggleChatFolder(Uuid),
+    /// Merges duplicate direct chats into a single chat. The duplicates are hidden locally but not deleted.
+    /// chat to keep, duplicates
+    #[display(fmt = "MergeChats")]
+    MergeChats(Uuid, Vec<Uuid>),
+    /// Removes invalid entries from the sidebar, favorites and merged chats, as reported by `State::check_integrity`
+    #[display(fmt = "RepairConversationIndexes")]
+    RepairConversationIndexes(Vec<IntegrityIssue>),
+    /// conversation id, member, role
+    #[display(fmt = "SetGroupRole")]
+    SetGroupRole(Uuid, DID, GroupRole),
+    /// conversation id, member, muted
+    #[display(fmt = "SetMemberMuted")]
+    SetMemberMuted(Uuid, DID, bool),
+    /// Sets the local title of a chat. None or an empty title removes it
+    #[display(fmt = "SetChatTitle")]
+    SetChatTitle(Uuid, Option<String>),
+    /// Sets the local topic of a chat. None or an empty topic removes it
+    #[display(fmt = "SetChatTopic")]
+    SetChatTopic(Uuid, Option<String>),
+    /// Sets the language of a chat. None goes back to detecting it from the messages
+    #[display(fmt = "SetChatLanguage")]
+    SetChatLanguage(Uuid, Option<String>),
+    /// Opens or closes the widgets panel of a chat
+    #[display(fmt = "ToggleConversationPanel")]
+    ToggleConversationPanel(Uuid),
+    /// Expands or collapses a widget in the panel of a chat. chat id, widget
+    #[display(fmt = "ToggleConversationWidget")]
+    ToggleConversationWidget(Uuid, String),
+    #[display(fmt = "SetNotificationRules")]
+    SetNotificationRules(Uuid, NotificationRules),
+    /// Sets how a chat looks. The default appearance goes back to the theme
+    #[display(fmt = "SetChatAppearance")]
+    SetChatAppearance(Uuid, ChatAppearance),
+    /// Scales the text of the messages in a chat. 1.0 goes back to the normal size
+    #[display(fmt = "SetChatZoom")]
+    SetChatZoom(Uuid, f32),
+    /// Sets the bots and the endpoint for the new messages of a chat
+    #[display(fmt = "SetChatWebhooks")]
+    SetChatWebhooks(Uuid, ChatWebhooks),
+    /// None uses the default notification sound
+    #[display(fmt = "SetFriendSound")]
+    SetFriendSound(DID, Option<Sounds>),
     /// Adds or removes a chat from the favorites page
     #[display(fmt = "ToggleFavorite")]
     ToggleFavorite(&'a Uuid),
+    /// Moves a favorite to where another one is in the slimbar
+    /// favorite to move, favorite it takes the place of
+    #[display(fmt = "MoveFavorite")]
+    MoveFavorite(Uuid, Uuid),
     // Messaging
     /// React to a given message by ID
     /// conversation id, message id, reaction
@@ -186,6 +298,19 @@ pub enum Action<'a> {
     /// Clear attachments on chat
     #[display(fmt = "ClearChatAttachments")]
     ClearChatAttachments(Uuid),
+    /// Queues a message which failed to send, to be sent again later
+    /// (Conversation Id, message, attachments, replying to)
+    #[display(fmt = "QueueMessage")]
+    QueueMessage(Uuid, Vec<String>, Vec<Location>, Option<Uuid>),
+    /// Removes a message from the outbox without sending it
+    #[display(fmt = "CancelQueuedMessage")]
+    CancelQueuedMessage(Uuid),
+    /// Retries a message in the outbox which failed too often to be retried on its own
+    #[display(fmt = "RetryQueuedMessage")]
+    RetryQueuedMessage(Uuid),
+    /// Changes the text of a message in the outbox
+    #[display(fmt = "EditQueuedMessage")]
+    EditQueuedMessage(Uuid, Vec<String>),
     /// Clears a drafted message from a given chat.
     #[display(fmt = "ClearChatDraft")]
     ClearChatDraft(Uuid),
@@ -212,6 +337,8 @@ pub enum Action<'a> {
 pub enum ConfigAction {
     #[display(fmt = "SetDyslexicEnabled {_0}")]
     SetDyslexicEnabled(bool),
+    #[display(fmt = "SetReducedMotion {_0}")]
+    SetReducedMotion(bool),
     #[display(fmt = "SetNotificationsEnabled {_0}")]
     SetNotificationsEnabled(bool),
     #[display(fmt = "SetTheme {_0}")]
@@ -236,6 +363,28 @@ pub enum ConfigAction {
     SetSettingsNotificationsEnabled(bool),
     #[display(fmt = "SetAutoEnableExtensions {_0}")]
     SetAutoEnableExtensions(bool),
+    #[display(fmt = "SetExtensionPermission {_0} {_1:?} {_2}")]
+    SetExtensionPermission(String, Permission, bool),
     #[display(fmt = "SetEchoCancellation {_0}")]
     SetEchoCancellation(bool),
+    #[display(fmt = "SetPreCallCheck {_0}")]
+    SetPreCallCheck(bool),
+    #[display(fmt = "SetReadReceiptsEnabled {_0}")]
+    SetReadReceiptsEnabled(bool),
+    #[display(fmt = "SetTypingIndicatorsEnabled {_0}")]
+    SetTypingIndicatorsEnabled(bool),
+    #[display(fmt = "SetKeychainUnlock {_0}")]
+    SetKeychainUnlock(bool),
+    #[display(fmt = "SetGifs")]
+    SetGifs(Gifs),
+    #[display(fmt = "SetWebhooks")]
+    SetWebhooks(Webhooks),
+    #[display(fmt = "SetTransfers")]
+    SetTransfers(Transfers),
+    #[display(fmt = "SetOpenItemsOn {_0:?}")]
+    SetOpenItemsOn(OpenItemsOn),
+    #[display(fmt = "SetPresence")]
+    SetPresence(Presence),
+    #[display(fmt = "SetAutomation")]
+    SetAutomation(Automation),
 }
@@ -0,0 +1,220 @@
+//! Recent events across the account which don't show up elsewhere at a glance: messages in muted
+//! chats, reactions to our messages and accepted friend requests. Finished transfers are kept in
+//! `transfer_history` and merged in by `Activity::timeline`.
+
+use std::{collections::VecDeque, fs};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+use warp::crypto::DID;
+
+use super::{
+    persistence,
+    transfer_history::{Outcome, TransferRecord},
+};
+use crate::{utils::handoff::Position, STATIC_ARGS};
+
+// older events are dropped
+const MAX_EVENTS: usize = 300;
+// the length of the message previews
+const PREVIEW_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityKind {
+    // a message in a muted chat
+    Message,
+    Reaction,
+    FriendAccepted,
+    Transfer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub kind: ActivityKind,
+    pub date: DateTime<Utc>,
+    // who sent the message, reacted or accepted the request
+    pub did: Option<DID>,
+    pub conversation_id: Option<Uuid>,
+    // the message to scroll to when the event is opened
+    pub position: Option<Position>,
+    // a preview of the message, the emoji or the file name
+    pub text: String,
+}
+
+impl ActivityEvent {
+    pub fn message(did: DID, conversation_id: Uuid, position: Position, text: &str) -> Self {
+        Self {
+            kind: ActivityKind::Message,
+            date: position.date,
+            did: Some(did),
+            conversation_id: Some(conversation_id),
+            position: Some(position),
+            text: preview(text),
+        }
+    }
+
+    pub fn reaction(did: DID, conversation_id: Uuid, position: Position, emoji: String) -> Self {
+        Self {
+            kind: ActivityKind::Reaction,
+            date: Utc::now(),
+            did: Some(did),
+            conversation_id: Some(conversation_id),
+            position: Some(position),
+            text: emoji,
+        }
+    }
+
+    pub fn friend_accepted(did: DID) -> Self {
+        Self {
+            kind: ActivityKind::FriendAccepted,
+            date: Utc::now(),
+            did: Some(did),
+            conversation_id: None,
+            position: None,
+            text: String::new(),
+        }
+    }
+
+    pub fn transfer(record: &TransferRecord) -> Self {
+        Self {
+            kind: ActivityKind::Transfer,
+            date: record.finished,
+            did: None,
+            conversation_id: record.conversation_id,
+            position: None,
+            text: record.file.clone(),
+        }
+    }
+}
+
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    match line.char_indices().nth(PREVIEW_CHARS) {
+        Some((idx, _)) => format!("{}…", &line[..idx]),
+        None => line.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Activity {
+    events: VecDeque<ActivityEvent>,
+}
+
+impl Activity {
+    pub fn load() -> Self {
+        let events = match fs::read_to_string(STATIC_ARGS.activity_path()) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::error!("failed to deserialize the activity: {e}");
+                VecDeque::new()
+            }),
+            Err(_) => VecDeque::new(),
+        };
+        Self { events }
+    }
+
+    // written by the writer of the state, see `persistence`
+    fn save(&self) {
+        let events = self.events.clone();
+        persistence::write_file(
+            STATIC_ARGS.activity_path(),
+            Box::new(move || serde_json::to_vec(&events)),
+        );
+    }
+
+    pub fn record(&mut self, event: ActivityEvent) {
+        self.push(event);
+        self.save();
+    }
+
+    fn push(&mut self, event: ActivityEvent) {
+        self.events.push_front(event);
+        self.events.truncate(MAX_EVENTS);
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.save();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The events of `kind`, or of every kind for `None`, merged with the completed transfers and
+    /// sorted newest first
+    pub fn timeline<'a>(
+        &self,
+        transfers: impl Iterator<Item = &'a TransferRecord>,
+        kind: Option<ActivityKind>,
+    ) -> Vec<ActivityEvent> {
+        let transfers = transfers
+            .filter(|r| r.outcome == Outcome::Completed)
+            .map(ActivityEvent::transfer);
+        let mut events: Vec<ActivityEvent> = self
+            .events
+            .iter()
+            .cloned()
+            .chain(transfers)
+            .filter(|e| kind.map_or(true, |k| k == e.kind))
+            .collect();
+        events.sort_by(|a, b| b.date.cmp(&a.date));
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::state::transfer_history::Direction;
+
+    #[test]
+    fn merges_transfers_into_timeline() {
+        let mut activity = Activity::default();
+        let position = Position {
+            message_id: Uuid::new_v4(),
+            date: Utc::now() - Duration::hours(1),
+        };
+        let mut reaction =
+            ActivityEvent::reaction(DID::default(), Uuid::new_v4(), position, "👍".into());
+        reaction.date = Utc::now() - Duration::minutes(5);
+        activity.push(reaction);
+        activity.push(ActivityEvent::friend_accepted(DID::default()));
+
+        let transfer = |file: &str, direction, outcome, minutes| TransferRecord {
+            file: file.into(),
+            size: 0,
+            direction,
+            outcome,
+            started: Utc::now() - Duration::minutes(minutes),
+            finished: Utc::now() - Duration::minutes(minutes),
+            conversation_id: None,
+        };
+        let transfers = [
+            transfer("a.png", Direction::Download, Outcome::Completed, 2),
+            transfer("b.png", Direction::Download, Outcome::Failed, 1),
+            transfer("c.png", Direction::Upload, Outcome::Completed, 10),
+        ];
+
+        let kinds: Vec<ActivityKind> = activity
+            .timeline(transfers.iter(), None)
+            .iter()
+            .map(|e| e.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ActivityKind::FriendAccepted,
+                ActivityKind::Transfer,
+                ActivityKind::Reaction,
+                ActivityKind::Transfer
+            ]
+        );
+        let reactions = activity.timeline(transfers.iter(), Some(ActivityKind::Reaction));
+        assert_eq!(reactions.len(), 1);
+        assert_eq!(reactions[0].text, "👍");
+    }
+}
@@ -1,11 +1,12 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     time::{Duration, Instant},
 };
 
 use anyhow::bail;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, Utc};
 use dioxus_desktop::wry::application::window::WindowId;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use warp::{blink::ParticipantState, crypto::DID};
 
@@ -20,6 +21,14 @@ pub struct ActiveCall {
     pub call: Call,
     pub answer_time: DateTime<Local>,
     pub popout_window_id: Option<WindowId>,
+    pub captions_enabled: bool,
+    // whether the camera is shown in the call view
+    pub camera_enabled: bool,
+    // everything captioned since captions were turned on
+    pub transcript: Vec<Caption>,
+    // participants who agreed to have the transcript saved to the conversation. only the own
+    // speech is captioned, so this is only ever the own DID
+    pub transcript_consent: HashSet<DID>,
 }
 
 impl From<Call> for ActiveCall {
@@ -28,10 +37,62 @@ impl From<Call> for ActiveCall {
             call: value,
             answer_time: Local::now(),
             popout_window_id: None,
+            captions_enabled: false,
+            camera_enabled: false,
+            transcript: vec![],
+            transcript_consent: HashSet::new(),
         }
     }
 }
 
+impl ActiveCall {
+    /// The transcript may only be saved if everyone who appears in it agreed to it
+    pub fn transcript_can_be_saved(&self) -> bool {
+        !self.transcript.is_empty()
+            && self
+                .transcript
+                .iter()
+                .all(|caption| self.transcript_consent.contains(&caption.speaker))
+    }
+}
+
+/// A call shown between the messages of the conversation it happened in
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallEvent {
+    pub call_id: Uuid,
+    pub date: DateTime<Utc>,
+    pub kind: CallEventKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CallEventKind {
+    /// by the given participant
+    Started(DID),
+    Ended {
+        duration_secs: u64,
+    },
+    /// rang until the caller gave up
+    Missed(DID),
+    Declined(DID),
+}
+
+impl CallEvent {
+    pub fn new(call_id: Uuid, kind: CallEventKind) -> Self {
+        Self {
+            call_id,
+            date: Utc::now(),
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Caption {
+    pub speaker: DID,
+    pub text: String,
+    pub time: DateTime<Local>,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct Call {
     pub id: Uuid,
@@ -39,6 +100,8 @@ pub struct Call {
     pub participants: Vec<DID>,
     pub participants_joined: HashMap<DID, ParticipantState>,
     pub participants_speaking: HashMap<DID, Instant>,
+    // when the own microphone last picked up speech
+    pub self_speaking: Option<Instant>,
     pub self_muted: bool,
     pub call_silenced: bool,
 }
@@ -121,6 +184,15 @@ impl CallInfo {
         Ok(())
     }
 
+    pub fn self_speaking(&mut self) -> anyhow::Result<()> {
+        let active_call = match self.active_call.as_mut() {
+            Some(c) => c,
+            None => bail!("call not in progress"),
+        };
+        active_call.call.self_speaking = Some(Instant::now());
+        Ok(())
+    }
+
     pub fn participant_joined(&mut self, call_id: Uuid, id: DID) -> anyhow::Result<()> {
         let active_call = match self.active_call.as_mut() {
             Some(c) => c,
@@ -201,6 +273,42 @@ impl CallInfo {
         Ok(())
     }
 
+    pub fn set_captions_enabled(&mut self, enabled: bool) {
+        if let Some(ac) = self.active_call.as_mut() {
+            ac.captions_enabled = enabled;
+        }
+    }
+
+    pub fn set_camera_enabled(&mut self, enabled: bool) {
+        if let Some(ac) = self.active_call.as_mut() {
+            ac.camera_enabled = enabled;
+        }
+    }
+
+    /// Adds a recognized phrase to the transcript. Only the own microphone is recognized, so the
+    /// phrase is always attributed to `own_id`
+    pub fn add_caption(&mut self, text: String, own_id: DID) {
+        let ac = match self.active_call.as_mut() {
+            Some(ac) if ac.captions_enabled => ac,
+            _ => return,
+        };
+        ac.transcript.push(Caption {
+            speaker: own_id,
+            text,
+            time: Local::now(),
+        });
+    }
+
+    pub fn set_transcript_consent(&mut self, id: DID, consent: bool) {
+        if let Some(ac) = self.active_call.as_mut() {
+            if consent {
+                ac.transcript_consent.insert(id);
+            } else {
+                ac.transcript_consent.remove(&id);
+            }
+        }
+    }
+
     pub fn set_popout_window_id(&mut self, popout_window_id: WindowId) {
         if let Some(ac) = self.active_call.as_mut() {
             ac.popout_window_id = Some(popout_window_id);
@@ -223,6 +331,7 @@ impl Call {
             participants,
             participants_joined: HashMap::new(),
             participants_speaking: HashMap::new(),
+            self_speaking: None,
             self_muted: false,
             call_silenced: false,
         }
@@ -256,7 +365,14 @@ impl Call {
         let len = self.participants_speaking.len();
         self.participants_speaking
             .retain(|_, time| time.elapsed() <= delay);
-        len != self.participants_speaking.len()
+        let self_stopped = self
+            .self_speaking
+            .map(|time| time.elapsed() > delay)
+            .unwrap_or(false);
+        if self_stopped {
+            self.self_speaking = None;
+        }
+        self_stopped || len != self.participants_speaking.len()
     }
 
     fn participant_not_speaking(&mut self, id: &DID) {
@@ -0,0 +1,233 @@
+//! The calls of every conversation. They are shown between the messages of their conversation and
+//! listed in the call history. Kept in a file of their own, like the activity, because the chats
+//! themselves are loaded from RayGun on every start.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+use warp::crypto::DID;
+
+use super::call::{CallEvent, CallEventKind};
+use crate::STATIC_ARGS;
+
+// older events of a conversation are dropped
+const MAX_CALL_EVENTS: usize = 200;
+
+/// A call as listed in the call history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallEntry {
+    pub call_id: Uuid,
+    pub conversation_id: Uuid,
+    pub date: DateTime<Utc>,
+    // who started the call
+    pub caller: DID,
+    pub outgoing: bool,
+    pub outcome: CallOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallOutcome {
+    // still going, or Uplink was closed during the call
+    Ongoing,
+    Ended { duration_secs: u64 },
+    Missed,
+    Declined,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallLog {
+    // by conversation, oldest first
+    #[serde(default)]
+    calls: HashMap<Uuid, VecDeque<CallEvent>>,
+    // the missed calls until then were seen in the call history
+    #[serde(default)]
+    seen: Option<DateTime<Utc>>,
+}
+
+impl CallLog {
+    pub fn load() -> Self {
+        match fs::read_to_string(STATIC_ARGS.call_log_path()) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::error!("failed to deserialize the call log: {e}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(self) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(STATIC_ARGS.call_log_path(), contents) {
+                    log::error!("failed to save the call log: {e}");
+                }
+            }
+            Err(e) => log::error!("failed to serialize the call log: {e}"),
+        }
+    }
+
+    pub fn record(&mut self, conversation_id: Uuid, event: CallEvent) {
+        self.push(conversation_id, event);
+        self.save();
+    }
+
+    fn push(&mut self, conversation_id: Uuid, event: CallEvent) {
+        let events = self.calls.entry(conversation_id).or_default();
+        if events.len() == MAX_CALL_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Turns the start of a call which wasn't answered into a missed or declined call
+    pub fn unanswered(&mut self, conversation_id: Uuid, call_id: Uuid, declined: bool) {
+        let Some(event) = self
+            .calls
+            .get_mut(&conversation_id)
+            .and_then(|events| events.iter_mut().rev().find(|e| e.call_id == call_id))
+        else {
+            return;
+        };
+        if let CallEventKind::Started(caller) = &event.kind {
+            event.kind = if declined {
+                CallEventKind::Declined(caller.clone())
+            } else {
+                CallEventKind::Missed(caller.clone())
+            };
+            self.save();
+        }
+    }
+
+    /// The events of a conversation, oldest first
+    pub fn events(&self, conversation_id: &Uuid) -> Vec<CallEvent> {
+        self.calls
+            .get(conversation_id)
+            .map(|events| events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Every call, newest first. `own_id` tells the outgoing calls apart
+    pub fn entries(&self, own_id: &DID) -> Vec<CallEntry> {
+        let mut entries: Vec<CallEntry> = vec![];
+        for (conversation_id, events) in &self.calls {
+            let mut calls: Vec<CallEntry> = vec![];
+            for event in events {
+                let entry = calls.iter_mut().find(|e| e.call_id == event.call_id);
+                let (caller, outcome) = match &event.kind {
+                    CallEventKind::Started(caller) => (caller, CallOutcome::Ongoing),
+                    CallEventKind::Missed(caller) => (caller, CallOutcome::Missed),
+                    CallEventKind::Declined(caller) => (caller, CallOutcome::Declined),
+                    CallEventKind::Ended { duration_secs } => {
+                        if let Some(entry) = entry {
+                            entry.outcome = CallOutcome::Ended {
+                                duration_secs: *duration_secs,
+                            };
+                        }
+                        continue;
+                    }
+                };
+                if entry.is_none() {
+                    calls.push(CallEntry {
+                        call_id: event.call_id,
+                        conversation_id: *conversation_id,
+                        date: event.date,
+                        caller: caller.clone(),
+                        outgoing: caller == own_id,
+                        outcome,
+                    });
+                }
+            }
+            entries.extend(calls);
+        }
+        entries.sort_by(|a, b| b.date.cmp(&a.date));
+        entries
+    }
+
+    /// The missed calls which weren't seen in the call history yet
+    pub fn unseen_missed(&self, own_id: &DID) -> usize {
+        self.entries(own_id)
+            .iter()
+            .filter(|e| e.outcome == CallOutcome::Missed)
+            .filter(|e| self.seen.map_or(true, |seen| e.date > seen))
+            .count()
+    }
+
+    pub fn mark_seen(&mut self) {
+        self.seen = Some(Utc::now());
+        self.save();
+    }
+
+    pub fn clear(&mut self) {
+        self.calls.clear();
+        self.save();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.values().all(|events| events.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn lists_calls_with_their_outcome() {
+        let mut log = CallLog::default();
+        let own = DID::default();
+        // a new random key
+        let other = DID::default();
+        let chat = Uuid::new_v4();
+        let event = |call_id, kind, minutes| CallEvent {
+            call_id,
+            date: Utc::now() - Duration::minutes(minutes),
+            kind,
+        };
+        let (outgoing, missed, declined) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        log.push(
+            chat,
+            event(outgoing, CallEventKind::Started(own.clone()), 30),
+        );
+        log.push(
+            chat,
+            event(outgoing, CallEventKind::Ended { duration_secs: 65 }, 29),
+        );
+        log.push(
+            chat,
+            event(missed, CallEventKind::Started(other.clone()), 20),
+        );
+        log.push(
+            chat,
+            event(declined, CallEventKind::Declined(other.clone()), 10),
+        );
+        // what unanswered does, without writing the file
+        if let Some(e) = log.calls.get_mut(&chat).unwrap().get_mut(2) {
+            e.kind = CallEventKind::Missed(other.clone());
+        }
+
+        let entries = log.entries(&own);
+        let outcomes: Vec<_> = entries
+            .iter()
+            .map(|e| (e.call_id, e.outgoing, e.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (declined, false, CallOutcome::Declined),
+                (missed, false, CallOutcome::Missed),
+                (outgoing, true, CallOutcome::Ended { duration_secs: 65 }),
+            ]
+        );
+        assert_eq!(log.unseen_missed(&own), 1);
+        log.seen = Some(Utc::now());
+        assert_eq!(log.unseen_missed(&own), 0);
+    }
+}
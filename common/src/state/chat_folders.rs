@@ -0,0 +1,106 @@
+//! Folders of the chat sidebar. A chat is in at most one folder, the chats which aren't in any are
+//! listed after the folders like before.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatFolder {
+    pub id: Uuid,
+    pub name: String,
+    // in the order they were added
+    pub chats: Vec<Uuid>,
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatFolders {
+    folders: Vec<ChatFolder>,
+}
+
+impl ChatFolders {
+    pub fn all(&self) -> &[ChatFolder] {
+        &self.folders
+    }
+
+    pub fn create(&mut self, name: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        self.folders.push(ChatFolder {
+            id,
+            name: name.trim().to_string(),
+            chats: vec![],
+            collapsed: false,
+        });
+        id
+    }
+
+    pub fn rename(&mut self, id: Uuid, name: &str) {
+        if let Some(folder) = self.folders.iter_mut().find(|f| f.id == id) {
+            folder.name = name.trim().to_string();
+        }
+    }
+
+    /// Removes the folder. Its chats go back to the rest of the sidebar
+    pub fn delete(&mut self, id: Uuid) {
+        self.folders.retain(|f| f.id != id);
+    }
+
+    /// Puts the chat in a folder, or takes it out of its folder for `None`
+    pub fn move_chat(&mut self, chat_id: Uuid, folder_id: Option<Uuid>) {
+        for folder in self.folders.iter_mut() {
+            folder.chats.retain(|id| *id != chat_id);
+        }
+        if let Some(folder) = folder_id.and_then(|id| self.folders.iter_mut().find(|f| f.id == id))
+        {
+            folder.chats.push(chat_id);
+        }
+    }
+
+    pub fn folder_of(&self, chat_id: &Uuid) -> Option<Uuid> {
+        self.folders
+            .iter()
+            .find(|f| f.chats.contains(chat_id))
+            .map(|f| f.id)
+    }
+
+    pub fn toggle_collapsed(&mut self, id: Uuid) {
+        if let Some(folder) = self.folders.iter_mut().find(|f| f.id == id) {
+            folder.collapsed = !folder.collapsed;
+        }
+    }
+
+    /// Forgets a chat which was deleted or left
+    pub fn remove_chat(&mut self, chat_id: &Uuid) {
+        for folder in self.folders.iter_mut() {
+            folder.chats.retain(|id| id != chat_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keeps_a_chat_in_one_folder() {
+        let mut folders = ChatFolders::default();
+        let work = folders.create(" Work ");
+        let family = folders.create("Family");
+        let chat = Uuid::new_v4();
+
+        folders.move_chat(chat, Some(work));
+        assert_eq!(folders.folder_of(&chat), Some(work));
+        folders.move_chat(chat, Some(family));
+        assert_eq!(folders.folder_of(&chat), Some(family));
+        assert!(folders.all()[0].chats.is_empty());
+        assert_eq!(folders.all()[0].name, "Work");
+
+        folders.toggle_collapsed(family);
+        assert!(folders.all()[1].collapsed);
+        // back to the rest of the sidebar with its folder
+        folders.delete(family);
+        assert_eq!(folders.folder_of(&chat), None);
+        assert_eq!(folders.all().len(), 1);
+    }
+}
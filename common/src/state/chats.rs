@@ -1,8 +1,10 @@
 use std::{
     collections::{HashMap, HashSet, VecDeque},
+    path::PathBuf,
     time::Instant,
 };
 
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use warp::{
@@ -10,9 +12,28 @@ use warp::{
     raygun::{self, ConversationSettings, ConversationType, Location},
 };
 
-use crate::{warp_runner::ui_adapter, STATIC_ARGS};
+use crate::{
+    language,
+    sounds::Sounds,
+    state::utils::{find_group_mention, GroupMention},
+    utils::webhooks,
+    warp_runner::{ui_adapter, GroupRole},
+    STATIC_ARGS,
+};
+
+use super::{
+    outbox::Outbox,
+    pending_message::{FileLocation, FileProgression, PendingMessage},
+    MAX_PINNED_MESSAGES,
+};
 
-use super::pending_message::{FileLocation, FileProgression, PendingMessage};
+// number of recent messages used to guess the language of a chat
+const LANGUAGE_DETECTION_MESSAGES: usize = 20;
+
+// how far the text of a chat can be zoomed in or out
+pub const CHAT_ZOOM_MIN: f32 = 0.5;
+pub const CHAT_ZOOM_MAX: f32 = 3.0;
+pub const CHAT_ZOOM_STEP: f32 = 0.1;
 
 // let (p = window_bottom) be an index into Chat.messages
 // show messages from (p - window_size) to (p + window_extra)
@@ -80,6 +101,9 @@ pub struct Chat {
     pub is_scrolled: bool,
     #[serde(skip)]
     pub pinned_messages: Vec<raygun::Message>,
+    // when @everyone or @admins last notified the user. used to throttle room-wide notifications
+    #[serde(skip)]
+    pub last_room_mention: Option<Instant>,
 }
 
 fn skip_chat_messages(_messages: &VecDeque<ui_adapter::Message>) -> bool {
@@ -108,6 +132,7 @@ impl Default for Chat {
             files_attached_to_send: Default::default(),
             is_scrolled: false,
             pinned_messages: Default::default(),
+            last_room_mention: Default::default(),
         }
     }
 }
@@ -138,6 +163,40 @@ impl Chat {
             ..Default::default()
         }
     }
+
+    /// Guesses the language of the chat from the loaded messages
+    pub fn detect_language(&self) -> Option<&'static str> {
+        let lines: Vec<String> = self
+            .messages
+            .iter()
+            .rev()
+            .take(LANGUAGE_DETECTION_MESSAGES)
+            .flat_map(|message| message.inner.lines())
+            .collect();
+        language::detect_language(lines.iter().map(|line| line.as_str()))
+    }
+
+    /// The group mention in `message` if it is meant for the user. @everyone only counts if the
+    /// sender is allowed to use it.
+    pub fn group_mention_for(
+        &self,
+        message: &raygun::Message,
+        own: &DID,
+        moderation: &GroupModeration,
+    ) -> Option<GroupMention> {
+        if self.conversation_type != ConversationType::Group {
+            return None;
+        }
+        let role_of = |did: &DID| moderation.role_of(self.creator.as_ref(), did);
+        match find_group_mention(&message.lines().join("\n"))? {
+            GroupMention::Everyone if role_of(&message.sender()).can_mention_everyone() => {
+                Some(GroupMention::Everyone)
+            }
+            GroupMention::Admins if role_of(own).can_manage_group() => Some(GroupMention::Admins),
+            _ => None,
+        }
+    }
+
     pub fn append_pending_msg(
         &mut self,
         chat_id: Uuid,
@@ -185,6 +244,16 @@ impl Chat {
         }
     }
 
+    pub fn set_pending_msg_text(&mut self, message_id: Uuid, msg: Vec<String>) {
+        if let Some(m) = self
+            .pending_outgoing_messages
+            .iter_mut()
+            .find(|m| m.id().eq(&message_id))
+        {
+            m.message.inner.set_lines(msg);
+        }
+    }
+
     pub fn remove_pending_msg(&mut self, message_id: Uuid) {
         self.pending_outgoing_messages
             .retain(|m| !m.id().eq(&message_id))
@@ -205,9 +274,249 @@ impl Chat {
     pub fn add_unread(&mut self, id: Uuid) {
         self.unreads.insert(id);
     }
+
+    pub fn extend_unreads(&mut self, other: &Chat) {
+        self.unreads.extend(other.unreads.iter().copied());
+    }
+
+    /// moves the local data of a duplicate chat with the same participants into this one
+    pub fn absorb_duplicate(&mut self, other: Chat) {
+        self.unreads.extend(other.unreads);
+        if self.draft.is_none() {
+            self.draft = other.draft;
+        }
+        self.pinned_messages.extend(other.pinned_messages);
+        self.pinned_messages
+            .sort_by_key(|r| std::cmp::Reverse(r.date()));
+        self.pinned_messages.truncate(MAX_PINNED_MESSAGES.into());
+        // only the most recent message is kept, for the sidebar
+        if let Some(msg) = other.messages.back() {
+            let is_newer = self
+                .messages
+                .back()
+                .map(|m| m.inner.date() < msg.inner.date())
+                .unwrap_or(true);
+            if is_newer {
+                self.messages = VecDeque::from([msg.clone()]);
+            }
+        }
+    }
+}
+
+/// The roles and muted members of a group chat. warp has no concept of roles, they are assigned by the
+/// group admins and sent to the other members, see `warp_runner::group_roles`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupModeration {
+    // members without an entry are regular members. the creator is always an admin.
+    #[serde(default)]
+    pub roles: HashMap<DID, GroupRole>,
+    // messages and typing indicators from these members are hidden.
+    #[serde(default)]
+    pub muted: HashSet<DID>,
+}
+
+impl GroupModeration {
+    pub fn role_of(&self, creator: Option<&DID>, did: &DID) -> GroupRole {
+        if creator == Some(did) {
+            return GroupRole::Admin;
+        }
+        self.roles.get(did).copied().unwrap_or_default()
+    }
+
+    pub fn set_role(&mut self, did: DID, role: GroupRole) {
+        if role == GroupRole::Member {
+            self.roles.remove(&did);
+        } else {
+            self.roles.insert(did, role);
+        }
+    }
+
+    pub fn set_muted(&mut self, did: DID, muted: bool) {
+        if muted {
+            self.muted.insert(did);
+        } else {
+            self.muted.remove(&did);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty() && self.muted.is_empty()
+    }
 }
 
 // warning: Chats implements Serialize
+/// Decides if new messages in a conversation trigger a notification.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationRules {
+    pub muted: Option<MuteUntil>,
+    // only notify for messages which mention the user
+    pub mentions_only: bool,
+    // None uses the default notification sound
+    pub sound: Option<Sounds>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MuteUntil {
+    Time(DateTime<Utc>),
+    // until the user turns notifications back on
+    Forever,
+}
+
+impl MuteUntil {
+    pub fn from_duration(duration: Duration) -> Self {
+        Self::Time(Utc::now() + duration)
+    }
+}
+
+impl NotificationRules {
+    pub fn is_muted(&self) -> bool {
+        match self.muted {
+            Some(MuteUntil::Time(time)) => Utc::now() < time,
+            Some(MuteUntil::Forever) => true,
+            None => false,
+        }
+    }
+
+    /// `ping` is true if the message mentions the user
+    pub fn should_notify(&self, ping: bool) -> bool {
+        !self.is_muted() && (ping || !self.mentions_only)
+    }
+}
+
+// warning: Chats implements Serialize
+/// The widgets panel shown next to a conversation. Widgets are identified by a string so that
+/// extensions can add their own; built in widgets use the constants below.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversationPanel {
+    pub open: bool,
+    // widgets are expanded unless the user collapsed them
+    pub collapsed: HashSet<String>,
+}
+
+impl ConversationPanel {
+    pub const PINNED: &'static str = "pinned";
+    pub const MEDIA: &'static str = "media";
+    pub const TASKS: &'static str = "tasks";
+    pub const MEMBERS: &'static str = "members";
+
+    pub fn is_expanded(&self, widget: &str) -> bool {
+        !self.collapsed.contains(widget)
+    }
+
+    pub fn toggle_widget(&mut self, widget: &str) {
+        if !self.collapsed.remove(widget) {
+            self.collapsed.insert(widget.to_string());
+        }
+    }
+}
+
+/// How a conversation looks on this device. Chats without an entry use the theme.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatAppearance {
+    pub background: Option<(u8, u8, u8)>,
+    // drawn over the background color
+    pub wallpaper: Option<PathBuf>,
+    // the average color of the wallpaper, worked out when it's picked
+    #[serde(default)]
+    pub wallpaper_color: Option<(u8, u8, u8)>,
+    // color of the messages sent by the user
+    pub bubble: Option<(u8, u8, u8)>,
+}
+
+// below this contrast ratio the bubble is hard to tell apart from the background
+const MIN_BUBBLE_CONTRAST: f64 = 1.5;
+
+impl ChatAppearance {
+    /// What the messages are drawn on: the wallpaper if there is one, else the background color
+    pub fn backdrop(&self) -> Option<(u8, u8, u8)> {
+        match &self.wallpaper {
+            Some(_) => self.wallpaper_color,
+            None => self.background,
+        }
+    }
+
+    /// The bubble color, or None when it would blend into the backdrop
+    pub fn bubble_color(&self) -> Option<(u8, u8, u8)> {
+        let bubble = self.bubble?;
+        match self.backdrop() {
+            Some(background) if contrast_ratio(bubble, background) < MIN_BUBBLE_CONTRAST => None,
+            _ => Some(bubble),
+        }
+    }
+}
+
+// See https://www.w3.org/TR/WCAG21/#dfn-relative-luminance
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let channel = |c: u8| {
+        let c = c as f64 / 255.;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+pub fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Whether dark text reads better than light text on the color
+pub fn needs_dark_text(color: (u8, u8, u8)) -> bool {
+    contrast_ratio(color, (0, 0, 0)) > contrast_ratio(color, (255, 255, 255))
+}
+
+/// Bots and integrations of a conversation. Chats without an entry have none.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatWebhooks {
+    // new messages are posted to it, see webhooks::forward
+    #[serde(default)]
+    pub outgoing: Option<OutgoingWebhook>,
+    // bots which post into the conversation, see incoming_webhooks
+    #[serde(default)]
+    pub incoming: Vec<IncomingWebhook>,
+}
+
+impl ChatWebhooks {
+    pub fn is_empty(&self) -> bool {
+        self.outgoing.is_none() && self.incoming.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutgoingWebhook {
+    pub url: String,
+    pub secret: String,
+}
+
+impl OutgoingWebhook {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            secret: webhooks::new_secret(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncomingWebhook {
+    // shown above the messages of the bot
+    pub name: String,
+    // part of the URL the bot posts to, anyone who knows it can post
+    pub token: String,
+}
+
+impl IncomingWebhook {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            token: webhooks::new_secret(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Debug, Default, Deserialize)]
 pub struct Chats {
     // All active chats from warp.
@@ -225,6 +534,44 @@ pub struct Chats {
     pub favorites: Vec<Uuid>,
     // If there was a problem with loading state or state was deleted we readd all existing chats to the sidebar.
     pub readd_sidebars: bool,
+    // Duplicate direct chats which were merged into another chat.
+    // (duplicate chat id, chat it was merged into)
+    #[serde(default)]
+    pub merged: HashMap<Uuid, Uuid>,
+    // Stored separately from `all` so that the rules are kept across restarts. They used to be a
+    // field of `Chat`, which is never saved, so there are no older rules to carry over.
+    // Chats without an entry use the default rules.
+    #[serde(default)]
+    pub notification_rules: HashMap<Uuid, NotificationRules>,
+    // Languages chosen by the user. Chats without an entry use the language detected from their messages.
+    #[serde(default)]
+    pub languages: HashMap<Uuid, String>,
+    // messages which failed to send
+    #[serde(default)]
+    pub outbox: Outbox,
+    // chats without an entry have the panel closed and every widget expanded
+    #[serde(default)]
+    pub panels: HashMap<Uuid, ConversationPanel>,
+    #[serde(default)]
+    pub appearances: HashMap<Uuid, ChatAppearance>,
+    // text size of the messages, chats without an entry aren't zoomed
+    #[serde(default)]
+    pub zooms: HashMap<Uuid, f32>,
+    #[serde(default)]
+    pub webhooks: HashMap<Uuid, ChatWebhooks>,
+    // Set by the user and only stored locally. Stored separately from `all` so that they are kept across restarts.
+    // a title overrides the conversation name, also for direct chats. a topic is a short subject line
+    #[serde(default)]
+    pub titles: HashMap<Uuid, String>,
+    #[serde(default)]
+    pub topics: HashMap<Uuid, String>,
+    // Stored separately from `all` so that the roles are kept across restarts.
+    // Group chats without an entry only have the creator as admin.
+    #[serde(default)]
+    pub moderation: HashMap<Uuid, GroupModeration>,
+    // the newest read receipt of every other participant, see `warp_runner::read_receipts`
+    #[serde(default)]
+    pub read_receipts: HashMap<Uuid, HashMap<DID, DateTime<Utc>>>,
 }
 
 impl Chats {
@@ -248,6 +595,137 @@ impl Chats {
         self.all.get(&id).map(|c| c.is_scrolled).unwrap_or_default()
     }
 
+    /// returns the id of the chat which should display the given conversation. this is only
+    /// different from `id` if the conversation was merged into another one.
+    pub fn resolve_merged(&self, id: Uuid) -> Uuid {
+        self.merged.get(&id).cloned().unwrap_or(id)
+    }
+
+    /// The language chosen for the chat, or else the one detected from its messages. Used for
+    /// spellchecking. Uplink can't translate messages, so nothing else uses it yet.
+    pub fn language(&self, id: &Uuid) -> Option<String> {
+        self.languages.get(id).cloned().or_else(|| {
+            self.all
+                .get(id)
+                .and_then(|chat| chat.detect_language())
+                .map(String::from)
+        })
+    }
+
+    /// The rules of the chat, or of the chat it was merged into
+    pub fn notification_rules(&self, id: &Uuid) -> NotificationRules {
+        self.notification_rules
+            .get(&self.resolve_merged(*id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn panel(&self, id: &Uuid) -> ConversationPanel {
+        self.panels.get(id).cloned().unwrap_or_default()
+    }
+
+    pub fn appearance(&self, id: &Uuid) -> ChatAppearance {
+        self.appearances.get(id).cloned().unwrap_or_default()
+    }
+
+    pub fn zoom(&self, id: &Uuid) -> f32 {
+        self.zooms.get(id).cloned().unwrap_or(1.0)
+    }
+
+    /// The local title if the user set one, otherwise the group name. None means the chat should be named
+    /// after its participants.
+    pub fn title(&self, chat: &Chat) -> Option<String> {
+        self.titles
+            .get(&chat.id)
+            .cloned()
+            .or_else(|| chat.conversation_name.clone())
+    }
+
+    pub fn topic(&self, id: &Uuid) -> Option<String> {
+        self.topics.get(id).cloned()
+    }
+
+    pub fn webhooks(&self, id: &Uuid) -> ChatWebhooks {
+        self.webhooks.get(id).cloned().unwrap_or_default()
+    }
+
+    /// the participants, other than the sender, whose read receipt covers a message sent at `date`
+    pub fn read_by(&self, id: &Uuid, sender: &DID, date: DateTime<Utc>) -> HashSet<DID> {
+        self.read_receipts
+            .get(id)
+            .map(|receipts| {
+                receipts
+                    .iter()
+                    .filter(|(did, up_to)| *did != sender && **up_to >= date)
+                    .map(|(did, _)| did.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn record_read_receipt(&mut self, id: Uuid, reader: DID, up_to: DateTime<Utc>) {
+        let newest = self
+            .read_receipts
+            .entry(id)
+            .or_default()
+            .entry(reader)
+            .or_insert(up_to);
+        *newest = up_to.max(*newest);
+    }
+
+    pub fn moderation(&self, id: &Uuid) -> GroupModeration {
+        self.moderation.get(id).cloned().unwrap_or_default()
+    }
+
+    pub fn role_of(&self, id: &Uuid, did: &DID) -> GroupRole {
+        let creator = self.all.get(id).and_then(|chat| chat.creator.as_ref());
+        self.moderation
+            .get(id)
+            .map(|moderation| moderation.role_of(creator, did))
+            .unwrap_or_else(|| GroupModeration::default().role_of(creator, did))
+    }
+
+    /// changes the roles or muted members of a group chat, entries which are back to the default are removed
+    pub fn moderate(&mut self, id: Uuid, f: impl FnOnce(&mut GroupModeration)) {
+        let moderation = self.moderation.entry(id).or_default();
+        f(moderation);
+        if moderation.is_empty() {
+            self.moderation.remove(&id);
+        }
+    }
+
+    /// groups the merged chats by the chat they were merged into
+    pub fn merged_by_chat(&self) -> HashMap<Uuid, Vec<Uuid>> {
+        let mut map: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for (duplicate, chat) in self.merged.iter() {
+            map.entry(*chat).or_default().push(*duplicate);
+        }
+        map
+    }
+
+    /// the roles and muted members of every group chat, used to initialize the warp runner
+    #[allow(clippy::type_complexity)]
+    pub fn group_roles(
+        &self,
+    ) -> (
+        HashMap<Uuid, HashMap<DID, GroupRole>>,
+        HashMap<Uuid, HashSet<DID>>,
+    ) {
+        let roles = self
+            .moderation
+            .iter()
+            .filter(|(_, moderation)| !moderation.roles.is_empty())
+            .map(|(id, moderation)| (*id, moderation.roles.clone()))
+            .collect();
+        let muted = self
+            .moderation
+            .iter()
+            .filter(|(_, moderation)| !moderation.muted.is_empty())
+            .map(|(id, moderation)| (*id, moderation.muted.clone()))
+            .collect();
+        (roles, muted)
+    }
+
     /// returns the UUID of the message being replied to by the active chat
     pub fn get_replying_to(&self) -> Option<Uuid> {
         self.active.and_then(|id| {
@@ -261,3 +739,55 @@ impl Chats {
 fn default_conversation_type() -> ConversationType {
     ConversationType::Direct
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn drops_a_bubble_which_blends_in() {
+        assert!(needs_dark_text((255, 234, 167)));
+        assert!(!needs_dark_text((20, 20, 60)));
+
+        let mut appearance = ChatAppearance {
+            background: Some((250, 250, 250)),
+            wallpaper: None,
+            wallpaper_color: None,
+            bubble: Some((255, 255, 255)),
+        };
+        assert_eq!(appearance.bubble_color(), None);
+        appearance.bubble = Some((24, 120, 255));
+        assert_eq!(appearance.bubble_color(), Some((24, 120, 255)));
+        // a light wallpaper over a dark background
+        appearance.background = Some((20, 20, 60));
+        appearance.bubble = Some((255, 255, 255));
+        appearance.wallpaper = Some(PathBuf::from("snow.png"));
+        appearance.wallpaper_color = Some((245, 245, 250));
+        assert_eq!(appearance.bubble_color(), None);
+        appearance.wallpaper = None;
+        appearance.background = None;
+        assert_eq!(appearance.bubble_color(), Some((255, 255, 255)));
+    }
+
+    #[test]
+    fn notification_rules_are_saved() {
+        let (kept, duplicate) = (Uuid::new_v4(), Uuid::new_v4());
+        let rules = NotificationRules {
+            muted: Some(MuteUntil::Forever),
+            mentions_only: true,
+            sound: Some(Sounds::Notification),
+        };
+        let mut chats = Chats::default();
+        chats.notification_rules.insert(kept, rules);
+        chats.merged.insert(duplicate, kept);
+
+        let saved = serde_json::to_string(&chats).unwrap();
+        let chats: Chats = serde_json::from_str(&saved).unwrap();
+        assert_eq!(chats.notification_rules(&kept), rules);
+        assert_eq!(chats.notification_rules(&duplicate), rules);
+        assert_eq!(
+            chats.notification_rules(&Uuid::new_v4()),
+            NotificationRules::default()
+        );
+    }
+}
@@ -1,7 +1,15 @@
+use std::collections::{HashMap, HashSet};
+
+use extensions::conversations::Permission;
 use serde::{Deserialize, Serialize};
 use tracing::log;
 
-use crate::STATIC_ARGS;
+use crate::{
+    control,
+    utils::webhooks,
+    warp_runner::{bandwidth, checksum, transfer_queue},
+    STATIC_ARGS,
+};
 
 use super::action::ConfigAction;
 
@@ -31,6 +39,30 @@ pub struct Configuration {
     /// Notification-related configuration options.
     #[serde(default)]
     pub notifications: Notifications,
+
+    /// Where the GIF picker searches for GIFs.
+    #[serde(default)]
+    pub gifs: Gifs,
+
+    /// Events posted to a local endpoint for scripts.
+    #[serde(default)]
+    pub webhooks: Webhooks,
+
+    /// Limits for uploads and downloads in Files.
+    #[serde(default)]
+    pub transfers: Transfers,
+
+    /// How items are opened in Files.
+    #[serde(default)]
+    pub files: Files,
+
+    /// Going away when the computer isn't used.
+    #[serde(default)]
+    pub presence: Presence,
+
+    /// The local socket for scripts and integrations.
+    #[serde(default)]
+    pub automation: Automation,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
@@ -45,14 +77,38 @@ pub struct General {
     pub show_splash: bool,
     #[serde(default)]
     pub enable_overlay: bool,
+    // stops animations and transitions
+    #[serde(default)]
+    pub reduced_motion: bool,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone)]
 pub struct Privacy {
     #[serde(default)]
     pub satellite_sync_nodes: bool,
     #[serde(default)]
     pub safer_file_scanning: bool,
+    // if disabled, others can't tell when you've read their messages and you can't tell when they've read yours.
+    #[serde(default = "bool_true")]
+    pub read_receipts: bool,
+    #[serde(default = "bool_true")]
+    pub typing_indicators: bool,
+    // the PIN is saved in the keychain of the OS the next time the account is unlocked. using it
+    // always requires Touch ID, Windows Hello or the password of the user
+    #[serde(default)]
+    pub keychain_unlock: bool,
+}
+
+impl Default for Privacy {
+    fn default() -> Self {
+        Self {
+            satellite_sync_nodes: false,
+            safer_file_scanning: false,
+            read_receipts: true,
+            typing_indicators: true,
+            keychain_unlock: false,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Copy, Clone, Eq, PartialEq)]
@@ -62,6 +118,9 @@ pub struct AudioVideo {
     pub interface_sounds: bool,
     pub message_sounds: bool,
     pub media_sounds: bool,
+    // show the device and network check before joining a call
+    #[serde(default = "bool_true")]
+    pub pre_call_check: bool,
 }
 
 impl Default for AudioVideo {
@@ -72,16 +131,29 @@ impl Default for AudioVideo {
             interface_sounds: false,
             message_sounds: true,
             media_sounds: true,
+            pre_call_check: true,
         }
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, Copy, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct Extensions {
     #[serde(default)]
     pub enable: bool,
     #[serde(default = "bool_true")]
     pub enable_automatically: bool,
+    // what the user allowed each extension to access, by the path of its library
+    #[serde(default)]
+    pub permissions: HashMap<String, HashSet<Permission>>,
+}
+
+impl Extensions {
+    pub fn is_granted(&self, extension: &str, permission: Permission) -> bool {
+        self.permissions
+            .get(extension)
+            .map(|granted| granted.contains(&permission))
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, Copy, Clone)]
@@ -120,6 +192,148 @@ impl Default for Notifications {
     }
 }
 
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, Eq, PartialEq)]
+pub enum GifProviderKind {
+    // searching GIFs sends the query to a third party, so it is off until the user picks a provider
+    #[default]
+    Disabled,
+    Tenor,
+    Giphy,
+    SelfHosted,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Eq, PartialEq)]
+pub struct Gifs {
+    #[serde(default)]
+    pub provider: GifProviderKind,
+    // needed by Tenor and Giphy
+    #[serde(default)]
+    pub api_key: String,
+    // search URL of a self hosted provider
+    #[serde(default)]
+    pub endpoint: String,
+    // if false, the link to the GIF is sent and shown as an embed
+    #[serde(default)]
+    pub send_as_attachment: bool,
+}
+
+// outgoing events for scripts, see utils::webhooks
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Webhooks {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default = "webhooks::new_secret")]
+    pub secret: String,
+    #[serde(default = "bool_true")]
+    pub new_message: bool,
+    #[serde(default = "bool_true")]
+    pub friend_request: bool,
+    #[serde(default = "bool_true")]
+    pub transfer_complete: bool,
+}
+
+impl Default for Webhooks {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            secret: webhooks::new_secret(),
+            new_message: true,
+            friend_request: true,
+            transfer_complete: true,
+        }
+    }
+}
+
+// the control socket, served while Uplink runs with its window. see control
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Automation {
+    #[serde(default)]
+    pub enabled: bool,
+    // clients have to send it before any other request
+    #[serde(default = "control::new_token")]
+    pub token: String,
+}
+
+impl Default for Automation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            token: control::new_token(),
+        }
+    }
+}
+
+// 0 for no limit. see warp_runner::bandwidth and warp_runner::transfer_queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Transfers {
+    // in kB/s
+    #[serde(default)]
+    pub max_upload_kbps: u32,
+    #[serde(default)]
+    pub max_download_kbps: u32,
+    // the other uploads wait in line
+    #[serde(default = "default_concurrent_uploads")]
+    pub max_concurrent_uploads: u32,
+    // downloads which don't match the checksum of the upload are tried again, see warp_runner::checksum
+    #[serde(default = "bool_true")]
+    pub retry_corrupted_downloads: bool,
+}
+
+fn default_concurrent_uploads() -> u32 {
+    3
+}
+
+impl Default for Transfers {
+    fn default() -> Self {
+        Self {
+            max_upload_kbps: 0,
+            max_download_kbps: 0,
+            max_concurrent_uploads: default_concurrent_uploads(),
+            retry_corrupted_downloads: true,
+        }
+    }
+}
+
+// single click opened items before this was configurable, so that stays the default
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OpenItemsOn {
+    #[default]
+    SingleClick,
+    // a single click selects the item instead, like the file managers of most platforms
+    DoubleClick,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Files {
+    #[serde(default)]
+    pub open_items_on: OpenItemsOn,
+}
+
+// the status is set to away when the keyboard and mouse weren't used for a while, and back once they are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Presence {
+    #[serde(default = "bool_true")]
+    pub auto_away: bool,
+    #[serde(default = "default_away_after_minutes")]
+    pub away_after_minutes: u32,
+}
+
+fn default_away_after_minutes() -> u32 {
+    10
+}
+
+impl Default for Presence {
+    fn default() -> Self {
+        Self {
+            auto_away: true,
+            away_after_minutes: default_away_after_minutes(),
+        }
+    }
+}
+
 impl Configuration {
     pub fn new() -> Self {
         // Create a default configuration here
@@ -130,7 +344,7 @@ impl Configuration {
 
 impl Configuration {
     pub fn load_or_default() -> Self {
-        if let Ok(b) = std::fs::read(&STATIC_ARGS.login_config_path) {
+        if let Ok(b) = std::fs::read(STATIC_ARGS.login_config_path()) {
             if let Ok(n) = serde_json::from_slice(&b) {
                 return n;
             }
@@ -146,6 +360,7 @@ impl Configuration {
             ConfigAction::SetTheme(theme_name) => self.general.theme = theme_name,
             ConfigAction::SetOverlayEnabled(overlay) => self.general.enable_overlay = overlay,
             ConfigAction::SetDyslexicEnabled(flag) => self.general.dyslexia_support = flag,
+            ConfigAction::SetReducedMotion(flag) => self.general.reduced_motion = flag,
             ConfigAction::SetDevModeEnabled(flag) => self.developer.developer_mode = flag,
             ConfigAction::SetExperimentalFeaturesEnabled(flag) => {
                 self.developer.experimental_features = flag
@@ -167,7 +382,36 @@ impl Configuration {
             ConfigAction::SetAutoEnableExtensions(flag) => {
                 self.extensions.enable_automatically = flag
             }
+            ConfigAction::SetExtensionPermission(extension, permission, granted) => {
+                let granted_permissions = self.extensions.permissions.entry(extension).or_default();
+                if granted {
+                    granted_permissions.insert(permission);
+                } else {
+                    granted_permissions.remove(&permission);
+                }
+            }
             ConfigAction::SetEchoCancellation(flag) => self.audiovideo.echo_cancellation = flag,
+            ConfigAction::SetPreCallCheck(flag) => self.audiovideo.pre_call_check = flag,
+            ConfigAction::SetReadReceiptsEnabled(flag) => self.privacy.read_receipts = flag,
+            ConfigAction::SetTypingIndicatorsEnabled(flag) => self.privacy.typing_indicators = flag,
+            ConfigAction::SetKeychainUnlock(flag) => self.privacy.keychain_unlock = flag,
+            ConfigAction::SetGifs(gifs) => self.gifs = gifs,
+            ConfigAction::SetWebhooks(hooks) => {
+                webhooks::configure(hooks.clone());
+                self.webhooks = hooks;
+            }
+            ConfigAction::SetTransfers(transfers) => {
+                bandwidth::configure(&transfers);
+                transfer_queue::configure(&transfers);
+                checksum::configure(&transfers);
+                self.transfers = transfers;
+            }
+            ConfigAction::SetOpenItemsOn(open_items_on) => self.files.open_items_on = open_items_on,
+            ConfigAction::SetPresence(presence) => self.presence = presence,
+            ConfigAction::SetAutomation(automation) => {
+                control::configure(&automation);
+                self.automation = automation;
+            }
         }
 
         if self.audiovideo != old_audiovideo {
@@ -178,7 +422,7 @@ impl Configuration {
                     return;
                 }
             };
-            if let Err(e) = std::fs::write(&STATIC_ARGS.login_config_path, contents) {
+            if let Err(e) = std::fs::write(STATIC_ARGS.login_config_path(), contents) {
                 log::error!("failed to save login_config: {e}");
             }
         }
@@ -0,0 +1,175 @@
+//! A status message with an emoji which can expire, e.g. "🎧 Focusing" for an hour. The emoji and
+//! the text are sent as the status message of the identity, so clients which don't know about it
+//! show them as they are. The expiry is kept in the metadata of the identity, next to the status
+//! message it belongs to, so a status set by one of those clients isn't taken for the old one.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Local, LocalResult, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_KEY: &str = "custom-status";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Expiry {
+    #[default]
+    Never,
+    ThirtyMinutes,
+    OneHour,
+    // the end of the local day
+    Today,
+}
+
+impl Expiry {
+    pub const ALL: [Expiry; 4] = [
+        Expiry::Never,
+        Expiry::ThirtyMinutes,
+        Expiry::OneHour,
+        Expiry::Today,
+    ];
+
+    pub fn deadline(&self, now: DateTime<Local>) -> Option<DateTime<Utc>> {
+        let deadline = match self {
+            Expiry::Never => return None,
+            Expiry::ThirtyMinutes => now + Duration::minutes(30),
+            Expiry::OneHour => now + Duration::hours(1),
+            Expiry::Today => {
+                let midnight = now.date_naive().succ_opt()?.and_time(NaiveTime::MIN);
+                match Local.from_local_datetime(&midnight) {
+                    LocalResult::Single(date) | LocalResult::Ambiguous(date, _) => date,
+                    LocalResult::None => return None,
+                }
+            }
+        };
+        Some(deadline.with_timezone(&Utc))
+    }
+
+    /// The key of its name in the locale files
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            Expiry::Never => "settings-profile.expiry-never",
+            Expiry::ThirtyMinutes => "settings-profile.expiry-30m",
+            Expiry::OneHour => "settings-profile.expiry-1h",
+            Expiry::Today => "settings-profile.expiry-today",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CustomStatus {
+    // empty for none
+    pub emoji: String,
+    pub text: String,
+    // what was picked, to pick it again
+    pub expiry: Expiry,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+// the metadata of a status
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    message: String,
+    emoji: String,
+    expiry: Expiry,
+    // a unix timestamp
+    expires: Option<i64>,
+}
+
+impl CustomStatus {
+    pub fn new(emoji: &str, text: &str, expiry: Expiry) -> Self {
+        Self {
+            emoji: emoji.trim().to_string(),
+            text: text.trim().to_string(),
+            expiry,
+            expires: expiry.deadline(Local::now()),
+        }
+    }
+
+    /// Reads the status message and the metadata of an identity. Status messages without
+    /// metadata, or which were changed after it, only have text
+    pub fn from_identity(message: &str, metadata: &HashMap<String, String>) -> Self {
+        let stored = metadata
+            .get(STATUS_KEY)
+            .and_then(|value| serde_json::from_str::<Stored>(value).ok())
+            .filter(|stored| stored.message == message);
+        let Some(stored) = stored else {
+            return Self {
+                text: message.to_string(),
+                ..Default::default()
+            };
+        };
+        let text = message
+            .strip_prefix(stored.emoji.as_str())
+            .unwrap_or(message);
+        Self {
+            text: text.trim_start().to_string(),
+            emoji: stored.emoji,
+            expiry: stored.expiry,
+            expires: stored
+                .expires
+                .and_then(|secs| Utc.timestamp_opt(secs, 0).single()),
+        }
+    }
+
+    /// The status message to send, `None` to clear it
+    pub fn to_message(&self) -> Option<String> {
+        let shown = self.shown();
+        (!shown.is_empty()).then_some(shown)
+    }
+
+    /// The value of the metadata key, `None` to remove it
+    pub fn to_metadata(&self) -> Option<String> {
+        let message = self.to_message()?;
+        if self.emoji.is_empty() && self.expires.is_none() {
+            return None;
+        }
+        serde_json::to_string(&Stored {
+            message,
+            emoji: self.emoji.clone(),
+            expiry: self.expiry,
+            expires: self.expires.map(|date| date.timestamp()),
+        })
+        .ok()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires.map_or(false, |date| date <= now)
+    }
+
+    /// The emoji and the text, the way they are shown
+    pub fn shown(&self) -> String {
+        match (self.emoji.is_empty(), self.text.is_empty()) {
+            (true, _) => self.text.clone(),
+            (false, true) => self.emoji.clone(),
+            (false, false) => format!("{} {}", self.emoji, self.text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_identity() {
+        let status = CustomStatus {
+            emoji: "🎧".into(),
+            text: "Focusing".into(),
+            expiry: Expiry::OneHour,
+            expires: Utc.timestamp_opt(1_700_000_000, 0).single(),
+        };
+        let message = status.to_message().unwrap();
+        assert_eq!(message, "🎧 Focusing");
+        let metadata = HashMap::from([(STATUS_KEY.to_string(), status.to_metadata().unwrap())]);
+        assert_eq!(CustomStatus::from_identity(&message, &metadata), status);
+        assert!(status.is_expired(Utc::now()));
+
+        // set by a client which doesn't know about the metadata
+        let plain = CustomStatus::from_identity("out for lunch", &metadata);
+        assert_eq!(plain.text, "out for lunch");
+        assert_eq!(plain.expires, None);
+        assert_eq!(plain.to_message().as_deref(), Some("out for lunch"));
+        assert_eq!(plain.to_metadata(), None);
+        assert_eq!(CustomStatus::default().to_message(), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Themes made in the theme editor. They are saved to the themes folder as plain stylesheets, the
+//! styles of the theme they started from followed by the colors of the palette on `:root`, so they
+//! can be shared, imported and edited by hand like any other theme.
+
+use std::{fs, io, path::Path};
+
+use titlecase::titlecase;
+
+use crate::STATIC_ARGS;
+
+use super::ui::Theme;
+
+/// The CSS variables the editor exposes, with the colors of the default theme
+pub const PALETTE: [(&str, &str); 16] = [
+    ("primary", "#4d4dff"),
+    ("secondary", "#21263a"),
+    ("secondary-light", "#23293e"),
+    ("secondary-dark", "#1a1e2d"),
+    ("background", "#0d0e16"),
+    ("background-dark", "#141420"),
+    ("border-color", "#1c1d2b"),
+    ("text-color", "#d6def4"),
+    ("text-color-muted", "#6b78ac"),
+    ("text-color-link", "#4761fc"),
+    ("placeholder", "#bcbcbc"),
+    ("success", "#1dd1a1"),
+    ("info", "#1b9cfc"),
+    ("warning", "#f5af19"),
+    ("danger", "#f93854"),
+    ("unread-indicator", "#ff0000"),
+];
+
+// starts the colors added by the editor. what comes before is kept as it was
+const MARKER: &str = "/* colors from the theme editor */";
+// what can be imported
+const THEME_EXTENSIONS: [&str; 2] = ["scss", "css"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomTheme {
+    pub name: String,
+    // (variable, #rrggbb) in the order of the palette
+    pub colors: Vec<(String, String)>,
+    // the styles of the theme it started from
+    base: String,
+}
+
+impl CustomTheme {
+    /// Starts from the colors of `styles`, the default ones for what it doesn't set
+    pub fn from_styles(name: &str, styles: &str) -> Self {
+        let colors = PALETTE
+            .iter()
+            .map(|(var, default)| {
+                let color = find_color(styles, var).unwrap_or_else(|| default.to_string());
+                (var.to_string(), color)
+            })
+            .collect();
+        let base = match styles.split_once(MARKER) {
+            Some((base, _)) => base,
+            None => styles,
+        };
+        Self {
+            name: name.to_string(),
+            colors,
+            base: base.trim_end().to_string(),
+        }
+    }
+
+    pub fn set_color(&mut self, var: &str, color: &str) {
+        if let Some((_, c)) = self.colors.iter_mut().find(|(v, _)| v == var) {
+            *c = color.to_string();
+        }
+    }
+
+    pub fn to_styles(&self) -> String {
+        let vars: String = self
+            .colors
+            .iter()
+            .map(|(var, color)| format!("    --{var}: {color};\n"))
+            .collect();
+        let base = if self.base.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n\n", self.base)
+        };
+        format!("{base}{MARKER}\n:root {{\n{vars}}}\n")
+    }
+
+    /// Writes the theme to the themes folder, replacing one with the same name
+    pub fn save(&self) -> io::Result<Theme> {
+        let name = self.name.trim();
+        if name.is_empty() || name.contains(['/', '\\', '.']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid theme name",
+            ));
+        }
+        fs::create_dir_all(&STATIC_ARGS.themes_path)?;
+        let path = STATIC_ARGS.themes_path.join(format!("{name}.scss"));
+        let styles = self.to_styles();
+        fs::write(&path, &styles)?;
+        // named like the themes read from the folder
+        Ok(Theme {
+            filename: path.display().to_string(),
+            name: titlecase(name),
+            styles,
+        })
+    }
+}
+
+// the last `--var: #rrggbb;` which isn't commented out, the one which applies
+fn find_color(styles: &str, var: &str) -> Option<String> {
+    let prefix = format!("--{var}:");
+    styles
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| line.strip_prefix(&prefix))
+        .map(|value| value.trim().trim_end_matches(';').trim())
+        .filter(|value| is_hex_color(value))
+        .last()
+        .map(|value| value.to_lowercase())
+}
+
+// the color picker only takes #rrggbb
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Copies a theme file shared by someone else into the themes folder
+pub fn import_theme(path: &Path) -> io::Result<()> {
+    let is_theme = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| THEME_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false);
+    let file_name = match path.file_name() {
+        Some(name) if is_theme => name,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "unsupported theme format",
+            ))
+        }
+    };
+    fs::create_dir_all(&STATIC_ARGS.themes_path)?;
+    fs::copy(path, STATIC_ARGS.themes_path.join(file_name))?;
+    Ok(())
+}
+
+pub fn export_theme(theme: &Theme, path: &Path) -> io::Result<()> {
+    fs::write(path, &theme.styles)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_and_writes_the_palette() {
+        let styles = ":root {\n    --primary: #AABBCC;\n    // --primary: #111111;\n    --secondary: color-mix(in srgb, red 50%, blue);\n}";
+        let mut theme = CustomTheme::from_styles("Ocean", styles);
+        assert_eq!(theme.colors[0], ("primary".into(), "#aabbcc".into()));
+        // not a plain color, so the default is kept
+        assert_eq!(theme.colors[1], ("secondary".into(), "#21263a".into()));
+
+        // editing it again keeps the styles it started from once
+        theme.set_color("danger", "#000000");
+        let saved = theme.to_styles();
+        assert!(saved.starts_with(styles));
+        let again = CustomTheme::from_styles("Ocean", &saved);
+        assert_eq!(again, theme);
+        assert_eq!(again.to_styles(), saved);
+    }
+}
@@ -1,12 +1,22 @@
-use std::sync::Arc;
+use std::{fs, sync::Arc};
 
+use chrono::{DateTime, Utc};
 use humansize::{format_size, DECIMAL};
 use tokio::sync::Mutex;
+use tracing::log;
 use uuid::Uuid;
 
-use crate::language::{get_local_text, get_local_text_with_args};
+use crate::{
+    language::{get_local_text, get_local_text_with_args},
+    utils::webhooks::{self, WebhookEvent},
+    warp_runner::checksum,
+    STATIC_ARGS,
+};
 
-use super::pending_message::FileProgression;
+use super::{
+    pending_message::FileProgression,
+    transfer_history::{Direction, Outcome, TransferHistory, TransferRecord},
+};
 
 static SCALE_DECIMAL: [&str; 9] = ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"];
 
@@ -35,12 +45,16 @@ impl TransferState {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransferProgress {
+    // waiting for the other transfers, see warp_runner::transfer_queue
+    Queued,
     Starting,
     Progress(u8),
     Finishing,
     Paused(u8),
     Cancelling(u8),
     Error(u8),
+    // the download didn't match the checksum of the upload, see warp_runner::checksum
+    VerificationFailed(u8),
 }
 
 impl TransferProgress {
@@ -49,7 +63,8 @@ impl TransferProgress {
             TransferProgress::Progress(p)
             | TransferProgress::Paused(p)
             | TransferProgress::Cancelling(p)
-            | TransferProgress::Error(p) => *p,
+            | TransferProgress::Error(p)
+            | TransferProgress::VerificationFailed(p) => *p,
             _ => 0,
         }
     }
@@ -104,6 +119,10 @@ pub struct FileProgress {
     pub description: String,
     // Flag used to pause or cancel this transfer
     pub state: TransferState,
+    // when it left the queue, for the history
+    pub started: DateTime<Utc>,
+    // the conversation of an attachment
+    pub conversation_id: Option<Uuid>,
 }
 
 impl PartialEq for FileProgress {
@@ -119,9 +138,48 @@ impl PartialEq for FileProgress {
 pub struct TransferTracker {
     pub file_progress_upload: Vec<FileProgress>,
     pub file_progress_download: Vec<FileProgress>,
+    // transfers are added once they're removed from above
+    pub history: TransferHistory,
 }
 
 impl TransferTracker {
+    /// uploads and downloads which are still running. paused and failed ones don't count
+    pub fn active_transfers(&self) -> impl Iterator<Item = &FileProgress> {
+        self.file_progress_upload
+            .iter()
+            .chain(self.file_progress_download.iter())
+            .filter(|f| {
+                !matches!(
+                    f.progress,
+                    TransferProgress::Paused(_)
+                        | TransferProgress::Error(_)
+                        | TransferProgress::VerificationFailed(_)
+                )
+            })
+    }
+
+    /// Adds a transfer which waits for its turn
+    pub fn queue_file_transfer(
+        &mut self,
+        id: Uuid,
+        file: String,
+        state: TransferState,
+        tracker: TrackerType,
+    ) {
+        self.get_tracker_from(tracker).push(FileProgress {
+            id,
+            file,
+            progress: TransferProgress::Queued,
+            size: 0,
+            total_size: 0,
+            description: get_local_text("files.transfer-queued"),
+            state,
+            started: Utc::now(),
+            conversation_id: None,
+        });
+        self.checkpoint();
+    }
+
     pub fn start_file_transfer(
         &mut self,
         id: Uuid,
@@ -129,6 +187,13 @@ impl TransferTracker {
         state: TransferState,
         tracker: TrackerType,
     ) {
+        let transfers = self.get_tracker_from(tracker.clone());
+        if let Some(f) = transfers.iter_mut().find(|p| id.eq(&p.id)) {
+            f.progress = TransferProgress::Starting;
+            f.description = get_local_text("files.transfer-start");
+            f.started = Utc::now();
+            return;
+        }
         match tracker {
             TrackerType::FileUpload => self.file_progress_upload.push(FileProgress {
                 id,
@@ -138,6 +203,8 @@ impl TransferTracker {
                 total_size: 0,
                 description: get_local_text("files.transfer-start"),
                 state,
+                started: Utc::now(),
+                conversation_id: None,
             }),
             TrackerType::FileDownload => self.file_progress_download.push(FileProgress {
                 id,
@@ -147,8 +214,11 @@ impl TransferTracker {
                 total_size: 0,
                 description: get_local_text("files.transfer-start"),
                 state,
+                started: Utc::now(),
+                conversation_id: None,
             }),
         }
+        self.checkpoint();
     }
 
     pub fn update_file_upload(
@@ -195,6 +265,11 @@ impl TransferTracker {
                     if let Some(total) = total {
                         f.total_size = total;
                     }
+                    webhooks::emit(WebhookEvent::TransferComplete {
+                        name: f.file.clone(),
+                        size: f.total_size,
+                        upload: !download,
+                    });
                     f.description = get_local_text_with_args(
                         "files.transfer-finishing",
                         vec![("size", format_size(f.total_size, DECIMAL))],
@@ -206,14 +281,20 @@ impl TransferTracker {
                     last_size,
                     error,
                 } => {
-                    f.description = get_local_text_with_args(
-                        "files.transfer-error",
-                        vec![("error", error.to_string())],
-                    );
                     if let Some(last_size) = last_size {
                         f.total_size = last_size;
                     }
-                    f.progress = TransferProgress::Error(f.progress.get_progress());
+                    if checksum::is_mismatch(&error) {
+                        f.description = get_local_text("files.transfer-verification-failed");
+                        f.progress =
+                            TransferProgress::VerificationFailed(f.progress.get_progress());
+                    } else {
+                        f.description = get_local_text_with_args(
+                            "files.transfer-error",
+                            vec![("error", error.to_string())],
+                        );
+                        f.progress = TransferProgress::Error(f.progress.get_progress());
+                    }
                 }
             };
         }
@@ -275,9 +356,104 @@ impl TransferTracker {
         }
     }
 
+    /// Marks the transfer as part of a conversation
+    pub fn set_conversation(&mut self, file_id: Uuid, conversation_id: Uuid, tracker: TrackerType) {
+        if let Some(f) = self
+            .get_tracker_from(tracker)
+            .iter_mut()
+            .find(|p| file_id.eq(&p.id))
+        {
+            f.conversation_id = Some(conversation_id);
+        }
+        self.checkpoint();
+    }
+
     pub fn remove_file_upload(&mut self, file_id: Uuid, tracker: TrackerType) {
+        let direction = match tracker {
+            TrackerType::FileUpload => Direction::Upload,
+            TrackerType::FileDownload => Direction::Download,
+        };
+        let transfers = self.get_tracker_from(tracker);
+        let Some(idx) = transfers.iter().position(|p| file_id.eq(&p.id)) else {
+            return;
+        };
+        let f = transfers.remove(idx);
+        self.checkpoint();
+        // cancelled transfers aren't kept
+        let outcome = match f.progress {
+            TransferProgress::Finishing => Outcome::Completed,
+            TransferProgress::Error(_) | TransferProgress::VerificationFailed(_) => Outcome::Failed,
+            _ => return,
+        };
+        self.history.record(TransferRecord {
+            file: f.file,
+            size: f.total_size,
+            direction,
+            outcome,
+            started: f.started,
+            finished: Utc::now(),
+            conversation_id: f.conversation_id,
+        });
+    }
+
+    /// Writes down the transfers which haven't finished yet. It's done whenever one is added or removed, so the ones
+    /// which were interrupted are known on the next launch even if Uplink didn't get to exit cleanly
+    pub fn checkpoint(&self) {
+        let path = STATIC_ARGS.interrupted_transfers_path();
+        let now = Utc::now();
+        let running: Vec<TransferRecord> = self
+            .file_progress_upload
+            .iter()
+            .map(|f| (f, Direction::Upload))
+            .chain(
+                self.file_progress_download
+                    .iter()
+                    .map(|f| (f, Direction::Download)),
+            )
+            .map(|(f, direction)| TransferRecord {
+                file: f.file.clone(),
+                size: f.total_size,
+                direction,
+                outcome: Outcome::Failed,
+                started: f.started,
+                finished: now,
+                conversation_id: f.conversation_id,
+            })
+            .collect();
+        if running.is_empty() {
+            let _ = fs::remove_file(path);
+            return;
+        }
+        match serde_json::to_string(&running) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    log::error!("failed to save the running transfers: {e}");
+                }
+            }
+            Err(e) => log::error!("failed to serialize the running transfers: {e}"),
+        }
+    }
+
+    /// Adds the transfers which were interrupted when Uplink last exited to the history, as failed, and returns their
+    /// names
+    pub fn take_interrupted(&mut self) -> Vec<String> {
+        let path = STATIC_ARGS.interrupted_transfers_path();
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return vec![];
+        };
+        let _ = fs::remove_file(&path);
+        let interrupted: Vec<TransferRecord> = serde_json::from_str(&contents).unwrap_or_default();
+        let names = interrupted.iter().map(|r| r.file.clone()).collect();
+        for record in interrupted {
+            self.history.record(record);
+        }
+        names
+    }
+
+    /// Puts the queued transfers after the running ones, in the order they'll start in
+    pub fn order_queued(&mut self, order: &[Uuid], tracker: TrackerType) {
         self.get_tracker_from(tracker)
-            .retain(|p| !file_id.eq(&p.id))
+            .sort_by_key(|p| order.iter().position(|id| id.eq(&p.id)));
     }
 
     fn get_tracker_from(&mut self, tracker: TrackerType) -> &mut Vec<FileProgress> {
@@ -10,6 +10,11 @@ pub fn get_default_keybinds() -> Vec<(GlobalShortcut, Shortcut)> {
     } else {
         ModifiersState::ALT
     };
+    let control_or_command_modifierstate = if cfg!(target_os = "macos") {
+        ModifiersState::SUPER
+    } else {
+        ModifiersState::CONTROL
+    };
     Vec::from([
         (
             GlobalShortcut::IncreaseFontSize,
@@ -45,6 +50,30 @@ pub fn get_default_keybinds() -> Vec<(GlobalShortcut, Shortcut)> {
                 true,
             )),
         ),
+        (
+            GlobalShortcut::AnswerCall,
+            Shortcut::from((
+                vec![KeyCode::A],
+                vec![alt_or_command_modifierstate, ModifiersState::SHIFT],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::HangUpCall,
+            Shortcut::from((
+                vec![KeyCode::H],
+                vec![alt_or_command_modifierstate, ModifiersState::SHIFT],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::CycleCallParticipants,
+            Shortcut::from((
+                vec![KeyCode::P],
+                vec![alt_or_command_modifierstate, ModifiersState::SHIFT],
+                false,
+            )),
+        ),
         (
             GlobalShortcut::OpenCloseDevTools,
             Shortcut::from((
@@ -61,6 +90,14 @@ pub fn get_default_keybinds() -> Vec<(GlobalShortcut, Shortcut)> {
                 false,
             )),
         ),
+        (
+            GlobalShortcut::ToggleProfilingOverlay,
+            Shortcut::from((
+                vec![KeyCode::O],
+                vec![ModifiersState::CONTROL, ModifiersState::SHIFT],
+                false,
+            )),
+        ),
         (
             GlobalShortcut::SetAppVisible,
             Shortcut::from((
@@ -69,6 +106,87 @@ pub fn get_default_keybinds() -> Vec<(GlobalShortcut, Shortcut)> {
                 true,
             )),
         ),
+        (
+            GlobalShortcut::OpenQuickSwitcher,
+            Shortcut::from((
+                vec![KeyCode::K],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::NextChat,
+            Shortcut::from((
+                vec![KeyCode::CloseBraket],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::PreviousChat,
+            Shortcut::from((
+                vec![KeyCode::OpenBracket],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::OpenSettings,
+            Shortcut::from((
+                vec![KeyCode::Comma],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::LockApp,
+            Shortcut::from((
+                vec![KeyCode::L],
+                vec![control_or_command_modifierstate, ModifiersState::SHIFT],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::ZoomInChat,
+            Shortcut::from((
+                vec![KeyCode::EqualSign],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::ZoomOutChat,
+            Shortcut::from((
+                vec![KeyCode::Subtract],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::ResetChatZoom,
+            Shortcut::from((
+                vec![KeyCode::Num0],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        // enter alone sends from the chatbar as well, this also sends from the expanded editor
+        (
+            GlobalShortcut::SendMessage,
+            Shortcut::from((
+                vec![KeyCode::Enter],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
+        (
+            GlobalShortcut::FocusSearch,
+            Shortcut::from((
+                vec![KeyCode::F],
+                vec![control_or_command_modifierstate],
+                false,
+            )),
+        ),
     ])
 }
 
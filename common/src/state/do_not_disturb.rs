@@ -0,0 +1,106 @@
+//! Do Not Disturb silences the desktop notifications and their sounds, either until turned off or
+//! during scheduled hours. Unread badges are still counted, so nothing is missed.
+
+use chrono::{Datelike, Duration, Local, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DoNotDisturb {
+    // turned on by hand, until turned off again
+    pub enabled: bool,
+    pub schedules: Vec<Schedule>,
+}
+
+/// A daily period of quiet. `end` may be before `start` for periods which span midnight, e.g.
+/// 22:00 to 08:00
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Schedule {
+    pub id: Uuid,
+    pub enabled: bool,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    // the days the period starts on, from Monday
+    pub days: [bool; 7],
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            enabled: true,
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap_or_default(),
+            end: NaiveTime::from_hms_opt(8, 0, 0).unwrap_or_default(),
+            days: [true; 7],
+        }
+    }
+}
+
+impl Schedule {
+    pub fn runs_on(&self, day: Weekday) -> bool {
+        self.days[day.num_days_from_monday() as usize]
+    }
+
+    fn is_active_at(&self, now: NaiveDateTime) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let time = now.time();
+        let today = self.runs_on(now.weekday());
+        if self.start < self.end {
+            today && self.start <= time && time < self.end
+        } else if self.start > self.end {
+            // the part after midnight belongs to the day before
+            let yesterday = self.runs_on((now - Duration::days(1)).weekday());
+            (today && time >= self.start) || (yesterday && time < self.end)
+        } else {
+            // the same start and end is taken as the whole day
+            today
+        }
+    }
+}
+
+impl DoNotDisturb {
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(Local::now().naive_local())
+    }
+
+    fn is_active_at(&self, now: NaiveDateTime) -> bool {
+        self.enabled || self.schedules.iter().any(|s| s.is_active_at(now))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn overnight_schedule() {
+        let mut dnd = DoNotDisturb {
+            enabled: false,
+            schedules: vec![Schedule::default()],
+        };
+        // 2024-01-05 is a Friday
+        let at = |day: u32, hour: u32| {
+            NaiveDate::from_ymd_opt(2024, 1, day)
+                .and_then(|d| d.and_hms_opt(hour, 30, 0))
+                .unwrap()
+        };
+        assert!(dnd.is_active_at(at(5, 23)));
+        assert!(dnd.is_active_at(at(6, 7)));
+        assert!(!dnd.is_active_at(at(6, 12)));
+
+        // only starting on Fridays: Saturday morning is still quiet, Saturday night isn't
+        dnd.schedules[0].days = [false, false, false, false, true, false, false];
+        assert!(dnd.is_active_at(at(6, 7)));
+        assert!(!dnd.is_active_at(at(6, 23)));
+        assert!(!dnd.is_active_at(at(5, 7)));
+
+        dnd.schedules[0].enabled = false;
+        assert!(!dnd.is_active_at(at(5, 23)));
+        dnd.enabled = true;
+        assert!(dnd.is_active_at(at(5, 12)));
+    }
+}
@@ -0,0 +1,84 @@
+//! Pronouns, an about text and links, shown when clicking on someone. They are kept in the metadata
+//! of the identity, so they reach everyone who can see the identity, like the banner does.
+
+use std::collections::HashMap;
+
+const PRONOUNS_KEY: &str = "pronouns";
+const ABOUT_KEY: &str = "about";
+// one per line
+const LINKS_KEY: &str = "links";
+
+pub const MAX_PRONOUNS_LEN: usize = 32;
+pub const MAX_ABOUT_LEN: usize = 300;
+pub const MAX_LINKS: usize = 3;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtendedProfile {
+    pub pronouns: String,
+    pub about: String,
+    pub links: Vec<String>,
+}
+
+impl ExtendedProfile {
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Self {
+        let get = |key: &str| metadata.get(key).cloned().unwrap_or_default();
+        Self {
+            pronouns: get(PRONOUNS_KEY),
+            about: get(ABOUT_KEY),
+            links: Self::parse_links(&get(LINKS_KEY)),
+        }
+    }
+
+    /// The metadata keys and their values, `None` for the ones to remove
+    pub fn to_metadata(&self) -> Vec<(String, Option<String>)> {
+        let value = |v: &str| (!v.is_empty()).then(|| v.to_string());
+        vec![
+            (PRONOUNS_KEY.into(), value(self.pronouns.trim())),
+            (ABOUT_KEY.into(), value(self.about.trim())),
+            (LINKS_KEY.into(), value(&self.links.join("\n"))),
+        ]
+    }
+
+    /// Web addresses separated by spaces, commas or lines. Anything else is dropped, as are the
+    /// ones past the limit
+    pub fn parse_links(text: &str) -> Vec<String> {
+        text.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|link| link.starts_with("https://") || link.starts_with("http://"))
+            .take(MAX_LINKS)
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pronouns.is_empty() && self.about.is_empty() && self.links.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_metadata() {
+        let profile = ExtendedProfile {
+            pronouns: "they/them".into(),
+            about: String::new(),
+            links: ExtendedProfile::parse_links(
+                "https://a.example, ftp://b.example\nhttp://c.example https://d.example https://e.example",
+            ),
+        };
+        assert_eq!(
+            profile.links,
+            vec!["https://a.example", "http://c.example", "https://d.example"]
+        );
+
+        let updates = profile.to_metadata();
+        assert!(updates.contains(&("about".into(), None)));
+        let metadata: HashMap<String, String> = updates
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect();
+        assert_eq!(ExtendedProfile::from_metadata(&metadata), profile);
+        assert!(ExtendedProfile::default().is_empty());
+    }
+}
@@ -1,8 +1,8 @@
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use warp::crypto::DID;
 
-use crate::STATIC_ARGS;
+use crate::{sounds::Sounds, STATIC_ARGS};
 
 // warning: Friends implements Serialize
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -18,6 +18,10 @@ pub struct Friends {
     pub incoming_requests: HashSet<DID>,
     #[serde(default)]
     pub outgoing_requests: HashSet<DID>,
+    // Notification sound for messages from a friend. Unlike the fields above this is local
+    // data, so it is always saved.
+    #[serde(default)]
+    pub sounds: HashMap<DID, Sounds>,
 }
 
 // don't skip friends data when using mock data
@@ -26,7 +30,7 @@ impl Serialize for Friends {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Friends", 4)?;
+        let mut state = serializer.serialize_struct("Friends", 5)?;
         if STATIC_ARGS.use_mock {
             state.serialize_field("all", &self.all)?;
             state.serialize_field("blocked", &self.blocked)?;
@@ -38,6 +42,7 @@ impl Serialize for Friends {
             state.skip_field("incoming_requests")?;
             state.skip_field("outgoing_requests")?;
         };
+        state.serialize_field("sounds", &self.sounds)?;
 
         state.end()
     }
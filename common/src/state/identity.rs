@@ -1,3 +1,4 @@
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 use warp::multipass::{
@@ -5,13 +6,22 @@ use warp::multipass::{
     identity::{Identity as WarpIdentity, IdentityStatus, Platform},
 };
 
+use super::{custom_status::CustomStatus, extended_profile::ExtendedProfile};
+use crate::utils::avatars::Avatar;
+
+// warp appends the bytes [11, 00, 23] to the pictures it generates for identities without one
+const DEFAULT_PICTURE_MARKER: &str = "\u{b}\0\u{17}";
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq)]
 pub struct Identity {
     identity: WarpIdentity,
     status: IdentityStatus,
     platform: Platform,
-    //TODO: Use `Option<String>` in the future unless this is split away
-    profile_image: String,
+    // the picture as it is shown, without the marker of default pictures. copies of the identity
+    // share it
+    profile_image: Avatar,
+    #[serde(default)]
+    default_picture: bool,
     profile_banner: String,
 }
 
@@ -41,7 +51,8 @@ impl From<WarpIdentity> for Identity {
             identity,
             status: IdentityStatus::Offline,
             platform: Default::default(),
-            profile_image: String::new(),
+            profile_image: Default::default(),
+            default_picture: false,
             profile_banner: String::new(),
         }
     }
@@ -66,10 +77,24 @@ impl Identity {
             identity,
             status,
             platform,
-            profile_image: String::new(),
+            profile_image: Default::default(),
+            default_picture: false,
             profile_banner: String::new(),
         }
     }
+    /// The status message with its emoji, unless it expired
+    pub fn custom_status(&self) -> Option<CustomStatus> {
+        self.identity
+            .status_message()
+            .map(|message| CustomStatus::from_identity(&message, &self.identity.metadata()))
+            .filter(|status| !status.is_expired(Utc::now()))
+    }
+
+    /// Pronouns, about text and links, from the metadata
+    pub fn extended_profile(&self) -> ExtendedProfile {
+        ExtendedProfile::from_metadata(&self.identity.metadata())
+    }
+
     pub fn identity_status(&self) -> IdentityStatus {
         self.status
     }
@@ -78,8 +103,20 @@ impl Identity {
         self.platform
     }
 
+    /// hashes the picture for the avatar cache, so it is only done when the picture changes
     pub fn set_profile_picture(&mut self, image: &str) {
-        self.profile_image = image.to_string();
+        self.default_picture = image.len() >= 6 && image.ends_with(DEFAULT_PICTURE_MARKER);
+        let shown = match self.default_picture {
+            true => &image[..image.len() - DEFAULT_PICTURE_MARKER.len()],
+            false => image,
+        };
+        self.profile_image = Avatar::new(shown);
+    }
+
+    /// keeps the picture of an older copy of the identity without hashing it again
+    pub fn copy_profile_picture(&mut self, other: &Identity) {
+        self.profile_image = other.profile_image.clone();
+        self.default_picture = other.default_picture;
     }
 
     pub fn set_profile_banner(&mut self, image: &str) {
@@ -87,11 +124,12 @@ impl Identity {
     }
 
     pub fn profile_picture(&self) -> String {
-        let picture = &self.profile_image;
-        match self.contains_default_picture() {
-            true => picture[..picture.len() - 3].to_string(),
-            false => picture.clone(),
-        }
+        self.profile_image.source().to_string()
+    }
+
+    /// the picture for `UserImage`, which is cheap to copy
+    pub fn avatar(&self) -> Avatar {
+        self.profile_image.clone()
     }
 
     pub fn profile_banner(&self) -> String {
@@ -99,19 +137,7 @@ impl Identity {
     }
 
     pub fn contains_default_picture(&self) -> bool {
-        let picture = &self.profile_image;
-
-        if picture.is_empty() || picture.len() < 6 {
-            return false;
-        }
-
-        let bytes = picture.as_bytes();
-        let length = bytes.len();
-
-        bytes
-            .get(length - 3..)
-            .map(|bytes| bytes == [11, 00, 23])
-            .unwrap_or_default()
+        self.default_picture
     }
 }
 
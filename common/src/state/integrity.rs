@@ -0,0 +1,92 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use derive_more::Display;
+use uuid::Uuid;
+
+use super::Chats;
+
+/// A problem found while checking the local data for consistency.
+#[derive(Clone, Debug, Display, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// A cached file preview whose file no longer exists in storage.
+    #[display(fmt = "OrphanedThumbnail {{ path: {path:?} }}")]
+    OrphanedThumbnail { path: PathBuf },
+    /// A file in storage whose contents are missing, usually from an interrupted upload.
+    /// `directory` is relative to the storage root.
+    #[display(fmt = "MissingBlob {{ directory: {directory:?}, name: {name} }}")]
+    MissingBlob { directory: PathBuf, name: String },
+    /// A conversation index (sidebar, favorites, active chat or merged chats)
+    /// referencing a conversation which doesn't exist.
+    #[display(fmt = "DanglingConversation {{ id: {id} }}")]
+    DanglingConversation { id: Uuid },
+    /// A conversation listed more than once in the sidebar.
+    #[display(fmt = "DuplicateSidebarEntry {{ id: {id} }}")]
+    DuplicateSidebarEntry { id: Uuid },
+}
+
+impl IntegrityIssue {
+    /// Issues which are repaired by mutating `State` rather than by the warp runner.
+    pub fn is_conversation_index(&self) -> bool {
+        matches!(
+            self,
+            Self::DanglingConversation { .. } | Self::DuplicateSidebarEntry { .. }
+        )
+    }
+}
+
+/// Checks that the sidebar, favorites, active chat and merged chats only reference known conversations.
+pub fn check_conversation_indexes(chats: &Chats) -> Vec<IntegrityIssue> {
+    let mut issues = vec![];
+    let mut dangling: HashSet<Uuid> = HashSet::new();
+    let mut seen: HashSet<Uuid> = HashSet::new();
+
+    for id in chats.in_sidebar.iter() {
+        if !seen.insert(*id) {
+            issues.push(IntegrityIssue::DuplicateSidebarEntry { id: *id });
+        }
+    }
+
+    let referenced = chats
+        .in_sidebar
+        .iter()
+        .chain(chats.favorites.iter())
+        .chain(chats.active.iter())
+        // merged duplicates are hidden on purpose; only the chat they were merged into must exist
+        .chain(chats.merged.values());
+    for id in referenced {
+        if !chats.all.contains_key(id) && dangling.insert(*id) {
+            issues.push(IntegrityIssue::DanglingConversation { id: *id });
+        }
+    }
+
+    issues
+}
+
+/// Removes the references described by the conversation index issues. Other issues are ignored.
+pub fn repair_conversation_indexes(chats: &mut Chats, issues: &[IntegrityIssue]) {
+    for issue in issues {
+        match issue {
+            IntegrityIssue::DanglingConversation { id } => {
+                chats.in_sidebar.retain(|x| x != id);
+                chats.favorites.retain(|x| x != id);
+                chats.merged.retain(|_, target| target != id);
+                if chats.active == Some(*id) {
+                    chats.active = None;
+                }
+            }
+            IntegrityIssue::DuplicateSidebarEntry { id } => {
+                // keep the first (highest) position
+                let mut found = false;
+                chats.in_sidebar.retain(|x| {
+                    if x != id {
+                        return true;
+                    }
+                    let keep = !found;
+                    found = true;
+                    keep
+                });
+            }
+            _ => {}
+        }
+    }
+}
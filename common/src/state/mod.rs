@@ -1,25 +1,44 @@
 pub mod action;
+pub mod activity;
 pub mod call;
+pub mod call_log;
+pub mod chat_folders;
 pub mod chats;
 pub mod configuration;
+pub mod custom_status;
+pub mod custom_theme;
 pub mod data_transfer;
 pub mod default_keybinds;
+pub mod do_not_disturb;
+pub mod extended_profile;
 pub mod friends;
 pub mod identity;
+pub mod integrity;
+pub mod network;
 pub mod notifications;
+pub mod onboarding;
+pub mod outbox;
 pub mod pending_message;
+pub mod persistence;
+pub mod quick_switcher;
 pub mod route;
 pub mod scope_ids;
 pub mod settings;
 pub mod storage;
+pub mod transfer_history;
 pub mod ui;
 pub mod utils;
 
-use crate::language::{change_language, get_local_text_with_args};
-use crate::notifications::NotificationAction;
+use crate::language::{
+    change_language, collation, get_local_text_with_args, load_community_languages,
+};
+use crate::notifications::{NotificationAction, Responses};
 use crate::warp_runner::WarpCmdTx;
 // export specific structs which the UI expects. these structs used to be in src/state.rs, before state.rs was turned into the `state` folder
-use crate::{language::get_local_text, warp_runner::ui_adapter};
+use crate::{
+    language::get_local_text,
+    warp_runner::{bandwidth, checksum, message_store, read_receipts, transfer_queue, ui_adapter},
+};
 pub use action::Action;
 pub use chats::{Chat, Chats};
 use dioxus_desktop::tao::window::WindowId;
@@ -35,17 +54,26 @@ use warp::raygun::{ConversationType, Location};
 use crate::STATIC_ARGS;
 
 use crate::{
+    control,
+    sounds::Sounds,
     testing::mock::generate_mock,
+    utils::{
+        app_lock,
+        handoff::Position,
+        incoming_webhooks,
+        webhooks::{self, WebhookEvent},
+    },
     warp_runner::{
         ui_adapter::{MessageEvent, MultiPassEvent, RayGunEvent},
-        WarpEvent,
+        GroupUpdate, WarpEvent,
     },
 };
+use chrono::Local;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, VecDeque};
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::HashMap,
     fmt, fs,
     time::{Duration, Instant},
 };
@@ -54,14 +82,20 @@ use warp::{crypto::DID, multipass::identity::IdentityStatus, raygun};
 
 use tracing::log;
 
-use self::call::Call;
+use self::activity::ActivityEvent;
+use self::call::{Call, CallEvent, CallEventKind};
+use self::chats::{ChatAppearance, NotificationRules, CHAT_ZOOM_MAX, CHAT_ZOOM_MIN};
+use self::outbox::QueuedMessage;
 use self::pending_message::{FileLocation, FileProgression, PendingMessage};
+use self::persistence::{Persistence, Snapshot, Store};
 
 use self::storage::Storage;
-use self::ui::{Font, Layout};
+use self::ui::{Font, Layout, SidebarFilter};
 use self::utils::get_available_themes;
 
 pub const MAX_PINNED_MESSAGES: u8 = 100;
+// @everyone and @admins notify at most once in this interval per group
+const ROOM_MENTION_COOLDOWN: Duration = Duration::from_secs(60);
 // todo: create an Identity cache and only store UUID in state.friends and state.chats
 // store the following information in the cache: key: DID, value: { Identity, HashSet<UUID of conversations this identity is participating in> }
 // the HashSet would be used to determine when to evict an identity. (they are not participating in any conversations and are not a friend)
@@ -83,8 +117,54 @@ pub struct State {
     identities: HashMap<DID, identity::Identity>,
     #[serde(skip)]
     pub initialized: bool,
+    // the chats loaded from disk were all replaced by the ones from RayGun
+    #[serde(skip)]
+    pub chats_loaded: bool,
+    // conversation events which arrived while the chats were loaded in batches. they are processed
+    // once all of them are there, otherwise they'd be dropped for chats which aren't loaded yet or
+    // overwritten by the batch of their chat
+    #[serde(skip)]
+    deferred_events: Vec<WarpEvent>,
     #[serde(skip)]
     warp_cmd_tx: Option<WarpCmdTx>,
+    #[serde(skip)]
+    persistence: Persistence,
+    // loaded from a file of its own in `load`
+    #[serde(skip)]
+    pub activity: activity::Activity,
+    // loaded from a file of its own in `load`
+    #[serde(skip)]
+    pub call_log: call_log::CallLog,
+}
+
+// the contents of the settings store, written from `SettingsStoreRef`
+#[derive(Default, Deserialize)]
+struct SettingsStore {
+    #[serde(default)]
+    settings: settings::Settings,
+    #[serde(default)]
+    configuration: configuration::Configuration,
+}
+
+#[derive(Serialize)]
+struct SettingsStoreRef<'a> {
+    settings: &'a settings::Settings,
+    configuration: &'a configuration::Configuration,
+}
+
+// the contents of the UI store, written from `UiStoreRef`
+#[derive(Default, Deserialize)]
+struct UiStore {
+    #[serde(default)]
+    route: route::Route,
+    #[serde(default)]
+    ui: ui::UI,
+}
+
+#[derive(Serialize)]
+struct UiStoreRef<'a> {
+    route: &'a route::Route,
+    ui: &'a ui::UI,
 }
 
 impl fmt::Debug for State {
@@ -113,7 +193,12 @@ impl Clone for State {
             configuration: self.configuration.clone(),
             identities: HashMap::new(),
             initialized: self.initialized,
+            chats_loaded: self.chats_loaded,
+            deferred_events: Vec::new(),
             warp_cmd_tx: None,
+            persistence: Default::default(),
+            activity: self.activity.clone(),
+            call_log: self.call_log.clone(),
         }
     }
 }
@@ -174,12 +259,11 @@ impl State {
                 }
             }
             // ===== Notifications =====
-            Action::AddNotification(kind, count, forced) => self.ui.notifications.increment(
-                &self.configuration,
-                kind,
-                count,
-                forced || !self.ui.metadata.focused,
-            ),
+            Action::AddNotification(kind, count) => {
+                self.ui
+                    .notifications
+                    .increment(&self.configuration, kind, count)
+            }
             Action::RemoveNotification(kind, count) => self.ui.notifications.decrement(kind, count),
             Action::ClearNotification(kind) => self.ui.notifications.clear_kind(kind),
             Action::ClearAllNotifications => self.ui.notifications.clear_all(),
@@ -209,14 +293,137 @@ impl State {
             Action::Favorite(chat) => self.favorite(&chat),
             Action::ToggleFavorite(chat) => self.toggle_favorite(chat),
             Action::UnFavorite(chat_id) => self.unfavorite(chat_id),
+            Action::MoveFavorite(chat_id, target) => self.move_favorite(chat_id, target),
             // Language
             Action::SetLanguage(language) => self.set_language(&language),
+            // Linked devices
+            Action::AddLinkedDevice(device) => self.settings.linked_devices.push(device),
+            Action::RevokeLinkedDevice(id) => self
+                .settings
+                .linked_devices
+                .retain(|device| device.id != id),
+            Action::SetDoNotDisturb(dnd) => self.settings.do_not_disturb = dnd,
+            Action::AddToDictionary(word) => {
+                self.settings.personal_dictionary.insert(word);
+            }
+            Action::RemoveFromDictionary(word) => {
+                self.settings.personal_dictionary.remove(&word);
+            }
             // Overlay
             Action::AddOverlay(window) => self.ui.overlays.push(window),
             Action::SetOverlay(enabled) => self.toggle_overlay(enabled),
             // Sidebar
             Action::RemoveFromSidebar(chat_id) => self.remove_sidebar_chat(chat_id),
+            Action::CreateChatFolder(name) => {
+                self.ui.chat_folders.create(&name);
+            }
+            Action::RenameChatFolder(id, name) => self.ui.chat_folders.rename(id, &name),
+            Action::DeleteChatFolder(id) => self.ui.chat_folders.delete(id),
+            Action::MoveChatToFolder(chat_id, folder_id) => {
+                self.ui.chat_folders.move_chat(chat_id, folder_id)
+            }
+            Action::ToggleChatFolder(id) => self.ui.chat_folders.toggle_collapsed(id),
+            Action::MergeChats(chat_id, duplicates) => self.merge_chats(chat_id, duplicates),
+            Action::RepairConversationIndexes(issues) => {
+                integrity::repair_conversation_indexes(&mut self.chats, &issues)
+            }
+            Action::SetGroupRole(chat_id, did, role) => self
+                .chats
+                .moderate(chat_id, |moderation| moderation.set_role(did, role)),
+            Action::SetMemberMuted(chat_id, did, muted) => self
+                .chats
+                .moderate(chat_id, |moderation| moderation.set_muted(did, muted)),
+            Action::SetChatTitle(chat_id, title) => {
+                let title = title
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty());
+                match title {
+                    Some(title) => {
+                        self.chats.titles.insert(chat_id, title);
+                    }
+                    None => {
+                        self.chats.titles.remove(&chat_id);
+                    }
+                }
+            }
+            Action::SetChatTopic(chat_id, topic) => {
+                let topic = topic
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty());
+                match topic {
+                    Some(topic) => {
+                        self.chats.topics.insert(chat_id, topic);
+                    }
+                    None => {
+                        self.chats.topics.remove(&chat_id);
+                    }
+                }
+            }
+            Action::SetChatLanguage(chat_id, language) => match language {
+                Some(language) => {
+                    self.chats.languages.insert(chat_id, language);
+                }
+                None => {
+                    self.chats.languages.remove(&chat_id);
+                }
+            },
+            Action::ToggleConversationPanel(chat_id) => {
+                let panel = self.chats.panels.entry(chat_id).or_default();
+                panel.open = !panel.open;
+            }
+            Action::ToggleConversationWidget(chat_id, widget) => {
+                self.chats
+                    .panels
+                    .entry(chat_id)
+                    .or_default()
+                    .toggle_widget(&widget);
+            }
+            Action::SetNotificationRules(chat_id, rules) => {
+                let chat_id = self.chats.resolve_merged(chat_id);
+                if rules == NotificationRules::default() {
+                    self.chats.notification_rules.remove(&chat_id);
+                } else {
+                    self.chats.notification_rules.insert(chat_id, rules);
+                }
+            }
+            Action::SetChatAppearance(chat_id, appearance) => {
+                if appearance == ChatAppearance::default() {
+                    self.chats.appearances.remove(&chat_id);
+                } else {
+                    self.chats.appearances.insert(chat_id, appearance);
+                }
+            }
+            Action::SetChatZoom(chat_id, zoom) => {
+                // rounded so that stepping in and out ends up at 1.0 again
+                let zoom = (zoom.clamp(CHAT_ZOOM_MIN, CHAT_ZOOM_MAX) * 100.).round() / 100.;
+                if zoom == 1.0 {
+                    self.chats.zooms.remove(&chat_id);
+                } else {
+                    self.chats.zooms.insert(chat_id, zoom);
+                }
+            }
+            Action::SetChatWebhooks(chat_id, webhooks) => {
+                if webhooks.is_empty() {
+                    self.chats.webhooks.remove(&chat_id);
+                } else {
+                    self.chats.webhooks.insert(chat_id, webhooks);
+                }
+                incoming_webhooks::configure(&self.chats.webhooks);
+                webhooks::configure_outgoing(&self.chats.webhooks, &self.chats.merged);
+            }
+            Action::SetFriendSound(did, sound) => match sound {
+                Some(sound) => {
+                    self.friends.sounds.insert(did, sound);
+                }
+                None => {
+                    self.friends.sounds.remove(&did);
+                }
+            },
             Action::SidebarHidden(hidden) => self.ui.sidebar_hidden = hidden,
+            Action::SetLocked(locked) => {
+                self.ui.locked = locked;
+                app_lock::set_locked(locked);
+            }
             // Navigation
             Action::Navigate(to) => self.set_active_route(to),
             // Generic UI
@@ -236,6 +443,13 @@ impl State {
             Action::SetEmojiPickerVisible(visible) => self.ui.emoji_picker_visible = visible,
             Action::SetTransformMarkdownText(flag) => self.ui.transform_markdown_text(flag),
             Action::SetTransformAsciiEmojis(flag) => self.ui.transform_ascii_emojis(flag),
+            Action::SetEmojiStyle(style) => self.ui.emoji_style = style,
+            Action::SetSidebarFilter(filter) => self.ui.sidebar_filter = filter,
+            Action::SetSplitChat(chat_id) => {
+                // the active conversation is already shown
+                self.ui.split_chat = chat_id.filter(|id| self.chats.active != Some(*id));
+            }
+            Action::SetOnboardingStep(step) => self.ui.onboarding = step,
             // ===== Settings =====
             Action::PauseGlobalKeybinds(b) => self.settings.pause_global_keybinds = b,
             Action::ResetKeybinds => {
@@ -245,13 +459,19 @@ impl State {
             Action::SetTheme(theme) => self.set_theme(theme),
             // Fonts
             Action::SetFont(font) => self.set_font(font),
+            Action::SetMessageFont(font) => self.ui.message_font = font,
+            Action::SetMonospaceFont(font) => self.ui.monospace_font = font,
             Action::SetFontScale(font_scale) => self.settings.set_font_scale(font_scale),
+            Action::SetMonitorScale(scale) => self.ui.set_monitor_scale(scale),
 
             // ===== Chats =====
             Action::ChatWith(chat, should_move_to_top) => {
                 // warning: ensure that warp is used to get/create the chat which is passed in here
                 //todo: check if (for the side which created the conversation) a warp event comes in and consider using that instead
                 self.set_active_chat(chat, should_move_to_top);
+                if self.ui.split_chat == Some(*chat) {
+                    self.ui.split_chat = None;
+                }
             }
             Action::ClearActiveChat => {
                 self.clear_active_chat();
@@ -264,7 +484,12 @@ impl State {
                     self.clear_unreads(id);
                 }
             }
-            Action::ClearAllUnreads => self.chats.all.values_mut().for_each(|c| c.clear_unreads()),
+            Action::ClearAllUnreads => {
+                let ids: Vec<Uuid> = self.chats.all.keys().copied().collect();
+                for id in ids {
+                    self.clear_unreads(id);
+                }
+            }
             Action::SetChatDraft(chat_id, value) => self.set_chat_draft(&chat_id, value),
             Action::ClearChatDraft(chat_id) => self.clear_chat_draft(&chat_id),
             Action::SetChatAttachments(chat_id, value) => {
@@ -289,6 +514,27 @@ impl State {
                 self.set_chat_attachments(&chat_id, current_files)
             }
             Action::ClearChatAttachments(chat_id) => self.clear_chat_attachments(&chat_id),
+            Action::QueueMessage(chat_id, msg, attachments, replying_to) => {
+                let id = self
+                    .chats
+                    .outbox
+                    .push(chat_id, msg.clone(), attachments, replying_to);
+                self.increment_outgoing_messages_for(chat_id, id, msg);
+            }
+            Action::CancelQueuedMessage(id) => {
+                if let Some(queued) = self.chats.outbox.remove(&id) {
+                    self.decrement_outgoing_messages(queued.conv_id, id);
+                }
+            }
+            Action::RetryQueuedMessage(id) => self.chats.outbox.try_again(&id),
+            Action::EditQueuedMessage(id, msg) => {
+                if let Some(conv_id) = self.chats.outbox.get(&id).map(|m| m.conv_id) {
+                    self.chats.outbox.set_text(&id, msg.clone());
+                    if let Some(chat) = self.chats.all.get_mut(&conv_id) {
+                        chat.set_pending_msg_text(id, msg);
+                    }
+                }
+            }
             Action::AddReaction(_, _, emoji) => {
                 self.ui.emojis.increment_emoji(emoji);
             }
@@ -319,8 +565,17 @@ impl State {
                     log::error!("failed to answer call: {e}");
                 }
             },
-            Action::RejectCall(id) => self.ui.call_info.reject_call(id),
+            Action::RejectCall(id) => {
+                self.unanswered_call(id, true);
+                self.ui.call_info.reject_call(id);
+            }
             Action::OfferCall(call) => {
+                let own_id = self.did_key();
+                self.add_call_event(
+                    call.conversation_id,
+                    call.id,
+                    CallEventKind::Started(own_id),
+                );
                 let _ = self.ui.call_info.pending_call(
                     call.id,
                     call.conversation_id,
@@ -331,10 +586,21 @@ impl State {
                 self.set_active_media(call.conversation_id);
             }
             Action::EndCall => {
+                self.record_call_end();
                 self.chats.active_media = None;
                 self.ui.popout_media_player = false;
                 self.ui.call_info.end_call();
             }
+            Action::SetCaptionsEnabled(enabled) => self.ui.call_info.set_captions_enabled(enabled),
+            Action::SetCameraEnabled(enabled) => self.ui.call_info.set_camera_enabled(enabled),
+            Action::AddCaption(text) => {
+                let own_id = self.did_key();
+                self.ui.call_info.add_caption(text, own_id);
+            }
+            Action::SetTranscriptConsent(consent) => {
+                let own_id = self.did_key();
+                self.ui.call_info.set_transcript_consent(own_id, consent);
+            }
             // ===== Configuration =====
             Action::Config(action) => self.configuration.mutate(action),
         }
@@ -364,6 +630,29 @@ impl State {
             log::trace!("process_warp_event: {event}");
         }
 
+        // only peers send these, so the network works
+        if matches!(
+            event,
+            WarpEvent::Message(MessageEvent::Received { .. })
+                | WarpEvent::Message(MessageEvent::TypingIndicator { .. })
+                | WarpEvent::MultiPass(MultiPassEvent::FriendOnline(_))
+                | WarpEvent::MultiPass(MultiPassEvent::FriendRequestReceived(_))
+        ) {
+            self.ui.network.peer_seen();
+        }
+
+        // typing indicators are out of date by then anyway
+        if !self.chats_loaded
+            && matches!(event, WarpEvent::RayGun(_) | WarpEvent::Message(_))
+            && !matches!(
+                event,
+                WarpEvent::Message(MessageEvent::TypingIndicator { .. })
+            )
+        {
+            self.deferred_events.push(event);
+            return;
+        }
+
         match event {
             WarpEvent::MultiPass(evt) => self.process_multipass_event(evt),
             WarpEvent::RayGun(evt) => self.process_raygun_event(evt),
@@ -379,27 +668,34 @@ impl State {
             MultiPassEvent::None => {}
             MultiPassEvent::FriendRequestReceived(identity) => {
                 self.new_incoming_request(&identity);
+                webhooks::emit(WebhookEvent::FriendRequest {
+                    did: identity.did_key().to_string(),
+                    username: identity.username(),
+                });
 
                 self.mutate(Action::AddNotification(
                     notifications::NotificationKind::FriendRequest,
                     1,
-                    false,
                 ));
 
                 // TODO: Get state available in this scope.
                 // Dispatch notifications only when we're not already focused on the application.
                 let notifications_enabled = self.configuration.notifications.friends_notifications;
 
-                if !self.ui.metadata.focused && notifications_enabled {
-                    crate::notifications::push_notification(
+                if !self.ui.metadata.focused
+                    && notifications_enabled
+                    && !self.settings.do_not_disturb.is_active()
+                {
+                    crate::notifications::push_notification_with_responses(
                         get_local_text("friends.new-request"),
                         get_local_text_with_args(
                             "friends.new-request-name",
                             vec![("name", identity.username())],
                         ),
-                        Some(crate::sounds::Sounds::Notification),
+                        (!self.is_busy()).then_some(crate::sounds::Sounds::Notification),
                         notify_rust::Timeout::Milliseconds(4),
                         NotificationAction::FriendListPending,
+                        Responses::FriendRequest(identity.did_key()),
                     );
                 }
             }
@@ -407,6 +703,10 @@ impl State {
                 self.new_outgoing_request(&identity);
             }
             MultiPassEvent::FriendAdded(identity) => {
+                if self.friends.outgoing_requests.contains(&identity.did_key()) {
+                    self.activity
+                        .record(ActivityEvent::friend_accepted(identity.did_key()));
+                }
                 self.complete_request(&identity);
             }
             MultiPassEvent::FriendRemoved(identity) => {
@@ -453,6 +753,19 @@ impl State {
             RayGunEvent::ConversationDeleted(id) => {
                 self.chats.in_sidebar.retain(|x| *x != id);
                 self.chats.all.remove(&id);
+                self.chats.outbox.remove_chat(&id);
+                self.chats.read_receipts.remove(&id);
+                self.chats.titles.remove(&id);
+                self.chats.topics.remove(&id);
+                // bots can't post into it anymore
+                if self.chats.webhooks.remove(&id).is_some() {
+                    incoming_webhooks::configure(&self.chats.webhooks);
+                    webhooks::configure_outgoing(&self.chats.webhooks, &self.chats.merged);
+                }
+                self.ui.chat_folders.remove_chat(&id);
+                if self.ui.split_chat == Some(id) {
+                    self.ui.split_chat = None;
+                }
                 if self.chats.active == Some(id) {
                     self.chats.active = None;
                 }
@@ -466,10 +779,56 @@ impl State {
                 conversation_id,
                 mut message,
             } => {
+                let conversation_id = self.chats.resolve_merged(conversation_id);
                 let own = self.get_own_identity().did_key();
-                let ping = message.is_mention_self(&own);
-                self.update_identity_status_hack(&message.inner.sender());
-                let id = self.identities.get(&message.inner.sender()).cloned();
+                let moderation = self.chats.moderation(&conversation_id);
+                // room-wide mentions notify at most once per cooldown, in case someone floods the group with them
+                let room_mention = self.chats.all.get_mut(&conversation_id).and_then(|chat| {
+                    let mention = chat.group_mention_for(&message.inner, &own, &moderation)?;
+                    if chat
+                        .last_room_mention
+                        .map(|last| last.elapsed() < ROOM_MENTION_COOLDOWN)
+                        .unwrap_or(false)
+                    {
+                        return None;
+                    }
+                    chat.last_room_mention = Some(Instant::now());
+                    Some(mention)
+                });
+                let ping = message.is_mention_self(&own) || room_mention.is_some();
+                let message_sender = message.inner.sender();
+                self.update_identity_status_hack(&message_sender);
+                let id = self.identities.get(&message_sender).cloned();
+                let webhook_event = WebhookEvent::new_message(
+                    conversation_id,
+                    &message.inner,
+                    id.as_ref().map(|id| id.username()).unwrap_or_default(),
+                );
+                let text = message.inner.lines().join("\n");
+                let username = id.as_ref().map(|id| id.username()).unwrap_or_default();
+                // what screen readers read out. only attachments have nothing to read
+                let announcement = if text.trim().is_empty() {
+                    get_local_text_with_args("messages.user-sent-message", vec![("user", username)])
+                } else {
+                    get_local_text_with_args(
+                        "accessibility.new-message",
+                        vec![("user", username), ("text", text)],
+                    )
+                };
+                // muted chats are caught up on from the activity feed
+                if message_sender != own
+                    && self.chats.notification_rules(&conversation_id).is_muted()
+                {
+                    self.activity.record(ActivityEvent::message(
+                        message_sender.clone(),
+                        conversation_id,
+                        Position {
+                            message_id: message.inner.id(),
+                            date: message.inner.date(),
+                        },
+                        &message.inner.lines().join("\n"),
+                    ));
+                }
                 // todo: don't load all the messages by default. if the user scrolled up, for example, this incoming message may not need to be fetched yet.
                 self.add_msg_to_chat(conversation_id, message);
 
@@ -477,10 +836,19 @@ impl State {
                 self.send_chat_to_top_of_sidebar(conversation_id);
                 //}
 
+                // the endpoint gets every message, whatever the notification rules of the chat
+                webhooks::emit(webhook_event);
+
+                // muted conversations and conversations set to mentions only don't count towards the badge either
+                let rules = self.chats.notification_rules(&conversation_id);
+                if !rules.should_notify(ping) {
+                    return;
+                }
+                self.ui.announcements.polite = announcement;
+
                 self.mutate(Action::AddNotification(
                     notifications::NotificationKind::Message,
                     1,
-                    ping,
                 ));
 
                 // Dispatch notifications only when we're not already focused on the application.
@@ -489,29 +857,47 @@ impl State {
                 let notifications_enabled = self.configuration.notifications.enabled;
                 let should_play_sound = self.ui.current_layout != Layout::Compose
                     && self.configuration.audiovideo.message_sounds;
-                let should_dispatch_notification =
-                    should_play_sound && message_notifications_enabled && notifications_enabled;
+                // do not disturb keeps the badges counting, but stays silent
+                let should_dispatch_notification = should_play_sound
+                    && message_notifications_enabled
+                    && notifications_enabled
+                    && !self.settings.do_not_disturb.is_active();
 
                 // This should be called if we have notifications enabled for new messages
                 if should_dispatch_notification {
-                    let sound = if self.configuration.audiovideo.message_sounds {
-                        Some(crate::sounds::Sounds::Notification)
+                    let sound = if self.configuration.audiovideo.message_sounds && !self.is_busy() {
+                        Some(self.notification_sound(&conversation_id, &message_sender))
                     } else {
                         None
                     };
-                    let text = match id {
+                    let text = match &id {
                         Some(id) => get_local_text_with_args(
                             "messages.user-sent-message",
                             vec![("user", id.username())],
                         ),
                         None => get_local_text("messages.unknown-sent-message"),
                     };
-                    crate::notifications::push_notification(
-                        get_local_text("messages.new"),
+                    // room-wide mentions get their own title so they stand out from regular messages
+                    let (title, text) = match room_mention {
+                        Some(mention) => (
+                            get_local_text("messages.new-room-mention"),
+                            get_local_text_with_args(
+                                "messages.user-mentioned-room",
+                                vec![
+                                    ("user", id.map(|id| id.username()).unwrap_or_default()),
+                                    ("mention", mention.to_string()),
+                                ],
+                            ),
+                        ),
+                        None => (get_local_text("messages.new"), text),
+                    };
+                    crate::notifications::push_notification_with_responses(
+                        title,
                         text,
                         sound,
                         notify_rust::Timeout::Milliseconds(4),
                         NotificationAction::DisplayChat(conversation_id),
+                        Responses::Reply(conversation_id),
                     );
                 }
             }
@@ -519,6 +905,7 @@ impl State {
                 conversation_id,
                 message,
             } => {
+                let conversation_id = self.chats.resolve_merged(conversation_id);
                 // todo: don't load all the messages by default. if the user scrolled up, for example, this incoming message may not need to be fetched yet.
                 let message_clone = message.clone();
                 if let Some(chat) = self.chats.all.get_mut(&conversation_id) {
@@ -531,6 +918,7 @@ impl State {
                 conversation_id,
                 mut message,
             } => {
+                let conversation_id = self.chats.resolve_merged(conversation_id);
                 self.update_identity_status_hack(&message.inner.sender());
                 let own = self.get_own_identity().did_key();
                 if let Some(chat) = self.chats.all.get_mut(&conversation_id) {
@@ -563,6 +951,7 @@ impl State {
                 message_id,
                 most_recent_message,
             } => {
+                let conversation_id = self.chats.resolve_merged(conversation_id);
                 // can't have 2 mutable borrows
                 let mut should_decrement_notifications = false;
                 if let Some(chat) = self.chats.all.get_mut(&conversation_id) {
@@ -602,7 +991,24 @@ impl State {
             MessageEvent::MessageUnpinned { message } => {
                 self.unpin_message(message);
             }
-            MessageEvent::MessageReactionAdded { message } => {
+            MessageEvent::MessageReactionAdded {
+                message,
+                reactor,
+                emoji,
+            } => {
+                // also for messages which aren't loaded
+                let own = self.did_key();
+                if message.sender() == own && reactor != own {
+                    self.activity.record(ActivityEvent::reaction(
+                        reactor,
+                        self.chats.resolve_merged(message.conversation_id()),
+                        Position {
+                            message_id: message.id(),
+                            date: message.date(),
+                        },
+                        emoji,
+                    ));
+                }
                 self.update_reactions(message);
             }
             MessageEvent::MessageReactionRemoved { message } => {
@@ -612,6 +1018,7 @@ impl State {
                 conversation_id,
                 participant,
             } => {
+                let conversation_id = self.chats.resolve_merged(conversation_id);
                 self.update_identity_status_hack(&participant);
                 if !self.chats.in_sidebar.contains(&conversation_id) {
                     return;
@@ -662,6 +1069,24 @@ impl State {
                     chat.settings = settings;
                 }
             }
+            MessageEvent::GroupUpdated {
+                conversation_id,
+                update,
+            } => self
+                .chats
+                .moderate(conversation_id, |moderation| match update {
+                    GroupUpdate::SetRole(did, role) => moderation.set_role(did, role),
+                    GroupUpdate::SetMuted(did, muted) => moderation.set_muted(did, muted),
+                }),
+            MessageEvent::ReadReceipt {
+                conversation_id,
+                reader,
+                up_to,
+            } => {
+                let conversation_id = self.chats.resolve_merged(conversation_id);
+                self.chats
+                    .record_read_receipt(conversation_id, reader, up_to);
+            }
             MessageEvent::AttachmentProgress { .. } => todo!(),
         }
     }
@@ -671,7 +1096,7 @@ impl State {
             BlinkEventKind::IncomingCall {
                 call_id,
                 conversation_id,
-                sender: _,
+                sender,
                 participants,
             } => {
                 let conversation_id = match conversation_id {
@@ -688,20 +1113,41 @@ impl State {
                         .pending_call(call_id, conversation_id, participants)
                 {
                     log::error!("failed to process IncomingCall event: {e}");
+                    return;
                 }
+                self.add_call_event(
+                    conversation_id,
+                    call_id,
+                    CallEventKind::Started(sender.clone()),
+                );
+                self.ui.announcements.assertive = get_local_text_with_args(
+                    "accessibility.incoming-call",
+                    vec![("user", self.username_of(&sender))],
+                );
             }
             BlinkEventKind::CallCancelled { call_id } => {
+                self.unanswered_call(call_id, false);
                 self.ui.call_info.remove_pending_call(call_id);
+                self.ui.announcements.polite = get_local_text("accessibility.call-cancelled");
             }
             BlinkEventKind::ParticipantJoined { call_id, peer_id } => {
+                let user = self.username_of(&peer_id);
                 if let Err(e) = self.ui.call_info.participant_joined(call_id, peer_id) {
                     log::error!("failed to process ParticipantJoined event : {e}");
+                    return;
                 }
+                self.ui.announcements.polite =
+                    get_local_text_with_args("accessibility.joined-call", vec![("user", user)]);
             }
             BlinkEventKind::ParticipantLeft { call_id, peer_id } => {
                 if let Err(e) = self.ui.call_info.participant_left(call_id, &peer_id) {
                     log::error!("failed to process ParticipantLeft event : {e}");
+                    return;
                 }
+                self.ui.announcements.polite = get_local_text_with_args(
+                    "accessibility.left-call",
+                    vec![("user", self.username_of(&peer_id))],
+                );
             }
             BlinkEventKind::CallTerminated { call_id } => {
                 if self
@@ -711,7 +1157,9 @@ impl State {
                     .map(|x| x.call.id == call_id)
                     .unwrap_or(false)
                 {
+                    self.record_call_end();
                     self.ui.call_info.end_call();
+                    self.ui.announcements.polite = get_local_text("accessibility.call-ended");
                 }
             }
             BlinkEventKind::ParticipantSpeaking { peer_id } => {
@@ -720,7 +1168,9 @@ impl State {
                 }
             }
             BlinkEventKind::SelfSpeaking => {
-                // todo
+                if let Err(e) = self.ui.call_info.self_speaking() {
+                    log::error!("failed to process SelfSpeaking event : {e}");
+                }
             }
             BlinkEventKind::AudioDegradation { peer_id } => {
                 // todo
@@ -762,51 +1212,100 @@ impl State {
             friends,
             identities,
             initialized: true,
+            chats_loaded: true,
             ..Default::default()
         }
     }
-    /// Saves the current state to disk.
+    /// Marks the state as changed. The stores which changed are written a moment later, in the background
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let serialized = serde_json::to_string_pretty(self)?;
-        let path = if STATIC_ARGS.use_mock {
-            &STATIC_ARGS.mock_cache_path
-        } else {
-            &STATIC_ARGS.cache_path
-        };
-        fs::write(path, serialized)?;
+        self.persistence.mark_changed();
+        Ok(())
+    }
+
+    /// Writes the stores which changed if no other change came in for a moment
+    pub fn save_if_due(&self) {
+        if self.persistence.is_due() {
+            self.write_changed_stores();
+        }
+    }
+
+    /// Writes the stores which changed and waits until they are on disk
+    pub fn save_now(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_changed_stores();
+        persistence::wait_for_writes()?;
         Ok(())
     }
 
+    fn write_changed_stores(&self) {
+        log::trace!("saving state stores");
+        self.persistence
+            .write_changed(&Self::stores_path(), |store| self.snapshot_store(store));
+    }
+
+    // the stores are copied here and serialized by the writer. the UI store can't be copied and a
+    // copy of the chats would copy every loaded conversation, which isn't saved. both are small
+    // enough to be serialized right away
+    fn snapshot_store(&self, store: Store) -> Snapshot {
+        match store {
+            Store::Chats => {
+                let chats = serde_json::to_vec(&self.chats);
+                Box::new(move || chats)
+            }
+            Store::Friends => {
+                let friends = self.friends.clone();
+                Box::new(move || serde_json::to_vec(&friends))
+            }
+            Store::Settings => {
+                let settings = self.settings.clone();
+                let configuration = self.configuration.clone();
+                Box::new(move || {
+                    serde_json::to_vec(&SettingsStoreRef {
+                        settings: &settings,
+                        configuration: &configuration,
+                    })
+                })
+            }
+            Store::Ui => {
+                let ui = serde_json::to_vec(&UiStoreRef {
+                    route: &self.route,
+                    ui: &self.ui,
+                });
+                Box::new(move || ui)
+            }
+            Store::Storage => {
+                let storage = self.storage.clone();
+                Box::new(move || serde_json::to_vec(&storage))
+            }
+        }
+    }
+
+    fn stores_path() -> std::path::PathBuf {
+        if STATIC_ARGS.use_mock {
+            STATIC_ARGS.uplink_path().join("mock-state")
+        } else {
+            STATIC_ARGS.state_path()
+        }
+    }
+
     pub fn get_json(&self) -> String {
         serde_json::to_string_pretty(self).unwrap_or_default()
     }
 
-    /// Loads the state from a file on disk, if it exists.
+    /// Loads the state from the stores on disk, or from state.json if it wasn't split into stores yet.
     pub fn load() -> Self {
         if STATIC_ARGS.use_mock {
             return State::load_mock();
         };
 
-        let mut success = true;
-
-        let mut state = {
-            match fs::read_to_string(&STATIC_ARGS.cache_path) {
-                Ok(contents) => match serde_json::from_str(&contents) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        log::error!(
-                            "state.json failed to deserialize: {e}. Initializing State with default values"
-                        );
-                        success = false;
-                        State::default()
-                    }
-                },
-                Err(_) => {
-                    log::info!("state.json not found. Initializing State with default values");
-                    success = false;
-                    State::default()
-                }
+        let dir = STATIC_ARGS.state_path();
+        let (mut state, success) = if dir.exists() {
+            State::load_stores(&dir)
+        } else {
+            let (state, success) = State::load_legacy();
+            if success {
+                state.migrate_legacy();
             }
+            (state, success)
         };
         // not sure how these defaulted to true, but this should serve as additional
         // protection in the future
@@ -851,14 +1350,99 @@ impl State {
         if let Some(t) = theme {
             state.set_theme(Some(t.clone()));
         }
+        load_community_languages();
         let user_lang_saved = state.settings.language.clone();
         change_language(user_lang_saved);
+        webhooks::configure(state.configuration.webhooks.clone());
+        incoming_webhooks::configure(&state.chats.webhooks);
+        webhooks::configure_outgoing(&state.chats.webhooks, &state.chats.merged);
+        control::configure(&state.configuration.automation);
+        bandwidth::configure(&state.configuration.transfers);
+        transfer_queue::configure(&state.configuration.transfers);
+        checksum::configure(&state.configuration.transfers);
+        state.activity = activity::Activity::load();
+        state.call_log = call_log::CallLog::load();
         state
     }
+    fn load_stores(dir: &std::path::Path) -> (Self, bool) {
+        let mut state = State::default();
+        let mut success = true;
+        let mut contents = HashMap::new();
+        for store in Store::ALL {
+            match persistence::read_store(dir, store) {
+                Ok(Some(data)) => {
+                    contents.insert(store, data);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("failed to read {store:?} store: {e}");
+                    success = false;
+                }
+            }
+        }
+        // the sidebars are rebuilt like for a new profile
+        if !contents.contains_key(&Store::Chats) {
+            success = false;
+        }
+        let get = |store| contents.get(&store).map(Vec::as_slice);
+
+        state.chats = persistence::parse_store(Store::Chats, get(Store::Chats), &mut success);
+        state.friends = persistence::parse_store(Store::Friends, get(Store::Friends), &mut success);
+        let settings: SettingsStore =
+            persistence::parse_store(Store::Settings, get(Store::Settings), &mut success);
+        state.settings = settings.settings;
+        state.configuration = settings.configuration;
+        let ui: UiStore = persistence::parse_store(Store::Ui, get(Store::Ui), &mut success);
+        state.route = ui.route;
+        state.ui = ui.ui;
+        state.storage = persistence::parse_store(Store::Storage, get(Store::Storage), &mut success);
+
+        // nothing has to be written until something changes
+        for (store, data) in &contents {
+            state.persistence.set_written(*store, data);
+        }
+        (state, success)
+    }
+
+    // state.json holds the whole state, from before it was split into stores
+    fn load_legacy() -> (Self, bool) {
+        match fs::read_to_string(STATIC_ARGS.cache_path()) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(s) => (s, true),
+                Err(e) => {
+                    log::error!(
+                        "state.json failed to deserialize: {e}. Initializing State with default values"
+                    );
+                    (State::default(), false)
+                }
+            },
+            Err(_) => {
+                log::info!("state.json not found. Initializing State with default values");
+                (State::default(), false)
+            }
+        }
+    }
+
+    // writes the stores, and keeps state.json next to them in case something goes wrong
+    fn migrate_legacy(&self) {
+        if let Err(e) = self.save_now() {
+            log::error!("failed to migrate state.json: {e}");
+            return;
+        }
+        let legacy = STATIC_ARGS.cache_path();
+        if let Err(e) = fs::rename(&legacy, legacy.with_extension("json.migrated")) {
+            log::warn!("failed to rename state.json after migrating it: {e}");
+        }
+        log::info!(
+            "migrated state.json to {}",
+            STATIC_ARGS.state_path().display()
+        );
+    }
+
     fn load_mock() -> Self {
         generate_mock()
         // the following doesn't work anymore now that Identities are centralized
-        // let contents = match fs::read_to_string(&STATIC_ARGS.mock_cache_path) {
+        // let contents = match fs::read_to_string(&STATIC_ARGS.mock_cache_path()) {
         //     Ok(r) => r,
         //     Err(_) => {
         //         return generate_mock();
@@ -867,14 +1451,25 @@ impl State {
         // serde_json::from_str(&contents).unwrap_or_else(|_| generate_mock())
     }
 
-    pub fn init_warp(
-        &mut self,
-        friends: Friends,
-        chats: HashMap<Uuid, Chat>,
-        mut identities: HashMap<DID, Identity>,
-    ) {
+    /// Sets the friends and identities from RayGun. The chats loaded from disk are shown until
+    /// `load_chats` replaces them
+    pub fn init_warp(&mut self, friends: Friends, mut identities: HashMap<DID, Identity>) {
         self.friends = friends;
+        self.identities.extend(identities.drain());
+        // conversations which were merged into another chat stay in RayGun but aren't shown
+        for duplicate in self.chats.merged.keys() {
+            self.chats.all.remove(duplicate);
+            self.chats.in_sidebar.retain(|id| id != duplicate);
+        }
+        self.initialized = true;
+    }
+
+    /// Adds a batch of chats from RayGun, keeping the local data of the ones loaded from disk
+    pub fn load_chats(&mut self, chats: HashMap<Uuid, Chat>) {
         for (id, chat) in chats {
+            if self.chats.merged.contains_key(&id) {
+                continue;
+            }
             if let Some(conv) = self.chats.all.get_mut(&id) {
                 conv.messages = chat.messages;
                 conv.conversation_type = chat.conversation_type;
@@ -882,20 +1477,34 @@ impl State {
                 conv.conversation_name = chat.conversation_name;
                 conv.creator = chat.creator;
                 conv.pinned_messages = chat.pinned_messages;
+                conv.extend_unreads(&chat);
             } else {
                 self.chats.all.insert(id, chat);
             }
+            // the messages left in the outbox when Uplink last exited
+            let queued: Vec<_> = self
+                .chats
+                .outbox
+                .in_chat(&id)
+                .map(|m| (m.id, m.msg.clone()))
+                .collect();
+            for (message_id, msg) in queued {
+                self.increment_outgoing_messages_for(id, message_id, msg);
+            }
         }
-        self.identities.extend(identities.drain());
+    }
 
+    pub fn finish_loading_chats(&mut self) {
         if self.chats.readd_sidebars {
             self.chats.readd_sidebars = false;
             self.chats
                 .in_sidebar
                 .append(&mut self.chats.all.keys().cloned().collect())
         }
-
-        self.initialized = true;
+        self.chats_loaded = true;
+        for event in std::mem::take(&mut self.deferred_events) {
+            self.process_warp_event(event);
+        }
     }
 }
 
@@ -939,6 +1548,41 @@ impl State {
             .cloned()
             .collect()
     }
+    /// Whether the chat is shown with the filter of the sidebar. The active chat always is, so it
+    /// doesn't go away once its messages are read
+    pub fn sidebar_filter_matches(&self, chat: &Chat) -> bool {
+        if self.chats.active == Some(chat.id) {
+            return true;
+        }
+        match self.ui.sidebar_filter {
+            SidebarFilter::All => true,
+            SidebarFilter::Unread => chat.unreads() > 0,
+            SidebarFilter::Groups => chat.conversation_type == ConversationType::Group,
+            SidebarFilter::Direct => chat.conversation_type == ConversationType::Direct,
+            SidebarFilter::Favorites => self.is_favorite(chat),
+        }
+    }
+    /// The unread messages of the chats the sidebar filter hides. Muted chats aren't counted
+    pub fn filtered_unread_count(&self) -> u32 {
+        self.chats
+            .in_sidebar
+            .iter()
+            .filter(|id| !self.chats.notification_rules(id).is_muted())
+            .filter_map(|id| self.chats.all.get(id))
+            .filter(|chat| !self.sidebar_filter_matches(chat))
+            .map(|chat| chat.unreads())
+            .sum()
+    }
+    /// The unread messages in the sidebar, shown on the app icon. Muted chats aren't counted
+    pub fn unread_count(&self) -> u32 {
+        self.chats
+            .in_sidebar
+            .iter()
+            .filter(|id| !self.chats.notification_rules(id).is_muted())
+            .filter_map(|id| self.chats.all.get(id))
+            .map(|chat| chat.unreads())
+            .sum()
+    }
     pub fn chat_participants(&self, chat: &Chat) -> Vec<Identity> {
         chat.participants
             .iter()
@@ -959,6 +1603,7 @@ impl State {
     fn add_msg_to_chat(&mut self, conversation_id: Uuid, message: ui_adapter::Message) {
         let msg_id = message.inner.id();
         let is_active_scrolled = self.chats.active_chat_is_scrolled();
+        let in_split = self.shown_split_chat() == Some(conversation_id);
         if let Some(chat) = self.chats.all.get_mut(&conversation_id) {
             chat.typing_indicator.remove(&message.inner.sender());
             chat.messages.push_back(message.clone());
@@ -968,15 +1613,26 @@ impl State {
                 chat.messages.pop_front();
             }
 
-            if self.ui.current_layout != ui::Layout::Compose
-                || self.chats.active != Some(conversation_id)
-                || is_active_scrolled
+            if !in_split
+                && (self.ui.current_layout != ui::Layout::Compose
+                    || self.chats.active != Some(conversation_id)
+                    || is_active_scrolled)
             {
                 chat.add_unread(msg_id);
             }
         }
     }
 
+    /// the conversation next to the active one, if it's on screen. it's hidden in the minimal view
+    /// and while no conversation is active, and its unreads are cleared when it's shown again
+    pub fn shown_split_chat(&self) -> Option<Uuid> {
+        self.ui.split_chat.filter(|_| {
+            self.ui.current_layout == ui::Layout::Compose
+                && self.chats.active.is_some()
+                && !self.ui.is_minimal_view()
+        })
+    }
+
     pub fn active_chat_has_draft(&self) -> bool {
         self.get_active_chat()
             .as_ref()
@@ -1001,7 +1657,13 @@ impl State {
         }
     }
     pub fn can_use_active_chat(&self) -> bool {
-        self.get_active_chat()
+        self.chats.active.map_or(false, |id| self.can_use_chat(&id))
+    }
+    /// Direct chats can only be used with friends
+    pub fn can_use_chat(&self, chat_id: &Uuid) -> bool {
+        self.chats
+            .all
+            .get(chat_id)
             .map(|c| {
                 if c.conversation_type == ConversationType::Direct {
                     return c
@@ -1055,7 +1717,8 @@ impl State {
         }
     }
 
-    /// Clear unreads  within a given chat on `State` struct.
+    /// Clear unreads  within a given chat on `State` struct. The other participants are told the messages were read,
+    /// unless the user turned read receipts off.
     ///
     /// # Arguments
     ///
@@ -1063,8 +1726,15 @@ impl State {
     ///
     fn clear_unreads(&mut self, chat_id: Uuid) {
         if let Some(chat) = self.chats.all.get_mut(&chat_id) {
+            // only once per batch of messages, not whenever the chat is looked at
+            if chat.unreads() > 0 && self.configuration.privacy.read_receipts {
+                if let Some(newest) = chat.messages.back() {
+                    read_receipts::send(chat_id, newest.inner.date());
+                }
+            }
             chat.clear_unreads();
         }
+        message_store::mark_read(chat_id);
     }
     /// Adds the given chat to the user's favorites.
     fn favorite(&mut self, chat: &Uuid) {
@@ -1091,6 +1761,19 @@ impl State {
     pub fn get_chat_by_id(&self, id: Uuid) -> Option<Chat> {
         self.chats.all.get(&id).cloned()
     }
+    pub fn friend_sound(&self, did: &DID) -> Option<Sounds> {
+        self.friends.sounds.get(did).cloned()
+    }
+
+    /// The sound chosen for the conversation, or else the one chosen for the sender
+    pub fn notification_sound(&self, conversation_id: &Uuid, sender: &DID) -> Sounds {
+        self.chats
+            .notification_rules(conversation_id)
+            .sound
+            .or_else(|| self.friend_sound(sender))
+            .unwrap_or(Sounds::Notification)
+    }
+
     pub fn get_chat_with_friend(&self, friend: DID) -> Option<Chat> {
         self.chats
             .all
@@ -1198,6 +1881,94 @@ impl State {
         }
     }
 
+    /// Returns the conversation index issues. Storage issues are checked by the warp runner.
+    pub fn check_integrity(&self) -> Vec<integrity::IntegrityIssue> {
+        integrity::check_conversation_indexes(&self.chats)
+    }
+
+    /// Returns groups of direct chats which are with the same user. The first chat in each group
+    /// is the one with the most recent message and should be kept when merging.
+    pub fn duplicate_direct_chats(&self) -> Vec<Vec<Uuid>> {
+        let own = self.did_key();
+        let mut by_friend: HashMap<DID, Vec<&Chat>> = HashMap::new();
+        for chat in self.chats.all.values() {
+            if chat.conversation_type != ConversationType::Direct {
+                continue;
+            }
+            if let Some(friend) = chat.participants.iter().find(|did| **did != own) {
+                by_friend.entry(friend.clone()).or_default().push(chat);
+            }
+        }
+
+        by_friend
+            .into_values()
+            .filter(|chats| chats.len() > 1)
+            .map(|mut chats| {
+                chats.sort_by_key(|chat| {
+                    std::cmp::Reverse(chat.messages.back().map(|msg| msg.inner.date()))
+                });
+                chats.iter().map(|chat| chat.id).collect()
+            })
+            .collect()
+    }
+
+    /// Merges the duplicate chats into `chat_id`. The duplicates are hidden, and their favorites, sidebar
+    /// position, unreads and drafts are moved over. The history is combined by the warp runner when fetching messages.
+    fn merge_chats(&mut self, chat_id: Uuid, duplicates: Vec<Uuid>) {
+        if !self.chats.all.contains_key(&chat_id) {
+            log::warn!("attempted to merge into nonexistent conversation");
+            return;
+        }
+
+        for duplicate in duplicates.iter().filter(|id| **id != chat_id) {
+            let chat = match self.chats.all.remove(duplicate) {
+                Some(c) => c,
+                None => continue,
+            };
+
+            if self.chats.favorites.contains(duplicate) {
+                self.unfavorite(*duplicate);
+                self.favorite(&chat_id);
+            }
+
+            // keep the highest sidebar position of the merged chats
+            if let Some(pos) = self.chats.in_sidebar.iter().position(|id| id == duplicate) {
+                let current = self.chats.in_sidebar.iter().position(|id| *id == chat_id);
+                if current.map(|c| pos < c).unwrap_or(true) {
+                    // removing chat_id doesn't shift pos because it comes later in the list
+                    self.chats.in_sidebar.retain(|id| *id != chat_id);
+                    self.chats.in_sidebar[pos] = chat_id;
+                } else {
+                    self.chats.in_sidebar.remove(pos);
+                }
+            }
+
+            if self.chats.active == Some(*duplicate) {
+                self.chats.active = Some(chat_id);
+            }
+
+            if let Some(kept) = self.chats.all.get_mut(&chat_id) {
+                kept.absorb_duplicate(chat);
+            }
+            // the rules of the kept chat win
+            if let Some(rules) = self.chats.notification_rules.remove(duplicate) {
+                self.chats
+                    .notification_rules
+                    .entry(chat_id)
+                    .or_insert(rules);
+            }
+
+            // anything which was merged into the duplicate now belongs to chat_id
+            for target in self.chats.merged.values_mut() {
+                if *target == *duplicate {
+                    *target = chat_id;
+                }
+            }
+            self.chats.merged.insert(*duplicate, chat_id);
+        }
+        webhooks::configure_outgoing(&self.chats.webhooks, &self.chats.merged);
+    }
+
     /// Remove a chat from the sidebar on `State` struct.
     ///
     /// # Arguments
@@ -1300,6 +2071,63 @@ impl State {
         }
     }
 
+    pub fn is_queued(&self, message_id: &Uuid) -> bool {
+        self.chats.outbox.contains(message_id)
+    }
+
+    /// The message is in the outbox but isn't retried anymore
+    pub fn queued_message_gave_up(&self, message_id: &Uuid) -> bool {
+        self.chats
+            .outbox
+            .get(message_id)
+            .map(|m| m.gave_up())
+            .unwrap_or_default()
+    }
+
+    pub fn due_queued_messages(&self) -> Vec<QueuedMessage> {
+        self.chats.outbox.due(Instant::now())
+    }
+
+    /// Replaces the queued message with the message warp is now sending
+    pub fn queued_message_sent(&mut self, queued: &QueuedMessage, message_id: Uuid) {
+        self.chats.outbox.remove(&queued.id);
+        self.decrement_outgoing_messages(queued.conv_id, queued.id);
+        self.increment_outgoing_messages_for(queued.conv_id, message_id, queued.msg.clone());
+    }
+
+    pub fn queued_message_count(&self) -> usize {
+        self.chats.outbox.len()
+    }
+
+    /// The chat of the message which has been waiting the longest
+    pub fn oldest_queued_chat(&self) -> Option<Uuid> {
+        self.chats.outbox.first().map(|m| m.conv_id)
+    }
+
+    pub fn queued_message_failed(&mut self, id: &Uuid) {
+        self.chats.outbox.retry_later(id);
+    }
+
+    /// Sends the queued messages on the next retry instead of waiting for their backoff
+    pub fn retry_queued_messages(&mut self) {
+        self.chats.outbox.retry_now();
+    }
+
+    /// Outgoing messages which are still being sent. Queued messages don't count while offline since
+    /// they couldn't be sent anyway, nor once they aren't retried anymore. Both are kept in the outbox
+    /// until the next launch
+    pub fn unsent_message_count(&self) -> usize {
+        let offline = self.ui.network.relay == network::RelayStatus::Unreachable;
+        self.chats
+            .all
+            .values()
+            .flat_map(|chat| chat.pending_outgoing_messages.iter())
+            .filter(|m| {
+                !(offline && self.is_queued(&m.id())) && !self.queued_message_gave_up(&m.id())
+            })
+            .count()
+    }
+
     pub fn decrement_outgoing_messages(&mut self, conv_id: Uuid, message_id: Uuid) {
         if let Some(chat) = self.chats.all.get_mut(&conv_id) {
             chat.remove_pending_msg(message_id);
@@ -1339,6 +2167,18 @@ impl State {
     fn unfavorite(&mut self, chat_id: Uuid) {
         self.chats.favorites.retain(|uid| *uid != chat_id);
     }
+    /// Puts a favorite where `target` is, moving the ones in between by one
+    fn move_favorite(&mut self, chat_id: Uuid, target: Uuid) {
+        let faves = &mut self.chats.favorites;
+        let (Some(from), Some(to)) = (
+            faves.iter().position(|uid| *uid == chat_id),
+            faves.iter().position(|uid| *uid == target),
+        ) else {
+            return;
+        };
+        let chat_id = faves.remove(from);
+        faves.insert(to, chat_id);
+    }
 }
 
 // for friends
@@ -1347,6 +2187,20 @@ impl State {
         &self.friends
     }
 
+    /// Friends which are currently reachable, used as the number of connected peers
+    pub fn online_friends_count(&self) -> usize {
+        self.friends
+            .all
+            .iter()
+            .filter_map(|did| self.identities.get(did))
+            .filter(|identity| identity.identity_status() != IdentityStatus::Offline)
+            .count()
+    }
+
+    pub fn connection_status(&self) -> network::ConnectionStatus {
+        self.ui.network.status(self.online_friends_count())
+    }
+
     fn block(&mut self, identity: &DID) {
         // If the identity is not already blocked, add it to the blocked list
         self.friends.blocked.insert(identity.clone());
@@ -1381,33 +2235,25 @@ impl State {
         self.friends.outgoing_requests.insert(identity.did_key());
         self.identities.insert(identity.did_key(), identity.clone());
     }
+    /// The friends sorted by username, grouped by the letter their username starts with
     pub fn get_friends_by_first_letter(
         friends: HashMap<DID, Identity>,
-    ) -> BTreeMap<char, Vec<Identity>> {
-        let mut friends_by_first_letter: BTreeMap<char, Vec<Identity>> = BTreeMap::new();
-
-        // Iterate over the friends and add each one to the appropriate Vec in the
-        // friends_by_first_letter HashMap
-        for (_, friend) in friends {
-            let first_letter = friend
-                .username()
-                .chars()
-                .next()
-                .expect("all friends should have a username")
-                .to_ascii_lowercase();
-
-            friends_by_first_letter
-                .entry(first_letter)
-                .or_default()
-                .push(friend.clone());
-        }
-
-        for (_, list) in friends_by_first_letter.iter_mut() {
-            list.sort_by(|a, b| {
-                a.username()
-                    .cmp(&b.username())
-                    .then(a.did_key().to_string().cmp(&b.did_key().to_string()))
-            })
+    ) -> Vec<(char, Vec<Identity>)> {
+        let mut friends: Vec<Identity> = friends.into_values().collect();
+        friends.sort_by(|a, b| {
+            collation::compare(&a.username(), &b.username())
+                .then(a.did_key().to_string().cmp(&b.did_key().to_string()))
+        });
+
+        // friends under the same letter are next to each other once sorted
+        let mut friends_by_first_letter: Vec<(char, Vec<Identity>)> = vec![];
+        for friend in friends {
+            let first_letter =
+                collation::initial(&friend.username()).expect("all friends should have a username");
+            match friends_by_first_letter.last_mut() {
+                Some((letter, list)) if *letter == first_letter => list.push(friend),
+                _ => friends_by_first_letter.push((first_letter, vec![friend])),
+            }
         }
 
         friends_by_first_letter
@@ -1475,7 +2321,6 @@ impl State {
                 &self.configuration,
                 notifications::NotificationKind::Settings,
                 1,
-                !self.ui.metadata.focused,
             )
         }
     }
@@ -1575,6 +2420,46 @@ impl State {
             .cloned()
             .collect()
     }
+    fn add_call_event(&mut self, conversation_id: Uuid, call_id: Uuid, kind: CallEventKind) {
+        self.call_log
+            .record(conversation_id, CallEvent::new(call_id, kind));
+    }
+
+    // a pending call which wasn't answered, either declined or cancelled by the caller
+    fn unanswered_call(&mut self, call_id: Uuid, declined: bool) {
+        let Some(call) = self
+            .ui
+            .call_info
+            .pending_calls()
+            .into_iter()
+            .find(|call| call.id == call_id)
+        else {
+            return;
+        };
+        self.call_log
+            .unanswered(call.conversation_id, call_id, declined);
+    }
+
+    fn record_call_end(&mut self) {
+        if let Some(active) = self.ui.call_info.active_call() {
+            let duration = Local::now() - active.answer_time;
+            self.add_call_event(
+                active.call.conversation_id,
+                active.call.id,
+                CallEventKind::Ended {
+                    duration_secs: duration.num_seconds().max(0) as u64,
+                },
+            );
+        }
+    }
+
+    // for what is read out to screen readers, empty for someone who isn't known
+    fn username_of(&self, did: &DID) -> String {
+        self.get_identity(did)
+            .map(|id| id.username())
+            .unwrap_or_default()
+    }
+
     pub fn get_identity(&self, did: &DID) -> Option<Identity> {
         self.identities.get(did).cloned()
     }
@@ -1584,6 +2469,14 @@ impl State {
             .cloned()
             .unwrap_or_default()
     }
+    /// Busy keeps the notifications but mutes their sounds
+    pub fn is_busy(&self) -> bool {
+        self.get_own_identity().identity_status() == IdentityStatus::Busy
+    }
+    /// Appearing offline. Messages still arrive, but nothing is sent which gives away being around
+    pub fn is_invisible(&self) -> bool {
+        self.get_own_identity().identity_status() == IdentityStatus::Offline
+    }
     pub fn incoming_fr_identities(&self) -> Vec<Identity> {
         self.friends
             .incoming_requests
@@ -1647,7 +2540,8 @@ impl State {
 
         (entries, identities)
     }
-    // lets the user search for a group chat by chat name or, if a chat is not named, by the names of its participants
+    // lets the user search for a group chat by chat name or, if a chat is not named, by the names of its participants.
+    // chats with a local title or topic can be found by those as well
     pub fn search_group_chats(
         &self,
         name_prefix: &str,
@@ -1671,30 +2565,42 @@ impl State {
             }
         };
 
-        let chats_entries = self
-            .chats
-            .all
-            .iter()
-            .filter(|(_, v)| v.conversation_type == ConversationType::Group)
-            .filter(|(_k, v)| {
-                let names: Vec<_> = v
+        // direct chats are found through their participant, unless the user gave them a title or topic
+        let chat_matches = |chat: &Chat| {
+            let user_name_match = chat.conversation_type == ConversationType::Group
+                && chat
                     .participants
                     .iter()
                     .filter_map(|id| self.identities.get(id))
-                    .map(|x| x.username())
-                    .collect();
-
-                let user_name_match = names.iter().any(|n| compare_str(n));
-                let group_name_match = match v.conversation_name.as_ref() {
-                    Some(n) => compare_str(n),
-                    None => false,
-                };
+                    .any(|x| compare_str(&x.username()));
+            let group_name_match = match chat.conversation_name.as_ref() {
+                Some(n) => compare_str(n),
+                None => false,
+            };
+            let title_match = self
+                .chats
+                .titles
+                .get(&chat.id)
+                .map(|t| compare_str(t))
+                .unwrap_or(false);
+            let topic_match = self
+                .chats
+                .topics
+                .get(&chat.id)
+                .map(|t| t.to_lowercase().contains(&name_prefix.to_lowercase()))
+                .unwrap_or(false);
+
+            user_name_match || group_name_match || title_match || topic_match
+        };
 
-                user_name_match || group_name_match
-            })
+        let chats_entries = self
+            .chats
+            .all
+            .iter()
+            .filter(|(_, v)| chat_matches(v))
             .map(|(k, v)| {
-                if let Some(name) = v.conversation_name.as_ref() {
-                    identity_search_result::Entry::from_chat(name.clone(), *k)
+                if let Some(name) = self.chats.title(v) {
+                    identity_search_result::Entry::from_chat(name, *k)
                 } else {
                     let name = get_display_name(v);
                     identity_search_result::Entry::from_chat(name, *k)
@@ -1705,25 +2611,9 @@ impl State {
         let chats: Vec<Chat> = self
             .chats
             .all
-            .iter()
-            .filter(|(_, v)| v.conversation_type == ConversationType::Group)
-            .filter(|(_k, v)| {
-                let names: Vec<_> = v
-                    .participants
-                    .iter()
-                    .filter_map(|id| self.identities.get(id))
-                    .map(|x| x.username())
-                    .collect();
-
-                let user_name_match = names.iter().any(|n| compare_str(n));
-                let group_name_match = match v.conversation_name.as_ref() {
-                    Some(n) => compare_str(n),
-                    None => false,
-                };
-
-                user_name_match || group_name_match
-            })
-            .map(|(_, v)| v.clone())
+            .values()
+            .filter(|v| chat_matches(v))
+            .cloned()
             .collect();
         (chats_entries, chats)
     }
@@ -0,0 +1,165 @@
+//! What Uplink knows about its connection to the network. Shown in the connection indicator and the network
+//! settings. Whether peers are reached is told by the events of warp: friends coming online, messages, typing
+//! indicators. Probing the relay only tells apart being offline from not reaching any peer.
+
+use std::{net::IpAddr, time::Duration};
+
+use chrono::{DateTime, Local};
+use derive_more::Display;
+
+// a peer which was heard from this recently still counts as connected
+const PEER_SEEN_WINDOW: chrono::Duration = chrono::Duration::minutes(2);
+// how long Uplink tries to reach a peer before it stops showing that it is connecting
+const CONNECT_TIMEOUT: chrono::Duration = chrono::Duration::seconds(45);
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    #[display(fmt = "connecting")]
+    Connecting,
+    // at least one peer is connected
+    #[display(fmt = "connected")]
+    Connected,
+    // the relay is reachable but no peer is connected
+    #[display(fmt = "relay-only")]
+    RelayOnly,
+    #[display(fmt = "offline")]
+    Offline,
+    // nothing was heard from any peer for longer than CONNECT_TIMEOUT, and the relay doesn't tell more
+    #[display(fmt = "timed-out")]
+    TimedOut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelayStatus {
+    // not probed yet
+    Unknown,
+    // discovery doesn't use a relay, peers are only reached directly
+    NotConfigured,
+    Reachable { rtt: Duration },
+    Unreachable,
+}
+
+/// The result of probing the network
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetworkProbe {
+    pub relay: RelayStatus,
+    // address of the interface used to reach the relay
+    pub local_addr: Option<IpAddr>,
+}
+
+#[derive(Clone, Debug)]
+pub struct NetworkStatus {
+    pub relay: RelayStatus,
+    pub local_addr: Option<IpAddr>,
+    pub last_checked: Option<DateTime<Local>>,
+    // the last warp event which came from a peer
+    pub peer_seen: Option<DateTime<Local>>,
+    started: DateTime<Local>,
+}
+
+impl Default for NetworkStatus {
+    fn default() -> Self {
+        Self {
+            relay: RelayStatus::Unknown,
+            local_addr: None,
+            last_checked: None,
+            peer_seen: None,
+            started: Local::now(),
+        }
+    }
+}
+
+impl NetworkStatus {
+    /// Stores the result of a probe. Returns true if the relay became reachable again.
+    pub fn update(&mut self, probe: NetworkProbe) -> bool {
+        let reconnected = self.relay == RelayStatus::Unreachable
+            && matches!(probe.relay, RelayStatus::Reachable { .. });
+        self.relay = probe.relay;
+        self.local_addr = probe.local_addr;
+        self.last_checked = Some(Local::now());
+        reconnected
+    }
+
+    /// Records that warp got an event from a peer, which means the network works
+    pub fn peer_seen(&mut self) {
+        self.peer_seen = Some(Local::now());
+    }
+
+    pub fn status(&self, connected_peers: usize) -> ConnectionStatus {
+        self.status_at(connected_peers, Local::now())
+    }
+
+    fn status_at(&self, connected_peers: usize, now: DateTime<Local>) -> ConnectionStatus {
+        let heard_from = self.peer_seen.unwrap_or(self.started);
+        let recently_seen = self
+            .peer_seen
+            .is_some_and(|seen| now - seen < PEER_SEEN_WINDOW);
+        match self.relay {
+            _ if connected_peers > 0 || recently_seen => ConnectionStatus::Connected,
+            RelayStatus::Reachable { .. } => ConnectionStatus::RelayOnly,
+            RelayStatus::Unreachable => ConnectionStatus::Offline,
+            RelayStatus::Unknown | RelayStatus::NotConfigured
+                if now - heard_from > CONNECT_TIMEOUT =>
+            {
+                ConnectionStatus::TimedOut
+            }
+            RelayStatus::Unknown | RelayStatus::NotConfigured => ConnectionStatus::Connecting,
+        }
+    }
+
+    /// Whether the local address is a private one, which means the connection goes through a NAT.
+    /// None if the address isn't known.
+    pub fn behind_nat(&self) -> Option<bool> {
+        self.local_addr.map(|addr| match addr {
+            IpAddr::V4(ip) => ip.is_private() || ip.is_loopback() || ip.is_link_local(),
+            // unique local addresses, fc00::/7
+            IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let mut network = NetworkStatus::default();
+        assert_eq!(network.status(0), ConnectionStatus::Connecting);
+        network.update(NetworkProbe {
+            relay: RelayStatus::Unreachable,
+            local_addr: Some("192.168.1.20".parse().unwrap()),
+        });
+        assert_eq!(network.status(0), ConnectionStatus::Offline);
+        assert_eq!(network.status(2), ConnectionStatus::Connected);
+        assert_eq!(network.behind_nat(), Some(true));
+        assert!(network.update(NetworkProbe {
+            relay: RelayStatus::Reachable {
+                rtt: Duration::from_millis(40)
+            },
+            local_addr: Some("203.0.113.7".parse().unwrap()),
+        }));
+        assert_eq!(network.status(0), ConnectionStatus::RelayOnly);
+        assert_eq!(network.behind_nat(), Some(false));
+    }
+
+    #[test]
+    fn test_status_from_peers() {
+        let mut network = NetworkStatus {
+            relay: RelayStatus::NotConfigured,
+            ..Default::default()
+        };
+        let now = network.started;
+        assert_eq!(network.status_at(0, now), ConnectionStatus::Connecting);
+        let later = now + chrono::Duration::minutes(1);
+        assert_eq!(network.status_at(0, later), ConnectionStatus::TimedOut);
+
+        network.peer_seen = Some(later);
+        assert_eq!(network.status_at(0, later), ConnectionStatus::Connected);
+        // a peer which was heard from doesn't keep it connected forever
+        let much_later = later + chrono::Duration::minutes(5);
+        assert_eq!(network.status_at(0, much_later), ConnectionStatus::TimedOut);
+        network.relay = RelayStatus::Unreachable;
+        assert_eq!(network.status_at(0, much_later), ConnectionStatus::Offline);
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use tracing::log;
 
 use crate::notifications::set_badge;
 
@@ -16,9 +17,9 @@ pub struct Notifications {
     pub friends: u32, // For notifications about new friends, friend requests and related CTAs.
     pub messages: u32, // For notifications about new messages, mentions.
     pub settings: u32, // For notifications about updates, issues and more.
-    // displays above the app icon on the desktop
+    // the unread count last shown on the app icon, see `State::unread_count`
     #[serde(skip)]
-    pub badge: u32,
+    badge: u32,
 }
 
 impl Notifications {
@@ -36,43 +37,24 @@ impl Notifications {
     }
 
     // Adds notification(s) to the specified kind.
-    pub fn increment(
-        &mut self,
-        config: &Configuration,
-        kind: NotificationKind,
-        count: u32,
-        increment_badge: bool,
-    ) {
+    pub fn increment(&mut self, config: &Configuration, kind: NotificationKind, count: u32) {
         match kind {
             NotificationKind::FriendRequest => {
                 if config.notifications.friends_notifications {
                     self.friends = self.friends.saturating_add(count);
-                    if increment_badge {
-                        self.badge = self.badge.saturating_add(count);
-                    }
                 }
             }
             NotificationKind::Message => {
                 if config.notifications.messages_notifications {
                     self.messages = self.messages.saturating_add(count);
-                    if increment_badge {
-                        self.badge = self.badge.saturating_add(count);
-                    }
                 }
             }
             NotificationKind::Settings => {
                 if config.notifications.settings_notifications {
                     self.settings = self.settings.saturating_add(count);
-                    if increment_badge {
-                        self.badge = self.badge.saturating_add(count);
-                    }
                 }
             }
         };
-
-        if increment_badge {
-            let _ = set_badge(self.badge);
-        }
     }
 
     // Removes notification(s) from the specified kind.
@@ -81,20 +63,14 @@ impl Notifications {
         match kind {
             NotificationKind::FriendRequest => {
                 self.friends = self.friends.saturating_sub(count);
-                self.badge = self.badge.saturating_sub(count);
             }
             NotificationKind::Message => {
                 self.messages = self.messages.saturating_sub(count);
-                self.badge = self.badge.saturating_sub(count);
             }
             NotificationKind::Settings => {
                 self.settings = self.settings.saturating_sub(count);
-                self.badge = self.badge.saturating_sub(count);
             }
         };
-
-        // Update the badge any time notifications are removed.
-        let _ = set_badge(self.badge);
     }
 
     // Returns the total count for a given notification kind.
@@ -110,20 +86,15 @@ impl Notifications {
     pub fn clear_kind(&mut self, kind: NotificationKind) {
         match kind {
             NotificationKind::FriendRequest => {
-                self.badge = self.badge.saturating_sub(self.friends);
                 self.friends = 0;
             }
             NotificationKind::Message => {
-                self.badge = self.badge.saturating_sub(self.messages);
                 self.messages = 0;
             }
             NotificationKind::Settings => {
-                self.badge = self.badge.saturating_sub(self.settings);
                 self.settings = 0;
             }
         };
-        // Update the badge with new possible totals.
-        let _ = set_badge(self.badge);
     }
 
     // Clears all notifications.
@@ -131,13 +102,16 @@ impl Notifications {
         self.friends = 0;
         self.messages = 0;
         self.settings = 0;
-
-        self.badge = 0;
-        let _ = set_badge(self.badge);
     }
 
-    pub fn clear_badge(&mut self) {
-        self.badge = 0;
-        let _ = set_badge(self.badge);
+    /// Shows the number of unread messages on the app icon, if it changed since it was last shown
+    pub fn update_badge(&mut self, unreads: u32) {
+        if self.badge == unreads {
+            return;
+        }
+        self.badge = unreads;
+        if let Err(e) = set_badge(unreads) {
+            log::warn!("failed to update the app icon badge: {e}");
+        }
     }
 }
@@ -0,0 +1,79 @@
+//! The steps shown once an account was created: the preferences, adding a first friend and the
+//! tour of the app. The step is kept in the state, so closing the app in between goes on where
+//! the user left off.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OnboardingStep {
+    #[default]
+    Notifications,
+    Theme,
+    AddFriend,
+    // asks whether the user wants to be shown around
+    TourOffer,
+    // the index of the stop of the tour
+    Tour(usize),
+}
+
+impl OnboardingStep {
+    /// The steps of the wizard, the tour is shown after them
+    pub const WIZARD: [OnboardingStep; 4] = [
+        OnboardingStep::Notifications,
+        OnboardingStep::Theme,
+        OnboardingStep::AddFriend,
+        OnboardingStep::TourOffer,
+    ];
+
+    /// The step after this one, or None once the tour of `tour_stops` stops is done
+    pub fn next(self, tour_stops: usize) -> Option<Self> {
+        match self {
+            OnboardingStep::Notifications => Some(OnboardingStep::Theme),
+            OnboardingStep::Theme => Some(OnboardingStep::AddFriend),
+            OnboardingStep::AddFriend => Some(OnboardingStep::TourOffer),
+            OnboardingStep::TourOffer => (tour_stops > 0).then_some(OnboardingStep::Tour(0)),
+            OnboardingStep::Tour(stop) => {
+                (stop + 1 < tour_stops).then_some(OnboardingStep::Tour(stop + 1))
+            }
+        }
+    }
+
+    /// The step before this one. The tour doesn't go back to the wizard
+    pub fn previous(self) -> Option<Self> {
+        match self {
+            OnboardingStep::Notifications => None,
+            OnboardingStep::Theme => Some(OnboardingStep::Notifications),
+            OnboardingStep::AddFriend => Some(OnboardingStep::Theme),
+            OnboardingStep::TourOffer => Some(OnboardingStep::AddFriend),
+            OnboardingStep::Tour(stop) => stop.checked_sub(1).map(OnboardingStep::Tour),
+        }
+    }
+
+    /// Where the step is in the wizard, None during the tour
+    pub fn wizard_index(self) -> Option<usize> {
+        Self::WIZARD.iter().position(|step| *step == self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn walks_through_the_wizard_and_the_tour() {
+        let mut step = OnboardingStep::default();
+        let mut seen = vec![step];
+        while let Some(next) = step.next(2) {
+            seen.push(next);
+            step = next;
+        }
+        assert_eq!(seen.len(), OnboardingStep::WIZARD.len() + 2);
+        assert_eq!(step, OnboardingStep::Tour(1));
+        assert_eq!(step.previous(), Some(OnboardingStep::Tour(0)));
+        assert_eq!(OnboardingStep::Tour(0).previous(), None);
+        assert_eq!(OnboardingStep::Tour(0).wizard_index(), None);
+        assert_eq!(OnboardingStep::AddFriend.wizard_index(), Some(2));
+        // without a tour the wizard ends with the offer
+        assert_eq!(OnboardingStep::TourOffer.next(0), None);
+    }
+}
@@ -0,0 +1,195 @@
+//! Messages which failed to send, usually because the node is offline. They are shown as pending in
+//! the chat and retried with a backoff until they are sent, the user cancels them or they failed
+//! too often. Those stay in the chat until the user tries them again. The outbox is saved with the
+//! chats, so the messages are sent after a restart too.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use warp::raygun::Location;
+
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+// about an hour of retries with the delays above
+const MAX_ATTEMPTS: u32 = 20;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueuedMessage {
+    // also the id of the pending message shown in the chat
+    pub id: Uuid,
+    pub conv_id: Uuid,
+    pub msg: Vec<String>,
+    #[serde(with = "locations")]
+    pub attachments: Vec<Location>,
+    pub replying_to: Option<Uuid>,
+    pub attempts: u32,
+    // after a restart the messages are retried right away
+    #[serde(skip, default = "Instant::now")]
+    pub next_attempt: Instant,
+}
+
+impl QueuedMessage {
+    /// It isn't retried anymore until the user asks for it
+    pub fn gave_up(&self) -> bool {
+        self.attempts >= MAX_ATTEMPTS
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Outbox {
+    messages: Vec<QueuedMessage>,
+}
+
+impl Outbox {
+    /// Queues a message which failed to send and returns its id
+    pub fn push(
+        &mut self,
+        conv_id: Uuid,
+        msg: Vec<String>,
+        attachments: Vec<Location>,
+        replying_to: Option<Uuid>,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        self.messages.push(QueuedMessage {
+            id,
+            conv_id,
+            msg,
+            attachments,
+            replying_to,
+            attempts: 1,
+            next_attempt: Instant::now() + retry_delay(1),
+        });
+        id
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&QueuedMessage> {
+        self.messages.iter().find(|m| m.id == *id)
+    }
+
+    pub fn contains(&self, id: &Uuid) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn remove(&mut self, id: &Uuid) -> Option<QueuedMessage> {
+        let idx = self.messages.iter().position(|m| m.id == *id)?;
+        Some(self.messages.remove(idx))
+    }
+
+    pub fn remove_chat(&mut self, conv_id: &Uuid) {
+        self.messages.retain(|m| m.conv_id != *conv_id);
+    }
+
+    pub fn set_text(&mut self, id: &Uuid, msg: Vec<String>) {
+        if let Some(m) = self.messages.iter_mut().find(|m| m.id == *id) {
+            m.msg = msg;
+        }
+    }
+
+    /// The messages which should be sent again, in the order they were queued
+    pub fn due(&self, now: Instant) -> Vec<QueuedMessage> {
+        self.messages
+            .iter()
+            .filter(|m| !m.gave_up() && m.next_attempt <= now)
+            .cloned()
+            .collect()
+    }
+
+    pub fn in_chat<'a>(&'a self, conv_id: &'a Uuid) -> impl Iterator<Item = &'a QueuedMessage> {
+        self.messages.iter().filter(move |m| m.conv_id == *conv_id)
+    }
+
+    /// Called when sending the message failed again
+    pub fn retry_later(&mut self, id: &Uuid) {
+        if let Some(m) = self.messages.iter_mut().find(|m| m.id == *id) {
+            m.attempts += 1;
+            m.next_attempt = Instant::now() + retry_delay(m.attempts);
+        }
+    }
+
+    /// Retries everything right away, for example once the node is reachable again. The messages
+    /// which failed too often are left alone
+    pub fn retry_now(&mut self) {
+        let now = Instant::now();
+        for m in self.messages.iter_mut() {
+            m.next_attempt = now;
+        }
+    }
+
+    /// Starts retrying a message which failed too often over again
+    pub fn try_again(&mut self, id: &Uuid) {
+        if let Some(m) = self.messages.iter_mut().find(|m| m.id == *id) {
+            m.attempts = 1;
+            m.next_attempt = Instant::now();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// The oldest queued message
+    pub fn first(&self) -> Option<&QueuedMessage> {
+        self.messages.first()
+    }
+}
+
+// doubles with every attempt
+fn retry_delay(attempts: u32) -> Duration {
+    MIN_RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1)))
+        .min(MAX_RETRY_DELAY)
+}
+
+// the queued attachments are saved as `FileLocation`s, so the file doesn't depend on how warp
+// serializes its own type
+mod locations {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use warp::raygun::Location;
+
+    use crate::state::pending_message::FileLocation;
+
+    pub fn serialize<S: Serializer>(locations: &[Location], s: S) -> Result<S::Ok, S::Error> {
+        let locations: Vec<FileLocation> = locations.iter().cloned().map(Into::into).collect();
+        locations.serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Location>, D::Error> {
+        let locations = Vec::<FileLocation>::deserialize(d)?;
+        Ok(locations.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), MIN_RETRY_DELAY);
+        assert_eq!(retry_delay(2), MIN_RETRY_DELAY * 2);
+        assert_eq!(retry_delay(3), MIN_RETRY_DELAY * 4);
+        assert_eq!(retry_delay(100), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn gives_up_and_survives_a_restart() {
+        let mut outbox = Outbox::default();
+        let id = outbox.push(Uuid::new_v4(), vec!["hi".into()], vec![], None);
+        for _ in 1..MAX_ATTEMPTS {
+            outbox.retry_later(&id);
+        }
+        outbox.retry_now();
+        assert!(outbox.due(Instant::now()).is_empty());
+
+        let saved = serde_json::to_string(&outbox).unwrap();
+        let mut outbox: Outbox = serde_json::from_str(&saved).unwrap();
+        assert!(outbox.get(&id).unwrap().gave_up());
+        outbox.try_again(&id);
+        assert_eq!(outbox.due(Instant::now()).len(), 1);
+    }
+}
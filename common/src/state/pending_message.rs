@@ -1,5 +1,6 @@
 use std::{collections::HashMap, path::PathBuf};
 
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use warp::{constellation::Progression, crypto::DID, raygun::Location};
 
@@ -12,7 +13,7 @@ pub struct PendingMessage {
     pub message: Message,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FileLocation {
     /// Use [`Constellation`] to send a file from constellation
     Constellation { path: String },
@@ -0,0 +1,289 @@
+//! The persisted parts of `State` are split into stores, one file each, so a change to the settings
+//! doesn't rewrite every chat. `State::save` only marks the state as changed. Shortly after, a copy
+//! of every store is sent to a thread of their own, which serializes them, compares them with what
+//! was last written and writes the ones which changed.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use tracing::log;
+
+// changes which come in quick succession are written together
+const QUIET_TIME: Duration = Duration::from_millis(300);
+// unless they keep coming, then they are written at least this often
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Store {
+    Chats,
+    Friends,
+    // the settings and the configuration
+    Settings,
+    // the route and the UI
+    Ui,
+    Storage,
+}
+
+impl Store {
+    pub const ALL: [Store; 5] = [
+        Store::Chats,
+        Store::Friends,
+        Store::Settings,
+        Store::Ui,
+        Store::Storage,
+    ];
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            Store::Chats => "chats.json",
+            Store::Friends => "friends.json",
+            Store::Settings => "settings.json",
+            Store::Ui => "ui.json",
+            Store::Storage => "storage.json",
+        }
+    }
+}
+
+/// A copy of a store, serialized by the writer
+pub type Snapshot = Box<dyn FnOnce() -> serde_json::Result<Vec<u8>> + Send>;
+
+// hashes of what was last written to every store
+type Written = Arc<Mutex<HashMap<Store, u64>>>;
+
+enum Job {
+    Write {
+        path: PathBuf,
+        store: Store,
+        snapshot: Snapshot,
+        written: Written,
+    },
+    // a file which isn't a store, written every time
+    File {
+        path: PathBuf,
+        snapshot: Snapshot,
+    },
+    // answered once everything sent before it was written, with the last error since the previous
+    // flush
+    Flush(mpsc::Sender<Result<(), String>>),
+}
+
+static WRITER: Lazy<Mutex<mpsc::Sender<Job>>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel::<Job>();
+    std::thread::spawn(move || {
+        let mut failed = None;
+        while let Ok(job) = rx.recv() {
+            match job {
+                Job::Write {
+                    path,
+                    store,
+                    snapshot,
+                    written,
+                } => {
+                    if let Err(e) = write_if_changed(&path, store, snapshot, &written) {
+                        log::error!("failed to write {}: {e}", path.display());
+                        failed = Some(e);
+                    }
+                }
+                Job::File { path, snapshot } => {
+                    let written = snapshot().map_err(|e| e.to_string()).and_then(|contents| {
+                        write_atomic(&path, &contents).map_err(|e| e.to_string())
+                    });
+                    if let Err(e) = written {
+                        log::error!("failed to write {}: {e}", path.display());
+                        failed = Some(e);
+                    }
+                }
+                Job::Flush(done) => {
+                    let _ = done.send(failed.take().map_or(Ok(()), Err));
+                }
+            }
+        }
+    });
+    Mutex::new(tx)
+});
+
+// the hash is only recorded once the store is on disk, so a failed write is tried again with the
+// next change
+fn write_if_changed(
+    path: &Path,
+    store: Store,
+    snapshot: Snapshot,
+    written: &Written,
+) -> Result<(), String> {
+    let contents = snapshot().map_err(|e| e.to_string())?;
+    let hash = hash(&contents);
+    if written.lock().get(&store) == Some(&hash) {
+        return Ok(());
+    }
+    write_atomic(path, &contents).map_err(|e| e.to_string())?;
+    written.lock().insert(store, hash);
+    Ok(())
+}
+
+fn hash(contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Default)]
+pub struct Persistence {
+    // the first and the last change which weren't written yet
+    pending: Mutex<Option<(Instant, Instant)>>,
+    written: Written,
+}
+
+impl Persistence {
+    pub fn mark_changed(&self) {
+        let now = Instant::now();
+        let mut pending = self.pending.lock();
+        let first = pending.map(|(first, _)| first).unwrap_or(now);
+        *pending = Some((first, now));
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.pending.lock().is_some()
+    }
+
+    pub fn is_due(&self) -> bool {
+        match *self.pending.lock() {
+            Some((first, last)) => last.elapsed() >= QUIET_TIME || first.elapsed() >= MAX_DELAY,
+            None => false,
+        }
+    }
+
+    /// Sends a copy of every store to the writer, which writes the ones that changed since they were
+    /// last written. `snapshot` is called for every store
+    pub fn write_changed(&self, dir: &Path, snapshot: impl Fn(Store) -> Snapshot) {
+        self.pending.lock().take();
+        let writer = WRITER.lock();
+        for store in Store::ALL {
+            let _ = writer.send(Job::Write {
+                path: dir.join(store.file_name()),
+                store,
+                snapshot: snapshot(store),
+                written: self.written.clone(),
+            });
+        }
+    }
+
+    /// Marks the contents of a store which was just read as written
+    pub fn set_written(&self, store: Store, contents: &[u8]) {
+        self.written.lock().insert(store, hash(contents));
+    }
+}
+
+/// Writes a file which isn't one of the stores on the thread of the writer, so the UI doesn't wait
+/// for it
+pub fn write_file(path: PathBuf, snapshot: Snapshot) {
+    let _ = WRITER.lock().send(Job::File { path, snapshot });
+}
+
+/// Waits until everything sent to the writer so far is on disk. Fails if a store couldn't be
+/// written since the last call
+pub fn wait_for_writes() -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    if WRITER.lock().send(Job::Flush(tx)).is_ok() {
+        return rx.recv().unwrap_or(Ok(()));
+    }
+    Ok(())
+}
+
+/// The contents of the store, `None` if it doesn't exist yet
+pub fn read_store(dir: &Path, store: Store) -> io::Result<Option<Vec<u8>>> {
+    match fs::read(dir.join(store.file_name())) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Parses a store, or returns the default if it doesn't exist or is broken
+pub fn parse_store<T: DeserializeOwned + Default>(
+    store: Store,
+    contents: Option<&[u8]>,
+    ok: &mut bool,
+) -> T {
+    let Some(contents) = contents else {
+        return T::default();
+    };
+    match serde_json::from_slice(contents) {
+        Ok(value) => value,
+        Err(e) => {
+            log::error!(
+                "{} failed to deserialize: {e}. Using default values",
+                store.file_name()
+            );
+            *ok = false;
+            T::default()
+        }
+    }
+}
+
+// the old contents stay in place if writing is interrupted
+fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn writes_only_changed_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        let persistence = Persistence::default();
+        let snapshot = |chats: &'static str| {
+            move |store: Store| -> Snapshot {
+                Box::new(move || {
+                    Ok(match store {
+                        Store::Chats => chats.as_bytes().to_vec(),
+                        _ => b"{}".to_vec(),
+                    })
+                })
+            }
+        };
+
+        persistence.mark_changed();
+        assert!(persistence.has_changes());
+        persistence.write_changed(dir.path(), snapshot("[1]"));
+        assert!(!persistence.has_changes());
+        wait_for_writes().unwrap();
+        let friends = dir.path().join(Store::Friends.file_name());
+        assert!(friends.exists());
+
+        // only the chats changed, the friends aren't written again
+        fs::remove_file(&friends).unwrap();
+        persistence.write_changed(dir.path(), snapshot("[1, 2]"));
+        wait_for_writes().unwrap();
+        let chats = read_store(dir.path(), Store::Chats).unwrap().unwrap();
+        assert_eq!(chats, b"[1, 2]");
+        assert!(!friends.exists());
+
+        // a failed write is tried again, even without a change
+        let blocked = dir.path().join("chats.json.tmp");
+        fs::create_dir(&blocked).unwrap();
+        persistence.write_changed(dir.path(), snapshot("[3]"));
+        assert!(wait_for_writes().is_err());
+        fs::remove_dir(&blocked).unwrap();
+        persistence.write_changed(dir.path(), snapshot("[3]"));
+        wait_for_writes().unwrap();
+        let chats = read_store(dir.path(), Store::Chats).unwrap().unwrap();
+        assert_eq!(chats, b"[3]");
+    }
+}
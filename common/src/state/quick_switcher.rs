@@ -0,0 +1,181 @@
+//! The quick switcher jumps to conversations, friends, files and settings by typing a few letters
+//! of their name. What was picked lately is remembered and ranked higher, so going back and forth
+//! between a couple of chats only takes the shortcut and Enter.
+
+use std::{cmp::Reverse, fs};
+
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+use warp::crypto::DID;
+
+use crate::STATIC_ARGS;
+
+// older picks are forgotten
+const MAX_RECENTS: usize = 20;
+// letters matched one after the other, e.g. "gen" in "General"
+const CONSECUTIVE_BONUS: u32 = 3;
+// letters starting a word, e.g. "ns" in "Notification Settings"
+const WORD_START_BONUS: u32 = 2;
+// the most recent pick outranks a slightly better match
+const RECENT_BONUS: u32 = 2;
+
+/// Something the quick switcher can open
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    Chat(Uuid),
+    // their direct conversation, which is created if there isn't one yet
+    Friend(DID),
+    // in the folder opened last on the files page, by name
+    Folder(String),
+    File(Uuid),
+    NewFolder,
+    // a settings page, e.g. "audio"
+    Settings(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub target: Target,
+    pub label: String,
+}
+
+impl Candidate {
+    pub fn new(target: Target, label: impl Into<String>) -> Self {
+        Self {
+            target,
+            label: label.into(),
+        }
+    }
+}
+
+/// How well `query` matches `text`, `None` if it doesn't. The letters of the query have to appear
+/// in order, not necessarily next to each other. Case and whitespace in the query are ignored
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for (idx, c) in text.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if *c != query[matched] {
+            continue;
+        }
+        score += 1;
+        if previous.map_or(false, |p| p + 1 == idx) {
+            score += CONSECUTIVE_BONUS;
+        }
+        if idx == 0 || !text[idx - 1].is_alphanumeric() {
+            score += WORD_START_BONUS;
+        }
+        previous = Some(idx);
+        matched += 1;
+    }
+    (matched == query.len()).then_some(score)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Recents {
+    // most recent first
+    targets: Vec<Target>,
+}
+
+impl Recents {
+    pub fn load() -> Self {
+        let targets = match fs::read_to_string(STATIC_ARGS.quick_switcher_path()) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::error!("failed to deserialize the quick switcher recents: {e}");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { targets }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(&self.targets) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(STATIC_ARGS.quick_switcher_path(), contents) {
+                    log::error!("failed to save the quick switcher recents: {e}");
+                }
+            }
+            Err(e) => log::error!("failed to serialize the quick switcher recents: {e}"),
+        }
+    }
+
+    pub fn record(&mut self, target: Target) {
+        self.push(target);
+        self.save();
+    }
+
+    fn push(&mut self, target: Target) {
+        self.targets.retain(|t| t != &target);
+        self.targets.insert(0, target);
+        self.targets.truncate(MAX_RECENTS);
+    }
+
+    fn bonus(&self, target: &Target) -> u32 {
+        self.targets
+            .iter()
+            .position(|t| t == target)
+            .map_or(0, |idx| (MAX_RECENTS - idx) as u32 * RECENT_BONUS)
+    }
+
+    /// The candidates matching `query`, best first. Equally good ones keep their order, so without a
+    /// query the recent picks come first and the rest stays as it was
+    pub fn rank(&self, query: &str, candidates: Vec<Candidate>) -> Vec<Candidate> {
+        let mut ranked: Vec<(u32, Candidate)> = candidates
+            .into_iter()
+            .filter_map(|c| Some((score(query, &c.label)? + self.bonus(&c.target), c)))
+            .collect();
+        ranked.sort_by_key(|(score, _)| Reverse(*score));
+        ranked.into_iter().map(|(_, c)| c).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ranks_matches_and_recent_picks() {
+        assert!(score("ntf", "Notifications").is_some());
+        assert!(score("snt", "Notifications").is_none());
+        assert!(score("gen", "General") > score("gen", "Language settings"));
+
+        let candidates = vec![
+            Candidate::new(Target::Settings("general".into()), "General"),
+            Candidate::new(Target::Settings("audio".into()), "Audio"),
+            Candidate::new(Target::NewFolder, "New Folder"),
+        ];
+        let mut recents = Recents::default();
+        let labels = |ranked: Vec<Candidate>| -> Vec<String> {
+            ranked.into_iter().map(|c| c.label).collect()
+        };
+        assert_eq!(
+            labels(recents.rank("", candidates.clone())),
+            vec!["General", "Audio", "New Folder"]
+        );
+        assert_eq!(
+            labels(recents.rank("e", candidates.clone())),
+            vec!["General", "New Folder"]
+        );
+
+        recents.push(Target::NewFolder);
+        assert_eq!(
+            labels(recents.rank("", candidates.clone())),
+            vec!["New Folder", "General", "Audio"]
+        );
+        assert_eq!(
+            labels(recents.rank("e", candidates)),
+            vec!["New Folder", "General"]
+        );
+    }
+}
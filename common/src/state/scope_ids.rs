@@ -8,6 +8,8 @@ pub struct ScopeIds {
     // This can then be removed
     pub file_transfer: Option<usize>,
     pub file_transfer_icon: Option<usize>,
+    pub status_bar: Option<usize>,
+    pub call_transfers: Option<usize>,
     pub pending_message_component: Option<usize>,
 }
 
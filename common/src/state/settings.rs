@@ -1,24 +1,41 @@
 use dioxus::prelude::*;
 use dioxus_desktop::tao::keyboard::ModifiersState;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
 
 use crate::language::get_id_of;
 use crate::language::US_ENGLISH;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use warp::crypto::DID;
 
+use super::do_not_disturb::DoNotDisturb;
 use super::State;
 
 #[derive(Eq, PartialEq, Hash, Debug, Clone, Deserialize, Serialize, Default)]
 pub enum GlobalShortcut {
     ToggleMute,
     ToggleDeafen,
+    AnswerCall,
+    HangUpCall,
+    CycleCallParticipants,
     IncreaseFontSize,
     DecreaseFontSize,
     OpenCloseDevTools,
     ToggleDevmode,
+    ToggleProfilingOverlay,
     SetAppVisible,
+    OpenQuickSwitcher,
+    NextChat,
+    PreviousChat,
+    OpenSettings,
+    LockApp,
+    ZoomInChat,
+    ZoomOutChat,
+    ResetChatZoom,
+    SendMessage,
+    FocusSearch,
     #[default]
     Unknown,
 }
@@ -28,11 +45,25 @@ impl fmt::Display for GlobalShortcut {
         match self {
             GlobalShortcut::ToggleMute => write!(f, "ToggleMute"),
             GlobalShortcut::ToggleDeafen => write!(f, "ToggleDeafen"),
+            GlobalShortcut::AnswerCall => write!(f, "AnswerCall"),
+            GlobalShortcut::HangUpCall => write!(f, "HangUpCall"),
+            GlobalShortcut::CycleCallParticipants => write!(f, "CycleCallParticipants"),
             GlobalShortcut::IncreaseFontSize => write!(f, "IncreaseFontSize"),
             GlobalShortcut::DecreaseFontSize => write!(f, "DecreaseFontSize"),
             GlobalShortcut::OpenCloseDevTools => write!(f, "OpenCloseDevTools"),
             GlobalShortcut::ToggleDevmode => write!(f, "ToggleDevmode"),
+            GlobalShortcut::ToggleProfilingOverlay => write!(f, "ToggleProfilingOverlay"),
             GlobalShortcut::SetAppVisible => write!(f, "SetAppVisible"),
+            GlobalShortcut::OpenQuickSwitcher => write!(f, "OpenQuickSwitcher"),
+            GlobalShortcut::NextChat => write!(f, "NextChat"),
+            GlobalShortcut::PreviousChat => write!(f, "PreviousChat"),
+            GlobalShortcut::OpenSettings => write!(f, "OpenSettings"),
+            GlobalShortcut::LockApp => write!(f, "LockApp"),
+            GlobalShortcut::ZoomInChat => write!(f, "ZoomInChat"),
+            GlobalShortcut::ZoomOutChat => write!(f, "ZoomOutChat"),
+            GlobalShortcut::ResetChatZoom => write!(f, "ResetChatZoom"),
+            GlobalShortcut::SendMessage => write!(f, "SendMessage"),
+            GlobalShortcut::FocusSearch => write!(f, "FocusSearch"),
             GlobalShortcut::Unknown => write!(f, "Unknown"),
         }
     }
@@ -169,6 +200,7 @@ impl Shortcut {
                 "Period" => key_code_vec.push(KeyCode::Period),
                 "Slash" => key_code_vec.push(KeyCode::ForwardSlash),
                 "Space" => key_code_vec.push(KeyCode::Space),
+                "Enter" => key_code_vec.push(KeyCode::Enter),
                 _ => (),
             }
         }
@@ -228,6 +260,7 @@ pub fn key_code_to_str(key_code: &KeyCode) -> &str {
         KeyCode::Period => ".",
         KeyCode::ForwardSlash => "/",
         KeyCode::Space => " ",
+        KeyCode::Enter => "enter",
         _ => "unknown",
     }
 }
@@ -271,12 +304,30 @@ pub struct Settings {
     pub update_dismissed: Option<String>,
     pub input_device: Option<String>,
     pub output_device: Option<String>,
+    // the id the webview gives the camera
+    #[serde(default)]
+    pub video_device: Option<String>,
     #[serde(default = "default_font_scale")]
     font_scale: f32,
     pub user_volumes: HashMap<DID, f32>,
     pub pause_global_keybinds: bool,
     pub is_recording_new_keybind: bool,
     pub keybinds: Vec<(GlobalShortcut, Shortcut)>,
+    // devices this one shared the account with
+    #[serde(default)]
+    pub linked_devices: Vec<LinkedDevice>,
+    #[serde(default)]
+    pub do_not_disturb: DoNotDisturb,
+    // words the spellchecker accepts on top of the dictionaries
+    #[serde(default)]
+    pub personal_dictionary: BTreeSet<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LinkedDevice {
+    pub id: Uuid,
+    pub name: String,
+    pub linked_at: DateTime<Utc>,
 }
 
 impl Default for Settings {
@@ -287,11 +338,15 @@ impl Default for Settings {
             update_available: None,
             input_device: None,
             output_device: None,
+            video_device: None,
             font_scale: 1.0,
             user_volumes: HashMap::new(),
             pause_global_keybinds: false,
             keybinds: super::default_keybinds::get_default_keybinds(),
             is_recording_new_keybind: false,
+            linked_devices: Vec::new(),
+            do_not_disturb: DoNotDisturb::default(),
+            personal_dictionary: BTreeSet::new(),
         }
     }
 }
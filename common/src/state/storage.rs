@@ -1,8 +1,12 @@
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use warp::{constellation::directory::Directory, constellation::file::File};
 
+// directories with thousands of items are sent to the UI a page at a time
+pub const ITEMS_PAGE_SIZE: usize = 200;
+
 // TODO: Properly wrap data which is expected to persist remotely in options, so we can know if we're still figuring out what exists "remotely", i.e. loading.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Storage {
@@ -21,6 +25,9 @@ pub struct Storage {
     // List of files inside current directory
     #[serde(skip)]
     pub files: Vec<File>,
+    // Number of items inside current directory. Only the first page of them is in `directories` and `files`
+    #[serde(skip)]
+    pub total_items: usize,
     #[serde(skip)]
     pub max_size: usize,
     #[serde(skip)]
@@ -28,3 +35,13 @@ pub struct Storage {
     #[serde(skip)]
     pub files_in_queue_to_upload: Vec<PathBuf>,
 }
+
+/// The items of the current directory from `offset`, directories first
+#[derive(Clone, Debug)]
+pub struct ItemsPage {
+    pub directory_id: Uuid,
+    pub offset: usize,
+    pub directories: Vec<Directory>,
+    pub files: Vec<File>,
+    pub total_items: usize,
+}
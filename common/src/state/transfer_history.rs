@@ -0,0 +1,172 @@
+//! The uploads and downloads which finished or failed, newest first. Kept next to the state in a
+//! file of its own, since it grows with every transfer and nothing else needs it.
+
+use std::{collections::VecDeque, fs};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::STATIC_ARGS;
+
+// older records are dropped
+const MAX_RECORDS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Upload,
+    Download,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferRecord {
+    pub file: String,
+    pub size: usize,
+    pub direction: Direction,
+    pub outcome: Outcome,
+    pub started: DateTime<Utc>,
+    pub finished: DateTime<Utc>,
+    // set for attachments of a conversation
+    pub conversation_id: Option<Uuid>,
+}
+
+impl TransferRecord {
+    pub fn duration(&self) -> chrono::Duration {
+        self.finished - self.started
+    }
+}
+
+/// What the history is narrowed down to. `None` matches everything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    pub direction: Option<Direction>,
+    pub outcome: Option<Outcome>,
+    // part of the file name, ignoring case
+    pub text: String,
+}
+
+impl HistoryFilter {
+    fn matches(&self, record: &TransferRecord) -> bool {
+        self.direction.map_or(true, |d| d == record.direction)
+            && self.outcome.map_or(true, |o| o == record.outcome)
+            && record
+                .file
+                .to_lowercase()
+                .contains(&self.text.trim().to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransferHistory {
+    records: VecDeque<TransferRecord>,
+}
+
+impl TransferHistory {
+    pub fn load() -> Self {
+        let records = match fs::read_to_string(STATIC_ARGS.transfer_history_path()) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                log::error!("failed to deserialize the transfer history: {e}");
+                VecDeque::new()
+            }),
+            Err(_) => VecDeque::new(),
+        };
+        Self { records }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(&self.records) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(STATIC_ARGS.transfer_history_path(), contents) {
+                    log::error!("failed to save the transfer history: {e}");
+                }
+            }
+            Err(e) => log::error!("failed to serialize the transfer history: {e}"),
+        }
+    }
+
+    pub fn record(&mut self, record: TransferRecord) {
+        self.push(record);
+        self.save();
+    }
+
+    fn push(&mut self, record: TransferRecord) {
+        self.records.push_front(record);
+        self.records.truncate(MAX_RECORDS);
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.save();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TransferRecord> {
+        self.records.iter()
+    }
+
+    pub fn filtered<'a>(
+        &'a self,
+        filter: &'a HistoryFilter,
+    ) -> impl Iterator<Item = &'a TransferRecord> {
+        self.records.iter().filter(|r| filter.matches(r))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(file: &str, direction: Direction, outcome: Outcome) -> TransferRecord {
+        TransferRecord {
+            file: file.into(),
+            size: 0,
+            direction,
+            outcome,
+            started: Utc::now(),
+            finished: Utc::now(),
+            conversation_id: None,
+        }
+    }
+
+    #[test]
+    fn filters_records() {
+        let mut history = TransferHistory::default();
+        history.push(record("Notes.txt", Direction::Upload, Outcome::Completed));
+        history.push(record("photo.png", Direction::Download, Outcome::Failed));
+        history.push(record(
+            "notes-old.txt",
+            Direction::Download,
+            Outcome::Completed,
+        ));
+
+        let files = |filter: HistoryFilter| -> Vec<String> {
+            history.filtered(&filter).map(|r| r.file.clone()).collect()
+        };
+        assert_eq!(files(HistoryFilter::default()).len(), 3);
+        assert_eq!(
+            files(HistoryFilter {
+                text: "NOTES".into(),
+                ..Default::default()
+            }),
+            vec!["notes-old.txt", "Notes.txt"]
+        );
+        assert_eq!(
+            files(HistoryFilter {
+                direction: Some(Direction::Download),
+                outcome: Some(Outcome::Failed),
+                ..Default::default()
+            }),
+            vec!["photo.png"]
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use crate::icons::outline::Shape as Icon;
+use crate::slash_commands::SlashCommands;
+use crate::utils::handoff::{self, Handoff};
 
 use dioxus_desktop::DesktopService;
 use dioxus_desktop::{tao::window::WindowId, DesktopContext};
@@ -14,7 +16,10 @@ use std::{
 use tracing::log;
 use uuid::Uuid;
 
-use super::{call, notifications::Notifications};
+use super::{
+    call, chat_folders::ChatFolders, network::NetworkStatus, notifications::Notifications,
+    onboarding::OnboardingStep,
+};
 
 pub type EmojiList = HashMap<String, u64>;
 
@@ -126,6 +131,37 @@ pub struct WindowMeta {
     pub minimal_view: bool, // We can use this to detect mobile or portrait mode
 }
 
+/// How emojis are drawn in the composer, messages, reactions and statuses.
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, Eq, PartialEq)]
+pub enum EmojiStyle {
+    /// Use the emoji font of the operating system
+    #[default]
+    Native,
+    /// Use the emoji set shipped with Uplink so emojis look the same on every platform
+    Bundled,
+}
+
+/// Which conversations the chat sidebar shows
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, Eq, PartialEq)]
+pub enum SidebarFilter {
+    #[default]
+    All,
+    Unread,
+    Groups,
+    Direct,
+    Favorites,
+}
+
+impl SidebarFilter {
+    pub const ALL: [SidebarFilter; 5] = [
+        SidebarFilter::All,
+        SidebarFilter::Unread,
+        SidebarFilter::Groups,
+        SidebarFilter::Direct,
+        SidebarFilter::Favorites,
+    ];
+}
+
 #[derive(Clone, Deserialize, Serialize, Eq, PartialEq)]
 pub enum Layout {
     Welcome,
@@ -133,6 +169,8 @@ pub enum Layout {
     Friends,
     Settings,
     Storage,
+    Activity,
+    Calls,
 }
 
 impl Default for Layout {
@@ -178,12 +216,24 @@ impl Default for EmojiDestination {
     }
 }
 
+/// Something to do on the files page which was started from elsewhere, like the quick switcher
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FilesIntent {
+    NewFolder,
+    // a folder in the current directory, by name
+    OpenFolder(String),
+    PreviewFile(Uuid),
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct UI {
     pub notifications: Notifications,
     // stores information related to the current call
     #[serde(skip)]
     pub call_info: call::CallInfo,
+    // what is known about the connection, updated by probing the relay
+    #[serde(skip)]
+    pub network: NetworkStatus,
     pub call_timer: bool,
     #[serde(skip)]
     pub current_debug_logger: Option<DebugLogger>,
@@ -196,12 +246,24 @@ pub struct UI {
     pub accent_color: Option<(u8, u8, u8)>,
     pub theme: Option<Theme>,
     pub font: Option<Font>,
+    // used for the text of messages. falls back to `font`
+    #[serde(default)]
+    pub message_font: Option<Font>,
+    // used for code blocks and inline code
+    #[serde(default)]
+    pub monospace_font: Option<Font>,
     pub enable_overlay: bool,
     pub active_welcome: bool,
     pub sidebar_hidden: bool,
     pub window_maximized: bool,
     pub window_size: Option<(u32, u32)>,
     pub window_position: Option<(i32, i32)>,
+    // manual UI scale for each monitor, keyed by monitor name. monitors without an entry use the system scale.
+    #[serde(default)]
+    monitor_scales: HashMap<String, f32>,
+    // name of the monitor the main window is on
+    #[serde(skip)]
+    pub current_monitor: Option<String>,
     pub metadata: WindowMeta,
     #[serde(default = "default_emojis")]
     pub emojis: EmojiCounter,
@@ -213,6 +275,8 @@ pub struct UI {
     transform_markdown_text: bool,
     #[serde(default = "bool_true")]
     transform_ascii_emojis: bool,
+    #[serde(default)]
+    pub emoji_style: EmojiStyle,
     #[serde(skip)]
     pub current_layout: Layout,
     // overlays or other windows are created via DesktopContext::new_window. they are stored here so they can be closed later.
@@ -220,6 +284,9 @@ pub struct UI {
     pub overlays: Vec<Weak<DesktopService>>,
     #[serde(default)]
     pub extensions: Extensions,
+    // commands which can be used in the chatbar
+    #[serde(skip)]
+    pub slash_commands: SlashCommands,
     #[serde(skip)]
     pub file_previews: HashMap<Uuid, WindowId>,
     #[serde(default = "bool_true")]
@@ -229,6 +296,52 @@ pub struct UI {
     pub cached_username: Option<String>,
     #[serde(skip)]
     pub ignore_focus: bool,
+    // only shown in developer mode
+    #[serde(skip)]
+    pub show_profiling_overlay: bool,
+    // the lock screen covers the app until the PIN is entered again
+    #[serde(skip)]
+    pub locked: bool,
+    #[serde(skip)]
+    pub show_quick_switcher: bool,
+    // the styles of the theme being edited, shown on top of the current theme until it is saved
+    #[serde(skip)]
+    pub theme_preview: Option<String>,
+    #[serde(skip)]
+    pub announcements: Announcements,
+    // what the files page does once it is shown, for actions started elsewhere
+    #[serde(skip)]
+    files_intent: Option<FilesIntent>,
+    // a handoff link which was opened, until its conversation is shown
+    #[serde(skip)]
+    handoff: Option<Handoff>,
+    // so a link to the conversation which is already open is applied too
+    #[serde(skip)]
+    pub handoffs_opened: u32,
+    #[serde(default)]
+    pub chat_folders: ChatFolders,
+    #[serde(default)]
+    pub sidebar_filter: SidebarFilter,
+    // the conversation shown next to the active one
+    #[serde(default)]
+    pub split_chat: Option<Uuid>,
+    // the conversation being dragged out of the sidebar
+    #[serde(skip)]
+    pub dragged_chat: Option<Uuid>,
+    // where the user is in the onboarding. only accounts created on this device start at the
+    // first step (see `use_bootstrap`): recovered accounts and older states skip it
+    #[serde(default)]
+    pub onboarding: Option<OnboardingStep>,
+}
+
+/// What screen readers read out from the live regions of the app. They only read changes, so each
+/// text replaces the one before it
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Announcements {
+    // read once the screen reader is done with what it is reading
+    pub polite: String,
+    // interrupts the screen reader, for incoming calls
+    pub assertive: String,
 }
 
 impl Default for UI {
@@ -236,6 +349,7 @@ impl Default for UI {
         Self {
             notifications: Default::default(),
             call_info: Default::default(),
+            network: Default::default(),
             call_timer: true,
             current_debug_logger: Default::default(),
             popout_media_player: Default::default(),
@@ -243,12 +357,16 @@ impl Default for UI {
             accent_color: Default::default(),
             theme: Default::default(),
             font: Default::default(),
+            message_font: Default::default(),
+            monospace_font: Default::default(),
             enable_overlay: Default::default(),
             active_welcome: Default::default(),
             sidebar_hidden: Default::default(),
             window_maximized: Default::default(),
             window_size: None,
             window_position: None,
+            monitor_scales: Default::default(),
+            current_monitor: None,
             metadata: Default::default(),
             emojis: default_emojis(),
             emoji_destination: Default::default(),
@@ -256,13 +374,28 @@ impl Default for UI {
             current_layout: Default::default(),
             overlays: Default::default(),
             extensions: Default::default(),
+            slash_commands: Default::default(),
             file_previews: Default::default(),
             show_settings_welcome: true,
             show_dev_settings: false,
             cached_username: Default::default(),
             ignore_focus: Default::default(),
+            show_profiling_overlay: false,
+            locked: false,
+            show_quick_switcher: false,
+            theme_preview: None,
+            announcements: Default::default(),
+            files_intent: None,
+            handoff: None,
+            handoffs_opened: 0,
+            chat_folders: Default::default(),
+            sidebar_filter: Default::default(),
+            split_chat: None,
+            dragged_chat: None,
+            onboarding: None,
             transform_markdown_text: true,
             transform_ascii_emojis: true,
+            emoji_style: Default::default(),
         }
     }
 }
@@ -301,6 +434,10 @@ impl Extensions {
             .map(|(id, ext)| (self.enabled_extension(id), ext))
     }
 
+    pub fn get(&self, name: &str) -> Option<&UplinkExtension> {
+        self.map.get(name)
+    }
+
     pub fn ext(&self) -> hash_map::Keys<String, UplinkExtension> {
         self.map.keys()
     }
@@ -320,6 +457,27 @@ impl Drop for UI {
 }
 
 impl UI {
+    pub fn open_handoff(&mut self, handoff: Handoff) {
+        self.handoff = Some(handoff);
+        self.handoffs_opened += 1;
+    }
+
+    /// Where the conversation should be opened, if a handoff link to it is pending. Only returned once
+    pub fn take_handoff_position(&mut self, conv_id: Uuid) -> Option<handoff::Position> {
+        let handoff = self.handoff.filter(|h| h.conversation_id == conv_id)?;
+        self.handoff = None;
+        handoff.position
+    }
+
+    pub fn set_files_intent(&mut self, intent: FilesIntent) {
+        self.files_intent = Some(intent);
+    }
+
+    /// The pending action for the files page. Only returned once
+    pub fn take_files_intent(&mut self) -> Option<FilesIntent> {
+        self.files_intent.take()
+    }
+
     pub fn track_emoji_usage(&mut self, emoji: String) {
         self.emojis.increment_emoji(emoji);
     }
@@ -336,6 +494,28 @@ impl UI {
         self.transform_markdown_text = flag;
     }
 
+    /// the manual UI scale of the monitor the main window is on
+    pub fn monitor_scale(&self) -> f32 {
+        self.current_monitor
+            .as_ref()
+            .and_then(|monitor| self.monitor_scales.get(monitor))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// sets the manual UI scale of the current monitor. a scale of 1.0 removes the override.
+    pub fn set_monitor_scale(&mut self, scale: f32) {
+        let Some(monitor) = self.current_monitor.clone() else {
+            log::warn!("attempted to set the UI scale without a known monitor");
+            return;
+        };
+        if scale == 1.0 {
+            self.monitor_scales.remove(&monitor);
+        } else {
+            self.monitor_scales.insert(monitor, scale);
+        }
+    }
+
     pub fn should_transform_ascii_emojis(&self) -> bool {
         self.transform_ascii_emojis
     }
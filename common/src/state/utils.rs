@@ -3,6 +3,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use derive_more::Display;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex, Replacer};
 use titlecase::titlecase;
@@ -19,6 +20,30 @@ pub static USER_NAME_TAGS_REGEX: Lazy<Regex> =
     Lazy::new(|| mention_regex_epattern("[A-z0-9]+#[A-z0-9]{8}"));
 pub static USER_DID_TAGS_REGEX: Lazy<Regex> =
     Lazy::new(|| mention_regex_epattern("did:key:[A-z0-9]{48}"));
+pub static GROUP_MENTION_REGEX: Lazy<Regex> =
+    Lazy::new(|| mention_regex_epattern("(?:everyone|admins)"));
+
+/// Mentions which target several members of a group at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum GroupMention {
+    #[display(fmt = "@everyone")]
+    Everyone,
+    #[display(fmt = "@admins")]
+    Admins,
+}
+
+/// Returns the widest group mention in the message. Mentions in code blocks are ignored.
+pub fn find_group_mention(message: &str) -> Option<GroupMention> {
+    let mut found = None;
+    for caps in GROUP_MENTION_REGEX.captures_iter(message) {
+        match caps.get(2).map(|m| m.as_str()) {
+            Some("@everyone") => return Some(GroupMention::Everyone),
+            Some("@admins") => found = Some(GroupMention::Admins),
+            _ => {}
+        }
+    }
+    found
+}
 
 pub fn get_available_themes() -> Vec<Theme> {
     let mut themes = vec![];
@@ -76,14 +101,35 @@ fn get_pretty_name<S: AsRef<str>>(name: S) -> String {
     last.to_string_lossy().into()
 }
 
+// font formats which can be loaded by the webview
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "woff", "woff2"];
+
+/// Returns the fonts imported by the user followed by the fonts installed on the system.
+/// Imported fonts take precedence over system fonts with the same name.
 pub fn get_available_fonts() -> Vec<Font> {
+    let mut fonts = fonts_in_dir(&STATIC_ARGS.fonts_path);
+    let mut system_fonts: Vec<Font> = system_font_dirs()
+        .iter()
+        .flat_map(|dir| fonts_in_dir(dir.as_path()))
+        .filter(|font| !fonts.iter().any(|f| f.name == font.name))
+        .collect();
+    system_fonts.sort_by(|a, b| a.name.cmp(&b.name));
+    system_fonts.dedup_by(|a, b| a.name == b.name);
+    fonts.append(&mut system_fonts);
+    fonts
+}
+
+fn fonts_in_dir(dir: &Path) -> Vec<Font> {
     let mut fonts = vec![];
 
-    for file in WalkDir::new(&STATIC_ARGS.fonts_path)
-        .into_iter()
-        .filter_map(|file| file.ok())
-    {
-        if file.metadata().map(|x| x.is_file()).unwrap_or(false) {
+    for file in WalkDir::new(dir).into_iter().filter_map(|file| file.ok()) {
+        let is_font = file
+            .path()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| FONT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+            .unwrap_or(false);
+        if is_font && file.metadata().map(|x| x.is_file()).unwrap_or(false) {
             let file_osstr = file.file_name();
             let mut pretty_name: String = file_osstr.to_str().unwrap_or_default().into();
             pretty_name = pretty_name
@@ -105,6 +151,57 @@ pub fn get_available_fonts() -> Vec<Font> {
     fonts
 }
 
+fn system_font_dirs() -> Vec<PathBuf> {
+    let mut font_dirs = vec![];
+    if cfg!(target_os = "windows") {
+        let windir = std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".into());
+        font_dirs.push(PathBuf::from(windir).join("Fonts"));
+        if let Some(local) = dirs::data_local_dir() {
+            font_dirs.push(local.join("Microsoft").join("Windows").join("Fonts"));
+        }
+    } else if cfg!(target_os = "macos") {
+        font_dirs.push(PathBuf::from("/System/Library/Fonts"));
+        font_dirs.push(PathBuf::from("/Library/Fonts"));
+        if let Some(home) = dirs::home_dir() {
+            font_dirs.push(home.join("Library").join("Fonts"));
+        }
+    } else {
+        font_dirs.push(PathBuf::from("/usr/share/fonts"));
+        font_dirs.push(PathBuf::from("/usr/local/share/fonts"));
+        if let Some(data) = dirs::data_dir() {
+            font_dirs.push(data.join("fonts"));
+        }
+        if let Some(home) = dirs::home_dir() {
+            font_dirs.push(home.join(".fonts"));
+        }
+    }
+    font_dirs
+}
+
+/// Copies a font file into the fonts folder so it stays available if the original is moved
+pub fn import_font(path: &Path) -> std::io::Result<Font> {
+    let is_font = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| FONT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false);
+    let file_name = match path.file_name() {
+        Some(name) if is_font => name,
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "unsupported font format",
+            ))
+        }
+    };
+    fs::create_dir_all(&STATIC_ARGS.fonts_path)?;
+    let dest = STATIC_ARGS.fonts_path.join(file_name);
+    fs::copy(path, &dest)?;
+    fonts_in_dir(&dest)
+        .pop()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "font not found"))
+}
+
 struct TagReplacer<'a, F: Fn(&Identity) -> String> {
     participants: &'a [Identity],
     own: &'a DID,
@@ -211,6 +308,20 @@ pub fn mention_replacement_pattern(id: &Identity, visual: bool) -> String {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_find_group_mention() {
+        assert_eq!(
+            find_group_mention("hey @admins and @everyone"),
+            Some(GroupMention::Everyone)
+        );
+        assert_eq!(
+            find_group_mention("@admins help"),
+            Some(GroupMention::Admins)
+        );
+        assert_eq!(find_group_mention("`@everyone` is a mention"), None);
+        assert_eq!(find_group_mention("mail@everyone.com"), None);
+    }
+
     #[test]
     fn test_get_pretty_name1() {
         if cfg!(windows) {
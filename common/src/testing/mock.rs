@@ -280,6 +280,7 @@ fn generate_fake_storage() -> Storage {
         directories_opened: vec![Directory::new("root")],
         directories,
         files,
+        total_items: 6,
         max_size: 1024,
         current_size: 500,
         files_in_queue_to_upload: Vec::new(),
@@ -9,6 +9,7 @@ use crate::state::{
 };
 
 pub enum UploadFileAction<T> {
+    Queued(Uuid, TransferState, String),
     Starting(Uuid, TransferState, String),
     SizeNotAvailable(PathBuf, String),
     Pausing(Uuid),
@@ -0,0 +1,14 @@
+//! Whether the app is locked (see the lock screen). Mirrors `ui.locked` of the state for what runs
+//! outside of the UI, like the responses to notifications and the control socket.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_locked(locked: bool) {
+    LOCKED.store(locked, Ordering::Relaxed);
+}
+
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::Relaxed)
+}
@@ -0,0 +1,293 @@
+//! Profile pictures arrive as full size base64 data URLs, and every `UserImage` used to send them
+//! to the webview as is. They are kept as an `Avatar`, which is hashed once when the picture is
+//! loaded and shared by every copy of the identity. This keeps downscaled copies of them, one per
+//! size they're shown at, and decodes them on a thread of its own. The least recently used copies
+//! are dropped once there are more than `MAX_ENTRIES`.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+    io::Cursor,
+    sync::{mpsc, Arc},
+};
+
+use base64::{engine::general_purpose, Engine};
+use futures::channel::oneshot;
+use image::{imageops::FilterType, ImageFormat};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tracing::log;
+
+const MAX_ENTRIES: usize = 256;
+const JPEG_QUALITY: u8 = 85;
+
+/// The size an avatar is shown at, `Small` is the size of an input. The copies are twice as big,
+/// for high density screens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AvatarSize {
+    #[default]
+    Small,
+    Medium,
+    Large,
+}
+
+impl AvatarSize {
+    fn pixels(&self) -> u32 {
+        match self {
+            AvatarSize::Small => 48,
+            AvatarSize::Medium => 96,
+            AvatarSize::Large => 192,
+        }
+    }
+}
+
+/// identifies a picture by its content, so the same one shown by many components is decoded once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AvatarKey(u64, AvatarSize);
+
+/// A profile picture. Its content is hashed when it is made, and clones share the picture instead
+/// of copying it
+#[derive(Clone, Default)]
+pub struct Avatar {
+    source: Arc<str>,
+    hash: u64,
+}
+
+impl Avatar {
+    pub fn new(source: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        Self {
+            source: source.into(),
+            hash: hasher.finish(),
+        }
+    }
+
+    /// the picture at its full size
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.source.is_empty()
+    }
+
+    pub fn key(&self, size: AvatarSize) -> AvatarKey {
+        AvatarKey(self.hash, size)
+    }
+}
+
+impl From<String> for Avatar {
+    fn from(source: String) -> Self {
+        Self::new(&source)
+    }
+}
+
+impl From<&str> for Avatar {
+    fn from(source: &str) -> Self {
+        Self::new(source)
+    }
+}
+
+impl PartialEq for Avatar {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.source, &other.source)
+            || (self.hash == other.hash && self.source == other.source)
+    }
+}
+
+impl Eq for Avatar {}
+
+// the pictures are too long to be logged
+impl fmt::Debug for Avatar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Avatar")
+            .field("hash", &self.hash)
+            .field("len", &self.source.len())
+            .finish()
+    }
+}
+
+// saved as the picture itself, the way it was before it was hashed
+impl Serialize for Avatar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Avatar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+struct Entry {
+    // pictures which aren't downscaled share the source of their avatar
+    url: Arc<str>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<AvatarKey, Entry>,
+    clock: u64,
+}
+
+impl Cache {
+    fn get(&mut self, key: &AvatarKey) -> Option<Arc<str>> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(entry.url.clone())
+    }
+
+    fn insert(&mut self, key: AvatarKey, url: Arc<str>) {
+        self.clock += 1;
+        if self.entries.len() >= MAX_ENTRIES && !self.entries.contains_key(&key) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                url,
+                last_used: self.clock,
+            },
+        );
+    }
+}
+
+struct Job {
+    avatar: Avatar,
+    size: AvatarSize,
+    rsp: oneshot::Sender<Arc<str>>,
+}
+
+static CACHE: Lazy<Mutex<Cache>> = Lazy::new(Default::default);
+static DECODER: Lazy<Mutex<mpsc::Sender<Job>>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel::<Job>();
+    std::thread::spawn(move || {
+        while let Ok(job) = rx.recv() {
+            let key = job.avatar.key(job.size);
+            // asked for by several components before the first one was done
+            let url = match CACHE.lock().get(&key) {
+                Some(url) => url,
+                None => {
+                    let url = downscale(job.avatar.source(), job.size)
+                        .map(Arc::from)
+                        .unwrap_or_else(|| job.avatar.source.clone());
+                    CACHE.lock().insert(key, url.clone());
+                    url
+                }
+            };
+            let _ = job.rsp.send(url);
+        }
+    });
+    Mutex::new(tx)
+});
+
+/// the copy of the picture, if it was already made
+pub fn cached(key: &AvatarKey) -> Option<Arc<str>> {
+    CACHE.lock().get(key)
+}
+
+/// the copy of the picture for `size`, made in the background if needed. pictures which can't be
+/// decoded, or are smaller than the size, are returned as they are
+pub async fn load(avatar: Avatar, size: AvatarSize) -> Arc<str> {
+    if let Some(url) = cached(&avatar.key(size)) {
+        return url;
+    }
+    if !avatar.source().starts_with("data:") {
+        return avatar.source;
+    }
+    let (tx, rx) = oneshot::channel();
+    let job = Job {
+        avatar: avatar.clone(),
+        size,
+        rsp: tx,
+    };
+    if DECODER.lock().send(job).is_err() {
+        return avatar.source;
+    }
+    rx.await.unwrap_or(avatar.source)
+}
+
+fn downscale(source: &str, size: AvatarSize) -> Option<String> {
+    let (_, data) = source.split_once(";base64,")?;
+    let bytes = general_purpose::STANDARD.decode(data).ok()?;
+    let image = match image::load_from_memory(&bytes) {
+        Ok(image) => image,
+        Err(e) => {
+            log::debug!("failed to decode avatar: {e}");
+            return None;
+        }
+    };
+    let pixels = size.pixels() * 2;
+    if image.width() <= pixels && image.height() <= pixels {
+        return None;
+    }
+    let image = image.resize_to_fill(pixels, pixels, FilterType::Triangle);
+
+    let mut out = Cursor::new(vec![]);
+    let mime = if image.color().has_alpha() {
+        image.write_to(&mut out, ImageFormat::Png).ok()?;
+        "image/png"
+    } else {
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY);
+        image.to_rgb8().write_with_encoder(encoder).ok()?;
+        "image/jpeg"
+    };
+    Some(format!(
+        "data:{mime};base64,{}",
+        general_purpose::STANDARD.encode(out.into_inner())
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(source: &str) -> AvatarKey {
+        Avatar::new(source).key(AvatarSize::Small)
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = Cache::default();
+        for i in 0..MAX_ENTRIES {
+            cache.insert(key(&i.to_string()), i.to_string().into());
+        }
+        let first = key("0");
+        assert!(cache.get(&first).is_some());
+        cache.insert(key("new"), "new".into());
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert!(cache.get(&first).is_some());
+        assert!(cache.get(&key("1")).is_none());
+    }
+
+    #[test]
+    fn avatars_are_hashed_once_and_shared() {
+        let avatar = Avatar::new("data:image/png;base64,AAAA");
+        let copy = avatar.clone();
+        assert!(Arc::ptr_eq(&avatar.source, &copy.source));
+        assert_eq!(copy.key(AvatarSize::Small), avatar.key(AvatarSize::Small));
+        assert_ne!(
+            avatar.key(AvatarSize::Small),
+            avatar.key(AvatarSize::Medium)
+        );
+
+        let saved = serde_json::to_string(&avatar).unwrap();
+        assert_eq!(saved, "\"data:image/png;base64,AAAA\"");
+        let loaded: Avatar = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded, avatar);
+        assert_eq!(loaded.key(AvatarSize::Small), avatar.key(AvatarSize::Small));
+    }
+}
@@ -0,0 +1,322 @@
+//! Links a new device to the account of this one. The linked device shows a pairing code which
+//! contains its address and a one time secret. The new device connects, introduces itself with a
+//! name encrypted with the secret and then receives a backup of the account, also encrypted with the
+//! secret. Warp syncs friends and conversations once the new device logs in with the same identity.
+//! Both devices need to be on the same network.
+//!
+//! Every linked device gets an id of its own, which it keeps in its profile. Warp knows a single key
+//! pair per account, so the devices share it, and revoking a device adds its id to a list in the
+//! metadata of the identity instead. A linked device which finds itself in the list when logging in
+//! deletes its copy of the account. The device which created the account has no id and can't be
+//! revoked.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{self, Read, Write},
+    net::{IpAddr, SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+use warp::crypto::cipher::Cipher;
+
+use crate::STATIC_ARGS;
+
+const SCHEME: &str = "uplink-link:";
+const SECRET_LEN: usize = 16;
+// a device name plus the encryption overhead
+const MAX_HELLO_LEN: u64 = 4096;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// the bundle is only sent once the user allowed the link and the export finished
+const IO_TIMEOUT: Duration = Duration::from_secs(300);
+// the identity metadata with the ids of the revoked devices, one per line
+pub const REVOKED_DEVICES_KEY: &str = "revoked-devices";
+/// the login error of a device which was revoked
+pub const REVOKED: &str = "this device was removed from the account";
+const DEVICE_FILE: &str = "device.json";
+/// Files of the profile which aren't sent to a new device. They describe this device or are local
+/// copies which the new device builds up again.
+pub const NOT_SHARED: &[&str] = &[
+    DEVICE_FILE,
+    "messages.db",
+    "storage_backend.json",
+    "checksums.json",
+    "transfer_history.json",
+    "activity.json",
+];
+
+#[derive(Serialize, Deserialize)]
+struct ThisDevice {
+    id: Uuid,
+}
+
+/// The id this device got when it was linked, `None` on the device which created the account
+pub fn this_device() -> Option<Uuid> {
+    let data = std::fs::read(STATIC_ARGS.uplink_path().join(DEVICE_FILE)).ok()?;
+    serde_json::from_slice::<ThisDevice>(&data)
+        .ok()
+        .map(|device| device.id)
+}
+
+/// Remembers the id of this device, in the profile the linked account was restored into
+pub fn set_this_device(id: Uuid) -> anyhow::Result<()> {
+    let data = serde_json::to_vec(&ThisDevice { id })?;
+    std::fs::write(STATIC_ARGS.uplink_path().join(DEVICE_FILE), data)?;
+    Ok(())
+}
+
+/// The devices revoked by any device of the account
+pub fn revoked_devices(metadata: &HashMap<String, String>) -> Vec<Uuid> {
+    metadata
+        .get(REVOKED_DEVICES_KEY)
+        .map(|list| list.lines().filter_map(|id| id.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+pub fn revoked_devices_value(devices: &[Uuid]) -> String {
+    devices
+        .iter()
+        .map(Uuid::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether another device of the account revoked this one
+pub fn is_revoked(metadata: &HashMap<String, String>) -> bool {
+    this_device().map_or(false, |id| revoked_devices(metadata).contains(&id))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairingCode {
+    pub addr: SocketAddr,
+    secret: String,
+}
+
+impl PairingCode {
+    /// the passphrase of the account bundle
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+}
+
+impl fmt::Display for PairingCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SCHEME}{}/{}", self.addr, self.secret)
+    }
+}
+
+impl FromStr for PairingCode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, secret) = s
+            .trim()
+            .strip_prefix(SCHEME)
+            .and_then(|s| s.rsplit_once('/'))
+            .context("not a pairing code")?;
+        if secret.len() != SECRET_LEN * 2 || !secret.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("invalid pairing secret");
+        }
+        Ok(Self {
+            addr: addr.parse()?,
+            secret: secret.to_ascii_lowercase(),
+        })
+    }
+}
+
+/// Waits for a new device on the device which is already logged in
+pub struct LinkOffer {
+    listener: TcpListener,
+    code: PairingCode,
+}
+
+impl LinkOffer {
+    pub fn bind(ip: IpAddr) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind((ip, 0))?;
+        let mut secret = [0_u8; SECRET_LEN];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let code = PairingCode {
+            addr: listener.local_addr()?,
+            secret: secret.iter().map(|b| format!("{b:02x}")).collect(),
+        };
+        Ok(Self { listener, code })
+    }
+
+    pub fn code(&self) -> &PairingCode {
+        &self.code
+    }
+
+    /// Waits until a device which knows the secret connects. Other connections are dropped. Fails once
+    /// `timeout` passed or `cancelled` is set.
+    pub async fn accept(
+        self,
+        timeout: Duration,
+        cancelled: Arc<AtomicBool>,
+    ) -> anyhow::Result<LinkRequest> {
+        tokio::task::spawn_blocking(move || {
+            let deadline = Instant::now() + timeout;
+            self.listener.set_nonblocking(true)?;
+            loop {
+                if cancelled.load(Ordering::Relaxed) {
+                    bail!("device linking was cancelled");
+                }
+                if Instant::now() > deadline {
+                    bail!("no device connected in time");
+                }
+                let mut stream = match self.listener.accept() {
+                    Ok((stream, _)) => stream,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(200));
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+                let hello = read_frame(&mut stream, MAX_HELLO_LEN)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|frame| decrypt(&frame, &self.code.secret));
+                match hello.and_then(|name| Ok(String::from_utf8(name)?)) {
+                    Ok(device_name) => {
+                        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+                        return Ok(LinkRequest {
+                            stream,
+                            device_name,
+                            secret: self.code.secret,
+                        });
+                    }
+                    Err(e) => log::warn!("dropped a device link attempt: {e}"),
+                }
+            }
+        })
+        .await?
+    }
+}
+
+/// A device which knows the pairing secret and waits for the account
+pub struct LinkRequest {
+    stream: TcpStream,
+    pub device_name: String,
+    secret: String,
+}
+
+impl LinkRequest {
+    /// the passphrase to encrypt the account bundle with
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    /// sends the id of the new device and a backup which was encrypted with the pairing secret
+    pub async fn send_bundle(mut self, device_id: Uuid, bundle: PathBuf) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking(move || {
+            let id = Cipher::direct_encrypt(device_id.as_bytes(), self.secret.as_bytes())?;
+            write_frame(&mut self.stream, &id)?;
+            let mut file = File::open(bundle)?;
+            let len = file.metadata()?.len();
+            self.stream.write_all(&len.to_be_bytes())?;
+            io::copy(&mut file, &mut self.stream)?;
+            self.stream.flush()?;
+            Ok(())
+        })
+        .await?
+    }
+}
+
+/// Connects to the device showing `code` and writes the account bundle it sends to `dest`. The bundle
+/// is restored with `TesseractCmd::RestoreBackup`, using the secret of the code as passphrase, and the
+/// returned id of this device is saved with `set_this_device` afterwards.
+pub async fn join(code: PairingCode, device_name: String, dest: PathBuf) -> anyhow::Result<Uuid> {
+    tokio::task::spawn_blocking(move || {
+        let mut stream = TcpStream::connect_timeout(&code.addr, CONNECT_TIMEOUT)?;
+        stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+        // the other device waits for the user to allow the link before sending anything
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+
+        let hello = Cipher::direct_encrypt(device_name.as_bytes(), code.secret.as_bytes())?;
+        write_frame(&mut stream, &hello)?;
+
+        let id = read_frame(&mut stream, MAX_HELLO_LEN)?;
+        let device_id = Uuid::from_slice(&decrypt(&id, &code.secret)?)?;
+
+        let mut len = [0_u8; 8];
+        stream.read_exact(&mut len)?;
+        let len = u64::from_be_bytes(len);
+        let mut file = File::create(&dest)?;
+        let copied = io::copy(&mut (&mut stream).take(len), &mut file)?;
+        if copied != len {
+            drop(file);
+            let _ = std::fs::remove_file(&dest);
+            bail!("the connection closed before the account was received");
+        }
+        file.sync_all()?;
+        Ok(device_id)
+    })
+    .await?
+}
+
+fn decrypt(data: &[u8], secret: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(Cipher::direct_decrypt(data, secret.as_bytes())?)
+}
+
+fn write_frame(stream: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    stream.write_all(&(data.len() as u64).to_be_bytes())?;
+    stream.write_all(data)
+}
+
+fn read_frame(stream: &mut impl Read, max_len: u64) -> io::Result<Vec<u8>> {
+    let mut len = [0_u8; 8];
+    stream.read_exact(&mut len)?;
+    let len = u64::from_be_bytes(len);
+    if len > max_len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "frame too large",
+        ));
+    }
+    let mut data = vec![0; len as usize];
+    stream.read_exact(&mut data)?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pairing_code_round_trip() {
+        let offer = LinkOffer::bind("127.0.0.1".parse().unwrap()).unwrap();
+        let code = offer.code().to_string();
+        assert!(code.starts_with(SCHEME));
+        assert_eq!(&code.parse::<PairingCode>().unwrap(), offer.code());
+
+        assert!("uplink-link:127.0.0.1:1/abc"
+            .parse::<PairingCode>()
+            .is_err());
+        assert!("127.0.0.1:1/00000000000000000000000000000000"
+            .parse::<PairingCode>()
+            .is_err());
+    }
+
+    #[test]
+    fn revoked_devices_round_trip() {
+        let devices = vec![Uuid::new_v4(), Uuid::new_v4()];
+        let metadata = HashMap::from([(
+            REVOKED_DEVICES_KEY.to_string(),
+            revoked_devices_value(&devices),
+        )]);
+        assert_eq!(revoked_devices(&metadata), devices);
+        assert!(revoked_devices(&HashMap::new()).is_empty());
+    }
+}
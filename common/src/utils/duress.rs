@@ -0,0 +1,149 @@
+//! An optional second PIN for users who could be forced to unlock their account. Entering it at the
+//! unlock screen either deletes the data of the profile or opens an empty decoy profile instead. The
+//! files are deleted like any other, they aren't overwritten and may be recoverable from the disk.
+//! Only a salted hash of the PIN is stored, next to the data of the profile it protects.
+
+use std::path::PathBuf;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{profiles::PROFILES_DIR, STATIC_ARGS};
+
+const DURESS_FILE: &str = "duress.json";
+// left behind when the duress PIN is entered at the lock screen, see schedule_wipe
+const PENDING_WIPE_FILE: &str = "duress_wipe";
+// makes guessing the PIN from the file slower
+const HASH_ROUNDS: usize = 100_000;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuressAction {
+    /// deletes the identity, messages and settings of the profile
+    #[default]
+    Wipe,
+    /// opens an empty profile which isn't listed with the other profiles
+    Decoy,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DuressPin {
+    salt: String,
+    hash: String,
+    action: DuressAction,
+    // the decoy account is created the first time the decoy is opened
+    decoy: Uuid,
+}
+
+/// sets the duress PIN of the current profile, replacing the previous one
+pub fn set(pin: &str, action: DuressAction) -> anyhow::Result<()> {
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let salt = to_hex(&salt);
+    let decoy = load().map(|d| d.decoy).unwrap_or_else(Uuid::new_v4);
+    let duress = DuressPin {
+        hash: hash(pin, &salt),
+        salt,
+        action,
+        decoy,
+    };
+    std::fs::write(path(), serde_json::to_string(&duress)?)?;
+    Ok(())
+}
+
+/// changes what the duress PIN does without changing the PIN
+pub fn set_action(action: DuressAction) -> anyhow::Result<()> {
+    let Some(mut duress) = load() else {
+        anyhow::bail!("no duress PIN is set");
+    };
+    duress.action = action;
+    std::fs::write(path(), serde_json::to_string(&duress)?)?;
+    Ok(())
+}
+
+/// removes the duress PIN and the decoy profile, if one was created. the PIN is removed even if the
+/// decoy couldn't be
+pub fn clear() -> anyhow::Result<()> {
+    let decoy = match decoy_path() {
+        Some(decoy) if decoy.exists() => std::fs::remove_dir_all(decoy),
+        _ => Ok(()),
+    };
+    match std::fs::remove_file(path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(decoy?),
+    }
+}
+
+/// the data of a profile which is logged in can't be deleted while warp is using it. Uplink exits
+/// instead and the profile is wiped when it starts again, before anything is shown
+pub fn schedule_wipe() -> anyhow::Result<()> {
+    std::fs::write(STATIC_ARGS.uplink_path().join(PENDING_WIPE_FILE), [])?;
+    Ok(())
+}
+
+/// whether the profile has to be wiped before it is opened
+pub fn wipe_pending() -> bool {
+    STATIC_ARGS.uplink_path().join(PENDING_WIPE_FILE).exists()
+}
+
+/// the action of the duress PIN of the current profile, if one is set
+pub fn action() -> Option<DuressAction> {
+    load().map(|d| d.action)
+}
+
+/// returns what to do if `pin` is the duress PIN of the current profile
+pub fn check(pin: &str) -> Option<DuressAction> {
+    load()
+        .filter(|d| hash(pin, &d.salt) == d.hash)
+        .map(|d| d.action)
+}
+
+/// where the decoy profile of the current profile keeps its data
+pub fn decoy_path() -> Option<PathBuf> {
+    load().map(|d| {
+        STATIC_ARGS
+            .dot_uplink
+            .join(PROFILES_DIR)
+            .join(d.decoy.to_string())
+    })
+}
+
+fn load() -> Option<DuressPin> {
+    let contents = std::fs::read_to_string(path()).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn path() -> PathBuf {
+    STATIC_ARGS.uplink_path().join(DURESS_FILE)
+}
+
+fn hash(pin: &str, salt: &str) -> String {
+    let mut digest = Sha256::new()
+        .chain_update(salt)
+        .chain_update(pin)
+        .finalize();
+    for _ in 1..HASH_ROUNDS {
+        digest = Sha256::new()
+            .chain_update(salt)
+            .chain_update(digest)
+            .finalize();
+    }
+    to_hex(&digest)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hash_depends_on_salt() {
+        assert_eq!(hash("1234", "salt"), hash("1234", "salt"));
+        assert_ne!(hash("1234", "salt"), hash("1235", "salt"));
+        assert_ne!(hash("1234", "salt"), hash("1234", "pepper"));
+    }
+}
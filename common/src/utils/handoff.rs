@@ -0,0 +1,204 @@
+//! Links which open a conversation on another device of the same account, scrolled to where it was
+//! left. Linked devices share the conversations (see device_link), so the link only needs to say
+//! which one and which message was at the top of the view.
+//!
+//! The installers register Uplink for the `uplink` scheme, so clicking a link launches Uplink with
+//! it (or, on macOS, sends it to the running app). A new instance forwards the link to the one
+//! which already runs, over a socket only the user can open, and exits.
+
+use std::{fmt, io, str::FromStr};
+
+use anyhow::Context;
+use chrono::{DateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::{
+    notifications::{NotificationAction, NOTIFICATION_LISTENER},
+    STATIC_ARGS,
+};
+
+const PREFIX: &str = "uplink://open/";
+// longer lines from the socket aren't links
+const MAX_LINK_LEN: u64 = 1024;
+
+// the link which is opened once the conversations are loaded
+static PENDING: Lazy<Mutex<Option<Handoff>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handoff {
+    pub conversation_id: Uuid,
+    // `None` opens the conversation at the most recent messages
+    pub position: Option<Position>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub message_id: Uuid,
+    // the messages around it are fetched by date
+    pub date: DateTime<Utc>,
+}
+
+impl fmt::Display for Handoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{PREFIX}{}", self.conversation_id)?;
+        if let Some(position) = self.position {
+            write!(
+                f,
+                "/{}/{}",
+                position.message_id,
+                position.date.timestamp_millis()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Handoff {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s
+            .trim()
+            .strip_prefix(PREFIX)
+            .context("not a handoff link")?
+            .split('/');
+        let conversation_id = parts.next().unwrap_or_default().parse()?;
+        let position = match (parts.next(), parts.next()) {
+            (Some(message_id), Some(millis)) => Some(Position {
+                message_id: message_id.parse()?,
+                date: Utc
+                    .timestamp_millis_opt(millis.parse()?)
+                    .single()
+                    .context("invalid message date")?,
+            }),
+            (None, None) => None,
+            _ => anyhow::bail!("invalid handoff position"),
+        };
+        Ok(Self {
+            conversation_id,
+            position,
+        })
+    }
+}
+
+/// Opens the conversation of the link once the user is logged in and the conversations are loaded.
+/// Only the newest link is kept until then
+pub fn open(handoff: Handoff) {
+    *PENDING.lock() = Some(handoff);
+    let _ = NOTIFICATION_LISTENER
+        .tx
+        .send(NotificationAction::OpenHandoff);
+}
+
+/// The link which should be opened, see `open`. Only returned once
+pub fn take_pending() -> Option<Handoff> {
+    PENDING.lock().take()
+}
+
+/// Passes the link to the Uplink which already runs. Returns false if none does
+pub fn forward(handoff: Handoff) -> bool {
+    use std::io::Write;
+
+    let path = STATIC_ARGS.links_socket_path();
+    #[cfg(unix)]
+    let stream = std::os::unix::net::UnixStream::connect(&path);
+    // the pipe is opened like a file
+    #[cfg(windows)]
+    let stream = std::fs::OpenOptions::new().write(true).open(&path);
+    match stream.and_then(|mut stream| writeln!(stream, "{handoff}")) {
+        Ok(()) => true,
+        Err(e) => {
+            log::debug!("no running Uplink to open the link: {e}");
+            false
+        }
+    }
+}
+
+/// Opens the links forwarded by new instances until an error occurs. Only the user can connect,
+/// the socket is created with 0600 permissions. Returns right away if another Uplink listens
+#[cfg(unix)]
+pub async fn listen() -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = STATIC_ARGS.links_socket_path();
+    if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+        log::info!("another Uplink opens the links");
+        return Ok(());
+    }
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(read_links(stream));
+    }
+}
+
+/// Opens the links forwarded by new instances until an error occurs. Only the user can open the
+/// pipe, see `control::pipe`. Fails if another Uplink listens
+#[cfg(windows)]
+pub async fn listen() -> io::Result<()> {
+    use crate::control::pipe;
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let path = STATIC_ARGS.links_socket_path();
+    let mut security = pipe::OwnerOnly::new()?;
+    let mut server = unsafe {
+        ServerOptions::new()
+            .first_pipe_instance(true)
+            .reject_remote_clients(true)
+            .create_with_security_attributes_raw(&path, security.as_ptr())?
+    };
+    loop {
+        server.connect().await?;
+        let next = unsafe {
+            ServerOptions::new()
+                .reject_remote_clients(true)
+                .create_with_security_attributes_raw(&path, security.as_ptr())?
+        };
+        let client = std::mem::replace(&mut server, next);
+        tokio::spawn(read_links(client));
+    }
+}
+
+async fn read_links<S: AsyncRead + Unpin>(stream: S) {
+    let mut lines = BufReader::new(stream.take(MAX_LINK_LEN)).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        match line.parse::<Handoff>() {
+            Ok(handoff) => open(handoff),
+            Err(e) => log::warn!("ignoring a forwarded link: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn handoff_round_trip() {
+        let mut handoff = Handoff {
+            conversation_id: Uuid::new_v4(),
+            position: None,
+        };
+        assert_eq!(handoff.to_string().parse::<Handoff>().unwrap(), handoff);
+
+        handoff.position = Some(Position {
+            message_id: Uuid::new_v4(),
+            date: Utc.timestamp_millis_opt(1_700_000_000_123).unwrap(),
+        });
+        assert_eq!(handoff.to_string().parse::<Handoff>().unwrap(), handoff);
+
+        assert!("uplink://join/abc".parse::<Handoff>().is_err());
+        assert!(format!("{PREFIX}{}/{}", Uuid::new_v4(), Uuid::new_v4())
+            .parse::<Handoff>()
+            .is_err());
+    }
+}
@@ -0,0 +1,358 @@
+//! Lets bots and scripts post into a conversation, like the incoming webhooks of other chat apps.
+//! Every bot registered in the settings of a conversation gets a URL on this device:
+//!
+//! ```text
+//! curl -X POST -d '{"text": "build passed"}' http://127.0.0.1:<port>/hooks/<token>
+//! ```
+//!
+//! The message is sent from the account of the user, under a line with the name of the bot, so the
+//! other participants see who posted it. The line starts with an invisible marker, which Uplink
+//! shows as the badge of the bot. The marker is removed from the messages typed by the user, so
+//! only this endpoint posts as a bot. Clients which don't know about it show the line as it is.
+//! The endpoint only listens on loopback; a relay on another machine (a reverse proxy or a tunnel)
+//! can forward to it. It is started with the app. The first time, the system picks a free port,
+//! which the profile keeps so the URLs of its bots don't change. If another program took that port
+//! in the meantime, another one is picked and the settings of the bots say so.
+
+use std::{collections::HashMap, time::Duration};
+
+use futures::channel::oneshot;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::{
+    state::chats::ChatWebhooks,
+    warp_runner::{RayGunCmd, WarpCmd},
+    STATIC_ARGS, WARP_CMD_CH,
+};
+
+const PATH_PREFIX: &str = "/hooks/";
+// U+2063 INVISIBLE SEPARATOR, before the name of the bot
+const BOT_MARKER: char = '\u{2063}';
+// the request line and headers, and the body
+const MAX_HEAD: u64 = 8 * 1024;
+const MAX_BODY: usize = 64 * 1024;
+// a client which doesn't send its request in time is dropped
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// token -> (conversation, name of the bot)
+static BOTS: Lazy<RwLock<HashMap<String, (Uuid, String)>>> = Lazy::new(Default::default);
+static STATUS: Lazy<RwLock<Status>> = Lazy::new(Default::default);
+
+/// Whether bots can post
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Status {
+    #[default]
+    Stopped,
+    Starting,
+    Listening {
+        port: u16,
+        // the port of the profile, when another program had taken it
+        taken: Option<u16>,
+    },
+    Failed(String),
+}
+
+// what the profile keeps
+#[derive(Default, Serialize, Deserialize)]
+struct Saved {
+    port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Reply(u16, &'static str);
+
+const NO_CONTENT: Reply = Reply(204, "No Content");
+const BAD_REQUEST: Reply = Reply(400, "Bad Request");
+const NOT_FOUND: Reply = Reply(404, "Not Found");
+const METHOD_NOT_ALLOWED: Reply = Reply(405, "Method Not Allowed");
+const PAYLOAD_TOO_LARGE: Reply = Reply(413, "Payload Too Large");
+const BAD_GATEWAY: Reply = Reply(502, "Bad Gateway");
+
+#[derive(Deserialize)]
+struct Post {
+    text: String,
+}
+
+/// Registers the bots of every conversation
+pub fn configure(webhooks: &HashMap<Uuid, ChatWebhooks>) {
+    *BOTS.write() = webhooks
+        .iter()
+        .flat_map(|(chat_id, webhooks)| {
+            webhooks
+                .incoming
+                .iter()
+                .map(|bot| (bot.token.clone(), (*chat_id, bot.name.clone())))
+        })
+        .collect();
+}
+
+/// Starts the endpoint, unless it is running. Called when the app starts and to try again after
+/// it failed
+pub fn start() {
+    {
+        let mut status = STATUS.write();
+        match &*status {
+            Status::Starting => return,
+            Status::Listening { port, .. } => {
+                // the profile which was opened keeps this port from now on
+                save_port(*port);
+                return;
+            }
+            Status::Stopped | Status::Failed(_) => *status = Status::Starting,
+        }
+    }
+    std::thread::spawn(|| {
+        match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime.block_on(listen()),
+            Err(e) => {
+                log::error!("failed to start incoming webhook runtime: {e}");
+                *STATUS.write() = Status::Failed(e.to_string());
+            }
+        }
+    });
+}
+
+pub fn status() -> Status {
+    STATUS.read().clone()
+}
+
+/// The URL a bot posts to, if the endpoint is running
+pub fn url(token: &str) -> Option<String> {
+    match *STATUS.read() {
+        Status::Listening { port, .. } => {
+            Some(format!("http://127.0.0.1:{port}{PATH_PREFIX}{token}"))
+        }
+        _ => None,
+    }
+}
+
+/// The lines of a message posted by a bot
+pub fn bot_message(name: &str, text: &str) -> Vec<String> {
+    std::iter::once(format!("{BOT_MARKER}🤖 **{}**", name.trim()))
+        .chain(text.lines().map(String::from))
+        .collect()
+}
+
+/// The name of the bot and the text of a message it posted, `None` for other messages
+pub fn bot_post(lines: &[String]) -> Option<(String, String)> {
+    let (first, text) = lines.split_first()?;
+    let name = first
+        .strip_prefix(BOT_MARKER)?
+        .strip_prefix("🤖 **")?
+        .strip_suffix("**")?;
+    Some((name.to_string(), text.join("\n")))
+}
+
+/// Removes the marker of bot posts from a message which wasn't posted by a bot
+pub fn escape(mut lines: Vec<String>) -> Vec<String> {
+    if let Some(first) = lines.first_mut() {
+        if first.starts_with(BOT_MARKER) {
+            *first = first.trim_start_matches(BOT_MARKER).to_string();
+        }
+    }
+    lines
+}
+
+async fn listen() {
+    let listener = match bind().await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("failed to listen for incoming webhooks: {e}");
+            *STATUS.write() = Status::Failed(e.to_string());
+            return;
+        }
+    };
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(async move {
+                    if let Err(e) = handle(stream).await {
+                        log::debug!("incoming webhook failed: {e}");
+                    }
+                });
+            }
+            Err(e) => log::warn!("failed to accept incoming webhook: {e}"),
+        }
+    }
+}
+
+// the port of the profile, or a free one if it has none or it was taken
+async fn bind() -> std::io::Result<TcpListener> {
+    let saved: Saved = std::fs::read(STATIC_ARGS.incoming_webhooks_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+    let mut taken = None;
+    if let Some(port) = saved.port {
+        match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => {
+                log::info!("listening for incoming webhooks on port {port}");
+                *STATUS.write() = Status::Listening { port, taken };
+                return Ok(listener);
+            }
+            Err(e) => {
+                log::warn!("port {port} of the incoming webhooks is taken: {e}");
+                taken = Some(port);
+            }
+        }
+    }
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+    let port = listener.local_addr()?.port();
+    save_port(port);
+    log::info!("listening for incoming webhooks on port {port}");
+    *STATUS.write() = Status::Listening { port, taken };
+    Ok(listener)
+}
+
+fn save_port(port: u16) {
+    let saved = Saved { port: Some(port) };
+    let result = serde_json::to_vec(&saved)
+        .map_err(std::io::Error::from)
+        .and_then(|bytes| std::fs::write(STATIC_ARGS.incoming_webhooks_path(), bytes));
+    if let Err(e) = result {
+        log::error!("failed to save the port of the incoming webhooks: {e}");
+    }
+}
+
+async fn handle(mut stream: TcpStream) -> std::io::Result<()> {
+    let status = match tokio::time::timeout(TIMEOUT, read_post(&mut stream)).await {
+        Ok(Ok((token, body))) => post(&token, &body).await,
+        Ok(Err(status)) => status,
+        Err(_) => return Ok(()),
+    };
+    let Reply(code, reason) = status;
+    stream
+        .write_all(
+            format!("HTTP/1.1 {code} {reason}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .as_bytes(),
+        )
+        .await
+}
+
+// the token in the path and the body of the request
+async fn read_post(stream: &mut TcpStream) -> Result<(String, Vec<u8>), Reply> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    {
+        let mut limited = (&mut reader).take(MAX_HEAD);
+        loop {
+            let read = limited
+                .read_line(&mut head)
+                .await
+                .map_err(|_| BAD_REQUEST)?;
+            if read == 0 {
+                return Err(BAD_REQUEST);
+            }
+            if head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+                break;
+            }
+        }
+    }
+    let (token, length) = parse_head(&head)?;
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|_| BAD_REQUEST)?;
+    Ok((token, body))
+}
+
+// the token and the length of the body
+fn parse_head(head: &str) -> Result<(String, usize), Reply> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (method, path) = (request_line.next(), request_line.next());
+    let token = path
+        .and_then(|path| path.strip_prefix(PATH_PREFIX))
+        .filter(|token| !token.is_empty() && !token.contains('/'))
+        .ok_or(NOT_FOUND)?;
+    if method != Some("POST") {
+        return Err(METHOD_NOT_ALLOWED);
+    }
+    let length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .ok_or(BAD_REQUEST)?
+        .1
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| BAD_REQUEST)?;
+    if length > MAX_BODY {
+        return Err(PAYLOAD_TOO_LARGE);
+    }
+    Ok((token.to_string(), length))
+}
+
+async fn post(token: &str, body: &[u8]) -> Reply {
+    let Some((conv_id, name)) = BOTS.read().get(token).cloned() else {
+        return NOT_FOUND;
+    };
+    let text = match serde_json::from_slice::<Post>(body) {
+        Ok(Post { text }) if !text.trim().is_empty() => text,
+        _ => return BAD_REQUEST,
+    };
+    let (tx, rx) = oneshot::channel();
+    let cmd = WarpCmd::RayGun(RayGunCmd::PostAsBot {
+        conv_id,
+        name: name.clone(),
+        text,
+        rsp: tx,
+    });
+    if WARP_CMD_CH.tx.send(cmd).is_err() {
+        return BAD_GATEWAY;
+    }
+    match rx.await {
+        Ok(Ok(_)) => NO_CONTENT,
+        Ok(Err(e)) => {
+            log::warn!("failed to send the message of {name}: {e}");
+            BAD_GATEWAY
+        }
+        Err(_) => BAD_GATEWAY,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reads_token_and_length_of_posts() {
+        let head = "POST /hooks/abc123 HTTP/1.1\r\nHost: 127.0.0.1\r\ncontent-length: 17\r\n\r\n";
+        assert_eq!(parse_head(head), Ok(("abc123".into(), 17)));
+        assert_eq!(
+            parse_head("GET /hooks/abc123 HTTP/1.1\r\n\r\n"),
+            Err(METHOD_NOT_ALLOWED)
+        );
+        assert_eq!(parse_head("POST / HTTP/1.1\r\n\r\n"), Err(NOT_FOUND));
+        assert_eq!(
+            parse_head("POST /hooks/abc/def HTTP/1.1\r\n\r\n"),
+            Err(NOT_FOUND)
+        );
+        assert_eq!(
+            parse_head("POST /hooks/abc HTTP/1.1\r\n\r\n"),
+            Err(BAD_REQUEST)
+        );
+        assert_eq!(
+            parse_head("POST /hooks/abc HTTP/1.1\r\nContent-Length: 1000000\r\n\r\n"),
+            Err(PAYLOAD_TOO_LARGE)
+        );
+        let post = bot_message(" CI ", "build passed\nin 3 minutes");
+        assert_eq!(
+            bot_post(&post),
+            Some(("CI".into(), "build passed\nin 3 minutes".into()))
+        );
+        // typed by someone
+        assert_eq!(bot_post(&escape(post)), None);
+    }
+}
@@ -0,0 +1,266 @@
+//! Keeps the PIN of the current profile in the keychain of the OS (the Keychain on macOS and the
+//! Credential Manager on Windows) so the account can be unlocked without typing it. The PIN is only
+//! stored encrypted. Its key is held by the OS behind a check of the user: on macOS it is a Keychain
+//! item which needs Touch ID or the password of the user to be read, on Windows it is derived from
+//! a signature of a Windows Hello key. Linux has no such check, so the PIN isn't saved there. Other
+//! secrets, like the token of a pinning service, are kept in the keychain as they are. The keychain
+//! may block or show a prompt, so everything runs on a blocking thread.
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use keyring::Entry;
+
+use crate::STATIC_ARGS;
+
+const SERVICE: &str = "uplink";
+const NONCE_LEN: usize = 12;
+
+/// whether the PIN can be saved on this device, see the module docs
+pub fn available() -> bool {
+    platform::available()
+}
+
+/// encrypts the PIN with a new key. creating the key may already ask the user to confirm it's them
+pub async fn store_pin(pin: String) -> anyhow::Result<()> {
+    blocking(move || {
+        let key = platform::create_key(&account())?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let encrypted = cipher
+            .encrypt(&nonce, pin.as_bytes())
+            .map_err(|e| anyhow::anyhow!("failed to encrypt the pin: {e}"))?;
+        let stored: Vec<u8> = nonce.iter().copied().chain(encrypted).collect();
+        Ok(entry()?.set_password(&to_hex(&stored))?)
+    })
+    .await
+}
+
+/// returns None if no PIN was stored for the current profile. asks the user to confirm it's them
+/// before the PIN can be decrypted, an error is returned if they don't
+pub async fn load_pin() -> anyhow::Result<Option<String>> {
+    blocking(|| {
+        let stored = match entry()?.get_password() {
+            Ok(stored) => from_hex(&stored)?,
+            Err(keyring::Error::NoEntry) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if stored.len() <= NONCE_LEN {
+            anyhow::bail!("the saved pin is invalid");
+        }
+        let (nonce, encrypted) = stored.split_at(NONCE_LEN);
+        let key = platform::load_key(&account())?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+        let pin = cipher
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|e| anyhow::anyhow!("failed to decrypt the pin: {e}"))?;
+        Ok(Some(String::from_utf8(pin)?))
+    })
+    .await
+}
+
+pub async fn forget_pin() -> anyhow::Result<()> {
+    blocking(|| {
+        platform::delete_key(&account())?;
+        match entry()?.delete_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    })
+    .await
+}
+
+/// keeps another secret of the current profile, like the token of a pinning service
+pub async fn store_secret(name: &'static str, secret: String) -> anyhow::Result<()> {
+    blocking(move || Ok(secret_entry(name)?.set_password(&secret)?)).await
+}
+
+/// returns None if no secret with this name was stored for the current profile
+pub async fn load_secret(name: &'static str) -> anyhow::Result<Option<String>> {
+    blocking(move || match secret_entry(name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    })
+    .await
+}
+
+// each profile has its own entry
+fn entry() -> anyhow::Result<Entry> {
+    Ok(Entry::new(SERVICE, &account())?)
+}
+
+fn account() -> String {
+    STATIC_ARGS.uplink_path().to_string_lossy().to_string()
+}
+
+fn secret_entry(name: &str) -> anyhow::Result<Entry> {
+    Ok(Entry::new(&format!("{SERVICE}-{name}"), &account())?)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> anyhow::Result<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        anyhow::bail!("odd length");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&hex[i..i + 2], 16)?))
+        .collect()
+}
+
+async fn blocking<T, F>(f: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await?
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use rand::RngCore;
+    use security_framework::{
+        passwords::{delete_generic_password, generic_password, set_generic_password_options},
+        passwords_options::{AccessControlOptions, PasswordOptions},
+    };
+
+    // errSecItemNotFound
+    const NOT_FOUND: i32 = -25300;
+    const KEY_SERVICE: &str = "uplink-pin-key";
+
+    pub fn available() -> bool {
+        true
+    }
+
+    // the item can only be read after Touch ID or the password of the user
+    pub fn create_key(account: &str) -> anyhow::Result<Vec<u8>> {
+        let mut key = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        delete_key(account)?;
+        let mut options = PasswordOptions::new_generic_password(KEY_SERVICE, account);
+        options.set_access_control_options(AccessControlOptions::USER_PRESENCE);
+        set_generic_password_options(&key, options)?;
+        Ok(key)
+    }
+
+    pub fn load_key(account: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(generic_password(PasswordOptions::new_generic_password(
+            KEY_SERVICE,
+            account,
+        ))?)
+    }
+
+    pub fn delete_key(account: &str) -> anyhow::Result<()> {
+        match delete_generic_password(KEY_SERVICE, account) {
+            Err(e) if e.code() != NOT_FOUND => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use sha2::{Digest, Sha256};
+    use windows::{
+        core::HSTRING,
+        Security::{
+            Credentials::{
+                KeyCredential, KeyCredentialCreationOption, KeyCredentialManager,
+                KeyCredentialStatus,
+            },
+            Cryptography::{BinaryStringEncoding, CryptographicBuffer},
+        },
+    };
+
+    // signed with the Windows Hello key of the profile. the signature is the same every time, it
+    // is hashed into the key
+    const CHALLENGE: &str = "uplink-pin-key";
+
+    pub fn available() -> bool {
+        KeyCredentialManager::IsSupportedAsync()
+            .and_then(|op| op.get())
+            .unwrap_or(false)
+    }
+
+    pub fn create_key(account: &str) -> anyhow::Result<Vec<u8>> {
+        let result = KeyCredentialManager::RequestCreateAsync(
+            &credential_name(account),
+            KeyCredentialCreationOption::ReplaceExisting,
+        )?
+        .get()?;
+        if result.Status()? != KeyCredentialStatus::Success {
+            anyhow::bail!("windows hello declined: {:?}", result.Status()?);
+        }
+        sign(&result.Credential()?)
+    }
+
+    pub fn load_key(account: &str) -> anyhow::Result<Vec<u8>> {
+        let result = KeyCredentialManager::OpenAsync(&credential_name(account))?.get()?;
+        if result.Status()? != KeyCredentialStatus::Success {
+            anyhow::bail!("no windows hello key: {:?}", result.Status()?);
+        }
+        sign(&result.Credential()?)
+    }
+
+    pub fn delete_key(account: &str) -> anyhow::Result<()> {
+        // fails if there is no key, which is fine
+        let _ = KeyCredentialManager::DeleteAsync(&credential_name(account))?.get();
+        Ok(())
+    }
+
+    // asks the user to confirm it's them
+    fn sign(credential: &KeyCredential) -> anyhow::Result<Vec<u8>> {
+        let challenge = CryptographicBuffer::ConvertStringToBinary(
+            &HSTRING::from(CHALLENGE),
+            BinaryStringEncoding::Utf8,
+        )?;
+        let signed = credential.RequestSignAsync(&challenge)?.get()?;
+        if signed.Status()? != KeyCredentialStatus::Success {
+            anyhow::bail!("windows hello declined: {:?}", signed.Status()?);
+        }
+        let mut signature = windows::core::Array::<u8>::new();
+        CryptographicBuffer::CopyToByteArray(&signed.Result()?, &mut signature)?;
+        Ok(Sha256::digest(signature.as_slice()).to_vec())
+    }
+
+    fn credential_name(account: &str) -> HSTRING {
+        HSTRING::from(format!("uplink-{:x}", Sha256::digest(account.as_bytes())))
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn create_key(_account: &str) -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!("the pin can't be saved on this platform")
+    }
+
+    pub fn load_key(_account: &str) -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!("the pin can't be saved on this platform")
+    }
+
+    pub fn delete_key(_account: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hex_round_trips() {
+        let bytes = [0u8, 1, 127, 128, 255];
+        assert_eq!(from_hex(&to_hex(&bytes)).unwrap(), bytes);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+    }
+}
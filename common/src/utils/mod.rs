@@ -1,4 +1,13 @@
+pub mod app_lock;
+pub mod avatars;
 pub mod clear_temp_files_dir;
+pub mod device_link;
+pub mod duress;
+pub mod handoff;
 pub mod img_dimensions_preview;
+pub mod incoming_webhooks;
+pub mod keychain;
 pub mod lifecycle;
 pub mod local_file_path;
+pub mod network_check;
+pub mod webhooks;
@@ -0,0 +1,121 @@
+//! Estimates the round trip time to the relay by timing a TCP handshake with it. Used by the pre-call check
+//! and the connection indicator.
+
+use std::{
+    net::{IpAddr, SocketAddr, TcpStream, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    state::network::{NetworkProbe, RelayStatus},
+    STATIC_ARGS,
+};
+
+/// Checks if the relay can be reached and which interface is used to reach it
+pub async fn probe(timeout: Duration) -> NetworkProbe {
+    if !has_relay() {
+        return NetworkProbe {
+            relay: RelayStatus::NotConfigured,
+            local_addr: None,
+        };
+    }
+    let relay = match relay_rtt(timeout).await {
+        Some(rtt) => RelayStatus::Reachable { rtt },
+        None => RelayStatus::Unreachable,
+    };
+    NetworkProbe {
+        relay,
+        local_addr: relay_addrs().first().and_then(local_addr),
+    }
+}
+
+/// The fastest handshake with any of the relays. None if no relay is configured or none could be reached
+/// within `timeout`.
+pub async fn relay_rtt(timeout: Duration) -> Option<Duration> {
+    let addrs = relay_addrs();
+    if addrs.is_empty() {
+        return None;
+    }
+
+    tokio::task::spawn_blocking(move || {
+        addrs
+            .iter()
+            .filter_map(|addr| {
+                let start = Instant::now();
+                TcpStream::connect_timeout(addr, timeout)
+                    .ok()
+                    .map(|_| start.elapsed())
+            })
+            .min()
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Whether a relay is configured at all. Without one calls are only connected directly.
+pub fn has_relay() -> bool {
+    !relay_addrs().is_empty()
+}
+
+/// The address other devices on the same network can reach this one with
+pub fn lan_addr() -> Option<IpAddr> {
+    relay_addrs()
+        .first()
+        .and_then(local_addr)
+        .or_else(|| local_addr(&SocketAddr::from(([1, 1, 1, 1], 80))))
+}
+
+fn relay_addrs() -> Vec<SocketAddr> {
+    STATIC_ARGS
+        .discovery
+        .addresses()
+        .iter()
+        .filter_map(|addr| socket_addr(addr))
+        .collect()
+}
+
+// connecting a UDP socket doesn't send anything, it only picks the interface the OS would route through
+fn local_addr(remote: &SocketAddr) -> Option<IpAddr> {
+    let unspecified: SocketAddr = if remote.is_ipv4() {
+        "0.0.0.0:0".parse().ok()?
+    } else {
+        "[::]:0".parse().ok()?
+    };
+    let socket = UdpSocket::bind(unspecified).ok()?;
+    socket.connect(remote).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+// only handles multiaddresses of the form /ip4/<ip>/tcp/<port>/..., which is what the relays use
+fn socket_addr(multiaddr: &str) -> Option<SocketAddr> {
+    let mut parts = multiaddr.trim_start_matches('/').split('/');
+    let ip: IpAddr = match (parts.next()?, parts.next()?) {
+        ("ip4", ip) | ("ip6", ip) => ip.parse().ok()?,
+        _ => return None,
+    };
+    let port: u16 = match (parts.next()?, parts.next()?) {
+        ("tcp", port) => port.parse().ok()?,
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_addr() {
+        assert_eq!(
+            socket_addr("/ip4/159.65.41.31/tcp/8848/p2p/12D3KooWRF2bz3KDRPvBs1FASRDRk7BfdYc1RUcfwKsz7UBEu7mL"),
+            Some("159.65.41.31:8848".parse().unwrap())
+        );
+        assert_eq!(
+            socket_addr("/ip6/::1/tcp/4001"),
+            Some("[::1]:4001".parse().unwrap())
+        );
+        assert_eq!(socket_addr("/ip4/127.0.0.1/udp/4001/quic-v1"), None);
+        assert_eq!(socket_addr("/dns4/example.com/tcp/4001"), None);
+    }
+}
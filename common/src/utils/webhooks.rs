@@ -0,0 +1,302 @@
+//! Posts events to a local endpoint chosen by the user, for scripts and home automation. Each event
+//! is sent as JSON, signed with HMAC-SHA256 over "<timestamp>.<body>" using the secret shown in the
+//! settings. The signature is in the `X-Uplink-Signature` header as "sha256=<hex>" and the unix
+//! timestamp in `X-Uplink-Timestamp`. Only loopback and private network addresses are accepted, and
+//! names are resolved and checked before every delivery, so events never leave the network of the
+//! user. Conversations can have their own endpoint for their
+//! new messages, see `forward`.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use rand::RngCore;
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tracing::log;
+use uuid::Uuid;
+use warp::raygun::Message;
+
+use crate::state::{
+    chats::{ChatWebhooks, OutgoingWebhook},
+    configuration::Webhooks,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// the settings of the current profile, set when the state is loaded or the settings change
+static CONFIG: Lazy<RwLock<Webhooks>> = Lazy::new(Default::default);
+// the endpoints of the conversations, by conversation id. merged duplicates point to the endpoint
+// of the chat they were merged into: (chat the message is reported in, endpoint)
+static OUTGOING: Lazy<RwLock<HashMap<Uuid, (Uuid, OutgoingWebhook)>>> = Lazy::new(Default::default);
+// events are delivered one at a time, in the order they happened
+static QUEUE: Lazy<mpsc::UnboundedSender<(Endpoint, WebhookEvent)>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime.block_on(deliver_all(rx)),
+            Err(e) => log::error!("failed to start webhook runtime: {e}"),
+        }
+    });
+    tx
+});
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    NewMessage {
+        conversation_id: Uuid,
+        message_id: Uuid,
+        sender: String,
+        sender_name: String,
+        text: String,
+        attachments: Vec<String>,
+    },
+    FriendRequest {
+        did: String,
+        username: String,
+    },
+    TransferComplete {
+        name: String,
+        size: usize,
+        upload: bool,
+    },
+    // sent from the settings
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn new_message(conversation_id: Uuid, message: &Message, sender_name: String) -> Self {
+        WebhookEvent::NewMessage {
+            conversation_id,
+            message_id: message.id(),
+            sender: message.sender().to_string(),
+            sender_name,
+            text: message.lines().join("\n"),
+            attachments: message
+                .attachments()
+                .iter()
+                .map(|file| file.name())
+                .collect(),
+        }
+    }
+}
+
+// where an event is posted and the secret it is signed with
+struct Endpoint {
+    url: String,
+    secret: String,
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    #[serde(flatten)]
+    event: &'a WebhookEvent,
+    timestamp: u64,
+}
+
+pub fn configure(webhooks: Webhooks) {
+    *CONFIG.write() = webhooks;
+}
+
+/// queues the event if the user enabled it
+pub fn emit(event: WebhookEvent) {
+    let config = CONFIG.read().clone();
+    if wants(&config, &event) {
+        let endpoint = Endpoint {
+            url: config.url,
+            secret: config.secret,
+        };
+        let _ = QUEUE.send((endpoint, event));
+    }
+}
+
+/// sets the endpoints of the conversations, called by the state whenever they or the merged chats
+/// change. new messages are forwarded by the warp runner, so this works without the UI too
+pub fn configure_outgoing(webhooks: &HashMap<Uuid, ChatWebhooks>, merged: &HashMap<Uuid, Uuid>) {
+    let mut outgoing: HashMap<Uuid, (Uuid, OutgoingWebhook)> = webhooks
+        .iter()
+        .filter_map(|(id, hooks)| Some((*id, (*id, hooks.outgoing.clone()?))))
+        .collect();
+    for (duplicate, chat) in merged {
+        if let Some(hook) = outgoing.get(chat).cloned() {
+            outgoing.insert(*duplicate, hook);
+        }
+    }
+    *OUTGOING.write() = outgoing;
+}
+
+/// the conversation a message should be reported as and the endpoint it's forwarded to, if any
+pub fn outgoing(conversation_id: &Uuid) -> Option<(Uuid, OutgoingWebhook)> {
+    OUTGOING.read().get(conversation_id).cloned()
+}
+
+/// queues a new message of a conversation for the endpoint set in its settings. this doesn't
+/// depend on the webhooks of the notification settings
+pub fn forward(webhook: &OutgoingWebhook, event: WebhookEvent) {
+    if is_local_endpoint(&webhook.url) {
+        let endpoint = Endpoint {
+            url: webhook.url.clone(),
+            secret: webhook.secret.clone(),
+        };
+        let _ = QUEUE.send((endpoint, event));
+    }
+}
+
+fn wants(config: &Webhooks, event: &WebhookEvent) -> bool {
+    if !config.enabled || !is_local_endpoint(&config.url) {
+        return false;
+    }
+    match event {
+        WebhookEvent::NewMessage { .. } => config.new_message,
+        WebhookEvent::FriendRequest { .. } => config.friend_request,
+        WebhookEvent::TransferComplete { .. } => config.transfer_complete,
+        WebhookEvent::Test => true,
+    }
+}
+
+/// the event is delivered even if it's not enabled, as long as webhooks are
+pub fn send_test() {
+    emit(WebhookEvent::Test);
+}
+
+pub fn new_secret() -> String {
+    let mut bytes = [0_u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// http(s) URLs of localhost and of addresses in private networks
+pub fn is_local_endpoint(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    // IPv6 addresses are in brackets
+    match host.trim_start_matches('[').trim_end_matches(']').parse() {
+        Ok(ip) => is_local_ip(ip),
+        Err(_) => host == "localhost" || host.ends_with(".localhost") || host.ends_with(".local"),
+    }
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        // unique local addresses, fc00::/7
+        IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+async fn deliver_all(mut rx: mpsc::UnboundedReceiver<(Endpoint, WebhookEvent)>) {
+    while let Some((endpoint, event)) = rx.recv().await {
+        if let Err(e) = deliver(&endpoint, &event).await {
+            log::warn!("failed to deliver webhook: {e}");
+        }
+    }
+}
+
+async fn deliver(endpoint: &Endpoint, event: &WebhookEvent) -> anyhow::Result<()> {
+    let client = local_client(&endpoint.url).await?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let body = serde_json::to_string(&Payload { event, timestamp })?;
+    client
+        .post(&endpoint.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-Uplink-Timestamp", timestamp.to_string())
+        .header(
+            "X-Uplink-Signature",
+            format!("sha256={}", sign(&endpoint.secret, timestamp, &body)),
+        )
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+// names like "homeassistant.local" could resolve to any address, so they are resolved here and
+// the request is pinned to the addresses which were checked
+async fn local_client(url: &str) -> anyhow::Result<reqwest::Client> {
+    let url = reqwest::Url::parse(url)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("webhook URL has no host"))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| anyhow::anyhow!("webhook URL has no port"))?;
+    let builder = reqwest::Client::builder()
+        .timeout(TIMEOUT)
+        // a local endpoint could otherwise send events anywhere
+        .redirect(reqwest::redirect::Policy::none());
+    // IPv6 addresses are in brackets
+    let builder = match host.trim_start_matches('[').trim_end_matches(']').parse() {
+        Ok(ip) if is_local_ip(ip) => builder,
+        Ok(_) => anyhow::bail!("{host} isn't a local address"),
+        Err(_) => {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+            if addrs.is_empty() || !addrs.iter().all(|addr| is_local_ip(addr.ip())) {
+                anyhow::bail!("{host} doesn't resolve to a local address");
+            }
+            builder.resolve_to_addrs(host, &addrs)
+        }
+    };
+    Ok(builder.build()?)
+}
+
+fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    to_hex(&mac.finalize().into_bytes())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_local_endpoints() {
+        assert!(is_local_endpoint("http://localhost:8123/hook"));
+        assert!(is_local_endpoint("http://127.0.0.1:5000"));
+        assert!(is_local_endpoint("https://192.168.1.20/api/webhook/uplink"));
+        assert!(is_local_endpoint("http://homeassistant.local:8123"));
+        assert!(is_local_endpoint("http://[::1]:8080"));
+        assert!(!is_local_endpoint("https://example.com/hook"));
+        assert!(!is_local_endpoint("http://8.8.8.8"));
+        assert!(!is_local_endpoint("ftp://localhost"));
+        assert!(!is_local_endpoint("localhost:8080"));
+    }
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        let body = serde_json::to_string(&Payload {
+            event: &WebhookEvent::Test,
+            timestamp: 1700000000,
+        })
+        .unwrap();
+        assert_eq!(body, r#"{"event":"test","timestamp":1700000000}"#);
+        // python3 -c "import hmac,hashlib;print(hmac.new(b'secret',b'1700000000.<body>',hashlib.sha256).hexdigest())"
+        assert_eq!(
+            sign("secret", 1700000000, &body),
+            "7cdd38293f1a2ab296d1db5dc198bcd0133617c5ef1c031a1a004d57146bdc82"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Caps how fast files are uploaded to and downloaded from Files, and how fast attachments are
+//! sent and downloaded. All the transfers in one direction take their bytes from the same token
+//! bucket, so the limit holds however many of them run at once.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use warp::{
+    constellation::{ConstellationProgressStream, Progression},
+    raygun::{AttachmentEventStream, AttachmentKind},
+};
+
+use crate::state::configuration::Transfers;
+
+pub static UPLOADS: Lazy<TokenBucket> = Lazy::new(TokenBucket::default);
+pub static DOWNLOADS: Lazy<TokenBucket> = Lazy::new(TokenBucket::default);
+
+// the size of the chunks uploads are read in, small enough to keep a slow limit smooth
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+pub fn configure(transfers: &Transfers) {
+    UPLOADS.set_rate(transfers.max_upload_kbps as usize * 1000);
+    DOWNLOADS.set_rate(transfers.max_download_kbps as usize * 1000);
+}
+
+/// Paces the files of a message. warp reads them as the stream is polled, so waiting for the bytes
+/// it reports before polling again keeps it to the limit
+pub fn limit_attachments(stream: AttachmentEventStream) -> AttachmentEventStream {
+    let mut progress = Progress::default();
+    stream
+        .then(move |event| {
+            let bytes = match &event {
+                AttachmentKind::AttachedProgress(_, p) => progress.advance(p),
+                AttachmentKind::Pending(_) => 0,
+            };
+            async move {
+                UPLOADS.acquire(bytes).await;
+                event
+            }
+        })
+        .boxed()
+}
+
+/// Paces the download of an attachment, like `limit_attachments`
+pub fn limit_download(stream: ConstellationProgressStream) -> ConstellationProgressStream {
+    let mut progress = Progress::default();
+    stream
+        .then(move |p| {
+            let bytes = progress.advance(&p);
+            async move {
+                DOWNLOADS.acquire(bytes).await;
+                p
+            }
+        })
+        .boxed()
+}
+
+// how far each file of a transfer got
+#[derive(Default)]
+struct Progress(HashMap<String, usize>);
+
+impl Progress {
+    // the bytes the file moved by since its last progress
+    fn advance(&mut self, progress: &Progression) -> usize {
+        let Progression::CurrentProgress { name, current, .. } = progress else {
+            return 0;
+        };
+        let previous = self.0.insert(name.clone(), *current).unwrap_or_default();
+        current.saturating_sub(previous)
+    }
+}
+
+#[derive(Default)]
+pub struct TokenBucket {
+    inner: Mutex<Bucket>,
+}
+
+#[derive(Default)]
+struct Bucket {
+    // bytes per second, 0 for no limit
+    rate: usize,
+    // can go below zero, when a chunk is larger than what was available
+    tokens: f64,
+    refilled: Option<Instant>,
+}
+
+impl TokenBucket {
+    pub fn set_rate(&self, bytes_per_second: usize) {
+        let mut bucket = self.inner.lock();
+        bucket.rate = bytes_per_second;
+        bucket.tokens = bucket.tokens.min(bytes_per_second as f64);
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.inner.lock().rate > 0
+    }
+
+    /// Waits until `bytes` can be sent without going over the limit
+    pub async fn acquire(&self, bytes: usize) {
+        if let Some(wait) = self.take(bytes, Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // takes the bytes, and returns how long to wait until they're covered
+    fn take(&self, bytes: usize, now: Instant) -> Option<Duration> {
+        let mut bucket = self.inner.lock();
+        if bucket.rate == 0 {
+            return None;
+        }
+        let rate = bucket.rate as f64;
+        // at most a second worth of bytes is saved up
+        let elapsed = bucket
+            .refilled
+            .map(|refilled| now.duration_since(refilled).as_secs_f64())
+            .unwrap_or(1.0);
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(rate);
+        bucket.refilled = Some(now);
+        bucket.tokens -= bytes as f64;
+        (bucket.tokens < 0.0).then(|| Duration::from_secs_f64(-bucket.tokens / rate))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shared_between_transfers() {
+        let bucket = TokenBucket::default();
+        let now = Instant::now();
+        assert_eq!(bucket.take(1_000_000, now), None);
+
+        bucket.set_rate(1000);
+        // a second worth of bytes is there at the start
+        assert_eq!(bucket.take(1000, now), None);
+        // two transfers asking at the same time wait one after the other
+        assert_eq!(bucket.take(500, now), Some(Duration::from_millis(500)));
+        assert_eq!(bucket.take(500, now), Some(Duration::from_secs(1)));
+        let later = now + Duration::from_secs(2);
+        assert_eq!(bucket.take(500, later), None);
+    }
+
+    #[test]
+    fn counts_what_a_file_moved_by() {
+        let at = |name: &str, current| Progression::CurrentProgress {
+            name: name.into(),
+            current,
+            total: None,
+        };
+        let mut progress = Progress::default();
+        assert_eq!(progress.advance(&at("a", 100)), 100);
+        assert_eq!(progress.advance(&at("b", 50)), 50);
+        assert_eq!(progress.advance(&at("a", 250)), 150);
+    }
+}
@@ -0,0 +1,114 @@
+//! Cooperative cancellation for warp commands. A command sent with `WarpCmd::cancellable` is skipped
+//! if its token was cancelled before the warp_runner got to it, and stops at its next await point if
+//! the token is cancelled while it runs. In both cases the response channel of the command is dropped
+//! so the receiver gets an error.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use tokio::sync::Notify;
+
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// completes once the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            // created before the check so a cancel in between isn't missed
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// runs `fut` unless the token is cancelled first. returns None if it was cancelled
+    pub async fn run_until_cancelled<F: Future>(&self, fut: F) -> Option<F::Output> {
+        if self.is_cancelled() {
+            return None;
+        }
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => None,
+            res = fut => Some(res),
+        }
+    }
+
+    /// cancels the token once the guard is dropped. useful for futures which dioxus drops when the
+    /// component goes away or its dependencies change.
+    pub fn drop_guard(&self) -> CancelGuard {
+        CancelGuard {
+            token: self.clone(),
+        }
+    }
+}
+
+pub struct CancelGuard {
+    token: CancelToken,
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cancelled_futures_stop() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let token = CancelToken::new();
+        assert_eq!(
+            runtime.block_on(token.run_until_cancelled(async { 1 })),
+            Some(1)
+        );
+
+        let guard = token.drop_guard();
+        let waiting = {
+            let token = token.clone();
+            runtime.spawn(async move {
+                token
+                    .run_until_cancelled(std::future::pending::<()>())
+                    .await
+            })
+        };
+        drop(guard);
+        assert!(token.is_cancelled());
+        assert_eq!(runtime.block_on(waiting).unwrap(), None);
+        assert_eq!(
+            runtime.block_on(token.run_until_cancelled(async { 1 })),
+            None
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Checks that files come back from Files the way they were uploaded. The blake3 hash of a file is
+//! computed while it is uploaded and kept under the id of the file in a store of the profile, apart
+//! from the description the user edits. Downloads are compared with it, and a download which doesn't
+//! match is tried again, if the settings allow it. Files uploaded from other devices aren't checked.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use blake3::{Hash, Hasher};
+use once_cell::sync::Lazy;
+use tracing::log;
+use uuid::Uuid;
+use warp::{constellation::item::Item, error::Error};
+
+use crate::{state::configuration::Transfers, STATIC_ARGS};
+
+// the id of a file -> the hex of its hash
+type Checksums = HashMap<Uuid, String>;
+
+// the context of the error a download fails with when it doesn't match
+const MISMATCH: &str = "checksum mismatch";
+// downloads are tried this many more times before giving up
+const MAX_RETRIES: usize = 2;
+
+static RETRY: AtomicBool = AtomicBool::new(true);
+// uploads which end together don't overwrite each other's hashes
+static WRITE: Lazy<tokio::sync::Mutex<()>> = Lazy::new(Default::default);
+
+pub fn configure(transfers: &Transfers) {
+    RETRY.store(transfers.retry_corrupted_downloads, Ordering::Relaxed);
+}
+
+/// How many times a corrupted download is tried again
+pub fn retries() -> usize {
+    if RETRY.load(Ordering::Relaxed) {
+        MAX_RETRIES
+    } else {
+        0
+    }
+}
+
+pub fn of_file(path: &Path) -> std::io::Result<Hash> {
+    let mut hasher = Hasher::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize())
+}
+
+/// The hash stored for the item, `None` for files uploaded before they had one or on another device
+pub async fn of_item(item: &Item) -> Option<Hash> {
+    lookup(&read().await, item)
+}
+
+/// Stores the hash of an uploaded file, the item must be the one the upload created
+pub async fn store(item: &Item, hash: &Hash) -> Result<(), Error> {
+    let _write = WRITE.lock().await;
+    let mut checksums = read().await;
+    checksums.insert(item.id(), hash.to_hex().to_string());
+    let contents = serde_json::to_string(&checksums).map_err(anyhow::Error::from)?;
+    tokio::fs::write(STATIC_ARGS.checksums_path(), contents)
+        .await
+        .map_err(anyhow::Error::from)?;
+    Ok(())
+}
+
+async fn read() -> Checksums {
+    match tokio::fs::read_to_string(STATIC_ARGS.checksums_path()).await {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::error!("failed to deserialize the checksums: {e}");
+            Checksums::new()
+        }),
+        Err(_) => Checksums::new(),
+    }
+}
+
+fn lookup(checksums: &Checksums, item: &Item) -> Option<Hash> {
+    checksums
+        .get(&item.id())
+        .and_then(|hex| Hash::from_hex(hex).ok())
+}
+
+pub fn mismatch_error() -> Error {
+    Error::OtherWithContext(MISMATCH.into())
+}
+
+pub fn is_mismatch(error: &Error) -> bool {
+    matches!(error, Error::OtherWithContext(context) if context == MISMATCH)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use warp::constellation::file::File;
+
+    #[test]
+    fn stored_by_item_id() {
+        let item = Item::from(File::new("file.txt"));
+        let other = Item::from(File::new("file.txt"));
+        let hash = blake3::hash(b"contents");
+        let checksums = Checksums::from([(item.id(), hash.to_hex().to_string())]);
+        assert_eq!(lookup(&checksums, &item), Some(hash));
+        // a file with the same name isn't the one which was uploaded
+        assert_eq!(lookup(&checksums, &other), None);
+        assert!(is_mismatch(&mismatch_error()));
+    }
+}
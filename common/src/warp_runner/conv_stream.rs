@@ -14,6 +14,9 @@ pub struct Manager {
     // (conversation_id, thread)
     handles: HashMap<Uuid, JoinHandle<()>>,
     msg_received_ch: mpsc::UnboundedSender<raygun::MessageEventKind>,
+    // duplicate direct conversations which were merged locally.
+    // (conversation_id, duplicates merged into it)
+    merged: HashMap<Uuid, Vec<Uuid>>,
 }
 
 impl Manager {
@@ -21,6 +24,7 @@ impl Manager {
         Self {
             handles: HashMap::new(),
             msg_received_ch,
+            merged: HashMap::new(),
         }
     }
 
@@ -49,6 +53,20 @@ impl Manager {
             handle.abort();
         }
     }
+
+    /// records that the history of `duplicates` should be shown as part of `conv_id`
+    pub fn merge_conversations(&mut self, conv_id: Uuid, duplicates: Vec<Uuid>) {
+        let entry = self.merged.entry(conv_id).or_default();
+        for id in duplicates {
+            if id != conv_id && !entry.contains(&id) {
+                entry.push(id);
+            }
+        }
+    }
+
+    pub fn get_merged(&self, conv_id: &Uuid) -> Vec<Uuid> {
+        self.merged.get(conv_id).cloned().unwrap_or_default()
+    }
 }
 
 impl std::ops::Drop for Manager {
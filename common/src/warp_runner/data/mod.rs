@@ -1,4 +1,6 @@
 use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::ui_adapter;
@@ -10,6 +12,7 @@ pub struct FetchMessagesResponse {
     pub most_recent: Option<Uuid>,
 }
 
+#[derive(Debug, Clone, Copy)]
 pub enum FetchMessagesConfig {
     MostRecent {
         limit: usize,
@@ -41,3 +44,32 @@ impl FetchMessagesConfig {
         }
     }
 }
+
+/// Roles of group chat members. Roles are kept locally; the creator of a group is always an admin.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+pub enum GroupRole {
+    #[display(fmt = "Admin")]
+    Admin,
+    #[display(fmt = "Moderator")]
+    Moderator,
+    #[default]
+    #[display(fmt = "Member")]
+    Member,
+}
+
+impl GroupRole {
+    /// rename the group, add and remove members and assign roles
+    pub fn can_manage_group(&self) -> bool {
+        matches!(self, Self::Admin)
+    }
+
+    /// delete messages of other members and mute members
+    pub fn can_moderate(&self) -> bool {
+        matches!(self, Self::Admin | Self::Moderator)
+    }
+
+    /// notify every member with @everyone
+    pub fn can_mention_everyone(&self) -> bool {
+        self.can_moderate()
+    }
+}
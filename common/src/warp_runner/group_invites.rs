@@ -0,0 +1,271 @@
+//! Invite links for group conversations. warp only lets members of a group add participants, so joining via a link
+//! works by sending the invite code to the member who created the link. Their warp runner checks that the invite is
+//! still valid and adds the sender to the group. Requests which came in while Uplink was closed are handled when it
+//! starts, and every request is only handled once.
+//!
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    str::FromStr,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+use warp::{
+    crypto::DID,
+    error::Error,
+    raygun::{self, ConversationType, MessageOptions},
+};
+
+use crate::STATIC_ARGS;
+
+use super::{
+    group_roles::{self, GroupAction},
+    Messaging,
+};
+
+const INVITE_LINK_PREFIX: &str = "uplink://join/";
+// messages starting with this are join requests and aren't shown to the user
+const JOIN_REQUEST_PREFIX: &str = "uplink-join-request:";
+// the join requests which were handled, by message id. older ones are forgotten
+const MAX_HANDLED: usize = 500;
+// the messages of every direct conversation which are checked for join requests on startup
+const CATCH_UP_MESSAGES: usize = 20;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupInvite {
+    pub code: String,
+    pub conversation_id: Uuid,
+    pub inviter: DID,
+    pub expires: Option<DateTime<Utc>>,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+}
+
+impl GroupInvite {
+    pub fn link(&self) -> String {
+        format!("{INVITE_LINK_PREFIX}{}/{}", self.inviter, self.code)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let expired = self.expires.map(|date| date < Utc::now()).unwrap_or(false);
+        let used_up = self.max_uses.map(|max| self.uses >= max).unwrap_or(false);
+        !expired && !used_up
+    }
+}
+
+/// The parts of an invite link needed to ask the inviter to join
+pub struct InviteLink {
+    pub inviter: DID,
+    pub code: String,
+}
+
+impl FromStr for InviteLink {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::OtherWithContext("invalid invite link".into());
+        let s = s.trim();
+        // the code can be shared without the link prefix
+        let s = s.strip_prefix(INVITE_LINK_PREFIX).unwrap_or(s);
+        let (inviter, code) = s.rsplit_once('/').ok_or_else(invalid)?;
+        if code.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            inviter: DID::from_str(inviter).map_err(|_| invalid())?,
+            code: code.into(),
+        })
+    }
+}
+
+/// the message sent to the inviter to join a group
+pub fn join_request(code: &str) -> Vec<String> {
+    vec![format!("{JOIN_REQUEST_PREFIX}{code}")]
+}
+
+/// returns the invite code if the message is a join request
+pub fn join_request_code(message: &raygun::Message) -> Option<String> {
+    match message.lines().as_slice() {
+        [line] => line.strip_prefix(JOIN_REQUEST_PREFIX).map(String::from),
+        _ => None,
+    }
+}
+
+/// Keeps track of the invites created by the user and of the join requests which were handled. Saved to disk whenever
+/// they change.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Manager {
+    // (code, invite)
+    invites: HashMap<String, GroupInvite>,
+    #[serde(default)]
+    handled: VecDeque<Uuid>,
+}
+
+impl Manager {
+    pub fn load() -> Self {
+        let Ok(contents) = fs::read_to_string(STATIC_ARGS.group_invites_path()) else {
+            return Self::default();
+        };
+        // older versions only saved the invites
+        serde_json::from_str(&contents)
+            .or_else(|_| {
+                serde_json::from_str(&contents).map(|invites| Self {
+                    invites,
+                    handled: VecDeque::new(),
+                })
+            })
+            .unwrap_or_else(|e| {
+                log::error!("failed to deserialize group invites: {e}");
+                Self::default()
+            })
+    }
+
+    fn save(&self) {
+        match serde_json::to_string(self) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(STATIC_ARGS.group_invites_path(), contents) {
+                    log::error!("failed to save group invites: {e}");
+                }
+            }
+            Err(e) => log::error!("failed to serialize group invites: {e}"),
+        }
+    }
+
+    pub fn create(
+        &mut self,
+        conversation_id: Uuid,
+        inviter: DID,
+        expires: Option<DateTime<Utc>>,
+        max_uses: Option<u32>,
+    ) -> GroupInvite {
+        let mut code = Uuid::new_v4().simple().to_string();
+        code.truncate(12);
+        let invite = GroupInvite {
+            code: code.clone(),
+            conversation_id,
+            inviter,
+            expires,
+            max_uses,
+            uses: 0,
+        };
+        self.invites.insert(code, invite.clone());
+        self.save();
+        invite
+    }
+
+    pub fn revoke(&mut self, code: &str) {
+        if self.invites.remove(code).is_some() {
+            self.save();
+        }
+    }
+
+    /// returns the invites for the conversation which can still be used. invalid invites are discarded.
+    pub fn list(&mut self, conversation_id: Uuid) -> Vec<GroupInvite> {
+        let num_invites = self.invites.len();
+        self.invites.retain(|_, invite| invite.is_valid());
+        if self.invites.len() != num_invites {
+            self.save();
+        }
+        let mut invites: Vec<_> = self
+            .invites
+            .values()
+            .filter(|invite| invite.conversation_id == conversation_id)
+            .cloned()
+            .collect();
+        invites.sort_by(|a, b| a.code.cmp(&b.code));
+        invites
+    }
+
+    /// handles the join requests in the latest messages of the direct conversations, which may have come in while
+    /// Uplink was closed
+    pub async fn catch_up(
+        &mut self,
+        own_did: &DID,
+        group_roles: &group_roles::Manager,
+        messaging: &mut Messaging,
+    ) -> Result<(), Error> {
+        // only the inviter is sent requests
+        if self.invites.is_empty() {
+            return Ok(());
+        }
+        for conv in messaging.list_conversations().await? {
+            if conv.conversation_type() != ConversationType::Direct {
+                continue;
+            }
+            let messages = messaging
+                .get_messages(
+                    conv.id(),
+                    MessageOptions::default()
+                        .set_reverse()
+                        .set_limit((CATCH_UP_MESSAGES as i64).try_into().unwrap_or_default()),
+                )
+                .await
+                .and_then(Vec::<_>::try_from)?;
+            for message in messages.iter().rev() {
+                let Some(code) = join_request_code(message) else {
+                    continue;
+                };
+                if message.sender() == *own_did || self.handled.contains(&message.id()) {
+                    continue;
+                }
+                match self
+                    .handle_join_request(&code, message, own_did, group_roles, messaging)
+                    .await
+                {
+                    Ok(conv_id) => log::debug!("added participant to {conv_id} via invite"),
+                    Err(e) => log::warn!("rejected join request: {e}"),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// adds the sender of the join request `message` to the group of the invite, if the invite is valid and the user
+    /// is still allowed to add members. returns the id of the group. a request is only handled once
+    pub async fn handle_join_request(
+        &mut self,
+        code: &str,
+        message: &raygun::Message,
+        own_did: &DID,
+        group_roles: &group_roles::Manager,
+        messaging: &mut Messaging,
+    ) -> Result<Uuid, Error> {
+        if self.handled.contains(&message.id()) {
+            return Err(Error::OtherWithContext(
+                "join request was already handled".into(),
+            ));
+        }
+        self.handled.push_back(message.id());
+        if self.handled.len() > MAX_HANDLED {
+            self.handled.pop_front();
+        }
+        self.save();
+        let sender = message.sender();
+        let invite = match self.invites.get(code) {
+            Some(invite) if invite.is_valid() => invite.clone(),
+            _ => return Err(Error::OtherWithContext("invalid invite code".into())),
+        };
+        group_roles
+            .check_permission(
+                invite.conversation_id,
+                own_did,
+                GroupAction::AddMembers,
+                messaging,
+            )
+            .await?;
+        let conv = messaging.get_conversation(invite.conversation_id).await?;
+        if !conv.recipients().contains(&sender) {
+            messaging
+                .add_recipient(invite.conversation_id, &sender)
+                .await?;
+        }
+        if let Some(invite) = self.invites.get_mut(code) {
+            invite.uses += 1;
+        }
+        self.save();
+        Ok(invite.conversation_id)
+    }
+}
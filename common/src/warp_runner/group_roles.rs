@@ -0,0 +1,191 @@
+//! Keeps track of the roles and muted members of group conversations, and checks that commands which
+//! modify a group are allowed for the user's role. warp has no concept of roles, so a change is sent
+//! to the other members as a message. They apply it if the sender is allowed to make it.
+//!
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use warp::{
+    crypto::DID,
+    error::Error,
+    raygun::{self, ConversationSettings},
+};
+
+use super::{GroupRole, Messaging};
+
+// @everyone can only be used once in this interval per group, to keep it from being used for spam
+const EVERYONE_MENTION_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+// messages starting with this are changes of the roles and aren't shown to the user
+const UPDATE_PREFIX: &str = "uplink-group-update:";
+
+/// A change of the roles or muted members of a group, sent to the other members
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupUpdate {
+    SetRole(DID, GroupRole),
+    SetMuted(DID, bool),
+}
+
+impl GroupUpdate {
+    /// the message sent to the group
+    pub fn message(&self) -> Result<Vec<String>, Error> {
+        let update = serde_json::to_string(self).map_err(anyhow::Error::from)?;
+        Ok(vec![format!("{UPDATE_PREFIX}{update}")])
+    }
+
+    /// returns the update if the message is one
+    pub fn from_message(message: &raygun::Message) -> Option<Self> {
+        match message.lines().as_slice() {
+            [line] => serde_json::from_str(line.strip_prefix(UPDATE_PREFIX)?).ok(),
+            _ => None,
+        }
+    }
+
+    fn action(&self) -> GroupAction {
+        match self {
+            Self::SetRole(..) => GroupAction::AssignRoles,
+            Self::SetMuted(..) => GroupAction::MuteMembers,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Manager {
+    // (conversation_id, (member, role)). members without an entry are regular members.
+    roles: HashMap<Uuid, HashMap<DID, GroupRole>>,
+    // (conversation_id, muted members)
+    muted: HashMap<Uuid, HashSet<DID>>,
+    // (conversation_id, when the user last mentioned @everyone)
+    everyone_mentions: HashMap<Uuid, Instant>,
+}
+
+/// What a command is going to do to a group. Used to check the permissions.
+pub enum GroupAction {
+    Rename,
+    AddMembers,
+    RemoveMembers,
+    AssignRoles,
+    DeleteOthersMessages,
+    MuteMembers,
+    MentionEveryone,
+}
+
+impl Manager {
+    pub fn init(
+        &mut self,
+        roles: HashMap<Uuid, HashMap<DID, GroupRole>>,
+        muted: HashMap<Uuid, HashSet<DID>>,
+    ) {
+        self.roles = roles;
+        self.muted = muted;
+    }
+
+    pub fn apply(&mut self, conv_id: Uuid, update: GroupUpdate) {
+        match update {
+            GroupUpdate::SetRole(member, role) => self.set_role(conv_id, member, role),
+            GroupUpdate::SetMuted(member, muted) => self.set_muted(conv_id, member, muted),
+        }
+    }
+
+    /// applies an update sent by another member, if they are allowed to make it
+    pub async fn handle_update(
+        &mut self,
+        conv_id: Uuid,
+        sender: &DID,
+        update: GroupUpdate,
+        messaging: &mut Messaging,
+    ) -> Result<(), Error> {
+        self.check_permission(conv_id, sender, update.action(), messaging)
+            .await?;
+        self.apply(conv_id, update);
+        Ok(())
+    }
+
+    fn set_role(&mut self, conv_id: Uuid, member: DID, role: GroupRole) {
+        let roles = self.roles.entry(conv_id).or_default();
+        if role == GroupRole::Member {
+            roles.remove(&member);
+        } else {
+            roles.insert(member, role);
+        }
+    }
+
+    fn set_muted(&mut self, conv_id: Uuid, member: DID, muted: bool) {
+        let members = self.muted.entry(conv_id).or_default();
+        if muted {
+            members.insert(member);
+        } else {
+            members.remove(&member);
+        }
+    }
+
+    pub fn is_muted(&self, conv_id: &Uuid, member: &DID) -> bool {
+        self.muted
+            .get(conv_id)
+            .map(|members| members.contains(member))
+            .unwrap_or_default()
+    }
+
+    /// returns an error if @everyone was used in the group too recently
+    pub fn check_everyone_cooldown(&self, conv_id: &Uuid) -> Result<(), Error> {
+        match self.everyone_mentions.get(conv_id) {
+            Some(last) if last.elapsed() < EVERYONE_MENTION_COOLDOWN => {
+                Err(Error::OtherWithContext(format!(
+                    "@everyone can only be used once every {} minutes",
+                    EVERYONE_MENTION_COOLDOWN.as_secs() / 60
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record_everyone_mention(&mut self, conv_id: Uuid) {
+        self.everyone_mentions.insert(conv_id, Instant::now());
+    }
+
+    /// returns an error if `member` isn't allowed to perform `action` in the group
+    pub async fn check_permission(
+        &self,
+        conv_id: Uuid,
+        member: &DID,
+        action: GroupAction,
+        messaging: &mut Messaging,
+    ) -> Result<(), Error> {
+        let conv = messaging.get_conversation(conv_id).await?;
+        let ConversationSettings::Group(settings) = conv.settings() else {
+            // direct conversations don't have roles
+            return Ok(());
+        };
+
+        let role = if conv.creator().as_ref() == Some(member) {
+            GroupRole::Admin
+        } else {
+            self.roles
+                .get(&conv_id)
+                .and_then(|roles| roles.get(member))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let allowed = match action {
+            GroupAction::Rename => role.can_manage_group() || settings.members_can_change_name(),
+            GroupAction::AddMembers => {
+                role.can_manage_group() || settings.members_can_add_participants()
+            }
+            GroupAction::RemoveMembers | GroupAction::AssignRoles => role.can_manage_group(),
+            GroupAction::DeleteOthersMessages | GroupAction::MuteMembers => role.can_moderate(),
+            GroupAction::MentionEveryone => role.can_mention_everyone(),
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::OtherWithContext(format!(
+                "a group {role} is not allowed to do this"
+            )))
+        }
+    }
+}
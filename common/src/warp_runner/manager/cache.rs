@@ -0,0 +1,209 @@
+//! Read-through cache for warp queries which don't change anything, like directory listings and
+//! identity images. Results are stored by the signature of the query and dropped when an event or
+//! another command changes the data behind them. Entries also expire after a while, in case an
+//! event was missed.
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tracing::log;
+use uuid::Uuid;
+use warp::{crypto::DID, error::Error, multipass::MultiPassEventKind};
+
+use super::commands::{ConstellationCmd, MultiPassCmd};
+
+const MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QueryKey {
+    // the path of the directory in constellation
+    DirectoryItems(PathBuf),
+    // the id of the directory, its size includes the subdirectories
+    DirectorySize(Uuid),
+    Identity(DID),
+    ProfilePicture(DID),
+    ProfileBanner(DID),
+}
+
+impl QueryKey {
+    fn did(&self) -> Option<&DID> {
+        match self {
+            QueryKey::Identity(did)
+            | QueryKey::ProfilePicture(did)
+            | QueryKey::ProfileBanner(did) => Some(did),
+            QueryKey::DirectoryItems(_) | QueryKey::DirectorySize(_) => None,
+        }
+    }
+}
+
+struct Entry {
+    stored: Instant,
+    value: Box<dyn Any + Send>,
+}
+
+// shared with the bulk lane, which invalidates directory listings when uploads finish
+static CACHE: Lazy<Mutex<HashMap<QueryKey, Entry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// returns the cached result for `key` or runs `fetch` and caches its result if it succeeded
+pub async fn read_through<T, F>(key: QueryKey, fetch: F) -> Result<T, Error>
+where
+    T: Clone + Send + 'static,
+    F: Future<Output = Result<T, Error>>,
+{
+    if let Some(value) = get::<T>(&key) {
+        log::trace!("cache hit: {key:?}");
+        return Ok(value);
+    }
+    let value = fetch.await?;
+    CACHE.lock().insert(
+        key,
+        Entry {
+            stored: Instant::now(),
+            value: Box::new(value.clone()),
+        },
+    );
+    Ok(value)
+}
+
+fn get<T: Clone + 'static>(key: &QueryKey) -> Option<T> {
+    let mut cache = CACHE.lock();
+    let fresh = cache.get(key)?.stored.elapsed() < MAX_AGE;
+    if !fresh {
+        cache.remove(key);
+        return None;
+    }
+    cache.get(key)?.value.downcast_ref::<T>().cloned()
+}
+
+pub fn invalidate_identity(did: &DID) {
+    CACHE.lock().retain(|key, _| key.did() != Some(did));
+}
+
+pub fn invalidate_identities() {
+    CACHE.lock().retain(|key, _| key.did().is_none());
+}
+
+pub fn invalidate_directories() {
+    CACHE.lock().retain(|key, _| {
+        !matches!(
+            key,
+            QueryKey::DirectoryItems(_) | QueryKey::DirectorySize(_)
+        )
+    });
+}
+
+/// needed when warp is restarted, for example after switching profiles
+pub fn clear() {
+    CACHE.lock().clear();
+}
+
+pub fn on_multipass_event(evt: &MultiPassEventKind) {
+    match evt {
+        MultiPassEventKind::IdentityUpdate { did, .. }
+        | MultiPassEventKind::IdentityOnline { did, .. }
+        | MultiPassEventKind::IdentityOffline { did, .. }
+        | MultiPassEventKind::FriendAdded { did, .. }
+        | MultiPassEventKind::FriendRemoved { did, .. }
+        | MultiPassEventKind::Blocked { did, .. }
+        | MultiPassEventKind::Unblocked { did, .. } => invalidate_identity(did),
+        _ => {}
+    }
+}
+
+/// what a command changes, see `invalidate`
+pub enum Changes {
+    Identities,
+    Identity(DID),
+    Directories,
+}
+
+impl Changes {
+    /// called before the command runs and again once it's done, so that neither the old data nor
+    /// what was read while the command ran is served afterwards
+    pub fn invalidate(&self) {
+        match self {
+            Changes::Identities => invalidate_identities(),
+            Changes::Identity(did) => invalidate_identity(did),
+            Changes::Directories => invalidate_directories(),
+        }
+    }
+}
+
+pub fn multipass_cmd_changes(cmd: &MultiPassCmd) -> Option<Changes> {
+    match cmd {
+        // these change the own identity
+        MultiPassCmd::UpdateProfilePicture { .. }
+        | MultiPassCmd::ClearProfilePicture { .. }
+        | MultiPassCmd::UpdateBanner { .. }
+        | MultiPassCmd::ClearBanner { .. }
+        | MultiPassCmd::UpdateCustomStatus { .. }
+        | MultiPassCmd::UpdateUsername { .. }
+        | MultiPassCmd::RevokeDevice { .. }
+        | MultiPassCmd::SetStatus { .. } => Some(Changes::Identities),
+        MultiPassCmd::RemoveFriend { did, .. }
+        | MultiPassCmd::Block { did, .. }
+        | MultiPassCmd::Unblock { did, .. }
+        | MultiPassCmd::AcceptRequest { did, .. } => Some(Changes::Identity(did.clone())),
+        _ => None,
+    }
+}
+
+pub fn constellation_cmd_changes(cmd: &ConstellationCmd) -> Option<Changes> {
+    match cmd {
+        ConstellationCmd::CreateNewDirectory { .. }
+        | ConstellationCmd::RenameItem { .. }
+        | ConstellationCmd::DeleteItems { .. }
+        | ConstellationCmd::UploadFiles { .. }
+        | ConstellationCmd::RepairStorageIntegrity { .. } => Some(Changes::Directories),
+        ConstellationCmd::InDirectory { cmd, .. } => constellation_cmd_changes(cmd),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cached_until_invalidated() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let key = QueryKey::DirectoryItems(PathBuf::from("/cache-test"));
+        let fetch = |value: usize| async move { Ok::<_, Error>(value) };
+
+        assert_eq!(
+            runtime
+                .block_on(read_through(key.clone(), fetch(1)))
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            runtime
+                .block_on(read_through(key.clone(), fetch(2)))
+                .unwrap(),
+            1
+        );
+
+        invalidate_directories();
+        assert_eq!(
+            runtime
+                .block_on(read_through(key.clone(), fetch(3)))
+                .unwrap(),
+            3
+        );
+
+        // errors are not cached
+        invalidate_directories();
+        let failed = read_through::<usize, _>(key.clone(), async {
+            Err(Error::OtherWithContext("failed".into()))
+        });
+        assert!(runtime.block_on(failed).is_err());
+        assert_eq!(runtime.block_on(read_through(key, fetch(4))).unwrap(), 4);
+    }
+}
@@ -0,0 +1,208 @@
+//! Runs a sequence of `WarpCmd`s as one unit. The steps are executed in order by the warp_runner
+//! without other commands in between. Later steps can use the results of earlier ones, and if a step
+//! fails the rollback hooks of the completed steps are run in reverse order.
+
+use std::any::Any;
+
+use derive_more::Display;
+use futures::{channel::oneshot, future::BoxFuture, FutureExt};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tracing::log;
+use warp::error::Error;
+
+use crate::{warp_runner::WarpCmd, WARP_CMD_CH};
+
+type StepOutput = Box<dyn Any + Send>;
+type BuildStep = Box<
+    dyn FnOnce(
+            &BatchContext,
+        ) -> Result<(WarpCmd, BoxFuture<'static, Result<StepOutput, Error>>), Error>
+        + Send,
+>;
+type Rollback = Box<dyn FnOnce(&BatchContext) -> Option<WarpCmd> + Send>;
+
+#[derive(Display)]
+#[display(fmt = "Batch {{ {label}, steps: {} }}", "steps.len()")]
+pub struct BatchCmd {
+    pub label: String,
+    pub steps: Vec<BatchStep>,
+    // receives an update before each step is executed. dropped once the batch is done
+    pub progress: Option<UnboundedSender<BatchProgress>>,
+    pub rsp: oneshot::Sender<Result<BatchContext, BatchError>>,
+}
+
+pub struct BatchStep {
+    label: String,
+    build: BuildStep,
+    rollback: Option<Rollback>,
+}
+
+impl BatchStep {
+    /// `build` creates the command of the step, using the results of the previous steps if needed.
+    /// The value the command responds with is stored in the `BatchContext`.
+    pub fn new<T, F>(label: impl Into<String>, build: F) -> Self
+    where
+        T: Send + 'static,
+        F: FnOnce(&BatchContext, oneshot::Sender<Result<T, Error>>) -> Result<WarpCmd, Error>
+            + Send
+            + 'static,
+    {
+        let build: BuildStep = Box::new(move |ctx| {
+            let (tx, rx) = oneshot::channel();
+            let cmd = build(ctx, tx)?;
+            let output = async move {
+                match rx.await {
+                    Ok(r) => r.map(|value| Box::new(value) as StepOutput),
+                    Err(_) => Err(Error::OtherWithContext(
+                        "command was dropped without a response".into(),
+                    )),
+                }
+            }
+            .boxed();
+            Ok((cmd, output))
+        });
+        Self {
+            label: label.into(),
+            build,
+            rollback: None,
+        }
+    }
+
+    /// returns the command which undoes this step. it is sent when a later step fails and its
+    /// response is ignored.
+    pub fn on_rollback<F>(mut self, rollback: F) -> Self
+    where
+        F: FnOnce(&BatchContext) -> Option<WarpCmd> + Send + 'static,
+    {
+        self.rollback = Some(Box::new(rollback));
+        self
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub(crate) fn into_parts(self) -> (String, BuildStep, Option<Rollback>) {
+        (self.label, self.build, self.rollback)
+    }
+}
+
+/// the results of the steps executed so far, in order
+#[derive(Default)]
+pub struct BatchContext {
+    outputs: Vec<StepOutput>,
+}
+
+impl BatchContext {
+    pub fn output<T: 'static>(&self, step: usize) -> Option<&T> {
+        self.outputs.get(step).and_then(|v| v.downcast_ref())
+    }
+
+    /// like `output` but fails the step which needs the value if it is missing
+    pub fn require<T: 'static>(&self, step: usize) -> Result<&T, Error> {
+        self.output(step).ok_or_else(|| {
+            Error::OtherWithContext(format!(
+                "batch step {step} has no result of the expected type"
+            ))
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, output: StepOutput) {
+        self.outputs.push(output);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchProgress {
+    // zero based index of the step about to run
+    pub step: usize,
+    pub total: usize,
+    pub label: String,
+}
+
+/// sends the steps to the warp_runner as one `BatchCmd` and waits for the result. `on_progress` is
+/// called before each step is executed.
+pub async fn send_batch(
+    label: impl Into<String>,
+    steps: Vec<BatchStep>,
+    mut on_progress: impl FnMut(BatchProgress),
+) -> Result<BatchContext, BatchError> {
+    let label = label.into();
+    let not_running = |label: String| BatchError {
+        step: 0,
+        label,
+        error: Error::OtherWithContext("the batch was not executed".into()),
+        rolled_back: 0,
+    };
+
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = WARP_CMD_CH.tx.send(WarpCmd::Batch(BatchCmd {
+        label: label.clone(),
+        steps,
+        progress: Some(progress_tx),
+        rsp: tx,
+    })) {
+        log::error!("failed to send warp command: {}", e);
+        return Err(not_running(label));
+    }
+
+    // ends once the warp_runner is done with the batch
+    while let Some(progress) = progress_rx.recv().await {
+        on_progress(progress);
+    }
+    rx.await.unwrap_or_else(|_| Err(not_running(label)))
+}
+
+#[derive(Debug, Display)]
+#[display(fmt = "batch step {step} ({label}) failed: {error}")]
+pub struct BatchError {
+    pub step: usize,
+    pub label: String,
+    pub error: Error,
+    // the number of completed steps which were undone
+    pub rolled_back: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::warp_runner::OtherCmd;
+
+    #[test]
+    fn step_results_are_stored_in_order() {
+        let step = BatchStep::new("compress", |_, rsp| {
+            Ok(WarpCmd::Other(OtherCmd::CompressFolder {
+                src: PathBuf::new(),
+                dest: PathBuf::new(),
+                rsp,
+            }))
+        });
+        let (_, build, rollback) = step.into_parts();
+        assert!(rollback.is_none());
+
+        let mut ctx = BatchContext::default();
+        let (cmd, output) = build(&ctx).unwrap();
+        match cmd {
+            WarpCmd::Other(OtherCmd::CompressFolder { rsp, .. }) => rsp.send(Ok(())).unwrap(),
+            _ => unreachable!(),
+        }
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        ctx.push(runtime.block_on(output).unwrap());
+
+        assert_eq!(ctx.len(), 1);
+        assert!(ctx.output::<()>(0).is_some());
+        assert!(ctx.require::<String>(0).is_err());
+        assert!(ctx.require::<()>(1).is_err());
+    }
+}
@@ -1,33 +1,42 @@
 use std::{
+    collections::HashSet,
     ffi::OsStr,
     io::{Read, Write},
     path::{Path, PathBuf},
     process::{Command, Stdio},
-    sync::mpsc,
+    sync::{mpsc, Arc},
     time::Duration,
 };
 
 use base64::{engine::general_purpose, Engine};
 use derive_more::Display;
 
-use futures::{channel::oneshot, stream, StreamExt};
+use futures::{channel::oneshot, stream, stream::BoxStream, StreamExt};
 use humansize::{format_size, DECIMAL};
 use once_cell::sync::Lazy;
 use tempfile::TempDir;
-use tokio::time::sleep;
+use tokio::{io::AsyncReadExt, task::JoinHandle, time::sleep};
 use uuid::Uuid;
 
 use crate::{
     language::get_local_text,
     state::{
         data_transfer::{TransferState, TransferStates},
+        integrity::IntegrityIssue,
         pending_message::FileProgression,
-        storage::Storage as uplink_storage,
+        storage::{ItemsPage, Storage as uplink_storage, ITEMS_PAGE_SIZE},
     },
     upload_file_channel::{UploadFileAction, UPLOAD_FILE_LISTENER},
-    ROOT_DIR_NAME, VIDEO_FILE_EXTENSIONS,
+    ROOT_DIR_NAME, STATIC_ARGS, VIDEO_FILE_EXTENSIONS,
+};
+use crate::{
+    warp_runner::{
+        bandwidth, checksum,
+        manager::cache::{self, QueryKey},
+        storage_backend, transfer_queue, Storage as warp_storage,
+    },
+    DOC_EXTENSIONS,
 };
-use crate::{warp_runner::Storage as warp_storage, DOC_EXTENSIONS};
 
 use warp::{
     constellation::{
@@ -39,7 +48,7 @@ use warp::{
     error::Error,
 };
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use tracing::log;
 
 static DIRECTORIES_AVAILABLE_TO_BROWSE: Lazy<RwLock<Vec<Directory>>> =
@@ -51,6 +60,18 @@ pub enum ConstellationCmd {
     GetItemsFromCurrentDirectory {
         rsp: oneshot::Sender<Result<uplink_storage, warp::error::Error>>,
     },
+    /// the next items of a directory which has more than `ITEMS_PAGE_SIZE` of them
+    #[display(fmt = "GetItemsPaged {{ offset: {offset} }} ")]
+    GetItemsPaged {
+        offset: usize,
+        rsp: oneshot::Sender<Result<ItemsPage, warp::error::Error>>,
+    },
+    /// the size of the files in the directory and its subdirectories
+    #[display(fmt = "GetDirectorySize {{ directory: {} }} ", "directory.name()")]
+    GetDirectorySize {
+        directory: Directory,
+        rsp: oneshot::Sender<Result<usize, warp::error::Error>>,
+    },
     #[display(fmt = "CreateNewDirectory {{ directory_name: {directory_name} }} ")]
     CreateNewDirectory {
         directory_name: String,
@@ -87,14 +108,77 @@ pub enum ConstellationCmd {
         item: Item,
         rsp: oneshot::Sender<Result<uplink_storage, warp::error::Error>>,
     },
+    /// runs `cmd` in the directory at `path`, like "photos/2023", for the control socket. the
+    /// directory the UI shows stays the same. `cmd` is dropped if there is no such directory
+    #[display(fmt = "InDirectory {{ path: {path}, cmd: {cmd} }} ")]
+    InDirectory {
+        path: String,
+        cmd: Box<ConstellationCmd>,
+    },
+    #[display(fmt = "CheckStorageIntegrity")]
+    CheckStorageIntegrity {
+        rsp: oneshot::Sender<Result<Vec<IntegrityIssue>, warp::error::Error>>,
+    },
+    /// returns the number of issues which were repaired
+    #[display(fmt = "RepairStorageIntegrity {{ issues: {} }} ", "issues.len()")]
+    RepairStorageIntegrity {
+        issues: Vec<IntegrityIssue>,
+        rsp: oneshot::Sender<Result<usize, warp::error::Error>>,
+    },
 }
 
 pub async fn handle_constellation_cmd(cmd: ConstellationCmd, warp_storage: &mut warp_storage) {
+    let changes = cache::constellation_cmd_changes(&cmd);
+    if let Some(changes) = &changes {
+        changes.invalidate();
+    }
+    match cmd {
+        ConstellationCmd::InDirectory { path, cmd } => {
+            let Some(directory) = directory_path(warp_storage, &path) else {
+                log::warn!("{path} is not a directory");
+                return;
+            };
+            let previous_path = warp_storage.get_path();
+            let opened = DIRECTORIES_AVAILABLE_TO_BROWSE.read().clone();
+            warp_storage.set_path(directory);
+            run_constellation_cmd(*cmd, warp_storage).await;
+            warp_storage.set_path(previous_path);
+            *DIRECTORIES_AVAILABLE_TO_BROWSE.write() = opened;
+        }
+        cmd => run_constellation_cmd(cmd, warp_storage).await,
+    }
+    if let Some(changes) = changes {
+        changes.invalidate();
+    }
+}
+
+async fn run_constellation_cmd(cmd: ConstellationCmd, warp_storage: &mut warp_storage) {
     match cmd {
         ConstellationCmd::GetItemsFromCurrentDirectory { rsp } => {
-            let r = get_items_from_current_directory(warp_storage);
+            let key = QueryKey::DirectoryItems(warp_storage.get_path());
+            let r = cache::read_through(key, async {
+                get_items_from_current_directory(warp_storage)
+            })
+            .await;
             let _ = rsp.send(r);
         }
+        ConstellationCmd::GetItemsPaged { offset, rsp } => {
+            let r = get_items_page(warp_storage, offset);
+            let _ = rsp.send(r);
+        }
+        ConstellationCmd::GetDirectorySize { directory, rsp } => {
+            // big trees take a while, the other commands shouldn't wait for them
+            tokio::spawn(async move {
+                let key = QueryKey::DirectorySize(directory.id());
+                let r = cache::read_through(key, async {
+                    tokio::task::spawn_blocking(move || directory_size(&directory))
+                        .await
+                        .map_err(|e| anyhow::Error::from(e).into())
+                })
+                .await;
+                let _ = rsp.send(r);
+            });
+        }
         ConstellationCmd::CreateNewDirectory {
             directory_name,
             rsp,
@@ -136,9 +220,145 @@ pub async fn handle_constellation_cmd(cmd: ConstellationCmd, warp_storage: &mut
             let r = delete_items(warp_storage, item).await;
             let _ = rsp.send(r);
         }
+        ConstellationCmd::CheckStorageIntegrity { rsp } => {
+            let r = check_storage_integrity(warp_storage).await;
+            let _ = rsp.send(r);
+        }
+        ConstellationCmd::RepairStorageIntegrity { issues, rsp } => {
+            let r = repair_storage_integrity(warp_storage, issues).await;
+            let _ = rsp.send(r);
+        }
+        ConstellationCmd::InDirectory { cmd, .. } => {
+            log::warn!("ignored {cmd}, InDirectory commands can't be nested");
+        }
+    }
+}
+
+// the path warp uses for the directory at `path`, in the format of `collect_files`
+fn directory_path(warp_storage: &warp_storage, path: &str) -> Option<PathBuf> {
+    let path = path.trim_matches('/');
+    if !path.is_empty() {
+        warp_storage
+            .root_directory()
+            .get_item_by_path(path)
+            .ok()?
+            .get_directory()
+            .ok()?;
+    }
+    Some(PathBuf::from(path))
+}
+
+async fn check_storage_integrity(
+    warp_storage: &mut warp_storage,
+) -> Result<Vec<IntegrityIssue>, Error> {
+    let mut files = vec![];
+    collect_files(&warp_storage.root_directory(), PathBuf::new(), &mut files);
+
+    let mut issues = vec![];
+    let mut file_ids = HashSet::new();
+    let previous_path = warp_storage.get_path();
+    for (directory, file) in files {
+        file_ids.insert(file.id());
+        // empty files are fine, only a blob which can't be read is missing
+        warp_storage.set_path(directory.clone());
+        if !blob_readable(warp_storage, &file.name()).await {
+            issues.push(IntegrityIssue::MissingBlob {
+                directory,
+                name: file.name(),
+            });
+        }
+    }
+    warp_storage.set_path(previous_path);
+
+    // file previews are cached in the temp folder as `<file id>.<extension>`. Anything else in
+    // there (chat attachments, cropped images) isn't tied to storage, so it is left alone.
+    let temp_files = STATIC_ARGS.temp_files.clone();
+    let orphaned = tokio::task::spawn_blocking(move || -> std::io::Result<Vec<PathBuf>> {
+        let mut orphaned = vec![];
+        if !temp_files.exists() {
+            return Ok(orphaned);
+        }
+        for entry in std::fs::read_dir(temp_files)? {
+            let path = entry?.path();
+            let id = path
+                .file_stem()
+                .and_then(OsStr::to_str)
+                .and_then(|stem| Uuid::parse_str(stem).ok());
+            if matches!(id, Some(id) if !file_ids.contains(&id)) {
+                orphaned.push(path);
+            }
+        }
+        Ok(orphaned)
+    })
+    .await
+    .map_err(anyhow::Error::from)?
+    .map_err(|e| Error::OtherWithContext(e.to_string()))?;
+
+    issues.extend(
+        orphaned
+            .into_iter()
+            .map(|path| IntegrityIssue::OrphanedThumbnail { path }),
+    );
+
+    log::info!("storage integrity check found {} issues", issues.len());
+    Ok(issues)
+}
+
+// fetches the first chunk of the file in the current directory
+async fn blob_readable(warp_storage: &warp_storage, name: &str) -> bool {
+    match warp_storage.get_stream(name).await {
+        Ok(mut stream) => !matches!(stream.next().await, Some(Err(_))),
+        Err(e) => {
+            log::warn!("contents of {name} can't be read: {e}");
+            false
+        }
+    }
+}
+
+fn collect_files(directory: &Directory, path: PathBuf, files: &mut Vec<(PathBuf, File)>) {
+    for item in directory.get_items() {
+        if let Ok(file) = item.get_file() {
+            files.push((path.clone(), file));
+        } else if let Ok(dir) = item.get_directory() {
+            let sub_path =
+                PathBuf::from(path.join(dir.name()).to_string_lossy().replace('\\', "/"));
+            collect_files(&dir, sub_path, files);
+        }
     }
 }
 
+async fn repair_storage_integrity(
+    warp_storage: &mut warp_storage,
+    issues: Vec<IntegrityIssue>,
+) -> Result<usize, Error> {
+    let previous_path = warp_storage.get_path();
+    let mut repaired = 0;
+    for issue in issues {
+        match issue {
+            IntegrityIssue::OrphanedThumbnail { path } => {
+                match tokio::fs::remove_file(&path).await {
+                    Ok(_) => repaired += 1,
+                    Err(e) => log::error!("failed to remove orphaned thumbnail {path:?}: {e}"),
+                }
+            }
+            IntegrityIssue::MissingBlob { directory, name } => {
+                warp_storage.set_path(directory);
+                match warp_storage.remove(&name, false).await {
+                    Ok(_) => repaired += 1,
+                    Err(e) => {
+                        log::error!("failed to remove file with missing contents {name}: {e}")
+                    }
+                }
+            }
+            // repaired by State
+            _ => {}
+        }
+    }
+    warp_storage.set_path(previous_path);
+    log::info!("repaired {repaired} storage integrity issues");
+    Ok(repaired)
+}
+
 async fn delete_items(
     warp_storage: &mut warp_storage,
     item: Item,
@@ -276,19 +496,10 @@ fn get_items_from_current_directory(
     let mut current_dirs = get_directories_opened();
     set_new_directory_opened(current_dirs.as_mut(), current_dir.clone());
 
-    let items = current_dir.get_items();
-
-    let mut directories = items
-        .iter()
-        .filter_map(|item| item.get_directory().ok())
-        .collect::<Vec<_>>();
-    let mut files = items
-        .iter()
-        .filter_map(|item| item.get_file().ok())
-        .collect::<Vec<_>>();
-
-    directories.sort_by_key(|b| std::cmp::Reverse(b.creation()));
-    files.sort_by_key(|b| std::cmp::Reverse(b.creation()));
+    let (mut directories, mut files) = sorted_items(&current_dir);
+    let total_items = directories.len() + files.len();
+    directories.truncate(ITEMS_PAGE_SIZE);
+    files.truncate(ITEMS_PAGE_SIZE - directories.len());
 
     let max_size = warp_storage.max_size();
     let current_size = warp_storage.current_size();
@@ -300,6 +511,7 @@ fn get_items_from_current_directory(
         directories_opened: get_directories_opened(),
         directories,
         files,
+        total_items,
         max_size,
         current_size,
         files_in_queue_to_upload,
@@ -308,6 +520,52 @@ fn get_items_from_current_directory(
     Ok(uplink_storage)
 }
 
+fn get_items_page(warp_storage: &mut warp_storage, offset: usize) -> Result<ItemsPage, Error> {
+    let current_dir = warp_storage.current_directory()?;
+    let (directories, files) = sorted_items(&current_dir);
+    let total_items = directories.len() + files.len();
+    let end = (offset + ITEMS_PAGE_SIZE).min(total_items);
+    let start = offset.min(end);
+    let num_dirs = directories.len();
+
+    Ok(ItemsPage {
+        directory_id: current_dir.id(),
+        offset,
+        directories: directories[start.min(num_dirs)..end.min(num_dirs)].to_vec(),
+        files: files[start.saturating_sub(num_dirs)..end.saturating_sub(num_dirs)].to_vec(),
+        total_items,
+    })
+}
+
+fn directory_size(directory: &Directory) -> usize {
+    directory
+        .get_items()
+        .iter()
+        .map(|item| match item.get_directory() {
+            Ok(directory) => directory_size(&directory),
+            Err(_) => item.size(),
+        })
+        .sum()
+}
+
+// directories and files, each sorted from the newest to the oldest
+fn sorted_items(current_dir: &Directory) -> (Vec<Directory>, Vec<File>) {
+    let items = current_dir.get_items();
+
+    let mut directories = items
+        .iter()
+        .filter_map(|item| item.get_directory().ok())
+        .collect::<Vec<_>>();
+    let mut files = items
+        .iter()
+        .filter_map(|item| item.get_file().ok())
+        .collect::<Vec<_>>();
+
+    directories.sort_by_key(|b| std::cmp::Reverse(b.creation()));
+    files.sort_by_key(|b| std::cmp::Reverse(b.creation()));
+    (directories, files)
+}
+
 fn get_directories_opened() -> Vec<Directory> {
     let mut dirs_opened = DIRECTORIES_AVAILABLE_TO_BROWSE.read().clone();
     if let Some(first_dir) = dirs_opened.first() {
@@ -378,6 +636,9 @@ async fn upload_files(warp_storage: &mut warp_storage, files_path: Vec<PathBuf>)
             return;
         }
     };
+    // the files go to the directory they were added in, also if the user opens another one
+    // before their turn comes
+    let target_path = warp_storage.get_path();
 
     let max_size_ipfs = warp_storage.max_size();
     let (tx, rx) = mpsc::channel();
@@ -426,32 +687,81 @@ async fn upload_files(warp_storage: &mut warp_storage, files_path: Vec<PathBuf>)
         let file_id = Uuid::new_v4();
         let file_state = TransferState::new();
         filename = rename_if_duplicate(current_directory.clone(), filename.clone(), file);
-        let _ = tx_upload_file.send(UploadFileAction::Starting(
+        let _ = tx_upload_file.send(UploadFileAction::Queued(
             file_id,
             file_state.clone(),
             filename.clone(),
         ));
 
-        match warp_storage.put(&filename, &local_path).await {
-            Ok(upload_progress) => {
-                // Handle each upload on another thread
-                let mut warp_storage = warp_storage.clone();
-                let res = tx.clone();
-                tokio::spawn(async move {
+        // Handle each upload on another thread, once it's its turn
+        let mut warp_storage = warp_storage.clone();
+        let directory = current_directory.clone();
+        let target_path = target_path.clone();
+        let res = tx.clone();
+        let tx_upload_file = tx_upload_file.clone();
+        tokio::spawn(async move {
+            let Some(_slot) = transfer_queue::UPLOADS
+                .wait_turn(file_id, &file_state)
+                .await
+            else {
+                log::info!("{:?} file cancelled before it started", filename);
+                let _ = tx_upload_file.send(UploadFileAction::Remove(file_path, file_id));
+                return;
+            };
+            // the files uploaded while this one waited count too
+            if warp_storage.current_size() + file_size > warp_storage.max_size() {
+                log::error!("Not available space for this file, {}", filename);
+                let _ = tx_upload_file.send(UploadFileAction::SizeNotAvailable(
+                    file_path.clone(),
+                    original.clone(),
+                ));
+                let _ = tx_upload_file.send(UploadFileAction::Remove(file_path, file_id));
+                return;
+            }
+            let filename =
+                rename_if_duplicate(directory.clone(), filename, PathBuf::from(&original));
+            let name_in_target = path_in(&target_path, &filename);
+            let _ = tx_upload_file.send(UploadFileAction::Starting(
+                file_id,
+                file_state.clone(),
+                filename.clone(),
+            ));
+            // hashed next to the upload, which reads the file on its own
+            let hashing = {
+                let path = file_path.clone();
+                tokio::task::spawn_blocking(move || checksum::of_file(&path))
+            };
+            let upload = if bandwidth::UPLOADS.is_limited() {
+                put_limited(&mut warp_storage, &name_in_target, &local_path, file_size).await
+            } else {
+                warp_storage.put(&name_in_target, &local_path).await
+            };
+            match upload {
+                Ok(upload_progress) => {
                     handle_upload_progress(
-                        &mut warp_storage,
+                        directory,
                         upload_progress,
                         filename,
                         file_id,
                         file_state,
                         file_path.clone(),
+                        file_size,
+                        hashing,
                     )
                     .await;
                     let _ = res.send(file_path);
-                });
+                }
+                Err(error) => {
+                    log::error!("Error when upload file: {:?}", error);
+                    let _ = tx_upload_file.send(UploadFileAction::Error(
+                        Some(file_path.clone()),
+                        Some(file_id),
+                    ));
+                    sleep(Duration::from_secs(3)).await;
+                    let _ = tx_upload_file.send(UploadFileAction::Remove(file_path, file_id));
+                }
             }
-            Err(error) => log::error!("Error when upload file: {:?}", error),
-        }
+        });
     }
     let mut warp_storage = warp_storage.clone();
     // Spawn a listener for when all files finished uploading
@@ -472,13 +782,79 @@ async fn upload_files(warp_storage: &mut warp_storage, files_path: Vec<PathBuf>)
     });
 }
 
-async fn handle_upload_progress(
+// reads the file in chunks, each of them waiting for the upload limit. warp only sees the end of
+// the chunks, so a file which can't be read all the way is removed again and reported as failed
+async fn put_limited(
     warp_storage: &mut warp_storage,
+    name: &str,
+    path: &str,
+    size: usize,
+) -> Result<ConstellationProgressStream, Error> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(anyhow::Error::from)?;
+    let read_error = Arc::new(Mutex::new(None));
+    let chunks = stream::unfold(file, {
+        let read_error = read_error.clone();
+        move |mut file| {
+            let read_error = read_error.clone();
+            async move {
+                let mut chunk = vec![0; bandwidth::CHUNK_SIZE];
+                match file.read(&mut chunk).await {
+                    Ok(0) => None,
+                    Ok(read) => {
+                        chunk.truncate(read);
+                        bandwidth::UPLOADS.acquire(read).await;
+                        Some((chunk, file))
+                    }
+                    Err(e) => {
+                        log::error!("failed to read file to upload: {e}");
+                        *read_error.lock() = Some(e);
+                        None
+                    }
+                }
+            }
+        }
+    });
+    let progress = warp_storage
+        .put_stream(name, Some(size), chunks.boxed())
+        .await?;
+    let warp_storage = warp_storage.clone();
+    let progress = progress.then(move |progress| {
+        let failed = match progress {
+            Progression::ProgressComplete { .. } => {
+                read_error.lock().take().map(|e| (e, warp_storage.clone()))
+            }
+            _ => None,
+        };
+        async move {
+            match (failed, progress) {
+                (Some((e, mut warp_storage)), Progression::ProgressComplete { name, total }) => {
+                    if let Err(e) = warp_storage.remove(&name, false).await {
+                        log::error!("failed to remove partly uploaded {name}: {e}");
+                    }
+                    Progression::ProgressFailed {
+                        name,
+                        last_size: total,
+                        error: Error::OtherWithContext(e.to_string()),
+                    }
+                }
+                (_, progress) => progress,
+            }
+        }
+    });
+    Ok(progress.boxed())
+}
+
+async fn handle_upload_progress(
+    directory: Directory,
     upload_progress: ConstellationProgressStream,
     filename: String,
     file_id: Uuid,
     file_state: TransferState,
     file_path: PathBuf,
+    file_size: usize,
+    hashing: JoinHandle<std::io::Result<blake3::Hash>>,
 ) {
     let tx_upload_file = UPLOAD_FILE_LISTENER.tx.clone();
     let mut previous_percentage: usize = 0;
@@ -581,6 +957,26 @@ async fn handle_upload_progress(
         file_id,
     )));
 
+    // the name was free when the upload started, a file of another size under it isn't this upload
+    let uploaded = directory
+        .get_item(&filename)
+        .ok()
+        .filter(|item| item.size() == file_size);
+    if let Some(item) = &uploaded {
+        storage_backend::pin(item);
+    }
+    let stored = match (hashing.await, &uploaded) {
+        (Ok(Ok(hash)), Some(item)) => checksum::store(item, &hash).await,
+        (Ok(Ok(_)), None) => Err(Error::OtherWithContext(
+            "the uploaded file wasn't found".into(),
+        )),
+        (Ok(Err(e)), _) => Err(Error::OtherWithContext(e.to_string())),
+        (Err(e), _) => Err(anyhow::Error::from(e).into()),
+    };
+    if let Err(e) = stored {
+        log::error!("failed to store the checksum of {filename}: {e}");
+    }
+
     let video_formats = VIDEO_FILE_EXTENSIONS.to_vec();
     let doc_formats = DOC_EXTENSIONS.to_vec();
 
@@ -591,8 +987,7 @@ async fn handle_upload_progress(
         .unwrap_or_default();
 
     if video_formats.iter().any(|f| f == &file_extension) {
-        match set_thumbnail_if_file_is_video(warp_storage, filename.clone(), file_path.clone())
-            .await
+        match set_thumbnail_if_file_is_video(&directory, filename.clone(), file_path.clone()).await
         {
             Ok(_) => {
                 log::info!("Video Thumbnail uploaded");
@@ -609,7 +1004,7 @@ async fn handle_upload_progress(
     }
 
     if doc_formats.iter().any(|f| f == &file_extension) {
-        match set_thumbnail_if_file_is_document(warp_storage, filename.clone(), file_path.clone())
+        match set_thumbnail_if_file_is_document(&directory, filename.clone(), file_path.clone())
             .await
         {
             Ok(_) => {
@@ -625,10 +1020,18 @@ async fn handle_upload_progress(
             }
         };
     }
+    // the listing is fetched again once the UI knows the upload finished
+    cache::invalidate_directories();
     let _ = tx_upload_file.send(UploadFileAction::Finishing(file_path, file_id));
     log::info!("{:?} file uploaded!", filename);
 }
 
+// the name `put` takes to store a file in the given directory rather than the current one
+fn path_in(directory: &Path, filename: &str) -> String {
+    let directory = directory.to_string_lossy().replace('\\', "/");
+    format!("{}/{}", directory.trim_end_matches('/'), filename)
+}
+
 fn rename_if_duplicate(
     current_directory: Directory,
     filename: String,
@@ -664,15 +1067,13 @@ fn rename_if_duplicate(
 }
 
 async fn set_thumbnail_if_file_is_video(
-    warp_storage: &warp_storage,
+    directory: &Directory,
     filename_to_save: String,
     file_path: PathBuf,
 ) -> Result<(), Error> {
-    let warp_storage = warp_storage.clone();
+    let directory = directory.clone();
     tokio::task::spawn_blocking(move || {
-        let item = warp_storage
-            .current_directory()?
-            .get_item(&filename_to_save)?;
+        let item = directory.get_item(&filename_to_save)?;
 
         let file_stem = file_path
             .file_stem()
@@ -723,15 +1124,13 @@ async fn set_thumbnail_if_file_is_video(
 }
 
 async fn set_thumbnail_if_file_is_document(
-    warp_storage: &warp_storage,
+    directory: &Directory,
     filename_to_save: String,
     file_path: PathBuf,
 ) -> Result<(), Error> {
-    let warp_storage = warp_storage.clone();
+    let directory = directory.clone();
     tokio::task::spawn_blocking(move || {
-        let item = warp_storage
-            .current_directory()?
-            .get_item(&filename_to_save)?;
+        let item = directory.get_item(&filename_to_save)?;
 
         let file_stem = file_path
             .file_stem()
@@ -785,44 +1184,133 @@ async fn download_file(
     file_name: String,
     local_path_to_save_file: PathBuf,
 ) -> Result<ConstellationProgressStream, Error> {
-    let size = warp_storage
+    let item = warp_storage
         .current_directory()?
         .get_item_by_path(&file_name)
-        .map(|d| d.size())
-        .unwrap_or_default();
-    let stream = warp_storage.get_stream(&file_name).await?;
-    let path = local_path_to_save_file.clone();
-    let mut file = std::fs::File::create(local_path_to_save_file)
-        .expect(&format!("Couldn't create file {:?}", path.as_os_str()));
-    let name = file_name.clone();
-    let name2 = file_name.clone();
-    let stream = stream
-        .map(move |v| match v {
-            Ok(data) => {
-                let _ = file.write(&data);
+        .ok();
+    let expected = match &item {
+        Some(item) => checksum::of_item(item).await,
+        None => None,
+    };
+    let download = Download {
+        warp_storage: warp_storage.clone(),
+        size: item.as_ref().map(|d| d.size()).unwrap_or_default(),
+        expected,
+        retries_left: checksum::retries(),
+        stream: warp_storage.get_stream(&file_name).await?,
+        file: create_download_file(&local_path_to_save_file)?,
+        hasher: blake3::Hasher::new(),
+        name: file_name,
+        path: local_path_to_save_file,
+        done: false,
+    };
+    let stream = stream::unfold(download, |mut download| async move {
+        if download.done {
+            return None;
+        }
+        let progression = download.next_progression().await;
+        Some((progression, download))
+    });
+    Ok(stream.boxed())
+}
+
+fn create_download_file(path: &Path) -> Result<std::fs::File, Error> {
+    std::fs::File::create(path).map_err(|e| {
+        Error::OtherWithContext(format!("Couldn't create file {:?}: {e}", path.as_os_str()))
+    })
+}
+
+// a download written to disk, which is checked against the hash stored with the file once it ends
+struct Download {
+    warp_storage: warp_storage,
+    name: String,
+    path: PathBuf,
+    size: usize,
+    // `None` for files uploaded before they had one, which aren't checked
+    expected: Option<blake3::Hash>,
+    retries_left: usize,
+    stream: BoxStream<'static, Result<Vec<u8>, Error>>,
+    file: std::fs::File,
+    hasher: blake3::Hasher,
+    done: bool,
+}
+
+impl Download {
+    async fn next_progression(&mut self) -> Progression {
+        match self.stream.next().await {
+            Some(Ok(data)) => {
+                bandwidth::DOWNLOADS.acquire(data.len()).await;
+                if let Err(e) = self.file.write_all(&data) {
+                    return self.failed(Error::OtherWithContext(e.to_string()));
+                }
+                self.hasher.update(&data);
                 Progression::CurrentProgress {
-                    name: file_name.clone(),
-                    current: file
-                        .metadata()
-                        .map(|d| d.len() as usize)
-                        .unwrap_or_default(),
-                    total: Some(size),
+                    name: self.name.clone(),
+                    current: self.written(),
+                    total: Some(self.size),
                 }
             }
-            Err(e) => Progression::ProgressFailed {
-                name: file_name.clone(),
-                last_size: file.metadata().map(|d| d.len() as usize).ok(),
-                error: e,
-            },
-        })
-        .chain(stream::once(async move {
-            Progression::ProgressComplete {
-                name,
-                total: path.metadata().map(|d| d.len() as usize).ok(),
+            Some(Err(e)) => self.failed(e),
+            None => self.verify().await,
+        }
+    }
+
+    async fn verify(&mut self) -> Progression {
+        let hash = self.hasher.finalize();
+        match self.expected {
+            Some(expected) if expected != hash => {
+                if self.retries_left == 0 {
+                    // a corrupted copy isn't worth keeping around
+                    let _ = std::fs::remove_file(&self.path);
+                    return self.failed(checksum::mismatch_error());
+                }
+                self.retries_left -= 1;
+                log::warn!(
+                    "{} doesn't match its checksum, downloading it again",
+                    self.name
+                );
+                if let Err(e) = self.restart().await {
+                    return self.failed(e);
+                }
+                Progression::CurrentProgress {
+                    name: self.name.clone(),
+                    current: 0,
+                    total: Some(self.size),
+                }
             }
-        }));
-    log::info!("{name2} downloaded");
-    Ok(stream.boxed())
+            _ => {
+                self.done = true;
+                log::info!("{} downloaded", self.name);
+                Progression::ProgressComplete {
+                    name: self.name.clone(),
+                    total: Some(self.written()),
+                }
+            }
+        }
+    }
+
+    async fn restart(&mut self) -> Result<(), Error> {
+        self.stream = self.warp_storage.get_stream(&self.name).await?;
+        self.file = create_download_file(&self.path)?;
+        self.hasher.reset();
+        Ok(())
+    }
+
+    fn failed(&mut self, error: Error) -> Progression {
+        self.done = true;
+        Progression::ProgressFailed {
+            name: self.name.clone(),
+            last_size: Some(self.written()),
+            error,
+        }
+    }
+
+    fn written(&self) -> usize {
+        self.file
+            .metadata()
+            .map(|d| d.len() as usize)
+            .unwrap_or_default()
+    }
 }
 
 pub fn thumbnail_to_base64(file: &File) -> String {
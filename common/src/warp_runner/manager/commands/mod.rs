@@ -1,3 +1,4 @@
+mod batch_commands;
 mod blink_commands;
 mod constellation_commands;
 mod multipass_commands;
@@ -6,9 +7,12 @@ mod raygun_commands;
 mod tesseract_commands;
 
 // this shortens the path required to use the functions and structs
+pub use batch_commands::{
+    send_batch, BatchCmd, BatchContext, BatchError, BatchProgress, BatchStep,
+};
 pub use blink_commands::{handle_blink_cmd, BlinkCmd};
 pub use constellation_commands::{handle_constellation_cmd, thumbnail_to_base64, ConstellationCmd};
 pub use multipass_commands::{handle_multipass_cmd, MultiPassCmd};
 pub use other_commands::*;
 pub use raygun_commands::{handle_raygun_cmd, RayGunCmd};
-pub use tesseract_commands::{handle_tesseract_cmd, TesseractCmd};
+pub use tesseract_commands::{handle_tesseract_cmd, DuressOutcome, PassphraseCheck, TesseractCmd};
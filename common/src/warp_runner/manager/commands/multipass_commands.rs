@@ -15,11 +15,22 @@ use warp::{
 };
 
 use tracing::log;
+use uuid::Uuid;
 
 use crate::{
     profile_update_channel::fetch_identity_data,
-    state::{self, Identity},
-    warp_runner::{ui_adapter::dids_to_identity, Account},
+    state::{
+        self,
+        custom_status::{self, CustomStatus},
+        extended_profile::ExtendedProfile,
+        Identity,
+    },
+    utils::device_link,
+    warp_runner::{
+        manager::cache::{self, QueryKey},
+        ui_adapter::dids_to_identity,
+        Account,
+    },
 };
 
 #[derive(Display)]
@@ -120,11 +131,23 @@ pub enum MultiPassCmd {
         banner: Vec<u8>,
         rsp: oneshot::Sender<Result<Identity, warp::error::Error>>,
     },
-    #[display(fmt = "UpdateStatusMessage")]
-    UpdateStatusMessage {
-        status: Option<String>,
+    /// sets the status message, and the metadata of its emoji and expiry
+    #[display(fmt = "UpdateCustomStatus")]
+    UpdateCustomStatus {
+        status: CustomStatus,
         rsp: oneshot::Sender<Result<Identity, warp::error::Error>>,
     },
+    #[display(fmt = "UpdateExtendedProfile")]
+    UpdateExtendedProfile {
+        profile: ExtendedProfile,
+        rsp: oneshot::Sender<Result<Identity, warp::error::Error>>,
+    },
+    /// adds a linked device to the revoked devices of the account
+    #[display(fmt = "RevokeDevice")]
+    RevokeDevice {
+        id: Uuid,
+        rsp: oneshot::Sender<Result<(), warp::error::Error>>,
+    },
     #[display(fmt = "UpdateUsername")]
     UpdateUsername {
         username: String,
@@ -156,6 +179,17 @@ impl std::fmt::Debug for MultiPassCmd {
 }
 
 pub async fn handle_multipass_cmd(cmd: MultiPassCmd, warp: &mut super::super::Warp) {
+    let changes = cache::multipass_cmd_changes(&cmd);
+    if let Some(changes) = &changes {
+        changes.invalidate();
+    }
+    run_multipass_cmd(cmd, warp).await;
+    if let Some(changes) = changes {
+        changes.invalidate();
+    }
+}
+
+async fn run_multipass_cmd(cmd: MultiPassCmd, warp: &mut super::super::Warp) {
     match cmd {
         MultiPassCmd::CreateIdentity { .. }
         | MultiPassCmd::TryLogIn { .. }
@@ -252,19 +286,23 @@ pub async fn handle_multipass_cmd(cmd: MultiPassCmd, warp: &mut super::super::Wa
             let _ = rsp.send(r);
         }
         MultiPassCmd::GetProfilePicture { did, rsp } => {
-            let pfp = warp
-                .multipass
-                .identity_picture(&did)
-                .await
-                .map(|img| identity_image_to_base64(&img));
+            let pfp = cache::read_through(QueryKey::ProfilePicture(did.clone()), async {
+                warp.multipass
+                    .identity_picture(&did)
+                    .await
+                    .map(|img| identity_image_to_base64(&img))
+            })
+            .await;
             let _ = rsp.send(pfp);
         }
         MultiPassCmd::GetProfileBanner { did, rsp } => {
-            let pfb = warp
-                .multipass
-                .identity_banner(&did)
-                .await
-                .map(|img| identity_image_to_base64(&img));
+            let pfb = cache::read_through(QueryKey::ProfileBanner(did.clone()), async {
+                warp.multipass
+                    .identity_banner(&did)
+                    .await
+                    .map(|img| identity_image_to_base64(&img))
+            })
+            .await;
             let _ = rsp.send(pfb);
         }
         MultiPassCmd::ClearProfilePicture { rsp } => {
@@ -362,11 +400,29 @@ pub async fn handle_multipass_cmd(cmd: MultiPassCmd, warp: &mut super::super::Wa
                 }
             };
         }
-        MultiPassCmd::UpdateStatusMessage { status, rsp } => {
-            let r = warp
+        MultiPassCmd::UpdateCustomStatus { status, rsp } => {
+            let mut r = warp
                 .multipass
-                .update_identity(IdentityUpdate::StatusMessage(status))
+                .update_identity(IdentityUpdate::StatusMessage(status.to_message()))
                 .await;
+            if r.is_ok() {
+                let key = custom_status::STATUS_KEY.to_string();
+                r = match status.to_metadata() {
+                    Some(value) => {
+                        warp.multipass
+                            .update_identity(IdentityUpdate::AddMetadataKey { key, value })
+                            .await
+                    }
+                    // removing a key which was never set fails, which is fine
+                    None => {
+                        let _ = warp
+                            .multipass
+                            .update_identity(IdentityUpdate::RemoveMetadataKey { key })
+                            .await;
+                        Ok(())
+                    }
+                };
+            }
             let mut id = match warp.multipass.get_own_identity().await.map(Identity::from) {
                 Ok(id) => id,
                 Err(e) => {
@@ -383,6 +439,62 @@ pub async fn handle_multipass_cmd(cmd: MultiPassCmd, warp: &mut super::super::Wa
                 }
             };
         }
+        MultiPassCmd::UpdateExtendedProfile { profile, rsp } => {
+            let mut r = Ok(());
+            for (key, value) in profile.to_metadata() {
+                // removing a key which was never set fails, which is fine
+                let update = match value {
+                    Some(value) => IdentityUpdate::AddMetadataKey { key, value },
+                    None => {
+                        let _ = warp
+                            .multipass
+                            .update_identity(IdentityUpdate::RemoveMetadataKey { key })
+                            .await;
+                        continue;
+                    }
+                };
+                if let Err(e) = warp.multipass.update_identity(update).await {
+                    r = Err(e);
+                    break;
+                }
+            }
+            let mut id = match warp.multipass.get_own_identity().await.map(Identity::from) {
+                Ok(id) => id,
+                Err(e) => {
+                    let _ = rsp.send(Err(e));
+                    return;
+                }
+            };
+            update_identity(&mut id, warp).await;
+            let _ = match r {
+                Ok(_) => rsp.send(Ok(id)),
+                Err(e) => {
+                    log::error!("failed to update the profile: {e}");
+                    rsp.send(Err(e))
+                }
+            };
+        }
+        MultiPassCmd::RevokeDevice { id, rsp } => {
+            let r = match warp.multipass.get_own_identity().await {
+                Ok(own) => {
+                    let mut revoked = device_link::revoked_devices(&own.metadata());
+                    if !revoked.contains(&id) {
+                        revoked.push(id);
+                    }
+                    warp.multipass
+                        .update_identity(IdentityUpdate::AddMetadataKey {
+                            key: device_link::REVOKED_DEVICES_KEY.into(),
+                            value: device_link::revoked_devices_value(&revoked),
+                        })
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &r {
+                log::error!("failed to revoke device {id}: {e}");
+            }
+            let _ = rsp.send(r);
+        }
         MultiPassCmd::UpdateUsername { username, rsp } => {
             let r = warp
                 .multipass
@@ -405,22 +517,21 @@ pub async fn handle_multipass_cmd(cmd: MultiPassCmd, warp: &mut super::super::Wa
             };
         }
         MultiPassCmd::GetIdentity { did, rsp } => {
-            let r = match warp
-                .multipass
-                .get_identity(Identifier::DID(did.clone()))
-                .await
-            {
-                Ok(ids) => {
-                    if ids.is_empty() {
-                        Err(Error::IdentityDoesntExist)
-                    } else {
-                        let mut id = Identity::from(ids[0].clone());
+            let r = cache::read_through(QueryKey::Identity(did.clone()), async {
+                let ids = warp
+                    .multipass
+                    .get_identity(Identifier::DID(did.clone()))
+                    .await?;
+                match ids.first() {
+                    Some(id) => {
+                        let mut id = Identity::from(id.clone());
                         update_identity(&mut id, warp).await;
                         Ok(id)
                     }
+                    None => Err(Error::IdentityDoesntExist),
                 }
-                Err(err) => Err(err),
-            };
+            })
+            .await;
             let _ = rsp.send(r);
         }
         MultiPassCmd::SetStatus { status, rsp } => {
@@ -1,6 +1,6 @@
 use std::{
     fs::File,
-    io::{Seek, Write},
+    io::{Cursor, Seek, Write},
     path::{Path, PathBuf},
 };
 use walkdir::WalkDir;
@@ -8,9 +8,12 @@ use zip::{result::ZipError, write::FileOptions};
 
 use derive_more::Display;
 use futures::channel::oneshot;
-use warp::error;
+use warp::{crypto::cipher::Cipher, error};
 
 use tracing::log;
+use uuid::Uuid;
+
+use crate::{warp_runner::manager::MainLoopPause, STATIC_ARGS};
 
 #[derive(Display)]
 pub enum OtherCmd {
@@ -20,17 +23,143 @@ pub enum OtherCmd {
         dest: PathBuf,
         rsp: oneshot::Sender<Result<(), error::Error>>,
     },
+    /// writes the data of the active profile to `dest`, encrypted with the passphrase. the paths in
+    /// `exclude` are relative to `src` and left out.
+    #[display(fmt = "ExportBackup {{ src: {src:?}, dest: {dest:?} }} ")]
+    ExportBackup {
+        src: PathBuf,
+        exclude: Vec<PathBuf>,
+        passphrase: String,
+        dest: PathBuf,
+        rsp: oneshot::Sender<Result<(), error::Error>>,
+    },
 }
 
-pub async fn handle_other_cmd(cmd: OtherCmd) {
+// written at the start of every backup, followed by the encrypted zip of the profile directory
+const BACKUP_HEADER: &[u8] = b"UPLINK-BACKUP-1\n";
+pub const BACKUP_EXTENSION: &str = "uplinkbackup";
+
+/// `pause` is `None` when the command runs in the main loop, which then handles nothing else meanwhile
+pub async fn handle_other_cmd(cmd: OtherCmd, pause: Option<&MainLoopPause>) {
     match cmd {
         OtherCmd::CompressFolder { src, dest, rsp } => {
             let r = compress_folder(src, dest).await;
             let _ = rsp.send(r);
         }
+        OtherCmd::ExportBackup {
+            src,
+            exclude,
+            passphrase,
+            dest,
+            rsp,
+        } => {
+            // the profile is copied while warp_runner doesn't change it, the copy is compressed and
+            // encrypted afterwards
+            let snapshot = STATIC_ARGS.temp_files.join(Uuid::new_v4().to_string());
+            let copied = match pause {
+                Some(pause) => pause.run(copy_profile(&src, &exclude, &snapshot)).await,
+                None => copy_profile(&src, &exclude, &snapshot).await,
+            };
+            let r = match copied {
+                Ok(_) => export_backup(snapshot.clone(), vec![], passphrase, dest).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = tokio::fs::remove_dir_all(&snapshot).await {
+                log::warn!("failed to remove the copy of the profile: {e}");
+            }
+            let _ = rsp.send(r);
+        }
     }
 }
 
+fn other_error(e: impl ToString) -> error::Error {
+    error::Error::OtherWithContext(e.to_string())
+}
+
+async fn export_backup(
+    src: PathBuf,
+    exclude: Vec<PathBuf>,
+    passphrase: String,
+    dest: PathBuf,
+) -> Result<(), error::Error> {
+    tokio::task::spawn_blocking(move || {
+        let mut bundle = Cursor::new(Vec::new());
+        let prefix = src.to_string_lossy().to_string();
+        let exclude: Vec<PathBuf> = exclude.iter().map(|path| src.join(path)).collect();
+        zip_dir(
+            &mut WalkDir::new(&src)
+                .into_iter()
+                .filter_entry(|e| !exclude.iter().any(|path| path == e.path()))
+                .filter_map(|e| e.ok()),
+            &prefix,
+            &mut bundle,
+            zip::CompressionMethod::Bzip2,
+        )
+        .map_err(other_error)?;
+        let encrypted = Cipher::direct_encrypt(bundle.get_ref(), passphrase.as_bytes())?;
+
+        let mut file = File::create(dest).map_err(other_error)?;
+        file.write_all(BACKUP_HEADER).map_err(other_error)?;
+        file.write_all(&encrypted).map_err(other_error)?;
+        file.sync_all().map_err(other_error)
+    })
+    .await
+    .map_err(other_error)?
+}
+
+// copies `src` to `dest` without the paths in `exclude`, which are relative to `src`
+async fn copy_profile(src: &Path, exclude: &[PathBuf], dest: &Path) -> Result<(), error::Error> {
+    let (src, dest) = (src.to_path_buf(), dest.to_path_buf());
+    let exclude: Vec<PathBuf> = exclude.iter().map(|path| src.join(path)).collect();
+    tokio::task::spawn_blocking(move || {
+        let entries = WalkDir::new(&src)
+            .into_iter()
+            .filter_entry(|e| !exclude.iter().any(|path| path == e.path()));
+        for entry in entries {
+            let entry = entry.map_err(other_error)?;
+            let Ok(name) = entry.path().strip_prefix(&src) else {
+                continue;
+            };
+            let target = dest.join(name);
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(&target).map_err(other_error)?;
+            } else if entry.file_type().is_file() {
+                std::fs::copy(entry.path(), &target).map_err(other_error)?;
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(other_error)?
+}
+
+/// Decrypts a backup made by `OtherCmd::ExportBackup` and extracts it into `dest`, which should be
+/// the directory of a new profile. Nothing is left behind if the passphrase is wrong.
+pub async fn restore_backup(
+    src: PathBuf,
+    passphrase: String,
+    dest: PathBuf,
+) -> Result<(), error::Error> {
+    tokio::task::spawn_blocking(move || {
+        let data = std::fs::read(src).map_err(other_error)?;
+        let encrypted = data
+            .strip_prefix(BACKUP_HEADER)
+            .ok_or_else(|| other_error("not an Uplink backup"))?;
+        let bundle = Cipher::direct_decrypt(encrypted, passphrase.as_bytes())?;
+        let mut archive = zip::ZipArchive::new(Cursor::new(bundle)).map_err(other_error)?;
+
+        let extracted = std::fs::create_dir_all(&dest)
+            .map_err(other_error)
+            .and_then(|_| archive.extract(&dest).map_err(other_error));
+        if extracted.is_err() {
+            let _ = std::fs::remove_dir_all(&dest);
+        }
+        extracted
+    })
+    .await
+    .map_err(other_error)?
+}
+
 async fn compress_folder(src: PathBuf, dest: PathBuf) -> Result<(), error::Error> {
     // I know that warp_runner is basically single threaded but still...put the blocking operation in a separate task and await it
     let handle = tokio::task::spawn_blocking(move || {
@@ -101,3 +230,52 @@ where
     zip.finish()?;
     Result::Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn backup_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = dir.path().join("profile");
+        std::fs::create_dir_all(profile.join("warp")).unwrap();
+        std::fs::write(profile.join("state.json"), "{}").unwrap();
+        std::fs::write(profile.join("warp").join("tesseract.json"), "keys").unwrap();
+        std::fs::write(profile.join("messages.db"), "local").unwrap();
+        let backup = dir.path().join("backup.uplinkbackup");
+        let wrong = dir.path().join("wrong");
+        let restored = dir.path().join("restored");
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            export_backup(
+                profile,
+                vec!["messages.db".into()],
+                "passphrase".into(),
+                backup.clone(),
+            )
+            .await
+            .unwrap();
+            assert!(
+                restore_backup(backup.clone(), "wrong".into(), wrong.clone())
+                    .await
+                    .is_err()
+            );
+            restore_backup(backup, "passphrase".into(), restored.clone())
+                .await
+                .unwrap();
+        });
+
+        assert!(!wrong.exists());
+        assert!(!restored.join("messages.db").exists());
+        assert_eq!(
+            std::fs::read_to_string(restored.join("warp").join("tesseract.json")).unwrap(),
+            "keys"
+        );
+        assert_eq!(
+            std::fs::read_to_string(restored.join("state.json")).unwrap(),
+            "{}"
+        );
+    }
+}
@@ -5,6 +5,7 @@ use std::{
     collections::{HashMap, HashSet},
     ops::Range,
     path::PathBuf,
+    str::FromStr,
 };
 use uuid::Uuid;
 use warp::{
@@ -20,32 +21,69 @@ use warp::{
 use tracing::log;
 
 use crate::{
-    state::{chats, identity, Friends},
+    state::{
+        chats, identity,
+        utils::{find_group_mention, GroupMention},
+        Friends,
+    },
+    utils::incoming_webhooks,
     warp_runner::{
-        conv_stream,
+        bandwidth, conv_stream,
+        group_invites::{self, InviteLink},
+        group_roles::{self, GroupAction, GroupUpdate},
+        message_store,
+        read_receipts::ReadReceipt,
         ui_adapter::{
-            self, conversation_to_chat, dids_to_identity, fetch_messages2, fetch_messages_between,
-            fetch_messages_from_chat, fetch_pinned_messages_from_chat, get_uninitialized_identity,
+            self, conversation_to_chat, dids_to_identity, fetch_merged_messages,
+            fetch_message_page, fetch_messages2, fetch_messages_between, fetch_messages_from_chat,
+            fetch_pinned_messages_from_chat, get_uninitialized_identity,
         },
-        Account, FetchMessagesConfig, FetchMessagesResponse, Messaging,
+        Account, FetchMessagesConfig, FetchMessagesResponse, GroupInvite, GroupRole, Messaging,
     },
 };
 
-pub type MultiChatResult = Vec<(Uuid, (Uuid, Option<AttachmentEventStream>))>;
+pub struct MultiChatResult {
+    // (conversation, (message, stream of the attachments))
+    pub sent: Vec<(Uuid, (Uuid, Option<AttachmentEventStream>))>,
+    // the conversations the message wasn't sent to, like groups where @everyone isn't allowed
+    pub failed: Vec<(Uuid, Error)>,
+}
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Display)]
 pub enum RayGunCmd {
     #[display(fmt = "InitializeWarp")]
     InitializeWarp {
+        // conversations which were merged locally: (conversation_id, duplicates merged into it)
+        merged: HashMap<Uuid, Vec<Uuid>>,
+        // roles and muted members of group conversations
+        roles: HashMap<Uuid, HashMap<DID, GroupRole>>,
+        muted: HashMap<Uuid, HashSet<DID>>,
         // need to send over own identity because 'State' sets it to default
         rsp: oneshot::Sender<Result<WarpInit, warp::error::Error>>,
     },
+    // sent after InitializeWarp, a few conversations at a time, so the sidebar fills in while the rest load
+    #[display(fmt = "LoadConversations")]
+    LoadConversations {
+        conv_ids: Vec<Uuid>,
+        rsp: oneshot::Sender<HashMap<Uuid, chats::Chat>>,
+    },
+    // the ids of all conversations, for clients which don't keep a State. see `control`
+    #[display(fmt = "ListConversations")]
+    ListConversations {
+        rsp: oneshot::Sender<Result<Vec<Uuid>, warp::error::Error>>,
+    },
     #[display(fmt = "CreateConversation")]
     CreateConversation {
         recipient: DID,
         rsp: oneshot::Sender<Result<Uuid, warp::error::Error>>,
     },
+    // a conversation with anyone, friend or not. see start_conversation
+    #[display(fmt = "StartConversation")]
+    StartConversation {
+        recipient: DID,
+        rsp: oneshot::Sender<Result<Uuid, warp::error::Error>>,
+    },
     #[display(fmt = "CreateGroupConversation")]
     CreateGroupConversation {
         recipients: Vec<DID>,
@@ -89,6 +127,14 @@ pub enum RayGunCmd {
         config: FetchMessagesConfig,
         rsp: oneshot::Sender<Result<FetchMessagesResponse, warp::error::Error>>,
     },
+    /// the newest messages of all conversations containing all the words of `query`, from the local
+    /// copy of the messages
+    #[display(fmt = "SearchMessages")]
+    SearchMessages {
+        query: String,
+        limit: usize,
+        rsp: oneshot::Sender<Vec<ui_adapter::Message>>,
+    },
     #[display(fmt = "FetchMessages {{ req: {to_fetch}, current_len: {current_len} }} ")]
     FetchMessagesDeprecated {
         conv_id: Uuid,
@@ -105,6 +151,14 @@ pub enum RayGunCmd {
         date_range: Range<DateTime<Utc>>,
         rsp: oneshot::Sender<Result<(Vec<ui_adapter::Message>, bool), warp::error::Error>>,
     },
+    // returns the shown messages in the range (oldest first) and the total number of messages, hidden ones included.
+    // used to export conversations
+    #[display(fmt = "FetchMessagePage {{ range: {range:?} }} ")]
+    FetchMessagePage {
+        conv_id: Uuid,
+        range: Range<usize>,
+        rsp: oneshot::Sender<Result<(Vec<raygun::Message>, usize), warp::error::Error>>,
+    },
     #[display(fmt = "FetchPinnedMessages")]
     FetchPinnedMessages {
         conv_id: Uuid,
@@ -117,6 +171,14 @@ pub enum RayGunCmd {
         attachments: Vec<Location>,
         rsp: oneshot::Sender<Result<(Uuid, Option<AttachmentEventStream>), warp::error::Error>>,
     },
+    /// sends the text under the name of a bot, see `incoming_webhooks`
+    #[display(fmt = "PostAsBot")]
+    PostAsBot {
+        conv_id: Uuid,
+        name: String,
+        text: String,
+        rsp: oneshot::Sender<Result<Uuid, warp::error::Error>>,
+    },
     #[display(fmt = "SendMessageForSeveralChats")]
     SendMessageForSeveralChats {
         convs_id: Vec<Uuid>,
@@ -159,6 +221,55 @@ pub enum RayGunCmd {
         recipient: DID,
         rsp: oneshot::Sender<Result<(), warp::error::Error>>,
     },
+    // shows the history of the duplicate conversations as part of conv_id. nothing is deleted from RayGun.
+    #[display(fmt = "MergeConversations")]
+    MergeConversations {
+        conv_id: Uuid,
+        duplicates: Vec<Uuid>,
+        rsp: oneshot::Sender<Result<(), warp::error::Error>>,
+    },
+    // only allowed for group admins
+    #[display(fmt = "SetGroupRole {{ role: {role} }} ")]
+    SetGroupRole {
+        conv_id: Uuid,
+        member: DID,
+        role: GroupRole,
+        rsp: oneshot::Sender<Result<(), warp::error::Error>>,
+    },
+    // messages and typing indicators from muted members are dropped. only allowed for group admins and moderators
+    #[display(fmt = "SetMemberMuted {{ muted: {muted} }} ")]
+    SetMemberMuted {
+        conv_id: Uuid,
+        member: DID,
+        muted: bool,
+        rsp: oneshot::Sender<Result<(), warp::error::Error>>,
+    },
+    // only allowed for members who can add participants
+    #[display(fmt = "CreateGroupInvite")]
+    CreateGroupInvite {
+        conv_id: Uuid,
+        expires: Option<DateTime<Utc>>,
+        max_uses: Option<u32>,
+        rsp: oneshot::Sender<Result<GroupInvite, warp::error::Error>>,
+    },
+    // returns the invites created by the user which are still valid
+    #[display(fmt = "ListGroupInvites")]
+    ListGroupInvites {
+        conv_id: Uuid,
+        rsp: oneshot::Sender<Vec<GroupInvite>>,
+    },
+    #[display(fmt = "RevokeGroupInvite")]
+    RevokeGroupInvite {
+        code: String,
+        rsp: oneshot::Sender<()>,
+    },
+    // sends the invite code to the inviter, who adds the user to the group once they receive it.
+    // returns the id of the direct conversation with the inviter
+    #[display(fmt = "JoinGroupViaInvite")]
+    JoinGroupViaInvite {
+        link: String,
+        rsp: oneshot::Sender<Result<Uuid, warp::error::Error>>,
+    },
     #[display(fmt = "React")]
     React {
         conversation_id: Uuid,
@@ -174,6 +285,13 @@ pub enum RayGunCmd {
         pinstate: PinState,
         rsp: oneshot::Sender<Result<(), warp::error::Error>>,
     },
+    // see `read_receipts`
+    #[display(fmt = "SendReadReceipt")]
+    SendReadReceipt {
+        conv_id: Uuid,
+        receipt: ReadReceipt,
+        rsp: oneshot::Sender<Result<(), warp::error::Error>>,
+    },
     #[display(fmt = "SendEvent")]
     SendEvent {
         conv_id: Uuid,
@@ -191,12 +309,47 @@ impl std::fmt::Debug for RayGunCmd {
 pub async fn handle_raygun_cmd(
     cmd: RayGunCmd,
     stream_manager: &mut conv_stream::Manager,
+    group_roles: &mut group_roles::Manager,
+    group_invites: &mut group_invites::Manager,
     account: &mut Account,
     messaging: &mut Messaging,
 ) {
     match cmd {
-        RayGunCmd::InitializeWarp { rsp } => {
+        RayGunCmd::InitializeWarp {
+            merged,
+            roles,
+            muted,
+            rsp,
+        } => {
+            for (conv_id, duplicates) in merged {
+                stream_manager.merge_conversations(conv_id, duplicates);
+            }
+            group_roles.init(roles, muted);
             let r = init_warp(stream_manager, account, messaging).await;
+            if r.is_ok() {
+                let caught_up = match account.get_own_identity().await {
+                    Ok(own) => {
+                        group_invites
+                            .catch_up(&own.did_key(), group_roles, messaging)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = caught_up {
+                    log::warn!("failed to handle the join requests sent while offline: {e}");
+                }
+            }
+            let _ = rsp.send(r);
+        }
+        RayGunCmd::LoadConversations { conv_ids, rsp } => {
+            let r = load_conversations(conv_ids, account, messaging).await;
+            let _ = rsp.send(r);
+        }
+        RayGunCmd::ListConversations { rsp } => {
+            let r = messaging
+                .list_conversations()
+                .await
+                .map(|convs| convs.iter().map(|conv| conv.id()).collect());
             let _ = rsp.send(r);
         }
         RayGunCmd::CreateConversation { recipient, rsp } => {
@@ -206,6 +359,10 @@ pub async fn handle_raygun_cmd(
             };
             let _ = rsp.send(r);
         }
+        RayGunCmd::StartConversation { recipient, rsp } => {
+            let r = start_conversation(recipient, account, messaging).await;
+            let _ = rsp.send(r);
+        }
         RayGunCmd::DeleteConversation { conv_id, rsp } => {
             let r = match messaging.delete(conv_id, None).await {
                 Ok(_) => Ok(conv_id),
@@ -228,7 +385,18 @@ pub async fn handle_raygun_cmd(
             recipients,
             rsp,
         } => {
-            let r = raygun_add_recipients_to_a_group(conv_id, recipients, messaging).await;
+            let r = match check_group_permission(
+                conv_id,
+                GroupAction::AddMembers,
+                group_roles,
+                account,
+                messaging,
+            )
+            .await
+            {
+                Ok(_) => raygun_add_recipients_to_a_group(conv_id, recipients, messaging).await,
+                Err(e) => Err(e),
+            };
             let _ = rsp.send(r);
         }
         RayGunCmd::RemoveGroupParticipants {
@@ -236,7 +404,20 @@ pub async fn handle_raygun_cmd(
             recipients,
             rsp,
         } => {
-            let r = raygun_remove_recipients_from_a_group(conv_id, recipients, messaging).await;
+            let r = match check_group_permission(
+                conv_id,
+                GroupAction::RemoveMembers,
+                group_roles,
+                account,
+                messaging,
+            )
+            .await
+            {
+                Ok(_) => {
+                    raygun_remove_recipients_from_a_group(conv_id, recipients, messaging).await
+                }
+                Err(e) => Err(e),
+            };
             let _ = rsp.send(r);
         }
         RayGunCmd::UpdateConversationName {
@@ -244,10 +425,21 @@ pub async fn handle_raygun_cmd(
             new_conversation_name,
             rsp,
         } => {
-            let r = messaging
-                .update_conversation_name(conv_id, &new_conversation_name)
-                .await
-                .map(|_| conv_id);
+            let r = match check_group_permission(
+                conv_id,
+                GroupAction::Rename,
+                group_roles,
+                account,
+                messaging,
+            )
+            .await
+            {
+                Ok(_) => messaging
+                    .update_conversation_name(conv_id, &new_conversation_name)
+                    .await
+                    .map(|_| conv_id),
+                Err(e) => Err(e),
+            };
             let _ = rsp.send(r);
         }
         RayGunCmd::UpdateConversationSettings {
@@ -261,12 +453,25 @@ pub async fn handle_raygun_cmd(
                 .map(|_| conv_id);
             let _ = rsp.send(r);
         }
+        RayGunCmd::SearchMessages { query, limit, rsp } => {
+            let _ = rsp.send(message_store::search(query, None, limit).await);
+        }
         RayGunCmd::FetchMessages {
             conv_id,
             config,
             rsp,
         } => {
-            let r = fetch_messages2(conv_id, messaging, config).await;
+            let merged = stream_manager.get_merged(&conv_id);
+            let r = if merged.is_empty() {
+                fetch_messages2(conv_id, messaging, config).await
+            } else {
+                fetch_merged_messages(conv_id, &merged, messaging, config).await
+            };
+            let r = r.map(|mut res| {
+                res.messages
+                    .retain(|msg| !group_roles.is_muted(&conv_id, &msg.inner.sender()));
+                res
+            });
             let _ = rsp.send(r);
         }
         RayGunCmd::FetchMessagesDeprecated {
@@ -286,6 +491,21 @@ pub async fn handle_raygun_cmd(
             let r = fetch_messages_between(conv_id, messaging, date_range).await;
             let _ = rsp.send(r);
         }
+        RayGunCmd::FetchMessagePage {
+            conv_id,
+            range,
+            rsp,
+        } => {
+            // the range is of all the messages, so a page may have fewer which are shown
+            let r =
+                fetch_message_page(conv_id, messaging, range)
+                    .await
+                    .map(|(mut messages, total)| {
+                        messages.retain(ui_adapter::is_shown);
+                        (messages, total)
+                    });
+            let _ = rsp.send(r);
+        }
         RayGunCmd::FetchPinnedMessages { conv_id, rsp } => {
             let r = fetch_pinned_messages_from_chat(conv_id, messaging).await;
             let _ = rsp.send(r);
@@ -296,6 +516,15 @@ pub async fn handle_raygun_cmd(
             attachments,
             rsp,
         } => {
+            let msg = incoming_webhooks::escape(msg);
+            let mentions_everyone =
+                match check_group_mention(conv_id, &msg, group_roles, account, messaging).await {
+                    Ok(mentions_everyone) => mentions_everyone,
+                    Err(e) => {
+                        let _ = rsp.send(Err(e));
+                        return;
+                    }
+                };
             let r = if attachments.is_empty() {
                 messaging.send(conv_id, msg).await.map(|id| (id, None))
             } else {
@@ -304,25 +533,66 @@ pub async fn handle_raygun_cmd(
                     .attach(conv_id, None, attachments.clone(), msg.clone())
                     .await
                 {
-                    Ok((id, stream)) => Result::Ok((id, Some(stream))),
+                    Ok((id, stream)) => {
+                        Result::Ok((id, Some(bandwidth::limit_attachments(stream))))
+                    }
                     Err(e) => Err(e),
                 }
             };
+            if mentions_everyone && r.is_ok() {
+                group_roles.record_everyone_mention(conv_id);
+            }
 
             let _ = rsp.send(r);
         }
+        RayGunCmd::PostAsBot {
+            conv_id,
+            name,
+            text,
+            rsp,
+        } => {
+            let msg = incoming_webhooks::bot_message(&name, &text);
+            let r = match check_group_mention(conv_id, &msg, group_roles, account, messaging).await
+            {
+                Ok(mentions_everyone) => {
+                    let r = messaging.send(conv_id, msg).await;
+                    if mentions_everyone && r.is_ok() {
+                        group_roles.record_everyone_mention(conv_id);
+                    }
+                    r
+                }
+                Err(e) => Err(e),
+            };
+            let _ = rsp.send(r);
+        }
         RayGunCmd::SendMessageForSeveralChats {
             convs_id,
             msg,
             attachments,
             rsp,
         } => {
-            let mut results = vec![];
+            let msg = incoming_webhooks::escape(msg);
+            let mut results = MultiChatResult {
+                sent: vec![],
+                failed: vec![],
+            };
             for chat_id in convs_id {
+                match check_group_mention(chat_id, &msg, group_roles, account, messaging).await {
+                    Ok(true) => group_roles.record_everyone_mention(chat_id),
+                    Ok(false) => {}
+                    Err(e) => {
+                        log::error!("Raygun: Send files to several chats: {}", e);
+                        results.failed.push((chat_id, e));
+                        continue;
+                    }
+                }
                 if attachments.is_empty() {
                     match messaging.send(chat_id, msg.clone()).await {
-                        Ok(id) => results.push((chat_id, (id, None))),
-                        Err(e) => log::error!("Raygun: Send files to several chats: {}", e),
+                        Ok(id) => results.sent.push((chat_id, (id, None))),
+                        Err(e) => {
+                            log::error!("Raygun: Send files to several chats: {}", e);
+                            results.failed.push((chat_id, e));
+                        }
                     }
                 } else {
                     //TODO: Pass stream off to attachment events
@@ -330,8 +600,13 @@ pub async fn handle_raygun_cmd(
                         .attach(chat_id, None, attachments.clone(), msg.clone())
                         .await
                     {
-                        Ok((id, stream)) => results.push((chat_id, (id, Some(stream)))),
-                        Err(e) => log::error!("Raygun: Send files to several chats: {}", e),
+                        Ok((id, stream)) => results
+                            .sent
+                            .push((chat_id, (id, Some(bandwidth::limit_attachments(stream))))),
+                        Err(e) => {
+                            log::error!("Raygun: Send files to several chats: {}", e);
+                            results.failed.push((chat_id, e));
+                        }
                     }
                 };
             }
@@ -344,7 +619,9 @@ pub async fn handle_raygun_cmd(
             msg,
             rsp,
         } => {
-            let r = messaging.edit(conv_id, msg_id, msg).await;
+            let r = messaging
+                .edit(conv_id, msg_id, incoming_webhooks::escape(msg))
+                .await;
             let _ = rsp.send(r);
         }
         RayGunCmd::DownloadAttachment {
@@ -356,7 +633,8 @@ pub async fn handle_raygun_cmd(
         } => {
             let r = messaging
                 .download(conv_id, msg_id, file_name, file_path_to_download)
-                .await;
+                .await
+                .map(bandwidth::limit_download);
             let _ = rsp.send(r);
         }
         RayGunCmd::DeleteMessage {
@@ -364,7 +642,13 @@ pub async fn handle_raygun_cmd(
             msg_id,
             rsp,
         } => {
-            let r = messaging.delete(conv_id, Some(msg_id)).await;
+            let r =
+                match raygun_check_delete_message(conv_id, msg_id, group_roles, account, messaging)
+                    .await
+                {
+                    Ok(_) => messaging.delete(conv_id, Some(msg_id)).await,
+                    Err(e) => Err(e),
+                };
             let _ = rsp.send(r);
         }
         RayGunCmd::Reply {
@@ -374,6 +658,7 @@ pub async fn handle_raygun_cmd(
             attachments,
             rsp,
         } => {
+            let msg = incoming_webhooks::escape(msg);
             let r = if attachments.is_empty() {
                 messaging
                     .reply(conv_id, reply_to, msg)
@@ -384,7 +669,9 @@ pub async fn handle_raygun_cmd(
                     .attach(conv_id, Some(reply_to), attachments, msg)
                     .await
                 {
-                    Ok((id, stream)) => Result::Ok((id, Some(stream))),
+                    Ok((id, stream)) => {
+                        Result::Ok((id, Some(bandwidth::limit_attachments(stream))))
+                    }
                     Err(e) => Err(e),
                 }
             };
@@ -395,6 +682,90 @@ pub async fn handle_raygun_cmd(
             let r = raygun_remove_direct_convs(recipient, messaging).await;
             let _ = rsp.send(r);
         }
+        RayGunCmd::MergeConversations {
+            conv_id,
+            duplicates,
+            rsp,
+        } => {
+            stream_manager.merge_conversations(conv_id, duplicates);
+            let _ = rsp.send(Ok(()));
+        }
+        RayGunCmd::SetGroupRole {
+            conv_id,
+            member,
+            role,
+            rsp,
+        } => {
+            let update = GroupUpdate::SetRole(member, role);
+            let r = match check_group_permission(
+                conv_id,
+                GroupAction::AssignRoles,
+                group_roles,
+                account,
+                messaging,
+            )
+            .await
+            {
+                Ok(_) => raygun_send_group_update(conv_id, update, group_roles, messaging).await,
+                Err(e) => Err(e),
+            };
+            let _ = rsp.send(r);
+        }
+        RayGunCmd::SetMemberMuted {
+            conv_id,
+            member,
+            muted,
+            rsp,
+        } => {
+            let update = GroupUpdate::SetMuted(member, muted);
+            let r = match check_group_permission(
+                conv_id,
+                GroupAction::MuteMembers,
+                group_roles,
+                account,
+                messaging,
+            )
+            .await
+            {
+                Ok(_) => raygun_send_group_update(conv_id, update, group_roles, messaging).await,
+                Err(e) => Err(e),
+            };
+            let _ = rsp.send(r);
+        }
+        RayGunCmd::CreateGroupInvite {
+            conv_id,
+            expires,
+            max_uses,
+            rsp,
+        } => {
+            let r = match check_group_permission(
+                conv_id,
+                GroupAction::AddMembers,
+                group_roles,
+                account,
+                messaging,
+            )
+            .await
+            {
+                Ok(_) => match account.get_own_identity().await {
+                    Ok(own) => Ok(group_invites.create(conv_id, own.did_key(), expires, max_uses)),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            let _ = rsp.send(r);
+        }
+        RayGunCmd::ListGroupInvites { conv_id, rsp } => {
+            let _ = rsp.send(group_invites.list(conv_id));
+        }
+        RayGunCmd::RevokeGroupInvite { code, rsp } => {
+            group_invites.revoke(&code);
+            let _ = rsp.send(());
+        }
+        RayGunCmd::JoinGroupViaInvite { link, rsp } => {
+            let r = raygun_join_group_via_invite(&link, account, messaging).await;
+            let _ = rsp.send(r);
+        }
         RayGunCmd::React {
             conversation_id,
             message_id,
@@ -416,6 +787,20 @@ pub async fn handle_raygun_cmd(
             let r = messaging.pin(conversation_id, message_id, pinstate).await;
             let _ = rsp.send(r);
         }
+        RayGunCmd::SendReadReceipt {
+            conv_id,
+            receipt,
+            rsp,
+        } => {
+            let r = match receipt.message() {
+                Ok(msg) => messaging.send(conv_id, msg).await.map(|_| ()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &r {
+                log::warn!("failed to send read receipt to {conv_id}: {e}");
+            }
+            let _ = rsp.send(r);
+        }
         RayGunCmd::SendEvent {
             conv_id,
             event,
@@ -427,18 +812,87 @@ pub async fn handle_raygun_cmd(
     }
 }
 
+async fn check_group_permission(
+    conv_id: Uuid,
+    action: GroupAction,
+    group_roles: &group_roles::Manager,
+    account: &Account,
+    messaging: &mut Messaging,
+) -> Result<(), Error> {
+    let own_did = account.get_own_identity().await?.did_key();
+    group_roles
+        .check_permission(conv_id, &own_did, action, messaging)
+        .await
+}
+
+// @everyone needs moderator rights and is rate limited. returns true if the message mentions @everyone
+async fn check_group_mention(
+    conv_id: Uuid,
+    msg: &[String],
+    group_roles: &group_roles::Manager,
+    account: &Account,
+    messaging: &mut Messaging,
+) -> Result<bool, Error> {
+    if find_group_mention(&msg.join("\n")) != Some(GroupMention::Everyone) {
+        return Ok(false);
+    }
+    check_group_permission(
+        conv_id,
+        GroupAction::MentionEveryone,
+        group_roles,
+        account,
+        messaging,
+    )
+    .await?;
+    group_roles.check_everyone_cooldown(&conv_id)?;
+    Ok(true)
+}
+
+// the other members apply the update once they receive it
+async fn raygun_send_group_update(
+    conv_id: Uuid,
+    update: GroupUpdate,
+    group_roles: &mut group_roles::Manager,
+    messaging: &mut Messaging,
+) -> Result<(), Error> {
+    messaging.send(conv_id, update.message()?).await?;
+    group_roles.apply(conv_id, update);
+    Ok(())
+}
+
+// anyone can delete their own messages. deleting someone else's message requires moderator rights.
+async fn raygun_check_delete_message(
+    conv_id: Uuid,
+    msg_id: Uuid,
+    group_roles: &group_roles::Manager,
+    account: &Account,
+    messaging: &mut Messaging,
+) -> Result<(), Error> {
+    let own_did = account.get_own_identity().await?.did_key();
+    let message = messaging.get_message(conv_id, msg_id).await?;
+    if message.sender() == own_did {
+        return Ok(());
+    }
+    group_roles
+        .check_permission(
+            conv_id,
+            &own_did,
+            GroupAction::DeleteOthersMessages,
+            messaging,
+        )
+        .await
+}
+
 pub struct WarpInit {
     pub friends: Friends,
     // at some point we may want to initialize identities on demand, such as only initialize the ones needed for the chats sidebar
     //all_identities: HashSet<DID>,
     pub converted_identities: HashMap<DID, identity::Identity>,
-    // todo: don't init all conversations at once. instead, store list of all conv ids
-    // and initialized conversations separately
-    //all_conv_ids: HashSet<Uuid>,
-    pub chats: HashMap<Uuid, chats::Chat>,
+    // the chats are loaded afterwards with RayGunCmd::LoadConversations
+    pub conversations: Vec<Uuid>,
 }
 
-// init friends, chats, and identities all at once
+// init friends and identities, and list the conversations
 async fn init_warp(
     stream_manager: &mut conv_stream::Manager,
     account: &mut Account,
@@ -459,10 +913,10 @@ async fn init_warp(
     all_identities.extend(friends.incoming_requests.iter().cloned());
     all_identities.extend(friends.outgoing_requests.iter().cloned());
 
-    let mut chats = HashMap::new();
+    let mut conv_ids = Vec::new();
     for conv in conversations {
         all_identities.extend(conv.recipients());
-        //all_conv_ids.insert(conv.id());
+        conv_ids.push(conv.id());
 
         if let Err(e) = stream_manager.add_stream(conv.id(), messaging).await {
             log::error!(
@@ -471,20 +925,14 @@ async fn init_warp(
                 e
             );
         }
-        match conversation_to_chat(&conv, messaging).await {
-            Ok(chat) => {
-                chats.insert(conv.id(), chat);
-            }
-            Err(e) => {
-                log::error!("failed to convert conversation to chat: {e}");
-            }
-        };
     }
 
     // ensure that own identity gets fetched
     let own_id = account.get_own_identity().await?;
     all_identities.insert(own_id.did_key());
 
+    tokio::spawn(message_store::backfill(messaging.clone(), conv_ids.clone()));
+
     let identifier_vec = Vec::from_iter(all_identities.iter().cloned());
     let mut converted_identities = HashMap::new();
     for identity in dids_to_identity(identifier_vec.into(), account)
@@ -508,15 +956,91 @@ async fn init_warp(
     log::trace!(
         "init warp with {} friends and {} conversations",
         friends.all.len(),
-        chats.len()
+        conv_ids.len()
     );
     Ok(WarpInit {
         friends,
         converted_identities,
-        chats,
+        conversations: conv_ids,
     })
 }
 
+// conversations which fail to load are left out
+async fn load_conversations(
+    conv_ids: Vec<Uuid>,
+    account: &mut Account,
+    messaging: &mut Messaging,
+) -> HashMap<Uuid, chats::Chat> {
+    // messages which arrived while Uplink was closed
+    let unread = match account.get_own_identity().await {
+        Ok(own) => message_store::unread(&own.did_key()).await,
+        Err(e) => {
+            log::error!("failed to get own identity: {e}");
+            HashMap::new()
+        }
+    };
+
+    let mut chats = HashMap::new();
+    for conv_id in conv_ids {
+        let conv = match messaging.get_conversation(conv_id).await {
+            Ok(conv) => conv,
+            Err(e) => {
+                log::error!("failed to get conversation {conv_id}: {e}");
+                continue;
+            }
+        };
+        match conversation_to_chat(&conv, messaging).await {
+            Ok(mut chat) => {
+                for id in unread.get(&conv_id).into_iter().flatten() {
+                    chat.add_unread(*id);
+                }
+                chats.insert(conv_id, chat);
+            }
+            Err(e) => {
+                log::error!("failed to convert conversation to chat: {e}");
+            }
+        };
+    }
+    chats
+}
+
+// whether someone who isn't a friend can message the recipient is up to the privacy settings of the
+// recipient, which warp checks when the conversation is created
+async fn start_conversation(
+    recipient: DID,
+    account: &Account,
+    messaging: &mut Messaging,
+) -> Result<Uuid, Error> {
+    if account.is_blocked(&recipient).await? {
+        return Err(Error::PublicKeyIsBlocked);
+    }
+    // a mistyped DID fails here, instead of leaving a conversation nobody answers
+    if dids_to_identity(recipient.clone().into(), account)
+        .await?
+        .is_empty()
+    {
+        return Err(Error::IdentityDoesntExist);
+    }
+    match messaging.create_conversation(&recipient).await {
+        Ok(conv) | Err(Error::ConversationExist { conversation: conv }) => Ok(conv.id()),
+        Err(e) => Err(e),
+    }
+}
+
+// fails like start_conversation if the inviter is blocked or can't be found
+async fn raygun_join_group_via_invite(
+    link: &str,
+    account: &Account,
+    messaging: &mut Messaging,
+) -> Result<Uuid, Error> {
+    let link = InviteLink::from_str(link)?;
+    let conv_id = start_conversation(link.inviter, account, messaging).await?;
+    messaging
+        .send(conv_id, group_invites::join_request(&link.code))
+        .await?;
+    Ok(conv_id)
+}
+
 async fn raygun_add_recipients_to_a_group(
     conv_id: Uuid,
     recipients: Vec<DID>,
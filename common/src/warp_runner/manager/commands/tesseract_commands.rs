@@ -1,6 +1,36 @@
 use derive_more::Display;
 use futures::channel::oneshot;
-use warp::tesseract::Tesseract;
+use once_cell::sync::Lazy;
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tracing::log;
+
+use uuid::Uuid;
+use warp::{multipass::identity::Identity, tesseract::Tesseract};
+
+use crate::{
+    utils::{
+        duress::{self, DuressAction},
+        keychain,
+    },
+    STATIC_ARGS,
+};
+
+// wrong PINs after which the lock screen has to wait before the next attempt
+const FREE_ATTEMPTS: u32 = 3;
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// the wrong PINs entered at the lock screen since the last right one. checks are done one at a
+// time, so several at once can't get around the backoff
+static ATTEMPTS: Lazy<tokio::sync::Mutex<Attempts>> = Lazy::new(Default::default);
+
+#[derive(Default)]
+struct Attempts {
+    failed: u32,
+    retry_at: Option<Instant>,
+}
 
 #[derive(Display)]
 pub enum TesseractCmd {
@@ -18,6 +48,50 @@ pub enum TesseractCmd {
     CheckMnemonicExist {
         rsp: oneshot::Sender<Result<bool, warp::error::Error>>,
     },
+    /// restarts warp with the data of another profile. returns whether the profile has an account
+    #[display(fmt = "SwitchProfile")]
+    SwitchProfile {
+        profile: Uuid,
+        rsp: oneshot::Sender<Result<bool, warp::error::Error>>,
+    },
+    /// restores a backup into a new profile and switches to it
+    #[display(fmt = "RestoreBackup {{ path: {path:?} }}")]
+    RestoreBackup {
+        path: PathBuf,
+        passphrase: String,
+        rsp: oneshot::Sender<Result<(), warp::error::Error>>,
+    },
+    /// sent instead of TryLogIn when the duress PIN of the profile was entered
+    #[display(fmt = "Duress")]
+    Duress {
+        passphrase: String,
+        rsp: oneshot::Sender<Result<DuressOutcome, warp::error::Error>>,
+    },
+    /// checks the PIN of the logged in account, to unlock the lock screen
+    #[display(fmt = "VerifyPassphrase")]
+    VerifyPassphrase {
+        passphrase: String,
+        rsp: oneshot::Sender<PassphraseCheck>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassphraseCheck {
+    Valid,
+    Invalid,
+    /// the duress PIN of the profile was entered
+    Duress(DuressAction),
+    /// too many wrong PINs, nothing is checked until then
+    RetryIn(Duration),
+}
+
+pub enum DuressOutcome {
+    /// the profile was deleted and warp restarted without an account
+    Wiped,
+    /// like Wiped, but some of the files of the profile couldn't be deleted
+    PartiallyWiped,
+    /// logged into the decoy profile, or into the real account if the passphrase was its PIN after all
+    LoggedIn(Identity),
 }
 
 impl std::fmt::Debug for TesseractCmd {
@@ -39,5 +113,95 @@ pub fn handle_tesseract_cmd(cmd: TesseractCmd, tesseract: &Tesseract) {
             let exists = tesseract.exist("mnemonic");
             let _ = rsp.send(Ok(exists));
         }
+        // profiles can only be switched or restored before logging in
+        TesseractCmd::SwitchProfile { rsp, .. } => {
+            let _ = rsp.send(Err(warp::error::Error::Other));
+        }
+        TesseractCmd::RestoreBackup { rsp, .. } => {
+            let _ = rsp.send(Err(warp::error::Error::Other));
+        }
+        TesseractCmd::Duress { rsp, .. } => {
+            let _ = rsp.send(Err(warp::error::Error::Other));
+        }
+        TesseractCmd::VerifyPassphrase { passphrase, rsp } => {
+            tokio::spawn(async move {
+                let _ = rsp.send(verify_passphrase(passphrase).await);
+            });
+        }
+    }
+}
+
+async fn verify_passphrase(passphrase: String) -> PassphraseCheck {
+    let mut attempts = ATTEMPTS.lock().await;
+    if let Some(wait) = attempts
+        .retry_at
+        .and_then(|at| at.checked_duration_since(Instant::now()))
+    {
+        return PassphraseCheck::RetryIn(wait);
+    }
+    // deriving the keys is slow on purpose
+    let check = tokio::task::spawn_blocking(move || check_passphrase(&passphrase))
+        .await
+        .unwrap_or(PassphraseCheck::Invalid);
+    if check == PassphraseCheck::Duress(DuressAction::Wipe) {
+        let _ = keychain::forget_pin().await;
+        if let Err(e) = duress::schedule_wipe() {
+            log::error!("failed to schedule the wipe: {e}");
+        }
+    }
+    if check == PassphraseCheck::Invalid {
+        attempts.failed += 1;
+        attempts.retry_at = Some(Instant::now() + backoff(attempts.failed));
+    } else {
+        *attempts = Attempts::default();
+    }
+    check
+}
+
+// a second tesseract is read from the file, so the one in use stays unlocked. without the file
+// there is nothing to check against and no PIN is accepted
+fn check_passphrase(passphrase: &str) -> PassphraseCheck {
+    let path = STATIC_ARGS.warp_path().join(&STATIC_ARGS.tesseract_file);
+    if !path.is_file() {
+        log::error!("the tesseract file of the profile is missing");
+        return PassphraseCheck::Invalid;
+    }
+    let valid = match Tesseract::from_file(&path) {
+        Ok(copy) => {
+            let valid = copy.unlock(passphrase.as_bytes()).is_ok();
+            copy.lock();
+            valid
+        }
+        Err(e) => {
+            log::error!("failed to read the tesseract file: {e}");
+            false
+        }
+    };
+    // the real PIN wins, in case the user chose the same one twice
+    match (valid, duress::check(passphrase)) {
+        (true, _) => PassphraseCheck::Valid,
+        (false, Some(action)) => PassphraseCheck::Duress(action),
+        (false, None) => PassphraseCheck::Invalid,
+    }
+}
+
+fn backoff(failed: u32) -> Duration {
+    match failed.checked_sub(FREE_ATTEMPTS) {
+        None => Duration::ZERO,
+        Some(extra) => Duration::from_secs(1 << extra.min(16)).min(MAX_BACKOFF),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_after_the_free_attempts() {
+        assert_eq!(backoff(1), Duration::ZERO);
+        assert_eq!(backoff(FREE_ATTEMPTS - 1), Duration::ZERO);
+        assert_eq!(backoff(FREE_ATTEMPTS), Duration::from_secs(1));
+        assert_eq!(backoff(FREE_ATTEMPTS + 3), Duration::from_secs(8));
+        assert_eq!(backoff(100), MAX_BACKOFF);
     }
 }
@@ -4,22 +4,27 @@ use warp::{
     raygun::{MessageEventKind, RayGunEventKind},
 };
 
+use std::time::Instant;
 use tracing::log;
 
 use crate::{
+    utils::webhooks::{self, WebhookEvent},
     warp_runner::{
-        conv_stream,
+        conv_stream, group_invites, group_roles,
         manager::commands::handle_blink_cmd,
-        ui_adapter::{self, did_to_identity, MultiPassEvent},
-        RayGunCmd, WarpCmd, WarpEvent,
+        message_store, metrics,
+        read_receipts::ReadReceipt,
+        ui_adapter::{self, did_to_identity, MessageEvent, MultiPassEvent},
+        CancelToken, GroupUpdate, RayGunCmd, WarpCmd, WarpEvent,
     },
     WARP_EVENT_CH,
 };
 
 use super::{
+    cache,
     commands::{
         handle_constellation_cmd, handle_multipass_cmd, handle_other_cmd, handle_raygun_cmd,
-        handle_tesseract_cmd,
+        handle_tesseract_cmd, BatchCmd, BatchContext, BatchError, BatchProgress,
     },
     MultiPassCmd,
 };
@@ -33,6 +38,7 @@ pub async fn handle_multipass_event(
         None => return Ok(()),
     };
     log::debug!("received multipass event: {:?}", &evt);
+    cache::on_multipass_event(&evt);
     let warp_event_tx = WARP_EVENT_CH.tx.clone();
     match ui_adapter::convert_multipass_event(evt, &mut warp.multipass, &mut warp.raygun).await {
         Ok(evt) => {
@@ -59,6 +65,7 @@ pub async fn handle_raygun_event(
         None => return Ok(()),
     };
     log::debug!("received raygun event: {:?}", &evt);
+    message_store::on_raygun_event(&evt);
     let warp_event_tx = WARP_EVENT_CH.tx.clone();
     match ui_adapter::convert_raygun_event(
         evt,
@@ -85,6 +92,8 @@ pub async fn handle_raygun_event(
 pub async fn handle_message_event(
     evt: Option<MessageEventKind>,
     warp: &mut super::Warp,
+    group_roles: &mut group_roles::Manager,
+    group_invites: &mut group_invites::Manager,
 ) -> Result<(), ()> {
     let msg = match evt {
         Some(e) => e,
@@ -93,6 +102,138 @@ pub async fn handle_message_event(
     let warp_event_tx = WARP_EVENT_CH.tx.clone();
     match ui_adapter::convert_message_event(msg, &mut warp.multipass, &mut warp.raygun).await {
         Ok(evt) => {
+            // changes of the roles are applied here and never shown
+            let evt = match evt {
+                MessageEvent::Received {
+                    conversation_id,
+                    message,
+                } => match GroupUpdate::from_message(&message.inner) {
+                    Some(update) => {
+                        let sender = message.inner.sender();
+                        match group_roles
+                            .handle_update(
+                                conversation_id,
+                                &sender,
+                                update.clone(),
+                                &mut warp.raygun,
+                            )
+                            .await
+                        {
+                            Ok(_) => MessageEvent::GroupUpdated {
+                                conversation_id,
+                                update,
+                            },
+                            Err(e) => {
+                                log::warn!("rejected group update from {sender}: {e}");
+                                return Ok(());
+                            }
+                        }
+                    }
+                    None => MessageEvent::Received {
+                        conversation_id,
+                        message,
+                    },
+                },
+                MessageEvent::Sent { message, .. }
+                    if GroupUpdate::from_message(&message.inner).is_some() =>
+                {
+                    return Ok(());
+                }
+                evt => evt,
+            };
+            // read receipts are saved like any other message, so the store has as many as RayGun, but never shown
+            let evt = match evt {
+                MessageEvent::Received {
+                    conversation_id,
+                    message,
+                } => match ReadReceipt::from_message(&message.inner) {
+                    Some(receipt) => {
+                        let reader = message.inner.sender();
+                        message_store::on_message_event(&MessageEvent::Received {
+                            conversation_id,
+                            message,
+                        });
+                        MessageEvent::ReadReceipt {
+                            conversation_id,
+                            reader,
+                            up_to: receipt.up_to,
+                        }
+                    }
+                    None => MessageEvent::Received {
+                        conversation_id,
+                        message,
+                    },
+                },
+                MessageEvent::Sent {
+                    conversation_id,
+                    message,
+                } if ReadReceipt::from_message(&message.inner).is_some() => {
+                    message_store::on_message_event(&MessageEvent::Sent {
+                        conversation_id,
+                        message,
+                    });
+                    return Ok(());
+                }
+                evt => evt,
+            };
+            message_store::on_message_event(&evt);
+            // drop messages and typing indicators from members who were muted by a group moderator
+            let muted = match &evt {
+                MessageEvent::Received {
+                    conversation_id,
+                    message,
+                } => group_roles.is_muted(conversation_id, &message.inner.sender()),
+                MessageEvent::TypingIndicator {
+                    conversation_id,
+                    participant,
+                } => group_roles.is_muted(conversation_id, participant),
+                _ => false,
+            };
+            if muted {
+                return Ok(());
+            }
+
+            // join requests are handled here and never shown
+            match &evt {
+                MessageEvent::Received { message, .. } => {
+                    if let Some(code) = group_invites::join_request_code(&message.inner) {
+                        handle_join_request(
+                            &code,
+                            &message.inner,
+                            warp,
+                            group_roles,
+                            group_invites,
+                        )
+                        .await;
+                        return Ok(());
+                    }
+                }
+                MessageEvent::Sent { message, .. } => {
+                    if group_invites::join_request_code(&message.inner).is_some() {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+
+            // forwarded here rather than by the UI so it works headless too
+            if let MessageEvent::Received {
+                conversation_id,
+                message,
+            } = &evt
+            {
+                if let Some((conversation_id, outgoing)) = webhooks::outgoing(conversation_id) {
+                    let sender_name = did_to_identity(&message.inner.sender(), &warp.multipass)
+                        .await
+                        .map(|id| id.username())
+                        .unwrap_or_default();
+                    webhooks::forward(
+                        &outgoing,
+                        WebhookEvent::new_message(conversation_id, &message.inner, sender_name),
+                    );
+                }
+            }
+
             if let Err(e) = warp_event_tx.send(WarpEvent::Message(evt)) {
                 log::error!("failed to send warp_event: {e}");
                 return Err(());
@@ -106,6 +247,29 @@ pub async fn handle_message_event(
     Ok(())
 }
 
+async fn handle_join_request(
+    code: &str,
+    message: &warp::raygun::Message,
+    warp: &mut super::Warp,
+    group_roles: &group_roles::Manager,
+    group_invites: &mut group_invites::Manager,
+) {
+    let own_did = match warp.multipass.get_own_identity().await {
+        Ok(ident) => ident.did_key(),
+        Err(e) => {
+            log::error!("failed to get own identity: {e}");
+            return;
+        }
+    };
+    match group_invites
+        .handle_join_request(code, message, &own_did, group_roles, &mut warp.raygun)
+        .await
+    {
+        Ok(conv_id) => log::debug!("added participant to {conv_id} via invite"),
+        Err(e) => log::warn!("rejected join request: {e}"),
+    }
+}
+
 // currently there's no need for warp runner to respond to blink events. all the other handle_x_event functions send forward the event over the WARP_EVENT_CH
 // this function does the same.
 pub async fn handle_blink_event(
@@ -121,11 +285,53 @@ pub async fn handle_warp_command(
     evt: Option<WarpCmd>,
     warp: &mut super::Warp,
     stream_manager: &mut conv_stream::Manager,
+    group_roles: &mut group_roles::Manager,
+    group_invites: &mut group_invites::Manager,
 ) -> Result<(), ()> {
     let cmd = match evt {
         Some(e) => e,
         None => return Ok(()),
     };
+    let (cmd, token) = match cmd {
+        WarpCmd::Cancellable(cmd, token) => (*cmd, token),
+        cmd => (cmd, CancelToken::default()),
+    };
+    if token.is_cancelled() {
+        log::debug!("skipping cancelled WARP CMD: {}", &cmd);
+        return Ok(());
+    }
+    let name = cmd.name();
+    let started = Instant::now();
+    let res = match cmd {
+        // once started, a batch runs to the end so a failure can still be rolled back
+        WarpCmd::Batch(batch) => {
+            run_batch(batch, warp, stream_manager, group_roles, group_invites).await
+        }
+        cmd => token
+            .run_until_cancelled(execute_warp_command(
+                cmd,
+                warp,
+                stream_manager,
+                group_roles,
+                group_invites,
+            ))
+            .await
+            .unwrap_or_else(|| {
+                log::debug!("WARP CMD was cancelled");
+                Ok(())
+            }),
+    };
+    metrics::record(&name, started.elapsed());
+    res
+}
+
+async fn execute_warp_command(
+    cmd: WarpCmd,
+    warp: &mut super::Warp,
+    stream_manager: &mut conv_stream::Manager,
+    group_roles: &mut group_roles::Manager,
+    group_invites: &mut group_invites::Manager,
+) -> Result<(), ()> {
     if !matches!(cmd, WarpCmd::RayGun(RayGunCmd::SendEvent { .. })) {
         log::debug!("WARP CMD: {}", &cmd);
     } else {
@@ -137,7 +343,7 @@ pub async fn handle_warp_command(
     match cmd {
         WarpCmd::Other(cmd) => {
             // this one could be parallelized
-            handle_other_cmd(cmd).await;
+            handle_other_cmd(cmd, None).await;
         }
         WarpCmd::Tesseract(cmd) => {
             handle_tesseract_cmd(cmd, &warp.tesseract);
@@ -169,11 +375,105 @@ pub async fn handle_warp_command(
         }
 
         WarpCmd::RayGun(cmd) => {
-            handle_raygun_cmd(cmd, stream_manager, &mut warp.multipass, &mut warp.raygun).await
+            handle_raygun_cmd(
+                cmd,
+                stream_manager,
+                group_roles,
+                group_invites,
+                &mut warp.multipass,
+                &mut warp.raygun,
+            )
+            .await
         }
 
         WarpCmd::Constellation(cmd) => handle_constellation_cmd(cmd, &mut warp.constellation).await,
         WarpCmd::Blink(cmd) => handle_blink_cmd(cmd, &mut warp.blink).await,
+        WarpCmd::Batch(batch) => {
+            let _ = batch.rsp.send(Err(BatchError {
+                step: 0,
+                label: batch.label,
+                error: warp::error::Error::OtherWithContext("batches can't be nested".into()),
+                rolled_back: 0,
+            }));
+        }
+        WarpCmd::Cancellable(cmd, _) => {
+            // dropping the command drops its response channel, which fails the batch step
+            log::error!("cancellable commands can't be part of a batch: {cmd}");
+        }
+    }
+    Ok(())
+}
+
+// executes the steps in order. the runner doesn't handle anything else until the batch is done
+async fn run_batch(
+    batch: BatchCmd,
+    warp: &mut super::Warp,
+    stream_manager: &mut conv_stream::Manager,
+    group_roles: &mut group_roles::Manager,
+    group_invites: &mut group_invites::Manager,
+) -> Result<(), ()> {
+    log::debug!("WARP CMD: {}", &batch);
+    let BatchCmd {
+        label: batch_label,
+        steps,
+        progress,
+        rsp,
+    } = batch;
+
+    let total = steps.len();
+    let mut ctx = BatchContext::default();
+    let mut rollbacks = Vec::with_capacity(total);
+    let mut failure = None;
+    for (idx, step) in steps.into_iter().enumerate() {
+        let (label, build, rollback) = step.into_parts();
+        if let Some(progress) = &progress {
+            let _ = progress.send(BatchProgress {
+                step: idx,
+                total,
+                label: label.clone(),
+            });
+        }
+        let result = match build(&ctx) {
+            Ok((cmd, output)) => {
+                execute_warp_command(cmd, warp, stream_manager, group_roles, group_invites).await?;
+                output.await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(output) => {
+                ctx.push(output);
+                rollbacks.push(rollback);
+            }
+            Err(error) => {
+                failure = Some((idx, label, error));
+                break;
+            }
+        }
+    }
+    // lets the receiver know that no more progress updates will come
+    drop(progress);
+
+    let (step, label, error) = match failure {
+        Some(failure) => failure,
+        None => {
+            let _ = rsp.send(Ok(ctx));
+            return Ok(());
+        }
+    };
+    log::warn!("batch {batch_label} failed at step {step} ({label}): {error}");
+    let mut rolled_back = 0;
+    for rollback in rollbacks.into_iter().rev().flatten() {
+        if let Some(cmd) = rollback(&ctx) {
+            execute_warp_command(cmd, warp, stream_manager, group_roles, group_invites).await?;
+            rolled_back += 1;
+        }
     }
+    let _ = rsp.send(Err(BatchError {
+        step,
+        label,
+        error,
+        rolled_back,
+    }));
     Ok(())
 }
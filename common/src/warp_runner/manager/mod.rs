@@ -1,10 +1,11 @@
 //! this is the main warp_runner task. It initializes Warp and sits between Warp and Uplink, allowing communication via channels.
 
+pub mod cache;
 pub mod commands;
 mod events;
-use futures::StreamExt;
-use std::sync::Arc;
-use tokio::sync::Notify;
+use futures::{channel::oneshot, Future, StreamExt};
+use std::{sync::Arc, time::Instant};
+use tokio::sync::{mpsc::UnboundedSender, Notify};
 
 use warp::{
     blink::BlinkEventStream, multipass::MultiPassEventStream, raygun::RayGunEventStream,
@@ -13,10 +14,16 @@ use warp::{
 
 use tracing::log;
 
-use super::{conv_stream, Account, Calling, Messaging, Storage};
+use super::{
+    conv_stream, group_invites, group_roles, message_store, metrics, storage_backend, Account,
+    Calling, CancelToken, Lane, Messaging, Storage, WarpCmd,
+};
 use crate::WARP_CMD_CH;
 
-pub use commands::{BlinkCmd, ConstellationCmd, MultiPassCmd, OtherCmd, RayGunCmd, TesseractCmd};
+pub use commands::{
+    BatchCmd, BatchContext, BatchError, BatchProgress, BatchStep, BlinkCmd, ConstellationCmd,
+    DuressOutcome, MultiPassCmd, OtherCmd, PassphraseCheck, RayGunCmd, TesseractCmd,
+};
 
 /// Contains the structs needed for run() to handle various events
 pub struct Warp {
@@ -27,7 +34,30 @@ pub struct Warp {
     pub blink: Calling,
 }
 
+/// Lets a command in the bulk lane pause the main loop, so that no other command or event changes the
+/// profile while it runs
+#[derive(Clone)]
+pub struct MainLoopPause(UnboundedSender<(oneshot::Sender<()>, oneshot::Receiver<()>)>);
+
+impl MainLoopPause {
+    /// runs `f` once the main loop is paused. the bulk lane itself handles one command at a time.
+    pub async fn run<T>(&self, f: impl Future<Output = T>) -> T {
+        let (paused_tx, paused_rx) = oneshot::channel();
+        let (resume_tx, resume_rx) = oneshot::channel();
+        if self.0.send((paused_tx, resume_rx)).is_ok() {
+            let _ = paused_rx.await;
+        }
+        let r = f.await;
+        let _ = resume_tx.send(());
+        r
+    }
+}
+
 pub async fn run(mut warp: Warp, notify: Arc<Notify>) {
+    // results from a previous login could belong to another profile
+    cache::clear();
+    message_store::open(&warp.tesseract);
+
     // receive command from Uplink
     let warp_cmd_rx = WARP_CMD_CH.rx.clone();
 
@@ -37,12 +67,24 @@ pub async fn run(mut warp: Warp, notify: Arc<Notify>) {
     // gather incoming messages from all conversations and read them from conversation_msg_rx
     let (conversation_msg_tx, mut conversation_msg_rx) = tokio::sync::mpsc::unbounded_channel();
     let mut conversation_manager = conv_stream::Manager::new(conversation_msg_tx.clone());
+    let mut group_roles = group_roles::Manager::default();
+    let mut group_invites = group_invites::Manager::load();
 
     // receive events from RayGun and MultiPass
     let mut raygun_stream = get_raygun_stream(&mut warp.raygun).await;
     let mut multipass_stream = get_multipass_stream(&mut warp.multipass).await;
     let mut blink_stream = get_blink_stream(&mut warp.blink).await;
 
+    // long running commands are handled by their own task, in the order they were sent
+    let (bulk_tx, bulk_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (pause_tx, mut pause_rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(run_bulk_lane(
+        warp.constellation.clone(),
+        MainLoopPause(pause_tx),
+        bulk_rx,
+    ));
+    tokio::spawn(storage_backend::pin_existing(warp.constellation.clone()));
+
     log::debug!("warp_runner::manager::run");
     loop {
         tokio::select! {
@@ -65,23 +107,72 @@ pub async fn run(mut warp: Warp, notify: Arc<Notify>) {
                 }
             },
             opt = conversation_msg_rx.recv() => {
-                if events::handle_message_event(opt, &mut warp).await.is_err() {
+                if events::handle_message_event(opt, &mut warp, &mut group_roles, &mut group_invites).await.is_err() {
                     break;
                 }
             }
             opt = warp_cmd_rx.recv() => {
-                if events::handle_warp_command(opt, &mut warp, &mut conversation_manager).await.is_err() {
+                let opt = match opt {
+                    Some(cmd) if cmd.lane() == Lane::Bulk => {
+                        if bulk_tx.send(cmd).is_err() {
+                            log::error!("the bulk lane stopped");
+                            break;
+                        }
+                        continue;
+                    }
+                    opt => opt,
+                };
+                if events::handle_warp_command(opt, &mut warp, &mut conversation_manager, &mut group_roles, &mut group_invites).await.is_err() {
                     break;
                 }
             },
+            Some((paused, resume)) = pause_rx.recv() => {
+                let _ = paused.send(());
+                let _ = resume.await;
+            }
             // the WarpRunner has been dropped. stop the task
             _ = notify.notified() => break,
         }
     }
 
+    message_store::close();
     log::debug!("terminating warp_runner thread");
 }
 
+// stops once the sender is dropped, when run() returns
+async fn run_bulk_lane(
+    mut storage: Storage,
+    pause: MainLoopPause,
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<WarpCmd>,
+) {
+    while let Some(cmd) = rx.recv().await {
+        let (cmd, token) = match cmd {
+            WarpCmd::Cancellable(cmd, token) => (*cmd, token),
+            cmd => (cmd, CancelToken::default()),
+        };
+        log::debug!("WARP CMD (bulk): {}", &cmd);
+        let name = cmd.name();
+        let started = Instant::now();
+        let storage = &mut storage;
+        let pause = &pause;
+        let handled = token
+            .run_until_cancelled(async move {
+                match cmd {
+                    WarpCmd::Constellation(cmd) => {
+                        commands::handle_constellation_cmd(cmd, storage).await
+                    }
+                    WarpCmd::Other(cmd) => commands::handle_other_cmd(cmd, Some(pause)).await,
+                    cmd => log::error!("not a bulk command: {cmd}"),
+                }
+            })
+            .await;
+        metrics::record(&name, started.elapsed());
+        if handled.is_none() {
+            log::debug!("bulk WARP CMD was cancelled");
+        }
+    }
+}
+
 async fn get_raygun_stream(rg: &mut Messaging) -> RayGunEventStream {
     loop {
         match rg.raygun_subscribe().await {
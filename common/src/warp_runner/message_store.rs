@@ -0,0 +1,772 @@
+//! A local copy of the messages of every conversation, in an SQLite database encrypted with a key
+//! kept in tesseract. Conversations which were copied completely are read from it instead of
+//! RayGun: the sidebar, paging through a conversation, searching and counting unread messages
+//! don't need to load the conversations into memory, which makes starting Uplink much faster for
+//! people with a long history.
+//!
+//! The copy is kept up to date by the message events. Conversations which weren't copied yet, the
+//! first time Uplink starts with the store, are copied in the background by `backfill`. A copied
+//! conversation with another number of messages than RayGun, because events were missed, is copied
+//! again.
+//!
+//! The queries run on a thread of their own, so the database is never read or written on the
+//! threads of the async runtime.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use futures::channel::oneshot;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rand::RngCore;
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
+use tracing::log;
+use uuid::Uuid;
+use warp::{
+    crypto::DID,
+    raygun::{self, RayGunEventKind},
+    tesseract::Tesseract,
+};
+
+use super::{
+    ui_adapter::{self, Message, MessageEvent},
+    FetchMessagesConfig, FetchMessagesResponse, Messaging,
+};
+use crate::{state::MAX_PINNED_MESSAGES, STATIC_ARGS};
+
+const KEY_NAME: &str = "message_store_key";
+const SCHEMA_VERSION: i64 = 1;
+// messages copied from RayGun at once by `backfill`
+const BACKFILL_PAGE: usize = 200;
+// the sidebar shows the last message, and needs the one before it when it's deleted
+const PREVIEW_MESSAGES: usize = 2;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS messages (
+    id TEXT PRIMARY KEY,
+    conversation_id TEXT NOT NULL,
+    date INTEGER NOT NULL,
+    sender TEXT NOT NULL,
+    pinned INTEGER NOT NULL,
+    body TEXT NOT NULL,
+    -- the ui_adapter::Message, as json
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS messages_by_date ON messages (conversation_id, date);
+CREATE INDEX IF NOT EXISTS messages_pinned ON messages (conversation_id, date) WHERE pinned = 1;
+
+CREATE VIRTUAL TABLE IF NOT EXISTS messages_text USING fts5 (
+    body, content = 'messages', content_rowid = 'rowid'
+);
+CREATE TRIGGER IF NOT EXISTS messages_inserted AFTER INSERT ON messages BEGIN
+    INSERT INTO messages_text (rowid, body) VALUES (new.rowid, new.body);
+END;
+CREATE TRIGGER IF NOT EXISTS messages_deleted AFTER DELETE ON messages BEGIN
+    INSERT INTO messages_text (messages_text, rowid, body) VALUES ('delete', old.rowid, old.body);
+END;
+CREATE TRIGGER IF NOT EXISTS messages_updated AFTER UPDATE ON messages BEGIN
+    INSERT INTO messages_text (messages_text, rowid, body) VALUES ('delete', old.rowid, old.body);
+    INSERT INTO messages_text (rowid, body) VALUES (new.rowid, new.body);
+END;
+
+CREATE TABLE IF NOT EXISTS conversations (
+    id TEXT PRIMARY KEY,
+    -- the whole history was copied from RayGun
+    complete INTEGER NOT NULL DEFAULT 0,
+    -- the messages after this date are unread
+    last_read INTEGER NOT NULL DEFAULT 0
+);
+";
+
+// runs on the store of the account which is logged in, `None` while there is none
+type Job = Box<dyn FnOnce(&mut Option<MessageStore>) + Send>;
+
+// the jobs run in the order they were sent, so a query sees what was saved before it
+static WORKER: Lazy<Mutex<mpsc::Sender<Job>>> = Lazy::new(|| {
+    let (tx, rx) = mpsc::channel::<Job>();
+    std::thread::spawn(move || {
+        let mut store = None;
+        while let Ok(job) = rx.recv() {
+            job(&mut store);
+        }
+    });
+    Mutex::new(tx)
+});
+// tells apart the stores of successive logins, so a backfill doesn't write into the next one
+static SESSION: AtomicU64 = AtomicU64::new(0);
+
+pub struct MessageStore {
+    conn: Connection,
+    session: u64,
+}
+
+impl MessageStore {
+    fn open(path: &Path, key: &str) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        // a raw key, so opening the database doesn't go through the key derivation
+        conn.pragma_update(None, "key", format!("x'{key}'"))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> rusqlite::Result<Self> {
+        // this is the first read, it fails if the key is wrong
+        let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version < SCHEMA_VERSION {
+            conn.execute_batch(SCHEMA)?;
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        Ok(Self {
+            conn,
+            session: SESSION.fetch_add(1, Ordering::Relaxed) + 1,
+        })
+    }
+
+    fn save(&mut self, messages: &[Message]) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO messages (id, conversation_id, date, sender, pinned, body, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (id) DO UPDATE SET date = ?3, pinned = ?5, body = ?6, data = ?7",
+            )?;
+            // a conversation seen for the first time starts with its first message unread
+            let mut conversation = tx.prepare_cached(
+                "INSERT OR IGNORE INTO conversations (id, last_read) VALUES (?1, ?2 - 1)",
+            )?;
+            for message in messages {
+                let inner = &message.inner;
+                let data = serde_json::to_string(message)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                insert.execute(params![
+                    inner.id().to_string(),
+                    inner.conversation_id().to_string(),
+                    inner.date().timestamp_millis(),
+                    inner.sender().to_string(),
+                    inner.pinned(),
+                    inner.lines().join("\n"),
+                    data,
+                ])?;
+                conversation.execute(params![
+                    inner.conversation_id().to_string(),
+                    inner.date().timestamp_millis(),
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
+    // replaces the RayGun message of a message which was already copied
+    fn update(&mut self, message: &raygun::Message) -> rusqlite::Result<()> {
+        let data: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT data FROM messages WHERE id = ?1",
+                params![message.id().to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(mut stored) = data.and_then(|data| serde_json::from_str::<Message>(&data).ok())
+        else {
+            return Ok(());
+        };
+        stored.inner = message.clone();
+        self.save(&[stored])
+    }
+
+    fn delete(&mut self, message_id: Uuid) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "DELETE FROM messages WHERE id = ?1",
+            params![message_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn delete_conversation(&mut self, conv_id: Uuid) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1",
+            params![conv_id.to_string()],
+        )?;
+        tx.execute(
+            "DELETE FROM conversations WHERE id = ?1",
+            params![conv_id.to_string()],
+        )?;
+        tx.commit()
+    }
+
+    // the messages from before a conversation is first copied count as read
+    fn start_backfill(&mut self, conv_id: Uuid, last_read: i64) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO conversations (id, last_read) VALUES (?1, ?2)",
+            params![conv_id.to_string(), last_read],
+        )?;
+        Ok(())
+    }
+
+    // the conversation is copied again, what was read stays read
+    fn reset(&mut self, conv_id: Uuid) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1",
+            params![conv_id.to_string()],
+        )?;
+        tx.execute(
+            "UPDATE conversations SET complete = 0 WHERE id = ?1",
+            params![conv_id.to_string()],
+        )?;
+        tx.commit()
+    }
+
+    fn count(&self, conv_id: Uuid) -> rusqlite::Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT count(*) FROM messages WHERE conversation_id = ?1",
+            params![conv_id.to_string()],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    fn set_complete(&mut self, conv_id: Uuid) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE conversations SET complete = 1 WHERE id = ?1",
+            params![conv_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn is_complete(&self, conv_id: Uuid) -> rusqlite::Result<bool> {
+        let complete: Option<bool> = self
+            .conn
+            .query_row(
+                "SELECT complete FROM conversations WHERE id = ?1",
+                params![conv_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(complete.unwrap_or_default())
+    }
+
+    // the last `limit` messages, from oldest to newest
+    fn latest(&self, conv_id: Uuid, limit: usize) -> rusqlite::Result<Vec<Message>> {
+        let mut messages = self.query(
+            "SELECT data FROM messages WHERE conversation_id = ?1
+             ORDER BY date DESC LIMIT ?2",
+            params![conv_id.to_string(), limit as i64],
+        )?;
+        messages.reverse();
+        Ok(messages)
+    }
+
+    // the `limit` messages before `date`, from oldest to newest
+    fn earlier(
+        &self,
+        conv_id: Uuid,
+        date: DateTime<Utc>,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<Message>> {
+        let mut messages = self.query(
+            "SELECT data FROM messages WHERE conversation_id = ?1 AND date < ?2
+             ORDER BY date DESC LIMIT ?3",
+            params![conv_id.to_string(), date.timestamp_millis(), limit as i64],
+        )?;
+        messages.reverse();
+        Ok(messages)
+    }
+
+    // the `limit` messages from `date` on, from oldest to newest
+    fn later(
+        &self,
+        conv_id: Uuid,
+        date: DateTime<Utc>,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<Message>> {
+        self.query(
+            "SELECT data FROM messages WHERE conversation_id = ?1 AND date >= ?2
+             ORDER BY date ASC LIMIT ?3",
+            params![conv_id.to_string(), date.timestamp_millis(), limit as i64],
+        )
+    }
+
+    // from newest to oldest, like RayGun returns them
+    fn pinned(&self, conv_id: Uuid, limit: usize) -> rusqlite::Result<Vec<raygun::Message>> {
+        let messages = self.query(
+            "SELECT data FROM messages WHERE conversation_id = ?1 AND pinned = 1
+             ORDER BY date DESC LIMIT ?2",
+            params![conv_id.to_string(), limit as i64],
+        )?;
+        Ok(messages.into_iter().map(|m| m.inner).collect())
+    }
+
+    // the newest messages containing all the words of `query`
+    fn search(
+        &self,
+        query: &str,
+        conv_id: Option<Uuid>,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<Message>> {
+        // every word is quoted, so what people type isn't read as the query syntax of fts5
+        let query = query
+            .split_whitespace()
+            .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if query.is_empty() {
+            return Ok(vec![]);
+        }
+        self.query(
+            "SELECT messages.data FROM messages_text
+             JOIN messages ON messages.rowid = messages_text.rowid
+             WHERE messages_text MATCH ?1 AND (?2 IS NULL OR messages.conversation_id = ?2)
+             ORDER BY messages.date DESC LIMIT ?3",
+            params![query, conv_id.map(|id| id.to_string()), limit as i64],
+        )
+    }
+
+    // the messages others sent after each conversation was last read. the ones which aren't shown can't be read
+    fn unread(&self, own: &DID) -> rusqlite::Result<HashMap<Uuid, Vec<Uuid>>> {
+        let messages = self.query(
+            "SELECT messages.data FROM conversations
+             JOIN messages ON messages.conversation_id = conversations.id
+             WHERE messages.date > conversations.last_read AND messages.sender != ?1",
+            params![own.to_string()],
+        )?;
+        let mut unread: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for message in messages {
+            if ui_adapter::is_shown(&message.inner) {
+                unread
+                    .entry(message.inner.conversation_id())
+                    .or_default()
+                    .push(message.inner.id());
+            }
+        }
+        Ok(unread)
+    }
+
+    fn mark_read(&mut self, conv_id: Uuid, date: DateTime<Utc>) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO conversations (id, last_read) VALUES (?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET last_read = max(last_read, ?2)",
+            params![conv_id.to_string(), date.timestamp_millis()],
+        )?;
+        Ok(())
+    }
+
+    fn query(&self, sql: &str, params: impl rusqlite::Params) -> rusqlite::Result<Vec<Message>> {
+        let mut stmt = self.conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+        let mut messages = vec![];
+        for data in rows {
+            match serde_json::from_str(&data?) {
+                Ok(message) => messages.push(message),
+                Err(e) => log::warn!("skipping a stored message which can't be read: {e}"),
+            }
+        }
+        Ok(messages)
+    }
+}
+
+/// Opens the store of the account which was just unlocked, creating it and its key the first
+/// time. A store which can't be read, because the key was lost with tesseract or the file is
+/// damaged, is started over: it's only a copy of what RayGun has
+pub fn open(tesseract: &Tesseract) {
+    let key = match key(tesseract) {
+        Ok(key) => key,
+        Err(e) => {
+            log::error!("no key for the message store: {e}");
+            return;
+        }
+    };
+    let path = STATIC_ARGS.message_store_path();
+    send(Box::new(move |store| {
+        *store = open_or_start_over(&path, &key)
+    }));
+}
+
+pub fn close() {
+    send(Box::new(|store| *store = None));
+}
+
+fn open_or_start_over(path: &Path, key: &str) -> Option<MessageStore> {
+    let opened = match MessageStore::open(path, key) {
+        Err(e) if is_unusable(&e) => {
+            log::warn!("starting the message store over: {e}");
+            for file in [
+                path.to_path_buf(),
+                sidecar(path, "-wal"),
+                sidecar(path, "-shm"),
+            ] {
+                let _ = fs::remove_file(file);
+            }
+            MessageStore::open(path, key)
+        }
+        opened => opened,
+    };
+    match opened {
+        Ok(store) => Some(store),
+        Err(e) => {
+            log::error!("failed to open the message store, messages are read from RayGun: {e}");
+            None
+        }
+    }
+}
+
+// the key doesn't fit, the file is damaged or its tables don't fit the schema. other errors, like a
+// full disk or a locked file, leave the store alone
+fn is_unusable(e: &rusqlite::Error) -> bool {
+    let rusqlite::Error::SqliteFailure(e, _) = e else {
+        return false;
+    };
+    matches!(
+        e.code,
+        ErrorCode::NotADatabase | ErrorCode::DatabaseCorrupt | ErrorCode::Unknown
+    )
+}
+
+// the write ahead log and its index next to the database
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn key(tesseract: &Tesseract) -> Result<String, warp::error::Error> {
+    if tesseract.exist(KEY_NAME) {
+        let key = tesseract.retrieve(KEY_NAME)?;
+        return Ok(String::from_utf8_lossy(&key).into_owned());
+    }
+    let mut bytes = [0_u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let key: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    tesseract.set(KEY_NAME, &key)?;
+    Ok(key)
+}
+
+fn send(job: Job) {
+    if WORKER.lock().send(job).is_err() {
+        log::error!("the message store stopped");
+    }
+}
+
+// runs `f` without waiting for it. an error is logged
+fn write(f: impl FnOnce(&mut MessageStore) -> rusqlite::Result<()> + Send + 'static) {
+    send(Box::new(move |store| {
+        if let Some(Err(e)) = store.as_mut().map(f) {
+            log::error!("message store: {e}");
+        }
+    }));
+}
+
+// `None` if there's no store or the query failed, which is logged
+async fn with_store<T: Send + 'static>(
+    f: impl FnOnce(&mut MessageStore) -> rusqlite::Result<T> + Send + 'static,
+) -> Option<T> {
+    let (tx, rx) = oneshot::channel();
+    send(Box::new(move |store| {
+        let value = match store.as_mut().map(f) {
+            Some(Ok(value)) => Some(value),
+            Some(Err(e)) => {
+                log::error!("message store: {e}");
+                None
+            }
+            None => None,
+        };
+        let _ = tx.send(value);
+    }));
+    rx.await.ok().flatten()
+}
+
+async fn current_session() -> Option<u64> {
+    with_store(|store| Ok(store.session)).await
+}
+
+pub fn save(messages: &[Message]) {
+    if !messages.is_empty() {
+        let messages = messages.to_vec();
+        write(move |store| store.save(&messages));
+    }
+}
+
+/// Whether the conversation can be read from the store instead of RayGun. A conversation which has
+/// another number of messages than in RayGun is copied again by the next `backfill`
+pub async fn reconcile(conv_id: Uuid, messaging: &Messaging) -> bool {
+    if !with_store(move |store| store.is_complete(conv_id))
+        .await
+        .unwrap_or_default()
+    {
+        return false;
+    }
+    let total = match messaging.get_message_count(conv_id).await {
+        Ok(total) => total,
+        Err(e) => {
+            log::warn!("failed to count the messages of {conv_id}: {e}");
+            return false;
+        }
+    };
+    with_store(move |store| {
+        if store.count(conv_id)? == total {
+            return Ok(true);
+        }
+        log::debug!("the stored messages of {conv_id} are out of date");
+        store.reset(conv_id)?;
+        Ok(false)
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Answers `config` from the store, `None` if the conversation wasn't copied completely
+pub async fn fetch(conv_id: Uuid, config: FetchMessagesConfig) -> Option<FetchMessagesResponse> {
+    with_store(move |store| {
+        if !store.is_complete(conv_id)? {
+            return Ok(None);
+        }
+        let mut messages = match config {
+            FetchMessagesConfig::MostRecent { limit } => store.latest(conv_id, limit)?,
+            FetchMessagesConfig::Earlier { start_date, limit } => {
+                store.earlier(conv_id, start_date, limit)?
+            }
+            FetchMessagesConfig::Later { start_date, limit } => {
+                store.later(conv_id, start_date, limit)?
+            }
+            _ => return Ok(None),
+        };
+        // like `ui_adapter::fetch_messages2`, counted before the hidden messages are dropped
+        let has_more = messages.len() >= config.get_limit();
+        messages.retain(|message| ui_adapter::is_shown(&message.inner));
+        let most_recent = store.latest(conv_id, 1)?.pop().map(|m| m.inner.id());
+        Ok(Some(FetchMessagesResponse {
+            messages,
+            has_more,
+            most_recent,
+        }))
+    })
+    .await
+    .flatten()
+}
+
+/// The messages shown in the sidebar and the pinned messages of a conversation, `None` if it
+/// wasn't copied completely
+pub async fn preview(conv_id: Uuid) -> Option<(VecDeque<Message>, Vec<raygun::Message>)> {
+    with_store(move |store| {
+        if !store.is_complete(conv_id)? {
+            return Ok(None);
+        }
+        let mut messages = store.latest(conv_id, PREVIEW_MESSAGES)?;
+        messages.retain(|message| ui_adapter::is_shown(&message.inner));
+        let pinned = store.pinned(conv_id, MAX_PINNED_MESSAGES as usize)?;
+        Ok(Some((messages.into(), pinned)))
+    })
+    .await
+    .flatten()
+}
+
+/// The newest messages containing all the words of `query`, in one conversation or in all of them
+pub async fn search(query: String, conv_id: Option<Uuid>, limit: usize) -> Vec<Message> {
+    with_store(move |store| {
+        let mut messages = store.search(&query, conv_id, limit)?;
+        messages.retain(|message| ui_adapter::is_shown(&message.inner));
+        Ok(messages)
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// The messages others sent since each conversation was last read, by conversation
+pub async fn unread(own: &DID) -> HashMap<Uuid, Vec<Uuid>> {
+    let own = own.clone();
+    with_store(move |store| store.unread(&own))
+        .await
+        .unwrap_or_default()
+}
+
+pub fn mark_read(conv_id: Uuid) {
+    let now = Utc::now();
+    write(move |store| store.mark_read(conv_id, now));
+}
+
+/// Keeps the store up to date. Called with every message event, before it's sent to the UI
+pub fn on_message_event(evt: &MessageEvent) {
+    match evt {
+        MessageEvent::Received { message, .. } | MessageEvent::Edited { message, .. } => {
+            save(std::slice::from_ref(message))
+        }
+        MessageEvent::Sent {
+            conversation_id,
+            message,
+        } => {
+            let (conv_id, message) = (*conversation_id, message.clone());
+            write(move |store| {
+                store.save(std::slice::from_ref(&message))?;
+                // answering a conversation reads it
+                store.mark_read(conv_id, message.inner.date())
+            });
+        }
+        MessageEvent::Deleted { message_id, .. } => {
+            let message_id = *message_id;
+            write(move |store| store.delete(message_id));
+        }
+        MessageEvent::MessagePinned { message }
+        | MessageEvent::MessageUnpinned { message }
+        | MessageEvent::MessageReactionAdded { message, .. }
+        | MessageEvent::MessageReactionRemoved { message } => {
+            let message = message.clone();
+            write(move |store| store.update(&message));
+        }
+        _ => {}
+    }
+}
+
+pub fn on_raygun_event(evt: &RayGunEventKind) {
+    if let RayGunEventKind::ConversationDeleted { conversation_id } = evt {
+        let conv_id = *conversation_id;
+        write(move |store| store.delete_conversation(conv_id));
+    }
+}
+
+/// Copies the history of the conversations which weren't copied completely yet, oldest messages
+/// first. This is the migration from reading everything from RayGun: it runs once for every
+/// conversation, and again for the ones it didn't finish or which got out of date. The messages
+/// from before a conversation was first copied count as read
+pub async fn backfill(mut messaging: Messaging, conversations: Vec<Uuid>) {
+    let Some(session) = current_session().await else {
+        return;
+    };
+    let now = Utc::now().timestamp_millis();
+    for conv_id in conversations {
+        let started = with_session(session, move |store| store.start_backfill(conv_id, now)).await;
+        if started.is_none() {
+            // logged out
+            return;
+        }
+        if reconcile(conv_id, &messaging).await {
+            continue;
+        }
+        match backfill_conversation(&mut messaging, conv_id, session).await {
+            Ok(true) => {
+                with_session(session, move |store| store.set_complete(conv_id)).await;
+            }
+            Ok(false) => return,
+            Err(e) => log::warn!("failed to copy the messages of {conv_id}: {e}"),
+        }
+    }
+    log::debug!("message store is up to date");
+}
+
+// false if the store was closed in the meantime
+async fn backfill_conversation(
+    messaging: &mut Messaging,
+    conv_id: Uuid,
+    session: u64,
+) -> Result<bool, warp::error::Error> {
+    let mut start = 0;
+    loop {
+        let (page, total) =
+            ui_adapter::fetch_message_page(conv_id, messaging, start..start + BACKFILL_PAGE)
+                .await?;
+        if page.is_empty() {
+            return Ok(true);
+        }
+        let mut messages = Vec::with_capacity(page.len());
+        for message in &page {
+            messages.push(ui_adapter::convert_raygun_message(messaging, message).await);
+        }
+        if with_session(session, move |store| store.save(&messages))
+            .await
+            .is_none()
+        {
+            return Ok(false);
+        }
+        start += page.len();
+        if start >= total {
+            return Ok(true);
+        }
+    }
+}
+
+async fn with_session<T: Send + 'static>(
+    session: u64,
+    f: impl FnOnce(&mut MessageStore) -> rusqlite::Result<T> + Send + 'static,
+) -> Option<T> {
+    with_store(move |store| {
+        if store.session != session {
+            return Ok(None);
+        }
+        f(store).map(Some)
+    })
+    .await
+    .flatten()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::warp_runner::read_receipts::ReadReceipt;
+    use chrono::TimeZone;
+
+    fn message(conv_id: Uuid, minute: u32, text: &str, sender: &DID) -> Message {
+        let mut inner = raygun::Message::default();
+        inner.set_id(Uuid::new_v4());
+        inner.set_conversation_id(conv_id);
+        inner.set_sender(sender.clone());
+        inner.set_date(Utc.with_ymd_and_hms(2024, 1, 1, 12, minute, 0).unwrap());
+        inner.set_lines(vec![text.to_string()]);
+        Message::new(inner, None, Uuid::new_v4().to_string())
+    }
+
+    #[test]
+    fn pages_search_and_unread() {
+        let mut store = MessageStore::init(Connection::open_in_memory().unwrap()).unwrap();
+        let conv_id = Uuid::new_v4();
+        let own = DID::default();
+        // a new random key
+        let other = DID::default();
+        let messages: Vec<_> = (0..10)
+            .map(|i| message(conv_id, i, &format!("message number {i}"), &other))
+            .collect();
+        store.save(&messages).unwrap();
+
+        let latest = store.latest(conv_id, 3).unwrap();
+        assert_eq!(latest, messages[7..]);
+        let earlier = store.earlier(conv_id, latest[0].inner.date(), 3).unwrap();
+        assert_eq!(earlier, messages[4..7]);
+        let later = store.later(conv_id, messages[8].inner.date(), 5).unwrap();
+        assert_eq!(later, messages[8..]);
+
+        let found = store.search("NUMBER 4", None, 10).unwrap();
+        assert_eq!(found, messages[4..5]);
+        store.delete(messages[4].inner.id()).unwrap();
+        assert!(store.search("number 4", None, 10).unwrap().is_empty());
+
+        assert_eq!(store.unread(&own).unwrap()[&conv_id].len(), 9);
+        store.mark_read(conv_id, messages[6].inner.date()).unwrap();
+        assert_eq!(store.unread(&own).unwrap()[&conv_id].len(), 3);
+
+        // copying it again keeps what was read
+        store.set_complete(conv_id).unwrap();
+        assert_eq!(store.count(conv_id).unwrap(), 9);
+        store.reset(conv_id).unwrap();
+        assert!(!store.is_complete(conv_id).unwrap());
+        assert_eq!(store.count(conv_id).unwrap(), 0);
+        store.start_backfill(conv_id, i64::MAX).unwrap();
+        store.save(&messages).unwrap();
+        assert_eq!(store.unread(&own).unwrap()[&conv_id].len(), 3);
+
+        // read receipts aren't shown, so they aren't unread
+        let mut receipt = message(conv_id, 30, "", &other);
+        let up_to = receipt.inner.date();
+        receipt
+            .inner
+            .set_lines(ReadReceipt { up_to }.message().unwrap());
+        store.save(&[receipt]).unwrap();
+        assert_eq!(store.unread(&own).unwrap()[&conv_id].len(), 3);
+    }
+}
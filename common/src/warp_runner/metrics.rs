@@ -0,0 +1,139 @@
+//! Measures how long the warp_runner takes to execute each kind of command. The most recent samples
+//! are kept per command so the percentiles follow changes in latency. Shown in the developer settings.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tracing::log;
+
+// per command
+const MAX_SAMPLES: usize = 256;
+/// upper bounds of the histogram buckets. the last bucket holds everything slower
+pub const HISTOGRAM_BUCKETS: [Duration; 6] = [
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+];
+
+static SAMPLES: Lazy<Mutex<HashMap<String, Samples>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static LOG_LATENCY: AtomicBool = AtomicBool::new(false);
+
+#[derive(Default)]
+struct Samples {
+    count: usize,
+    recent: VecDeque<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandStats {
+    pub name: String,
+    // including the samples which were dropped
+    pub count: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+    // of the recent samples, with one more bucket than `HISTOGRAM_BUCKETS`
+    pub histogram: Vec<usize>,
+}
+
+pub fn record(name: &str, elapsed: Duration) {
+    if LOG_LATENCY.load(Ordering::Relaxed) {
+        log::info!("{name} took {} ms", elapsed.as_millis());
+    }
+    let mut samples = SAMPLES.lock();
+    let samples = samples.entry(name.to_string()).or_default();
+    samples.count += 1;
+    if samples.recent.len() == MAX_SAMPLES {
+        samples.recent.pop_front();
+    }
+    samples.recent.push_back(elapsed);
+}
+
+/// the stats of every command executed so far, slowest (by p90) first
+pub fn snapshot() -> Vec<CommandStats> {
+    let samples = SAMPLES.lock();
+    let mut stats: Vec<_> = samples
+        .iter()
+        .map(|(name, samples)| {
+            let mut sorted: Vec<_> = samples.recent.iter().copied().collect();
+            sorted.sort();
+            CommandStats {
+                name: name.clone(),
+                count: samples.count,
+                p50: percentile(&sorted, 50),
+                p90: percentile(&sorted, 90),
+                p99: percentile(&sorted, 99),
+                max: sorted.last().copied().unwrap_or_default(),
+                histogram: histogram(&sorted),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.p90.cmp(&a.p90).then_with(|| a.name.cmp(&b.name)));
+    stats
+}
+
+pub fn reset() {
+    SAMPLES.lock().clear();
+}
+
+/// logs the duration of every command
+pub fn set_logging(enabled: bool) {
+    LOG_LATENCY.store(enabled, Ordering::Relaxed);
+}
+
+pub fn logging_enabled() -> bool {
+    LOG_LATENCY.load(Ordering::Relaxed)
+}
+
+fn histogram(samples: &[Duration]) -> Vec<usize> {
+    let mut buckets = vec![0; HISTOGRAM_BUCKETS.len() + 1];
+    for sample in samples {
+        let idx = HISTOGRAM_BUCKETS
+            .iter()
+            .position(|bound| sample <= bound)
+            .unwrap_or(HISTOGRAM_BUCKETS.len());
+        buckets[idx] += 1;
+    }
+    buckets
+}
+
+// nearest rank
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let sorted: Vec<_> = (1..=10).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50), Duration::from_millis(5));
+        assert_eq!(percentile(&sorted, 90), Duration::from_millis(9));
+        assert_eq!(percentile(&sorted, 99), Duration::from_millis(10));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+    }
+
+    #[test]
+    fn histogram_counts_slow_samples_in_last_bucket() {
+        let samples: Vec<_> = [0, 1, 5, 60, 2000]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        assert_eq!(histogram(&samples), vec![2, 1, 0, 1, 0, 0, 1]);
+    }
+}
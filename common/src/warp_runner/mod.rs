@@ -1,6 +1,8 @@
 //! Defines important types and structs, and spawns the main task for warp_runner - manager::run.
 use derive_more::Display;
-use std::sync::Arc;
+use names::Generator;
+use std::{path::PathBuf, sync::Arc};
+use titlecase::titlecase;
 
 use tokio::sync::{
     broadcast,
@@ -8,6 +10,7 @@ use tokio::sync::{
     Mutex, Notify,
 };
 use tracing::log;
+use uuid::Uuid;
 use warp::{
     blink::{
         Blink::{self},
@@ -24,18 +27,42 @@ use warp_ipfs::{
     WarpIpfsBuilder,
 };
 
-use crate::{DiscoveryMode, STATIC_ARGS, WARP_CMD_CH};
+use crate::{
+    profiles::Profiles,
+    utils::{
+        device_link,
+        duress::{self, DuressAction},
+        keychain,
+    },
+    DiscoveryMode, STATIC_ARGS, WARP_CMD_CH,
+};
 
 use self::ui_adapter::{MultiPassEvent, RayGunEvent};
 
+pub mod bandwidth;
+mod cancellation;
+pub mod checksum;
 mod conv_stream;
 mod data;
+mod group_invites;
+mod group_roles;
 mod manager;
+pub mod message_store;
+pub mod metrics;
+pub mod read_receipts;
+pub mod storage_backend;
+pub mod transfer_queue;
 pub mod ui_adapter;
 
+pub use cancellation::{CancelGuard, CancelToken};
 pub use data::*;
-pub use manager::commands::thumbnail_to_base64;
-pub use manager::{BlinkCmd, ConstellationCmd, MultiPassCmd, OtherCmd, RayGunCmd, TesseractCmd};
+pub use group_invites::{GroupInvite, InviteLink};
+pub use group_roles::GroupUpdate;
+pub use manager::commands::{send_batch, thumbnail_to_base64};
+pub use manager::{
+    BatchCmd, BatchContext, BatchError, BatchProgress, BatchStep, BlinkCmd, ConstellationCmd,
+    DuressOutcome, MultiPassCmd, OtherCmd, PassphraseCheck, RayGunCmd, TesseractCmd,
+};
 
 pub type WarpCmdTx = UnboundedSender<WarpCmd>;
 pub type WarpCmdRx = Arc<Mutex<UnboundedReceiver<WarpCmd>>>;
@@ -91,6 +118,69 @@ pub enum WarpCmd {
     // async task, executed separately from the UI
     #[display(fmt = "Other {{ {_0} }} ")]
     Other(OtherCmd),
+    // several of the above, executed in order as one unit
+    #[display(fmt = "{_0} ")]
+    Batch(BatchCmd),
+    // created by WarpCmd::cancellable
+    #[display(fmt = "{_0}(cancellable) ")]
+    Cancellable(Box<WarpCmd>, CancelToken),
+}
+
+/// Commands which may take long are handled in the bulk lane, separately from the interactive ones.
+/// This way sending a message doesn't wait for a large upload to be processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lane {
+    Interactive,
+    Bulk,
+}
+
+impl WarpCmd {
+    /// lets the sender abort the command with `token`, for example once the result isn't needed
+    /// anymore because the user navigated away. a batch is only skipped if it didn't start yet.
+    pub fn cancellable(self, token: CancelToken) -> Self {
+        match self {
+            WarpCmd::Cancellable(cmd, _) => WarpCmd::Cancellable(cmd, token),
+            cmd => WarpCmd::Cancellable(Box::new(cmd), token),
+        }
+    }
+
+    /// the kind of command without its arguments, like "RayGun::SendMessage"
+    pub fn name(&self) -> String {
+        let (kind, cmd) = match self {
+            WarpCmd::Tesseract(cmd) => ("Tesseract", cmd.to_string()),
+            WarpCmd::MultiPass(cmd) => ("MultiPass", cmd.to_string()),
+            WarpCmd::RayGun(cmd) => ("RayGun", cmd.to_string()),
+            WarpCmd::Constellation(cmd) => ("Constellation", cmd.to_string()),
+            WarpCmd::Blink(cmd) => ("Blink", cmd.to_string()),
+            WarpCmd::Other(cmd) => ("Other", cmd.to_string()),
+            WarpCmd::Batch(batch) => return format!("Batch::{}", batch.label),
+            WarpCmd::Cancellable(cmd, _) => return cmd.name(),
+        };
+        let variant = cmd.split_whitespace().next().unwrap_or_default();
+        format!("{kind}::{variant}")
+    }
+
+    pub fn lane(&self) -> Lane {
+        match self {
+            WarpCmd::Cancellable(cmd, _) => cmd.lane(),
+            WarpCmd::Constellation(
+                ConstellationCmd::UploadFiles { .. }
+                | ConstellationCmd::DownloadFile { .. }
+                | ConstellationCmd::CheckStorageIntegrity { .. }
+                | ConstellationCmd::RepairStorageIntegrity { .. },
+            )
+            | WarpCmd::Other(_) => Lane::Bulk,
+            WarpCmd::Constellation(ConstellationCmd::InDirectory { cmd, .. })
+                if matches!(
+                    **cmd,
+                    ConstellationCmd::UploadFiles { .. } | ConstellationCmd::DownloadFile { .. }
+                ) =>
+            {
+                Lane::Bulk
+            }
+            _ => Lane::Interactive,
+        }
+    }
 }
 
 /// Spawns a task which manages multiple streams, channels, and tasks related to warp
@@ -138,6 +228,10 @@ async fn handle_login(notify: Arc<Notify>) {
     // be sure to drop this channel before calling manager::run()
     let mut warp_cmd_rx = warp_cmd_rx.lock().await;
 
+    // the duress PIN was entered at the lock screen the last time
+    if duress::wipe_pending() {
+        wipe_profile().await;
+    }
     let tesseract = init_tesseract(false)
         .await
         .expect("failed to initialize tesseract");
@@ -150,7 +244,7 @@ async fn handle_login(notify: Arc<Notify>) {
         }
     };
 
-    let account_exists = warp.tesseract.exist("keypair");
+    let mut account_exists = warp.tesseract.exist("keypair");
 
     // until the user logs in, raygun and multipass are no use.
     let warp: Option<manager::Warp> = loop {
@@ -248,6 +342,21 @@ async fn handle_login(notify: Arc<Notify>) {
                             continue;
                         };
                         match wait_for_multipass(&mut warp, notify.clone()).await {
+                            Ok(ident) if device_link::is_revoked(&ident.metadata()) => {
+                                // another device of the account revoked this one
+                                log::warn!("this device was revoked, deleting the account from it");
+                                warp.tesseract.lock();
+                                wipe_profile().await;
+                                warp = match open_current().await {
+                                    Ok(w) => w,
+                                    Err(e) => {
+                                        let _ = rsp.send(Err(e));
+                                        continue;
+                                    }
+                                };
+                                account_exists = false;
+                                let _ = rsp.send(Err(Error::OtherWithContext(device_link::REVOKED.into())));
+                            }
                             Ok(ident) => {
                                 let _ = rsp.send(Ok(ident));
                                 break Some(warp);
@@ -262,6 +371,133 @@ async fn handle_login(notify: Arc<Notify>) {
                     Some(WarpCmd::Tesseract(TesseractCmd::AccountExists { rsp }))  => {
                         let _ = rsp.send(account_exists);
                     }
+                    Some(WarpCmd::Tesseract(TesseractCmd::SwitchProfile { profile, rsp })) => {
+                        let profiles = Profiles::load();
+                        if profiles.get(&profile).is_none() {
+                            let _ = rsp.send(Err(Error::Other));
+                            continue;
+                        }
+                        // replacing warp shuts down the instance of the previous profile
+                        warp = match open_profile(profiles, profile).await {
+                            Ok(w) => w,
+                            // the previous profile stays open
+                            Err(e) => {
+                                log::error!("warp init failed: {}", e);
+                                let _ = rsp.send(Err(e));
+                                continue;
+                            }
+                        };
+                        account_exists = warp.tesseract.exist("keypair");
+                        let _ = rsp.send(Ok(account_exists));
+                    }
+                    Some(WarpCmd::Tesseract(TesseractCmd::RestoreBackup { path, passphrase, rsp })) => {
+                        // the backup goes into a new profile so nothing is overwritten
+                        let mut profiles = Profiles::load();
+                        let name = path
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        let profile = profiles.add(&name);
+                        let dest = profile.path(&STATIC_ARGS.dot_uplink);
+                        if let Err(e) = manager::commands::restore_backup(path, passphrase, dest).await {
+                            log::warn!("failed to restore backup: {}", e);
+                            let _ = rsp.send(Err(e));
+                            continue;
+                        }
+                        warp = match open_profile(profiles, profile.id).await {
+                            Ok(w) => w,
+                            Err(e) => {
+                                log::error!("warp init failed: {}", e);
+                                let _ = rsp.send(Err(e));
+                                continue;
+                            }
+                        };
+                        account_exists = warp.tesseract.exist("keypair");
+                        let _ = rsp.send(Ok(()));
+                    }
+                    Some(WarpCmd::Tesseract(TesseractCmd::Duress { passphrase, rsp })) => {
+                        // the real PIN wins, in case the user chose the same one twice
+                        if account_exists && warp.tesseract.unlock(passphrase.as_bytes()).is_ok() {
+                            match wait_for_multipass(&mut warp, notify.clone()).await {
+                                Ok(ident) => {
+                                    let _ = rsp.send(Ok(DuressOutcome::LoggedIn(ident)));
+                                    break Some(warp);
+                                },
+                                Err(e) => {
+                                    warp.tesseract.lock();
+                                    let _ = rsp.send(Err(e));
+                                    continue;
+                                }
+                            }
+                        }
+                        // the hash is slow on purpose
+                        let checked = passphrase.clone();
+                        let action = tokio::task::spawn_blocking(move || duress::check(&checked))
+                            .await
+                            .unwrap_or(None);
+                        let (action, decoy) = match (action, duress::decoy_path()) {
+                            (Some(action), Some(decoy)) => (action, decoy),
+                            _ => {
+                                let _ = rsp.send(Err(Error::DecryptionError));
+                                continue;
+                            }
+                        };
+                        match action {
+                            DuressAction::Wipe => {
+                                // nothing is logged, the log would tell that the profile existed
+                                let complete = wipe_profile().await;
+                                warp = match open_current().await {
+                                    Ok(w) => w,
+                                    Err(e) => {
+                                        let _ = rsp.send(Err(e));
+                                        continue;
+                                    }
+                                };
+                                account_exists = false;
+                                let outcome = if complete {
+                                    DuressOutcome::Wiped
+                                } else {
+                                    DuressOutcome::PartiallyWiped
+                                };
+                                let _ = rsp.send(Ok(outcome));
+                            }
+                            DuressAction::Decoy => {
+                                warp = match open_decoy(decoy).await {
+                                    Ok(w) => w,
+                                    Err(e) => {
+                                        log::error!("warp init failed: {}", e);
+                                        let _ = rsp.send(Err(e));
+                                        continue;
+                                    }
+                                };
+                                account_exists = warp.tesseract.exist("keypair");
+                                if let Err(e) = warp.tesseract.unlock(passphrase.as_bytes()) {
+                                    let _ = rsp.send(Err(e));
+                                    continue;
+                                }
+                                if !account_exists {
+                                    let username = Generator::default().next().unwrap_or_default().replace('-', " ");
+                                    if let Err(e) = warp.multipass.create_identity(Some(&titlecase(&username)), None).await {
+                                        log::error!("failed to create the decoy identity: {}", e);
+                                        let _ = rsp.send(Err(e));
+                                        continue;
+                                    }
+                                    account_exists = true;
+                                }
+                                match wait_for_multipass(&mut warp, notify.clone()).await {
+                                    Ok(ident) => {
+                                        let _ = rsp.send(Ok(DuressOutcome::LoggedIn(ident)));
+                                        break Some(warp);
+                                    },
+                                    Err(e) => {
+                                        warp.tesseract.lock();
+                                        let _ = rsp.send(Err(e));
+                                        continue;
+                                    }
+                                }
+                            }
+                        }
+                    }
                     _ => {}
                 }
             },
@@ -311,6 +547,80 @@ async fn wait_for_multipass(
     }
 }
 
+// points STATIC_ARGS at the profile, starts warp with its data and remembers it as the active profile
+async fn open_profile(mut profiles: Profiles, profile: Uuid) -> Result<manager::Warp, Error> {
+    let warp = match profiles
+        .get(&profile)
+        .map(|p| p.path(&STATIC_ARGS.dot_uplink))
+    {
+        Some(path) => {
+            log::debug!("switching to profile {profile}");
+            open_path(path).await?
+        }
+        None => open_current().await?,
+    };
+    profiles.active = profile;
+    profiles.save();
+    Ok(warp)
+}
+
+// like open_profile, but the decoy is never remembered as the active profile so the next launch shows
+// the real one again
+async fn open_decoy(path: PathBuf) -> Result<manager::Warp, Error> {
+    open_path(path).await
+}
+
+// if warp can't be started for the profile at `path`, STATIC_ARGS is pointed back at the previous
+// profile, whose warp instance stays in use
+async fn open_path(path: PathBuf) -> Result<manager::Warp, Error> {
+    let previous = STATIC_ARGS.uplink_path();
+    STATIC_ARGS.set_profile_path(path);
+    if let Err(e) = tokio::fs::create_dir_all(STATIC_ARGS.warp_path()).await {
+        log::warn!("failed to create warp directory: {}", e);
+    }
+    let opened = match init_tesseract(false).await {
+        Ok(tesseract) => warp_initialization(tesseract).await,
+        Err(e) => Err(e),
+    };
+    if opened.is_err() {
+        STATIC_ARGS.set_profile_path(previous);
+    }
+    opened
+}
+
+// starts warp again with the data of the current profile
+async fn open_current() -> Result<manager::Warp, Error> {
+    let tesseract = init_tesseract(false).await?;
+    warp_initialization(tesseract).await
+}
+
+// deletes the data of the current profile, its duress PIN and decoy and its PIN in the keychain.
+// the files are only deleted, not overwritten. returns false if some of them couldn't be deleted,
+// the rest is deleted anyway
+async fn wipe_profile() -> bool {
+    let mut complete = keychain::forget_pin().await.is_ok();
+    complete &= duress::clear().is_ok();
+    let path = STATIC_ARGS.uplink_path();
+    match std::fs::read_dir(&path) {
+        Ok(entries) => {
+            for entry in entries {
+                let removed = entry.and_then(|entry| {
+                    let path = entry.path();
+                    if entry.file_type()?.is_dir() {
+                        std::fs::remove_dir_all(path)
+                    } else {
+                        std::fs::remove_file(path)
+                    }
+                });
+                complete &= removed.is_ok();
+            }
+        }
+        Err(e) => complete &= e.kind() == std::io::ErrorKind::NotFound,
+    }
+    let _ = tokio::fs::create_dir_all(STATIC_ARGS.warp_path()).await;
+    complete
+}
+
 // don't set file or autosave until tesseract is unlocked
 // assumes that all anyone needs from tesseract is "keypair"
 // otherwise, Tesseract::to_file probably needs to call file.sync_all()
@@ -319,18 +629,18 @@ async fn init_tesseract(overwrite_old_account: bool) -> Result<Tesseract, Error>
 
     if overwrite_old_account {
         // delete old account data
-        if let Err(e) = tokio::fs::remove_dir_all(&STATIC_ARGS.uplink_path).await {
+        if let Err(e) = tokio::fs::remove_dir_all(STATIC_ARGS.uplink_path()).await {
             log::warn!("failed to delete uplink directory: {}", e);
         }
 
         // create directories
-        if let Err(e) = tokio::fs::create_dir_all(&STATIC_ARGS.warp_path).await {
+        if let Err(e) = tokio::fs::create_dir_all(STATIC_ARGS.warp_path()).await {
             log::warn!("failed to create warp directory: {}", e);
         }
     }
 
     // open existing file or create new one
-    Tesseract::open_or_create(&STATIC_ARGS.warp_path, &STATIC_ARGS.tesseract_file)
+    Tesseract::open_or_create(&STATIC_ARGS.warp_path(), &STATIC_ARGS.tesseract_file)
 }
 
 impl From<&DiscoveryMode> for Discovery {
@@ -347,20 +657,11 @@ impl From<&DiscoveryMode> for Discovery {
                 },
             },
             DiscoveryMode::Shuttle => {
-                let env_addrs = std::env::var("SHUTTLE_ADDR_POINT")
-                    .map(|val| {
-                        val.split(',')
-                            .filter_map(|addr_str| addr_str.parse::<_>().ok())
-                            .collect::<Vec<_>>()
-                    })
-                    .unwrap_or_default();
-
-                let addresses = match env_addrs.is_empty() {
-                    true => Vec::from_iter(["/ip4/159.65.41.31/tcp/8848/p2p/12D3KooWRF2bz3KDRPvBs1FASRDRk7BfdYc1RUcfwKsz7UBEu7mL"
-                                .parse()
-                                .expect("valid addr")]),
-                    false => env_addrs
-                };
+                let addresses = mode
+                    .addresses()
+                    .iter()
+                    .filter_map(|addr_str| addr_str.parse::<_>().ok())
+                    .collect::<Vec<_>>();
 
                 log::debug!("shuttle addresses: {:?}", addresses);
 
@@ -381,8 +682,8 @@ impl From<DiscoveryMode> for Discovery {
 async fn warp_initialization(tesseract: Tesseract) -> Result<manager::Warp, warp::error::Error> {
     log::debug!("warp initialization");
 
-    let path = &STATIC_ARGS.warp_path;
-    let mut config = Config::production(path);
+    let path = storage_backend::prepare().await?;
+    let mut config = Config::production(&path);
 
     *config.save_phrase_mut() = true; // TODO: This should be bound to a setting within Uplink so that the user can choose not to reveal the phrase for increased security.``
     *config.bootstrap_mut() = Bootstrap::None;
@@ -0,0 +1,175 @@
+//! Read receipts. RayGun doesn't send any, so when the user reads a conversation the date of the newest message they
+//! have seen is sent to the other participants as a message. Receipts are never shown, and the participants keep the
+//! newest one of every member of the conversation to tell who read a message. As every receipt is a message to every
+//! member, a conversation gets at most one every `THROTTLE`, for the newest message read in it.
+//!
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use futures::channel::oneshot;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+use warp::{error::Error, raygun};
+
+use super::{RayGunCmd, WarpCmd};
+use crate::WARP_CMD_CH;
+
+// messages starting with this are read receipts and aren't shown to the user
+const RECEIPT_PREFIX: &str = "uplink-read:";
+
+const THROTTLE: Duration = Duration::from_secs(60);
+
+static THROTTLES: Lazy<Mutex<HashMap<Uuid, Throttle>>> = Lazy::new(Default::default);
+
+/// The messages sent up to `up_to` were read by the sender of the receipt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadReceipt {
+    pub up_to: DateTime<Utc>,
+}
+
+impl ReadReceipt {
+    /// the message sent to the conversation
+    pub fn message(&self) -> Result<Vec<String>, Error> {
+        let receipt = serde_json::to_string(self).map_err(anyhow::Error::from)?;
+        Ok(vec![format!("{RECEIPT_PREFIX}{receipt}")])
+    }
+
+    /// returns the receipt if the message is one
+    pub fn from_message(message: &raygun::Message) -> Option<Self> {
+        match message.lines().as_slice() {
+            [line] => serde_json::from_str(line.strip_prefix(RECEIPT_PREFIX)?).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Tells the other participants of the conversation that the user read the messages sent up to `up_to`. If a receipt
+/// was sent to the conversation less than `THROTTLE` ago, this one is sent once it passed, unless a newer one replaces
+/// it. Failures are only logged: the next receipt covers the same messages
+pub fn send(conv_id: Uuid, up_to: DateTime<Utc>) {
+    let now = Instant::now();
+    let next = THROTTLES
+        .lock()
+        .entry(conv_id)
+        .or_default()
+        .next(up_to, now);
+    match next {
+        Next::Send => send_now(conv_id, up_to),
+        Next::After(wait) => {
+            tokio::spawn(async move {
+                tokio::time::sleep(wait).await;
+                let pending = THROTTLES
+                    .lock()
+                    .get_mut(&conv_id)
+                    .and_then(|throttle| throttle.flush(Instant::now()));
+                if let Some(up_to) = pending {
+                    send_now(conv_id, up_to);
+                }
+            });
+        }
+        Next::Skip => {}
+    }
+}
+
+fn send_now(conv_id: Uuid, up_to: DateTime<Utc>) {
+    let (tx, _) = oneshot::channel();
+    let cmd = WarpCmd::RayGun(RayGunCmd::SendReadReceipt {
+        conv_id,
+        receipt: ReadReceipt { up_to },
+        rsp: tx,
+    });
+    if let Err(e) = WARP_CMD_CH.tx.send(cmd) {
+        log::error!("failed to send warp command: {e}");
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Next {
+    Send,
+    // send what is pending after this long
+    After(Duration),
+    // already sent or pending
+    Skip,
+}
+
+// the receipts of one conversation
+#[derive(Default)]
+struct Throttle {
+    sent: Option<(DateTime<Utc>, Instant)>,
+    // waiting for the throttle to pass
+    pending: Option<DateTime<Utc>>,
+}
+
+impl Throttle {
+    fn next(&mut self, up_to: DateTime<Utc>, now: Instant) -> Next {
+        let newest = self.pending.or(self.sent.map(|(date, _)| date));
+        if newest.is_some_and(|newest| newest >= up_to) {
+            return Next::Skip;
+        }
+        match self.sent {
+            Some((_, at)) if now.duration_since(at) < THROTTLE => {
+                // only the first one which waits schedules the send
+                match self.pending.replace(up_to) {
+                    Some(_) => Next::Skip,
+                    None => Next::After(THROTTLE - now.duration_since(at)),
+                }
+            }
+            _ => {
+                self.sent = Some((up_to, now));
+                Next::Send
+            }
+        }
+    }
+
+    fn flush(&mut self, now: Instant) -> Option<DateTime<Utc>> {
+        let up_to = self.pending.take()?;
+        self.sent = Some((up_to, now));
+        Some(up_to)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn receipts_are_told_apart_from_text() {
+        let receipt = ReadReceipt {
+            up_to: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+        };
+        let mut message = raygun::Message::default();
+        message.set_lines(receipt.message().unwrap());
+        assert_eq!(ReadReceipt::from_message(&message), Some(receipt));
+
+        message.set_lines(vec!["uplink-read: not a receipt".into()]);
+        assert_eq!(ReadReceipt::from_message(&message), None);
+    }
+
+    #[test]
+    fn receipts_are_throttled() {
+        let date = |minute| Utc.with_ymd_and_hms(2024, 1, 1, 12, minute, 0).unwrap();
+        let start = Instant::now();
+        let mut throttle = Throttle::default();
+
+        assert_eq!(throttle.next(date(1), start), Next::Send);
+        assert_eq!(throttle.next(date(1), start), Next::Skip);
+        let later = start + Duration::from_secs(10);
+        assert_eq!(
+            throttle.next(date(2), later),
+            Next::After(THROTTLE - Duration::from_secs(10))
+        );
+        // replaces the pending one without sending another
+        assert_eq!(throttle.next(date(3), later), Next::Skip);
+        assert_eq!(throttle.flush(start + THROTTLE), Some(date(3)));
+        assert_eq!(throttle.flush(start + THROTTLE), None);
+
+        assert_eq!(throttle.next(date(4), start + THROTTLE * 3), Next::Send);
+    }
+}
@@ -0,0 +1,340 @@
+//! Where the data pinned by Constellation is kept. The choice is needed when warp starts, before the
+//! state is loaded, so it has a file of its own. A new backend is applied on the next start, when
+//! nothing has the repository open and its data can be moved. Warp keeps file contents in the same
+//! block store as the rest of the account, so the repository is moved as a whole, the tesseract
+//! stays on this device. The token of a pinning service is kept in the keychain of the OS.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::log;
+use uuid::Uuid;
+use warp::{
+    constellation::{directory::Directory, item::Item},
+    error::Error,
+};
+
+use super::Storage;
+use crate::{utils::keychain, STATIC_ARGS};
+
+// external disks get a directory of their own, so nothing else on them is touched. every profile
+// has its own directory inside it
+const EXTERNAL_DIR: &str = "uplink-data";
+// data is copied here first and only renamed once all of it was copied
+const PARTIAL_SUFFIX: &str = ".partial";
+const TOKEN_SECRET: &str = "pinning-token";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageBackend {
+    #[default]
+    Local,
+    ExternalDisk {
+        path: PathBuf,
+    },
+    // a service implementing the IPFS Pinning Service API. the data is still kept locally, the
+    // service keeps it available while this device is offline
+    RemotePinning {
+        endpoint: String,
+        // only read to move the token of older versions into the keychain
+        #[serde(default, skip_serializing)]
+        token: Option<String>,
+    },
+}
+
+impl StorageBackend {
+    /// The directory of the repository of the profile with the given storage id
+    pub fn data_path(&self, id: &Uuid) -> PathBuf {
+        match self {
+            Self::ExternalDisk { path } => path.join(EXTERNAL_DIR).join(id.to_string()),
+            Self::Local | Self::RemotePinning { .. } => STATIC_ARGS.warp_path(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageConfig {
+    pub active: StorageBackend,
+    // applied on the next start
+    pub pending: Option<StorageBackend>,
+    // names the directory of the profile on external disks. set when a backend is first chosen
+    #[serde(default)]
+    pub id: Uuid,
+    // the files uploaded before the pinning service was chosen are pinned once
+    #[serde(default)]
+    pub pinned_existing: bool,
+}
+
+pub fn load() -> StorageConfig {
+    match fs::read_to_string(STATIC_ARGS.storage_backend_path()) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::error!("failed to deserialize the storage backend: {e}");
+            StorageConfig::default()
+        }),
+        Err(_) => StorageConfig::default(),
+    }
+}
+
+fn save(config: &StorageConfig) -> io::Result<()> {
+    let contents = serde_json::to_string(config)?;
+    fs::write(STATIC_ARGS.storage_backend_path(), contents)
+}
+
+/// Chooses the backend to use from the next start on. The token of a pinning service is saved in
+/// the keychain, None keeps the one saved before
+pub async fn select(backend: StorageBackend, token: Option<String>) -> anyhow::Result<()> {
+    if let Some(token) = token {
+        keychain::store_secret(TOKEN_SECRET, token).await?;
+    }
+    let mut config = load();
+    if config.id.is_nil() {
+        config.id = Uuid::new_v4();
+    }
+    config.pending = (backend != config.active).then_some(backend);
+    save(&config)?;
+    Ok(())
+}
+
+/// Applies a pending backend, moving the data if it is kept somewhere else, and returns the
+/// directory of the repository. The previous backend stays active if the data can't be moved.
+/// Fails if the repository is on an external disk which isn't connected, rather than starting
+/// over with an empty one
+pub async fn prepare() -> Result<PathBuf, Error> {
+    let mut config = load();
+    if let Some(pending) = config.pending.take() {
+        let (from, to) = (
+            config.active.data_path(&config.id),
+            pending.data_path(&config.id),
+        );
+        let moved = match disk_connected(&pending) {
+            Ok(_) => migrate(&from, &to, &[&STATIC_ARGS.tesseract_file]),
+            Err(e) => Err(e),
+        };
+        match moved {
+            Ok(_) => {
+                log::info!("moved the pinned data from {from:?} to {to:?}");
+                if matches!(pending, StorageBackend::RemotePinning { .. }) {
+                    config.pinned_existing = false;
+                }
+                config.active = pending;
+            }
+            Err(e) => log::error!("failed to move the pinned data to {to:?}: {e}"),
+        }
+        if let Err(e) = save(&config) {
+            log::error!("failed to save the storage backend: {e}");
+        }
+    }
+    if let StorageBackend::RemotePinning { token, .. } = &mut config.active {
+        if let Some(token) = token.take() {
+            match keychain::store_secret(TOKEN_SECRET, token).await {
+                Ok(_) => {
+                    if let Err(e) = save(&config) {
+                        log::error!("failed to save the storage backend: {e}");
+                    }
+                }
+                Err(e) => log::error!("failed to move the pinning token to the keychain: {e}"),
+            }
+        }
+    }
+    disk_connected(&config.active).map_err(|e| Error::OtherWithContext(e.to_string()))?;
+    let path = config.active.data_path(&config.id);
+    if let Err(e) = fs::create_dir_all(&path) {
+        log::error!("failed to create {path:?}: {e}");
+    }
+    Ok(path)
+}
+
+fn disk_connected(backend: &StorageBackend) -> io::Result<()> {
+    match backend {
+        StorageBackend::ExternalDisk { path } if !path.is_dir() => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("the storage disk at {path:?} is not connected"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// copies everything but `keep` into a directory next to `to`, which is renamed once all of it was
+// copied. the originals are only removed after that. `to` must not exist yet, nothing is merged
+fn migrate(from: &Path, to: &Path, keep: &[&str]) -> io::Result<()> {
+    if from == to || !from.exists() {
+        return Ok(());
+    }
+    if to.exists() && fs::read_dir(to)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{to:?} already contains data"),
+        ));
+    }
+    let mut partial = to.as_os_str().to_owned();
+    partial.push(PARTIAL_SUFFIX);
+    let partial = PathBuf::from(partial);
+    if partial.exists() {
+        fs::remove_dir_all(&partial)?;
+    }
+    let copied = copy_except(from, &partial, keep).and_then(|moved| {
+        if to.exists() {
+            fs::remove_dir(to)?;
+        }
+        fs::rename(&partial, to)?;
+        Ok(moved)
+    });
+    let moved = match copied {
+        Ok(moved) => moved,
+        Err(e) => {
+            if let Err(e) = fs::remove_dir_all(&partial) {
+                log::warn!("failed to remove the partial copy {partial:?}: {e}");
+            }
+            return Err(e);
+        }
+    };
+    for path in moved {
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if let Err(e) = removed {
+            log::warn!("failed to remove {path:?} after moving it: {e}");
+        }
+    }
+    Ok(())
+}
+
+// returns the paths which were copied
+fn copy_except(from: &Path, to: &Path, keep: &[&str]) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(to)?;
+    let mut copied = Vec::new();
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if keep.iter().any(|name| entry.file_name() == **name) {
+            continue;
+        }
+        copy_all(&entry.path(), &to.join(entry.file_name()))?;
+        copied.push(entry.path());
+    }
+    Ok(copied)
+}
+
+fn copy_all(from: &Path, to: &Path) -> io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_all(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// Asks the remote pinning service, if one is used, to pin an uploaded item
+pub fn pin(item: &Item) {
+    let StorageBackend::RemotePinning { endpoint, .. } = load().active else {
+        return;
+    };
+    let Some(cid) = item.get_file().ok().and_then(|file| file.reference()) else {
+        log::warn!("{} has no reference to pin", item.name());
+        return;
+    };
+    let name = item.name();
+    tokio::spawn(async move {
+        match pin_cid(&endpoint, &cid, &name).await {
+            Ok(_) => log::debug!("pinned {name} remotely"),
+            Err(e) => log::error!("failed to pin {name} remotely: {e}"),
+        }
+    });
+}
+
+/// Pins the files which were uploaded before the pinning service was chosen, once
+pub async fn pin_existing(storage: Storage) {
+    let mut config = load();
+    let StorageBackend::RemotePinning { endpoint, .. } = &config.active else {
+        return;
+    };
+    if config.pinned_existing {
+        return;
+    }
+    let mut files = vec![];
+    collect_references(&storage.root_directory(), &mut files);
+    let mut failed = 0;
+    for (name, cid) in files {
+        if let Err(e) = pin_cid(endpoint, &cid, &name).await {
+            log::error!("failed to pin {name} remotely: {e}");
+            failed += 1;
+        }
+    }
+    // tried again on the next start
+    if failed > 0 {
+        return;
+    }
+    config.pinned_existing = true;
+    if let Err(e) = save(&config) {
+        log::error!("failed to save the storage backend: {e}");
+    }
+}
+
+// the names and references of every file
+fn collect_references(directory: &Directory, files: &mut Vec<(String, String)>) {
+    for item in directory.get_items() {
+        if let Ok(file) = item.get_file() {
+            if let Some(cid) = file.reference() {
+                files.push((file.name(), cid));
+            }
+        } else if let Ok(dir) = item.get_directory() {
+            collect_references(&dir, files);
+        }
+    }
+}
+
+async fn pin_cid(endpoint: &str, cid: &str, name: &str) -> anyhow::Result<()> {
+    let token = keychain::load_secret(TOKEN_SECRET)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no token for the pinning service"))?;
+    let client = reqwest::Client::builder().timeout(TIMEOUT).build()?;
+    client
+        .post(format!("{}/pins", endpoint.trim_end_matches('/')))
+        .bearer_auth(token)
+        .json(&serde_json::json!({
+            "cid": cid.trim_start_matches("/ipfs/"),
+            "name": name,
+        }))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn migrates_all_but_kept_files() {
+        let root = std::env::temp_dir().join(format!("uplink-storage-{}", uuid::Uuid::new_v4()));
+        let (from, to) = (root.join("from"), root.join("to"));
+        fs::create_dir_all(from.join("blocks")).unwrap();
+        fs::write(from.join("blocks").join("block"), b"data").unwrap();
+        fs::write(from.join("tesseract.json"), b"keys").unwrap();
+
+        migrate(&from, &to, &["tesseract.json"]).unwrap();
+        assert_eq!(fs::read(to.join("blocks").join("block")).unwrap(), b"data");
+        assert!(!from.join("blocks").exists());
+        assert!(from.join("tesseract.json").exists());
+        assert!(!to.join("tesseract.json").exists());
+        assert!(!root.join("to.partial").exists());
+
+        // nothing is merged into data which is already there
+        fs::write(from.join("other"), b"data").unwrap();
+        assert!(migrate(&from, &to, &["tesseract.json"]).is_err());
+        assert!(from.join("other").exists());
+        assert!(!root.join("to.partial").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
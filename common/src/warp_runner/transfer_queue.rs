@@ -0,0 +1,156 @@
+//! Keeps the number of uploads running at once under the limit of the settings. The others wait
+//! in line, and start in the order they were added unless the user moves one of them up.
+
+use std::{collections::VecDeque, time::Duration};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::state::{
+    configuration::Transfers,
+    data_transfer::{TransferState, TransferStates},
+};
+
+pub static UPLOADS: Lazy<TransferQueue> = Lazy::new(TransferQueue::default);
+
+// how often a waiting transfer checks if it was cancelled
+const CANCEL_CHECK: Duration = Duration::from_millis(500);
+
+pub fn configure(transfers: &Transfers) {
+    UPLOADS.set_max_running(transfers.max_concurrent_uploads as usize);
+}
+
+#[derive(Default)]
+pub struct TransferQueue {
+    inner: Mutex<Queue>,
+    changed: Notify,
+}
+
+#[derive(Default)]
+struct Queue {
+    // 0 for no limit
+    max_running: usize,
+    running: usize,
+    waiting: VecDeque<Uuid>,
+}
+
+impl Queue {
+    fn has_room(&self) -> bool {
+        self.max_running == 0 || self.running < self.max_running
+    }
+}
+
+/// A transfer which left the queue. The next one can start once it is dropped
+pub struct Slot<'a> {
+    queue: &'a TransferQueue,
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        self.queue.inner.lock().running -= 1;
+        self.queue.changed.notify_waiters();
+    }
+}
+
+impl TransferQueue {
+    pub fn set_max_running(&self, max_running: usize) {
+        self.inner.lock().max_running = max_running;
+        self.changed.notify_waiters();
+    }
+
+    /// Waits until it's the turn of the transfer. `None` if it was cancelled in the meantime
+    pub async fn wait_turn(&self, id: Uuid, state: &TransferState) -> Option<Slot<'_>> {
+        self.inner.lock().waiting.push_back(id);
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            // so a change between the check and the wait isn't missed
+            changed.as_mut().enable();
+            if state.matches(TransferStates::Cancel).await {
+                self.remove(id);
+                return None;
+            }
+            if self.try_start(id) {
+                return Some(Slot { queue: self });
+            }
+            tokio::select! {
+                _ = changed => {}
+                _ = tokio::time::sleep(CANCEL_CHECK) => {}
+            }
+        }
+    }
+
+    fn try_start(&self, id: Uuid) -> bool {
+        let mut queue = self.inner.lock();
+        if !queue.has_room() || queue.waiting.front() != Some(&id) {
+            return false;
+        }
+        queue.waiting.pop_front();
+        queue.running += 1;
+        // there may be room for the next one too
+        self.changed.notify_waiters();
+        true
+    }
+
+    fn remove(&self, id: Uuid) {
+        self.inner.lock().waiting.retain(|i| i != &id);
+        self.changed.notify_waiters();
+    }
+
+    /// The transfers which are waiting, in the order they'll start in
+    pub fn waiting(&self) -> Vec<Uuid> {
+        self.inner.lock().waiting.iter().copied().collect()
+    }
+
+    /// Swaps the transfer with the one before it
+    pub fn move_up(&self, id: Uuid) {
+        let mut queue = self.inner.lock();
+        if let Some(idx) = queue.waiting.iter().position(|i| i == &id) {
+            if idx > 0 {
+                queue.waiting.swap(idx - 1, idx);
+            }
+        }
+        drop(queue);
+        self.changed.notify_waiters();
+    }
+
+    /// Moves the transfer to the front, so it starts next
+    pub fn prioritize(&self, id: Uuid) {
+        let mut queue = self.inner.lock();
+        if let Some(idx) = queue.waiting.iter().position(|i| i == &id) {
+            queue.waiting.remove(idx);
+            queue.waiting.push_front(id);
+        }
+        drop(queue);
+        self.changed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn starts_in_order() {
+        let queue = TransferQueue::default();
+        queue.set_max_running(1);
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        queue.inner.lock().waiting.extend([first, second, third]);
+
+        assert!(!queue.try_start(second));
+        assert!(queue.try_start(first));
+        let slot = Slot { queue: &queue };
+        assert!(!queue.try_start(second));
+
+        queue.prioritize(third);
+        assert_eq!(queue.waiting(), vec![third, second]);
+        drop(slot);
+        assert!(!queue.try_start(second));
+        assert!(queue.try_start(third));
+
+        queue.set_max_running(0);
+        assert!(queue.try_start(second));
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use derive_more::Display;
 use futures::{stream::FuturesOrdered, FutureExt, StreamExt};
 use uuid::Uuid;
@@ -12,7 +13,7 @@ use crate::{
     state::{self, pending_message::FileProgression},
     warp_runner::{
         ui_adapter::{convert_raygun_message, did_to_identity},
-        Messaging,
+        GroupUpdate, Messaging,
     },
 };
 
@@ -46,7 +47,12 @@ pub enum MessageEvent {
     #[display(fmt = "MessageUnpinned")]
     MessageUnpinned { message: warp::raygun::Message },
     #[display(fmt = "MessageReactionAdded")]
-    MessageReactionAdded { message: warp::raygun::Message },
+    MessageReactionAdded {
+        message: warp::raygun::Message,
+        // who reacted and with which emoji
+        reactor: DID,
+        emoji: String,
+    },
     #[display(fmt = "MessageReactionRemoved")]
     MessageReactionRemoved { message: warp::raygun::Message },
     #[display(fmt = "TypingIndicator")]
@@ -68,6 +74,19 @@ pub enum MessageEvent {
         conversation: raygun::Conversation,
         settings: raygun::ConversationSettings,
     },
+    // sent by another member of the group, who is allowed to make the change
+    #[display(fmt = "GroupUpdated")]
+    GroupUpdated {
+        conversation_id: Uuid,
+        update: GroupUpdate,
+    },
+    // another participant read the messages sent up to `up_to`
+    #[display(fmt = "ReadReceipt")]
+    ReadReceipt {
+        conversation_id: Uuid,
+        reader: DID,
+        up_to: DateTime<Utc>,
+    },
     #[display(fmt = "AttachmentProgress")]
     AttachmentProgress {
         progress: FileProgression,
@@ -116,9 +135,12 @@ pub async fn convert_message_event(
         MessageEventKind::MessageReactionAdded {
             conversation_id,
             message_id,
-            ..
+            did_key,
+            reaction,
         } => MessageEvent::MessageReactionAdded {
             message: messaging.get_message(conversation_id, message_id).await?,
+            reactor: did_key,
+            emoji: reaction,
         },
         MessageEventKind::MessageReactionRemoved {
             conversation_id,
@@ -205,6 +227,7 @@ async fn fetch_latest(messaging: &mut Messaging, conv_id: Uuid) -> Option<Messag
     let mut messages: Vec<_> = FuturesOrdered::from_iter(
         messages
             .iter()
+            .filter(|message| super::is_shown(message))
             .map(|message| convert_raygun_message(messaging, message).boxed()),
     )
     .collect()
@@ -33,7 +33,10 @@ use warp::{
 
 use tracing::log;
 
-use super::{FetchMessagesConfig, FetchMessagesResponse};
+use super::{
+    group_invites, message_store, read_receipts::ReadReceipt, FetchMessagesConfig,
+    FetchMessagesResponse, GroupUpdate,
+};
 
 /// the UI needs additional information for message replies, namely the text of the message being replied to.
 /// fetch that before sending the message to the UI.
@@ -82,6 +85,14 @@ pub struct ChatAdapter {
     pub identities: HashSet<state::identity::Identity>,
 }
 
+/// join requests, changes of the roles of a group and read receipts are handled by the warp runner. they aren't shown
+/// in the chat or the sidebar
+pub fn is_shown(msg: &raygun::Message) -> bool {
+    group_invites::join_request_code(msg).is_none()
+        && GroupUpdate::from_message(msg).is_none()
+        && ReadReceipt::from_message(msg).is_none()
+}
+
 /// if a raygun::Message is in reply to another message, attempt to fetch part of the message text
 pub async fn convert_raygun_message(
     messaging: &super::Messaging,
@@ -268,11 +279,33 @@ pub async fn fetch_pinned_messages_from_chat(
     Ok(messages)
 }
 
+/// Returns the messages in `range`, ordered from oldest to newest, along with the total number of messages.
+/// Used to walk through a whole conversation without loading it into memory at once.
+pub async fn fetch_message_page(
+    conv_id: Uuid,
+    messaging: &mut super::Messaging,
+    range: Range<usize>,
+) -> Result<(Vec<raygun::Message>, usize), Error> {
+    let total_messages = messaging.get_message_count(conv_id).await?;
+    let range = range.start.min(total_messages)..range.end.min(total_messages);
+    if range.is_empty() {
+        return Ok((vec![], total_messages));
+    }
+    let messages = messaging
+        .get_messages(conv_id, MessageOptions::default().set_range(range))
+        .await
+        .and_then(Vec::<_>::try_from)?;
+    Ok((messages, total_messages))
+}
+
 pub async fn fetch_messages2(
     conv_id: Uuid,
     messaging: &mut super::Messaging,
     config: FetchMessagesConfig,
 ) -> Result<FetchMessagesResponse, Error> {
+    if let Some(response) = message_store::fetch(conv_id, config).await {
+        return Ok(response);
+    }
     let total_messages = messaging.get_message_count(conv_id).await?;
 
     let message_options = match config {
@@ -300,12 +333,16 @@ pub async fn fetch_messages2(
     )
     .collect()
     .await;
+    // the conversation is read from the store once `message_store::backfill` copied all of it
+    message_store::save(&messages);
 
     if matches!(config, FetchMessagesConfig::Earlier { .. }) {
         messages = messages.drain(..).rev().collect();
     }
 
+    // counted before the hidden messages are dropped, or paging would stop at a page full of them
     let has_more = messages.len() >= config.get_limit();
+    messages.retain(|message| is_shown(&message.inner));
 
     let mut most_recent = messaging
         .get_messages(
@@ -323,10 +360,76 @@ pub async fn fetch_messages2(
     })
 }
 
+/// fetches messages for a conversation along with the duplicate conversations which were merged into it.
+/// the results are combined by date and trimmed to the requested limit, so the UI sees a single thread.
+pub async fn fetch_merged_messages(
+    conv_id: Uuid,
+    merged: &[Uuid],
+    messaging: &mut super::Messaging,
+    config: FetchMessagesConfig,
+) -> Result<FetchMessagesResponse, Error> {
+    let mut response = fetch_messages2(conv_id, messaging, config).await?;
+    for id in merged {
+        match fetch_messages2(*id, messaging, config).await {
+            Ok(mut r) => {
+                response.has_more |= r.has_more;
+                response.messages.append(&mut r.messages);
+            }
+            Err(e) => {
+                log::error!("failed to fetch messages for merged conversation {id}: {e}");
+            }
+        }
+    }
+
+    response.messages.sort_by_key(|m| m.inner.date());
+    let limit = config.get_limit();
+    if response.messages.len() > limit {
+        let extra = response.messages.len() - limit;
+        match config {
+            // keep the messages closest to the start date
+            FetchMessagesConfig::Later { .. } => response.messages.truncate(limit),
+            _ => {
+                response.messages.drain(..extra);
+            }
+        }
+        response.has_more = true;
+    }
+    // most_recent still refers to conv_id. new messages are only ever sent to the conversation which was kept.
+    Ok(response)
+}
+
 pub async fn conversation_to_chat(
     conv: &Conversation,
     messaging: &super::Messaging,
 ) -> Result<chats::Chat, Error> {
+    let stored = match message_store::reconcile(conv.id(), messaging).await {
+        true => message_store::preview(conv.id()).await,
+        false => None,
+    };
+    let (messages, pinned_messages) = match stored {
+        Some(preview) => preview,
+        None => fetch_chat_preview(conv, messaging).await?,
+    };
+
+    // let has_more_messages = total_messages > to_take;
+    let chat = chats::Chat::new(
+        conv.id(),
+        HashSet::from_iter(conv.recipients()),
+        conv.settings(),
+        conv.name(),
+        conv.creator(),
+        messages,
+        pinned_messages,
+    );
+    // chat.has_more_messages = has_more_messages;
+    Ok(chat)
+}
+
+// the messages for the sidebar and the pinned messages, from RayGun
+async fn fetch_chat_preview(
+    conv: &Conversation,
+    messaging: &super::Messaging,
+) -> Result<(VecDeque<Message>, Vec<raygun::Message>), Error> {
     let total_messages = messaging.get_message_count(conv.id()).await?;
     // only want 1 message - for the sidebar
     let to_take = std::cmp::min(total_messages, 1);
@@ -343,6 +446,7 @@ pub async fn conversation_to_chat(
     let messages: VecDeque<_> = FuturesOrdered::from_iter(
         messages
             .iter()
+            .filter(|message| is_shown(message))
             .map(|message| convert_raygun_message(messaging, message).boxed()),
     )
     .collect()
@@ -360,18 +464,7 @@ pub async fn conversation_to_chat(
         .await
         .and_then(Vec::<_>::try_from)?;
 
-    // let has_more_messages = total_messages > to_take;
-    let chat = chats::Chat::new(
-        conv.id(),
-        HashSet::from_iter(conv.recipients()),
-        conv.settings(),
-        conv.name(),
-        conv.creator(),
-        messages,
-        pinned_messages,
-    );
-    // chat.has_more_messages = has_more_messages;
-    Ok(chat)
+    Ok((messages, pinned_messages))
 }
 
 pub async fn init_conversation(
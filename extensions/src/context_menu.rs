@@ -0,0 +1,80 @@
+//! Items extensions add to the context menus of Uplink. Each enabled extension is asked for its
+//! items with `Extension::context_menu_items` when a menu is shown for something new, and told
+//! which one the user picked with `Extension::on_context_menu_item`. Extensions are only told about
+//! messages, conversations and friends if they were granted the permission to read them.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::conversations::Permission;
+
+/// What a context menu was opened on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MenuContext {
+    File {
+        id: Uuid,
+        name: String,
+    },
+    Folder {
+        id: Uuid,
+        name: String,
+    },
+    Message {
+        conversation_id: Uuid,
+        message_id: Uuid,
+    },
+    Friend {
+        did: String,
+        username: String,
+    },
+    Conversation {
+        id: Uuid,
+    },
+}
+
+impl MenuContext {
+    /// what an extension has to be granted to add items to the menu
+    pub fn permission(&self) -> Option<Permission> {
+        match self {
+            MenuContext::Message { .. } => Some(Permission::ReadMessages),
+            MenuContext::Conversation { .. } | MenuContext::Friend { .. } => {
+                Some(Permission::ReadConversations)
+            }
+            MenuContext::File { .. } | MenuContext::Folder { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MenuItem {
+    // passed back to `Extension::on_context_menu_item`. unique within the extension
+    pub id: String,
+    pub text: String,
+    // shown in red, for items which delete something
+    pub danger: bool,
+    // items with children open a submenu instead of being picked
+    pub children: Vec<MenuItem>,
+}
+
+impl MenuItem {
+    pub fn new(id: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn submenu(text: impl Into<String>, children: Vec<MenuItem>) -> Self {
+        Self {
+            text: text.into(),
+            children,
+            ..Default::default()
+        }
+    }
+
+    pub fn danger(mut self) -> Self {
+        self.danger = true;
+        self
+    }
+}
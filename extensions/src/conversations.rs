@@ -0,0 +1,140 @@
+//! Read-only access to the conversations of the user, for extensions like analytics or backups.
+//! Extensions list the permissions they need with `Extension::permissions` and the user grants
+//! them per extension in the settings. Uplink renders every extension in its own scope, with a
+//! `ConversationProvider` for the library it was loaded from in the context. Extensions never touch
+//! the provider, the files or the keys behind it.
+
+use std::{fmt, future::Future, ops::Range, pin::Pin, rc::Rc};
+
+use chrono::{DateTime, Utc};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Permission {
+    // the list of conversations, their names and participants
+    ReadConversations,
+    // the messages of every conversation, including the names of attachments
+    ReadMessages,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 2] = [Permission::ReadConversations, Permission::ReadMessages];
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversationInfo {
+    pub id: Uuid,
+    // None for direct conversations without a local title
+    pub name: Option<String>,
+    pub is_group: bool,
+    // (DID, username) of everyone in the conversation, including the user
+    pub participants: Vec<(String, String)>,
+    pub unreads: u32,
+    pub favorite: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageInfo {
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    // the DID of the sender
+    pub sender: String,
+    pub date: DateTime<Utc>,
+    pub edited: bool,
+    pub lines: Vec<String>,
+    // file names only
+    pub attachments: Vec<String>,
+    pub replied_to: Option<Uuid>,
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    PermissionDenied(Permission),
+    ConversationNotFound(Uuid),
+    Failed(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::PermissionDenied(permission) => {
+                write!(f, "permission {permission:?} was not granted")
+            }
+            ApiError::ConversationNotFound(id) => write!(f, "conversation {id} not found"),
+            ApiError::Failed(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Implemented by Uplink, once for every extension. The permissions are checked by `ConversationApi`
+/// before calling it.
+pub trait ConversationProvider {
+    fn is_granted(&self, permission: Permission) -> bool;
+    fn conversations(&self) -> Vec<ConversationInfo>;
+    /// the messages in `range` (oldest first) and the total number of messages in the conversation
+    fn messages(
+        &self,
+        conversation_id: Uuid,
+        range: Range<usize>,
+    ) -> BoxFuture<Result<(Vec<MessageInfo>, usize), ApiError>>;
+}
+
+/// Put into the context of an extension by Uplink. The provider can't be taken out of it, so
+/// extensions can only reach it through `ConversationApi`.
+#[derive(Clone)]
+pub struct ProviderHandle(Rc<dyn ConversationProvider>);
+
+impl ProviderHandle {
+    pub fn new(provider: Rc<dyn ConversationProvider>) -> Self {
+        Self(provider)
+    }
+}
+
+/// What extensions use to read conversations. Every call fails with `ApiError::PermissionDenied`
+/// until the user granted the needed permission to the extension.
+#[derive(Clone)]
+pub struct ConversationApi {
+    provider: Rc<dyn ConversationProvider>,
+}
+
+impl ConversationApi {
+    /// None if Uplink doesn't provide the API where the extension is rendered.
+    pub fn get(cx: &ScopeState) -> Option<Self> {
+        let ProviderHandle(provider) = cx.consume_context::<ProviderHandle>()?;
+        Some(Self { provider })
+    }
+
+    pub fn is_granted(&self, permission: Permission) -> bool {
+        self.provider.is_granted(permission)
+    }
+
+    pub fn conversations(&self) -> Result<Vec<ConversationInfo>, ApiError> {
+        self.check(Permission::ReadConversations)?;
+        Ok(self.provider.conversations())
+    }
+
+    pub async fn messages(
+        &self,
+        conversation_id: Uuid,
+        range: Range<usize>,
+    ) -> Result<(Vec<MessageInfo>, usize), ApiError> {
+        self.check(Permission::ReadMessages)?;
+        self.provider.messages(conversation_id, range).await
+    }
+
+    fn check(&self, permission: Permission) -> Result<(), ApiError> {
+        if self.is_granted(permission) {
+            Ok(())
+        } else {
+            Err(ApiError::PermissionDenied(permission))
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! What is passed between Uplink and the libraries of the extensions. Only C types cross the
+//! boundary: lists are a pointer and a length, and structured data is JSON in a C string. A string
+//! returned by an extension is freed by the `free_string` of that extension.
+
+use std::ffi::{c_char, CStr, CString};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::conversations::Permission;
+
+/// The permissions of an extension, they live as long as its library is loaded
+#[repr(C)]
+pub struct PermissionList {
+    ptr: *const Permission,
+    len: usize,
+}
+
+impl PermissionList {
+    pub fn new(permissions: &'static [Permission]) -> Self {
+        Self {
+            ptr: permissions.as_ptr(),
+            len: permissions.len(),
+        }
+    }
+
+    /// # Safety
+    /// the list must come from `new` in a library which stays loaded for `'a`
+    pub unsafe fn as_slice<'a>(&self) -> &'a [Permission] {
+        if self.ptr.is_null() {
+            return &[];
+        }
+        std::slice::from_raw_parts(self.ptr, self.len)
+    }
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> Option<CString> {
+    serde_json::to_string(value)
+        .ok()
+        .and_then(|json| CString::new(json).ok())
+}
+
+/// # Safety
+/// `json` must be null or point to a C string which stays valid during the call
+pub unsafe fn from_json<T: DeserializeOwned>(json: *const c_char) -> Option<T> {
+    if json.is_null() {
+        return None;
+    }
+    serde_json::from_slice(CStr::from_ptr(json).to_bytes()).ok()
+}
+
+/// # Safety
+/// `s` must be null or come from `CString::into_raw` in the same library, and not be used afterwards
+pub unsafe fn free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::context_menu::{MenuContext, MenuItem};
+
+    #[test]
+    fn menu_items_survive_the_boundary() {
+        let items = vec![MenuItem::submenu(
+            "Export",
+            vec![MenuItem::new("pdf", "As PDF").danger()],
+        )];
+        let json = to_json(&items).unwrap().into_raw();
+        let back: Option<Vec<MenuItem>> = unsafe { from_json(json) };
+        unsafe { free_string(json) };
+        assert_eq!(back, Some(items));
+
+        let context = MenuContext::Conversation {
+            id: uuid::Uuid::new_v4(),
+        };
+        let json = to_json(&context).unwrap();
+        assert_eq!(unsafe { from_json(json.as_ptr()) }, Some(context));
+        assert_eq!(unsafe { from_json::<MenuContext>(std::ptr::null()) }, None);
+
+        let permissions = PermissionList::new(&Permission::ALL);
+        assert_eq!(unsafe { permissions.as_slice() }, &Permission::ALL);
+    }
+}
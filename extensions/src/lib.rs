@@ -1,5 +1,15 @@
+use context_menu::{MenuContext, MenuItem};
+use conversations::Permission;
 use dioxus::prelude::*;
-use std::path::PathBuf;
+use ffi::PermissionList;
+use std::{
+    ffi::c_char,
+    path::{Path, PathBuf},
+};
+
+pub mod context_menu;
+pub mod conversations;
+pub mod ffi;
 
 // these help filling in Details
 pub static CARGO_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -23,6 +33,16 @@ pub trait Extension {
     fn cargo_version(&self) -> &'static str {
         CARGO_VERSION
     }
+    // the user is asked to grant these in the settings
+    fn permissions(&self) -> &'static [Permission] {
+        &[]
+    }
+    // items added to the context menu opened on `context`
+    fn context_menu_items(&self, _context: &MenuContext) -> Vec<MenuItem> {
+        vec![]
+    }
+    // called with `MenuItem::id` when the user picks one of the items
+    fn on_context_menu_item(&self, _item: &str, _context: &MenuContext) {}
 }
 
 /// after defining a struct (say as a static variable) and implementing the Extension trait, call this: `export_extension!(<name of struct variable>); `
@@ -59,6 +79,43 @@ macro_rules! export_extension {
         pub extern "C" fn cargo_version() -> &'static str {
             $a.cargo_version()
         }
+
+        #[doc(hidden)]
+        #[no_mangle]
+        pub extern "C" fn permissions() -> $crate::ffi::PermissionList {
+            $crate::ffi::PermissionList::new($a.permissions())
+        }
+
+        // the context and the items are passed as JSON, see `ffi`
+        #[doc(hidden)]
+        #[no_mangle]
+        pub unsafe extern "C" fn context_menu_items(
+            context: *const std::ffi::c_char,
+        ) -> *mut std::ffi::c_char {
+            $crate::ffi::from_json::<$crate::context_menu::MenuContext>(context)
+                .and_then(|context| $crate::ffi::to_json(&$a.context_menu_items(&context)))
+                .map(std::ffi::CString::into_raw)
+                .unwrap_or(std::ptr::null_mut())
+        }
+
+        #[doc(hidden)]
+        #[no_mangle]
+        pub unsafe extern "C" fn on_context_menu_item(
+            item: *const std::ffi::c_char,
+            context: *const std::ffi::c_char,
+        ) {
+            let item = $crate::ffi::from_json::<String>(item);
+            let context = $crate::ffi::from_json::<$crate::context_menu::MenuContext>(context);
+            if let (Some(item), Some(context)) = (item, context) {
+                $a.on_context_menu_item(&item, &context)
+            }
+        }
+
+        #[doc(hidden)]
+        #[no_mangle]
+        pub unsafe extern "C" fn free_string(s: *mut std::ffi::c_char) {
+            $crate::ffi::free_string(s)
+        }
     };
 }
 
@@ -66,28 +123,38 @@ macro_rules! export_extension {
 /// This is used by Uplink to interact with shared libraries
 pub struct UplinkExtension {
     lib: libloading::Library,
+    // identifies the extension, `Meta::name` is chosen by the extension itself
+    path: PathBuf,
     details: Details,
     stylesheet: String,
     rustc_version: &'static str,
     cargo_version: &'static str,
+    permissions: &'static [Permission],
 }
 
 impl UplinkExtension {
     pub fn new(location: PathBuf) -> Result<Self, libloading::Error> {
         unsafe {
-            let lib = libloading::Library::new(location)?;
+            let lib = libloading::Library::new(&location)?;
             let details = lib.get::<unsafe extern "C" fn() -> Details>(b"details\0")?();
             let stylesheet = lib.get::<unsafe extern "C" fn() -> String>(b"stylesheet\0")?();
             let rustc_version =
                 lib.get::<unsafe extern "C" fn() -> &'static str>(b"rustc_version\0")?();
             let cargo_version =
                 lib.get::<unsafe extern "C" fn() -> &'static str>(b"cargo_version\0")?();
+            // missing in extensions built before permissions existed. the list lives as long as `lib`
+            let permissions = lib
+                .get::<unsafe extern "C" fn() -> PermissionList>(b"permissions\0")
+                .map(|f| f().as_slice())
+                .unwrap_or(&[]);
             Ok(Self {
                 lib,
+                path: location,
                 details,
                 stylesheet,
                 rustc_version,
                 cargo_version,
+                permissions,
             })
         }
     }
@@ -95,6 +162,11 @@ impl UplinkExtension {
         &self.details
     }
 
+    /// the library the extension was loaded from. permissions are granted to it
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn stylesheet(&self) -> &str {
         &self.stylesheet
     }
@@ -119,6 +191,52 @@ impl UplinkExtension {
     pub fn cargo_version(&self) -> &'static str {
         self.cargo_version
     }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        self.permissions
+    }
+
+    // extensions built before context menu items existed don't add any
+    pub fn context_menu_items(&self, context: &MenuContext) -> Vec<MenuItem> {
+        let Some(context) = ffi::to_json(context) else {
+            return vec![];
+        };
+        unsafe {
+            let items = self
+                .lib
+                .get::<unsafe extern "C" fn(context: *const c_char) -> *mut c_char>(
+                    b"context_menu_items\0",
+                );
+            let free = self
+                .lib
+                .get::<unsafe extern "C" fn(s: *mut c_char)>(b"free_string\0");
+            match (items, free) {
+                (Ok(items), Ok(free)) => {
+                    let json = items(context.as_ptr());
+                    let items = ffi::from_json(json).unwrap_or_default();
+                    free(json);
+                    items
+                }
+                _ => vec![],
+            }
+        }
+    }
+
+    pub fn on_context_menu_item(&self, item: &str, context: &MenuContext) {
+        let (Some(item), Some(context)) = (ffi::to_json(&item), ffi::to_json(context)) else {
+            return;
+        };
+        unsafe {
+            let res = self
+                .lib
+                .get::<unsafe extern "C" fn(item: *const c_char, context: *const c_char)>(
+                    b"on_context_menu_item\0",
+                );
+            if let Ok(f) = res {
+                f(item.as_ptr(), context.as_ptr())
+            }
+        }
+    }
 }
 
 #[repr(C)]
@@ -141,6 +259,8 @@ pub enum Location {
     Replies,
     Sidebar,
     Settings,
+    // A collapsible widget in the panel next to a conversation. The widget is titled with `Meta::pretty_name`.
+    ConversationPanel,
 }
 
 // Right now IconLaunched is the only supported render mode*. This will evolve over time.
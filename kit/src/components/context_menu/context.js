@@ -1,17 +1,53 @@
-let click = (toggle) => function (ev) {
-  ev.stopPropagation()
-  ev.preventDefault()
+// the items of a menu, without the ones of its submenus
+const menu_items = (menu) =>
+  Array.from(menu.querySelectorAll("button.context-item")).filter(
+    (item) => item.closest(".context-menu") === menu && !item.classList.contains("context-item-disabled")
+  )
+
+const focus_item = (items, index) => {
+  if (items.length === 0) return
+  items[(index + items.length) % items.length].focus()
+}
+
+// submenus open to the left when there's no room for them on the right
+const place_submenu = (submenu) => {
+  submenu.classList.remove("context-submenu-left")
+  const rect = submenu.querySelector(":scope > .context-submenu-items").getBoundingClientRect()
+  if (rect.right > window.innerWidth) {
+    submenu.classList.add("context-submenu-left")
+  }
+}
+
+const open_submenu = (submenu) => {
+  submenu.classList.add("open")
+  place_submenu(submenu)
+  focus_item(menu_items(submenu.querySelector(":scope > .context-submenu-items")), 0)
+}
+
+const close_submenu = (submenu) => {
+  submenu.classList.remove("open")
+  submenu.querySelector(":scope > .context-submenu-trigger").focus()
+}
+
+const hide = (context_menu) => {
+  context_menu.classList.add("hidden")
+  for (const submenu of context_menu.querySelectorAll(".context-submenu.open")) {
+    submenu.classList.remove("open")
+  }
+}
+
+// shows the menu at (x, y), returns false if it was hidden instead
+const show = (toggle, x, y) => {
   // Hide any open context menus
-  const menus = document.getElementsByClassName("context-menu")
+  const menus = document.querySelectorAll(".context-menu:not(.context-submenu-items)")
   // Select the current one
   const context_menu = document.getElementById("UUID-context-menu")
   let hidden = context_menu.classList.contains("hidden");
-  for (var i = 0; i < menus.length; i++) {
-    menus.item(i).classList.add("hidden")
+  for (const menu of menus) {
+    hide(menu)
   }
   if (toggle && !hidden) {
-    context_menu.classList.add("hidden")
-    return
+    return false
   } else {
     // Check if the context menu has any elements in the first place
     // Children can be <pre hidden></pre> so simply checking if children > 0 doesn't work
@@ -23,21 +59,28 @@ let click = (toggle) => function (ev) {
     }
   }
   const { width, height } = context_menu.getBoundingClientRect()
-  let offsetX = ev.pageX
-  let offsetY = ev.pageY
-  let screenWidth = ev.view.innerWidth
-  let screenHeight = ev.view.innerHeight
-  let overFlowY = screenHeight < height + offsetY
-  let overFlowX = screenWidth < width + offsetX
-  let topY = Math.max(5, overFlowY ? offsetY - height : offsetY)
+  let screenWidth = window.innerWidth
+  let screenHeight = window.innerHeight
+  let overFlowY = screenHeight < height + y
+  let overFlowX = screenWidth < width + x
+  let topY = Math.max(5, overFlowY ? y - height : y)
   let minX = 5
   let compose = document.getElementsByClassName("slimbar")[0]
   if (compose) {
     minX = compose.getBoundingClientRect().right
   }
-  let topX = Math.max(minX, overFlowX ? offsetX - width : offsetX)
+  let topX = Math.max(minX, overFlowX ? x - width : x)
   context_menu.style.top = `${topY}px`
   context_menu.style.left = `${topX}px`
+  // so the arrow keys move through the items
+  context_menu.focus({ preventScroll: true })
+  return true
+}
+
+let click = (toggle) => function (ev) {
+  ev.stopPropagation()
+  ev.preventDefault()
+  show(toggle, ev.pageX, ev.pageY)
 }
 
 document.getElementById("UUID").addEventListener(
@@ -54,14 +97,96 @@ if (ON_CLICK) {
   )
 }
 
+// the menu key and Shift+F10 open the menu of the focused element, below it
+document.getElementById("UUID").addEventListener("keydown", (ev) => {
+  if (ev.key !== "ContextMenu" && !(ev.shiftKey && ev.key === "F10")) return
+  ev.stopPropagation()
+  ev.preventDefault()
+  const rect = ev.currentTarget.getBoundingClientRect()
+  if (show(false, rect.left, rect.bottom)) {
+    focus_item(menu_items(document.getElementById("UUID-context-menu")), 0)
+  }
+})
+
+document.getElementById("UUID-context-menu").addEventListener("keydown", (ev) => {
+  const context_menu = document.getElementById("UUID-context-menu")
+  const current = document.activeElement
+  const menu = (current && current.closest(".context-menu")) || context_menu
+  const items = menu_items(menu)
+  const index = items.indexOf(current)
+  const on_trigger = current && current.classList.contains("context-submenu-trigger")
+  switch (ev.key) {
+    case "ArrowDown":
+      focus_item(items, index + 1)
+      break
+    case "ArrowUp":
+      focus_item(items, index < 0 ? -1 : index - 1)
+      break
+    case "Home":
+      focus_item(items, 0)
+      break
+    case "End":
+      focus_item(items, -1)
+      break
+    case "ArrowRight":
+    case "Enter":
+    case " ":
+      // other items are buttons, which are clicked by Enter and Space
+      if (!on_trigger) return
+      open_submenu(current.parentElement)
+      break
+    case "ArrowLeft":
+      if (menu === context_menu) return
+      close_submenu(menu.parentElement)
+      break
+    case "Escape":
+      if (menu === context_menu) {
+        hide(context_menu)
+      } else {
+        close_submenu(menu.parentElement)
+      }
+      break
+    case "Tab":
+      hide(context_menu)
+      return
+    default:
+      return
+  }
+  ev.stopPropagation()
+  ev.preventDefault()
+})
+
+document.getElementById("UUID-context-menu").addEventListener("mouseover", (e) => {
+  const submenu = e.target.closest(".context-submenu")
+  if (submenu && !submenu.classList.contains("open")) {
+    place_submenu(submenu)
+  }
+})
+
 //Hide the context menu only if context items are clicked
 document.getElementById("UUID-context-menu").addEventListener("click", (e) => {
   const context_menu = document.getElementById("UUID-context-menu")
+  // opening a submenu keeps the menu open
+  const trigger = e.target.closest(".context-submenu-trigger")
+  if (trigger) {
+    const submenu = trigger.parentElement
+    if (submenu.classList.contains("open")) {
+      submenu.classList.remove("open")
+    } else {
+      open_submenu(submenu)
+    }
+    return
+  }
+  // picked with the keyboard, there's no position to compare
+  if (e.detail === 0 && e.target.closest(".context-item")) {
+    hide(context_menu)
+    return
+  }
   const ctx_items = context_menu.getElementsByClassName("context-item");
   for (const i of ctx_items) {
     const rect = i.getBoundingClientRect()
     if (e.clientX >= rect.left && e.clientX <= rect.right && e.clientY >= rect.top && e.clientY <= rect.bottom) {
-      context_menu.classList.add("hidden")
+      hide(context_menu)
       break;
     }
   }
@@ -70,9 +195,9 @@ document.getElementById("UUID-context-menu").addEventListener("click", (e) => {
 //Hides the context menu if clicked outside
 document.addEventListener("click", (e) => {
   const context_menu = document.getElementById("UUID-context-menu")
-  if (context_menu != null) {
+  if (context_menu != null && !context_menu.contains(e.target)) {
     const rect = context_menu.getBoundingClientRect()
     if (e.clientX < rect.left || e.clientX > rect.right || e.clientY < rect.top || e.clientY > rect.bottom)
-      context_menu.classList.add("hidden")
+      hide(context_menu)
   }
 })
@@ -93,6 +93,7 @@ pub fn ContextItem<'a>(cx: Scope<'a, ItemProps<'a>>) -> Element<'a> {
                 },
                 button {
                     class: format_args!("{class} {}", if disabled {"context-item-disabled"} else {""}),
+                    role: "menuitem",
                     aria_label: "{aria_label}",
                     onclick: move |e| {
                         if !disabled {
@@ -119,6 +120,45 @@ pub fn ContextItem<'a>(cx: Scope<'a, ItemProps<'a>>) -> Element<'a> {
     }
 }
 
+#[derive(Props)]
+pub struct SubmenuProps<'a> {
+    text: String,
+    #[props(optional)]
+    icon: Option<icons::outline::Shape>,
+    aria_label: Option<String>,
+    children: Element<'a>,
+}
+
+/// An item which opens the items in `children` next to the menu, when hovered or with the right
+/// arrow key
+#[allow(non_snake_case)]
+pub fn ContextSubmenu<'a>(cx: Scope<'a, SubmenuProps<'a>>) -> Element<'a> {
+    let aria_label = cx.props.aria_label.clone().unwrap_or_default();
+
+    cx.render(rsx!(
+        div {
+            class: "context-submenu",
+            button {
+                class: "context-item context-submenu-trigger",
+                role: "menuitem",
+                aria_haspopup: "menu",
+                aria_label: "{aria_label}",
+                cx.props.icon.map(|icon| rsx!(icons::Icon { icon: icon })),
+                div {
+                    class: "context-item-text",
+                    "{cx.props.text}"
+                },
+                icons::Icon { icon: icons::outline::Shape::ChevronRight },
+            },
+            div {
+                class: "context-menu context-submenu-items",
+                role: "menu",
+                &cx.props.children,
+            }
+        }
+    ))
+}
+
 #[derive(PartialEq, Props)]
 pub struct IdentityProps {
     sender_did: DID,
@@ -205,6 +245,8 @@ pub fn ContextMenu<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
             div {
                 id: "{id}-context-menu",
                 class: "context-menu hidden",
+                role: "menu",
+                tabindex: "-1",
                 aria_label: "Context Menu",
                 &cx.props.items,
                 devmode.then(|| rsx!(
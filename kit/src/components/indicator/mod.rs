@@ -77,11 +77,25 @@ pub enum Status {
     #[display(fmt = "do-not-disturb")]
     DoNotDisturb,
 
+    // The user appears offline to others, shown to themselves only
+    #[display(fmt = "invisible")]
+    Invisible,
+
     // The user has an unknown status
     #[display(fmt = "unknown")]
     Unknown,
 }
 
+impl Status {
+    // The status the user picked for themselves. Offline means they are still around, but invisible
+    pub fn own(value: IdentityStatus) -> Self {
+        match value {
+            IdentityStatus::Offline => Self::Invisible,
+            status => status.into(),
+        }
+    }
+}
+
 impl From<identity::IdentityStatus> for Status {
     fn from(value: identity::IdentityStatus) -> Self {
         match value {
@@ -14,7 +14,7 @@ use derive_more::Display;
 use dioxus::prelude::*;
 use futures::StreamExt;
 use once_cell::sync::Lazy;
-use pulldown_cmark::{CodeBlockKind, Options, Tag, TagEnd};
+use pulldown_cmark::{Alignment, CodeBlockKind, Options, Tag, TagEnd};
 use regex::{Captures, Regex, Replacer};
 use uuid::Uuid;
 use warp::error::Error;
@@ -56,12 +56,24 @@ pub enum Order {
     Last,
 }
 
+// delivery state of a message sent by the local user
+#[derive(Eq, PartialEq, Clone, Copy, Display)]
+pub enum Receipt {
+    #[display(fmt = "delivered")]
+    Delivered,
+
+    #[display(fmt = "read")]
+    Read,
+}
+
 #[derive(Eq, PartialEq, Clone)]
 pub struct ReactionAdapter {
     pub emoji: String,
     pub alt: String,
     pub self_reacted: bool,
     pub reaction_count: usize,
+    // the names of who reacted, listed when hovering the reaction
+    pub reacted_by: Vec<String>,
 }
 
 #[derive(Props)]
@@ -122,6 +134,25 @@ pub struct Props<'a> {
 
     is_mention: bool,
 
+    // the message mentions @everyone or @admins
+    #[props(default)]
+    is_room_mention: bool,
+
+    // An optional field that, if set, shows a checkmark for delivered messages and two for read messages.
+    receipt: Option<Receipt>,
+
+    // the message failed to send and waits in the outbox to be sent again
+    #[props(default)]
+    queued: bool,
+
+    // the message is in the outbox but isn't retried anymore, it failed too often
+    #[props(default)]
+    send_failed: bool,
+
+    // the name of the bot which posted the message, see `incoming_webhooks`
+    #[props(default)]
+    bot: Option<String>,
+
     state: &'a UseSharedState<State>,
 
     chat: Uuid,
@@ -185,6 +216,11 @@ pub fn Message<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
     // omitting the class will display the reactions starting from the bottom right corner
     let remote_class = ""; //if is_remote { "remote" } else { "" };
     let reactions_class = format!("message-reactions-container {remote_class}");
+    let queued_class = if cx.props.send_failed {
+        "message-queued failed"
+    } else {
+        "message-queued"
+    };
 
     let has_attachments = cx
         .props
@@ -262,7 +298,11 @@ pub fn Message<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
 
     let loading_class = loading.then_some("loading").unwrap_or_default();
     let remote_class = is_remote.then_some("remote").unwrap_or_default();
-    let mention_class = cx.props.is_mention.then_some("mention").unwrap_or_default();
+    let mention_class = match (cx.props.is_mention, cx.props.is_room_mention) {
+        (_, true) => "mention room-mention",
+        (true, false) => "mention",
+        _ => "",
+    };
     let order_class = order.to_string();
     let msg_pending_class = cx
         .props
@@ -337,6 +377,13 @@ pub fn Message<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                     }
                 )
             ),
+            cx.props.bot.as_ref().map(|name| rsx!(
+                div {
+                    class: "message-bot",
+                    aria_label: "message-bot",
+                    "🤖 {name}"
+                }
+            )),
             (cx.props.with_text.is_some() && !cx.props.editing).then(|| rsx!(
                 ChatText {
                     text: cx.props.with_text.as_ref().cloned().unwrap_or_default(),
@@ -361,6 +408,55 @@ pub fn Message<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
             pending_attachment_list.map(|node| {
                 rsx!(node)
             })
+            cx.props.queued.then(|| rsx!(
+                div {
+                    class: "{queued_class}",
+                    aria_label: "message-queued",
+                    common::icons::Icon {
+                        ..common::icons::IconProps {
+                            class: None,
+                            size: 12,
+                            fill:"currentColor",
+                            icon: Icon::Clock,
+                            disabled: false,
+                            disabled_fill: "#9CA3AF"
+                        },
+                    },
+                    if cx.props.send_failed {
+                        get_local_text("messages.queued-failed")
+                    } else {
+                        get_local_text("messages.queued")
+                    }
+                }
+            )),
+            cx.props.receipt.map(|receipt| rsx!(
+                div {
+                    class: "message-receipt {receipt}",
+                    aria_label: "message-receipt-{receipt}",
+                    common::icons::Icon {
+                        ..common::icons::IconProps {
+                            class: None,
+                            size: 12,
+                            fill:"currentColor",
+                            icon: Icon::Check,
+                            disabled: false,
+                            disabled_fill: "#9CA3AF"
+                        },
+                    },
+                    (receipt == Receipt::Read).then(|| rsx!(
+                        common::icons::Icon {
+                            ..common::icons::IconProps {
+                                class: None,
+                                size: 12,
+                                fill:"currentColor",
+                                icon: Icon::Check,
+                                disabled: false,
+                                disabled_fill: "#9CA3AF"
+                            },
+                        },
+                    ))
+                }
+            ))
         },
         div {
             class: "{reactions_class}",
@@ -385,10 +481,26 @@ pub fn Message<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                                 } else { "remote" }
                             )
                         },
+                        tabindex: "0",
                         onclick: move |_| {
                             cx.props.on_click_reaction.call(emoji.clone());
                         },
-                        "{emoji} {reaction_count}"
+                        "{emoji} {reaction_count}",
+                        (!reaction.reacted_by.is_empty()).then(|| rsx!(div {
+                            class: "reaction-popover",
+                            aria_label: "reaction-popover",
+                            role: "tooltip",
+                            // only the reaction itself toggles it
+                            onclick: move |e| e.stop_propagation(),
+                            span {
+                                class: "reaction-popover-emoji",
+                                "{emoji}"
+                            },
+                            reaction.reacted_by.iter().map(|name| rsx!(p {
+                                class: "reaction-popover-user",
+                                "{name}"
+                            }))
+                        }))
                     }
                 )
             })
@@ -463,6 +575,8 @@ pub fn ChatText<'a>(cx: Scope<'a, ChatMessageProps<'a>>) -> Element<'a> {
             p {
                 class: text_type_class,
                 aria_label: "message-text-{cx.props.text}",
+                // each message follows the direction of its own text, whatever the app language is
+                dir: "auto",
                 dangerous_inner_html: "{formatted_text}",
             },
             links.first().and_then(|l| cx.render(rsx!(
@@ -484,9 +598,8 @@ pub fn format_text(
     // warning: this will probably break markdown regarding block quotes. still seems like an improvement.
     let safe_text = HTML_ESCAPES
         .iter()
-        .fold(Cow::from(text), |s, (from, to)| s.replace(*from, to).into())
-        .replace('\n', "&nbsp;&nbsp;\n");
-    let mut text = safe_text;
+        .fold(Cow::from(text), |s, (from, to)| s.replace(*from, to).into());
+    let mut text = escape_line_breaks(&safe_text);
     // We want to do this after we escape html tags
     if let Some((state, chat, visual)) = data {
         if let Some(participants) = state
@@ -513,6 +626,38 @@ pub fn format_text(
     }
 }
 
+// markdown tables can't have anything after the closing pipe, so their rows keep a plain line break
+fn escape_line_breaks(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut lines = text.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        result.push_str(line);
+        if lines.peek().is_some() {
+            let trimmed = line.trim();
+            if !(trimmed.len() > 1 && trimmed.starts_with('|') && trimmed.ends_with('|')) {
+                result.push_str("&nbsp;&nbsp;");
+            }
+            result.push('\n');
+        }
+    }
+    result
+}
+
+// the copy button of a code block is added by prism, which reads its labels from these attributes
+fn code_block_start(lang: &str) -> String {
+    let lang: String = lang
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '+' | '#'))
+        .collect();
+    format!(
+        "<pre data-prismjs-copy=\"{}\" data-prismjs-copy-success=\"{}\" data-prismjs-copy-error=\"{}\"><code class=\"language-{}\">",
+        get_local_text("messages.copy-code"),
+        get_local_text("messages.code-copied"),
+        get_local_text("messages.copy-code-failed"),
+        if lang.is_empty() { "none" } else { &lang }
+    )
+}
+
 fn stack_processor(stack: &str, unescape_html: bool, emojis: bool) -> &str {
     if unescape_html {
         if let Some((esc, _)) = HTML_ESCAPES.iter().find(|(_, s)| stack.eq(*s)) {
@@ -605,6 +750,7 @@ fn markdown(text: &str, emojis: bool) -> String {
 
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
 
     let text = MARKDOWN_PROCESSOR_REGEX.replace_all(txt, RegexReplacer);
 
@@ -612,6 +758,9 @@ fn markdown(text: &str, emojis: bool) -> String {
     let mut in_paragraph = false;
     let mut in_code_block = false;
     let (mut skipping, mut in_link) = (false, false);
+    // alignment of the columns of the current table, the header and the index of the current cell
+    let mut table_alignments = vec![];
+    let (mut in_table_head, mut table_cell) = (false, 0);
 
     let parser = pulldown_cmark::Parser::new_ext(&text, options);
     for (event, range) in parser.into_offset_iter() {
@@ -638,7 +787,60 @@ fn markdown(text: &str, emojis: bool) -> String {
                     std::iter::once(pulldown_cmark::Event::Code(txt)),
                 )
             }
-            pulldown_cmark::Event::End(TagEnd::CodeBlock) => {}
+            pulldown_cmark::Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
+                in_code_block = true;
+                html_output.push_str(&code_block_start(&lang));
+            }
+            pulldown_cmark::Event::End(TagEnd::CodeBlock) => {
+                if in_code_block {
+                    in_code_block = false;
+                    html_output.push_str("</code></pre>\n");
+                }
+            }
+            // the html writer of pulldown_cmark keeps track of the table itself, which doesn't work when
+            // it is given one event at a time
+            pulldown_cmark::Event::Start(Tag::Table(alignments)) => {
+                table_alignments = alignments;
+                html_output.push_str("<div class=\"markdown-table\"><table>");
+            }
+            pulldown_cmark::Event::End(TagEnd::Table) => {
+                html_output.push_str("</tbody></table></div>\n");
+            }
+            pulldown_cmark::Event::Start(Tag::TableHead) => {
+                in_table_head = true;
+                table_cell = 0;
+                html_output.push_str("<thead><tr>");
+            }
+            pulldown_cmark::Event::End(TagEnd::TableHead) => {
+                in_table_head = false;
+                html_output.push_str("</tr></thead><tbody>");
+            }
+            pulldown_cmark::Event::Start(Tag::TableRow) => {
+                table_cell = 0;
+                html_output.push_str("<tr>");
+            }
+            pulldown_cmark::Event::End(TagEnd::TableRow) => {
+                html_output.push_str("</tr>");
+            }
+            pulldown_cmark::Event::Start(Tag::TableCell) => {
+                let tag = if in_table_head { "th" } else { "td" };
+                match table_alignments.get(table_cell) {
+                    Some(Alignment::Left) => {
+                        html_output.push_str(&format!("<{tag} style=\"text-align: left\">"))
+                    }
+                    Some(Alignment::Center) => {
+                        html_output.push_str(&format!("<{tag} style=\"text-align: center\">"))
+                    }
+                    Some(Alignment::Right) => {
+                        html_output.push_str(&format!("<{tag} style=\"text-align: right\">"))
+                    }
+                    _ => html_output.push_str(&format!("<{tag}>")),
+                }
+            }
+            pulldown_cmark::Event::End(TagEnd::TableCell) => {
+                table_cell += 1;
+                html_output.push_str(if in_table_head { "</th>" } else { "</td>" });
+            }
             pulldown_cmark::Event::SoftBreak => {
                 if in_paragraph {
                     html_output.push_str("</p>\n<p>");
@@ -679,18 +881,7 @@ fn markdown(text: &str, emojis: bool) -> String {
                     );
                 }
             }
-            event => {
-                match event {
-                    pulldown_cmark::Event::Start(Tag::CodeBlock(_)) => {
-                        in_code_block = true;
-                    }
-                    pulldown_cmark::Event::End(TagEnd::CodeBlock) => {
-                        in_code_block = false;
-                    }
-                    _ => {}
-                }
-                pulldown_cmark::html::push_html(&mut html_output, std::iter::once(event))
-            }
+            event => pulldown_cmark::html::push_html(&mut html_output, std::iter::once(event)),
         }
     }
     html_output.push('\n');
@@ -878,7 +1069,7 @@ pub fn IdentityMessage(cx: Scope<IdentityMessageProps>) -> Element {
                                     format!("{}", identity.username())
                                 }
                             }
-                            identity.status_message().and_then(|s|{
+                            identity.custom_status().map(|s| s.shown()).and_then(|s|{
                                 cx.render(rsx!(
                                     div {
                                         id: "profile-status",
@@ -991,6 +1182,23 @@ mod tests2 {
         assert_eq!(&format_text(input, true, true, None), expected);
         assert_eq!(&format_text(input, false, true, None), expected);
     }
+
+    #[test]
+    fn test_format_table() {
+        let input = "| a | b |\n|---|:-:|\n| 1 | 2 |";
+        let output = format_text(input, true, false, None);
+        assert!(output
+            .contains("<thead><tr><th>a</th><th style=\"text-align: center\">b</th></tr></thead>"));
+        assert!(output.contains("<tr><td>1</td><td style=\"text-align: center\">2</td></tr>"));
+    }
+
+    #[test]
+    fn test_format_code_block() {
+        let input = "```rust\nlet a = 1;\n```";
+        let output = format_text(input, true, false, None);
+        assert!(output.contains("<code class=\"language-rust\">"));
+        assert!(output.contains("</code></pre>"));
+    }
 }
 
 #[cfg(test)]
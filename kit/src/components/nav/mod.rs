@@ -111,6 +111,7 @@ pub fn Nav<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
     cx.render(rsx!(
         div {
             aria_label: "button-nav",
+            role: "navigation",
             class: {
                 format_args!("nav disable-select {}", if bubble { "bubble" } else { "" })
             },
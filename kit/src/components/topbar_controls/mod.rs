@@ -3,8 +3,15 @@ use common::{icons::outline::Shape as Icon, state::State};
 use dioxus::prelude::*;
 use dioxus_desktop::{use_window, LogicalSize};
 
+#[derive(Props)]
+pub struct Props<'a> {
+    // replaces closing the window, for example to finish some work first
+    #[props(optional)]
+    onclose: Option<EventHandler<'a, ()>>,
+}
+
 #[allow(non_snake_case)]
-pub fn TopbarControls(cx: Scope) -> Element {
+pub fn TopbarControls<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
     let state = use_ref(cx, State::load);
     let desktop = use_window(cx);
     let first_resize = use_ref(cx, || true);
@@ -39,8 +46,9 @@ pub fn TopbarControls(cx: Scope) -> Element {
                     aria_label: "close-button".into(),
                     icon: Icon::XMark,
                     appearance: Appearance::Transparent,
-                    onpress: move |_| {
-                        desktop.close();
+                    onpress: move |_| match &cx.props.onclose {
+                        Some(f) => f.call(()),
+                        None => desktop.close(),
                     }
                 },
             }
@@ -1,3 +1,4 @@
+use common::utils::avatars::{self, Avatar, AvatarSize};
 use dioxus::{
     core::Event,
     events::{MouseData, MouseEvent},
@@ -13,8 +14,9 @@ use crate::{
 pub struct Props<'a> {
     #[props(optional)]
     loading: Option<bool>,
-    #[props(optional)]
-    image: Option<String>,
+    // pass `Identity::avatar`, which was hashed when the picture was loaded
+    #[props(optional, into)]
+    image: Option<Avatar>,
     #[props(optional)]
     typing: Option<bool>,
     #[props(optional)]
@@ -25,9 +27,12 @@ pub struct Props<'a> {
     oncontextmenu: Option<EventHandler<'a, MouseEvent>>,
     status: Option<Status>,
     platform: Platform,
+    // the picture is downscaled for it, `Small` by default
+    #[props(optional)]
+    size: Option<AvatarSize>,
 }
 
-pub fn get_image(cx: &Scope<Props>) -> String {
+pub fn get_image(cx: &Scope<Props>) -> Avatar {
     cx.props.image.clone().unwrap_or_default()
 }
 
@@ -48,7 +53,21 @@ pub fn emit_context(cx: &Scope<Props>, e: Event<MouseData>) {
 
 #[allow(non_snake_case)]
 pub fn UserImage<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
-    let image_data: String = get_image(&cx);
+    let avatar = get_image(&cx);
+    let size = cx.props.size.unwrap_or_default();
+    // blank until the picture is downscaled, which only takes a while the first time it is shown
+    let key = avatar.key(size);
+    let loaded = use_future(cx, (&key,), move |(key,)| async move {
+        (key, avatars::load(avatar, size).await)
+    });
+    let image_data = avatars::cached(&key)
+        .or_else(|| {
+            loaded
+                .value()
+                .filter(|(loaded_key, _)| *loaded_key == key)
+                .map(|(_, url)| url.clone())
+        })
+        .unwrap_or_default();
     let status = cx.props.status;
     let platform = cx.props.platform;
     let typing = cx.props.typing.unwrap_or_default();
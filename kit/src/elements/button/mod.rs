@@ -18,6 +18,8 @@ pub struct Props<'a> {
     small: Option<bool>,
     with_title: Option<bool>,
     with_progress: Option<i8>,
+    // makes it a toggle button, read as pressed or not
+    pressed: Option<bool>,
 }
 
 /// Generates the appearance for the button.
@@ -114,6 +116,7 @@ pub fn Button<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                 aria_label: "{aria_label}",
                 name: "{aria_label}",
                 title: "{title}",
+                aria_pressed: cx.props.pressed.map(|pressed| pressed.to_string()),
                 disabled: if disabled { "true" } else { "false" },
                 class: "{button_class}",
                 // Optionally pass through click events.
@@ -25,6 +25,14 @@ pub struct Props<'a> {
     onrename: Option<EventHandler<'a, (String, Code)>>,
     #[props(optional)]
     onpress: Option<EventHandler<'a>>,
+    // a click selects the file and a double click opens it, instead of a click opening it
+    #[props(optional)]
+    open_on_double_click: Option<bool>,
+    // called with the modifiers of the click, for multi-select
+    #[props(optional)]
+    onselect: Option<EventHandler<'a, Modifiers>>,
+    #[props(optional)]
+    selected: Option<bool>,
     #[props(optional)]
     loading: Option<bool>,
 }
@@ -45,6 +53,12 @@ pub fn emit_press(cx: &Scope<Props>) {
     }
 }
 
+pub fn emit_select(cx: &Scope<Props>, modifiers: Modifiers) {
+    if let Some(f) = cx.props.onselect.as_ref() {
+        f.call(modifiers)
+    }
+}
+
 pub fn get_file_extension(file_name: String) -> String {
     // don't append a '.' to a file name if it has no extension
     std::path::Path::new(&file_name)
@@ -66,6 +80,8 @@ pub fn File<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
     let disabled = cx.props.disabled.unwrap_or_default();
     let thumbnail = cx.props.thumbnail.clone().unwrap_or_default();
     let is_video = is_video(&cx.props.text.clone());
+    let open_on_double_click = cx.props.open_on_double_click.unwrap_or_default();
+    let selected = cx.props.selected.unwrap_or_default();
 
     let loading = cx.props.loading.unwrap_or_default();
 
@@ -75,11 +91,19 @@ pub fn File<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
         cx.render(rsx!(
             div {
                 class: {
-                    format_args!("file {}", if disabled { "disabled" } else { "" })
+                    format_args!("file {} {}", if disabled { "disabled" } else { "" }, if selected { "selected" } else { "" })
                 },
                 aria_label: "{aria_label}",
+                aria_selected: "{selected}",
                 onclick: move |mouse_event_data| {
-                    if mouse_event_data.modifiers() != Modifiers::CONTROL {
+                    if open_on_double_click {
+                        emit_select(&cx, mouse_event_data.modifiers());
+                    } else if mouse_event_data.modifiers() != Modifiers::CONTROL {
+                        emit_press(&cx);
+                    }
+                },
+                ondblclick: move |_| {
+                    if open_on_double_click {
                         emit_press(&cx);
                     }
                 },
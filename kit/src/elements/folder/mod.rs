@@ -1,7 +1,9 @@
 use dioxus::prelude::*;
 use dioxus_elements::input_data::keyboard_types::Code;
+use dioxus_html::input_data::keyboard_types::Modifiers;
 
 use crate::elements::input::{Input, Options, Size, SpecialCharsAction, Validation};
+use crate::elements::loader::Loader;
 
 use common::icons::outline::Shape as Icon;
 use common::icons::Icon as IconElement;
@@ -22,8 +24,22 @@ pub struct Props<'a> {
     onrename: Option<EventHandler<'a, (String, Code)>>,
     #[props(optional)]
     onpress: Option<EventHandler<'a>>,
+    // a click selects the folder and a double click opens it, instead of a click opening it
+    #[props(optional)]
+    open_on_double_click: Option<bool>,
+    // called with the modifiers of the click, for multi-select
+    #[props(optional)]
+    onselect: Option<EventHandler<'a, Modifiers>>,
+    #[props(optional)]
+    selected: Option<bool>,
     #[props(optional)]
     loading: Option<bool>,
+    // shown below the name
+    #[props(optional)]
+    size: Option<String>,
+    // shows a spinner instead of the size
+    #[props(optional)]
+    size_loading: Option<bool>,
 }
 
 pub fn get_aria_label(cx: &Scope<Props>) -> String {
@@ -42,6 +58,12 @@ pub fn emit_press(cx: &Scope<Props>) {
     }
 }
 
+pub fn emit_select(cx: &Scope<Props>, modifiers: Modifiers) {
+    if let Some(f) = cx.props.onselect.as_ref() {
+        f.call(modifiers)
+    }
+}
+
 #[allow(non_snake_case)]
 pub fn Folder<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
     let open = cx.props.open.unwrap_or_default();
@@ -53,6 +75,10 @@ pub fn Folder<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
     let disabled = cx.props.disabled.unwrap_or_default();
 
     let loading = cx.props.loading.unwrap_or_default();
+    let size = cx.props.size.clone().unwrap_or_default();
+    let size_loading = cx.props.size_loading.unwrap_or_default();
+    let open_on_double_click = cx.props.open_on_double_click.unwrap_or_default();
+    let selected = cx.props.selected.unwrap_or_default();
 
     if loading {
         cx.render(rsx!(FolderSkeletal {}))
@@ -60,12 +86,24 @@ pub fn Folder<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
         cx.render(rsx!(
             div {
                 class: {
-                    format_args!("folder {}", if disabled { "disabled" } else { "" })
+                    format_args!("folder {} {}", if disabled { "disabled" } else { "" }, if selected { "selected" } else { "" })
                 },
                 aria_label: "{aria_label}",
+                aria_selected: "{selected}",
                 div {
                     class: "icon alignment",
-                    onclick: move |_| emit_press(&cx),
+                    onclick: move |mouse_event_data| {
+                        if open_on_double_click {
+                            emit_select(&cx, mouse_event_data.modifiers());
+                        } else {
+                            emit_press(&cx);
+                        }
+                    },
+                    ondblclick: move |_| {
+                        if open_on_double_click {
+                            emit_press(&cx);
+                        }
+                    },
                     IconElement {
                         icon: icon,
                     },
@@ -106,6 +144,19 @@ pub fn Folder<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                         title: "{&folder_name}",
                         "{folder_name}"
                     }
+                )),
+                (!with_rename && size_loading).then(|| rsx!(
+                    div {
+                        class: "folder-size",
+                        Loader { spinning: true },
+                    }
+                )),
+                (!with_rename && !size_loading && !size.is_empty()).then(|| rsx!(
+                    span {
+                        class: "folder-size item-alignment",
+                        aria_label: "folder-size",
+                        "{size}"
+                    }
                 ))
             }
         ))
@@ -28,6 +28,17 @@ pub enum JSTextData {
     Cursor(i64),
     KeyPress(Code),
     Submit,
+    Misspelled(MisspelledWord),
+}
+
+/// A misspelled word which was right clicked, where the suggestions should open
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct MisspelledWord {
+    /// the word in the text, in UTF-16 code units
+    pub from: usize,
+    pub to: usize,
+    pub x: f64,
+    pub y: f64,
 }
 
 impl Size {
@@ -68,6 +79,13 @@ pub struct Props<'a> {
     #[props(default = false)]
     prevent_up_down_arrows: bool,
     onup_down_arrow: Option<EventHandler<'a, Code>>,
+    // language of the text, used by the spellchecker. empty uses the app language
+    #[props(default = "".to_owned())]
+    lang: String,
+    // words to underline, as (from, to) in UTF-16 code units. only used by the rich editor
+    #[props(default)]
+    misspelled: Vec<(usize, usize)>,
+    on_misspelled: Option<EventHandler<'a, MisspelledWord>>,
 }
 
 #[allow(non_snake_case)]
@@ -98,6 +116,9 @@ pub fn Input<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
         show_char_counter,
         prevent_up_down_arrows,
         onup_down_arrow,
+        lang,
+        misspelled: _,
+        on_misspelled: _,
     } = &cx.props;
 
     let id = if cx.props.id.is_empty() {
@@ -159,6 +180,8 @@ pub fn Input<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                     class: format_args!("{} {}", "input_textarea", if *prevent_up_down_arrows {"up-down-disabled"} else {""}),
                     id: "{id}",
                     aria_label: "{aria_label}",
+                    lang: "{lang}",
+                    dir: "auto",
                     disabled: "{disabled}",
                     value: "{text_value.read()}",
                     maxlength: "{max_length}",
@@ -321,6 +344,9 @@ pub fn InputRich<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
         show_char_counter,
         prevent_up_down_arrows,
         onup_down_arrow,
+        lang,
+        misspelled,
+        on_misspelled,
     } = &cx.props;
 
     let id = if cx.props.id.is_empty() {
@@ -364,6 +390,22 @@ pub fn InputRich<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
         },
     );
 
+    // Underline the misspelled words
+    use_future(cx, misspelled, |misspelled| {
+        to_owned![eval];
+        let ranges = misspelled
+            .iter()
+            .map(|(from, to)| format!("[{from},{to}]"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let spellcheck_script = include_str!("./spellcheck.js")
+            .replace("$UUID", &id)
+            .replace("$RANGES", &ranges);
+        async move {
+            let _ = eval(&spellcheck_script);
+        }
+    });
+
     use_effect(cx, (), |_| {
         to_owned![listener_data, eval, value];
         let rich_editor: String = include_str!("./rich_editor_handler.js")
@@ -436,6 +478,11 @@ pub fn InputRich<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                     };
                 }
             }
+            JSTextData::Misspelled(word) => {
+                if let Some(e) = on_misspelled {
+                    e.call(word);
+                }
+            }
             JSTextData::Init => {
                 let focus_script = include_str!("./focus.js").replace("$UUID", &id);
                 let _ = eval(&focus_script);
@@ -456,6 +503,8 @@ pub fn InputRich<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                     class: format_args!("{} {}", "input_textarea", if *prevent_up_down_arrows {"up-down-disabled"} else {""}),
                     id: "{id}",
                     aria_label: "{aria_label}",
+                    lang: "{lang}",
+                    dir: "auto",
                     disabled: "{disabled}",
                     maxlength: "{max_length}",
                     placeholder: format_args!("{}", if *is_disabled {""} else {placeholder}),
@@ -85,6 +85,16 @@ editor.registerListener("selection", ({ _element, _codemirror, selection }) => {
     dioxus.send(`{\"Cursor\":${selection.main.to}}`)
 });
 
+// Right clicking a misspelled word opens the suggestions instead of the context menu
+editor.codemirror.dom.addEventListener("contextmenu", (e) => {
+    let pos = editor.codemirror.posAtCoords({ x: e.clientX, y: e.clientY })
+    let word = (text.misspelledRanges || []).find(([from, to]) => pos !== null && from <= pos && pos <= to)
+    if (word) {
+        e.preventDefault()
+        dioxus.send(`{\"Misspelled\":{\"from\":${word[0]},\"to\":${word[1]},\"x\":${e.clientX},\"y\":${e.clientY}}}`)
+    }
+});
+
 setTimeout(()=>{
     dioxus.send(`\"Init\"`)
 }, 100);
\ No newline at end of file
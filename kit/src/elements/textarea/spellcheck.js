@@ -0,0 +1,31 @@
+// Underlines the misspelled words of the rich editor with a custom highlight, which doesn't touch
+// the content of the editor
+var e = document.getElementById('$UUID')
+if (e && e.markdownEditor && window.CSS && CSS.highlights) {
+  e.misspelledRanges = [$RANGES]
+  if (!e.underlineMisspelled) {
+    let view = e.markdownEditor.codemirror
+    e.underlineMisspelled = () => {
+      let length = view.state.doc.length
+      let ranges = []
+      for (const [from, to] of e.misspelledRanges) {
+        if (to > length) continue
+        try {
+          let start = view.domAtPos(from)
+          let end = view.domAtPos(to)
+          let range = new Range()
+          range.setStart(start.node, start.offset)
+          range.setEnd(end.node, end.offset)
+          ranges.push(range)
+        } catch (_) {
+          // not rendered yet
+        }
+      }
+      CSS.highlights.set("misspelled", new Highlight(...ranges))
+    }
+    // the lines are drawn again while typing and scrolling
+    view.dom.addEventListener("input", () => requestAnimationFrame(e.underlineMisspelled))
+    view.scrollDOM.addEventListener("scroll", () => requestAnimationFrame(e.underlineMisspelled))
+  }
+  e.underlineMisspelled()
+}
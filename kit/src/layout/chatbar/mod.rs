@@ -9,7 +9,12 @@ use crate::{
         embeds::file_embed::FileEmbed, message::format_text, message_typing::MessageTyping,
         user_image::UserImage,
     },
-    elements::{button::Button, label::Label, textarea, Appearance},
+    elements::{
+        button::Button,
+        label::Label,
+        textarea::{self, MisspelledWord},
+        Appearance,
+    },
 };
 
 use common::{icons, language::get_local_text, warp_runner::thumbnail_to_base64};
@@ -21,6 +26,8 @@ pub enum SuggestionType {
     Emoji(String, Vec<(String, String)>),
     // Username tag suggestions. First is the string that was matched. Second is the users that matched
     Tag(String, Vec<Identity>),
+    // Slash command suggestions. First is the string that was matched. Second is the usage and description of the commands matched
+    Command(String, Vec<(String, String)>),
 }
 
 impl SuggestionType {
@@ -32,6 +39,9 @@ impl SuggestionType {
                 pattern.clone(),
                 format!("{}#{}", v[index].username(), v[index].short_id()),
             ),
+            SuggestionType::Command(pattern, v) => {
+                (pattern.clone(), command_replacement(&v[index].0))
+            }
         }
     }
 
@@ -40,9 +50,15 @@ impl SuggestionType {
             SuggestionType::None => true,
             SuggestionType::Emoji(_, v) => v.is_empty(),
             SuggestionType::Tag(_, v) => v.is_empty(),
+            SuggestionType::Command(_, v) => v.is_empty(),
         }
     }
 }
+
+// the name of the command from its usage, followed by a space for the arguments
+fn command_replacement(usage: &str) -> String {
+    format!("{} ", usage.split(' ').next().unwrap_or_default())
+}
 #[derive(Clone, PartialEq)]
 pub struct Route {
     pub to: To,
@@ -77,6 +93,12 @@ pub struct Props<'a> {
     oncursor_update: Option<EventHandler<'a, (String, i64)>>,
     on_suggestion_click: Option<EventHandler<'a, (String, String, i64)>>,
     onup_down_arrow: Option<EventHandler<'a, Code>>,
+    // passed to the textarea for spellchecking
+    #[props(default = "".to_owned())]
+    lang: String,
+    #[props(default)]
+    misspelled: Vec<(usize, usize)>,
+    on_misspelled: Option<EventHandler<'a, MisspelledWord>>,
 }
 
 #[derive(Props)]
@@ -197,6 +219,13 @@ pub fn Chatbar<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                     placeholder: cx.props.placeholder.clone(),
                     ignore_focus: cx.props.ignore_focus,
                     show_char_counter: true,
+                    lang: cx.props.lang.clone(),
+                    misspelled: cx.props.misspelled.clone(),
+                    on_misspelled: move |word| {
+                        if let Some(e) = cx.props.on_misspelled.as_ref() {
+                            e.call(word);
+                        }
+                    },
                     value: if cx.props.is_disabled { get_local_text("messages.loading")} else { cx.props.value.clone().unwrap_or_default()},
                     onkeyup: move |keycode| {
                         if !*is_suggestion_modal_closed.read() && (keycode == Code::Escape || keycode == Code::Tab) {
@@ -250,6 +279,7 @@ pub fn Chatbar<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                                 SuggestionType::None => 0,
                                 SuggestionType::Emoji(_, v) => v.len(),
                                 SuggestionType::Tag(_, v) => v.len(),
+                                SuggestionType::Command(_, v) => v.len(),
                             };
                             if amount == 0 {
                                 *selected_suggestion.write_silent() = None;
@@ -383,7 +413,7 @@ fn SuggestionsMenu<'a>(cx: Scope<'a, SuggestionProps<'a>>) -> Element<'a> {
                         UserImage {
                             platform: id.platform().into(),
                             status: id.identity_status().into(),
-                            image: id.profile_picture()
+                            image: id.avatar()
                         }
                     }
                     format_args!("{username}"),
@@ -391,6 +421,42 @@ fn SuggestionsMenu<'a>(cx: Scope<'a, SuggestionProps<'a>>) -> Element<'a> {
             }).collect();
             (get_local_text("messages.username-suggestion"), component)
         }
+        SuggestionType::Command(pattern, commands) => {
+            let component = commands.iter().enumerate().map(|(num, (usage, description))| {
+                let replacement = command_replacement(usage);
+                rsx!(div {
+                    class: format_args!("{} {}", "chatbar-suggestion", match cx.props.selected.read().as_ref() {
+                        Some(v) => if *v == num {"chatbar-selected"} else {""},
+                        None => ""
+                    }),
+                    aria_label: {
+                        format_args!(
+                            "command-suggested-{usage}",
+                        )
+                    },
+                    onclick: move |_| {
+                        cx.props.on_click.call((replacement.clone(), pattern.clone()))
+                    },
+                    onmouseover: move |_| {
+                        cx.props.arrow_selected.with_mut(|arrow|{
+                            if !*arrow {
+                                *cx.props.selected.write() = Some(num);
+                            }
+                            *arrow = false
+                        });
+                    },
+                    code {
+                        class: "command-suggestion-usage",
+                        "{usage}"
+                    },
+                    span {
+                        class: "command-suggestion-description",
+                        "{description}"
+                    }
+                })
+            }).collect();
+            (get_local_text("messages.command-suggestion"), component)
+        }
     };
     cx.render(rsx!(div {
         id: "chatbar-suggestions",
@@ -2,6 +2,7 @@
 //      Low priority and can be ignored
 
 #![allow(clippy::type_complexity)]
+use common::utils::avatars::Avatar;
 use components::indicator::{Platform, Status};
 
 pub mod components;
@@ -13,7 +14,7 @@ pub const STYLE: &str = include_str!("./compiled_styles.css");
 #[derive(Eq, PartialEq, Clone)]
 pub struct User {
     pub username: String,
-    pub photo: String,
+    pub photo: Avatar,
     pub status: Status,
     pub platform: Platform,
 }
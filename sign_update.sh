@@ -0,0 +1,21 @@
+#!/bin/bash
+# Signs an installer so Uplink can install it as an update, see ui/src/utils/auto_updater.rs.
+# The signature covers the version, the name of the installer and its contents, and is written
+# base64 encoded to <installer>.sig. The private ed25519 key is read as PEM from
+# UPLINK_UPDATE_SIGNING_KEY. Needs OpenSSL 3, set OPENSSL if it isn't the default openssl.
+#
+# usage: sign_update.sh <installer> <version>
+
+set -euo pipefail
+
+INSTALLER=$1
+VERSION=$2
+OPENSSL=${OPENSSL:-openssl}
+
+KEY=$(mktemp)
+MESSAGE=$(mktemp)
+trap 'rm -f "$KEY" "$MESSAGE"' EXIT
+
+printf '%s\n' "$UPLINK_UPDATE_SIGNING_KEY" > "$KEY"
+{ printf 'uplink-update\n%s\n%s\n' "$VERSION" "$(basename "$INSTALLER")"; cat "$INSTALLER"; } > "$MESSAGE"
+"$OPENSSL" pkeyutl -sign -rawin -inkey "$KEY" -in "$MESSAGE" | base64 | tr -d '\n' > "$INSTALLER.sig"
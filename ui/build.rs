@@ -20,6 +20,19 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     println!("cargo:rustc-env=RUSTC_VERSION={}", version);
 
+    // the public key the installers of releases are signed with, see `utils::auto_updater`. it's
+    // set by the release workflows, builds without it show the steps to update manually instead
+    println!("cargo:rerun-if-env-changed=UPLINK_UPDATE_PUBLIC_KEY");
+    match std::env::var("UPLINK_UPDATE_PUBLIC_KEY") {
+        Ok(key) if !key.trim().is_empty() => {
+            println!("cargo:rustc-env=UPLINK_UPDATE_PUBLIC_KEY={}", key.trim())
+        }
+        _ if cfg!(feature = "production_mode") => {
+            println!("cargo:warning=UPLINK_UPDATE_PUBLIC_KEY is not set, this build can't install updates")
+        }
+        _ => {}
+    }
+
     #[cfg(windows)]
     {
         //https://github.com/rust-lang/rfcs/blob/master/text/1665-windows-subsystem.md
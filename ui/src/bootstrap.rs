@@ -1,18 +1,30 @@
 use std::backtrace::Backtrace;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use super::*;
 
 use crate::utils::auto_updater::DownloadState;
 use chrono::Local;
 use common::state::data_transfer::TransferTracker;
+use common::state::onboarding::OnboardingStep;
+use common::state::transfer_history::TransferHistory;
 use common::state::ui::WindowMeta;
 use common::state::State;
+use common::utils::incoming_webhooks;
 use common::STATIC_ARGS;
 use dioxus_desktop::use_window;
 use overlay::make_config;
 use overlay::OverlayDom;
+use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
 use warp::multipass;
 
+// set when launched with `--safe-mode` or when the user accepted safe mode after a crash loop
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+// launches without reaching a stable run before safe mode is offered
+const CRASH_LOOP_THRESHOLD: u32 = 3;
+// how long Uplink has to run before a launch is considered successful
+pub const STABLE_RUN_SECONDS: u64 = 30;
+
 pub fn use_warp_runner(cx: &ScopeState) {
     cx.use_hook(|| {
         // Now turn on the warp runner and save it to the hook so it doesn't get dropped
@@ -25,11 +37,15 @@ pub fn use_warp_runner(cx: &ScopeState) {
 pub(crate) fn use_bootstrap<'a>(
     cx: &'a ScopeState,
     identity: &multipass::identity::Identity,
+    new_account: bool,
 ) -> Option<&'a UseSharedState<State>> {
     let desktop = use_window(cx);
     use_shared_state_provider(cx, DownloadState::default);
     use_shared_state_provider(cx, || components::settings::sidebar::Page::Profile);
-    use_shared_state_provider(cx, TransferTracker::default);
+    use_shared_state_provider(cx, || TransferTracker {
+        history: TransferHistory::load(),
+        ..Default::default()
+    });
     use_shared_state_provider(cx, || {
         let mut state = State::load();
 
@@ -38,6 +54,10 @@ pub(crate) fn use_bootstrap<'a>(
         } else {
             state.set_own_identity(identity.clone().into());
         }
+        // accounts recovered or linked from another device don't go through the onboarding
+        if new_account {
+            state.ui.onboarding = Some(OnboardingStep::default());
+        }
 
         // TODO: This overlay needs to be fixed in windows
         if cfg!(not(target_os = "windows")) && state.configuration.general.enable_overlay {
@@ -57,6 +77,7 @@ pub(crate) fn use_bootstrap<'a>(
         };
         state.ui.metadata = window_meta;
         state.set_warp_ch(WARP_CMD_CH.tx.clone());
+        incoming_webhooks::start();
 
         state
     });
@@ -107,15 +128,59 @@ pub fn create_uplink_dirs() {
             .expect("Error removing temp files directory");
     }
     // Initializes the cache dir if needed
-    std::fs::create_dir_all(&STATIC_ARGS.uplink_path).expect("Error creating Uplink directory");
-    std::fs::create_dir_all(&STATIC_ARGS.warp_path).expect("Error creating Warp directory");
+    std::fs::create_dir_all(STATIC_ARGS.uplink_path()).expect("Error creating Uplink directory");
+    std::fs::create_dir_all(STATIC_ARGS.warp_path()).expect("Error creating Warp directory");
     std::fs::create_dir_all(&STATIC_ARGS.themes_path).expect("error creating themes directory");
     std::fs::create_dir_all(&STATIC_ARGS.fonts_path)
         .expect("error creating fonts themes directory");
+    std::fs::create_dir_all(&STATIC_ARGS.locales_path).expect("error creating locales directory");
+    std::fs::create_dir_all(&STATIC_ARGS.dictionaries_path)
+        .expect("error creating dictionaries directory");
     std::fs::create_dir_all(&STATIC_ARGS.temp_files)
         .expect("error creatings temporary files directory");
 }
 
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::Relaxed)
+}
+
+// counts the launch and decides if safe mode should be used. must be called after `create_uplink_dirs`.
+pub fn configure_safe_mode(safe_mode_flag: bool) {
+    let attempts = fs::read_to_string(&STATIC_ARGS.startup_attempts_path)
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .unwrap_or_default()
+        + 1;
+    if let Err(e) = fs::write(&STATIC_ARGS.startup_attempts_path, attempts.to_string()) {
+        ::log::error!("failed to save startup attempts: {e}");
+    }
+
+    let safe_mode = safe_mode_flag || (attempts > CRASH_LOOP_THRESHOLD && offer_safe_mode());
+    if safe_mode {
+        ::log::warn!("starting uplink in safe mode");
+    }
+    SAFE_MODE.store(safe_mode, Ordering::Relaxed);
+}
+
+fn offer_safe_mode() -> bool {
+    let res = MessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title(get_local_text("safe-mode.offer-title"))
+        .set_description(get_local_text("safe-mode.offer-description"))
+        .set_buttons(MessageButtons::YesNo)
+        .show();
+    matches!(res, MessageDialogResult::Yes)
+}
+
+// called once Uplink has been running for `STABLE_RUN_SECONDS`
+pub fn mark_startup_successful() {
+    if STATIC_ARGS.startup_attempts_path.exists() {
+        if let Err(e) = fs::remove_file(&STATIC_ARGS.startup_attempts_path) {
+            ::log::error!("failed to reset startup attempts: {e}");
+        }
+    }
+}
+
 pub fn platform_quirks() {
     // Attempts to increase the file desc limit on unix-like systems
     // Note: Will be changed out in the future
@@ -0,0 +1,134 @@
+use common::{icons::outline::Shape as Icon, state::State};
+use dioxus::prelude::*;
+use extensions::{
+    context_menu::{MenuContext, MenuItem},
+    UplinkExtension,
+};
+use kit::components::context_menu::{ContextItem, ContextSubmenu};
+
+#[derive(PartialEq, Props)]
+pub struct Props {
+    context: MenuContext,
+}
+
+// what the items were asked for: the context and the library of every extension allowed to see it
+type CacheKey = (MenuContext, Vec<String>);
+
+// the items the enabled extensions add to a context menu, below the ones of Uplink. extensions are
+// only asked again when the menu is for something else or the extensions or their permissions
+// changed, not every time the menu renders
+#[allow(non_snake_case)]
+pub fn ExtensionMenuItems(cx: Scope<Props>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let cache: &UseRef<Option<(CacheKey, Vec<(&'static str, MenuItem)>)>> = use_ref(cx, || None);
+    let context = &cx.props.context;
+    let state = state.read();
+    let allowed: Vec<&UplinkExtension> = state
+        .ui
+        .extensions
+        .values()
+        .filter(|(is_enabled, ext)| *is_enabled && is_allowed(&state, ext, context))
+        .map(|(_, ext)| ext)
+        .collect();
+    let key: CacheKey = (
+        context.clone(),
+        allowed
+            .iter()
+            .map(|ext| ext.path().to_string_lossy().to_string())
+            .collect(),
+    );
+    if cache.read().as_ref().map(|(cached, _)| cached) != Some(&key) {
+        let items = allowed
+            .iter()
+            .flat_map(|ext| {
+                let name = ext.details().meta.name;
+                ext.context_menu_items(context)
+                    .into_iter()
+                    .map(move |item| (name, item))
+            })
+            .collect();
+        *cache.write_silent() = Some((key, items));
+    }
+    let items = cache
+        .read()
+        .as_ref()
+        .map(|(_, items)| items.clone())
+        .unwrap_or_default();
+
+    if items.is_empty() {
+        return None;
+    }
+
+    cx.render(rsx!(
+        hr {},
+        items.into_iter().map(|(extension, item)| {
+            let key = format!("{extension}-{}-{}", item.id, item.text);
+            rsx!(MenuEntry {
+                key: "{key}",
+                extension: extension,
+                item: item,
+                context: cx.props.context.clone(),
+            })
+        })
+    ))
+}
+
+#[derive(PartialEq, Props)]
+struct EntryProps {
+    extension: &'static str,
+    item: MenuItem,
+    context: MenuContext,
+}
+
+#[allow(non_snake_case)]
+fn MenuEntry(cx: Scope<EntryProps>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let extension = cx.props.extension;
+    let item = &cx.props.item;
+
+    if !item.children.is_empty() {
+        return cx.render(rsx!(ContextSubmenu {
+            icon: Icon::Sparkles,
+            text: item.text.clone(),
+            aria_label: format!("{extension}-{}", item.text),
+            item.children.iter().map(|child| {
+                let key = format!("{}-{}", child.id, child.text);
+                rsx!(MenuEntry {
+                    key: "{key}",
+                    extension: extension,
+                    item: child.clone(),
+                    context: cx.props.context.clone(),
+                })
+            })
+        }));
+    }
+
+    cx.render(rsx!(ContextItem {
+        icon: Icon::Sparkles,
+        danger: item.danger,
+        text: item.text.clone(),
+        aria_label: format!("{extension}-{}", item.id),
+        onpress: move |_| {
+            let state = state.read();
+            let ext = state.ui.extensions.values().find(|(is_enabled, ext)| {
+                *is_enabled
+                    && ext.details().meta.name == extension
+                    && is_allowed(&state, ext, &cx.props.context)
+            });
+            if let Some((_, ext)) = ext {
+                ext.on_context_menu_item(&cx.props.item.id, &cx.props.context);
+            }
+        }
+    }))
+}
+
+// extensions are only told about messages, conversations and friends if the user allowed them to
+// read those
+fn is_allowed(state: &State, ext: &UplinkExtension, context: &MenuContext) -> bool {
+    context.permission().map_or(true, |permission| {
+        state
+            .configuration
+            .extensions
+            .is_granted(&ext.path().to_string_lossy(), permission)
+    })
+}
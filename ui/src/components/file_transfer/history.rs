@@ -0,0 +1,148 @@
+use common::icons::outline::Shape as Icon;
+use common::icons::Icon as IconElement;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::data_transfer::TransferTracker;
+use common::state::transfer_history::{Direction, HistoryFilter, Outcome, TransferRecord};
+use common::state::State;
+use dioxus::prelude::*;
+use humansize::{format_size, DECIMAL};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    Appearance,
+};
+
+use crate::utils::format_timestamp::format_timestamp_timeago;
+
+// the transfers which finished or failed, with the newest first
+#[allow(non_snake_case)]
+pub fn TransferHistory(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
+    let filter = use_ref(cx, HistoryFilter::default);
+
+    let language = state.read().settings.language_id();
+    let tracker = file_tracker.read();
+    let records: Vec<TransferRecord> = tracker.history.filtered(&filter.read()).cloned().collect();
+    let (direction, outcome) = {
+        let filter = filter.read();
+        (filter.direction, filter.outcome)
+    };
+    let directions = [
+        ("files.history-all", None),
+        ("files.history-uploads", Some(Direction::Upload)),
+        ("files.history-downloads", Some(Direction::Download)),
+    ];
+    let outcomes = [
+        ("files.history-any-outcome", None),
+        ("files.history-completed", Some(Outcome::Completed)),
+        ("files.history-failed", Some(Outcome::Failed)),
+    ];
+
+    cx.render(rsx!(
+        div {
+            id: "transfer-history",
+            aria_label: "transfer-history",
+            Input {
+                placeholder: get_local_text("files.history-search"),
+                aria_label: "transfer-history-search".into(),
+                icon: Icon::MagnifyingGlass,
+                options: Options {
+                    with_clear_btn: true,
+                    clear_on_submit: false,
+                    ..Options::default()
+                },
+                onchange: move |(v, _): (String, _)| {
+                    filter.write().text = v;
+                },
+            },
+            div {
+                class: "transfer-history-filters",
+                directions.into_iter().map(|(label, value)| rsx!(Button {
+                    key: "{label}",
+                    text: get_local_text(label),
+                    aria_label: format!("{label}-filter"),
+                    small: true,
+                    appearance: if direction == value { Appearance::Primary } else { Appearance::Secondary },
+                    onpress: move |_| filter.write().direction = value,
+                })),
+            },
+            div {
+                class: "transfer-history-filters",
+                outcomes.into_iter().map(|(label, value)| rsx!(Button {
+                    key: "{label}",
+                    text: get_local_text(label),
+                    aria_label: format!("{label}-filter"),
+                    small: true,
+                    appearance: if outcome == value { Appearance::Primary } else { Appearance::Secondary },
+                    onpress: move |_| filter.write().outcome = value,
+                })),
+            },
+            div {
+                class: "transfer-history-list",
+                aria_label: "transfer-history-list",
+                if records.is_empty() {
+                    rsx!(p {
+                        class: "transfer-history-empty",
+                        get_local_text("files.history-empty")
+                    })
+                }
+                records.iter().enumerate().map(|(idx, record)| {
+                    let upload = record.direction == Direction::Upload;
+                    let failed = record.outcome == Outcome::Failed;
+                    let size = format_size(record.size, DECIMAL);
+                    let seconds = record.duration().num_seconds().max(0);
+                    let duration = format!("{}:{:02}", seconds / 60, seconds % 60);
+                    let when = format_timestamp_timeago(record.finished, &language);
+                    let conversation = record
+                        .conversation_id
+                        .and_then(|id| state.read().get_chat_by_id(id))
+                        .map(|chat| match state.read().chats().title(&chat) {
+                            Some(title) => title,
+                            None => {
+                                let participants = state.read().chat_participants(&chat);
+                                State::join_usernames(&state.read().remove_self(&participants))
+                            }
+                        });
+                    rsx!(div {
+                        key: "{idx}",
+                        class: format_args!("transfer-history-record {}", if failed { "failed" } else { "" }),
+                        aria_label: "transfer-history-record",
+                        IconElement {
+                            icon: if upload { Icon::ArrowUpTray } else { Icon::ArrowDownTray },
+                        },
+                        div {
+                            class: "transfer-history-details",
+                            p {
+                                class: "transfer-history-file",
+                                title: "{record.file}",
+                                "{record.file}"
+                            },
+                            p {
+                                class: "transfer-history-info",
+                                "{size} · {duration} · {when}"
+                            },
+                            conversation.map(|name| rsx!(p {
+                                class: "transfer-history-info",
+                                get_local_text_with_args("files.history-conversation", vec![("name", name)])
+                            })),
+                        },
+                        IconElement {
+                            icon: if failed { Icon::XMark } else { Icon::Check },
+                        },
+                    })
+                })
+            },
+            Button {
+                text: get_local_text("files.history-clear"),
+                aria_label: "clear-transfer-history".into(),
+                icon: Icon::Trash,
+                appearance: Appearance::Danger,
+                disabled: tracker.history.is_empty(),
+                onpress: move |_| {
+                    file_tracker.write().history.clear();
+                },
+            },
+        }
+    ))
+}
@@ -1,10 +1,24 @@
+mod history;
+
+use std::collections::HashMap;
+
 use common::icons::outline::Shape as Icon;
+use common::language::get_local_text_with_args;
 use common::state::data_transfer::{TrackerType, TransferProgress, TransferTracker};
 use common::state::State;
+use common::warp_runner::transfer_queue::{self, TransferQueue};
 use common::{language::get_local_text, state::data_transfer::FileProgress};
 use dioxus::prelude::*;
 use futures::StreamExt;
-use kit::elements::{button::Button, Appearance};
+use kit::elements::{
+    button::Button,
+    tooltip::{ArrowPosition, Tooltip},
+    Appearance,
+};
+use kit::layout::modal::Modal;
+use uuid::Uuid;
+
+use self::history::TransferHistory;
 
 #[derive(Props)]
 pub struct Props<'a> {
@@ -20,12 +34,64 @@ pub fn FileTransferModal<'a>(cx: Scope<'a, Props>) -> Element<'a> {
         tracker.get_tracker(TrackerType::FileUpload),
         tracker.get_tracker(TrackerType::FileDownload),
     );
+    // what screen readers are told about the transfers. the ones which were already running when
+    // this was rendered the first time aren't announced
+    let tracked = use_ref(cx, || None::<HashMap<Uuid, Tracked>>);
+    let announcement = use_ref(cx, String::new);
+    {
+        let mut tracked = tracked.write_silent();
+        let first = tracked.is_none();
+        let announced = announce(
+            tracked.get_or_insert_with(HashMap::new),
+            &file_progress_upload,
+            &file_progress_download,
+        );
+        if !first && !announced.is_empty() {
+            *announcement.write_silent() = announced;
+        }
+    }
+    let announcement = announcement.read().clone();
+    let show_history = use_state(cx, || false);
+    let history = rsx!(
+        Button {
+            text: get_local_text("files.history"),
+            aria_label: "transfer-history-button".into(),
+            icon: Icon::Clock,
+            small: true,
+            appearance: Appearance::Secondary,
+            onpress: move |_| show_history.set(true),
+        },
+        show_history.then(|| rsx!(
+            Modal {
+                open: true,
+                transparent: true,
+                with_title: get_local_text("files.history"),
+                onclose: move |_| show_history.set(false),
+                TransferHistory {}
+            }
+        ))
+    );
+    // the live region stays in place, screen readers only read changes to regions they know
+    let live_region = rsx!(div {
+        class: "visually-hidden",
+        role: "status",
+        aria_live: "polite",
+        "{announcement}"
+    });
+
     if file_progress_upload.is_empty() && file_progress_download.is_empty() {
-        return cx.render(rsx!(()));
+        return cx.render(rsx!(
+            live_region,
+            div {
+                class: "file-transfer-history",
+                history
+            }
+        ));
     }
     let modal = cx.props.modal.unwrap_or_default();
     cx.render(rsx!(div {
         class: format_args!("file-transfer-wrap {}", if modal {"file-transfer-modal"} else {""}),
+        live_region,
         (!file_progress_upload.is_empty()).then(||
             rsx!(FileTransferElement {
                 transfers: file_progress_upload.clone(),
@@ -38,6 +104,10 @@ pub fn FileTransferModal<'a>(cx: Scope<'a, Props>) -> Element<'a> {
                 label: get_local_text("uplink.download-queue"),
             })
         ),
+        div {
+            class: "file-transfer-history",
+            history
+        }
     }))
 }
 
@@ -48,6 +118,21 @@ pub struct TransferProps {
 }
 
 pub fn FileTransferElement(cx: Scope<TransferProps>) -> Element {
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
+    let queued: Vec<Uuid> = cx
+        .props
+        .transfers
+        .iter()
+        .filter(|f| f.progress == TransferProgress::Queued)
+        .map(|f| f.id)
+        .collect();
+    // only uploads wait in line
+    let reorder = move |f: &dyn Fn(&TransferQueue)| {
+        f(&transfer_queue::UPLOADS);
+        file_tracker
+            .write()
+            .order_queued(&transfer_queue::UPLOADS.waiting(), TrackerType::FileUpload);
+    };
     cx.render(rsx!(div {
         class: "file-transfer-container",
         aria_label: "file-transfer-container",
@@ -61,6 +146,9 @@ pub fn FileTransferElement(cx: Scope<TransferProps>) -> Element {
         },
         cx.props.transfers.iter().map(|f| {
             let progress = f.progress.get_progress();
+            let id = f.id;
+            let is_queued = queued.contains(&id);
+            let is_next = queued.first() == Some(&id);
             let state = f.state.clone();
             let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<bool>| {
                 to_owned![state];
@@ -78,13 +166,16 @@ pub fn FileTransferElement(cx: Scope<TransferProps>) -> Element {
                         class: "progress-container",
                         aria_label: "progress-container",
                         p {
+                            id: "transfer-{f.id}",
                             class: "filename-and-file-queue-text",
                             aria_label: "filename-and-file-queue-text",
                             margin_right: "auto",
                             f.file.to_string(),
                         },
                         ProgressIndicator {
-                            progress: progress
+                            progress: progress,
+                            labelled_by: format!("transfer-{}", f.id),
+                            value_text: f.description.clone(),
                         },
                         p {
                             class: "transfer-progress-percentage",
@@ -94,16 +185,45 @@ pub fn FileTransferElement(cx: Scope<TransferProps>) -> Element {
                     },
                     div {
                         class: "file-transfer-buttons",
-                        Button {
+                        role: "group",
+                        aria_labelledby: "transfer-{f.id}",
+                        is_queued.then(|| rsx!(
+                            Button {
+                                aria_label: "move-up-upload".into(),
+                                disabled: is_next,
+                                appearance: Appearance::Primary,
+                                small: true,
+                                icon: Icon::ChevronUp,
+                                onpress: move |_| reorder(&|queue| queue.move_up(id)),
+                                tooltip: cx.render(rsx!(Tooltip {
+                                    arrow_position: ArrowPosition::Top,
+                                    text: get_local_text("files.move-up"),
+                                })),
+                            },
+                            Button {
+                                aria_label: "prioritize-upload".into(),
+                                disabled: is_next,
+                                appearance: Appearance::Primary,
+                                small: true,
+                                icon: Icon::ChevronDoubleUp,
+                                onpress: move |_| reorder(&|queue| queue.prioritize(id)),
+                                tooltip: cx.render(rsx!(Tooltip {
+                                    arrow_position: ArrowPosition::Top,
+                                    text: get_local_text("files.start-next"),
+                                })),
+                            },
+                        )),
+                        (!is_queued).then(|| rsx!(Button {
                             aria_label: "pause-upload".into(),
                             disabled: matches!(f.progress, TransferProgress::Progress(100)),
                             appearance: Appearance::Primary,
                             small: true,
+                            pressed: matches!(f.progress, TransferProgress::Paused(_)),
                             icon: if matches!(f.progress, TransferProgress::Paused(_)) { Icon::Play } else { Icon::Pause },
                             onpress: move |_| {
                                 ch.send(false);
                             },
-                        },
+                        })),
                         Button {
                             aria_label: "cancel-upload".into(),
                             disabled: matches!(f.progress, TransferProgress::Cancelling(_) | TransferProgress::Progress(100)),
@@ -124,11 +244,21 @@ pub fn FileTransferElement(cx: Scope<TransferProps>) -> Element {
 #[derive(Props, PartialEq)]
 pub struct ProgressIndicatorProps {
     progress: u8,
+    // the id of the element naming what's in progress
+    labelled_by: String,
+    // read instead of the percentage
+    value_text: String,
 }
 
 pub fn ProgressIndicator(cx: Scope<ProgressIndicatorProps>) -> Element {
     cx.render(rsx!(div{
         class: "progress-indicator-wrap",
+        role: "progressbar",
+        aria_valuemin: "0",
+        aria_valuemax: "100",
+        aria_valuenow: "{cx.props.progress}",
+        aria_valuetext: "{cx.props.value_text}",
+        aria_labelledby: "{cx.props.labelled_by}",
         div {
             class: "progress-indicator",
             div {
@@ -138,3 +268,110 @@ pub fn ProgressIndicator(cx: Scope<ProgressIndicatorProps>) -> Element {
         }
     }))
 }
+
+// the steps of a transfer which are announced. progress is announced every 25%
+#[derive(Clone, Copy, PartialEq)]
+enum Milestone {
+    Queued,
+    Started,
+    Quarter(u8),
+    Finishing,
+    Paused,
+    Cancelling,
+    Failed,
+    VerificationFailed,
+}
+
+impl Milestone {
+    fn of(progress: &TransferProgress) -> Self {
+        match progress {
+            TransferProgress::Queued => Milestone::Queued,
+            TransferProgress::Starting => Milestone::Started,
+            TransferProgress::Progress(p) => Milestone::Quarter(p / 25),
+            TransferProgress::Finishing => Milestone::Finishing,
+            TransferProgress::Paused(_) => Milestone::Paused,
+            TransferProgress::Cancelling(_) => Milestone::Cancelling,
+            TransferProgress::Error(_) => Milestone::Failed,
+            TransferProgress::VerificationFailed(_) => Milestone::VerificationFailed,
+        }
+    }
+}
+
+struct Tracked {
+    file: String,
+    upload: bool,
+    milestone: Milestone,
+}
+
+// what changed since the last time, for screen readers. empty if nothing worth saying did
+fn announce(
+    tracked: &mut HashMap<Uuid, Tracked>,
+    uploads: &[FileProgress],
+    downloads: &[FileProgress],
+) -> String {
+    let mut announcements = vec![];
+    let transfers = uploads
+        .iter()
+        .map(|f| (f, true))
+        .chain(downloads.iter().map(|f| (f, false)));
+    let mut current = HashMap::new();
+    for (f, upload) in transfers {
+        let milestone = Milestone::of(&f.progress);
+        let previous = tracked.remove(&f.id).map(|t| t.milestone);
+        if previous != Some(milestone) {
+            let key = match (previous, milestone) {
+                (None, Milestone::Queued) => Some("files.announce-queued"),
+                (None, _)
+                | (Some(Milestone::Queued), Milestone::Started | Milestone::Quarter(_))
+                    if upload =>
+                {
+                    Some("files.announce-upload-started")
+                }
+                (None, _)
+                | (Some(Milestone::Queued), Milestone::Started | Milestone::Quarter(_)) => {
+                    Some("files.announce-download-started")
+                }
+                (Some(Milestone::Paused), Milestone::Quarter(_)) => Some("files.announce-resumed"),
+                (_, Milestone::Quarter(0)) => None,
+                (_, Milestone::Quarter(_)) => Some("files.announce-progress"),
+                (_, Milestone::Finishing) => Some("files.announce-finishing"),
+                (_, Milestone::Paused) => Some("files.announce-paused"),
+                (_, Milestone::Cancelling) => Some("files.announce-cancelling"),
+                (_, Milestone::Failed) => Some("files.announce-failed"),
+                (_, Milestone::VerificationFailed) => Some("files.announce-verification-failed"),
+                (_, Milestone::Queued | Milestone::Started) => None,
+            };
+            if let Some(key) = key {
+                let percent = f.progress.get_progress();
+                announcements.push(get_local_text_with_args(
+                    key,
+                    vec![("file", f.file.clone()), ("percent", percent.to_string())],
+                ));
+            }
+        }
+        current.insert(
+            f.id,
+            Tracked {
+                file: f.file.clone(),
+                upload,
+                milestone,
+            },
+        );
+    }
+    // the ones which are gone either finished or were cancelled
+    for t in tracked.values() {
+        let key = match t.milestone {
+            // transfers only leave the queue early when they're cancelled
+            Milestone::Cancelling | Milestone::Queued => "files.announce-cancelled",
+            Milestone::Failed | Milestone::VerificationFailed => continue,
+            _ if t.upload => "files.announce-upload-done",
+            _ => "files.announce-download-done",
+        };
+        announcements.push(get_local_text_with_args(
+            key,
+            vec![("file", t.file.clone())],
+        ));
+    }
+    *tracked = current;
+    announcements.join(" ")
+}
@@ -100,14 +100,14 @@ pub fn BlockedUsers(cx: Scope) -> Element {
                             username: blocked_user.username(),
                             aria_label: blocked_user.username(),
                             suffix: did_suffix,
-                            status_message: blocked_user.status_message().unwrap_or_default(),
+                            status_message: blocked_user.custom_status().map(|s| s.shown()).unwrap_or_default(),
                             relationship: relationship,
                             remove_button_disabled: unblock_in_progress.current().contains(&blocked_user.did_key()),
                             user_image: cx.render(rsx! (
                                 UserImage {
                                     platform: platform,
                                     status: blocked_user.identity_status().into(),
-                                    image: blocked_user.profile_picture()
+                                    image: blocked_user.avatar()
                                 }
                             )),
                             onremove: move |_| {
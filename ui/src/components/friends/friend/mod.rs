@@ -28,6 +28,8 @@ pub struct Props<'a> {
     relationship: Relationship,
     // Status message from friend
     status_message: String,
+    // The emoji of their custom status, shown next to the username
+    status_emoji: Option<String>,
     // The user image element to display
     user_image: Element<'a>,
     // An optional event handler for the "onchat" event
@@ -70,6 +72,13 @@ pub fn Friend<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
                             "#{cx.props.suffix}"
                         }
                     )),
+                    cx.props.status_emoji.as_ref().map(|emoji| rsx!(
+                        span {
+                            class: "status-emoji",
+                            aria_label: "status-emoji",
+                            "{emoji}"
+                        }
+                    )),
                 },
                 if relationship.friends() {
                    rsx!(p {
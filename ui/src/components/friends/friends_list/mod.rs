@@ -2,44 +2,40 @@ use std::collections::{HashMap, HashSet};
 
 use dioxus::prelude::*;
 use dioxus_router::prelude::use_navigator;
+use extensions::context_menu::MenuContext;
 use futures::{channel::oneshot, StreamExt};
 use kit::{
     components::{
         context_menu::{ContextItem, ContextMenu},
-        message::format_text,
-        user::User,
         user_image::UserImage,
-        user_image_group::UserImageGroup,
     },
     elements::{
-        button::Button,
-        checkbox::Checkbox,
         input::{Input, Options},
         label::Label,
-        Appearance,
+        select::Select,
     },
     layout::modal::Modal,
 };
 
-use common::{get_images_dir, icons::outline::Shape as Icon, language::get_local_text_with_args};
-use common::{language::get_local_text, state::Identity};
+use common::sounds::{self, Sounds, NOTIFICATION_SOUNDS};
+use common::{get_images_dir, icons::outline::Shape as Icon, language::get_local_text};
 use common::{
     state::{Action, Chat, State},
     warp_runner::{MultiPassCmd, RayGunCmd, WarpCmd},
     STATIC_ARGS, WARP_CMD_CH,
 };
 use uuid::Uuid;
-use warp::{
-    crypto::DID,
-    multipass::identity::Relationship,
-    raygun::{self, ConversationType},
-};
+use warp::{crypto::DID, multipass::identity::Relationship};
 
 use tracing::log;
 
 use crate::{
-    components::friends::friend::{Friend, SkeletalFriend},
-    utils::build_participants,
+    components::{
+        extension_menu_items::ExtensionMenuItems,
+        friends::friend::{Friend, SkeletalFriend},
+        profile_details::ProfileDetails,
+        select_chats::SelectChatsModal,
+    },
     UplinkRoute,
 };
 
@@ -73,6 +69,7 @@ pub fn Friends(cx: Scope) -> Element {
     let remove_in_progress: &UseState<HashSet<DID>> = use_state(cx, HashSet::new);
 
     let share_did = use_state(cx, || None);
+    let sound_did = use_state(cx, || None);
 
     let friends = State::get_friends_by_first_letter(friends_list);
 
@@ -233,6 +230,11 @@ pub fn Friends(cx: Scope) -> Element {
                     did: share_did.clone()
                 })
             }),
+            sound_did.is_some().then(||{
+                rsx!(FriendSoundModal{
+                    did: sound_did.clone()
+                })
+            }),
             friends.into_iter().map(|(letter, sorted_friends)| {
                 let group_letter = letter.to_string();
                 rsx!(
@@ -256,6 +258,7 @@ pub fn Friends(cx: Scope) -> Element {
                             let block_friend_2 = friend.clone();
                             let context_friend = friend.clone();
                             let share_friend = friend.clone();
+                            let sound_friend = friend.clone();
                             let mut relationship = Relationship::default();
                             relationship.set_friends(true);
                             let platform = friend.platform().into();
@@ -265,6 +268,9 @@ pub fn Friends(cx: Scope) -> Element {
                                     key: "{did}-friend-listing",
                                     devmode: state.read().configuration.developer.developer_mode,
                                     items: cx.render(rsx!(
+                                        ProfileDetails {
+                                            did_key: friend.did_key(),
+                                        },
                                         ContextItem {
                                             icon: Icon::ChatBubbleBottomCenterText,
                                             text: get_local_text("uplink.chat"),
@@ -282,6 +288,14 @@ pub fn Friends(cx: Scope) -> Element {
                                                 share_did.set(Some(share_friend.did_key()));
                                             }
                                         },
+                                        ContextItem {
+                                            icon: Icon::MusicalNote,
+                                            text: get_local_text("friends.notification-sound"),
+                                            aria_label: "friends-notification-sound".into(),
+                                            onpress: move |_| {
+                                                sound_did.set(Some(sound_friend.did_key()));
+                                            }
+                                        },
                                         if let Some(f) = favorite {
                                             rsx!(ContextItem {
                                                 icon: if f {Icon::HeartSlash} else {Icon::Heart},
@@ -331,12 +345,16 @@ pub fn Friends(cx: Scope) -> Element {
                                                 }
                                             }
                                         },
+                                        ExtensionMenuItems {
+                                            context: MenuContext::Friend { did: friend.did_key().to_string(), username: friend.username() },
+                                        },
                                     )),
                                     Friend {
                                         username: friend.username(),
                                         aria_label: friend.username(),
                                         suffix: did_suffix,
-                                        status_message: friend.status_message().unwrap_or_default(),
+                                        status_message: friend.custom_status().map(|s| s.text).unwrap_or_default(),
+                                        status_emoji: friend.custom_status().map(|s| s.emoji).filter(|e| !e.is_empty()),
                                         relationship: relationship,
                                         block_button_disabled: block_in_progress.current().contains(&friend.did_key()),
                                         remove_button_disabled: remove_in_progress.current().contains(&friend.did_key()),
@@ -344,7 +362,7 @@ pub fn Friends(cx: Scope) -> Element {
                                             UserImage {
                                                 platform: platform,
                                                 status: friend.identity_status().into(),
-                                                image: friend.profile_picture()
+                                                image: friend.avatar()
                                             }
                                         )),
                                         onchat: move |_| {
@@ -397,6 +415,43 @@ pub fn FriendsSkeletal(cx: Scope) -> Element {
     ))
 }
 
+#[derive(PartialEq, Props)]
+pub struct FriendSoundProps {
+    did: UseState<Option<DID>>,
+}
+
+// lets the user pick the sound played for new messages from a friend
+#[allow(non_snake_case)]
+pub fn FriendSoundModal(cx: Scope<FriendSoundProps>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let did = cx.props.did.get().clone()?;
+    let sound = state.read().friend_sound(&did);
+
+    cx.render(rsx!(Modal {
+        open: true,
+        onclose: move |_| cx.props.did.set(None),
+        with_title: get_local_text("friends.notification-sound"),
+        transparent: false,
+        div {
+            aria_label: "friend-sound-modal",
+            class: "modal-friend-sound",
+            Select {
+                initial_value: sound.unwrap_or(Sounds::Notification).label(),
+                options: NOTIFICATION_SOUNDS.iter().map(|sound| sound.label()).collect(),
+                onselect: move |value: String| {
+                    let sound = NOTIFICATION_SOUNDS
+                        .iter()
+                        .copied()
+                        .find(|sound| sound.label() == value)
+                        .filter(|sound| *sound != Sounds::Notification);
+                    sounds::Play(sound.unwrap_or(Sounds::Notification));
+                    state.write().mutate(Action::SetFriendSound(did.clone(), sound));
+                }
+            }
+        }
+    }))
+}
+
 #[derive(PartialEq, Props)]
 pub struct FriendProps {
     did: UseState<Option<DID>>,
@@ -404,8 +459,6 @@ pub struct FriendProps {
 }
 
 pub fn ShareFriendsModal(cx: Scope<FriendProps>) -> Element {
-    let state = use_shared_state::<State>(cx)?;
-    let chats_selected = use_ref(cx, Vec::new);
     let ch = use_coroutine(
         cx,
         |mut rx: UnboundedReceiver<(DID, Vec<Uuid>)>| async move {
@@ -431,139 +484,17 @@ pub fn ShareFriendsModal(cx: Scope<FriendProps>) -> Element {
             }
         },
     );
-    let chats: Vec<_> = state
-        .read()
-        .chats_sidebar()
-        .iter()
-        .filter(|c| {
-            cx.props
-                .excluded_chat
-                .map(|id| !c.id.eq(&id))
-                .unwrap_or(true)
-        })
-        .cloned()
-        .collect();
-    cx.render(rsx!(Modal {
-        open: cx.props.did.get().is_some(),
+    cx.render(rsx!(SelectChatsModal {
+        aria_label: "share-did-modal".into(),
+        button_text: get_local_text("friends.share-to-chat"),
+        button_icon: Icon::Share,
+        button_aria_label: "share-to-chat-button".into(),
+        excluded_chat: cx.props.excluded_chat,
         onclose: move |_| cx.props.did.set(None),
-        show_close_button: false,
-        transparent: false,
-        close_on_click_inside_modal: false,
-        dont_pad: true,
-        div {
-            aria_label: "share-did-modal",
-            class: "modal-share-friends",
-            div {
-                class: "modal-share-friends-header",
-                padding: "12px",
-                Label {
-                    aria_label: "share-did-header".into(),
-                    text: get_local_text("friends.select-chat"),
-                },
-                div {
-                    class: "send-chat-button",
-                    Button {
-                        text: get_local_text("friends.share-to-chat"),
-                        icon: Icon::Share,
-                        aria_label: "share-to-chat-button".into(),
-                        appearance: Appearance::Secondary,
-                        disabled: chats_selected.read().is_empty(),
-                        onpress: move |_| {
-                            ch.send((cx.props.did.as_ref().unwrap().clone(), chats_selected.read().clone()));
-                            cx.props.did.set(None);
-                        },
-                    },
-                }
+        onsubmit: move |chats| {
+            if let Some(did) = cx.props.did.get() {
+                ch.send((did.clone(), chats));
             }
-            chats.is_empty().then(||{
-                rsx!(div {
-                    class: "modal-share-friend-empty",
-                    aria_label: "modal-share-friend-empty",
-                    get_local_text("messages.no-chats")
-                })
-            }),
-            chats.iter().map(|chat| {
-                let id = chat.id;
-                let participants = state.read().chat_participants(chat);
-                let other_participants =  state.read().remove_self(&participants);
-                let user: Identity = other_participants.first().cloned().unwrap_or_default();
-                let platform = user.platform().into();
-                // todo: how to tell who is participating in a group chat if the chat has a conversation_name?
-                let participants_name = match &chat.conversation_name {
-                    Some(name) => name.clone(),
-                    None => State::join_usernames(&other_participants)
-                };
-                let unwrapped_message = match chat.messages.iter().last() {Some(m) => m.inner.clone(),None => raygun::Message::default()};
-                let subtext_val = match unwrapped_message.lines().iter().map(|x| x.trim()).find(|x| !x.is_empty()) {
-                    Some(v) => format_text(v, state.read().ui.should_transform_markdown_text(), state.read().ui.should_transform_ascii_emojis(), Some((&state.read(), &chat.id, true))),
-                    _ => match &unwrapped_message.attachments()[..] {
-                        [] => get_local_text("sidebar.chat-new"),
-                        [ file ] => file.name(),
-                        _ => match participants.iter().find(|p| p.did_key()  == unwrapped_message.sender()).map(|x| x.username()) {
-                            Some(name) => get_local_text_with_args("sidebar.subtext", vec![("user", name)]),
-                            None => {
-                                log::error!("error calculating subtext for sidebar chat");
-                                // Still return default message
-                                get_local_text("sidebar.chat-new")
-                            }
-                        }
-                    }
-                };
-                let selected = chats_selected.read().contains(&id);
-                rsx!(div {
-                    class: format_args!("modal-share-friend {}", if selected {"share-friend-selected"} else {""}),
-                    User {
-                        aria_label: participants_name.clone(),
-                        username: participants_name,
-                        subtext: subtext_val,
-                        timestamp: raygun::Message::default().date(),
-                        active: false,
-                        user_image: cx.render(rsx!(
-                            div {
-                                class: "modal-share-friend-image-group",
-                                Checkbox {
-                                    aria_label: "user-to-share-did-checkbox".into(),
-                                    disabled: false,
-                                    width: "1em".into(),
-                                    height: "1em".into(),
-                                    is_checked: selected,
-                                    on_click: move |_| {
-                                        chats_selected.with_mut(|v|{
-                                            if !selected {
-                                                v.push(id);
-                                            } else {
-                                                v.retain(|c|!c.eq(&id));
-                                            }
-                                        });
-                                    }
-                                },
-                                match chat.conversation_type {
-                                    ConversationType::Direct => rsx!(UserImage {
-                                        platform: platform,
-                                        status:  user.identity_status().into(),
-                                        image: user.profile_picture(),
-                                        typing: false,
-                                    }),
-                                    _ => rsx!(UserImageGroup {
-                                        participants: build_participants(&participants),
-                                        typing: false,
-                                    })
-                                }
-                            }
-                        )),
-                        onpress: move |_| {
-                            chats_selected.with_mut(|v|{
-                                if !selected {
-                                    v.push(id);
-                                } else {
-                                    v.retain(|c|!c.eq(&id));
-                                }
-                            });
-                        }
-                    }
-                }
-            )
-            })
-        }
+        },
     }))
 }
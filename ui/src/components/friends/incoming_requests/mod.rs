@@ -3,10 +3,10 @@ use std::rc::Rc;
 
 use crate::components::friends::friend::Friend;
 use common::icons::outline::Shape as Icon;
-use common::language::get_local_text;
+use common::language::{get_local_text, get_local_text_with_args};
 use common::{
     state::{Action, State},
-    warp_runner::{MultiPassCmd, WarpCmd},
+    warp_runner::{send_batch, BatchProgress, BatchStep, MultiPassCmd, WarpCmd},
     STATIC_ARGS, WARP_CMD_CH,
 };
 use dioxus::prelude::*;
@@ -16,7 +16,7 @@ use kit::{
         context_menu::{ContextItem, ContextMenu},
         user_image::UserImage,
     },
-    elements::label::Label,
+    elements::{button::Button, label::Label, Appearance},
 };
 use warp::crypto::DID;
 use warp::multipass::identity::Relationship;
@@ -26,6 +26,9 @@ use tracing::log;
 enum ChanCmd {
     AcceptRequest(DID),
     DenyRequest(DID),
+    // accepts all requests, or none of them if one fails
+    AcceptAll(Vec<DID>),
+    DenyAll(Vec<DID>),
 }
 
 #[allow(non_snake_case)]
@@ -34,9 +37,10 @@ pub fn PendingFriends(cx: Scope) -> Element {
     let friends_list = state.read().incoming_fr_identities();
     let deny_in_progress: &UseState<HashSet<DID>> = use_state(cx, HashSet::new);
     let accept_in_progress: &UseState<HashSet<DID>> = use_state(cx, HashSet::new);
+    let bulk_progress: &UseState<Option<BatchProgress>> = use_state(cx, || None);
 
     let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<ChanCmd>| {
-        to_owned![deny_in_progress, accept_in_progress];
+        to_owned![deny_in_progress, accept_in_progress, bulk_progress];
         async move {
             let warp_cmd_tx = WARP_CMD_CH.tx.clone();
             while let Some(cmd) = rx.next().await {
@@ -80,6 +84,58 @@ pub fn PendingFriends(cx: Scope) -> Element {
                             log::error!("failed to deny request: {}", e);
                         }
                     }
+                    ChanCmd::AcceptAll(dids) => {
+                        let steps = dids
+                            .iter()
+                            .cloned()
+                            .map(|did| {
+                                let did2 = did.clone();
+                                BatchStep::new("accept-request", move |_, rsp| {
+                                    Ok(WarpCmd::MultiPass(MultiPassCmd::AcceptRequest { did, rsp }))
+                                })
+                                .on_rollback(move |_| {
+                                    let (rsp, _) = oneshot::channel();
+                                    Some(WarpCmd::MultiPass(MultiPassCmd::RemoveFriend {
+                                        did: did2,
+                                        rsp,
+                                    }))
+                                })
+                            })
+                            .collect();
+                        let res = send_batch("accept-all-requests", steps, |progress| {
+                            bulk_progress.set(Some(progress))
+                        })
+                        .await;
+                        bulk_progress.set(None);
+                        accept_in_progress
+                            .make_mut()
+                            .retain(|did| !dids.contains(did));
+                        if let Err(e) = res {
+                            log::error!("failed to accept requests: {}", e);
+                        }
+                    }
+                    ChanCmd::DenyAll(dids) => {
+                        let steps = dids
+                            .iter()
+                            .cloned()
+                            .map(|did| {
+                                BatchStep::new("deny-request", move |_, rsp| {
+                                    Ok(WarpCmd::MultiPass(MultiPassCmd::DenyRequest { did, rsp }))
+                                })
+                            })
+                            .collect();
+                        let res = send_batch("deny-all-requests", steps, |progress| {
+                            bulk_progress.set(Some(progress))
+                        })
+                        .await;
+                        bulk_progress.set(None);
+                        deny_in_progress
+                            .make_mut()
+                            .retain(|did| !dids.contains(did));
+                        if let Err(e) = res {
+                            log::error!("failed to deny requests: {}", e);
+                        }
+                    }
                 }
             }
         }
@@ -91,14 +147,60 @@ pub fn PendingFriends(cx: Scope) -> Element {
     cx.render(rsx!(rsx!(div {
         class: "friends-list",
         aria_label: "Incoming Requests List",
-        Label {
-            text: get_local_text("friends.incoming_requests"),
-            aria_label: "incoming-list-label".into(),
+        div {
+            class: "friends-list-header",
+            Label {
+                text: get_local_text("friends.incoming_requests"),
+                aria_label: "incoming-list-label".into(),
+            },
+            (friends_list.len() > 1 && !STATIC_ARGS.use_mock).then(|| {
+                let dids: Vec<DID> = friends_list.iter().map(|friend| friend.did_key()).collect();
+                let dids2 = dids.clone();
+                let busy = bulk_progress.is_some();
+                rsx!(
+                    bulk_progress.as_ref().map(|progress| {
+                        let text = get_local_text_with_args("friends.bulk-progress", vec![
+                            ("current", progress.step + 1),
+                            ("total", progress.total),
+                        ]);
+                        rsx!(span {
+                            class: "bulk-progress",
+                            aria_label: "bulk-progress",
+                            "{text}"
+                        })
+                    }),
+                    Button {
+                        text: get_local_text("friends.accept-all"),
+                        aria_label: "accept-all-button".into(),
+                        icon: Icon::Check,
+                        appearance: Appearance::Secondary,
+                        small: true,
+                        loading: busy,
+                        disabled: busy,
+                        onpress: move |_| {
+                            accept_in_progress.make_mut().extend(dids.iter().cloned());
+                            ch.send(ChanCmd::AcceptAll(dids.clone()));
+                        }
+                    },
+                    Button {
+                        text: get_local_text("friends.deny-all"),
+                        aria_label: "deny-all-button".into(),
+                        icon: Icon::XMark,
+                        appearance: Appearance::Secondary,
+                        small: true,
+                        disabled: busy,
+                        onpress: move |_| {
+                            deny_in_progress.make_mut().extend(dids2.iter().cloned());
+                            ch.send(ChanCmd::DenyAll(dids2.clone()));
+                        }
+                    },
+                )
+            }),
         },
         friends_list.into_iter().map(|friend| {
             let friend = Rc::new(friend);
             let _username = friend.username();
-            let _status_message = friend.status_message().unwrap_or_default();
+            let _status_message = friend.custom_status().map(|s| s.shown()).unwrap_or_default();
             let did = friend.did_key();
             let did2 = did.clone();
             let did_suffix = friend.short_id().to_string();
@@ -161,7 +263,7 @@ pub fn PendingFriends(cx: Scope) -> Element {
                             UserImage {
                                 platform: platform,
                                 status: friend2.identity_status().into(),
-                                image: friend2.profile_picture()
+                                image: friend2.avatar()
                             }
                         )),
                         accept_button_disabled: accept_in_progress.current().contains(&did2),
@@ -94,7 +94,7 @@ pub fn OutgoingRequests(cx: Scope) -> Element {
                             username: friend.username(),
                             aria_label: friend.username(),
                             suffix: did_suffix,
-                            status_message: friend.status_message().unwrap_or_default(),
+                            status_message: friend.custom_status().map(|s| s.shown()).unwrap_or_default(),
                             relationship: {
                                 let mut relationship = Relationship::default();
                                 relationship.set_sent_friend_request(true);
@@ -105,7 +105,7 @@ pub fn OutgoingRequests(cx: Scope) -> Element {
                                 UserImage {
                                     platform: platform,
                                     status: friend.identity_status().into(),
-                                    image: friend.profile_picture()
+                                    image: friend.avatar()
                                 }
                             )),
                             onremove: move |_| {
@@ -0,0 +1,33 @@
+use common::state::State;
+use dioxus::prelude::*;
+
+// where screen readers hear about new messages and calls. the regions are always there, screen
+// readers only read changes to regions they already know
+#[allow(non_snake_case)]
+pub fn LiveRegions(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let state = state.read();
+    // nothing is read out on the lock screen, it would give away who wrote
+    let announcements = if state.ui.locked {
+        Default::default()
+    } else {
+        state.ui.announcements.clone()
+    };
+
+    cx.render(rsx!(
+        div {
+            class: "visually-hidden",
+            aria_label: "live-region-polite",
+            role: "status",
+            aria_live: "polite",
+            "{announcements.polite}"
+        },
+        div {
+            class: "visually-hidden",
+            aria_label: "live-region-assertive",
+            role: "alert",
+            aria_live: "assertive",
+            "{announcements.assertive}"
+        }
+    ))
+}
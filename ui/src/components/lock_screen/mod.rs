@@ -0,0 +1,115 @@
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::{Action, State};
+use common::warp_runner::{PassphraseCheck, TesseractCmd, WarpCmd};
+use common::WARP_CMD_CH;
+use dioxus::prelude::*;
+use dioxus_desktop::use_window;
+use futures::channel::oneshot;
+use futures::StreamExt;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+};
+use tracing::log;
+
+// shown instead of the app while it is locked. warp keeps running, so messages still arrive
+#[allow(non_snake_case)]
+pub fn LockScreen(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let pin = use_ref(cx, String::new);
+    let error: &UseState<Option<String>> = use_state(cx, || None);
+    let checking = use_state(cx, || false);
+    let desktop = use_window(cx);
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<String>| {
+        to_owned![state, error, checking, desktop];
+        async move {
+            while let Some(passphrase) = rx.next().await {
+                checking.set(true);
+                let (tx, rsp) = oneshot::channel();
+                if let Err(e) =
+                    WARP_CMD_CH
+                        .tx
+                        .send(WarpCmd::Tesseract(TesseractCmd::VerifyPassphrase {
+                            passphrase,
+                            rsp: tx,
+                        }))
+                {
+                    log::error!("failed to send warp command: {}", e);
+                    checking.set(false);
+                    continue;
+                }
+                let check = rsp.await.unwrap_or(PassphraseCheck::Invalid);
+                checking.set(false);
+                match check {
+                    PassphraseCheck::Valid => state.write().mutate(Action::SetLocked(false)),
+                    PassphraseCheck::Invalid => {
+                        error.set(Some(get_local_text("unlock.invalid-pin")))
+                    }
+                    PassphraseCheck::RetryIn(wait) => error.set(Some(get_local_text_with_args(
+                        "lock-screen.retry-in",
+                        vec![("seconds", wait.as_secs().max(1))],
+                    ))),
+                    // Uplink closes like it crashed. a wipe is finished on the next start, the decoy
+                    // opens with the duress PIN on the next unlock
+                    PassphraseCheck::Duress(_) => desktop.close(),
+                }
+            }
+        }
+    });
+
+    let submit = move || {
+        let value = pin.read().clone();
+        if !value.is_empty() && !*checking.get() {
+            ch.send(value);
+        }
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "lock-screen",
+            aria_label: "lock-screen",
+            // no inspect element
+            prevent_default: "oncontextmenu",
+            oncontextmenu: |_| {},
+            div {
+                class: "lock-screen-content",
+                h2 {
+                    get_local_text("lock-screen.title")
+                },
+                p {
+                    get_local_text("lock-screen.description")
+                },
+                Input {
+                    focus: true,
+                    is_password: true,
+                    icon: Icon::Key,
+                    aria_label: "lock-screen-pin-input".into(),
+                    placeholder: get_local_text("unlock.enter-pin"),
+                    disabled: *checking.get(),
+                    options: Options {
+                        with_clear_btn: true,
+                        ..Default::default()
+                    },
+                    onchange: move |(v, _): (String, bool)| {
+                        *pin.write_silent() = v;
+                        error.set(None);
+                    },
+                    onreturn: move |_| submit(),
+                },
+                error.get().as_ref().map(|error| rsx!(span {
+                    class: "error",
+                    "{error}"
+                })),
+                Button {
+                    text: get_local_text("lock-screen.unlock"),
+                    aria_label: "lock-screen-unlock-button".into(),
+                    icon: Icon::LockOpen,
+                    loading: *checking.get(),
+                    onpress: move |_| submit(),
+                },
+            }
+        }
+    ))
+}
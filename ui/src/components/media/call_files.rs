@@ -0,0 +1,216 @@
+//! Files dropped onto the call are sent to its conversation like any other attachment. Their
+//! progress goes to the `TransferTracker`, which the chips of the call are drawn from.
+
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use common::{
+    icons::outline::Shape as Icon,
+    icons::Icon as IconElement,
+    language::get_local_text,
+    state::{
+        data_transfer::{TrackerType, TransferProgress, TransferState, TransferTracker},
+        Action, State, ToastNotification,
+    },
+    warp_runner::{ui_adapter::MessageEvent, RayGunCmd, WarpCmd, WarpEvent},
+    WARP_CMD_CH, WARP_EVENT_CH,
+};
+use dioxus::prelude::*;
+use dioxus_desktop::{
+    tao::dpi::{LogicalPosition, PhysicalPosition},
+    DesktopContext,
+};
+use futures::{channel::oneshot, StreamExt};
+use uuid::Uuid;
+use warp::raygun::{AttachmentKind, Location};
+
+use crate::components::file_transfer::ProgressIndicator;
+use crate::utils::async_task_queue::{ListenerAction, ACTION_LISTENER};
+use crate::utils::EvalProvider;
+
+const OVER_CALL_SCRIPT: &str = r#"
+    const over = document
+        .elementsFromPoint($X, $Y)
+        .some((element) => element.matches('#remote-controls.in-chat'))
+    dioxus.send(over)
+"#;
+
+// finished chips stay for a moment so the end of the transfer can be seen
+const FINISHED_DELAY: Duration = Duration::from_secs(2);
+
+/// Whether files dropped at `position` land on the call shown in the chat
+pub async fn is_over_call(
+    eval: &EvalProvider,
+    window: &DesktopContext,
+    position: PhysicalPosition<f64>,
+) -> bool {
+    let position: LogicalPosition<f64> =
+        position.to_logical(window.webview.window().scale_factor());
+    let script = OVER_CALL_SCRIPT
+        .replace("$X", &position.x.to_string())
+        .replace("$Y", &position.y.to_string());
+    let Ok(check) = eval(&script) else {
+        return false;
+    };
+    match check.recv().await {
+        Ok(over) => over.as_bool().unwrap_or_default(),
+        Err(e) => {
+            log::error!("failed to find where files were dropped: {e:?}");
+            false
+        }
+    }
+}
+
+/// Sends `files` to the conversation of the call. The upload goes on in the background
+pub async fn share_in_call(
+    state: &UseSharedState<State>,
+    file_tracker: &UseSharedState<TransferTracker>,
+    conv_id: Uuid,
+    files: Vec<PathBuf>,
+) {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = WARP_CMD_CH.tx.send(WarpCmd::RayGun(RayGunCmd::SendMessage {
+        conv_id,
+        msg: vec![],
+        attachments: files
+            .iter()
+            .map(|path| Location::Disk { path: path.clone() })
+            .collect(),
+        rsp: tx,
+    })) {
+        log::error!("failed to send warp command: {e}");
+        return;
+    }
+
+    let (message_id, stream) = match rx.await.expect("command canceled") {
+        Ok(rsp) => rsp,
+        Err(e) => {
+            log::error!("failed to share files in the call: {e}");
+            state
+                .write()
+                .mutate(Action::AddToastNotification(ToastNotification::init(
+                    "".into(),
+                    get_local_text("remote-controls.share-failed"),
+                    None,
+                    2,
+                )));
+            return;
+        }
+    };
+    state
+        .write()
+        .increment_outgoing_messages_for(conv_id, message_id, vec![]);
+    let Some(mut stream) = stream else {
+        return;
+    };
+
+    let transfers: HashMap<PathBuf, Uuid> = files
+        .into_iter()
+        .map(|path| {
+            let id = Uuid::new_v4();
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut tracker = file_tracker.write();
+            tracker.start_file_transfer(id, name, TransferState::new(), TrackerType::FileUpload);
+            tracker.set_conversation(id, conv_id, TrackerType::FileUpload);
+            (path, id)
+        })
+        .collect();
+
+    // the call may be left before the upload is done
+    tokio::spawn(async move {
+        while let Some(kind) = stream.next().await {
+            match kind {
+                AttachmentKind::AttachedProgress(location, progress) => {
+                    let progression = progress.into();
+                    if let Location::Disk { path } = &location {
+                        if let Some(id) = transfers.get(path) {
+                            let _ = ACTION_LISTENER.tx.send(ListenerAction::TransferProgress {
+                                id: *id,
+                                progression: progression.clone(),
+                                download: false,
+                            });
+                        }
+                    }
+                    // the pending message in the chat
+                    if let Err(e) = WARP_EVENT_CH.tx.send(WarpEvent::Message(
+                        MessageEvent::AttachmentProgress {
+                            progress: progression,
+                            location,
+                            conversation_id: conv_id,
+                            msg: message_id,
+                        },
+                    )) {
+                        log::error!("failed to send warp_event: {e}");
+                    }
+                }
+                AttachmentKind::Pending(res) => {
+                    if let Err(e) = res {
+                        log::error!("failed to share files in the call: {e}");
+                    }
+                    break;
+                }
+            }
+        }
+        tokio::time::sleep(FINISHED_DELAY).await;
+        for id in transfers.into_values() {
+            let _ = ACTION_LISTENER.tx.send(ListenerAction::FinishTransfer {
+                id,
+                download: false,
+            });
+        }
+    });
+}
+
+#[derive(PartialEq, Props)]
+pub struct CallTransfersProps {
+    conversation_id: Uuid,
+}
+
+/// A chip for every file being shared in the call
+#[allow(non_snake_case)]
+pub fn CallTransfers(cx: Scope<CallTransfersProps>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
+    // the tracker is updated silently, see ListenerAction
+    state.write_silent().scope_ids.call_transfers = Some(cx.scope_id().0);
+
+    let tracker = file_tracker.read();
+    let transfers: Vec<_> = tracker
+        .get_tracker(TrackerType::FileUpload)
+        .iter()
+        .filter(|f| f.conversation_id == Some(cx.props.conversation_id))
+        .collect();
+    if transfers.is_empty() {
+        return cx.render(rsx!(()));
+    }
+
+    cx.render(rsx!(div {
+        class: "call-transfers",
+        aria_label: "call-transfers",
+        transfers.into_iter().map(|f| {
+            let failed = matches!(f.progress, TransferProgress::Error(_) | TransferProgress::VerificationFailed(_));
+            let done = f.progress == TransferProgress::Finishing;
+            rsx!(div {
+                key: "{f.id}",
+                class: format_args!("call-transfer {}", if failed { "failed" } else if done { "done" } else { "" }),
+                aria_label: "call-transfer",
+                title: "{f.description}",
+                IconElement {
+                    icon: if failed { Icon::ExclamationTriangle } else if done { Icon::Check } else { Icon::ArrowUpTray },
+                },
+                p {
+                    id: "call-transfer-{f.id}",
+                    class: "call-transfer-name",
+                    "{f.file}"
+                },
+                ProgressIndicator {
+                    progress: if done { 100 } else { f.progress.get_progress() },
+                    labelled_by: format!("call-transfer-{}", f.id),
+                    value_text: f.description.clone(),
+                },
+            })
+        })
+    }))
+}
@@ -0,0 +1,136 @@
+use common::{
+    icons::{outline::Shape as Icon, Icon as IconElement},
+    language::get_local_text,
+    state::{call::ActiveCall, State},
+    utils::avatars::AvatarSize,
+};
+use dioxus::prelude::*;
+use kit::{
+    components::user_image::UserImage,
+    elements::{button::Button, range::Range, Appearance},
+};
+use warp::crypto::DID;
+
+use super::camera_preview::CameraPreview;
+use crate::utils::build_user_from_identity;
+
+pub const USER_VOL_MIN: f32 = 0.25;
+pub const USER_VOL_MAX: f32 = 5.0;
+
+#[derive(Props)]
+pub struct Props<'a> {
+    active_call: ActiveCall,
+    onvolume: EventHandler<'a, (DID, f32)>,
+    onclose: EventHandler<'a, ()>,
+}
+
+/// Everyone in the call as a grid of tiles over the chat, with who is speaking and the volume of
+/// each participant
+#[allow(non_snake_case)]
+pub fn CallGrid<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let call = &cx.props.active_call.call;
+    let own = state.read().get_own_identity();
+    let own_did = own.did_key();
+    let self_user = build_user_from_identity(&own);
+    let others: Vec<_> = call
+        .participants
+        .iter()
+        .filter(|did| **did != own_did)
+        .filter_map(|did| state.read().get_identity(did))
+        .collect();
+    let volumes = state.read().settings.user_volumes.clone();
+
+    cx.render(rsx!(
+        div {
+            class: "call-grid",
+            aria_label: "call-grid",
+            div {
+                class: "call-grid-header",
+                Button {
+                    icon: Icon::XMark,
+                    aria_label: "close-call-grid-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| cx.props.onclose.call(()),
+                },
+            },
+            div {
+                class: "call-grid-tiles",
+                div {
+                    class: format_args!("call-tile {}", if call.self_speaking.is_some() && !call.self_muted { "speaking" } else { "" }),
+                    aria_label: "call-tile-self",
+                    if cx.props.active_call.camera_enabled {
+                        rsx!(CameraPreview {
+                            id: "local-video-tile".into(),
+                            device: state.read().settings.video_device.clone(),
+                        })
+                    } else {
+                        rsx!(UserImage {
+                            platform: self_user.platform,
+                            image: self_user.photo,
+                            size: AvatarSize::Large,
+                        })
+                    },
+                    p {
+                        class: "call-tile-name",
+                        "{self_user.username}"
+                    },
+                    div {
+                        class: "call-status",
+                        call.self_muted.then(|| rsx!(IconElement { icon: Icon::MicrophoneSlash })),
+                        call.call_silenced.then(|| rsx!(IconElement { icon: Icon::HeadphonesSlash })),
+                    }
+                },
+                others.into_iter().map(|identity| {
+                    let did = identity.did_key();
+                    let user = build_user_from_identity(&identity);
+                    let joined = call.participants_joined.get(&did).cloned();
+                    let speaking = call.participants_speaking.contains_key(&did);
+                    let volume = volumes.get(&did).cloned().unwrap_or(1.0);
+                    let class = match (&joined, speaking) {
+                        (None, _) => "call-tile waiting",
+                        (Some(_), true) => "call-tile speaking",
+                        (Some(_), false) => "call-tile",
+                    };
+                    rsx!(div {
+                        key: "{did}",
+                        class: class,
+                        aria_label: "call-tile",
+                        UserImage {
+                            platform: user.platform,
+                            image: user.photo,
+                            size: AvatarSize::Large,
+                        },
+                        p {
+                            class: "call-tile-name",
+                            "{user.username}"
+                        },
+                        joined.as_ref().map(|s| rsx!(div {
+                            class: "call-status",
+                            s.muted.then(|| rsx!(IconElement { icon: Icon::MicrophoneSlash })),
+                            s.deafened.then(|| rsx!(IconElement { icon: Icon::HeadphonesSlash })),
+                        })),
+                        if joined.is_some() {
+                            rsx!(Range {
+                                aria_label: "call-tile-volume".into(),
+                                initial_value: volume,
+                                min: USER_VOL_MIN,
+                                max: USER_VOL_MAX,
+                                step: 0.1,
+                                no_num: true,
+                                icon_left: Icon::Speaker,
+                                icon_right: Icon::SpeakerWave,
+                                onchange: move |val| cx.props.onvolume.call((did.clone(), val)),
+                            })
+                        } else {
+                            rsx!(p {
+                                class: "call-tile-waiting",
+                                get_local_text("remote-controls.waiting")
+                            })
+                        }
+                    })
+                })
+            }
+        }
+    ))
+}
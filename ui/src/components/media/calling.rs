@@ -23,6 +23,10 @@ use kit::{
 };
 use warp::{blink::ParticipantState, crypto::DID};
 
+use super::{
+    call_files::CallTransfers, call_grid::CallGrid, camera_preview::CameraPreview,
+    captions::LiveCaptions, pre_call_check::PreCallCheck,
+};
 use crate::utils::{
     build_participants, build_user_from_identity, format_timestamp::format_timestamp_timeago,
 };
@@ -32,8 +36,10 @@ use common::{
     state::{
         call::{ActiveCall, Call},
         ui::Layout,
+        ToastNotification,
     },
-    warp_runner::{BlinkCmd, WarpCmd},
+    utils::avatars::AvatarSize,
+    warp_runner::{BlinkCmd, RayGunCmd, WarpCmd},
     STATIC_ARGS, WARP_CMD_CH,
 };
 use common::{
@@ -51,8 +57,12 @@ pub enum CallDialogCmd {
     StopRecording,
     SilenceCall,
     UnsilenceCall,
+    // sends the lines of the transcript to the conversation
+    SaveTranscript(Uuid, Vec<String>),
 }
 
+const FOCUS_TRAP_SCRIPT: &str = include_str!("./focus_trap.js");
+
 enum PendingCallDialogCmd {
     Accept(Uuid),
     Reject(Uuid),
@@ -63,6 +73,51 @@ pub struct Props {
     in_chat: bool,
 }
 
+/// Everyone in a conversation but us, None if the conversation isn't known
+pub fn participants_to_call(state: &State, conversation_id: Uuid) -> Option<Vec<DID>> {
+    let chat = state.get_chat_by_id(conversation_id)?;
+    let own_id = state.did_key();
+    Some(
+        chat.participants
+            .iter()
+            .filter(|did| **did != own_id)
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Calls the other participants of a conversation. Returns whether the call was started
+pub async fn offer_call(
+    state: UseSharedState<State>,
+    conversation_id: Uuid,
+    participants: Vec<DID>,
+) -> bool {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = WARP_CMD_CH.tx.send(WarpCmd::Blink(BlinkCmd::OfferCall {
+        conversation_id,
+        participants: participants.clone(),
+        rsp: tx,
+    })) {
+        log::error!("failed to send command to warp_runner: {e}");
+        return false;
+    }
+
+    match rx.await.expect("warp runner failed") {
+        Ok(call_id) => {
+            state.write().mutate(Action::OfferCall(Call::new(
+                call_id,
+                conversation_id,
+                participants,
+            )));
+            true
+        }
+        Err(e) => {
+            log::error!("BlinkCmd::OfferCall failed: {e}");
+            false
+        }
+    }
+}
+
 #[allow(non_snake_case)]
 pub fn CallControl(cx: Scope<Props>) -> Element {
     let state = use_shared_state::<State>(cx)?;
@@ -111,6 +166,7 @@ fn ActiveCallControl(cx: Scope<ActiveCallProps>) -> Element {
     let update_fn = cx.schedule_update_any();
 
     let recording = use_ref(cx, || false);
+    let show_grid = use_state(cx, || false);
 
     use_future(
         cx,
@@ -279,6 +335,36 @@ fn ActiveCallControl(cx: Scope<ActiveCallProps>) -> Element {
                             }
                         }
                     }
+                    CallDialogCmd::SaveTranscript(conv_id, msg) => {
+                        let (tx, rx) = oneshot::channel();
+                        if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::SendMessage {
+                            conv_id,
+                            msg: msg.clone(),
+                            attachments: vec![],
+                            rsp: tx,
+                        })) {
+                            log::error!("failed to send warp command: {e}");
+                            continue;
+                        }
+
+                        match rx.await.expect("command canceled") {
+                            Ok((id, _)) => {
+                                let mut state = state.write();
+                                state.increment_outgoing_messages_for(conv_id, id, msg);
+                                state.mutate(Action::AddToastNotification(
+                                    ToastNotification::init(
+                                        "".into(),
+                                        get_local_text("remote-controls.transcript-saved"),
+                                        None,
+                                        2,
+                                    ),
+                                ));
+                            }
+                            Err(e) => {
+                                log::error!("failed to save transcript: {e}");
+                            }
+                        }
+                    }
                     CallDialogCmd::AdjustVolume(user, volume) => {
                         let (tx, rx) = oneshot::channel();
                         if let Err(e) = warp_cmd_tx.send(WarpCmd::Blink(BlinkCmd::AdjustVolume {
@@ -408,14 +494,35 @@ fn ActiveCallControl(cx: Scope<ActiveCallProps>) -> Element {
                 }
             )),
             cx.props.in_chat.then(||rsx!(div {
-                class: "self-identity",
+                class: format_args!("self-identity {}", if call.self_speaking.is_some() && !call.self_muted { "speaking" } else { "" }),
                 UserImage {
                     platform: self_id.platform,
                     status: self_id.status,
-                    image: self_id.photo
+                    image: self_id.photo,
+                    size: AvatarSize::Medium,
                 }
             }))
         },
+        // the grid shows the camera in its own tile
+        (cx.props.in_chat && active_call.camera_enabled && !*show_grid.get()).then(|| rsx!(div {
+            class: "local-video",
+            aria_label: "local-video",
+            CameraPreview {
+                id: "local-video-pip".into(),
+                device: state.read().settings.video_device.clone(),
+            }
+        })),
+        (cx.props.in_chat && *show_grid.get()).then(|| rsx!(CallGrid {
+            active_call: active_call.clone(),
+            onvolume: move |(did, volume)| ch.send(CallDialogCmd::AdjustVolume(Box::new(did), volume)),
+            onclose: move |_| show_grid.set(false),
+        })),
+        CallTransfers {
+            conversation_id: call.conversation_id,
+        },
+        active_call.captions_enabled.then(|| rsx!(LiveCaptions {
+            active_call: active_call.clone(),
+        })),
         div {
             class: "controls",
             aria_label: "call-controls",
@@ -447,6 +554,46 @@ fn ActiveCallControl(cx: Scope<ActiveCallProps>) -> Element {
                     if call.call_silenced { ch.send(CallDialogCmd::UnsilenceCall); } else { ch.send(CallDialogCmd::SilenceCall); }
                 }
             },
+            Button {
+                icon: if active_call.camera_enabled { Icon::VideoCamera } else { Icon::VideoCameraSlash },
+                aria_label: "call-camera-button".into(),
+                appearance: if active_call.camera_enabled { Appearance::Primary } else { Appearance::Secondary },
+                tooltip: cx.render(rsx!(
+                    Tooltip {
+                        arrow_position: ArrowPosition::Bottom,
+                        text: if active_call.camera_enabled { get_local_text("remote-controls.camera-off") } else { get_local_text("remote-controls.camera-on") }
+                    }
+                )),
+                onpress: move |_| {
+                    state.write().mutate(Action::SetCameraEnabled(!active_call.camera_enabled));
+                }
+            },
+            cx.props.in_chat.then(|| rsx!(Button {
+                icon: Icon::Users,
+                aria_label: "call-grid-button".into(),
+                appearance: if *show_grid.get() { Appearance::Primary } else { Appearance::Secondary },
+                tooltip: cx.render(rsx!(
+                    Tooltip {
+                        arrow_position: ArrowPosition::Bottom,
+                        text: get_local_text("remote-controls.participants")
+                    }
+                )),
+                onpress: move |_| show_grid.set(!show_grid.get()),
+            })),
+            Button {
+                icon: Icon::Language,
+                aria_label: "call-captions-button".into(),
+                appearance: if active_call.captions_enabled { Appearance::Primary } else { Appearance::Secondary },
+                tooltip: cx.render(rsx!(
+                    Tooltip {
+                        arrow_position: ArrowPosition::Bottom,
+                        text: if active_call.captions_enabled { get_local_text("remote-controls.hide-captions") } else { get_local_text("remote-controls.show-captions") }
+                    }
+                )),
+                onpress: move |_| {
+                    state.write().mutate(Action::SetCaptionsEnabled(!active_call.captions_enabled));
+                }
+            },
             (!outgoing).then(||{
                 if *recording.read() {
                     rsx!(Button {
@@ -511,6 +658,7 @@ pub struct PendingCallProps {
 fn PendingCallDialog(cx: Scope<PendingCallProps>) -> Element {
     log::trace!("Rendering pending call window");
     let state = use_shared_state::<State>(cx)?;
+    let show_pre_call_check = use_state(cx, || false);
     let ch = use_coroutine(cx, |mut rx| {
         to_owned![state];
         async move {
@@ -561,6 +709,20 @@ fn PendingCallDialog(cx: Scope<PendingCallProps>) -> Element {
     });
 
     let call = &cx.props.call;
+    let dialog_id = format!(
+        "incoming-call-{}{}",
+        call.id,
+        if cx.props.in_chat { "-in-chat" } else { "" }
+    );
+    let eval = use_eval(cx);
+    use_effect(cx, &dialog_id, |dialog_id| {
+        to_owned![eval];
+        async move {
+            if let Err(e) = eval(&FOCUS_TRAP_SCRIPT.replace("$ID", &dialog_id)) {
+                log::error!("failed to trap focus in the call dialog: {:?}", e);
+            }
+        }
+    });
     if state.read().ui.current_layout == Layout::Compose {
         match state.read().get_active_chat() {
             None => {
@@ -583,38 +745,55 @@ fn PendingCallDialog(cx: Scope<PendingCallProps>) -> Element {
     let mut participants = state.read().get_identities_from_call(call);
     participants = state.read().remove_self(&participants);
     let usernames = match state.read().get_chat_by_id(call.id) {
-        Some(c) => match c.conversation_name {
+        Some(c) => match state.read().chats().title(&c) {
             Some(name) => name,
             None => State::join_usernames(&participants),
         },
         None => State::join_usernames(&participants),
     };
 
-    cx.render(rsx!(CallDialog {
-        caller: cx.render(rsx!(UserImageGroup {
-            participants: build_participants(&participants),
-        },)),
-        in_chat: cx.props.in_chat,
-        usernames: usernames,
-        icon: Icon::PhoneArrowDownLeft,
-        description: get_local_text("remote-controls.incoming-call"),
-        with_accept_btn: cx.render(rsx!(Button {
-            aria_label: "accept-call-button".into(),
-            icon: Icon::Phone,
-            appearance: Appearance::Success,
-            onpress: move |_| {
+    cx.render(rsx!(
+        CallDialog {
+            id: dialog_id,
+            caller: cx.render(rsx!(UserImageGroup {
+                participants: build_participants(&participants),
+            },)),
+            in_chat: cx.props.in_chat,
+            usernames: usernames,
+            icon: Icon::PhoneArrowDownLeft,
+            description: get_local_text("remote-controls.incoming-call"),
+            with_accept_btn: cx.render(rsx!(Button {
+                aria_label: "accept-call-button".into(),
+                icon: Icon::Phone,
+                appearance: Appearance::Success,
+                onpress: move |_| {
+                    if state.read().configuration.audiovideo.pre_call_check {
+                        show_pre_call_check.set(true);
+                    } else {
+                        ch.send(PendingCallDialogCmd::Accept(call.id));
+                    }
+                }
+            })),
+            with_deny_btn: cx.render(rsx!(Button {
+                aria_label: "deny-call-button".into(),
+                icon: Icon::PhoneXMark,
+                appearance: Appearance::Danger,
+                onpress: move |_| {
+                    ch.send(PendingCallDialogCmd::Reject(call.id));
+                }
+            })),
+        },
+        show_pre_call_check.then(|| rsx!(PreCallCheck {
+            video: false,
+            onjoin: move |_| {
+                show_pre_call_check.set(false);
                 ch.send(PendingCallDialogCmd::Accept(call.id));
-            }
-        })),
-        with_deny_btn: cx.render(rsx!(Button {
-            aria_label: "deny-call-button".into(),
-            icon: Icon::PhoneXMark,
-            appearance: Appearance::Danger,
-            onpress: move |_| {
-                ch.send(PendingCallDialogCmd::Reject(call.id));
-            }
-        })),
-    }))
+            },
+            oncancel: move |_| {
+                show_pre_call_check.set(false);
+            },
+        }))
+    ))
 }
 
 #[derive(Props)]
@@ -628,6 +807,8 @@ pub struct CallDialogProps<'a> {
     with_accept_btn: Option<Element<'a>>,
     #[props(optional)]
     with_deny_btn: Option<Element<'a>>,
+    #[props(optional)]
+    id: Option<String>,
 }
 
 // todo: remove this
@@ -644,7 +825,9 @@ pub fn CallDialog<'a>(cx: Scope<'a, CallDialogProps<'a>>) -> Element<'a> {
     };
     cx.render(rsx! (
         div {
+            id: "{cx.props.id.clone().unwrap_or_default()}",
             class:format_args!("call-dialog {}", if cx.props.in_chat {"in-chat"} else {""}),
+            role: "alertdialog",
             aria_label: format_args!("call-dialog-{}", if cx.props.in_chat {"in-chat"} else {""}),
             div {
                 class: "call-information",
@@ -765,9 +948,12 @@ pub fn CallUserImageGroup(cx: Scope<CallUserImageProps>) -> Element {
         visible.iter().map(|(speaking, user_state, user)| {
             rsx!(div {
                 class: format_args!("call-user {}", if *speaking {"speaking"} else {""}),
+                tabindex: "0",
+                aria_label: "{user.username}",
                 UserImage {
                     platform: user.platform,
                     image: user.photo.clone(),
+                    size: AvatarSize::Medium,
                 }
                 user_state_icons(user_state.clone())
             })
@@ -0,0 +1,70 @@
+use common::{
+    icons::{outline::Shape as Icon, Icon as IconElement},
+    language::get_local_text,
+};
+use dioxus::prelude::*;
+use tracing::log;
+
+use crate::utils::{
+    camera::{show_camera, stop_camera},
+    EvalProvider,
+};
+
+// the camera is released with the preview
+struct StopCamera(EvalProvider, String);
+
+impl Drop for StopCamera {
+    fn drop(&mut self) {
+        stop_camera(&self.0, &self.1);
+    }
+}
+
+#[derive(Props, PartialEq)]
+pub struct Props {
+    // of the video element, unique in the window
+    id: String,
+    // the camera to show, the default one if None
+    device: Option<String>,
+}
+
+/// What the camera sees, mirrored like a mirror would
+#[allow(non_snake_case)]
+pub fn CameraPreview(cx: Scope<Props>) -> Element {
+    let eval = use_eval(cx);
+    let failed = use_state(cx, || false);
+    cx.use_hook(|| StopCamera(eval.clone(), cx.props.id.clone()));
+
+    use_effect(cx, &cx.props.device, |device| {
+        to_owned![eval, failed];
+        let id = cx.props.id.clone();
+        async move {
+            match show_camera(&eval, &id, device.as_deref()).await {
+                Ok(_) => failed.set(false),
+                Err(e) => {
+                    log::error!("failed to start the camera: {e}");
+                    failed.set(true);
+                }
+            }
+        }
+    });
+
+    cx.render(rsx!(
+        div {
+            class: "camera-preview",
+            aria_label: "camera-preview",
+            video {
+                id: "{cx.props.id}",
+                autoplay: true,
+                muted: true,
+                "playsinline": "true",
+            },
+            failed.then(|| rsx!(div {
+                class: "camera-unavailable",
+                IconElement {
+                    icon: Icon::VideoCameraSlash,
+                },
+                p { get_local_text("remote-controls.camera-unavailable") }
+            }))
+        }
+    ))
+}
@@ -0,0 +1,155 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::{get_id_of, get_local_text},
+    state::{
+        call::{ActiveCall, Caption},
+        Action, State, ToastNotification,
+    },
+};
+use dioxus::prelude::*;
+use kit::elements::{button::Button, checkbox::Checkbox, Appearance};
+use tracing::log;
+
+use super::calling::CallDialogCmd;
+use crate::utils::speech_recognition::{SpeechEvent, SpeechRecognizer, WebSpeechRecognizer};
+
+// number of phrases shown in the overlay
+const CAPTIONS_SHOWN: usize = 3;
+
+#[derive(Props, PartialEq)]
+pub struct Props {
+    active_call: ActiveCall,
+}
+
+// captions of what the user says in the active call, with the option to save the transcript to the
+// conversation
+#[allow(non_snake_case)]
+pub fn LiveCaptions(cx: Scope<Props>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let ch = use_coroutine_handle::<CallDialogCmd>(cx)?;
+    let eval = use_eval(cx);
+    let active_call = &cx.props.active_call;
+    let conversation_id = active_call.call.conversation_id;
+    // recognize the language set for the conversation, if any
+    let language = state
+        .read()
+        .chats()
+        .language(&conversation_id)
+        .map(|language| get_id_of(&language))
+        .unwrap_or_else(|| state.read().settings.language_id());
+
+    use_future(cx, &language, |language| {
+        to_owned![eval, state];
+        async move {
+            let mut recognizer = WebSpeechRecognizer::new(eval);
+            if let Err(e) = recognizer.start(&language) {
+                log::error!("failed to start speech recognition: {e}");
+                return;
+            }
+            while let Some(event) = recognizer.recv().await {
+                match event {
+                    SpeechEvent::Phrase(text) => {
+                        if !text.is_empty() {
+                            state.write().mutate(Action::AddCaption(text));
+                        }
+                    }
+                    SpeechEvent::Error(e) => {
+                        log::error!("speech recognition failed: {e}");
+                        let mut state = state.write();
+                        state.mutate(Action::SetCaptionsEnabled(false));
+                        state.mutate(Action::AddToastNotification(ToastNotification::init(
+                            "".into(),
+                            get_local_text("remote-controls.captions-unavailable"),
+                            None,
+                            3,
+                        )));
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let own_id = state.read().did_key();
+    let speaker_name = |caption: &Caption| {
+        if caption.speaker == own_id {
+            return get_local_text("remote-controls.captions-you");
+        }
+        state
+            .read()
+            .get_identity(&caption.speaker)
+            .map(|identity| identity.username())
+            .unwrap_or_default()
+    };
+    let shown = active_call
+        .transcript
+        .iter()
+        .rev()
+        .take(CAPTIONS_SHOWN)
+        .rev()
+        .map(|caption| (speaker_name(caption), caption.text.clone()))
+        .collect::<Vec<_>>();
+    let own_consent = active_call.transcript_consent.contains(&own_id);
+    let transcript: Vec<String> = active_call
+        .transcript
+        .iter()
+        .map(|caption| {
+            format!(
+                "[{}] {}: {}",
+                caption.time.format("%H:%M"),
+                speaker_name(caption),
+                caption.text
+            )
+        })
+        .collect();
+
+    cx.render(rsx!(
+        div {
+            class: "live-captions",
+            aria_label: "live-captions",
+            p {
+                class: "live-captions-notice",
+                aria_label: "live-captions-notice",
+                get_local_text("remote-controls.captions-notice")
+            },
+            if shown.is_empty() {
+                rsx!(p {
+                    class: "live-captions-empty",
+                    get_local_text("remote-controls.captions-listening")
+                })
+            } else {
+                rsx!(shown.into_iter().map(|(speaker, text)| rsx!(
+                    p {
+                        class: "caption",
+                        span { class: "caption-speaker", "{speaker}" },
+                        "{text}"
+                    }
+                )))
+            },
+            div {
+                class: "live-captions-transcript",
+                Checkbox {
+                    aria_label: "transcript-consent-checkbox".into(),
+                    disabled: false,
+                    is_checked: own_consent,
+                    on_click: move |_| {
+                        state.write().mutate(Action::SetTranscriptConsent(!own_consent));
+                    }
+                },
+                span {
+                    get_local_text("remote-controls.transcript-consent")
+                },
+                Button {
+                    icon: Icon::DocumentText,
+                    text: get_local_text("remote-controls.save-transcript"),
+                    aria_label: "save-transcript-button".into(),
+                    appearance: Appearance::Secondary,
+                    disabled: !active_call.transcript_can_be_saved(),
+                    onpress: move |_| {
+                        ch.send(CallDialogCmd::SaveTranscript(conversation_id, transcript.clone()));
+                    }
+                }
+            }
+        }
+    ))
+}
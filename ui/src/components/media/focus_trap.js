@@ -0,0 +1,81 @@
+// keeps keyboard focus inside the incoming call dialog. Escape releases it, and focus goes back to where it was
+// once the dialog is gone
+;(() => {
+  // the dialog may not have been rendered yet
+  const trap = (attempts) => {
+    const container = document.getElementById("$ID")
+    if (!container) {
+      if (attempts > 0) {
+        setTimeout(() => trap(attempts - 1), 50)
+      }
+      return
+    }
+    if (!container.dataset.focusTrapped) {
+      trapFocus(container)
+    }
+  }
+
+  const trapFocus = (container) => {
+    container.dataset.focusTrapped = "true"
+    const previous = document.activeElement
+    const focusable = () =>
+      Array.from(
+        container.querySelectorAll(
+          'button:not([disabled]), [href], input:not([disabled]), [tabindex]:not([tabindex="-1"])'
+        )
+      )
+
+    const release = () => {
+      document.removeEventListener("keydown", onKeyDown, true)
+      observer.disconnect()
+    }
+
+    const onKeyDown = (e) => {
+      if (e.key === "Escape") {
+        release()
+        if (previous && document.contains(previous)) {
+          previous.focus()
+        }
+        return
+      }
+      if (e.key !== "Tab") {
+        return
+      }
+      const items = focusable()
+      if (items.length === 0) {
+        return
+      }
+      const first = items[0]
+      const last = items[items.length - 1]
+      if (!container.contains(document.activeElement)) {
+        e.preventDefault()
+        first.focus()
+      } else if (e.shiftKey && document.activeElement === first) {
+        e.preventDefault()
+        last.focus()
+      } else if (!e.shiftKey && document.activeElement === last) {
+        e.preventDefault()
+        first.focus()
+      }
+    }
+
+    const observer = new MutationObserver(() => {
+      if (document.contains(container)) {
+        return
+      }
+      release()
+      if (previous && document.contains(previous)) {
+        previous.focus()
+      }
+    })
+
+    document.addEventListener("keydown", onKeyDown, true)
+    observer.observe(document.body, { childList: true, subtree: true })
+    const items = focusable()
+    if (items.length > 0) {
+      items[0].focus()
+    }
+  }
+
+  trap(10)
+})()
@@ -1,3 +1,8 @@
+pub mod call_files;
+pub mod call_grid;
 pub mod calling;
+pub mod camera_preview;
+pub mod captions;
 pub mod player;
 pub mod popout_player;
+pub mod pre_call_check;
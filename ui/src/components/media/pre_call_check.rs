@@ -0,0 +1,350 @@
+use std::time::Duration;
+
+use common::icons::Icon as IconElement;
+use common::{
+    icons::outline::Shape as Icon,
+    language::{get_local_text, get_local_text_with_args},
+    state::{action::ConfigAction, Action, State},
+    utils::network_check,
+    warp_runner::{BlinkCmd, WarpCmd},
+    WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use futures::channel::oneshot;
+use kit::{
+    elements::{button::Button, checkbox::Checkbox, Appearance},
+    layout::modal::Modal,
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::log;
+use warp::blink::AudioTestEvent;
+
+use crate::utils::camera::list_cameras;
+
+// a handshake slower than this makes for a laggy call
+const SLOW_RTT: Duration = Duration::from_millis(300);
+const RELAY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CheckStatus {
+    Running,
+    Passed,
+    Warning,
+    Failed,
+    // the check doesn't apply, doesn't count towards the summary
+    Skipped,
+}
+
+impl CheckStatus {
+    fn icon(&self) -> Icon {
+        match self {
+            CheckStatus::Running => Icon::ArrowPath,
+            CheckStatus::Passed => Icon::CheckCircle,
+            CheckStatus::Warning => Icon::ExclamationTriangle,
+            CheckStatus::Failed => Icon::XCircle,
+            CheckStatus::Skipped => Icon::MinusCircle,
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            CheckStatus::Running => "running",
+            CheckStatus::Passed => "passed",
+            CheckStatus::Warning => "warning",
+            CheckStatus::Failed => "failed",
+            CheckStatus::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+struct Check {
+    status: CheckStatus,
+    detail: String,
+}
+
+impl Check {
+    fn new(status: CheckStatus, detail: &str) -> Self {
+        Self {
+            status,
+            detail: get_local_text(detail),
+        }
+    }
+
+    fn running() -> Self {
+        Self::new(CheckStatus::Running, "pre-call.checking")
+    }
+}
+
+#[derive(Props)]
+pub struct Props<'a> {
+    // checks the camera too
+    video: bool,
+    onjoin: EventHandler<'a, ()>,
+    oncancel: EventHandler<'a, ()>,
+}
+
+// verifies the microphone, speaker and connection to the relay before joining a call
+#[allow(non_snake_case)]
+pub fn PreCallCheck<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let microphone = use_ref(cx, Check::running);
+    let speaker = use_ref(cx, Check::running);
+    let network = use_ref(cx, Check::running);
+    let dont_show_again = use_state(cx, || false);
+    let camera = use_ref(cx, || {
+        if cx.props.video {
+            Check::running()
+        } else {
+            Check::new(CheckStatus::Skipped, "pre-call.camera-off")
+        }
+    });
+    let eval = use_eval(cx);
+
+    use_future(cx, (), |_| {
+        to_owned![camera, eval, state];
+        let video = cx.props.video;
+        async move {
+            if !video {
+                return;
+            }
+            let selected = state.read().settings.video_device.clone();
+            *camera.write() = match list_cameras(&eval).await {
+                Ok(cameras) if cameras.is_empty() => {
+                    Check::new(CheckStatus::Failed, "pre-call.no-camera")
+                }
+                Ok(cameras) => {
+                    // the selected camera may have been unplugged, the default one is used then
+                    let used = cameras
+                        .iter()
+                        .find(|c| Some(&c.id) == selected.as_ref())
+                        .unwrap_or(&cameras[0]);
+                    Check {
+                        status: CheckStatus::Passed,
+                        detail: used.label.clone(),
+                    }
+                }
+                Err(e) => {
+                    log::error!("failed to list cameras: {e}");
+                    Check::new(CheckStatus::Failed, "pre-call.device-error")
+                }
+            };
+        }
+    });
+
+    use_future(cx, (), |_| {
+        to_owned![microphone, speaker];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            let (tx, rx) = oneshot::channel();
+            if let Err(e) =
+                warp_cmd_tx.send(WarpCmd::Blink(BlinkCmd::GetAudioDeviceConfig { rsp: tx }))
+            {
+                log::error!("failed to send blink command: {e}");
+                return;
+            }
+            let audio_config = match rx.await {
+                Ok(Ok(config)) => config,
+                _ => {
+                    log::error!("warp_runner failed to get audio config");
+                    *microphone.write() = Check::new(CheckStatus::Failed, "pre-call.device-error");
+                    *speaker.write() = Check::new(CheckStatus::Failed, "pre-call.device-error");
+                    return;
+                }
+            };
+            let has_microphone = !audio_config
+                .get_available_microphones()
+                .unwrap_or_default()
+                .is_empty();
+            let has_speaker = !audio_config
+                .get_available_speakers()
+                .unwrap_or_default()
+                .is_empty();
+            let microphone_name = audio_config.microphone_device_name().unwrap_or_default();
+            let speaker_name = audio_config.speaker_device_name().unwrap_or_default();
+
+            *microphone.write() = if !has_microphone {
+                Check::new(CheckStatus::Failed, "pre-call.no-microphone")
+            } else {
+                match audio_test(|rsp| BlinkCmd::TestMicrophone { rsp }).await {
+                    Some(true) => Check {
+                        status: CheckStatus::Passed,
+                        detail: microphone_name,
+                    },
+                    Some(false) => Check::new(CheckStatus::Warning, "pre-call.microphone-silent"),
+                    None => Check::new(CheckStatus::Failed, "pre-call.device-error"),
+                }
+            };
+            *speaker.write() = if !has_speaker {
+                Check::new(CheckStatus::Failed, "pre-call.no-speaker")
+            } else {
+                match audio_test(|rsp| BlinkCmd::TestSpeaker { rsp }).await {
+                    Some(true) => Check {
+                        status: CheckStatus::Passed,
+                        detail: speaker_name,
+                    },
+                    Some(false) => Check::new(CheckStatus::Warning, "pre-call.speaker-silent"),
+                    None => Check::new(CheckStatus::Failed, "pre-call.device-error"),
+                }
+            };
+        }
+    });
+
+    use_future(cx, (), |_| {
+        to_owned![network];
+        async move {
+            if !network_check::has_relay() {
+                *network.write() = Check::new(CheckStatus::Skipped, "pre-call.no-relay");
+                return;
+            }
+            *network.write() = match network_check::relay_rtt(RELAY_TIMEOUT).await {
+                Some(rtt) => Check {
+                    status: if rtt > SLOW_RTT {
+                        CheckStatus::Warning
+                    } else {
+                        CheckStatus::Passed
+                    },
+                    detail: get_local_text_with_args(
+                        "pre-call.latency",
+                        vec![("ms", rtt.as_millis() as u64)],
+                    ),
+                },
+                None => Check::new(CheckStatus::Failed, "pre-call.relay-unreachable"),
+            };
+        }
+    });
+
+    let checks = vec![
+        (
+            "pre-call.microphone",
+            Icon::Microphone,
+            microphone.read().clone(),
+        ),
+        (
+            "pre-call.speaker",
+            Icon::SpeakerWave,
+            speaker.read().clone(),
+        ),
+        ("pre-call.camera", Icon::VideoCamera, camera.read().clone()),
+        ("pre-call.network", Icon::GlobeAlt, network.read().clone()),
+    ];
+    let statuses: Vec<CheckStatus> = checks.iter().map(|(_, _, check)| check.status).collect();
+    let (summary_status, summary) = if statuses.contains(&CheckStatus::Running) {
+        (CheckStatus::Running, "pre-call.summary-running")
+    } else if statuses.contains(&CheckStatus::Failed) {
+        (CheckStatus::Failed, "pre-call.summary-no-go")
+    } else if statuses.contains(&CheckStatus::Warning) {
+        (CheckStatus::Warning, "pre-call.summary-warning")
+    } else {
+        (CheckStatus::Passed, "pre-call.summary-go")
+    };
+
+    cx.render(rsx!(Modal {
+        open: true,
+        transparent: false,
+        with_title: get_local_text("pre-call.title"),
+        onclose: move |_| cx.props.oncancel.call(()),
+        div {
+            class: "pre-call-check",
+            aria_label: "pre-call-check",
+            checks.into_iter().map(|(label, icon, check)| rsx!(
+                div {
+                    key: "{label}",
+                    class: "pre-call-check-row",
+                    aria_label: "{label}",
+                    IconElement {
+                        icon: icon,
+                    },
+                    div {
+                        class: "pre-call-check-text",
+                        p { get_local_text(label) },
+                        p {
+                            class: "pre-call-check-detail",
+                            "{check.detail}"
+                        }
+                    },
+                    div {
+                        class: "pre-call-check-status {check.status.class()}",
+                        IconElement {
+                            icon: check.status.icon(),
+                        },
+                    }
+                }
+            )),
+            div {
+                class: "pre-call-check-summary {summary_status.class()}",
+                aria_label: "pre-call-check-summary",
+                IconElement {
+                    icon: summary_status.icon(),
+                },
+                p { get_local_text(summary) }
+            },
+            div {
+                class: "pre-call-check-skip",
+                Checkbox {
+                    aria_label: "pre-call-check-skip-checkbox".into(),
+                    disabled: false,
+                    is_checked: *dont_show_again.get(),
+                    on_click: move |_| {
+                        dont_show_again.set(!dont_show_again.get());
+                    }
+                },
+                span {
+                    get_local_text("pre-call.dont-show-again")
+                }
+            },
+            div {
+                class: "pre-call-check-controls",
+                Button {
+                    text: get_local_text("uplink.cancel"),
+                    aria_label: "pre-call-check-cancel".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| cx.props.oncancel.call(()),
+                },
+                Button {
+                    icon: Icon::Phone,
+                    text: get_local_text(if summary_status == CheckStatus::Failed {
+                        "pre-call.join-anyway"
+                    } else {
+                        "pre-call.join"
+                    }),
+                    aria_label: "pre-call-check-join".into(),
+                    appearance: if summary_status == CheckStatus::Failed {
+                        Appearance::Danger
+                    } else {
+                        Appearance::Success
+                    },
+                    onpress: move |_| {
+                        if *dont_show_again.get() {
+                            state
+                                .write()
+                                .mutate(Action::Config(ConfigAction::SetPreCallCheck(false)));
+                        }
+                        cx.props.onjoin.call(());
+                    },
+                }
+            }
+        }
+    }))
+}
+
+// runs the microphone or speaker test. returns whether any sound was measured, None if the test couldn't run
+async fn audio_test(
+    cmd: impl FnOnce(oneshot::Sender<UnboundedReceiver<AudioTestEvent>>) -> BlinkCmd,
+) -> Option<bool> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = WARP_CMD_CH.tx.send(WarpCmd::Blink(cmd(tx))) {
+        log::error!("failed to send blink command: {e}");
+        return None;
+    }
+    let mut ch = rx.await.ok()?;
+    let mut heard = false;
+    while let Some(evt) = ch.recv().await {
+        match evt {
+            AudioTestEvent::Done => break,
+            AudioTestEvent::Output { loudness } => heard |= loudness as f32 > 0.0,
+            _ => {}
+        }
+    }
+    Some(heard)
+}
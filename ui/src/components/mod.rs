@@ -2,11 +2,20 @@ pub mod community;
 pub mod crop_image_tool;
 pub mod debug_logger;
 pub mod emoji_group;
+pub mod extension_menu_items;
 pub mod file_transfer;
 pub mod files;
 pub mod friends;
+pub mod live_regions;
+pub mod lock_screen;
 pub mod media;
+pub mod onboarding;
+pub mod profile_details;
+pub mod profiling_overlay;
+pub mod quick_switcher;
+pub mod select_chats;
 pub mod settings;
 pub mod shortcuts;
+pub mod status_bar;
 pub mod toast;
 pub mod topbar;
@@ -0,0 +1,224 @@
+//! The wizard shown once an account was created, see `OnboardingStep`. The username, picture and
+//! recovery phrase come before it on the pages of the login, which show the same step dots.
+
+mod tour;
+
+use common::icons::outline::Shape as Icon;
+use common::language::get_local_text;
+use common::sounds;
+use common::state::configuration::ConfigAction;
+use common::state::onboarding::OnboardingStep;
+use common::state::utils::get_available_themes;
+use common::state::{Action, State};
+use dioxus::prelude::*;
+use kit::elements::{button::Button, label::Label, select::Select, switch::Switch, Appearance};
+
+use crate::components::friends::add::AddFriend;
+use crate::components::settings::SettingSection;
+
+pub use tour::{Tour, TOUR_STOPS};
+
+/// The steps on the pages of the login: the profile and the recovery phrase
+pub const ACCOUNT_STEPS: usize = 2;
+pub const STEPS: usize = ACCOUNT_STEPS + OnboardingStep::WIZARD.len();
+
+#[derive(Props, PartialEq)]
+pub struct StepDotsProps {
+    current: usize,
+}
+
+/// Where the user is among all the steps of the onboarding
+#[allow(non_snake_case)]
+pub fn StepDots(cx: Scope<StepDotsProps>) -> Element {
+    let current = cx.props.current;
+    let step_number = current + 1;
+    cx.render(rsx!(div {
+        class: "onboarding-steps",
+        role: "progressbar",
+        aria_label: "onboarding-steps",
+        aria_valuemin: "1",
+        aria_valuemax: "{STEPS}",
+        aria_valuenow: "{step_number}",
+        (0..STEPS).map(|step| rsx!(span {
+            key: "{step}",
+            class: format_args!(
+                "onboarding-step {}",
+                if step == current { "current" } else if step < current { "done" } else { "" }
+            ),
+        }))
+    }))
+}
+
+// covers the app until the wizard is done or skipped. the tour is drawn over the app instead
+#[allow(non_snake_case)]
+pub fn Onboarding(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let Some(step) = state.read().ui.onboarding else {
+        return cx.render(rsx!(()));
+    };
+    let Some(index) = step.wizard_index() else {
+        return cx.render(rsx!(Tour {}));
+    };
+
+    let go_to = move |step: Option<OnboardingStep>| {
+        state.write().mutate(Action::SetOnboardingStep(step));
+    };
+    let (title, description) = match step {
+        OnboardingStep::Notifications => (
+            "onboarding.notifications",
+            "onboarding.notifications-description",
+        ),
+        OnboardingStep::Theme => ("onboarding.theme", "onboarding.theme-description"),
+        OnboardingStep::AddFriend => ("onboarding.add-friend", "onboarding.add-friend-description"),
+        _ => ("onboarding.tour", "onboarding.tour-description"),
+    };
+
+    cx.render(rsx!(div {
+        id: "onboarding",
+        aria_label: "onboarding",
+        div {
+            class: "onboarding-content",
+            role: "dialog",
+            aria_modal: "true",
+            StepDots {
+                current: ACCOUNT_STEPS + index,
+            },
+            Label {
+                text: get_local_text(title),
+            },
+            p {
+                class: "instructions",
+                get_local_text(description)
+            },
+            div {
+                class: "onboarding-body",
+                match step {
+                    OnboardingStep::Notifications => rsx!(NotificationsStep {}),
+                    OnboardingStep::Theme => rsx!(ThemeStep {}),
+                    OnboardingStep::AddFriend => rsx!(AddFriend {}),
+                    _ => rsx!(()),
+                }
+            },
+            div {
+                class: "onboarding-controls",
+                step.previous().map(|previous| rsx!(Button {
+                    text: get_local_text("uplink.go-back"),
+                    aria_label: "onboarding-back".into(),
+                    icon: Icon::ChevronLeft,
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| go_to(Some(previous)),
+                })),
+                if step == OnboardingStep::TourOffer {
+                    rsx!(
+                        Button {
+                            text: get_local_text("onboarding.skip-tour"),
+                            aria_label: "onboarding-skip-tour".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| go_to(None),
+                        },
+                        Button {
+                            text: get_local_text("onboarding.start-tour"),
+                            aria_label: "onboarding-start-tour".into(),
+                            icon: Icon::Sparkles,
+                            onpress: move |_| go_to(step.next(TOUR_STOPS)),
+                        }
+                    )
+                } else {
+                    rsx!(
+                        Button {
+                            text: get_local_text("onboarding.skip"),
+                            aria_label: "onboarding-skip".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| go_to(None),
+                        },
+                        Button {
+                            text: get_local_text("onboarding.next"),
+                            aria_label: "onboarding-next".into(),
+                            icon: Icon::ChevronRight,
+                            onpress: move |_| go_to(step.next(TOUR_STOPS)),
+                        }
+                    )
+                }
+            }
+        }
+    }))
+}
+
+#[allow(non_snake_case)]
+fn NotificationsStep(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let notifications = state.read().configuration.notifications;
+    let flip = move |action: ConfigAction| {
+        if state.read().configuration.audiovideo.interface_sounds {
+            sounds::Play(sounds::Sounds::Flip);
+        }
+        state.write().mutate(Action::Config(action));
+    };
+
+    cx.render(rsx!(
+        SettingSection {
+            aria_label: "onboarding-notifications-section".into(),
+            section_label: get_local_text("settings-notifications.enabled"),
+            section_description: get_local_text("settings-notifications.enabled-description"),
+            Switch {
+                active: notifications.enabled,
+                onflipped: move |e| flip(ConfigAction::SetNotificationsEnabled(e)),
+            }
+        },
+        SettingSection {
+            aria_label: "onboarding-messages-notifications-section".into(),
+            section_label: get_local_text("messages"),
+            section_description: get_local_text("settings-notifications.messages-description"),
+            Switch {
+                active: notifications.enabled && notifications.messages_notifications,
+                disabled: !notifications.enabled,
+                onflipped: move |e| flip(ConfigAction::SetMessagesNotificationsEnabled(e)),
+            }
+        },
+        SettingSection {
+            aria_label: "onboarding-friends-notifications-section".into(),
+            section_label: get_local_text("friends"),
+            section_description: get_local_text("settings-notifications.friends-description"),
+            no_border: true,
+            Switch {
+                active: notifications.enabled && notifications.friends_notifications,
+                disabled: !notifications.enabled,
+                onflipped: move |e| flip(ConfigAction::SetFriendsNotificationsEnabled(e)),
+            }
+        },
+    ))
+}
+
+#[allow(non_snake_case)]
+fn ThemeStep(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let themes = use_future(cx, (), |_| async move { get_available_themes() });
+    let themes = themes.value().cloned().unwrap_or_default();
+    let current = state
+        .read()
+        .ui
+        .theme
+        .as_ref()
+        .map(|theme| theme.name.clone())
+        .unwrap_or_else(|| "Default".into());
+    let mut options = vec!["Default".to_string()];
+    options.extend(themes.iter().map(|theme| theme.name.clone()));
+    let loaded = themes.len();
+
+    cx.render(rsx!(SettingSection {
+        aria_label: "onboarding-theme-section".into(),
+        section_label: get_local_text("settings-general.theme"),
+        section_description: get_local_text("settings-general.theme-description"),
+        no_border: true,
+        Select {
+            // the themes are read after the first render
+            key: "{loaded}",
+            initial_value: current,
+            options: options,
+            onselect: move |value: String| {
+                let theme = themes.iter().find(|theme| theme.name == value).cloned();
+                state.write().mutate(Action::SetTheme(theme));
+            }
+        }
+    }))
+}
@@ -0,0 +1,185 @@
+//! Coach marks which walk through the main layouts. Every stop opens its layout and points at a
+//! part of it, the rest of the app is dimmed.
+
+use std::time::Duration;
+
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::{onboarding::OnboardingStep, Action, State};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use kit::elements::{button::Button, Appearance};
+use tracing::log;
+
+use crate::UplinkRoute;
+
+const TARGET_SCRIPT: &str = r#"
+    const target = document.querySelector('$SELECTOR')
+    if (!target) {
+        return null
+    }
+    const rect = target.getBoundingClientRect()
+    return [rect.left, rect.top, rect.width, rect.height]
+"#;
+
+// the layout of a stop is rendered before its target is looked up
+const LAYOUT_DELAY: Duration = Duration::from_millis(150);
+// room between the target and the card
+const CARD_GAP: f64 = 12.0;
+
+pub const TOUR_STOPS: usize = 5;
+
+struct TourStop {
+    route: UplinkRoute,
+    selector: &'static str,
+    // the key of the title, the text is under `{key}-description`
+    key: &'static str,
+}
+
+fn tour_stops() -> [TourStop; TOUR_STOPS] {
+    [
+        TourStop {
+            route: UplinkRoute::ChatLayout {},
+            selector: "[aria-label=\"button-nav\"]",
+            key: "onboarding.tour-nav",
+        },
+        TourStop {
+            route: UplinkRoute::ChatLayout {},
+            selector: "#chats",
+            key: "onboarding.tour-chats",
+        },
+        TourStop {
+            route: UplinkRoute::FriendsLayout {},
+            selector: ".add-friend",
+            key: "onboarding.tour-friends",
+        },
+        TourStop {
+            route: UplinkRoute::FilesLayout {},
+            selector: "#files-layout",
+            key: "onboarding.tour-files",
+        },
+        TourStop {
+            route: UplinkRoute::SettingsLayout {},
+            selector: "#settings-layout",
+            key: "onboarding.tour-settings",
+        },
+    ]
+}
+
+#[allow(non_snake_case)]
+pub fn Tour(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let navigator = use_navigator(cx);
+    let eval = use_eval(cx);
+    let stop = match state.read().ui.onboarding {
+        Some(OnboardingStep::Tour(stop)) => stop.min(TOUR_STOPS - 1),
+        _ => 0,
+    };
+    // left, top, width and height of the target. None while it is looked up or if it isn't shown
+    let target: &UseState<Option<(f64, f64, f64, f64)>> = use_state(cx, || None);
+
+    use_future(cx, &stop, |stop| {
+        to_owned![eval, navigator, target];
+        async move {
+            target.set(None);
+            let TourStop {
+                route, selector, ..
+            } = tour_stops()
+                .into_iter()
+                .nth(stop)
+                .expect("stop is in the tour");
+            navigator.replace(route);
+            tokio::time::sleep(LAYOUT_DELAY).await;
+            let Ok(rect) = eval(&TARGET_SCRIPT.replace("$SELECTOR", selector)) else {
+                return;
+            };
+            match rect.join().await {
+                Ok(rect) => target.set(serde_json::from_value(rect).unwrap_or_default()),
+                Err(e) => log::error!("failed to find the target of the tour: {e:?}"),
+            }
+        }
+    });
+
+    let go_to = move |step| state.write().mutate(Action::SetOnboardingStep(step));
+    let current = state.read().ui.onboarding;
+    let next = current.and_then(|step| step.next(TOUR_STOPS));
+    let previous = current.and_then(|step| step.previous());
+    let key = tour_stops()[stop].key;
+    let title = get_local_text(key);
+    let text = get_local_text(&format!("{key}-description"));
+    let progress = get_local_text_with_args(
+        "onboarding.tour-progress",
+        vec![
+            ("current", (stop + 1).to_string()),
+            ("total", TOUR_STOPS.to_string()),
+        ],
+    );
+    // the card goes under the target, or next to it if the target is too tall. the css keeps it
+    // in the window
+    let (spotlight, card) = match *target.get() {
+        Some((left, top, width, height)) => {
+            let (card_left, card_top) = if height > 300.0 {
+                (left + width + CARD_GAP, top + CARD_GAP)
+            } else {
+                (left, top + height + CARD_GAP)
+            };
+            (
+                format!("left: {left}px; top: {top}px; width: {width}px; height: {height}px;"),
+                format!("--card-left: {card_left}px; --card-top: {card_top}px;"),
+            )
+        }
+        None => (String::new(), String::new()),
+    };
+
+    cx.render(rsx!(div {
+        id: "tour",
+        aria_label: "tour",
+        class: if target.get().is_some() { "" } else { "centered" },
+        target.get().is_some().then(|| rsx!(div {
+            class: "tour-spotlight",
+            style: "{spotlight}",
+        })),
+        div {
+            class: "tour-card",
+            role: "dialog",
+            aria_labelledby: "tour-title",
+            aria_describedby: "tour-text",
+            style: "{card}",
+            p {
+                class: "tour-progress",
+                "{progress}"
+            },
+            h3 {
+                id: "tour-title",
+                "{title}"
+            },
+            p {
+                id: "tour-text",
+                "{text}"
+            },
+            div {
+                class: "tour-controls",
+                Button {
+                    text: get_local_text("onboarding.end-tour"),
+                    aria_label: "tour-end".into(),
+                    appearance: Appearance::Secondary,
+                    small: true,
+                    onpress: move |_| go_to(None),
+                },
+                previous.map(|previous| rsx!(Button {
+                    icon: Icon::ChevronLeft,
+                    aria_label: "tour-back".into(),
+                    appearance: Appearance::Secondary,
+                    small: true,
+                    onpress: move |_| go_to(Some(previous)),
+                })),
+                Button {
+                    text: get_local_text(if next.is_some() { "onboarding.next" } else { "onboarding.finish" }),
+                    aria_label: "tour-next".into(),
+                    small: true,
+                    onpress: move |_| go_to(next),
+                },
+            }
+        }
+    }))
+}
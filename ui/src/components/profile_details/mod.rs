@@ -0,0 +1,59 @@
+use common::icons::outline::Shape as Icon;
+use common::icons::Icon as IconElement;
+use common::state::State;
+use dioxus::prelude::*;
+use tracing::log;
+use warp::crypto::DID;
+
+#[derive(PartialEq, Props)]
+pub struct Props {
+    did_key: DID,
+}
+
+// the pronouns, about text and links of someone, below the banner of their profile popover
+#[allow(non_snake_case)]
+pub fn ProfileDetails(cx: Scope<Props>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let profile = state
+        .read()
+        .get_identity(&cx.props.did_key)
+        .unwrap_or_default()
+        .extended_profile();
+    if profile.is_empty() {
+        return None;
+    }
+
+    cx.render(rsx!(
+        div {
+            class: "profile-details",
+            aria_label: "profile-details",
+            (!profile.pronouns.is_empty()).then(|| rsx!(p {
+                class: "profile-pronouns",
+                aria_label: "profile-pronouns",
+                "{profile.pronouns}"
+            })),
+            (!profile.about.is_empty()).then(|| rsx!(p {
+                class: "profile-about",
+                aria_label: "profile-about",
+                "{profile.about}"
+            })),
+            profile.links.into_iter().map(|link| {
+                let shown = link.trim_start_matches("https://").trim_start_matches("http://").to_string();
+                rsx!(div {
+                    key: "{link}",
+                    class: "profile-link",
+                    aria_label: "profile-link",
+                    onclick: move |_| {
+                        if let Err(e) = open::that(&link) {
+                            log::error!("failed to open {link}: {e}");
+                        }
+                    },
+                    IconElement {
+                        icon: Icon::Link,
+                    },
+                    span { "{shown}" }
+                })
+            })
+        }
+    ))
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use common::{
+    language::get_local_text,
+    state::State,
+    warp_runner::metrics::{self, CommandStats, HISTOGRAM_BUCKETS},
+};
+use dioxus::prelude::*;
+use humansize::{format_size, DECIMAL};
+use kit::elements::{button::Button, Appearance};
+
+use crate::utils::profiling;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_COMPONENTS: usize = 12;
+const MAX_COMMANDS: usize = 6;
+
+// stops counting renders once the overlay is closed
+struct RenderCounting;
+
+impl RenderCounting {
+    fn start() -> Self {
+        profiling::set_enabled(true);
+        Self
+    }
+}
+
+impl Drop for RenderCounting {
+    fn drop(&mut self) {
+        profiling::set_enabled(false);
+    }
+}
+
+#[derive(Default)]
+struct Snapshot {
+    // (component, renders, renders since the last refresh)
+    renders: Vec<(&'static str, u64, u64)>,
+    commands: Vec<CommandStats>,
+    memory: Option<usize>,
+}
+
+// shows where the time goes while the app is janky. toggled with a keybind in developer mode
+#[allow(non_snake_case)]
+pub fn ProfilingOverlay(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    cx.use_hook(RenderCounting::start);
+    let snapshot: &UseRef<Snapshot> = use_ref(cx, Snapshot::default);
+
+    use_future(cx, (), |_| {
+        to_owned![snapshot];
+        async move {
+            loop {
+                snapshot.with_mut(|snapshot| {
+                    let previous = std::mem::take(&mut snapshot.renders);
+                    snapshot.renders = profiling::render_counts()
+                        .into_iter()
+                        .take(MAX_COMPONENTS)
+                        .map(|(name, count)| {
+                            let before = previous
+                                .iter()
+                                .find(|(n, _, _)| *n == name)
+                                .map(|(_, count, _)| *count)
+                                .unwrap_or_default();
+                            (name, count, count.saturating_sub(before))
+                        })
+                        .collect();
+                    snapshot.commands =
+                        metrics::snapshot().into_iter().take(MAX_COMMANDS).collect();
+                    snapshot.memory = profiling::memory_usage();
+                });
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+            }
+        }
+    });
+
+    let snapshot = snapshot.read();
+    let memory = snapshot
+        .memory
+        .map(|bytes| format_size(bytes, DECIMAL))
+        .unwrap_or_else(|| "-".into());
+    let bucket_labels: Vec<String> = HISTOGRAM_BUCKETS
+        .iter()
+        .map(|bound| format!("≤{} ms", bound.as_millis()))
+        .chain(std::iter::once(format!(
+            ">{} ms",
+            HISTOGRAM_BUCKETS[HISTOGRAM_BUCKETS.len() - 1].as_millis()
+        )))
+        .collect();
+
+    cx.render(rsx!(
+        div {
+            id: "profiling-overlay",
+            aria_label: "profiling-overlay",
+            div {
+                class: "profiling-header",
+                span { get_local_text("profiling.title") },
+                Button {
+                    aria_label: "profiling-reset-button".into(),
+                    text: get_local_text("profiling.reset"),
+                    appearance: Appearance::Secondary,
+                    small: true,
+                    onpress: move |_| {
+                        profiling::reset();
+                        metrics::reset();
+                    },
+                },
+                Button {
+                    aria_label: "profiling-close-button".into(),
+                    text: get_local_text("profiling.close"),
+                    appearance: Appearance::Secondary,
+                    small: true,
+                    onpress: move |_| state.write().ui.show_profiling_overlay = false,
+                },
+            },
+            p {
+                class: "profiling-memory",
+                get_local_text("profiling.memory"),
+                " {memory}"
+            },
+            table {
+                class: "profiling-renders",
+                tr {
+                    th { get_local_text("profiling.component") },
+                    th { get_local_text("profiling.renders") },
+                    th { get_local_text("profiling.per-second") },
+                },
+                snapshot.renders.iter().map(|(name, count, recent)| rsx!(
+                    tr {
+                        key: "{name}",
+                        td { "{name}" },
+                        td { "{count}" },
+                        td { "{recent}" },
+                    }
+                ))
+            },
+            snapshot.commands.iter().map(|stats| {
+                let name = &stats.name;
+                let p90 = format!("{:.1} ms", stats.p90.as_secs_f64() * 1000.0);
+                let max = stats.histogram.iter().copied().max().unwrap_or_default().max(1);
+                rsx!(
+                    div {
+                        key: "{name}",
+                        class: "profiling-command",
+                        div {
+                            class: "profiling-command-name",
+                            span { "{name}" },
+                            span { class: "muted", "p90 {p90}" },
+                        },
+                        div {
+                            class: "profiling-histogram",
+                            stats.histogram.iter().zip(bucket_labels.iter()).map(|(count, label)| {
+                                let height = count * 100 / max;
+                                rsx!(
+                                    div {
+                                        class: "bar",
+                                        title: "{label}: {count}",
+                                        style: "height: {height}%",
+                                    }
+                                )
+                            })
+                        }
+                    }
+                )
+            })
+        }
+    ))
+}
@@ -0,0 +1,240 @@
+use std::str::FromStr;
+
+use common::icons::outline::Shape as Icon;
+use common::icons::Icon as IconElement;
+use common::language::get_local_text;
+use common::state::{
+    quick_switcher::{Candidate, Recents, Target},
+    ui::FilesIntent,
+    Action, State,
+};
+use common::warp_runner::{RayGunCmd, WarpCmd};
+use common::WARP_CMD_CH;
+use dioxus::prelude::*;
+use dioxus_html::input_data::keyboard_types::Code;
+use dioxus_router::prelude::use_navigator;
+use futures::{channel::oneshot, StreamExt};
+use kit::elements::input::{Input, Options};
+use tracing::log;
+use uuid::Uuid;
+use warp::crypto::DID;
+
+use crate::{components::settings::sidebar::Page, UplinkRoute};
+
+const MAX_RESULTS: usize = 8;
+// in the order of the settings sidebar
+const SETTINGS_PAGES: [&str; 14] = [
+    "profile",
+    "general",
+    "messages",
+    "privacy",
+    "audio",
+    "network",
+    "storage",
+    "devices",
+    "extensions",
+    "keybinds",
+    "accessibility",
+    "notifications",
+    "about",
+    "licenses",
+];
+
+// everything which can be picked. files and folders are the ones of the folder opened last
+fn candidates(state: &State) -> Vec<Candidate> {
+    let chats = state.chats_sidebar().into_iter().map(|chat| {
+        let name = state.chats().title(&chat).unwrap_or_else(|| {
+            State::join_usernames(&state.remove_self(&state.chat_participants(&chat)))
+        });
+        Candidate::new(Target::Chat(chat.id), name)
+    });
+    let friends = state
+        .friend_identities()
+        .into_iter()
+        .map(|friend| Candidate::new(Target::Friend(friend.did_key()), friend.username()));
+    let folders = state
+        .storage
+        .directories
+        .iter()
+        .map(|dir| Candidate::new(Target::Folder(dir.name()), dir.name()));
+    let files = state
+        .storage
+        .files
+        .iter()
+        .map(|file| Candidate::new(Target::File(file.id()), file.name()));
+    let new_folder = Candidate::new(Target::NewFolder, get_local_text("files.new-folder"));
+    let settings = SETTINGS_PAGES.iter().map(|page| {
+        Candidate::new(
+            Target::Settings(page.to_string()),
+            get_local_text(&format!("settings.{page}")),
+        )
+    });
+    chats
+        .chain(friends)
+        .chain(folders)
+        .chain(files)
+        .chain(std::iter::once(new_folder))
+        .chain(settings)
+        .collect()
+}
+
+fn icon_and_kind(target: &Target) -> (Icon, String) {
+    let (icon, kind) = match target {
+        Target::Chat(_) => (Icon::ChatBubbleBottomCenterText, "quick-switcher.chat"),
+        Target::Friend(_) => (Icon::User, "quick-switcher.friend"),
+        Target::Folder(_) => (Icon::Folder, "quick-switcher.folder"),
+        Target::File(_) => (Icon::DocumentText, "quick-switcher.file"),
+        Target::NewFolder => (Icon::FolderPlus, "quick-switcher.action"),
+        Target::Settings(_) => (Icon::Cog6Tooth, "quick-switcher.settings"),
+    };
+    (icon, get_local_text(kind))
+}
+
+// opened with Ctrl/Cmd + K. the arrow keys choose, Enter opens and Escape closes
+#[allow(non_snake_case)]
+pub fn QuickSwitcher(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let settings_page = use_shared_state::<Page>(cx)?;
+    let router = use_navigator(cx);
+    let recents = use_ref(cx, Recents::load);
+    let query = use_state(cx, String::new);
+    let selected = use_state(cx, || 0_usize);
+    // a conversation with a friend which was just created
+    let chat_with: &UseState<Option<Uuid>> = use_state(cx, || None);
+
+    let close = move || state.write().ui.show_quick_switcher = false;
+    let open_chat = move |id: Uuid| {
+        state.write().mutate(Action::ChatWith(&id, true));
+        router.replace(UplinkRoute::ChatLayout {});
+        close();
+    };
+    let open_files = move |intent: FilesIntent| {
+        state.write().ui.set_files_intent(intent);
+        router.replace(UplinkRoute::FilesLayout {});
+        close();
+    };
+
+    if let Some(id) = *chat_with.get() {
+        chat_with.set(None);
+        open_chat(id);
+    }
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<DID>| {
+        to_owned![chat_with];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some(recipient) = rx.next().await {
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::CreateConversation {
+                    recipient,
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send warp command: {}", e);
+                    continue;
+                }
+                match rx.await {
+                    Ok(Ok(id)) => chat_with.set(Some(id)),
+                    Ok(Err(e)) => log::error!("failed to create conversation: {}", e),
+                    Err(e) => log::error!("failed to create conversation: {}", e),
+                }
+            }
+        }
+    });
+
+    let open = move |target: Target| {
+        recents.write_silent().record(target.clone());
+        match target {
+            Target::Chat(id) => open_chat(id),
+            Target::Friend(did) => {
+                let chat = state.read().get_chat_with_friend(did.clone());
+                match chat {
+                    Some(chat) => open_chat(chat.id),
+                    None => ch.send(did),
+                }
+            }
+            Target::Folder(name) => open_files(FilesIntent::OpenFolder(name)),
+            Target::File(id) => open_files(FilesIntent::PreviewFile(id)),
+            Target::NewFolder => open_files(FilesIntent::NewFolder),
+            Target::Settings(page) => {
+                settings_page
+                    .write()
+                    .set(Page::from_str(&page).unwrap_or(Page::General));
+                router.replace(UplinkRoute::SettingsLayout {});
+                close();
+            }
+        }
+    };
+
+    let results: Vec<Candidate> = recents
+        .read()
+        .rank(query.get(), candidates(&state.read()))
+        .into_iter()
+        .take(MAX_RESULTS)
+        .collect();
+    let count = results.len();
+    let current = (*selected.get()).min(count.saturating_sub(1));
+    let picked = results.get(current).map(|c| c.target.clone());
+
+    cx.render(rsx!(
+        div {
+            id: "quick-switcher",
+            aria_label: "quick-switcher",
+            onclick: move |_| close(),
+            div {
+                class: "quick-switcher-content",
+                onclick: move |e| e.stop_propagation(),
+                onkeydown: move |e: Event<KeyboardData>| match e.code() {
+                    Code::ArrowDown if count > 0 => selected.set((current + 1) % count),
+                    Code::ArrowUp if count > 0 => selected.set((current + count - 1) % count),
+                    Code::Escape => close(),
+                    _ => {}
+                },
+                Input {
+                    focus: true,
+                    icon: Icon::MagnifyingGlass,
+                    aria_label: "quick-switcher-input".into(),
+                    placeholder: get_local_text("quick-switcher.placeholder"),
+                    options: Options {
+                        clear_on_submit: false,
+                        ..Default::default()
+                    },
+                    onchange: move |(v, _): (String, bool)| {
+                        query.set(v);
+                        selected.set(0);
+                    },
+                    onreturn: move |_| {
+                        if let Some(target) = picked.clone() {
+                            open(target);
+                        }
+                    },
+                },
+                (count == 0).then(|| rsx!(p {
+                    class: "quick-switcher-empty",
+                    get_local_text("quick-switcher.no-results")
+                })),
+                results.into_iter().enumerate().map(|(idx, candidate)| {
+                    let (icon, kind) = icon_and_kind(&candidate.target);
+                    let target = candidate.target;
+                    rsx!(div {
+                        key: "{idx}",
+                        class: format_args!("quick-switcher-item {}", if idx == current { "selected" } else { "" }),
+                        aria_label: "quick-switcher-item",
+                        onmouseenter: move |_| selected.set(idx),
+                        onclick: move |_| open(target.clone()),
+                        IconElement {
+                            icon: icon,
+                        },
+                        span {
+                            class: "quick-switcher-label",
+                            "{candidate.label}"
+                        },
+                        span {
+                            class: "quick-switcher-kind",
+                            "{kind}"
+                        }
+                    })
+                })
+            }
+        }
+    ))
+}
@@ -0,0 +1,166 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::{get_local_text, get_local_text_with_args},
+    state::{Identity, State},
+};
+use dioxus::prelude::*;
+use kit::{
+    components::{
+        message::format_text, user::User, user_image::UserImage, user_image_group::UserImageGroup,
+    },
+    elements::{button::Button, checkbox::Checkbox, label::Label, Appearance},
+    layout::modal::Modal,
+};
+use tracing::log;
+use uuid::Uuid;
+use warp::raygun::{self, ConversationType};
+
+use crate::utils::build_participants;
+
+#[derive(Props)]
+pub struct Props<'a> {
+    aria_label: String,
+    button_text: String,
+    button_icon: Icon,
+    button_aria_label: String,
+    #[props(!optional)]
+    excluded_chat: Option<Uuid>,
+    onclose: EventHandler<'a, ()>,
+    // called with the chats which were picked
+    onsubmit: EventHandler<'a, Vec<Uuid>>,
+}
+
+// lets the user pick the chats something is sent to, like a friend which is shared or a message which is forwarded
+#[allow(non_snake_case)]
+pub fn SelectChatsModal<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let chats_selected = use_ref(cx, Vec::new);
+    let chats: Vec<_> = state
+        .read()
+        .chats_sidebar()
+        .iter()
+        .filter(|c| {
+            cx.props
+                .excluded_chat
+                .map(|id| !c.id.eq(&id))
+                .unwrap_or(true)
+        })
+        .cloned()
+        .collect();
+
+    cx.render(rsx!(Modal {
+        open: true,
+        onclose: move |_| cx.props.onclose.call(()),
+        show_close_button: false,
+        transparent: false,
+        close_on_click_inside_modal: false,
+        dont_pad: true,
+        div {
+            aria_label: "{cx.props.aria_label}",
+            class: "modal-share-friends",
+            div {
+                class: "modal-share-friends-header",
+                padding: "12px",
+                Label {
+                    aria_label: "select-chats-header".into(),
+                    text: get_local_text("friends.select-chat"),
+                },
+                div {
+                    class: "send-chat-button",
+                    Button {
+                        text: cx.props.button_text.clone(),
+                        icon: cx.props.button_icon,
+                        aria_label: cx.props.button_aria_label.clone(),
+                        appearance: Appearance::Secondary,
+                        disabled: chats_selected.read().is_empty(),
+                        onpress: move |_| {
+                            cx.props.onsubmit.call(chats_selected.read().clone());
+                            cx.props.onclose.call(());
+                        },
+                    },
+                }
+            }
+            chats.is_empty().then(||{
+                rsx!(div {
+                    class: "modal-share-friend-empty",
+                    aria_label: "modal-select-chats-empty",
+                    get_local_text("messages.no-chats")
+                })
+            }),
+            chats.iter().map(|chat| {
+                let id = chat.id;
+                let participants = state.read().chat_participants(chat);
+                let other_participants =  state.read().remove_self(&participants);
+                let user: Identity = other_participants.first().cloned().unwrap_or_default();
+                let platform = user.platform().into();
+                // todo: how to tell who is participating in a group chat if the chat has a conversation_name?
+                let participants_name = match state.read().chats().title(chat) {
+                    Some(name) => name,
+                    None => State::join_usernames(&other_participants)
+                };
+                let unwrapped_message = match chat.messages.iter().last() {Some(m) => m.inner.clone(),None => raygun::Message::default()};
+                let subtext_val = match unwrapped_message.lines().iter().map(|x| x.trim()).find(|x| !x.is_empty()) {
+                    Some(v) => format_text(v, state.read().ui.should_transform_markdown_text(), state.read().ui.should_transform_ascii_emojis(), Some((&state.read(), &chat.id, true))),
+                    _ => match &unwrapped_message.attachments()[..] {
+                        [] => get_local_text("sidebar.chat-new"),
+                        [ file ] => file.name(),
+                        _ => match participants.iter().find(|p| p.did_key()  == unwrapped_message.sender()).map(|x| x.username()) {
+                            Some(name) => get_local_text_with_args("sidebar.subtext", vec![("user", name)]),
+                            None => {
+                                log::error!("error calculating subtext for sidebar chat");
+                                // Still return default message
+                                get_local_text("sidebar.chat-new")
+                            }
+                        }
+                    }
+                };
+                let selected = chats_selected.read().contains(&id);
+                let toggle = move || {
+                    chats_selected.with_mut(|v: &mut Vec<Uuid>| {
+                        if !selected {
+                            v.push(id);
+                        } else {
+                            v.retain(|c| !c.eq(&id));
+                        }
+                    });
+                };
+                rsx!(div {
+                    class: format_args!("modal-share-friend {}", if selected {"share-friend-selected"} else {""}),
+                    User {
+                        aria_label: participants_name.clone(),
+                        username: participants_name,
+                        subtext: subtext_val,
+                        timestamp: raygun::Message::default().date(),
+                        active: false,
+                        user_image: cx.render(rsx!(
+                            div {
+                                class: "modal-share-friend-image-group",
+                                Checkbox {
+                                    aria_label: "chat-to-select-checkbox".into(),
+                                    disabled: false,
+                                    width: "1em".into(),
+                                    height: "1em".into(),
+                                    is_checked: selected,
+                                    on_click: move |_| toggle(),
+                                },
+                                match chat.conversation_type {
+                                    ConversationType::Direct => rsx!(UserImage {
+                                        platform: platform,
+                                        status:  user.identity_status().into(),
+                                        image: user.avatar(),
+                                        typing: false,
+                                    }),
+                                    _ => rsx!(UserImageGroup {
+                                        participants: build_participants(&participants),
+                                        typing: false,
+                                    })
+                                }
+                            }
+                        )),
+                        onpress: move |_| toggle(),
+                    }
+                })
+            })
+        }
+    }))
+}
@@ -22,8 +22,11 @@ pub enum Page {
     Extensions,
     General,
     Messages,
+    Network,
+    Storage,
+    Devices,
     //Files,
-    //Privacy,
+    Privacy,
     Keybinds,
     Profile,
     Notifications,
@@ -57,8 +60,11 @@ impl FromStr for Page {
             //"files" => Ok(Page::Files),
             "general" => Ok(Page::General),
             "messages" => Ok(Page::Messages),
+            "network" => Ok(Page::Network),
+            "storage" => Ok(Page::Storage),
+            "devices" => Ok(Page::Devices),
             "keybinds" => Ok(Page::Keybinds),
-            //"privacy" => Ok(Page::Privacy),
+            "privacy" => Ok(Page::Privacy),
             "profile" => Ok(Page::Profile),
             "notifications" => Ok(Page::Notifications),
             "accessibility" => Ok(Page::Accessibility),
@@ -116,12 +122,30 @@ pub fn Sidebar<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
         icon: Icon::MusicalNote,
         ..UIRoute::default()
     };
-    /*let privacy = UIRoute {
+    let privacy = UIRoute {
         to: "privacy",
         name: get_local_text("settings.privacy"),
         icon: Icon::LockClosed,
         ..UIRoute::default()
-    };*/
+    };
+    let network = UIRoute {
+        to: "network",
+        name: get_local_text("settings.network"),
+        icon: Icon::GlobeAlt,
+        ..UIRoute::default()
+    };
+    let storage = UIRoute {
+        to: "storage",
+        name: get_local_text("settings.storage"),
+        icon: Icon::CircleStack,
+        ..UIRoute::default()
+    };
+    let devices = UIRoute {
+        to: "devices",
+        name: get_local_text("settings.devices"),
+        icon: Icon::DevicePhoneMobile,
+        ..UIRoute::default()
+    };
     /*let files = UIRoute {
         to: "files",
         name: get_local_text("settings.files"),
@@ -173,8 +197,11 @@ pub fn Sidebar<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
 
     let mut routes = vec![profile, general, messages];
     // To control order of routes, add them here.
-    // routes.push(privacy);
+    routes.push(privacy);
     routes.push(audio);
+    routes.push(network);
+    routes.push(storage);
+    routes.push(devices);
     // routes.push(files);
     routes.push(extensions);
     routes.push(keybinds);
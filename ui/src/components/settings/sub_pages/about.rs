@@ -1,22 +1,19 @@
-use std::process::Command;
-
 use common::get_images_dir;
 use common::language::get_local_text;
 use common::state::{Action, ToastNotification};
 use common::{icons::outline::Shape as Icon, state::State};
 use dioxus::prelude::*;
-use dioxus_desktop::use_window;
 use futures::StreamExt;
 use kit::elements::{button::Button, Appearance};
 
 use tracing::log;
 
-use crate::get_download_modal;
 use crate::utils::auto_updater::{DownloadProgress, DownloadState, SoftwareDownloadCmd};
 use crate::{
     components::settings::SettingSection,
     utils::{self, auto_updater::GitHubRelease},
 };
+use crate::{get_download_modal, get_install_modal, start_update_download};
 
 #[allow(non_snake_case)]
 pub fn AboutPage(cx: Scope) -> Element {
@@ -26,12 +23,16 @@ pub fn AboutPage(cx: Scope) -> Element {
     let download_state = use_shared_state::<DownloadState>(cx)?;
     let update_button_loading = use_state(cx, || false);
     let download_available: &UseState<Option<GitHubRelease>> = use_state(cx, || None);
-    let desktop = use_window(cx);
 
     let click_count = use_state(cx, || 0);
 
     let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<()>| {
-        to_owned![download_available, update_button_loading, state];
+        to_owned![
+            download_available,
+            update_button_loading,
+            state,
+            download_state
+        ];
         async move {
             while rx.next().await.is_some() {
                 match utils::auto_updater::check_for_release().await {
@@ -46,6 +47,9 @@ pub fn AboutPage(cx: Scope) -> Element {
                                 ),
                             ))
                         }
+                        if let Some(release) = &opt {
+                            download_state.write().release = Some(release.clone());
+                        }
                         download_available.set(opt);
                     }
                     Err(e) => {
@@ -74,7 +78,7 @@ pub fn AboutPage(cx: Scope) -> Element {
         }
     });
 
-    let _download_ch = use_coroutine_handle::<SoftwareDownloadCmd>(cx)?;
+    let download_ch = use_coroutine_handle::<SoftwareDownloadCmd>(cx)?;
 
     let opt = download_available.get().clone();
     let stage = download_state.read().stage;
@@ -127,9 +131,7 @@ pub fn AboutPage(cx: Scope) -> Element {
                     aria_label: "check-for-updates-button".into(),
                     appearance: Appearance::Secondary,
                     icon: Icon::ArrowDown,
-                    onpress: move |_| {
-                        download_state.write().stage = DownloadProgress::PickFolder;
-                    }
+                    onpress: move |_| start_update_download(download_state, download_ch),
                 })
             }
             DownloadProgress::PickFolder => rsx!(get_download_modal {
@@ -143,7 +145,7 @@ pub fn AboutPage(cx: Scope) -> Element {
                 //     download_ch.send(SoftwareDownloadCmd(dest));
                 // }
             }),
-            DownloadProgress::_Pending => {
+            DownloadProgress::Pending => {
                 rsx!(Button {
                     key: "{pending_key}",
                     text: format!("{}%", download_state.read().progress as u32),
@@ -162,31 +164,15 @@ pub fn AboutPage(cx: Scope) -> Element {
                     appearance: Appearance::Secondary,
                     icon: Icon::ArrowDown,
                     onpress: move |_| {
-                        // be sure to update this before closing the app
-                        state.write().mutate(Action::DismissUpdate);
-                        if let Some(dest) = download_state.read().destination.clone() {
-                            std::thread::spawn(move || {
-                                let cmd = if cfg!(target_os = "windows") {
-                                    "explorer"
-                                } else if cfg!(target_os = "linux") {
-                                    "xdg-open"
-                                } else if cfg!(target_os = "macos") {
-                                    "open"
-                                } else {
-                                    eprintln!("unknown OS type. failed to open files browser");
-                                    return;
-                                };
-                                Command::new(cmd).arg(dest).spawn().unwrap();
-                            });
-                            desktop.close();
-                        } else {
-                            log::error!("attempted to download update without download location");
-                        }
-                        download_state.write().destination = None;
-                        download_state.write().stage = DownloadProgress::Idle;
+                        download_state.write().stage = DownloadProgress::ConfirmInstall;
                     }
                 })
             }
+            DownloadProgress::ConfirmInstall => rsx!(get_install_modal {
+                on_dismiss: move |_| {
+                    download_state.write().stage = DownloadProgress::Finished;
+                },
+            }),
         },
     }));
 
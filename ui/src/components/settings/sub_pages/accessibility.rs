@@ -1,17 +1,28 @@
 use common::language::get_local_text;
-use common::state::{action::ConfigAction, Action, State};
+use common::state::{action::ConfigAction, utils::get_available_themes, Action, State};
 use dioxus::prelude::*;
 use kit::elements::switch::Switch;
 use tracing::log;
 
 use crate::components::settings::SettingSection;
 
+// the file of the bundled high contrast theme
+const HIGH_CONTRAST_THEME: &str = "high-contrast.scss";
+
 #[allow(non_snake_case)]
 pub fn AccessibilitySettings(cx: Scope) -> Element {
     let state = use_shared_state::<State>(cx)?;
 
     log::trace!("Accessibility settings page rendered.");
 
+    let high_contrast = state
+        .read()
+        .ui
+        .theme
+        .as_ref()
+        .map(|theme| theme.filename.ends_with(HIGH_CONTRAST_THEME))
+        .unwrap_or_default();
+
     cx.render(rsx!(
         div {
             id: "settings-general",
@@ -30,6 +41,36 @@ pub fn AccessibilitySettings(cx: Scope) -> Element {
                     }
                 },
             },
+            SettingSection {
+                aria_label: "high-contrast-section".into(),
+                section_label: get_local_text("settings-accessibility.high-contrast"),
+                section_description: get_local_text("settings-accessibility.high-contrast-description"),
+                Switch {
+                    active: high_contrast,
+                    onflipped: move |e| {
+                        let theme = e.then(|| {
+                            get_available_themes()
+                                .into_iter()
+                                .find(|theme| theme.filename.ends_with(HIGH_CONTRAST_THEME))
+                        }).flatten();
+                        if e && theme.is_none() {
+                            log::warn!("the high contrast theme is missing");
+                        }
+                        state.write().mutate(Action::SetTheme(theme));
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "reduced-motion-section".into(),
+                section_label: get_local_text("settings-accessibility.reduced-motion"),
+                section_description: get_local_text("settings-accessibility.reduced-motion-description"),
+                Switch {
+                    active: state.read().configuration.general.reduced_motion,
+                    onflipped: move |e| {
+                        state.write().mutate(Action::Config(ConfigAction::SetReducedMotion(e)));
+                    }
+                }
+            },
         }
     ))
 }
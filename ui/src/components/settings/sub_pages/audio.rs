@@ -12,13 +12,19 @@ use kit::elements::switch::Switch;
 use tracing::log;
 use warp::blink::AudioTestEvent;
 
+use crate::components::media::camera_preview::CameraPreview;
 use crate::components::settings::{SettingSection, SettingSectionSimple};
+use crate::utils::camera::{list_cameras, Camera};
 use common::state::{action::ConfigAction, Action, State};
 use common::{sounds, WARP_CMD_CH};
 
 // pub const VOL_MIN: f32 = 0.0;
 // pub const VOL_MAX: f32 = 200.0;
 pub const MAX_VOLUME: f32 = 127_f32 * 0.01;
+// the loudest moment of a microphone test, on the scale of the volume indicator, which tells
+// whether the microphone is too quiet or too loud
+const QUIET_LEVEL: u8 = 10;
+const LOUD_LEVEL: u8 = 95;
 
 enum AudioCmd {
     FetchOutputDevices,
@@ -39,6 +45,10 @@ pub fn AudioSettings(cx: Scope) -> Element {
 
     let speaker_volume = use_ref(cx, || 0);
     let microphone_volume = use_ref(cx, || 0);
+    let cameras: &UseRef<Vec<Camera>> = use_ref(cx, Vec::new);
+    let camera_preview = use_state(cx, || false);
+    let eval = use_eval(cx);
+    let microphone_peak = use_ref(cx, || None::<u8>);
 
     let ch = use_coroutine(cx, |mut rx| {
         to_owned![
@@ -46,7 +56,8 @@ pub fn AudioSettings(cx: Scope) -> Element {
             input_devices,
             output_devices,
             speaker_volume,
-            microphone_volume
+            microphone_volume,
+            microphone_peak
         ];
         async move {
             let warp_cmd_tx = WARP_CMD_CH.tx.clone();
@@ -210,6 +221,7 @@ pub fn AudioSettings(cx: Scope) -> Element {
                                 }
                             };
 
+                            *microphone_peak.write() = None;
                             while let Some(evt) = ch.recv().await {
                                 match evt {
                                     AudioTestEvent::Done => {
@@ -217,8 +229,10 @@ pub fn AudioSettings(cx: Scope) -> Element {
                                         break;
                                     }
                                     AudioTestEvent::Output { loudness } => {
-                                        *microphone_volume.write() =
-                                            (loudness as f32 / MAX_VOLUME) as u8;
+                                        let level = (loudness as f32 / MAX_VOLUME) as u8;
+                                        *microphone_volume.write() = level;
+                                        let mut peak = microphone_peak.write();
+                                        *peak = Some(peak.map_or(level, |peak| peak.max(level)));
                                     }
                                     _ => {}
                                 }
@@ -243,6 +257,36 @@ pub fn AudioSettings(cx: Scope) -> Element {
         }
     });
 
+    // refreshed with the preview, the names of the cameras are only known once one was allowed
+    use_future(cx, camera_preview.get(), |_| {
+        to_owned![cameras, eval];
+        async move {
+            match list_cameras(&eval).await {
+                Ok(list) => *cameras.write() = list,
+                Err(e) => log::error!("failed to list cameras: {e}"),
+            }
+        }
+    });
+    let mic_verdict = microphone_peak.read().map(|peak| {
+        get_local_text(if peak < QUIET_LEVEL {
+            "settings-audio.mic-too-quiet"
+        } else if peak > LOUD_LEVEL {
+            "settings-audio.mic-too-loud"
+        } else {
+            "settings-audio.mic-good"
+        })
+    });
+
+    let selected_camera = state
+        .read()
+        .settings
+        .video_device
+        .as_ref()
+        .and_then(|id| cameras.read().iter().find(|c| &c.id == id).cloned())
+        .or_else(|| cameras.read().first().cloned())
+        .map(|c| c.label)
+        .unwrap_or_default();
+
     cx.render(rsx!(
         div {
             id: "settings-audio",
@@ -282,7 +326,11 @@ pub fn AudioSettings(cx: Scope) -> Element {
                 },
                 VolumeIndicator {
                     volume: microphone_volume.clone(),
-                }
+                },
+                mic_verdict.map(|verdict| rsx!(p {
+                    class: "mic-check-verdict",
+                    "{verdict}"
+                }))
             },
             SettingSection {
                 aria_label: "output-device-section".into(),
@@ -322,6 +370,32 @@ pub fn AudioSettings(cx: Scope) -> Element {
                 }
             },
 
+            SettingSection {
+                aria_label: "camera-section".into(),
+                section_label: get_local_text("settings-audio.camera"),
+                section_description: get_local_text("settings-audio.camera-description"),
+                Select {
+                    initial_value: selected_camera,
+                    options: cameras.read().iter().map(|c| c.label.clone()).collect(),
+                    onselect: move |label: String| {
+                        let id = cameras.read().iter().find(|c| c.label == label).map(|c| c.id.clone());
+                        state.write().settings.video_device = id;
+                    }
+                },
+            },
+            SettingSectionSimple {
+                aria_label: "camera-preview-section".into(),
+                Button {
+                    text: get_local_text(if *camera_preview.get() { "settings-audio.camera-stop-preview" } else { "settings-audio.camera-preview" }),
+                    aria_label: "camera-preview-button".into(),
+                    onpress: move |_| camera_preview.set(!camera_preview.get()),
+                },
+            },
+            camera_preview.then(|| rsx!(CameraPreview {
+                id: "settings-camera-preview".into(),
+                device: state.read().settings.video_device.clone(),
+            })),
+
             // currently does nothing
             //SettingSection {
             //    section_label: get_local_text("settings-audio.sample-rate"),
@@ -365,6 +439,20 @@ pub fn AudioSettings(cx: Scope) -> Element {
                 }
             },
 
+            SettingSection {
+                aria_label: "pre-call-check-section".into(),
+                section_label: get_local_text("settings-audio.pre-call-check"),
+                section_description: get_local_text("settings-audio.pre-call-check-description"),
+                Switch {
+                    active: state.read().configuration.audiovideo.pre_call_check,
+                    onflipped: move |e| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+                        state.write().mutate(Action::Config(ConfigAction::SetPreCallCheck(e)));
+                    }
+                }
+            },
             SettingSection {
                 aria_label: "interface-sounds-section".into(),
                 section_label: get_local_text("settings-audio.interface-sounds"),
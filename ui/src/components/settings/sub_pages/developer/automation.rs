@@ -0,0 +1,93 @@
+use arboard::Clipboard;
+use common::icons::outline::Shape as Icon;
+use common::language::get_local_text;
+use common::sounds;
+use common::state::{
+    action::ConfigAction, configuration::Automation, Action, State, ToastNotification,
+};
+use common::{control, STATIC_ARGS};
+use dioxus::prelude::*;
+use kit::elements::{button::Button, switch::Switch, Appearance};
+use tracing::log;
+
+use crate::components::settings::SettingSection;
+
+// the control socket for scripts, see common::control
+#[allow(non_snake_case)]
+pub fn AutomationSettings(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let automation = state.read().configuration.automation.clone();
+    let socket_path = STATIC_ARGS.control_socket_path().display().to_string();
+
+    let set_automation = move |f: &dyn Fn(&mut Automation)| {
+        let mut automation = state.read().configuration.automation.clone();
+        f(&mut automation);
+        state
+            .write()
+            .mutate(Action::Config(ConfigAction::SetAutomation(automation)));
+    };
+    let copy_token = move |_| {
+        let token = state.read().configuration.automation.token.clone();
+        match Clipboard::new().and_then(|mut c| c.set_text(token)) {
+            Ok(_) => state
+                .write()
+                .mutate(Action::AddToastNotification(ToastNotification::init(
+                    "".into(),
+                    get_local_text("settings-developer.automation-token-copied"),
+                    None,
+                    2,
+                ))),
+            Err(e) => log::warn!("Unable to set text to clipboard: {e}"),
+        }
+    };
+
+    cx.render(rsx!(
+        SettingSection {
+            aria_label: "automation-section".into(),
+            section_label: get_local_text("settings-developer.automation"),
+            section_description: get_local_text("settings-developer.automation-description"),
+            Switch {
+                active: automation.enabled,
+                onflipped: move |e| {
+                    if state.read().configuration.audiovideo.interface_sounds {
+                        sounds::Play(sounds::Sounds::Flip);
+                    }
+                    set_automation(&|automation| automation.enabled = e);
+                },
+            }
+        },
+        automation.enabled.then(|| rsx!(
+            SettingSection {
+                aria_label: "automation-socket-section".into(),
+                section_label: get_local_text("settings-developer.automation-socket"),
+                section_description: get_local_text("settings-developer.automation-socket-description"),
+                code {
+                    aria_label: "automation-socket-path",
+                    "{socket_path}"
+                }
+            },
+            SettingSection {
+                aria_label: "automation-token-section".into(),
+                section_label: get_local_text("settings-developer.automation-token"),
+                section_description: get_local_text("settings-developer.automation-token-description"),
+                div {
+                    class: "button-group",
+                    Button {
+                        aria_label: "copy-automation-token-button".into(),
+                        text: get_local_text("settings-developer.automation-copy-token"),
+                        icon: Icon::ClipboardDocument,
+                        appearance: Appearance::Secondary,
+                        onpress: copy_token,
+                    },
+                    Button {
+                        aria_label: "regenerate-automation-token-button".into(),
+                        text: get_local_text("settings-developer.automation-regenerate-token"),
+                        icon: Icon::ArrowPath,
+                        appearance: Appearance::Secondary,
+                        onpress: move |_| set_automation(&|automation| automation.token = control::new_token()),
+                    },
+                }
+            },
+        ))
+    ))
+}
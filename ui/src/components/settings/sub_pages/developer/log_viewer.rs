@@ -0,0 +1,367 @@
+use std::{collections::VecDeque, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use common::{
+    icons::outline::Shape as Icon,
+    language::{get_local_text, get_local_text_with_args},
+    state::{Action, State, ToastNotification},
+};
+use dioxus::prelude::*;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    select::Select,
+    switch::Switch,
+    Appearance,
+};
+use regex::Regex;
+use rfd::FileDialog;
+use tracing::log::{self, Level};
+
+use crate::logger::{
+    self,
+    log_file::{self, LogTail},
+    Log,
+};
+
+// entries kept in memory, older ones are only in the file
+const MAX_ENTRIES: usize = 5000;
+// how much of the file is read when the viewer is opened
+const TAIL_BYTES: u64 = 1024 * 1024;
+// the DOM gets slow with too many rows. the newest matching entries are rendered
+const MAX_RENDERED: usize = 1000;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const RANGE_FORMAT: &str = "%Y-%m-%d %H:%M";
+const LEVELS: [Level; 5] = [
+    Level::Error,
+    Level::Warn,
+    Level::Info,
+    Level::Debug,
+    Level::Trace,
+];
+const SCROLL_TO_BOTTOM: &str = r#"
+setTimeout(() => {
+    const entries = document.getElementById("log-viewer-entries")
+    if (entries) entries.scrollTop = entries.scrollHeight
+}, 50)
+"#;
+
+#[derive(Clone)]
+struct Filter {
+    max_level: Level,
+    module: String,
+    search: Option<Regex>,
+}
+
+impl Filter {
+    fn matches(&self, log: &Log) -> bool {
+        log.level <= self.max_level
+            && log.module.contains(&self.module)
+            && self
+                .search
+                .as_ref()
+                .map(|regex| regex.is_match(&log.message))
+                .unwrap_or(true)
+    }
+}
+
+// tails the log file, which is written while "save logs to file" is on
+#[allow(non_snake_case)]
+pub fn LogViewer(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let entries: &UseRef<VecDeque<Log>> = use_ref(cx, VecDeque::new);
+    let following = use_state(cx, || true);
+    // read while paused, shown once following again
+    let pending = use_state(cx, || 0_usize);
+    let max_level = use_state(cx, || Level::Debug);
+    let module = use_state(cx, String::new);
+    let search = use_state(cx, String::new);
+    let from = use_state(cx, String::new);
+    let to = use_state(cx, String::new);
+    let exporting = use_state(cx, || false);
+    let eval = use_eval(cx);
+
+    use_future(cx, (), |_| {
+        to_owned![entries, following, pending, eval];
+        async move {
+            let mut tail = Some(LogTail::open(logger::log_file(), TAIL_BYTES));
+            let mut buffered: Vec<Log> = vec![];
+            let mut failed = false;
+            loop {
+                let Some(mut current) = tail.take() else {
+                    break;
+                };
+                let res = tokio::task::spawn_blocking(move || {
+                    let res = current.read_new();
+                    (current, res)
+                })
+                .await;
+                match res {
+                    Ok((current, res)) => {
+                        tail = Some(current);
+                        match res {
+                            Ok(new) => {
+                                buffered.extend(new);
+                                failed = false;
+                            }
+                            // only once, the error would be read back otherwise
+                            Err(e) if !failed => {
+                                log::warn!("failed to read log file: {e}");
+                                failed = true;
+                            }
+                            Err(_) => {}
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("log viewer stopped: {e}");
+                        break;
+                    }
+                }
+
+                if buffered.len() > MAX_ENTRIES {
+                    buffered.drain(..buffered.len() - MAX_ENTRIES);
+                }
+                if *following.current() && !buffered.is_empty() {
+                    entries.with_mut(|entries| {
+                        entries.extend(buffered.drain(..));
+                        while entries.len() > MAX_ENTRIES {
+                            entries.pop_front();
+                        }
+                    });
+                    let _ = eval(SCROLL_TO_BOTTOM);
+                }
+                if *pending.current() != buffered.len() {
+                    pending.set(buffered.len());
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    });
+
+    let search_regex = if search.is_empty() {
+        Ok(None)
+    } else {
+        Regex::new(search.get()).map(Some)
+    };
+    let filter = Filter {
+        max_level: *max_level.get(),
+        module: module.trim().to_string(),
+        search: search_regex.clone().ok().flatten(),
+    };
+    let range = (parse_time(from.get()), parse_time(to.get()));
+    let invalid_range = matches!(range, (Err(_), _) | (_, Err(_)));
+
+    let matching: Vec<Log> = {
+        let entries = entries.read();
+        let mut matching: Vec<_> = entries
+            .iter()
+            .rev()
+            .filter(|log| filter.matches(log))
+            .take(MAX_RENDERED)
+            .cloned()
+            .collect();
+        matching.reverse();
+        matching
+    };
+
+    let empty_text = if logger::get_save_to_file() {
+        get_local_text("settings-developer.logs-empty")
+    } else {
+        get_local_text("settings-developer.logs-not-saved")
+    };
+
+    let export = move || {
+        let (Ok(start), Ok(end)) = (parse_time(from.get()), parse_time(to.get())) else {
+            return;
+        };
+        let Some(dest) = FileDialog::new()
+            .set_directory(dirs::home_dir().unwrap_or(".".into()))
+            .set_file_name("uplink-logs.txt")
+            .save_file()
+        else {
+            return;
+        };
+        exporting.set(true);
+        let filter = filter.clone();
+        cx.spawn({
+            to_owned![state, exporting];
+            async move {
+                let res = export_slice(dest, filter, start, end).await;
+                exporting.set(false);
+                let text = match res {
+                    Ok(num) => get_local_text_with_args(
+                        "settings-developer.logs-exported",
+                        vec![("num", num.to_string())],
+                    ),
+                    Err(e) => {
+                        log::error!("failed to export logs: {e}");
+                        get_local_text("settings-developer.logs-export-failed")
+                    }
+                };
+                state
+                    .write()
+                    .mutate(Action::AddToastNotification(ToastNotification::init(
+                        "".into(),
+                        text,
+                        None,
+                        2,
+                    )));
+            }
+        });
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "log-viewer",
+            aria_label: "log-viewer",
+            div {
+                class: "log-viewer-filters",
+                Select {
+                    initial_value: max_level.to_string(),
+                    options: LEVELS.iter().map(|level| level.to_string()).collect(),
+                    onselect: move |value: String| {
+                        if let Ok(level) = value.parse() {
+                            max_level.set(level);
+                        }
+                    }
+                },
+                Input {
+                    placeholder: get_local_text("settings-developer.logs-module"),
+                    aria_label: "log-module-input".into(),
+                    icon: Icon::CodeBracket,
+                    options: Options {
+                        clear_on_submit: false,
+                        with_clear_btn: true,
+                        ..Default::default()
+                    },
+                    onchange: move |(value, _): (String, bool)| module.set(value),
+                },
+                Input {
+                    placeholder: get_local_text("settings-developer.logs-search"),
+                    aria_label: "log-search-input".into(),
+                    icon: Icon::MagnifyingGlass,
+                    options: Options {
+                        clear_on_submit: false,
+                        with_clear_btn: true,
+                        ..Default::default()
+                    },
+                    onchange: move |(value, _): (String, bool)| search.set(value),
+                },
+                div {
+                    class: "log-viewer-follow",
+                    span { get_local_text("settings-developer.logs-follow") },
+                    Switch {
+                        active: *following.get(),
+                        onflipped: move |value| following.set(value),
+                    }
+                },
+            },
+            search_regex.is_err().then(|| rsx!(
+                p {
+                    class: "error",
+                    get_local_text("settings-developer.logs-invalid-regex")
+                }
+            )),
+            (!*following.get() && *pending.get() > 0).then(|| rsx!(
+                p {
+                    class: "log-viewer-paused",
+                    get_local_text_with_args("settings-developer.logs-paused", vec![("num", pending.get().to_string())])
+                }
+            )),
+            div {
+                id: "log-viewer-entries",
+                class: "log-viewer-entries",
+                if matching.is_empty() {
+                    rsx!(p {
+                        class: "log-viewer-empty",
+                        "{empty_text}"
+                    })
+                } else {
+                    rsx!(matching.iter().map(|log| {
+                        let datetime = log.datetime.format("%Y-%m-%d %H:%M:%S").to_string();
+                        let level = log.level;
+                        let module = &log.module;
+                        let message = &log.message;
+                        rsx!(p {
+                            class: "log-entry",
+                            span { class: "muted", "{datetime}" },
+                            span { class: "level {level}", "{level}" },
+                            span { class: "muted", "{module}" },
+                            span { class: "message", "{message}" },
+                        })
+                    }))
+                }
+            },
+            div {
+                class: "log-viewer-export",
+                Input {
+                    placeholder: get_local_text("settings-developer.logs-from"),
+                    aria_label: "log-from-input".into(),
+                    options: Options {
+                        clear_on_submit: false,
+                        ..Default::default()
+                    },
+                    onchange: move |(value, _): (String, bool)| from.set(value),
+                },
+                Input {
+                    placeholder: get_local_text("settings-developer.logs-to"),
+                    aria_label: "log-to-input".into(),
+                    options: Options {
+                        clear_on_submit: false,
+                        ..Default::default()
+                    },
+                    onchange: move |(value, _): (String, bool)| to.set(value),
+                },
+                Button {
+                    text: get_local_text("settings-developer.logs-export"),
+                    aria_label: "export-logs-button".into(),
+                    appearance: Appearance::Secondary,
+                    icon: Icon::ArrowDownTray,
+                    loading: *exporting.get(),
+                    disabled: *exporting.get() || invalid_range,
+                    onpress: move |_| export(),
+                },
+            },
+            invalid_range.then(|| rsx!(
+                p {
+                    class: "error",
+                    get_local_text("settings-developer.logs-invalid-range")
+                }
+            )),
+        }
+    ))
+}
+
+// an empty input means no bound
+fn parse_time(value: &str) -> Result<Option<DateTime<Local>>, ()> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let datetime = NaiveDateTime::parse_from_str(value, RANGE_FORMAT).map_err(|_| ())?;
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(Some)
+        .ok_or(())
+}
+
+// reads the whole file, the viewer only holds its end. `end` includes the whole minute
+async fn export_slice(
+    dest: PathBuf,
+    filter: Filter,
+    start: Option<DateTime<Local>>,
+    end: Option<DateTime<Local>>,
+) -> anyhow::Result<usize> {
+    let src = logger::log_file();
+    let end = end.map(|end| end + chrono::Duration::minutes(1));
+    let num = tokio::task::spawn_blocking(move || {
+        log_file::export(&src, &dest, |log| {
+            start.map(|start| log.datetime >= start).unwrap_or(true)
+                && end.map(|end| log.datetime < end).unwrap_or(true)
+                && filter.matches(log)
+        })
+    })
+    .await??;
+    Ok(num)
+}
@@ -0,0 +1,426 @@
+use std::path::PathBuf;
+
+use common::notifications::{push_notification, NotificationAction};
+use common::state::integrity::IntegrityIssue;
+use common::state::ToastNotification;
+use common::warp_runner::{metrics, ConstellationCmd, OtherCmd, WarpCmd};
+use common::WARP_CMD_CH;
+use dioxus::prelude::*;
+
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::{
+    sounds::{self, Sounds},
+    state::{action::ConfigAction, notifications::NotificationKind, Action, State},
+    STATIC_ARGS,
+};
+use futures::channel::oneshot;
+use futures::StreamExt;
+use kit::elements::{button::Button, switch::Switch, Appearance};
+use rfd::FileDialog;
+use tracing::log;
+
+use crate::{
+    components::settings::{SettingSection, SettingSectionSimple},
+    logger,
+};
+
+mod automation;
+mod log_viewer;
+
+#[allow(non_snake_case)]
+pub fn DeveloperSettings(cx: Scope) -> Element {
+    log::trace!("Developer settings page rendered.");
+    let state = use_shared_state::<State>(cx)?;
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<PathBuf>| {
+        //to_owned![];
+        async move {
+            while let Some(cmd) = rx.next().await {
+                let dest = cmd.join("uplink.zip");
+                let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+                let (tx, rx) = oneshot::channel::<Result<(), warp::error::Error>>();
+                let compress_cmd = OtherCmd::CompressFolder {
+                    src: STATIC_ARGS.uplink_path(),
+                    dest,
+                    rsp: tx,
+                };
+
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::Other(compress_cmd)) {
+                    log::error!("failed to send warp command: {}", e);
+                    continue;
+                }
+
+                let res = rx.await.expect("command canceled");
+                match res {
+                    Ok(_) => {
+                        log::debug!("cache export complete");
+                    }
+                    Err(e) => {
+                        log::error!("failed to download cache: {e}");
+                    }
+                };
+            }
+        }
+    });
+
+    // None until a check was run
+    let integrity_issues: &UseRef<Option<Vec<IntegrityIssue>>> = use_ref(cx, || None);
+    let integrity_in_progress = use_state(cx, || false);
+
+    let integrity_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<bool>| {
+        to_owned![state, integrity_issues, integrity_in_progress];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            // true to repair the issues found by the last check, false to run a new check
+            while let Some(repair) = rx.next().await {
+                integrity_in_progress.set(true);
+                if repair {
+                    let issues = integrity_issues.write().take().unwrap_or_default();
+                    let (index_issues, storage_issues): (Vec<_>, Vec<_>) = issues
+                        .into_iter()
+                        .partition(IntegrityIssue::is_conversation_index);
+                    let mut repaired = index_issues.len();
+                    state
+                        .write()
+                        .mutate(Action::RepairConversationIndexes(index_issues));
+
+                    let (tx, rx) = oneshot::channel::<Result<usize, warp::error::Error>>();
+                    if let Err(e) = warp_cmd_tx.send(WarpCmd::Constellation(
+                        ConstellationCmd::RepairStorageIntegrity {
+                            issues: storage_issues,
+                            rsp: tx,
+                        },
+                    )) {
+                        log::error!("failed to send warp command: {}", e);
+                        integrity_in_progress.set(false);
+                        continue;
+                    }
+
+                    match rx.await.expect("command canceled") {
+                        Ok(num) => repaired += num,
+                        Err(e) => log::error!("failed to repair storage: {e}"),
+                    }
+                    state
+                        .write()
+                        .mutate(Action::AddToastNotification(ToastNotification::init(
+                            "".into(),
+                            get_local_text_with_args(
+                                "settings-developer.integrity-repaired",
+                                vec![("num", repaired.to_string())],
+                            ),
+                            None,
+                            2,
+                        )));
+                } else {
+                    let mut issues = state.read().check_integrity();
+
+                    let (tx, rx) =
+                        oneshot::channel::<Result<Vec<IntegrityIssue>, warp::error::Error>>();
+                    if let Err(e) = warp_cmd_tx.send(WarpCmd::Constellation(
+                        ConstellationCmd::CheckStorageIntegrity { rsp: tx },
+                    )) {
+                        log::error!("failed to send warp command: {}", e);
+                        integrity_in_progress.set(false);
+                        continue;
+                    }
+
+                    match rx.await.expect("command canceled") {
+                        Ok(storage_issues) => issues.extend(storage_issues),
+                        Err(e) => log::error!("failed to check storage integrity: {e}"),
+                    }
+                    for issue in issues.iter() {
+                        log::info!("integrity issue: {issue}");
+                    }
+                    integrity_issues.set(Some(issues));
+                }
+                integrity_in_progress.set(false);
+            }
+        }
+    });
+
+    let integrity_description = match integrity_issues.read().as_ref() {
+        None => get_local_text("settings-developer.check-integrity-description"),
+        Some(issues) if issues.is_empty() => {
+            get_local_text("settings-developer.integrity-no-issues")
+        }
+        Some(issues) => {
+            let count = |f: fn(&IntegrityIssue) -> bool| {
+                issues.iter().filter(|issue| f(issue)).count().to_string()
+            };
+            get_local_text_with_args(
+                "settings-developer.integrity-issues",
+                vec![
+                    (
+                        "thumbnails",
+                        count(|i| matches!(i, IntegrityIssue::OrphanedThumbnail { .. })),
+                    ),
+                    (
+                        "blobs",
+                        count(|i| matches!(i, IntegrityIssue::MissingBlob { .. })),
+                    ),
+                    ("indexes", count(IntegrityIssue::is_conversation_index)),
+                ],
+            )
+        }
+    };
+    // refreshed with the button, the runner doesn't notify about new samples
+    let latency = use_state(cx, metrics::snapshot);
+
+    let has_integrity_issues = integrity_issues
+        .read()
+        .as_ref()
+        .map(|issues| !issues.is_empty())
+        .unwrap_or_default();
+
+    cx.render(rsx!(
+        div {
+            id: "settings-developer",
+            aria_label: "settings-developer",
+            SettingSection {
+                aria_label: "experimental-features-section".into(),
+                section_label: get_local_text("settings-developer.experimental-features"),
+                section_description: get_local_text("settings-developer.experimental-features-description"),
+                Switch {
+                    active: state.read().configuration.developer.experimental_features,
+                    onflipped: move |value| {
+                        state.write().mutate(Action::Config(ConfigAction::SetExperimentalFeaturesEnabled(value)));
+                    },
+                }
+            },
+            SettingSection {
+                aria_label: "developer-mode-section".into(),
+                section_label: get_local_text("settings-developer.developer-mode"),
+                section_description: get_local_text("settings-developer.developer-mode-description"),
+                Switch {
+                    active: state.read().configuration.developer.developer_mode,
+                    onflipped: move |value| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+
+                        state.write().mutate(Action::Config(ConfigAction::SetDevModeEnabled(value)));
+                    },
+                }
+            },
+            SettingSection {
+                aria_label: "test-notification-section".into(),
+                section_label: get_local_text("settings-developer.test-notification"),
+                section_description: get_local_text("settings-developer.test-notification-description"),
+                Button {
+                    text: get_local_text("settings-developer.test-notification"),
+                    aria_label: "test-notification-button".into(),
+                    appearance: Appearance::Secondary,
+                    icon: Icon::BellAlert,
+                    onpress: move |_| {
+                        push_notification(
+                            get_local_text("settings-developer.test-popup"),
+                            get_local_text("settings-developer.test-popup"),
+                            Some(Sounds::General),
+                            notify_rust::Timeout::Milliseconds(4),
+                            NotificationAction::Dummy
+                        );
+                        state
+                            .write()
+                            .mutate(Action::AddNotification(NotificationKind::Settings, 1));
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "open-cache-section".into(),
+                section_label: get_local_text("settings-developer.open-cache"),
+                section_description: get_local_text("settings-developer.open-cache-description"),
+                Button {
+                    text: get_local_text("settings-developer.open-cache-folder"),
+                    aria_label: "open-cache-folder-button".into(),
+                    appearance: Appearance::Secondary,
+                    icon: Icon::FolderOpen,
+                    onpress: |_| {
+                        let _ = opener::open(STATIC_ARGS.uplink_path());
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "compress-download-cache-section".into(),
+                section_label: get_local_text("settings-developer.compress-download-cache"),
+                section_description: get_local_text("settings-developer.compress-download-cache-description"),
+                Button {
+                    text: get_local_text("settings-developer.compress"),
+                    aria_label: "compress-button".into(),
+                    appearance: Appearance::Secondary,
+                    icon: Icon::ArchiveBoxArrowDown,
+                    onpress: |_| {
+                        if let Some(path) =  FileDialog::new().set_directory(dirs::home_dir().unwrap_or(".".into())).pick_folder() {
+                            ch.send(path);
+                        };
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "print-state-section".into(),
+                section_label: get_local_text("settings-developer.print-state"),
+                section_description: get_local_text("settings-developer.print-state-description"),
+                Button {
+                    text: get_local_text("settings-developer.print-state"),
+                    aria_label: "print-state-button".into(),
+                    appearance: Appearance::Secondary,
+                    icon: Icon::DocumentChartBar,
+                    onpress: move |_| {
+                        log::debug!("{:#?}", &*state.read());
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "clear-cache-section".into(),
+                section_label: get_local_text("settings-developer.clear-cache"),
+                section_description: get_local_text("settings-developer.clear-cache-description"),
+                Button {
+                    text: get_local_text("settings-developer.clear"),
+                    aria_label: "clear-button".into(),
+                    appearance: Appearance::Danger,
+                    icon: Icon::Trash,
+                    onpress: move |_| {
+                        state.write().clear();
+                    }
+                }
+            }
+            SettingSection {
+                aria_label: "check-integrity-section".into(),
+                section_label: get_local_text("settings-developer.check-integrity"),
+                section_description: integrity_description,
+                if has_integrity_issues {
+                    rsx!(Button {
+                        text: get_local_text("settings-developer.repair"),
+                        aria_label: "repair-integrity-button".into(),
+                        appearance: Appearance::Secondary,
+                        icon: Icon::WrenchScrewdriver,
+                        loading: *integrity_in_progress.get(),
+                        disabled: *integrity_in_progress.get(),
+                        onpress: move |_| {
+                            integrity_ch.send(true);
+                        }
+                    })
+                } else {
+                    rsx!(Button {
+                        text: get_local_text("settings-developer.check"),
+                        aria_label: "check-integrity-button".into(),
+                        appearance: Appearance::Secondary,
+                        icon: Icon::ShieldCheck,
+                        loading: *integrity_in_progress.get(),
+                        disabled: *integrity_in_progress.get(),
+                        onpress: move |_| {
+                            integrity_ch.send(false);
+                        }
+                    })
+                }
+            },
+            SettingSection {
+                aria_label: "command-latency-section".into(),
+                section_label: get_local_text("settings-developer.command-latency"),
+                section_description: get_local_text("settings-developer.command-latency-description"),
+                no_border: true,
+                div {
+                    class: "button-group",
+                    Button {
+                        text: get_local_text("settings-developer.refresh"),
+                        aria_label: "refresh-latency-button".into(),
+                        appearance: Appearance::Secondary,
+                        icon: Icon::ArrowPath,
+                        onpress: move |_| latency.set(metrics::snapshot()),
+                    },
+                    Button {
+                        text: get_local_text("settings-developer.reset"),
+                        aria_label: "reset-latency-button".into(),
+                        appearance: Appearance::Secondary,
+                        icon: Icon::Trash,
+                        onpress: move |_| {
+                            metrics::reset();
+                            latency.set(Vec::new());
+                        },
+                    },
+                }
+            },
+            SettingSectionSimple {
+                aria_label: "command-latency-table".into(),
+                if latency.is_empty() {
+                    rsx!(p {
+                        class: "latency-empty",
+                        get_local_text("settings-developer.no-commands")
+                    })
+                } else {
+                    rsx!(table {
+                        class: "latency-table",
+                        tr {
+                            th { get_local_text("settings-developer.command") },
+                            th { get_local_text("settings-developer.count") },
+                            th { "p50" },
+                            th { "p90" },
+                            th { "p99" },
+                            th { "max" },
+                        },
+                        latency.iter().map(|stats| {
+                            let name = &stats.name;
+                            let count = stats.count;
+                            let p50 = as_millis(stats.p50);
+                            let p90 = as_millis(stats.p90);
+                            let p99 = as_millis(stats.p99);
+                            let max = as_millis(stats.max);
+                            rsx!(tr {
+                                key: "{name}",
+                                td { "{name}" },
+                                td { "{count}" },
+                                td { "{p50}" },
+                                td { "{p90}" },
+                                td { "{p99}" },
+                                td { "{max}" },
+                            })
+                        })
+                    })
+                }
+            },
+            SettingSection {
+                aria_label: "log-latency-section".into(),
+                section_label: get_local_text("settings-developer.log-latency"),
+                section_description: get_local_text("settings-developer.log-latency-description"),
+                Switch {
+                    active: metrics::logging_enabled(),
+                    onflipped: move |value| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+                        metrics::set_logging(value);
+                    },
+                }
+            },
+            SettingSection {
+                aria_label: "save-logs-section".into(),
+                section_label: get_local_text("settings-developer.save-logs-to-file"),
+                section_description: get_local_text("settings-developer.save-logs-to-file-description"),
+                Switch {
+                    active: logger::get_save_to_file(),
+                    onflipped: move |value| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+                        logger::set_save_to_file(value);
+                    },
+                }
+            },
+            automation::AutomationSettings {},
+            SettingSection {
+                aria_label: "log-viewer-section".into(),
+                section_label: get_local_text("settings-developer.log-viewer"),
+                section_description: get_local_text("settings-developer.log-viewer-description"),
+                no_border: true,
+            },
+            SettingSectionSimple {
+                aria_label: "log-viewer".into(),
+                log_viewer::LogViewer {}
+            }
+        }
+    ))
+}
+
+fn as_millis(duration: std::time::Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,319 @@
+use std::path::PathBuf;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+use anyhow::Context;
+use arboard::Clipboard;
+use chrono::{Local, Utc};
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::{settings::LinkedDevice, Action, State, ToastNotification};
+use common::utils::{
+    device_link::{self, LinkOffer, LinkRequest},
+    network_check,
+};
+use common::warp_runner::{MultiPassCmd, OtherCmd, WarpCmd, BACKUP_EXTENSION};
+use common::{STATIC_ARGS, WARP_CMD_CH};
+use dioxus::prelude::*;
+use futures::{channel::oneshot, StreamExt};
+use kit::elements::{button::Button, Appearance};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::components::settings::{SettingSection, SettingSectionSimple};
+
+// how long the pairing code stays valid
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, PartialEq)]
+enum Stage {
+    Idle,
+    // the pairing code is shown
+    Waiting(String),
+    // a device connected and the user needs to allow it
+    Confirm(String),
+    Sending(String),
+}
+
+enum LinkCmd {
+    Start,
+    Allow,
+    Deny,
+}
+
+#[allow(non_snake_case)]
+pub fn LinkedDevices(cx: Scope) -> Element {
+    log::trace!("Linked devices settings page rendered.");
+    let state = use_shared_state::<State>(cx)?;
+    let stage = use_state(cx, || Stage::Idle);
+    let cancelled = use_ref(cx, || Arc::new(AtomicBool::new(false)));
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<LinkCmd>| {
+        to_owned![state, stage, cancelled];
+        async move {
+            let mut pending: Option<LinkRequest> = None;
+            while let Some(cmd) = rx.next().await {
+                match cmd {
+                    LinkCmd::Start => {
+                        let offer = match network_check::lan_addr()
+                            .context("no network available")
+                            .and_then(LinkOffer::bind)
+                        {
+                            Ok(offer) => offer,
+                            Err(e) => {
+                                log::error!("failed to offer device link: {e}");
+                                toast(&state, "settings-devices.link-failed");
+                                continue;
+                            }
+                        };
+                        let flag = Arc::new(AtomicBool::new(false));
+                        *cancelled.write_silent() = flag.clone();
+                        stage.set(Stage::Waiting(offer.code().to_string()));
+                        match offer.accept(PAIRING_TIMEOUT, flag.clone()).await {
+                            Ok(request) => {
+                                stage.set(Stage::Confirm(request.device_name.clone()));
+                                pending = Some(request);
+                            }
+                            Err(e) => {
+                                if !flag.load(Ordering::Relaxed) {
+                                    log::warn!("device linking stopped: {e}");
+                                    toast(&state, "settings-devices.link-failed");
+                                }
+                                stage.set(Stage::Idle);
+                            }
+                        }
+                    }
+                    LinkCmd::Allow => {
+                        let request = match pending.take() {
+                            Some(request) => request,
+                            None => continue,
+                        };
+                        let name = request.device_name.clone();
+                        let id = Uuid::new_v4();
+                        stage.set(Stage::Sending(name.clone()));
+                        match share_account(&state, id, request).await {
+                            Ok(_) => {
+                                state.write().mutate(Action::AddLinkedDevice(LinkedDevice {
+                                    id,
+                                    name,
+                                    linked_at: Utc::now(),
+                                }));
+                                toast(&state, "settings-devices.linked");
+                            }
+                            Err(e) => {
+                                log::error!("failed to link device: {e}");
+                                toast(&state, "settings-devices.link-failed");
+                            }
+                        }
+                        stage.set(Stage::Idle);
+                    }
+                    LinkCmd::Deny => {
+                        pending = None;
+                        stage.set(Stage::Idle);
+                    }
+                }
+            }
+        }
+    });
+
+    let devices = state.read().settings.linked_devices.clone();
+
+    cx.render(rsx!(
+        div {
+            id: "settings-devices",
+            aria_label: "settings-devices",
+            SettingSection {
+                aria_label: "link-device-section".into(),
+                section_label: get_local_text("settings-devices.link"),
+                section_description: get_local_text("settings-devices.link-description"),
+                no_border: *stage.get() != Stage::Idle,
+                Button {
+                    text: get_local_text("settings-devices.link"),
+                    aria_label: "link-device-button".into(),
+                    icon: Icon::Link,
+                    appearance: Appearance::Secondary,
+                    disabled: *stage.get() != Stage::Idle,
+                    onpress: move |_| ch.send(LinkCmd::Start),
+                },
+            },
+            match stage.get() {
+                Stage::Idle => None,
+                Stage::Waiting(code) => {
+                    let code = code.clone();
+                    let copied_code = code.clone();
+                    Some(rsx!(
+                        SettingSectionSimple {
+                            aria_label: "pairing-code-section".into(),
+                            div {
+                                class: "pairing",
+                                p { get_local_text("settings-devices.pairing-instructions") },
+                                code {
+                                    class: "pairing-code",
+                                    aria_label: "pairing-code",
+                                    "{code}"
+                                },
+                                div {
+                                    class: "button-group",
+                                    Button {
+                                        text: get_local_text("uplink.copy-text"),
+                                        aria_label: "copy-pairing-code-button".into(),
+                                        icon: Icon::DocumentDuplicate,
+                                        appearance: Appearance::Secondary,
+                                        onpress: move |_| {
+                                            let copied = Clipboard::new().and_then(|mut c| c.set_text(copied_code.clone()));
+                                            if let Err(e) = copied {
+                                                log::warn!("Unable to set text to clipboard: {e}");
+                                            }
+                                        },
+                                    },
+                                    Button {
+                                        text: get_local_text("uplink.cancel"),
+                                        aria_label: "cancel-link-button".into(),
+                                        icon: Icon::NoSymbol,
+                                        appearance: Appearance::Secondary,
+                                        onpress: move |_| {
+                                            cancelled.read().store(true, Ordering::Relaxed);
+                                            stage.set(Stage::Idle);
+                                        },
+                                    },
+                                }
+                            }
+                        }
+                    ))
+                }
+                Stage::Confirm(name) => {
+                    let text = get_local_text_with_args("settings-devices.confirm", vec![("name", name.clone())]);
+                    Some(rsx!(
+                        SettingSectionSimple {
+                            aria_label: "confirm-link-section".into(),
+                            div {
+                                class: "pairing",
+                                p { "{text}" },
+                                div {
+                                    class: "button-group",
+                                    Button {
+                                        text: get_local_text("settings-devices.allow"),
+                                        aria_label: "allow-link-button".into(),
+                                        icon: Icon::Check,
+                                        onpress: move |_| ch.send(LinkCmd::Allow),
+                                    },
+                                    Button {
+                                        text: get_local_text("settings-devices.deny"),
+                                        aria_label: "deny-link-button".into(),
+                                        icon: Icon::XMark,
+                                        appearance: Appearance::Secondary,
+                                        onpress: move |_| ch.send(LinkCmd::Deny),
+                                    },
+                                }
+                            }
+                        }
+                    ))
+                }
+                Stage::Sending(name) => {
+                    let text = get_local_text_with_args("settings-devices.sending", vec![("name", name.clone())]);
+                    Some(rsx!(
+                        SettingSectionSimple {
+                            aria_label: "sending-account-section".into(),
+                            p { "{text}" }
+                        }
+                    ))
+                }
+            },
+            if devices.is_empty() {
+                rsx!(p {
+                    class: "devices-empty",
+                    get_local_text("settings-devices.none")
+                })
+            } else {
+                rsx!(devices.into_iter().map(|device| {
+                    let key = device.id;
+                    let linked_at = get_local_text_with_args("settings-devices.linked-at", vec![(
+                        "date",
+                        device.linked_at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+                    )]);
+                    rsx!(SettingSection {
+                        key: "{key}",
+                        section_label: device.name.clone(),
+                        section_description: linked_at,
+                        Button {
+                            text: get_local_text("settings-devices.revoke"),
+                            aria_label: "revoke-device-button".into(),
+                            icon: Icon::Trash,
+                            appearance: Appearance::Danger,
+                            onpress: move |_| {
+                                to_owned![state];
+                                cx.spawn(async move { revoke(&state, device.id).await });
+                            },
+                        },
+                    })
+                }))
+            }
+        }
+    ))
+}
+
+// exports the account encrypted with the pairing secret and sends it to the new device, which is
+// known as `id` from now on
+async fn share_account(
+    state: &UseSharedState<State>,
+    id: Uuid,
+    request: LinkRequest,
+) -> anyhow::Result<()> {
+    // the state file is part of the bundle
+    if let Err(e) = state.read().save_now() {
+        log::error!("failed to save state before sharing the account: {e}");
+    }
+    std::fs::create_dir_all(&STATIC_ARGS.temp_files)?;
+    let bundle = STATIC_ARGS
+        .temp_files
+        .join(format!("{}.{BACKUP_EXTENSION}", Uuid::new_v4()));
+
+    let (tx, rx) = oneshot::channel();
+    WARP_CMD_CH
+        .tx
+        .send(WarpCmd::Other(OtherCmd::ExportBackup {
+            src: STATIC_ARGS.uplink_path(),
+            exclude: device_link::NOT_SHARED.iter().map(PathBuf::from).collect(),
+            passphrase: request.secret().to_string(),
+            dest: bundle.clone(),
+            rsp: tx,
+        }))
+        .map_err(|_| anyhow::anyhow!("failed to send warp command"))?;
+    rx.await??;
+
+    let sent = request.send_bundle(id, bundle.clone()).await;
+    let _ = std::fs::remove_file(bundle);
+    sent
+}
+
+// the device is only removed from the list once the revocation was published to the other devices
+async fn revoke(state: &UseSharedState<State>, id: Uuid) {
+    let (tx, rx) = oneshot::channel();
+    let sent = WARP_CMD_CH
+        .tx
+        .send(WarpCmd::MultiPass(MultiPassCmd::RevokeDevice {
+            id,
+            rsp: tx,
+        }));
+    match (sent, rx.await) {
+        (Ok(_), Ok(Ok(_))) => {
+            state.write().mutate(Action::RevokeLinkedDevice(id));
+            toast(state, "settings-devices.revoked");
+        }
+        _ => toast(state, "settings-devices.revoke-failed"),
+    }
+}
+
+fn toast(state: &UseSharedState<State>, message: &str) {
+    state
+        .write()
+        .mutate(Action::AddToastNotification(ToastNotification::init(
+            "".into(),
+            get_local_text(message),
+            None,
+            2,
+        )));
+}
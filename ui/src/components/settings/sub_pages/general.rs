@@ -1,20 +1,32 @@
-use common::language::{change_language, get_available_languages, get_local_text};
-use common::state::utils::{get_available_fonts, get_available_themes};
+use common::language::{
+    change_language, get_available_languages, get_local_text, get_local_text_with_args,
+    load_community_languages,
+};
+use common::state::configuration::OpenItemsOn;
+use common::state::custom_theme::{export_theme, import_theme};
+use common::state::ui::Font;
+use common::state::utils::{get_available_fonts, get_available_themes, import_font};
 #[allow(unused_imports)]
-use common::state::{action::ConfigAction, Action, State};
+use common::state::{action::ConfigAction, Action, State, ToastNotification};
 use common::{icons::outline::Shape as Icon, STATIC_ARGS};
 use dioxus::prelude::*;
 use kit::components::slide_selector::{ButtonsFormat, SlideSelector};
 use kit::components::swatch::ColorSwatch;
 use kit::elements::button::Button;
 use kit::elements::tooltip::{ArrowPosition, Tooltip};
+use kit::elements::Appearance;
 #[allow(unused_imports)]
 use kit::elements::{select::Select, switch::Switch};
+use rfd::FileDialog;
 use tracing::log;
 
 use crate::components::settings::{SettingSection, SettingSectionSimple};
+
+use super::theme_editor::ThemeEditor;
 use crate::utils::get_font_sizes::FONT_SIZE_OPTIONS;
 
+const MONITOR_SCALE_OPTIONS: [f32; 8] = [0.75, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0];
+
 #[allow(non_snake_case)]
 pub fn GeneralSettings(cx: Scope) -> Element {
     let state = use_shared_state::<State>(cx)?;
@@ -22,6 +34,7 @@ pub fn GeneralSettings(cx: Scope) -> Element {
 
     let themes_fut = use_future(cx, (), |_| async move { get_available_themes() });
     let font_fut = use_future(cx, (), |_| async move { get_available_fonts() });
+    let editing_theme = use_state(cx, || false);
 
     log::trace!("General settings page rendered.");
 
@@ -36,6 +49,19 @@ pub fn GeneralSettings(cx: Scope) -> Element {
         }
     };
 
+    let monitor_scale_options = MONITOR_SCALE_OPTIONS.to_vec();
+    let monitor_scale = state.read().ui.monitor_scale();
+    let initial_monitor_scale_idx = monitor_scale_options
+        .iter()
+        .position(|r| r == &monitor_scale)
+        .unwrap_or(2);
+    let has_monitor = state.read().ui.current_monitor.is_some();
+
+    let open_items_label = |open_items_on: OpenItemsOn| match open_items_on {
+        OpenItemsOn::SingleClick => get_local_text("settings-general.single-click"),
+        OpenItemsOn::DoubleClick => get_local_text("settings-general.double-click"),
+    };
+
     // TODO: This could go into a config file but I think the better approach is to allow the user to create and remove their own custom colors to create rudementary themes. Until we get there, this is fine.
     let available_colors = vec![
         (255, 95, 87),   // Red
@@ -76,26 +102,85 @@ pub fn GeneralSettings(cx: Scope) -> Element {
                         let new_app_lang = change_language(value);
                         state.write().mutate(Action::SetLanguage(new_app_lang));
                     }
-                }
+                },
+                Button {
+                    icon: Icon::ArrowPath,
+                    aria_label: "reload-translations-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        let count = load_community_languages();
+                        // the language in use may have changed or be gone
+                        let language = change_language(state.read().settings.language.clone());
+                        state.write().mutate(Action::SetLanguage(language));
+                        state
+                            .write()
+                            .mutate(Action::AddToastNotification(ToastNotification::init(
+                                "".into(),
+                                get_local_text_with_args("settings-general.translations-loaded", vec![("count", count)]),
+                                None,
+                                2,
+                            )));
+                    },
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: get_local_text("settings-general.reload-translations"),
+                    }))
+                },
+                Button {
+                    icon: Icon::FolderOpen,
+                    aria_label: "open-locales-folder-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        let _ = opener::open(&STATIC_ARGS.locales_path);
+                    },
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: get_local_text("settings-general.open-locales-folder"),
+                    }))
+                },
             },
             SettingSection {
                 aria_label: "font-section".into(),
                 section_label: get_local_text("settings-general.font"),
                 section_description: get_local_text("settings-general.font-description"),
-                Select {
-                    initial_value: if let Some(font) = &state.read().ui.font {
-                        font.name.clone()
-                    } else {
-                        "Default".into()
-                    },
-                    options: font_fut.value().cloned().unwrap_or_default().iter().map(|font| font.name.clone()).collect(),
-                    onselect: move |value| {
-                        font_fut.value().cloned().unwrap_or_default().iter().for_each(|font| {
-                            if font.name.clone() == value {
-                                state.write().mutate(Action::SetFont(Some(font.to_owned())));
+                FontSelect {
+                    current: state.read().ui.font.clone(),
+                    fonts: font_fut.value().cloned().unwrap_or_default(),
+                    onselect: move |font| state.write().mutate(Action::SetFont(font)),
+                },
+                Button {
+                    icon: Icon::DocumentPlus,
+                    aria_label: "import-font-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        let path = match FileDialog::new()
+                            .add_filter("font", &["ttf", "otf", "woff", "woff2"])
+                            .pick_file()
+                        {
+                            Some(path) => path,
+                            None => return,
+                        };
+                        let text = match import_font(&path) {
+                            Ok(font) => {
+                                font_fut.restart();
+                                get_local_text_with_args("settings-general.font-imported", vec![("font", font.name)])
                             }
-                        })
-                    }
+                            Err(e) => {
+                                log::error!("failed to import font {}: {e}", path.display());
+                                get_local_text("settings-general.font-import-failed")
+                            }
+                        };
+                        state.write().mutate(Action::AddToastNotification(ToastNotification::init(
+                            "".into(),
+                            text,
+                            None,
+                            2,
+                        )));
+                    },
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: get_local_text("settings-general.import-font"),
+                    }))
                 },
                 Button {
                     icon: Icon::FolderOpen,
@@ -109,6 +194,26 @@ pub fn GeneralSettings(cx: Scope) -> Element {
                     }))
                 },
             },
+            SettingSection {
+                aria_label: "message-font-section".into(),
+                section_label: get_local_text("settings-general.message-font"),
+                section_description: get_local_text("settings-general.message-font-description"),
+                FontSelect {
+                    current: state.read().ui.message_font.clone(),
+                    fonts: font_fut.value().cloned().unwrap_or_default(),
+                    onselect: move |font| state.write().mutate(Action::SetMessageFont(font)),
+                },
+            },
+            SettingSection {
+                aria_label: "monospace-font-section".into(),
+                section_label: get_local_text("settings-general.monospace-font"),
+                section_description: get_local_text("settings-general.monospace-font-description"),
+                FontSelect {
+                    current: state.read().ui.monospace_font.clone(),
+                    fonts: font_fut.value().cloned().unwrap_or_default(),
+                    onselect: move |font| state.write().mutate(Action::SetMonospaceFont(font)),
+                },
+            },
             SettingSection {
                 aria_label: "font-scaling-section".into(),
                 section_label: get_local_text("settings-general.font-scaling"),
@@ -122,6 +227,36 @@ pub fn GeneralSettings(cx: Scope) -> Element {
                     }
                 }
             },
+            has_monitor.then(|| rsx!(SettingSection {
+                aria_label: "monitor-scaling-section".into(),
+                section_label: get_local_text("settings-general.monitor-scaling"),
+                section_description: get_local_text("settings-general.monitor-scaling-description"),
+                SlideSelector {
+                    buttons_format: ButtonsFormat::PlusAndMinus,
+                    values: monitor_scale_options,
+                    initial_index: initial_monitor_scale_idx,
+                    onset: move |value| {
+                        state.write().mutate(Action::SetMonitorScale(value));
+                    }
+                }
+            })),
+            SettingSection {
+                aria_label: "open-items-section".into(),
+                section_label: get_local_text("settings-general.open-items"),
+                section_description: get_local_text("settings-general.open-items-description"),
+                Select {
+                    initial_value: open_items_label(state.read().configuration.files.open_items_on),
+                    options: vec![open_items_label(OpenItemsOn::SingleClick), open_items_label(OpenItemsOn::DoubleClick)],
+                    onselect: move |value: String| {
+                        let open_items_on = if value == open_items_label(OpenItemsOn::DoubleClick) {
+                            OpenItemsOn::DoubleClick
+                        } else {
+                            OpenItemsOn::SingleClick
+                        };
+                        state.write().mutate(Action::Config(ConfigAction::SetOpenItemsOn(open_items_on)));
+                    }
+                }
+            },
             SettingSection {
                 aria_label: "theme-section".into(),
                 section_label: get_local_text("settings-general.theme"),
@@ -160,6 +295,78 @@ pub fn GeneralSettings(cx: Scope) -> Element {
                         })
                     }
                 },
+                Button {
+                    icon: Icon::PaintBrush,
+                    aria_label: "edit-theme-button".into(),
+                    appearance: if *editing_theme.get() { Appearance::Primary } else { Appearance::Secondary },
+                    onpress: move |_| editing_theme.set(!editing_theme.get()),
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: get_local_text("settings-general.edit-theme"),
+                    }))
+                },
+                Button {
+                    icon: Icon::ArrowDownTray,
+                    aria_label: "import-theme-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        let Some(path) = FileDialog::new().add_filter("theme", &["scss", "css"]).pick_file() else {
+                            return;
+                        };
+                        let text = match import_theme(&path) {
+                            Ok(_) => {
+                                themes_fut.restart();
+                                get_local_text("settings-general.theme-imported")
+                            }
+                            Err(e) => {
+                                log::error!("failed to import theme {}: {e}", path.display());
+                                get_local_text("settings-general.theme-import-failed")
+                            }
+                        };
+                        state.write().mutate(Action::AddToastNotification(ToastNotification::init(
+                            "".into(),
+                            text,
+                            None,
+                            2,
+                        )));
+                    },
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: get_local_text("settings-general.import-theme"),
+                    }))
+                },
+                Button {
+                    icon: Icon::ArrowUpTray,
+                    aria_label: "export-theme-button".into(),
+                    appearance: Appearance::Secondary,
+                    // the default theme has no file
+                    disabled: state.read().ui.theme.as_ref().map_or(true, |t| t.filename.is_empty()),
+                    onpress: move |_| {
+                        let Some(theme) = state.read().ui.theme.clone() else {
+                            return;
+                        };
+                        let Some(path) = FileDialog::new()
+                            .set_file_name(&format!("{}.scss", theme.name))
+                            .add_filter("theme", &["scss"])
+                            .save_file()
+                        else {
+                            return;
+                        };
+                        if let Err(e) = export_theme(&theme, &path) {
+                            log::error!("failed to export theme to {}: {e}", path.display());
+                            state.write().mutate(Action::AddToastNotification(ToastNotification::init(
+                                "".into(),
+                                get_local_text("settings-general.theme-export-failed"),
+                                None,
+                                2,
+                            )));
+                        }
+                    },
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: get_local_text("settings-general.export-theme"),
+                    }))
+                },
                 Button {
                     icon: Icon::FolderOpen,
                     aria_label: "open-themes-folder-button".into(),
@@ -172,6 +379,14 @@ pub fn GeneralSettings(cx: Scope) -> Element {
                     }))
                 },
             },
+            editing_theme.get().then(|| rsx!(ThemeEditor {
+                onclose: move |saved| {
+                    if saved {
+                        themes_fut.restart();
+                    }
+                    editing_theme.set(false);
+                },
+            })),
             SettingSectionSimple {
                 aria_label: "color-section".into(),
                 div {
@@ -200,3 +415,40 @@ pub fn GeneralSettings(cx: Scope) -> Element {
         }
     ))
 }
+
+#[derive(Props)]
+struct FontSelectProps<'a> {
+    #[props(!optional)]
+    current: Option<Font>,
+    fonts: Vec<Font>,
+    onselect: EventHandler<'a, Option<Font>>,
+}
+
+// lists the available fonts. the first option resets to the default font
+#[allow(non_snake_case)]
+fn FontSelect<'a>(cx: Scope<'a, FontSelectProps<'a>>) -> Element<'a> {
+    let default_font = get_local_text("settings-general.default-font");
+    let mut options = vec![default_font.clone()];
+    options.extend(cx.props.fonts.iter().map(|font| font.name.clone()));
+
+    cx.render(rsx!(Select {
+        initial_value: cx
+            .props
+            .current
+            .as_ref()
+            .map(|font| font.name.clone())
+            .unwrap_or(default_font.clone()),
+        options: options,
+        onselect: move |value: String| {
+            let font = cx
+                .props
+                .fonts
+                .iter()
+                .find(|font| font.name == value)
+                .cloned();
+            if font.is_some() || value == default_font {
+                cx.props.onselect.call(font);
+            }
+        }
+    }))
+}
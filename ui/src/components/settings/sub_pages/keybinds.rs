@@ -20,6 +20,7 @@ use kit::elements::{
 use muda::accelerator::Modifiers;
 
 use crate::components::settings::SettingSection;
+use crate::utils::keyboard::hotkeys;
 
 const AVOID_INPUT_ON_DIV: &str = r#"
     document.getElementById("$UUID").addEventListener("keypress", function (event) {
@@ -36,6 +37,63 @@ const UNFOCUS_DIV_ON_SUBMIT: &str = r#"
           }
 "#;
 
+// every shortcut of the app, grouped like the page shows them. the name is both the key of the
+// label and the start of the aria label
+const KEYBIND_GROUPS: [(&str, &[(GlobalShortcut, &str)]); 5] = [
+    (
+        "navigation",
+        &[
+            (GlobalShortcut::OpenQuickSwitcher, "open-quick-switcher"),
+            (GlobalShortcut::NextChat, "next-chat"),
+            (GlobalShortcut::PreviousChat, "previous-chat"),
+            (GlobalShortcut::OpenSettings, "open-settings"),
+            (GlobalShortcut::SetAppVisible, "hide-focus-uplink"),
+            (GlobalShortcut::LockApp, "lock-app"),
+        ],
+    ),
+    (
+        "chats",
+        &[
+            (GlobalShortcut::SendMessage, "send-message"),
+            (GlobalShortcut::FocusSearch, "focus-search"),
+        ],
+    ),
+    (
+        "calls",
+        &[
+            (GlobalShortcut::ToggleMute, "toggle-mute"),
+            (GlobalShortcut::ToggleDeafen, "toggle-deafen"),
+            (GlobalShortcut::AnswerCall, "answer-call"),
+            (GlobalShortcut::HangUpCall, "hang-up-call"),
+            (
+                GlobalShortcut::CycleCallParticipants,
+                "cycle-call-participants",
+            ),
+        ],
+    ),
+    (
+        "appearance",
+        &[
+            (GlobalShortcut::IncreaseFontSize, "increase-font-size"),
+            (GlobalShortcut::DecreaseFontSize, "decrease-font-size"),
+            (GlobalShortcut::ZoomInChat, "zoom-in-chat"),
+            (GlobalShortcut::ZoomOutChat, "zoom-out-chat"),
+            (GlobalShortcut::ResetChatZoom, "reset-chat-zoom"),
+        ],
+    ),
+    (
+        "developer",
+        &[
+            (GlobalShortcut::OpenCloseDevTools, "open-close-dev-tools"),
+            (GlobalShortcut::ToggleDevmode, "toggle-devmode"),
+            (
+                GlobalShortcut::ToggleProfilingOverlay,
+                "toggle-profiling-overlay",
+            ),
+        ],
+    ),
+];
+
 #[derive(PartialEq, Props)]
 pub struct KeybindProps {
     pub keys: Vec<String>, // TODO: This should be a Vec<Key>
@@ -166,8 +224,17 @@ pub fn KeybindSection(cx: Scope<KeybindSectionProps>) -> Element {
     }
 
     let has_conflicts = check_for_conflicts(sc, cx.props.bindings.clone());
-
-    if has_conflicts {
+    // taken by another application
+    let refused = system_shortcut && hotkeys::is_refused(&cx.props.shortcut);
+    let conflict = if has_conflicts {
+        Some(get_local_text("settings-keybinds.conflicting-keybinds"))
+    } else if refused {
+        Some(get_local_text("settings-keybinds.hotkey-taken"))
+    } else {
+        None
+    };
+
+    if conflict.is_some() {
         keybind_class.push_str(" conflicting");
     }
     cx.render(rsx!(
@@ -229,12 +296,12 @@ pub fn KeybindSection(cx: Scope<KeybindSectionProps>) -> Element {
                     cx.props.is_recording.set(None);
                     state.write().settings.is_recording_new_keybind = false;
                 },
-                if has_conflicts {
+                if conflict.is_some() {
                     rsx!(TooltipWrap {
                         tooltip: cx.render(rsx!(
                             Tooltip {
                                 arrow_position: ArrowPosition::Top,
-                                text: get_local_text("settings-keybinds.conflicting-keybinds")
+                                text: conflict.clone().unwrap_or_default(),
                             }
                         )),
                         Keybind {
@@ -247,6 +314,28 @@ pub fn KeybindSection(cx: Scope<KeybindSectionProps>) -> Element {
                     })
                 }
             },
+            Button {
+                aria_label: "system-wide-keybind-button".into(),
+                icon: Icon::GlobeAlt,
+                onpress: move |_| {
+                    if let Some((_, shortcut)) = state
+                        .write()
+                        .settings
+                        .keybinds
+                        .iter_mut()
+                        .find(|(gs, _)| *gs == cx.props.shortcut)
+                    {
+                        shortcut.system_shortcut = !shortcut.system_shortcut;
+                    }
+                },
+                appearance: if system_shortcut { kit::elements::Appearance::Primary } else { kit::elements::Appearance::Secondary },
+                tooltip: cx.render(rsx!(
+                    Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: get_local_text(if system_shortcut { "settings-keybinds.system-wide" } else { "settings-keybinds.in-app" })
+                    }
+                )),
+            },
             Button {
                 aria_label: "reset-single-keybind-button".into(),
                 icon: Icon::ArrowUturnDown,
@@ -313,62 +402,28 @@ pub fn KeybindSettings(cx: Scope) -> Element {
                     appearance: kit::elements::Appearance::Secondary
                 },
             },
-            KeybindSection {
-                aria_label: "increase-font-size-section".into(),
-                id: format!("{:?}", GlobalShortcut::IncreaseFontSize),
-                section_label: get_local_text("settings-keybinds.increase-font-size"),
-                bindings: bindings.clone(),
-                shortcut: GlobalShortcut::IncreaseFontSize,
-                is_recording: is_recording.clone(),
-            }
-            KeybindSection {
-                aria_label: "decrease-font-size-section".into(),
-                id: format!("{:?}", GlobalShortcut::DecreaseFontSize),
-                section_label: get_local_text("settings-keybinds.decrease-font-size"),
-                bindings: bindings.clone(),
-                shortcut: GlobalShortcut::DecreaseFontSize,
-                is_recording: is_recording.clone(),
-            }
-            KeybindSection {
-                aria_label: "toggle-mute-section".into(),
-                id: format!("{:?}", GlobalShortcut::ToggleMute),
-                section_label: get_local_text("settings-keybinds.toggle-mute"),
-                bindings: bindings.clone(),
-                shortcut: GlobalShortcut::ToggleMute,
-                is_recording: is_recording.clone(),
-            }
-            KeybindSection {
-                aria_label: "toggle-deafen-section".into(),
-                id: format!("{:?}", GlobalShortcut::ToggleDeafen),
-                section_label: get_local_text("settings-keybinds.toggle-deafen"),
-                bindings: bindings.clone(),
-                shortcut: GlobalShortcut::ToggleDeafen,
-                is_recording: is_recording.clone(),
-            }
-            KeybindSection {
-                aria_label: "open-close-dev-tools-section".into(),
-                id: format!("{:?}", GlobalShortcut::OpenCloseDevTools),
-                section_label: get_local_text("settings-keybinds.open-close-dev-tools"),
-                bindings: bindings.clone(),
-                shortcut: GlobalShortcut::OpenCloseDevTools,
-                is_recording: is_recording.clone(),
-            }
-            KeybindSection {
-                aria_label: "toggle-devmode-section".into(),
-                id: format!("{:?}", GlobalShortcut::ToggleDevmode),
-                section_label: get_local_text("settings-keybinds.toggle-devmode"),
-                bindings: bindings.clone(),
-                shortcut: GlobalShortcut::ToggleDevmode,
-                is_recording: is_recording.clone(),
-            }
-            KeybindSection {
-                aria_label: "hide-focus-uplink-section".into(),
-                id: format!("{:?}", GlobalShortcut::SetAppVisible),
-                section_label: get_local_text("settings-keybinds.hide-focus-uplink"),
-                bindings: bindings.clone(),
-                shortcut: GlobalShortcut::SetAppVisible,
-                is_recording: is_recording.clone(),
-            }
+            KEYBIND_GROUPS.iter().map(|(group, shortcuts)| rsx!(
+                div {
+                    key: "{group}",
+                    class: "keybind-group",
+                    aria_label: "keybind-group-{group}",
+                    p {
+                        class: "keybind-group-label",
+                        get_local_text(&format!("settings-keybinds.group-{group}"))
+                    },
+                    shortcuts.iter().map(|(shortcut, name)| rsx!(
+                        KeybindSection {
+                            key: "{name}",
+                            aria_label: format!("{name}-section"),
+                            id: format!("{:?}", shortcut),
+                            section_label: get_local_text(&format!("settings-keybinds.{name}")),
+                            bindings: bindings.clone(),
+                            shortcut: shortcut.clone(),
+                            is_recording: is_recording.clone(),
+                        }
+                    ))
+                }
+            ))
         }
     ))
 }
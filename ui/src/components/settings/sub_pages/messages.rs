@@ -1,17 +1,122 @@
 use common::{
-    language::get_local_text,
-    state::{Action, State},
+    icons::outline::Shape as Icon,
+    language::{get_local_text, get_local_text_with_args},
+    spellcheck::reload_dictionaries,
+    state::{
+        action::ConfigAction,
+        configuration::{GifProviderKind, Gifs},
+        ui::EmojiStyle,
+        Action, State, ToastNotification,
+    },
+    warp_runner::{RayGunCmd, WarpCmd},
+    STATIC_ARGS, WARP_CMD_CH,
 };
 use dioxus::prelude::*;
-use kit::elements::switch::Switch;
+use futures::{channel::oneshot, StreamExt};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    select::Select,
+    switch::Switch,
+    tooltip::{ArrowPosition, Tooltip},
+    Appearance,
+};
 use tracing::log;
+use uuid::Uuid;
 
 use crate::components::settings::SettingSection;
+use crate::BUNDLED_EMOJI_FONT_PATH;
 
 #[allow(non_snake_case)]
 pub fn Messages(cx: Scope) -> Element {
     log::trace!("Messages settings page rendered.");
     let state = use_shared_state::<State>(cx)?;
+    let duplicates = state.read().duplicate_direct_chats();
+    let num_duplicates: usize = duplicates.iter().map(|group| group.len() - 1).sum();
+    let personal_dictionary = state.read().settings.personal_dictionary.clone();
+
+    // each group is (chat to keep, duplicates...)
+    let merge_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<Vec<Vec<Uuid>>>| {
+        to_owned![state];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some(groups) = rx.next().await {
+                let mut merged = 0;
+                for group in groups {
+                    let (chat_id, duplicates) = match group.split_first() {
+                        Some((id, rest)) => (*id, rest.to_vec()),
+                        None => continue,
+                    };
+                    let (tx, rx) = oneshot::channel::<Result<(), warp::error::Error>>();
+                    if let Err(e) =
+                        warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::MergeConversations {
+                            conv_id: chat_id,
+                            duplicates: duplicates.clone(),
+                            rsp: tx,
+                        }))
+                    {
+                        log::error!("failed to send warp command: {}", e);
+                        continue;
+                    }
+
+                    let res = rx.await.expect("command canceled");
+                    match res {
+                        Ok(_) => {
+                            merged += duplicates.len();
+                            state
+                                .write()
+                                .mutate(Action::MergeChats(chat_id, duplicates));
+                        }
+                        Err(e) => {
+                            log::error!("failed to merge conversations: {e}");
+                        }
+                    }
+                }
+                state
+                    .write()
+                    .mutate(Action::AddToastNotification(ToastNotification::init(
+                        "".into(),
+                        get_local_text_with_args(
+                            "settings-messages.merged-duplicates",
+                            vec![("num", merged.to_string())],
+                        ),
+                        None,
+                        2,
+                    )));
+            }
+        }
+    });
+
+    let emoji_style_label = |style: EmojiStyle| match style {
+        EmojiStyle::Native => get_local_text("settings-messages.emoji-style-native"),
+        EmojiStyle::Bundled => get_local_text("settings-messages.emoji-style-bundled"),
+    };
+
+    // the bundled set is only offered if its font was installed
+    let emoji_styles = if BUNDLED_EMOJI_FONT_PATH.is_some() {
+        vec![EmojiStyle::Native, EmojiStyle::Bundled]
+    } else {
+        vec![EmojiStyle::Native]
+    };
+    let emoji_style = Some(state.read().ui.emoji_style)
+        .filter(|style| emoji_styles.contains(style))
+        .unwrap_or(EmojiStyle::Native);
+
+    let gifs = state.read().configuration.gifs.clone();
+    let gif_provider_label = |provider: GifProviderKind| match provider {
+        GifProviderKind::Disabled => get_local_text("settings-messages.gif-provider-disabled"),
+        GifProviderKind::Tenor => "Tenor".into(),
+        GifProviderKind::Giphy => "Giphy".into(),
+        GifProviderKind::SelfHosted => get_local_text("settings-messages.gif-provider-self-hosted"),
+    };
+    let set_gifs = move |f: &dyn Fn(&mut Gifs)| {
+        let mut gifs = state.read().configuration.gifs.clone();
+        f(&mut gifs);
+        state
+            .write()
+            .mutate(Action::Config(ConfigAction::SetGifs(gifs)));
+    };
+
     cx.render(rsx!(
         div {
             id: "settings-messages",
@@ -27,6 +132,97 @@ pub fn Messages(cx: Scope) -> Element {
                     }
                  }
              },
+            SettingSection {
+                aria_label: "emoji-style-section".into(),
+                section_label: get_local_text("settings-messages.emoji-style"),
+                section_description: get_local_text("settings-messages.emoji-style-description"),
+                Select {
+                    initial_value: emoji_style_label(emoji_style),
+                    options: emoji_styles.iter().map(|style| emoji_style_label(*style)).collect(),
+                    onselect: move |value: String| {
+                        let style = if value == emoji_style_label(EmojiStyle::Bundled) {
+                            EmojiStyle::Bundled
+                        } else {
+                            EmojiStyle::Native
+                        };
+                        state.write().mutate(Action::SetEmojiStyle(style));
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "gif-provider-section".into(),
+                section_label: get_local_text("settings-messages.gif-provider"),
+                section_description: get_local_text("settings-messages.gif-provider-description"),
+                Select {
+                    initial_value: gif_provider_label(gifs.provider),
+                    options: [GifProviderKind::Disabled, GifProviderKind::Tenor, GifProviderKind::Giphy, GifProviderKind::SelfHosted]
+                        .into_iter()
+                        .map(gif_provider_label)
+                        .collect(),
+                    onselect: move |value: String| {
+                        let provider = [GifProviderKind::Tenor, GifProviderKind::Giphy, GifProviderKind::SelfHosted]
+                            .into_iter()
+                            .find(|provider| gif_provider_label(*provider) == value)
+                            .unwrap_or_default();
+                        set_gifs(&|gifs| gifs.provider = provider);
+                    }
+                }
+            },
+            match gifs.provider {
+                GifProviderKind::Disabled => rsx!(""),
+                GifProviderKind::SelfHosted => rsx!(
+                    SettingSection {
+                        aria_label: "gif-endpoint-section".into(),
+                        section_label: get_local_text("settings-messages.gif-endpoint"),
+                        section_description: get_local_text("settings-messages.gif-endpoint-description"),
+                        Input {
+                            placeholder: "https://".into(),
+                            default_text: gifs.endpoint.clone(),
+                            aria_label: "gif-endpoint-input".into(),
+                            options: Options {
+                                clear_on_submit: false,
+                                ..Default::default()
+                            },
+                            onchange: move |(v, _): (String, bool)| {
+                                set_gifs(&|gifs| gifs.endpoint = v.clone());
+                            },
+                        }
+                    }
+                ),
+                _ => rsx!(
+                    SettingSection {
+                        aria_label: "gif-api-key-section".into(),
+                        section_label: get_local_text("settings-messages.gif-api-key"),
+                        section_description: get_local_text("settings-messages.gif-api-key-description"),
+                        Input {
+                            placeholder: get_local_text("settings-messages.gif-api-key"),
+                            default_text: gifs.api_key.clone(),
+                            aria_label: "gif-api-key-input".into(),
+                            is_password: true,
+                            options: Options {
+                                clear_on_submit: false,
+                                ..Default::default()
+                            },
+                            onchange: move |(v, _): (String, bool)| {
+                                set_gifs(&|gifs| gifs.api_key = v.clone());
+                            },
+                        }
+                    }
+                ),
+            },
+            (gifs.provider != GifProviderKind::Disabled).then(|| rsx!(
+                SettingSection {
+                    aria_label: "gif-attachment-section".into(),
+                    section_label: get_local_text("settings-messages.gif-attachment"),
+                    section_description: get_local_text("settings-messages.gif-attachment-description"),
+                    Switch {
+                        active: gifs.send_as_attachment,
+                        onflipped: move |flag| {
+                            set_gifs(&|gifs| gifs.send_as_attachment = flag);
+                        }
+                    }
+                }
+            )),
             SettingSection {
                 aria_label: "markdown-support-section".into(),
                 section_label: get_local_text("settings-messages.markdown-support"),
@@ -37,6 +233,99 @@ pub fn Messages(cx: Scope) -> Element {
                         state.write().mutate(Action::SetTransformMarkdownText(flag));
                     }
                 }
+            },
+            SettingSection {
+                aria_label: "dictionaries-section".into(),
+                section_label: get_local_text("settings-messages.dictionaries"),
+                section_description: get_local_text("settings-messages.dictionaries-description"),
+                div {
+                    class: "button-group",
+                    Button {
+                        icon: Icon::ArrowPath,
+                        aria_label: "reload-dictionaries-button".into(),
+                        appearance: Appearance::Secondary,
+                        onpress: move |_| {
+                            reload_dictionaries();
+                            state.write().mutate(Action::AddToastNotification(ToastNotification::init(
+                                "".into(),
+                                get_local_text("settings-messages.dictionaries-reloaded"),
+                                None,
+                                2,
+                            )));
+                        },
+                        tooltip: cx.render(rsx!(Tooltip {
+                            arrow_position: ArrowPosition::Right,
+                            text: get_local_text("settings-messages.reload-dictionaries"),
+                        }))
+                    },
+                    Button {
+                        icon: Icon::FolderOpen,
+                        aria_label: "open-dictionaries-folder-button".into(),
+                        appearance: Appearance::Secondary,
+                        onpress: move |_| {
+                            let _ = opener::open(&STATIC_ARGS.dictionaries_path);
+                        },
+                        tooltip: cx.render(rsx!(Tooltip {
+                            arrow_position: ArrowPosition::Right,
+                            text: get_local_text("settings-messages.open-dictionaries-folder"),
+                        }))
+                    },
+                }
+            },
+            SettingSection {
+                aria_label: "personal-dictionary-section".into(),
+                section_label: get_local_text("settings-messages.personal-dictionary"),
+                section_description: get_local_text("settings-messages.personal-dictionary-description"),
+                Input {
+                    placeholder: get_local_text("settings-messages.add-word"),
+                    aria_label: "personal-dictionary-input".into(),
+                    onreturn: move |(v, _, _): (String, bool, _)| {
+                        let word = v.trim().to_string();
+                        if !word.is_empty() && !word.contains(char::is_whitespace) {
+                            state.write().mutate(Action::AddToDictionary(word));
+                        }
+                    },
+                }
+            },
+            (!personal_dictionary.is_empty()).then(|| rsx!(
+                div {
+                    class: "personal-dictionary",
+                    aria_label: "personal-dictionary",
+                    personal_dictionary.into_iter().map(|word| {
+                        let removed = word.clone();
+                        rsx!(div {
+                            key: "{word}",
+                            class: "personal-dictionary-word",
+                            span { "{word}" },
+                            Button {
+                                icon: Icon::XMark,
+                                aria_label: "remove-word-button".into(),
+                                appearance: Appearance::Secondary,
+                                onpress: move |_| {
+                                    state.write().mutate(Action::RemoveFromDictionary(removed.clone()));
+                                },
+                            }
+                        })
+                    })
+                }
+            )),
+            SettingSection {
+                aria_label: "merge-duplicates-section".into(),
+                section_label: get_local_text("settings-messages.merge-duplicates"),
+                section_description: get_local_text_with_args(
+                    "settings-messages.merge-duplicates-description",
+                    vec![("num", num_duplicates.to_string())],
+                ),
+                Button {
+                    text: get_local_text("settings-messages.merge"),
+                    aria_label: "merge-duplicates-button".into(),
+                    appearance: Appearance::Secondary,
+                    icon: Icon::ArrowsPointingIn,
+                    disabled: num_duplicates == 0,
+                    onpress: move |_| {
+                        merge_ch.send(duplicates.clone());
+                    }
+                }
             }
         }
     ))
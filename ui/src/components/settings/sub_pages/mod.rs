@@ -2,12 +2,16 @@ pub mod about;
 pub mod accessibility;
 pub mod audio;
 pub mod developer;
+pub mod devices;
 pub mod extensions;
 pub mod files;
 pub mod general;
 pub mod keybinds;
 pub mod licenses;
 pub mod messages;
+pub mod network;
 pub mod notifications;
 pub mod privacy;
 pub mod profile;
+pub mod storage;
+pub mod theme_editor;
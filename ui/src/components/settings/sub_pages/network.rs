@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+use arboard::Clipboard;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::sounds;
+use common::state::{
+    action::ConfigAction,
+    network::{ConnectionStatus, RelayStatus},
+    Action, State, ToastNotification,
+};
+use common::utils::network_check;
+use common::{icons::outline::Shape as Icon, DiscoveryMode, STATIC_ARGS};
+use dioxus::prelude::*;
+use futures::StreamExt;
+use humansize::{format_size, DECIMAL};
+use kit::elements::{button::Button, range::Range, switch::Switch, Appearance};
+use tracing::log;
+
+use crate::components::settings::SettingSection;
+
+#[allow(non_snake_case)]
+pub fn NetworkSettings(cx: Scope) -> Element {
+    log::trace!("Network settings page rendered.");
+    let state = use_shared_state::<State>(cx)?;
+    let checking = use_state(cx, || false);
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<()>| {
+        to_owned![state, checking];
+        async move {
+            while rx.next().await.is_some() {
+                let probe = network_check::probe(Duration::from_secs(5)).await;
+                let mut state = state.write();
+                if state.ui.network.update(probe) {
+                    state.retry_queued_messages();
+                }
+                checking.set(false);
+            }
+        }
+    });
+
+    let diagnostics = diagnostics(&state.read());
+    let copied_text = diagnostics
+        .iter()
+        .map(|(label, value)| format!("{label}: {value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let transfers = state.read().configuration.transfers;
+
+    cx.render(rsx!(
+        div {
+            id: "settings-network",
+            aria_label: "settings-network",
+            SettingSection {
+                aria_label: "max-upload-section".into(),
+                section_label: get_local_text("settings-network.max-upload"),
+                section_description: limit_text(transfers.max_upload_kbps),
+                Range {
+                    aria_label: "max-upload-range".into(),
+                    initial_value: transfers.max_upload_kbps as f32 / 1000.0,
+                    min: 0.0,
+                    max: MAX_LIMIT_MBPS,
+                    step: 0.5,
+                    with_buttons: true,
+                    onchange: move |mbps: f32| {
+                        let mut transfers = state.read().configuration.transfers;
+                        transfers.max_upload_kbps = (mbps * 1000.0).round() as u32;
+                        state.write().mutate(Action::Config(ConfigAction::SetTransfers(transfers)));
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "max-download-section".into(),
+                section_label: get_local_text("settings-network.max-download"),
+                section_description: limit_text(transfers.max_download_kbps),
+                Range {
+                    aria_label: "max-download-range".into(),
+                    initial_value: transfers.max_download_kbps as f32 / 1000.0,
+                    min: 0.0,
+                    max: MAX_LIMIT_MBPS,
+                    step: 0.5,
+                    with_buttons: true,
+                    onchange: move |mbps: f32| {
+                        let mut transfers = state.read().configuration.transfers;
+                        transfers.max_download_kbps = (mbps * 1000.0).round() as u32;
+                        state.write().mutate(Action::Config(ConfigAction::SetTransfers(transfers)));
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "concurrent-uploads-section".into(),
+                section_label: get_local_text("settings-network.concurrent-uploads"),
+                section_description: concurrent_text(transfers.max_concurrent_uploads),
+                Range {
+                    aria_label: "concurrent-uploads-range".into(),
+                    initial_value: transfers.max_concurrent_uploads as f32,
+                    min: 0.0,
+                    max: MAX_CONCURRENT_UPLOADS,
+                    step: 1.0,
+                    with_buttons: true,
+                    onchange: move |count: f32| {
+                        let mut transfers = state.read().configuration.transfers;
+                        transfers.max_concurrent_uploads = count.round() as u32;
+                        state.write().mutate(Action::Config(ConfigAction::SetTransfers(transfers)));
+                    }
+                }
+            },
+            SettingSection {
+                aria_label: "retry-corrupted-section".into(),
+                section_label: get_local_text("settings-network.retry-corrupted"),
+                section_description: get_local_text("settings-network.retry-corrupted-description"),
+                Switch {
+                    active: transfers.retry_corrupted_downloads,
+                    onflipped: move |value| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+                        let mut transfers = state.read().configuration.transfers;
+                        transfers.retry_corrupted_downloads = value;
+                        state.write().mutate(Action::Config(ConfigAction::SetTransfers(transfers)));
+                    },
+                }
+            },
+            diagnostics.into_iter().map(|(label, value)| rsx!(
+                SettingSection {
+                    key: "{label}",
+                    section_label: label,
+                    section_description: value,
+                }
+            )),
+            SettingSection {
+                aria_label: "network-diagnostics-section".into(),
+                section_label: get_local_text("settings-network.diagnostics"),
+                section_description: get_local_text("settings-network.diagnostics-description"),
+                no_border: true,
+                Button {
+                    text: get_local_text("settings-network.check-again"),
+                    aria_label: "check-network-button".into(),
+                    icon: Icon::ArrowPath,
+                    appearance: Appearance::Secondary,
+                    loading: *checking.get(),
+                    disabled: *checking.get(),
+                    onpress: move |_| {
+                        checking.set(true);
+                        ch.send(());
+                    },
+                },
+                Button {
+                    text: get_local_text("settings-network.copy-diagnostics"),
+                    aria_label: "copy-diagnostics-button".into(),
+                    icon: Icon::DocumentDuplicate,
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        let text = format!("Uplink {}\n{copied_text}", env!("CARGO_PKG_VERSION"));
+                        let copied = match Clipboard::new() {
+                            Ok(mut c) => c.set_text(text).map_err(|e| e.to_string()),
+                            Err(e) => Err(e.to_string()),
+                        };
+                        let message = match copied {
+                            Ok(_) => "settings-network.diagnostics-copied",
+                            Err(e) => {
+                                log::error!("failed to copy diagnostics: {e}");
+                                "settings-network.copy-failed"
+                            }
+                        };
+                        state.write().mutate(Action::AddToastNotification(ToastNotification::init(
+                            "".into(),
+                            get_local_text(message),
+                            None,
+                            2,
+                        )));
+                    },
+                },
+            },
+        }
+    ))
+}
+
+// the limits go up to this, in MB/s. 0 is no limit
+const MAX_LIMIT_MBPS: f32 = 50.0;
+
+fn limit_text(kbps: u32) -> String {
+    if kbps == 0 {
+        return get_local_text("settings-network.no-limit");
+    }
+    get_local_text_with_args(
+        "settings-network.limit",
+        vec![("speed", format_size(kbps as u64 * 1000, DECIMAL))],
+    )
+}
+
+// 0 starts all the uploads at once
+const MAX_CONCURRENT_UPLOADS: f32 = 10.0;
+
+fn concurrent_text(count: u32) -> String {
+    if count == 0 {
+        return get_local_text("settings-network.concurrent-uploads-all");
+    }
+    get_local_text_with_args(
+        "settings-network.concurrent-uploads-description",
+        vec![("count", count)],
+    )
+}
+
+// labels and values shown on the page and copied to the clipboard
+fn diagnostics(state: &State) -> Vec<(String, String)> {
+    let network = &state.ui.network;
+    let unknown = || get_local_text("settings-network.unknown");
+
+    let status = get_local_text(match state.connection_status() {
+        ConnectionStatus::Connecting => "network.connecting",
+        ConnectionStatus::Connected => "network.connected",
+        ConnectionStatus::RelayOnly => "network.relay-only",
+        ConnectionStatus::Offline => "network.offline",
+        ConnectionStatus::TimedOut => "network.timed-out",
+    });
+    let relay = match network.relay {
+        RelayStatus::Unknown => unknown(),
+        RelayStatus::NotConfigured => get_local_text("settings-network.relay-not-used"),
+        RelayStatus::Reachable { rtt } => get_local_text_with_args(
+            "settings-network.relay-reachable",
+            vec![("ms", rtt.as_millis() as u64)],
+        ),
+        RelayStatus::Unreachable => get_local_text("settings-network.relay-unreachable"),
+    };
+    let relay_addresses = STATIC_ARGS.discovery.addresses();
+    let relay_addresses = if relay_addresses.is_empty() {
+        get_local_text("settings-network.none")
+    } else {
+        relay_addresses.join(", ")
+    };
+    let local_address = network
+        .local_addr
+        .map(|addr| addr.to_string())
+        .unwrap_or_else(unknown);
+    let nat = match network.behind_nat() {
+        Some(true) => get_local_text("settings-network.nat-private"),
+        Some(false) => get_local_text("settings-network.nat-public"),
+        None => unknown(),
+    };
+    let discovery = match &STATIC_ARGS.discovery {
+        DiscoveryMode::Full => "DHT".to_string(),
+        DiscoveryMode::Shuttle => "Shuttle".to_string(),
+        DiscoveryMode::RzPoint { .. } => "Rendezvous".to_string(),
+        DiscoveryMode::Disable => get_local_text("settings-network.none"),
+    };
+    let transports = if STATIC_ARGS.disable_quic {
+        "TCP"
+    } else {
+        "TCP, QUIC"
+    };
+    let last_checked = network
+        .last_checked
+        .map(|time| time.format("%H:%M:%S").to_string())
+        .unwrap_or_else(unknown);
+    let peer_seen = network
+        .peer_seen
+        .map(|time| time.format("%H:%M:%S").to_string())
+        .unwrap_or_else(unknown);
+
+    vec![
+        ("settings-network.status", status),
+        (
+            "settings-network.peers",
+            state.online_friends_count().to_string(),
+        ),
+        ("settings-network.relay", relay),
+        ("settings-network.relay-addresses", relay_addresses),
+        ("settings-network.local-address", local_address),
+        ("settings-network.nat", nat),
+        ("settings-network.discovery", discovery),
+        ("settings-network.transports", transports.to_string()),
+        ("settings-network.last-checked", last_checked),
+        ("settings-network.peer-seen", peer_seen),
+    ]
+    .into_iter()
+    .map(|(label, value)| (get_local_text(label), value))
+    .collect()
+}
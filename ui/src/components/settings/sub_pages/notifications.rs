@@ -1,11 +1,21 @@
+use arboard::Clipboard;
 #[allow(unused_imports)]
 use common::icons::outline::Shape as Icon;
 use common::language::get_local_text;
 use common::sounds;
-use common::state::{action::ConfigAction, Action, State};
+use common::state::{
+    action::ConfigAction,
+    configuration::Webhooks,
+    do_not_disturb::{DoNotDisturb, Schedule},
+    Action, State, ToastNotification,
+};
+use common::utils::webhooks;
 use dioxus::prelude::*;
+use kit::elements::input::{Input, Options};
 #[allow(unused_imports)]
-use kit::elements::{button::Button, switch::Switch};
+use kit::elements::{button::Button, switch::Switch, Appearance};
+use tracing::log;
+use uuid::Uuid;
 
 use crate::components::settings::SettingSection;
 
@@ -13,6 +23,30 @@ use crate::components::settings::SettingSection;
 pub fn NotificationSettings(cx: Scope) -> Element {
     let state = use_shared_state::<State>(cx)?;
 
+    let hooks = state.read().configuration.webhooks.clone();
+    let invalid_url = !hooks.url.is_empty() && !webhooks::is_local_endpoint(&hooks.url);
+    let set_webhooks = move |f: &dyn Fn(&mut Webhooks)| {
+        let mut hooks = state.read().configuration.webhooks.clone();
+        f(&mut hooks);
+        state
+            .write()
+            .mutate(Action::Config(ConfigAction::SetWebhooks(hooks)));
+    };
+    let copy_secret = move |_| {
+        let secret = state.read().configuration.webhooks.secret.clone();
+        match Clipboard::new().and_then(|mut c| c.set_text(secret)) {
+            Ok(_) => state
+                .write()
+                .mutate(Action::AddToastNotification(ToastNotification::init(
+                    "".into(),
+                    get_local_text("settings-notifications.webhook-secret-copied"),
+                    None,
+                    2,
+                ))),
+            Err(e) => log::warn!("Unable to set text to clipboard: {e}"),
+        }
+    };
+
     cx.render(rsx!(
         div {
             id: "settings-notifications",
@@ -43,6 +77,7 @@ pub fn NotificationSettings(cx: Scope) -> Element {
                     }
                 }
             },
+            DoNotDisturbSettings {},
             div {
                 class: format_args!("{}", if state.read().configuration.notifications.enabled { "enabled" } else { "disabled" }),
                 SettingSection {
@@ -90,7 +125,235 @@ pub fn NotificationSettings(cx: Scope) -> Element {
                         }
                     }
                 },
+            },
+            SettingSection {
+                aria_label: "webhooks-section".into(),
+                section_label: get_local_text("settings-notifications.webhooks"),
+                section_description: get_local_text("settings-notifications.webhooks-description"),
+                Switch {
+                    active: hooks.enabled,
+                    onflipped: move |e| set_webhooks(&|hooks| hooks.enabled = e),
+                }
+            },
+            hooks.enabled.then(|| rsx!(
+                div {
+                    class: "webhooks",
+                    SettingSection {
+                        aria_label: "webhook-url-section".into(),
+                        section_label: get_local_text("settings-notifications.webhook-url"),
+                        section_description: get_local_text("settings-notifications.webhook-url-description"),
+                        div {
+                            class: "webhook-url",
+                            Input {
+                                placeholder: "http://localhost:8080/uplink".into(),
+                                default_text: hooks.url.clone(),
+                                aria_label: "webhook-url-input".into(),
+                                options: Options {
+                                    clear_on_submit: false,
+                                    ..Default::default()
+                                },
+                                onchange: move |(v, _): (String, bool)| {
+                                    let v = v.trim().to_string();
+                                    set_webhooks(&|hooks| hooks.url = v.clone());
+                                },
+                            },
+                            invalid_url.then(|| rsx!(
+                                span {
+                                    class: "error",
+                                    aria_label: "webhook-url-error",
+                                    get_local_text("settings-notifications.webhook-url-invalid")
+                                }
+                            ))
+                        }
+                    },
+                    SettingSection {
+                        aria_label: "webhook-secret-section".into(),
+                        section_label: get_local_text("settings-notifications.webhook-secret"),
+                        section_description: get_local_text("settings-notifications.webhook-secret-description"),
+                        div {
+                            class: "button-group",
+                            Button {
+                                aria_label: "copy-webhook-secret-button".into(),
+                                text: get_local_text("settings-notifications.webhook-copy-secret"),
+                                icon: Icon::ClipboardDocument,
+                                appearance: Appearance::Secondary,
+                                onpress: copy_secret,
+                            },
+                            Button {
+                                aria_label: "regenerate-webhook-secret-button".into(),
+                                text: get_local_text("settings-notifications.webhook-regenerate-secret"),
+                                icon: Icon::ArrowPath,
+                                appearance: Appearance::Secondary,
+                                onpress: move |_| set_webhooks(&|hooks| hooks.secret = webhooks::new_secret()),
+                            },
+                        }
+                    },
+                    SettingSection {
+                        aria_label: "webhook-messages-section".into(),
+                        section_label: get_local_text("messages"),
+                        section_description: get_local_text("settings-notifications.webhook-messages-description"),
+                        Switch {
+                            active: hooks.new_message,
+                            onflipped: move |e| set_webhooks(&|hooks| hooks.new_message = e),
+                        }
+                    },
+                    SettingSection {
+                        aria_label: "webhook-friends-section".into(),
+                        section_label: get_local_text("friends"),
+                        section_description: get_local_text("settings-notifications.webhook-friends-description"),
+                        Switch {
+                            active: hooks.friend_request,
+                            onflipped: move |e| set_webhooks(&|hooks| hooks.friend_request = e),
+                        }
+                    },
+                    SettingSection {
+                        aria_label: "webhook-transfers-section".into(),
+                        section_label: get_local_text("settings-notifications.webhook-transfers"),
+                        section_description: get_local_text("settings-notifications.webhook-transfers-description"),
+                        Switch {
+                            active: hooks.transfer_complete,
+                            onflipped: move |e| set_webhooks(&|hooks| hooks.transfer_complete = e),
+                        }
+                    },
+                    SettingSection {
+                        aria_label: "webhook-test-section".into(),
+                        section_label: get_local_text("settings-notifications.webhook-test"),
+                        section_description: get_local_text("settings-notifications.webhook-test-description"),
+                        Button {
+                            aria_label: "webhook-test-button".into(),
+                            text: get_local_text("settings-notifications.webhook-test"),
+                            icon: Icon::PaperAirplane,
+                            appearance: Appearance::Secondary,
+                            disabled: hooks.url.is_empty() || invalid_url,
+                            onpress: move |_| webhooks::send_test(),
+                        }
+                    },
+                }
+            ))
+        }
+    ))
+}
+
+const DAYS: [&str; 7] = [
+    "settings-notifications.monday",
+    "settings-notifications.tuesday",
+    "settings-notifications.wednesday",
+    "settings-notifications.thursday",
+    "settings-notifications.friday",
+    "settings-notifications.saturday",
+    "settings-notifications.sunday",
+];
+
+#[allow(non_snake_case)]
+fn DoNotDisturbSettings(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+
+    let dnd = state.read().settings.do_not_disturb.clone();
+    let set_dnd = move |f: &dyn Fn(&mut DoNotDisturb)| {
+        let mut dnd = state.read().settings.do_not_disturb.clone();
+        f(&mut dnd);
+        state.write().mutate(Action::SetDoNotDisturb(dnd));
+    };
+    let set_schedule = move |id: Uuid, f: &dyn Fn(&mut Schedule)| {
+        set_dnd(&|dnd| {
+            if let Some(schedule) = dnd.schedules.iter_mut().find(|s| s.id == id) {
+                f(schedule);
             }
+        })
+    };
+    // times are entered as 22:00, anything else is ignored until it parses
+    let parse_time = |v: &str| chrono::NaiveTime::parse_from_str(v.trim(), "%H:%M").ok();
+
+    cx.render(rsx!(
+        SettingSection {
+            aria_label: "do-not-disturb-section".into(),
+            section_label: get_local_text("settings-notifications.do-not-disturb"),
+            section_description: get_local_text("settings-notifications.do-not-disturb-description"),
+            Switch {
+                active: dnd.enabled,
+                onflipped: move |e| {
+                    if state.read().configuration.audiovideo.interface_sounds {
+                        sounds::Play(sounds::Sounds::Flip);
+                    }
+                    set_dnd(&|dnd| dnd.enabled = e);
+                }
+            }
+        },
+        SettingSection {
+            aria_label: "do-not-disturb-schedules-section".into(),
+            section_label: get_local_text("settings-notifications.schedules"),
+            section_description: get_local_text("settings-notifications.schedules-description"),
+            Button {
+                aria_label: "add-schedule-button".into(),
+                text: get_local_text("settings-notifications.add-schedule"),
+                icon: Icon::Plus,
+                appearance: Appearance::Secondary,
+                onpress: move |_| set_dnd(&|dnd| dnd.schedules.push(Schedule::default())),
+            }
+        },
+        div {
+            class: "dnd-schedules",
+            dnd.schedules.iter().map(|schedule| {
+                let id = schedule.id;
+                rsx!(div {
+                    key: "{id}",
+                    class: "dnd-schedule",
+                    aria_label: "dnd-schedule",
+                    Switch {
+                        active: schedule.enabled,
+                        onflipped: move |e| set_schedule(id, &|s| s.enabled = e),
+                    },
+                    div {
+                        class: "dnd-schedule-times",
+                        Input {
+                            placeholder: "22:00".into(),
+                            default_text: schedule.start.format("%H:%M").to_string(),
+                            aria_label: "dnd-schedule-start".into(),
+                            options: Options {
+                                clear_on_submit: false,
+                                ..Default::default()
+                            },
+                            onchange: move |(v, _): (String, bool)| {
+                                if let Some(time) = parse_time(&v) {
+                                    set_schedule(id, &|s| s.start = time);
+                                }
+                            },
+                        },
+                        span { "–" },
+                        Input {
+                            placeholder: "08:00".into(),
+                            default_text: schedule.end.format("%H:%M").to_string(),
+                            aria_label: "dnd-schedule-end".into(),
+                            options: Options {
+                                clear_on_submit: false,
+                                ..Default::default()
+                            },
+                            onchange: move |(v, _): (String, bool)| {
+                                if let Some(time) = parse_time(&v) {
+                                    set_schedule(id, &|s| s.end = time);
+                                }
+                            },
+                        },
+                    },
+                    div {
+                        class: "dnd-schedule-days",
+                        DAYS.iter().enumerate().map(|(day, label)| rsx!(Button {
+                            key: "{label}",
+                            text: get_local_text(label),
+                            aria_label: format!("dnd-schedule-day-{day}"),
+                            small: true,
+                            appearance: if schedule.days[day] { Appearance::Primary } else { Appearance::Secondary },
+                            onpress: move |_| set_schedule(id, &|s| s.days[day] = !s.days[day]),
+                        })),
+                    },
+                    Button {
+                        aria_label: "remove-schedule-button".into(),
+                        icon: Icon::Trash,
+                        appearance: Appearance::Secondary,
+                        onpress: move |_| set_dnd(&|dnd| dnd.schedules.retain(|s| s.id != id)),
+                    },
+                })
+            })
         }
     ))
 }
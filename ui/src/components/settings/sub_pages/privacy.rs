@@ -1,30 +1,203 @@
-/*
-use common::icons::outline::Shape as Icon;
 use common::language::get_local_text;
+use common::{
+    sounds,
+    state::{action::ConfigAction, Action, State},
+    utils::{
+        duress::{self, DuressAction},
+        keychain,
+    },
+};
 use dioxus::prelude::*;
-use kit::elements::{button::Button, Appearance};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options, Validation},
+    select::Select,
+    switch::Switch,
+    Appearance,
+};
 use tracing::log;
 
 use crate::components::settings::SettingSection;
 
+fn action_label(action: DuressAction) -> String {
+    match action {
+        DuressAction::Wipe => get_local_text("settings-privacy.duress-action-wipe"),
+        DuressAction::Decoy => get_local_text("settings-privacy.duress-action-decoy"),
+    }
+}
+
 #[allow(non_snake_case)]
 pub fn PrivacySettings(cx: Scope) -> Element {
     log::trace!("Privacy settings page rendered.");
+    let state = use_shared_state::<State>(cx)?;
+    let keychain_available = use_state(cx, keychain::available);
+    let privacy = state.read().configuration.privacy;
+    // None if no duress pin is set
+    let duress_action = use_state(cx, duress::action);
+    let new_duress_pin = use_state(cx, String::new);
+    let reset_duress_input = use_state(cx, || false);
+    let duress_saved = use_state(cx, || false);
+
+    // same rules as the pin itself
+    let pin_validation = Validation {
+        max_length: Some(32),
+        min_length: Some(4),
+        alpha_numeric_only: false,
+        no_whitespace: true,
+        ignore_colons: false,
+        special_chars: None,
+    };
+    let save_duress_pin = move || {
+        let action = duress_action.get().unwrap_or_default();
+        match duress::set(new_duress_pin.get(), action) {
+            Ok(_) => {
+                duress_action.set(Some(action));
+                duress_saved.set(true);
+                // the saved pin would unlock the account without the duress pin being checked
+                cx.spawn(async {
+                    if let Err(e) = keychain::forget_pin().await {
+                        log::error!("failed to remove the PIN from the keychain: {e}");
+                    }
+                });
+            }
+            Err(e) => log::error!("failed to save the duress pin: {e}"),
+        }
+        new_duress_pin.set(String::new());
+        reset_duress_input.set(true);
+    };
+
     cx.render(rsx!(
         div {
             id: "settings-privacy",
             aria_label: "settings-privacy",
             SettingSection {
-                section_label: get_local_text("settings-privacy.backup-recovery-phrase"),
-                section_description: get_local_text("settings-privacy.backup-phrase-description"),
-                Button {
-                    text: get_local_text("settings-privacy.backup-phrase"),
-                    aria_label: "backup-phrase-button".into(),
-                    appearance: Appearance::Secondary,
-                    icon: Icon::DocumentText,
+                aria_label: "read-receipts-section".into(),
+                section_label: get_local_text("settings-privacy.read-receipts"),
+                section_description: get_local_text("settings-privacy.read-receipts-description"),
+                Switch {
+                    active: state.read().configuration.privacy.read_receipts,
+                    onflipped: move |value| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+                        state.write().mutate(Action::Config(ConfigAction::SetReadReceiptsEnabled(value)));
+                    },
+                }
+            },
+            SettingSection {
+                aria_label: "typing-indicators-section".into(),
+                section_label: get_local_text("settings-privacy.typing-indicators"),
+                section_description: get_local_text("settings-privacy.typing-indicators-description"),
+                Switch {
+                    active: state.read().configuration.privacy.typing_indicators,
+                    onflipped: move |value| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+                        state.write().mutate(Action::Config(ConfigAction::SetTypingIndicatorsEnabled(value)));
+                    },
+                }
+            },
+            keychain_available.get().then(|| rsx!(SettingSection {
+                aria_label: "keychain-unlock-section".into(),
+                section_label: get_local_text("settings-privacy.keychain-unlock"),
+                section_description: get_local_text("settings-privacy.keychain-unlock-description"),
+                Switch {
+                    active: privacy.keychain_unlock,
+                    onflipped: move |value| {
+                        if state.read().configuration.audiovideo.interface_sounds {
+                            sounds::Play(sounds::Sounds::Flip);
+                        }
+                        state.write().mutate(Action::Config(ConfigAction::SetKeychainUnlock(value)));
+                        if !value {
+                            cx.spawn(async {
+                                if let Err(e) = keychain::forget_pin().await {
+                                    log::error!("failed to remove the PIN from the keychain: {e}");
+                                }
+                            });
+                        }
+                    },
                 }
+            })),
+            SettingSection {
+                aria_label: "duress-pin-section".into(),
+                section_label: get_local_text("settings-privacy.duress-pin"),
+                section_description: get_local_text("settings-privacy.duress-pin-description"),
+                no_border: duress_action.is_some(),
+                div {
+                    class: "duress-pin",
+                    Input {
+                        id: "duress-pin-input".to_owned(),
+                        is_password: true,
+                        disable_onblur: true,
+                        aria_label: "duress-pin-input".into(),
+                        placeholder: get_local_text("settings-privacy.duress-pin-placeholder"),
+                        reset: reset_duress_input.clone(),
+                        options: Options {
+                            with_validation: Some(pin_validation),
+                            with_clear_btn: true,
+                            ..Default::default()
+                        },
+                        onchange: move |(val, valid): (String, bool)| {
+                            duress_saved.set(false);
+                            new_duress_pin.set(if valid { val } else { String::new() });
+                        },
+                        onreturn: move |_| {
+                            if !new_duress_pin.is_empty() {
+                                save_duress_pin();
+                            }
+                        },
+                    },
+                    Button {
+                        aria_label: "save-duress-pin-button".into(),
+                        text: get_local_text("settings-privacy.duress-pin-save"),
+                        disabled: new_duress_pin.is_empty(),
+                        onpress: move |_| save_duress_pin(),
+                    },
+                    duress_action.is_some().then(|| rsx!(
+                        Button {
+                            aria_label: "remove-duress-pin-button".into(),
+                            text: get_local_text("settings-privacy.duress-pin-remove"),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| {
+                                if let Err(e) = duress::clear() {
+                                    log::error!("failed to remove the duress pin: {e}");
+                                }
+                                duress_action.set(None);
+                                duress_saved.set(false);
+                            },
+                        }
+                    )),
+                },
+                (*duress_saved.get()).then(|| rsx!(
+                    span {
+                        class: "duress-pin-saved",
+                        get_local_text("settings-privacy.duress-pin-saved")
+                    }
+                )),
             },
+            duress_action.get().map(|action| rsx!(
+                SettingSection {
+                    aria_label: "duress-action-section".into(),
+                    section_label: get_local_text("settings-privacy.duress-action"),
+                    section_description: get_local_text("settings-privacy.duress-action-description"),
+                    Select {
+                        initial_value: action_label(action),
+                        options: vec![action_label(DuressAction::Wipe), action_label(DuressAction::Decoy)],
+                        onselect: move |label: String| {
+                            let action = if label == action_label(DuressAction::Decoy) {
+                                DuressAction::Decoy
+                            } else {
+                                DuressAction::Wipe
+                            };
+                            match duress::set_action(action) {
+                                Ok(_) => duress_action.set(Some(action)),
+                                Err(e) => log::error!("failed to change the duress action: {e}"),
+                            }
+                        }
+                    }
+                }
+            )),
         }
     ))
 }
-*/
@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+
+use common::icons::outline::Shape as Icon;
+use common::language::get_local_text;
+use common::state::{Action, State, ToastNotification};
+use common::warp_runner::{OtherCmd, WarpCmd, BACKUP_EXTENSION};
+use common::{STATIC_ARGS, WARP_CMD_CH};
+use dioxus::prelude::*;
+use futures::channel::oneshot;
+use futures::StreamExt;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    label::Label,
+    Appearance,
+};
+use kit::layout::modal::Modal;
+use rfd::FileDialog;
+use tracing::log;
+
+use crate::components::settings::SettingSection;
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+// exports the active profile (keys, friends, conversations and settings) as a file encrypted with a passphrase.
+// it can be restored from the create account page
+#[allow(non_snake_case)]
+pub fn BackupSection(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let show_modal = use_state(cx, || false);
+    let passphrase = use_state(cx, String::new);
+    let confirmation = use_state(cx, String::new);
+    let exporting = use_state(cx, || false);
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<(String, PathBuf)>| {
+        to_owned![state, show_modal, exporting];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some((passphrase, dest)) = rx.next().await {
+                // the state file is part of the backup, make sure it is up to date
+                if let Err(e) = state.read().save_now() {
+                    log::error!("failed to save state before the backup: {e}");
+                }
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::Other(OtherCmd::ExportBackup {
+                    src: STATIC_ARGS.uplink_path(),
+                    exclude: vec![],
+                    passphrase,
+                    dest,
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send warp command: {}", e);
+                    exporting.set(false);
+                    continue;
+                }
+
+                let message = match rx.await.expect("command canceled") {
+                    Ok(_) => {
+                        show_modal.set(false);
+                        "settings-profile.backup-exported"
+                    }
+                    Err(e) => {
+                        log::error!("failed to export backup: {e}");
+                        "settings-profile.backup-export-failed"
+                    }
+                };
+                exporting.set(false);
+                state
+                    .write()
+                    .mutate(Action::AddToastNotification(ToastNotification::init(
+                        "".into(),
+                        get_local_text(message),
+                        None,
+                        2,
+                    )));
+            }
+        }
+    });
+
+    let error = if passphrase.is_empty() {
+        None
+    } else if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        Some(get_local_text("settings-profile.backup-passphrase-short"))
+    } else if !confirmation.is_empty() && passphrase.get() != confirmation.get() {
+        Some(get_local_text(
+            "settings-profile.backup-passphrase-mismatch",
+        ))
+    } else {
+        None
+    };
+    let can_export = error.is_none() && !passphrase.is_empty() && passphrase == confirmation;
+
+    let close = move || {
+        show_modal.set(false);
+        passphrase.set(String::new());
+        confirmation.set(String::new());
+    };
+
+    cx.render(rsx!(
+        SettingSection {
+            aria_label: "backup-section".into(),
+            section_label: get_local_text("settings-profile.backup"),
+            section_description: get_local_text("settings-profile.backup-description"),
+            Button {
+                text: get_local_text("settings-profile.export-backup"),
+                aria_label: "export-backup-button".into(),
+                icon: Icon::ArrowDownTray,
+                appearance: Appearance::Secondary,
+                onpress: move |_| show_modal.set(true),
+            }
+        },
+        show_modal.then(|| rsx!(
+            Modal {
+                open: true,
+                onclose: move |_| close(),
+                transparent: false,
+                close_on_click_inside_modal: false,
+                div {
+                    class: "backup-container",
+                    aria_label: "backup-modal",
+                    Label {
+                        text: get_local_text("settings-profile.export-backup"),
+                        aria_label: "backup-modal-label".into(),
+                    },
+                    p {
+                        get_local_text("settings-profile.backup-passphrase-description")
+                    },
+                    Input {
+                        id: "backup-passphrase-input".to_owned(),
+                        focus: true,
+                        is_password: true,
+                        disable_onblur: true,
+                        aria_label: "backup-passphrase-input".into(),
+                        icon: Icon::Key,
+                        placeholder: get_local_text("settings-profile.backup-passphrase"),
+                        options: Options {
+                            clear_on_submit: false,
+                            ..Default::default()
+                        },
+                        onchange: move |(val, _): (String, bool)| passphrase.set(val),
+                    },
+                    Input {
+                        id: "backup-confirm-input".to_owned(),
+                        is_password: true,
+                        disable_onblur: true,
+                        aria_label: "backup-confirm-input".into(),
+                        icon: Icon::Key,
+                        placeholder: get_local_text("settings-profile.backup-passphrase-confirm"),
+                        options: Options {
+                            clear_on_submit: false,
+                            ..Default::default()
+                        },
+                        onchange: move |(val, _): (String, bool)| confirmation.set(val),
+                    },
+                    error.map(|error| rsx!(
+                        span {
+                            class: "error",
+                            "{error}"
+                        }
+                    )),
+                    div {
+                        class: "button-group",
+                        Button {
+                            text: get_local_text("settings-profile.export-backup"),
+                            aria_label: "confirm-export-backup-button".into(),
+                            icon: Icon::ArrowDownTray,
+                            loading: *exporting.get(),
+                            disabled: !can_export || *exporting.get(),
+                            onpress: move |_| {
+                                let username = state.read().username();
+                                let dest = match FileDialog::new()
+                                    .set_directory(dirs::home_dir().unwrap_or_default())
+                                    .set_file_name(&format!("uplink-backup-{username}.{BACKUP_EXTENSION}"))
+                                    .save_file()
+                                {
+                                    Some(dest) => dest,
+                                    None => return,
+                                };
+                                exporting.set(true);
+                                ch.send((passphrase.get().clone(), dest));
+                            }
+                        },
+                        Button {
+                            text: get_local_text("uplink.cancel"),
+                            aria_label: "cancel-export-backup-button".into(),
+                            icon: Icon::NoSymbol,
+                            appearance: Appearance::Secondary,
+                            disabled: *exporting.get(),
+                            onpress: move |_| close(),
+                        }
+                    }
+                }
+            }
+        ))
+    ))
+}
@@ -1,10 +1,16 @@
 use std::path::PathBuf;
 
 use arboard::Clipboard;
+use chrono::Local;
 use common::get_images_dir;
 use common::icons::Icon as IconElement;
-use common::language::get_local_text;
-use common::state::{Action, Identity, State, ToastNotification};
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::configuration::Presence;
+use common::state::custom_status::{CustomStatus, Expiry};
+use common::state::extended_profile::{
+    ExtendedProfile, MAX_ABOUT_LEN, MAX_LINKS, MAX_PRONOUNS_LEN,
+};
+use common::state::{action::ConfigAction, Action, Identity, State, ToastNotification};
 use common::warp_runner::{MultiPassCmd, TesseractCmd, WarpCmd};
 use common::{icons::outline::Shape as Icon, WARP_CMD_CH};
 use dioxus::prelude::*;
@@ -15,13 +21,14 @@ use kit::components::context_menu::{ContextItem, ContextMenu};
 use kit::components::indicator::{Indicator, Platform, Status};
 use kit::elements::checkbox::Checkbox;
 use kit::elements::loader::Loader;
-use kit::elements::select::FancySelect;
+use kit::elements::select::{FancySelect, Select};
 use kit::elements::tooltip::Tooltip;
 use kit::elements::Appearance;
 use kit::elements::{
     button::Button,
     input::{Input, Options, Validation},
     label::Label,
+    switch::Switch,
 };
 use kit::layout::modal::Modal;
 use mime::*;
@@ -35,6 +42,18 @@ use crate::components::crop_image_tool::circle_format_tool::CropCircleImageModal
 use crate::components::crop_image_tool::rectangle_format_tool::CropRectImageModal;
 use crate::components::settings::{SettingSection, SettingSectionSimple};
 
+mod backup;
+
+// the choices for going away without input
+const AWAY_AFTER_MINUTES: [u32; 5] = [5, 10, 15, 30, 60];
+
+fn away_after_label(minutes: u32) -> String {
+    get_local_text_with_args(
+        "settings-profile.away-after-minutes",
+        vec![("minutes", minutes.to_string())],
+    )
+}
+
 #[derive(Clone)]
 enum ChanCmd {
     Profile(Vec<u8>),
@@ -42,8 +61,9 @@ enum ChanCmd {
     Banner(Vec<u8>),
     ClearBanner,
     Username(String),
-    StatusMessage(String),
+    CustomStatus(CustomStatus),
     Status(IdentityStatus),
+    ExtendedProfile(ExtendedProfile),
 }
 
 #[allow(non_snake_case)]
@@ -53,7 +73,17 @@ pub fn ProfileSettings(cx: Scope) -> Element {
     let first_render = use_state(cx, || true);
 
     let identity = state.read().get_own_identity();
-    let user_status = identity.status_message().unwrap_or_default();
+    let custom_status = CustomStatus::from_identity(
+        &identity.status_message().unwrap_or_default(),
+        &identity.metadata(),
+    );
+    let status_emoji = use_ref(cx, || custom_status.emoji.clone());
+    let status_text = use_ref(cx, || custom_status.text.clone());
+    let status_expiry = use_state(cx, || custom_status.expiry);
+    let extended_profile = identity.extended_profile();
+    let pronouns = use_ref(cx, || extended_profile.pronouns.clone());
+    let about = use_ref(cx, || extended_profile.about.clone());
+    let links = use_ref(cx, || extended_profile.links.join(" "));
     let online_status = identity.identity_status();
     let identity_status_values = [
         IdentityStatus::Online,
@@ -192,7 +222,7 @@ pub fn ProfileSettings(cx: Scope) -> Element {
         let mut ident = ident.clone();
         let current = state.read().get_own_identity();
         ident.set_profile_banner(&current.profile_banner());
-        ident.set_profile_picture(&current.profile_picture());
+        ident.copy_profile_picture(&current);
         state.write().set_own_identity(ident);
         state
             .write()
@@ -238,15 +268,13 @@ pub fn ProfileSettings(cx: Scope) -> Element {
                     ChanCmd::Username(username) => {
                         MultiPassCmd::UpdateUsername { username, rsp: tx }
                     }
-                    ChanCmd::StatusMessage(status) => MultiPassCmd::UpdateStatusMessage {
-                        status: if status.is_empty() {
-                            None
-                        } else {
-                            Some(status)
-                        },
-                        rsp: tx,
-                    },
+                    ChanCmd::CustomStatus(status) => {
+                        MultiPassCmd::UpdateCustomStatus { status, rsp: tx }
+                    }
                     ChanCmd::Status(status) => MultiPassCmd::SetStatus { status, rsp: tx },
+                    ChanCmd::ExtendedProfile(profile) => {
+                        MultiPassCmd::UpdateExtendedProfile { profile, rsp: tx }
+                    }
                 };
 
                 if let Err(e) = warp_cmd_tx.send(WarpCmd::MultiPass(warp_cmd)) {
@@ -276,6 +304,40 @@ pub fn ProfileSettings(cx: Scope) -> Element {
         }
     });
 
+    let presence = state.read().configuration.presence;
+    let set_presence = move |f: &dyn Fn(&mut Presence)| {
+        let mut presence = state.read().configuration.presence;
+        f(&mut presence);
+        state
+            .write()
+            .mutate(Action::Config(ConfigAction::SetPresence(presence)));
+    };
+
+    let set_status = move |expiry: Expiry| {
+        let status = CustomStatus::new(&status_emoji.read(), &status_text.read(), expiry);
+        let current = state.read().get_own_identity();
+        let current = CustomStatus::from_identity(
+            &current.status_message().unwrap_or_default(),
+            &current.metadata(),
+        );
+        if status.to_message() != current.to_message()
+            || status.to_metadata() != current.to_metadata()
+        {
+            ch.send(ChanCmd::CustomStatus(status));
+        }
+    };
+
+    let set_extended_profile = move || {
+        let profile = ExtendedProfile {
+            pronouns: pronouns.read().trim().to_string(),
+            about: about.read().trim().to_string(),
+            links: ExtendedProfile::parse_links(&links.read()),
+        };
+        if profile != state.read().get_own_identity().extended_profile() {
+            ch.send(ChanCmd::ExtendedProfile(profile));
+        }
+    };
+
     // Set up validation options for the input field
     let username_validation_options = Validation {
         // The input should have a maximum length of 32
@@ -293,6 +355,16 @@ pub fn ProfileSettings(cx: Scope) -> Element {
         special_chars: None,
     };
 
+    let emoji_validation_options = Validation {
+        // an emoji can be made of several characters
+        max_length: Some(16),
+        min_length: Some(0),
+        alpha_numeric_only: false,
+        no_whitespace: true,
+        ignore_colons: false,
+        special_chars: None,
+    };
+
     let status_validation_options = Validation {
         // The input should have a maximum length of 128
         max_length: Some(128),
@@ -309,6 +381,20 @@ pub fn ProfileSettings(cx: Scope) -> Element {
         special_chars: None,
     };
 
+    let pronouns_validation_options = Validation {
+        max_length: Some(MAX_PRONOUNS_LEN as i32),
+        min_length: Some(0),
+        alpha_numeric_only: false,
+        no_whitespace: false,
+        ignore_colons: false,
+        special_chars: None,
+    };
+
+    let about_validation_options = Validation {
+        max_length: Some(MAX_ABOUT_LEN as i32),
+        ..pronouns_validation_options.clone()
+    };
+
     let did_short = identity.short_id().to_string();
     let did_key = identity.did_key();
     let short_name = format!("{}#{}", username, did_short);
@@ -571,26 +657,127 @@ pub fn ProfileSettings(cx: Scope) -> Element {
                 div {
                     class: "content-item",
                     Label {
-                        text: get_local_text("uplink.status"),
-                        aria_label: "profile-status-label".into(),
+                        text: get_local_text("settings-profile.pronouns"),
+                        aria_label: "profile-pronouns-label".into(),
                     },
                     Input {
-                        placeholder: get_local_text("uplink.status"),
-                        default_text: user_status.clone(),
-                        aria_label: "status-input".into(),
-                        options: Options {
-                            with_clear_btn: true,
-                            ..get_input_options(status_validation_options)
+                        placeholder: get_local_text("settings-profile.pronouns-placeholder"),
+                        default_text: extended_profile.pronouns.clone(),
+                        aria_label: "pronouns-input".into(),
+                        options: get_input_options(pronouns_validation_options),
+                        onchange: move |(v, _): (String, bool)| {
+                            *pronouns.write_silent() = v;
                         },
-                        onreturn: move |(v, is_valid, _): (String, bool, _)| {
-                            if !is_valid {
-                                return;
+                        onreturn: move |(_, is_valid, _): (String, bool, _)| {
+                            if is_valid {
+                                set_extended_profile();
                             }
-                            if v != user_status {
-                                ch.send(ChanCmd::StatusMessage(v));
+                        },
+                    },
+                },
+                div {
+                    class: "content-item",
+                    Label {
+                        text: get_local_text("settings-profile.about"),
+                        aria_label: "profile-about-label".into(),
+                    },
+                    Input {
+                        placeholder: get_local_text("settings-profile.about-placeholder"),
+                        default_text: extended_profile.about.clone(),
+                        aria_label: "about-input".into(),
+                        options: get_input_options(about_validation_options),
+                        onchange: move |(v, _): (String, bool)| {
+                            *about.write_silent() = v;
+                        },
+                        onreturn: move |(_, is_valid, _): (String, bool, _)| {
+                            if is_valid {
+                                set_extended_profile();
                             }
                         },
-                    }
+                    },
+                },
+                div {
+                    class: "content-item",
+                    Label {
+                        text: get_local_text("settings-profile.links"),
+                        aria_label: "profile-links-label".into(),
+                    },
+                    Input {
+                        placeholder: get_local_text_with_args("settings-profile.links-placeholder", vec![("max", MAX_LINKS)]),
+                        default_text: extended_profile.links.join(" "),
+                        aria_label: "links-input".into(),
+                        options: Options {
+                            clear_on_submit: false,
+                            ..Default::default()
+                        },
+                        onchange: move |(v, _): (String, bool)| {
+                            *links.write_silent() = v;
+                        },
+                        onreturn: move |_| set_extended_profile(),
+                    },
+                },
+                div {
+                    class: "content-item",
+                    Label {
+                        text: get_local_text("uplink.status"),
+                        aria_label: "profile-status-label".into(),
+                    },
+                    div {
+                        class: "custom-status",
+                        Input {
+                            placeholder: "🙂".into(),
+                            default_text: custom_status.emoji.clone(),
+                            aria_label: "status-emoji-input".into(),
+                            options: get_input_options(emoji_validation_options),
+                            onchange: move |(v, _): (String, bool)| {
+                                *status_emoji.write_silent() = v;
+                            },
+                            onreturn: move |(_, is_valid, _): (String, bool, _)| {
+                                if is_valid {
+                                    set_status(*status_expiry.get());
+                                }
+                            },
+                        },
+                        Input {
+                            placeholder: get_local_text("uplink.status"),
+                            default_text: custom_status.text.clone(),
+                            aria_label: "status-input".into(),
+                            options: Options {
+                                with_clear_btn: true,
+                                ..get_input_options(status_validation_options)
+                            },
+                            onchange: move |(v, _): (String, bool)| {
+                                *status_text.write_silent() = v;
+                            },
+                            onreturn: move |(_, is_valid, _): (String, bool, _)| {
+                                if is_valid {
+                                    set_status(*status_expiry.get());
+                                }
+                            },
+                        },
+                    },
+                },
+                SettingSection {
+                    aria_label: "status-expiry-section".into(),
+                    section_label: get_local_text("settings-profile.status-expiry"),
+                    section_description: match custom_status.expires {
+                        Some(date) => get_local_text_with_args("settings-profile.status-expires-at", vec![
+                            ("time", date.with_timezone(&Local).format("%H:%M").to_string()),
+                        ]),
+                        None => get_local_text("settings-profile.status-expiry-description"),
+                    },
+                    Select {
+                        initial_value: get_local_text(status_expiry.get().locale_key()),
+                        options: Expiry::ALL.iter().map(|e| get_local_text(e.locale_key())).collect(),
+                        onselect: move |value: String| {
+                            let expiry = Expiry::ALL
+                                .into_iter()
+                                .find(|e| get_local_text(e.locale_key()) == value)
+                                .unwrap_or(Expiry::Never);
+                            status_expiry.set(expiry);
+                            set_status(expiry);
+                        }
+                    },
                 },
                 SettingSection {
                     aria_label: "online-status-section".into(),
@@ -606,6 +793,32 @@ pub fn ProfileSettings(cx: Scope) -> Element {
                         }
                     },
                 },
+                SettingSection {
+                    aria_label: "auto-away-section".into(),
+                    section_label: get_local_text("settings-profile.auto-away"),
+                    section_description: get_local_text("settings-profile.auto-away-description"),
+                    Switch {
+                        active: presence.auto_away,
+                        onflipped: move |e| set_presence(&|p| p.auto_away = e),
+                    }
+                },
+                presence.auto_away.then(|| rsx!(
+                    SettingSection {
+                        aria_label: "away-after-section".into(),
+                        section_label: get_local_text("settings-profile.away-after"),
+                        section_description: get_local_text("settings-profile.away-after-description"),
+                        Select {
+                            initial_value: away_after_label(presence.away_after_minutes),
+                            options: AWAY_AFTER_MINUTES.iter().map(|m| away_after_label(*m)).collect(),
+                            onselect: move |value: String| {
+                                if let Some(minutes) = AWAY_AFTER_MINUTES.into_iter().find(|m| away_after_label(*m) == value) {
+                                    set_presence(&|p| p.away_after_minutes = minutes);
+                                }
+                            }
+                        },
+                    }
+                )),
+                backup::BackupSection {},
                 if *phrase_exists.get() {rsx!(
                     SettingSection {
                         aria_label: "recovery-seed-section".into(),
@@ -810,7 +1023,8 @@ fn set_banner(open_crop_image_modal_for_banner_picture: UseState<(bool, (Vec<u8>
     };
 }
 
-fn set_image() -> Result<(Vec<u8>, String), Box<dyn std::error::Error>> {
+/// Asks for an image and reads it. Returns its bytes and the prefix of its data url
+pub fn set_image() -> Result<(Vec<u8>, String), Box<dyn std::error::Error>> {
     let path = match FileDialog::new()
         .add_filter("image", &["jpg", "png", "jpeg", "svg"])
         .set_directory(".")
@@ -823,7 +1037,7 @@ fn set_image() -> Result<(Vec<u8>, String), Box<dyn std::error::Error>> {
     transform_file_into_base64_image(path)
 }
 
-fn transform_file_into_base64_image(
+pub fn transform_file_into_base64_image(
     path: std::path::PathBuf,
 ) -> Result<(Vec<u8>, String), Box<dyn std::error::Error>> {
     let file = std::fs::read(&path)?;
@@ -868,7 +1082,7 @@ fn get_input_options(validation_options: Validation) -> Options {
 }
 
 fn get_status_option<'a>(cx: Scope<'a>, status: &IdentityStatus) -> (String, Element<'a>) {
-    let indicator = Status::from(*status);
+    let indicator = Status::own(*status);
     (
         serde_json::to_string::<IdentityStatus>(status).unwrap_or_default(),
         cx.render(rsx!(div {
@@ -0,0 +1,212 @@
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::{Action, State, ToastNotification};
+use common::warp_runner::storage_backend::{self, StorageBackend};
+use dioxus::prelude::*;
+use futures::StreamExt;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    Appearance,
+};
+use rfd::FileDialog;
+use tracing::log;
+
+use crate::components::settings::SettingSection;
+
+fn backend_name(backend: &StorageBackend) -> String {
+    get_local_text(match backend {
+        StorageBackend::Local => "settings-storage.local",
+        StorageBackend::ExternalDisk { .. } => "settings-storage.external-disk",
+        StorageBackend::RemotePinning { .. } => "settings-storage.remote-pinning",
+    })
+}
+
+#[allow(non_snake_case)]
+pub fn StorageSettings(cx: Scope) -> Element {
+    log::trace!("Storage settings page rendered.");
+    let state = use_shared_state::<State>(cx)?;
+    let config = use_state(cx, storage_backend::load);
+    // the backend being edited, starting from the one chosen last
+    let draft = use_ref(cx, || {
+        config
+            .pending
+            .clone()
+            .unwrap_or_else(|| config.active.clone())
+    });
+    // a new token for the pinning service. it goes to the keychain and is never shown again
+    let token: &UseRef<Option<String>> = use_ref(cx, || None);
+
+    let selected = draft.read().clone();
+    let kinds = [
+        StorageBackend::Local,
+        StorageBackend::ExternalDisk {
+            path: Default::default(),
+        },
+        StorageBackend::RemotePinning {
+            endpoint: String::new(),
+            token: None,
+        },
+    ];
+    let complete = match &selected {
+        StorageBackend::Local => true,
+        StorageBackend::ExternalDisk { path } => path.is_absolute(),
+        StorageBackend::RemotePinning { endpoint, .. } => reqwest::Url::parse(endpoint).is_ok(),
+    };
+    let chosen = config.pending.as_ref().unwrap_or(&config.active);
+    let ch =
+        use_coroutine(
+            cx,
+            |mut rx: UnboundedReceiver<(StorageBackend, Option<String>)>| {
+                to_owned![state, config];
+                async move {
+                    while let Some((backend, token)) = rx.next().await {
+                        let text = match storage_backend::select(backend, token).await {
+                            Ok(_) => get_local_text("settings-storage.restart"),
+                            Err(e) => {
+                                log::error!("failed to select the storage backend: {e}");
+                                get_local_text("settings-storage.select-failed")
+                            }
+                        };
+                        config.set(storage_backend::load());
+                        state.write().mutate(Action::AddToastNotification(
+                            ToastNotification::init("".into(), text, None, 2),
+                        ));
+                    }
+                }
+            },
+        );
+    let apply = move |backend: StorageBackend| {
+        let new_token = match &backend {
+            StorageBackend::RemotePinning { .. } => token.write_silent().take(),
+            _ => None,
+        };
+        ch.send((backend, new_token));
+    };
+
+    let fields = match selected.clone() {
+        StorageBackend::Local => None,
+        StorageBackend::ExternalDisk { path } => Some(rsx!(SettingSection {
+            aria_label: "storage-path-section".into(),
+            section_label: get_local_text("settings-storage.path"),
+            section_description: if path.as_os_str().is_empty() {
+                get_local_text("settings-storage.path-description")
+            } else {
+                path.display().to_string()
+            },
+            Button {
+                aria_label: "choose-storage-path-button".into(),
+                text: get_local_text("settings-storage.choose-folder"),
+                icon: Icon::FolderOpen,
+                appearance: Appearance::Secondary,
+                onpress: move |_| {
+                    if let Some(path) = FileDialog::new().pick_folder() {
+                        *draft.write() = StorageBackend::ExternalDisk { path };
+                    }
+                },
+            }
+        })),
+        StorageBackend::RemotePinning { endpoint, .. } => Some(rsx!(
+            SettingSection {
+                aria_label: "pinning-endpoint-section".into(),
+                section_label: get_local_text("settings-storage.endpoint"),
+                section_description: get_local_text("settings-storage.endpoint-description"),
+                Input {
+                    placeholder: "https://api.pinning.example/psa".into(),
+                    default_text: endpoint,
+                    aria_label: "pinning-endpoint-input".into(),
+                    options: Options {
+                        clear_on_submit: false,
+                        ..Default::default()
+                    },
+                    onchange: move |(v, _): (String, bool)| {
+                        if let StorageBackend::RemotePinning { endpoint, .. } = &mut *draft.write() {
+                            *endpoint = v.trim().to_string();
+                        }
+                    },
+                }
+            },
+            SettingSection {
+                aria_label: "pinning-token-section".into(),
+                section_label: get_local_text("settings-storage.token"),
+                section_description: get_local_text("settings-storage.token-description"),
+                Input {
+                    placeholder: get_local_text("settings-storage.token"),
+                    aria_label: "pinning-token-input".into(),
+                    is_password: true,
+                    options: Options {
+                        clear_on_submit: false,
+                        ..Default::default()
+                    },
+                    onchange: move |(v, _): (String, bool)| {
+                        let v = v.trim().to_string();
+                        *token.write() = (!v.is_empty()).then_some(v);
+                    },
+                }
+            }
+        )),
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "settings-storage",
+            aria_label: "settings-storage",
+            SettingSection {
+                aria_label: "storage-backend-section".into(),
+                section_label: get_local_text("settings-storage.backend"),
+                section_description: get_local_text_with_args("settings-storage.backend-description", vec![
+                    ("backend", backend_name(&config.active)),
+                ]),
+                div {
+                    class: "button-group",
+                    kinds.into_iter().map(|kind| {
+                        let name = backend_name(&kind);
+                        let active = std::mem::discriminant(&kind) == std::mem::discriminant(&selected);
+                        rsx!(Button {
+                            key: "{name}",
+                            text: name.clone(),
+                            aria_label: format!("storage-backend-{name}"),
+                            small: true,
+                            appearance: if active { Appearance::Primary } else { Appearance::Secondary },
+                            onpress: move |_| {
+                                if !active {
+                                    *draft.write() = kind.clone();
+                                }
+                            },
+                        })
+                    })
+                }
+            },
+            fields,
+            SettingSection {
+                aria_label: "apply-storage-backend-section".into(),
+                section_label: get_local_text("settings-storage.apply"),
+                section_description: match &config.pending {
+                    Some(pending) => get_local_text_with_args("settings-storage.pending", vec![
+                        ("backend", backend_name(pending)),
+                    ]),
+                    None => get_local_text("settings-storage.apply-description"),
+                },
+                div {
+                    class: "button-group",
+                    config.pending.is_some().then(|| rsx!(Button {
+                        aria_label: "cancel-storage-backend-button".into(),
+                        text: get_local_text("uplink.cancel"),
+                        appearance: Appearance::Secondary,
+                        onpress: move |_| {
+                            *draft.write() = config.active.clone();
+                            apply(config.active.clone());
+                        },
+                    })),
+                    Button {
+                        aria_label: "apply-storage-backend-button".into(),
+                        text: get_local_text("settings-storage.apply"),
+                        icon: Icon::Check,
+                        disabled: !complete || (&selected == chosen && token.read().is_none()),
+                        onpress: move |_| apply(draft.read().clone()),
+                    },
+                }
+            },
+        }
+    ))
+}
@@ -0,0 +1,144 @@
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::{
+    custom_theme::{CustomTheme, PALETTE},
+    Action, State, ToastNotification,
+};
+use dioxus::prelude::*;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    label::Label,
+    Appearance,
+};
+use tracing::log;
+
+// the preview goes away with the editor, also when leaving the page without saving
+struct ClearPreview(UseSharedState<State>);
+
+impl Drop for ClearPreview {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.0.try_write() {
+            state.ui.theme_preview = None;
+        }
+    }
+}
+
+#[derive(Props)]
+pub struct Props<'a> {
+    // called with true once the theme was saved
+    onclose: EventHandler<'a, bool>,
+}
+
+/// Color pickers for the palette of the current theme. Every change is previewed right away
+#[allow(non_snake_case)]
+pub fn ThemeEditor<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let editor = use_ref(cx, || {
+        let current = state
+            .read()
+            .ui
+            .theme
+            .clone()
+            .filter(|t| !t.filename.is_empty());
+        match current {
+            Some(theme) => CustomTheme::from_styles(&theme.name, &theme.styles),
+            None => CustomTheme::from_styles(&get_local_text("settings-general.my-theme"), ""),
+        }
+    });
+    cx.use_hook(|| ClearPreview(state.clone()));
+
+    let save = move |_| {
+        let text = match editor.read().save() {
+            Ok(theme) => {
+                let name = theme.name.clone();
+                state.write().ui.theme_preview = None;
+                state.write().mutate(Action::SetTheme(Some(theme)));
+                cx.props.onclose.call(true);
+                get_local_text_with_args("settings-general.theme-saved", vec![("theme", name)])
+            }
+            Err(e) => {
+                log::error!("failed to save the theme: {e}");
+                get_local_text("settings-general.theme-save-failed")
+            }
+        };
+        state
+            .write()
+            .mutate(Action::AddToastNotification(ToastNotification::init(
+                "".into(),
+                text,
+                None,
+                2,
+            )));
+    };
+
+    let name = editor.read().name.clone();
+    let colors = editor.read().colors.clone();
+
+    cx.render(rsx!(
+        div {
+            class: "theme-editor",
+            aria_label: "theme-editor",
+            div {
+                class: "theme-editor-name",
+                Label {
+                    text: get_local_text("settings-general.theme-name"),
+                },
+                Input {
+                    default_text: name,
+                    aria_label: "theme-name-input".into(),
+                    options: Options {
+                        clear_on_submit: false,
+                        ..Default::default()
+                    },
+                    onchange: move |(v, _): (String, bool)| {
+                        editor.write_silent().name = v;
+                    },
+                },
+            },
+            div {
+                class: "theme-editor-colors",
+                colors.into_iter().map(|(var, color)| {
+                    let default = PALETTE.iter().find(|(v, _)| *v == var).map(|(_, c)| *c).unwrap_or_default();
+                    let changed = var.clone();
+                    rsx!(label {
+                        key: "{var}",
+                        class: "theme-editor-color",
+                        aria_label: "theme-color-{var}",
+                        input {
+                            r#type: "color",
+                            value: "{color}",
+                            oninput: move |e| {
+                                editor.write().set_color(&changed, &e.value);
+                                let styles = editor.read().to_styles();
+                                state.write().ui.theme_preview = Some(styles);
+                            },
+                        },
+                        span {
+                            class: if color == default { "" } else { "changed" },
+                            "--{var}"
+                        }
+                    })
+                })
+            },
+            div {
+                class: "button-group",
+                Button {
+                    text: get_local_text("settings-general.save-theme"),
+                    aria_label: "save-theme-button".into(),
+                    icon: Icon::Check,
+                    onpress: save,
+                },
+                Button {
+                    text: get_local_text("uplink.cancel"),
+                    aria_label: "cancel-theme-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        state.write().ui.theme_preview = None;
+                        cx.props.onclose.call(false);
+                    },
+                },
+            }
+        }
+    ))
+}
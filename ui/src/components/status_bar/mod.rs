@@ -0,0 +1,106 @@
+use common::icons::outline::Shape as Icon;
+use common::icons::Icon as IconElement;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::{data_transfer::TransferTracker, network::ConnectionStatus, Action, State};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+
+use crate::{
+    components::{file_transfer::FileTransferModal, settings::sidebar::Page},
+    UplinkRoute,
+};
+
+// a summary of what Uplink is doing in the background, at the bottom of the app.
+// every item opens the place where it can be looked at more closely
+#[allow(non_snake_case)]
+pub fn StatusBar(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let tracker = use_shared_state::<TransferTracker>(cx)?;
+    let settings_page = use_shared_state::<Page>(cx)?;
+    let router = use_navigator(cx);
+    let transfers_open = use_state(cx, || false);
+    state.write_silent().scope_ids.status_bar = Some(cx.scope_id().0);
+
+    let status = state.read().connection_status();
+    let connection = get_local_text(match status {
+        ConnectionStatus::Connecting => "network.connecting",
+        ConnectionStatus::Connected => "network.connected",
+        ConnectionStatus::RelayOnly => "network.relay-only",
+        ConnectionStatus::Offline => "network.offline",
+        ConnectionStatus::TimedOut => "network.timed-out",
+    });
+
+    let active_transfers = tracker.read().active_transfers().count();
+    let progress = tracker.read().total_progress();
+    let transfers = if active_transfers == 0 {
+        get_local_text("status-bar.no-transfers")
+    } else {
+        get_local_text_with_args(
+            "status-bar.transfers",
+            vec![
+                ("num", active_transfers.to_string()),
+                ("percent", progress.max(0).to_string()),
+            ],
+        )
+    };
+    // the list closes by itself once everything is done
+    let show_transfers = *transfers_open.get() && active_transfers > 0;
+
+    let queued = state.read().queued_message_count();
+    let outbox = if queued == 0 {
+        get_local_text("status-bar.outbox-empty")
+    } else {
+        get_local_text_with_args("status-bar.outbox", vec![("num", queued.to_string())])
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "status-bar",
+            aria_label: "status-bar",
+            class: "disable-select",
+            div {
+                class: "status-bar-item connection {status}",
+                aria_label: "status-bar-connection",
+                onclick: move |_| {
+                    settings_page.write().set(Page::Network);
+                    router.replace(UplinkRoute::SettingsLayout {});
+                },
+                span { class: "connection-status-dot" },
+                span { "{connection}" },
+            },
+            div {
+                class: format_args!("status-bar-item {}", if active_transfers > 0 { "active" } else { "" }),
+                aria_label: "status-bar-transfers",
+                onclick: move |_| {
+                    if active_transfers > 0 {
+                        transfers_open.set(!show_transfers);
+                    }
+                },
+                IconElement { icon: Icon::ArrowDownTray },
+                span { "{transfers}" },
+            },
+            div {
+                class: format_args!("status-bar-item {}", if queued > 0 { "active" } else { "" }),
+                aria_label: "status-bar-outbox",
+                onclick: move |_| {
+                    let oldest = state.read().oldest_queued_chat();
+                    if let Some(id) = oldest {
+                        state.write().mutate(Action::ChatWith(&id, true));
+                        router.replace(UplinkRoute::ChatLayout {});
+                    }
+                },
+                IconElement { icon: Icon::PaperAirplane },
+                span { "{outbox}" },
+            },
+            show_transfers.then(|| rsx!(
+                div {
+                    class: "status-bar-transfers",
+                    FileTransferModal {
+                        state: state,
+                        modal: true,
+                    }
+                }
+            )),
+        }
+    ))
+}
@@ -0,0 +1,39 @@
+use common::language::get_local_text;
+use common::state::{network::ConnectionStatus, State};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+
+use crate::{components::settings::sidebar::Page, UplinkRoute};
+
+// shows whether Uplink is connected. clicking it opens the network diagnostics
+#[allow(non_snake_case)]
+pub fn ConnectionIndicator(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let settings_page = use_shared_state::<Page>(cx)?;
+    let router = use_navigator(cx);
+    let status = state.read().connection_status();
+    let text = get_local_text(match status {
+        ConnectionStatus::Connecting => "network.connecting",
+        ConnectionStatus::Connected => "network.connected",
+        ConnectionStatus::RelayOnly => "network.relay-only",
+        ConnectionStatus::Offline => "network.offline",
+        ConnectionStatus::TimedOut => "network.timed-out",
+    });
+
+    cx.render(rsx!(
+        div {
+            id: "connection-status",
+            class: "topbar-item {status}",
+            aria_label: "connection-status-{status}",
+            title: "{text}",
+            onclick: move |_| {
+                settings_page.write().set(Page::Network);
+                router.replace(UplinkRoute::SettingsLayout {});
+            },
+            span {
+                class: "connection-status-dot",
+            },
+            p { "{text}" }
+        }
+    ))
+}
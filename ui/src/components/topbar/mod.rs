@@ -1 +1,2 @@
+pub mod connection_status;
 pub mod release_info;
@@ -8,6 +8,7 @@ use kit::elements::label::Label;
 use crate::components::settings::{ExtensionSetting, SettingSection};
 use common::sounds;
 use dioxus::prelude::*;
+use extensions::conversations::Permission;
 use kit::elements::input::{Input, Options};
 use kit::{
     components::nav::{Nav, Route},
@@ -88,7 +89,14 @@ pub fn Installed(cx: Scope) -> Element {
         .ui
         .extensions
         .values()
-        .map(|(enabled, ext)| (enabled, ext.details().meta.clone()))
+        .map(|(enabled, ext)| {
+            (
+                enabled,
+                ext.details().meta.clone(),
+                ext.path().to_string_lossy().to_string(),
+                ext.permissions(),
+            )
+        })
         .collect();
 
     cx.render(rsx!(
@@ -104,7 +112,7 @@ pub fn Installed(cx: Scope) -> Element {
                 }
             )
         } else {
-            rsx!( metas.iter().cloned().map(|(enabled, meta)| {
+            rsx!( metas.iter().cloned().map(|(enabled, meta, path, permissions)| {
                 rsx!(
                     ExtensionSetting {
                         title: meta.pretty_name.to_owned(),
@@ -120,13 +128,54 @@ pub fn Installed(cx: Scope) -> Element {
                                 state.write().mutate(Action::SetExtensionEnabled(meta.name.to_owned(), value));
                             }
                         }
-                    }
+                    },
+                    (!permissions.is_empty()).then(|| rsx!(
+                        div {
+                            class: "extension-permissions",
+                            aria_label: "extension-permissions",
+                            Label {
+                                text: get_local_text("settings-extensions.permissions"),
+                                aria_label: String::from("extension-permissions-label"),
+                            },
+                            permissions.iter().copied().map(move |permission| {
+                                let path = path.clone();
+                                let granted = state.read().configuration.extensions.is_granted(&path, permission);
+                                rsx!(
+                                    div {
+                                        key: "{permission:?}",
+                                        class: "extension-permission",
+                                        p {
+                                            permission_label(permission)
+                                        },
+                                        Switch {
+                                            active: granted,
+                                            onflipped: move |value| {
+                                                if state.read().configuration.audiovideo.interface_sounds {
+                                                    sounds::Play(sounds::Sounds::Flip);
+                                                }
+                                                state.write().mutate(Action::Config(ConfigAction::SetExtensionPermission(path.clone(), permission, value)));
+                                            }
+                                        }
+                                    }
+                                )
+                            })
+                        }
+                    ))
                 )
             }))
         }
         ))
 }
 
+fn permission_label(permission: Permission) -> String {
+    match permission {
+        Permission::ReadConversations => {
+            get_local_text("settings-extensions.permission-read-conversations")
+        }
+        Permission::ReadMessages => get_local_text("settings-extensions.permission-read-messages"),
+    }
+}
+
 #[allow(non_snake_case)]
 pub fn ExtensionsBrowser(cx: Scope) -> Element {
     let routes = vec![
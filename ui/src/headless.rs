@@ -0,0 +1,211 @@
+//! Runs the account of the active profile without a window, for servers and bots. Once logged in,
+//! Uplink is driven through the control socket, see `common::control`. If the profile has no
+//! account yet, one is created with the username in UPLINK_USERNAME and its recovery seed is
+//! shown once: on the terminal, or without one in a file only the user can read. The duress PIN of
+//! the profile works like it does at the unlock screen.
+
+use std::{io::IsTerminal, path::Path};
+
+use anyhow::{bail, Context};
+use common::{
+    control,
+    state::State,
+    upload_file_channel::{UploadFileAction, UPLOAD_FILE_LISTENER},
+    utils::{duress, incoming_webhooks},
+    warp_runner::{DuressOutcome, MultiPassCmd, RayGunCmd, TesseractCmd, WarpCmd, WarpRunner},
+    STATIC_ARGS, WARP_CMD_CH,
+};
+use futures::channel::oneshot;
+use serde_json::json;
+use tracing::log;
+use warp::multipass::identity::Identity;
+
+const PIN_VAR: &str = "UPLINK_PIN";
+const USERNAME_VAR: &str = "UPLINK_USERNAME";
+// where the recovery seed is written if there is no terminal to show it on
+const SEED_FILE: &str = "recovery_seed.txt";
+
+pub fn run() -> anyhow::Result<()> {
+    let runtime = tokio::runtime::Runtime::new().context("failed to start the runtime")?;
+    runtime.block_on(async {
+        // warp stops once the runner is dropped
+        let mut runner = WarpRunner::new();
+        runner.run();
+
+        let identity = log_in().await?;
+        log::info!("logged in as {}", identity.did_key());
+        initialize_warp().await?;
+        incoming_webhooks::start();
+        tokio::spawn(publish_uploads());
+
+        let path = STATIC_ARGS.control_socket_path();
+        let result = tokio::select! {
+            r = control::serve(&path, false) => r.context("the control socket failed"),
+            _ = tokio::signal::ctrl_c() => Ok(()),
+        };
+        if cfg!(unix) {
+            let _ = std::fs::remove_file(&path);
+        }
+        drop(runner);
+        result
+    })
+}
+
+async fn log_in() -> anyhow::Result<Identity> {
+    let account_exists =
+        send(|rsp| WarpCmd::Tesseract(TesseractCmd::AccountExists { rsp })).await?;
+    let username = std::env::var(USERNAME_VAR).ok();
+    if !account_exists && username.is_none() {
+        bail!("the profile has no account. set {USERNAME_VAR} to create one");
+    }
+    let pin = read_pin()?;
+
+    let result = match username.filter(|_| !account_exists) {
+        Some(username) => {
+            let seed_words = warp::crypto::keypair::generate_mnemonic_phrase(
+                warp::crypto::keypair::PhraseType::Standard,
+            )
+            .into_phrase();
+            // written before the account exists, so it isn't created if the seed can't be kept
+            let seed_file = STATIC_ARGS.uplink_path().join(SEED_FILE);
+            let on_terminal = std::io::stdout().is_terminal();
+            if !on_terminal {
+                write_private(&seed_file, &seed_words)
+                    .context("failed to write the recovery seed")?;
+            }
+            let result = send(|rsp| {
+                WarpCmd::MultiPass(MultiPassCmd::CreateIdentity {
+                    username,
+                    tesseract_passphrase: pin,
+                    seed_words: seed_words.clone(),
+                    rsp,
+                })
+            })
+            .await?;
+            match (&result, on_terminal) {
+                // the only time the phrase is shown
+                (Ok(_), true) => {
+                    println!("Account created. Write down the recovery seed:\n{seed_words}")
+                }
+                (Ok(_), false) => eprintln!(
+                    "Account created. Its recovery seed is in {}, write it down and delete the file",
+                    seed_file.display()
+                ),
+                (Err(_), _) => {
+                    let _ = std::fs::remove_file(&seed_file);
+                }
+            }
+            result
+        }
+        None => {
+            let result = send(|rsp| {
+                WarpCmd::MultiPass(MultiPassCmd::TryLogIn {
+                    passphrase: pin.clone(),
+                    rsp,
+                })
+            })
+            .await?;
+            match result {
+                Err(e) => log_in_under_duress(pin).await?.ok_or(e),
+                ok => ok,
+            }
+        }
+    };
+    result.context("failed to log in")
+}
+
+// what the unlock screen does when the PIN was wrong: the duress PIN opens the decoy or wipes the
+// profile, which looks like any other wrong PIN
+async fn log_in_under_duress(pin: String) -> anyhow::Result<Option<Identity>> {
+    let checked = pin.clone();
+    let is_duress = tokio::task::spawn_blocking(move || duress::check(&checked).is_some())
+        .await
+        .unwrap_or(false);
+    if !is_duress {
+        return Ok(None);
+    }
+    let outcome = send(|rsp| {
+        WarpCmd::Tesseract(TesseractCmd::Duress {
+            passphrase: pin,
+            rsp,
+        })
+    })
+    .await?;
+    Ok(match outcome {
+        Ok(DuressOutcome::LoggedIn(identity)) => Some(identity),
+        _ => None,
+    })
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::{io::Write, os::unix::fs::OpenOptionsExt};
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+// the profile is in the folder of the user, which others can't read
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+fn read_pin() -> anyhow::Result<String> {
+    if let Ok(pin) = std::env::var(PIN_VAR) {
+        return Ok(pin);
+    }
+    // read from the terminal without showing it
+    rpassword::prompt_password("PIN: ")
+        .with_context(|| format!("failed to read the PIN. without a terminal, set {PIN_VAR}"))
+}
+
+// opens the conversation streams, like the UI does after logging in
+async fn initialize_warp() -> anyhow::Result<()> {
+    let chats = State::load().chats;
+    let merged = chats.merged_by_chat();
+    let (roles, muted) = chats.group_roles();
+    send(|rsp| {
+        WarpCmd::RayGun(RayGunCmd::InitializeWarp {
+            merged,
+            roles,
+            muted,
+            rsp,
+        })
+    })
+    .await?
+    .context("failed to initialize warp")?;
+    Ok(())
+}
+
+// the UI isn't there to show the uploads, their results are sent to the control socket instead
+async fn publish_uploads() {
+    let rx = UPLOAD_FILE_LISTENER.rx.clone();
+    let mut rx = rx.lock().await;
+    while let Some(action) = rx.recv().await {
+        match action {
+            UploadFileAction::Finishing(path, _) => {
+                control::publish("files.uploaded", json!({ "path": path }));
+            }
+            UploadFileAction::Error(Some(path), _)
+            | UploadFileAction::SizeNotAvailable(path, _) => {
+                control::publish("files.upload_failed", json!({ "path": path }));
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn send<T>(cmd: impl FnOnce(oneshot::Sender<T>) -> WarpCmd) -> anyhow::Result<T> {
+    let (tx, rx) = oneshot::channel();
+    WARP_CMD_CH
+        .tx
+        .send(cmd(tx))
+        .map_err(|e| anyhow::anyhow!("failed to send warp command: {e}"))?;
+    rx.await.context("the warp runner stopped")
+}
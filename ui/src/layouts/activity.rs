@@ -0,0 +1,248 @@
+use common::icons::outline::Shape as Icon;
+use common::icons::Icon as IconElement;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::activity::{ActivityEvent, ActivityKind};
+use common::state::data_transfer::TransferTracker;
+use common::state::{ui, Action, State, ToastNotification};
+use common::utils::handoff::Handoff;
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use kit::{
+    elements::{
+        button::Button,
+        tooltip::{ArrowPosition, Tooltip},
+        Appearance,
+    },
+    layout::topbar::Topbar,
+};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::layouts::chats::ChatSidebar;
+use crate::layouts::slimbar::SlimbarLayout;
+use crate::utils::format_timestamp::format_timestamp_timeago;
+use crate::UplinkRoute;
+
+const FILTERS: [(&str, Icon, Option<ActivityKind>); 5] = [
+    ("activity.all", Icon::Bell, None),
+    (
+        "activity.messages",
+        Icon::BellSlash,
+        Some(ActivityKind::Message),
+    ),
+    (
+        "activity.reactions",
+        Icon::FaceSmile,
+        Some(ActivityKind::Reaction),
+    ),
+    (
+        "activity.friends",
+        Icon::UserPlus,
+        Some(ActivityKind::FriendAccepted),
+    ),
+    (
+        "activity.transfers",
+        Icon::ArrowDownTray,
+        Some(ActivityKind::Transfer),
+    ),
+];
+
+// a timeline of what happened across the account, which would otherwise be easy to miss
+#[allow(non_snake_case)]
+pub fn ActivityLayout(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
+    let kind = use_state(cx, || None::<ActivityKind>);
+    let router = use_navigator(cx);
+    state.write_silent().ui.current_layout = ui::Layout::Activity;
+    log::trace!("rendering ActivityLayout");
+
+    let minimal = state.read().ui.is_minimal_view();
+    let show_slimbar = state.read().show_slimbar() & !minimal;
+    let language = state.read().settings.language_id();
+    let events = state
+        .read()
+        .activity
+        .timeline(file_tracker.read().history.iter(), *kind.get());
+
+    let chat_name = move |id: Uuid| {
+        let state = state.read();
+        let chat = state.get_chat_by_id(id)?;
+        Some(state.chats().title(&chat).unwrap_or_else(|| {
+            State::join_usernames(&state.remove_self(&state.chat_participants(&chat)))
+        }))
+    };
+
+    // messages and reactions open the chat at the message, friends their chat and transfers the
+    // chat they were attached in
+    let open = move |event: &ActivityEvent| {
+        let chat_id = match event.kind {
+            ActivityKind::FriendAccepted => {
+                let chat = event
+                    .did
+                    .clone()
+                    .and_then(|did| state.read().get_chat_with_friend(did));
+                match chat {
+                    Some(chat) => Some(chat.id),
+                    None => {
+                        router.replace(UplinkRoute::FriendsLayout {});
+                        return;
+                    }
+                }
+            }
+            _ => event.conversation_id,
+        };
+        let Some(conversation_id) = chat_id else {
+            router.replace(UplinkRoute::FilesLayout {});
+            return;
+        };
+        if state.read().get_chat_by_id(conversation_id).is_none() {
+            state
+                .write()
+                .mutate(Action::AddToastNotification(ToastNotification::init(
+                    "".into(),
+                    get_local_text("activity.conversation-not-found"),
+                    None,
+                    2,
+                )));
+            return;
+        }
+        state.write_silent().ui.open_handoff(Handoff {
+            conversation_id,
+            position: event.position,
+        });
+        state
+            .write()
+            .mutate(Action::ChatWith(&conversation_id, true));
+        router.replace(UplinkRoute::ChatLayout {});
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "activity-layout",
+            aria_label: "activity-layout",
+            class: "disable-select",
+            if show_slimbar {
+                rsx!(SlimbarLayout { active: UplinkRoute::ActivityLayout {} })
+            },
+            if !minimal {
+                rsx!(ChatSidebar { active_route: UplinkRoute::ActivityLayout {} })
+            },
+            div {
+                class: "activity-body",
+                aria_label: "activity-body",
+                Topbar {
+                    controls: cx.render(rsx!(
+                        FILTERS.into_iter().map(|(label, icon, value)| rsx!(Button {
+                            key: "{label}",
+                            icon: icon,
+                            text: if minimal { "".into() } else { get_local_text(label) },
+                            aria_label: format!("{label}-filter"),
+                            appearance: if *kind.get() == value { Appearance::Primary } else { Appearance::Secondary },
+                            onpress: move |_| kind.set(value),
+                        })),
+                        Button {
+                            icon: Icon::Trash,
+                            aria_label: "clear-activity-button".into(),
+                            appearance: Appearance::Secondary,
+                            tooltip: cx.render(rsx!(Tooltip {
+                                arrow_position: ArrowPosition::Top,
+                                text: get_local_text("activity.clear"),
+                            })),
+                            disabled: state.read().activity.is_empty(),
+                            onpress: move |_| {
+                                state.write().activity.clear();
+                            },
+                        },
+                    )),
+                },
+                div {
+                    class: "activity-timeline",
+                    aria_label: "activity-timeline",
+                    if events.is_empty() {
+                        rsx!(p {
+                            class: "activity-empty",
+                            get_local_text("activity.empty")
+                        })
+                    }
+                    events.iter().enumerate().map(|(idx, event)| {
+                        let name = event.did.as_ref().map(|did| {
+                            state
+                                .read()
+                                .get_identity(did)
+                                .map(|id| id.username())
+                                .unwrap_or_else(|| did.to_string())
+                        }).unwrap_or_default();
+                        let chat = event.conversation_id.and_then(chat_name);
+                        let (icon, title, detail) = match event.kind {
+                            ActivityKind::Message => (
+                                Icon::BellSlash,
+                                get_local_text_with_args("activity.message", vec![
+                                    ("name", name),
+                                    ("chat", chat.unwrap_or_default()),
+                                ]),
+                                Some(event.text.clone()),
+                            ),
+                            ActivityKind::Reaction => (
+                                Icon::FaceSmile,
+                                get_local_text_with_args("activity.reaction", vec![
+                                    ("name", name),
+                                    ("emoji", event.text.clone()),
+                                    ("chat", chat.unwrap_or_default()),
+                                ]),
+                                None,
+                            ),
+                            ActivityKind::FriendAccepted => (
+                                Icon::UserPlus,
+                                get_local_text_with_args("activity.friend-accepted", vec![("name", name)]),
+                                None,
+                            ),
+                            ActivityKind::Transfer => (
+                                Icon::ArrowDownTray,
+                                match chat {
+                                    Some(chat) => get_local_text_with_args("activity.transfer-in", vec![
+                                        ("file", event.text.clone()),
+                                        ("chat", chat),
+                                    ]),
+                                    None => get_local_text_with_args("activity.transfer", vec![("file", event.text.clone())]),
+                                },
+                                None,
+                            ),
+                        };
+                        let when = format_timestamp_timeago(event.date, &language);
+                        let event = event.clone();
+                        rsx!(div {
+                            key: "{idx}",
+                            class: "activity-event",
+                            aria_label: "activity-event",
+                            onclick: move |_| open(&event),
+                            IconElement {
+                                icon: icon,
+                            },
+                            div {
+                                class: "activity-details",
+                                p {
+                                    class: "activity-title",
+                                    "{title}"
+                                },
+                                detail.map(|text| rsx!(p {
+                                    class: "activity-preview",
+                                    "{text}"
+                                })),
+                            },
+                            p {
+                                class: "activity-time",
+                                "{when}"
+                            },
+                        })
+                    })
+                },
+                if minimal {
+                    rsx!(crate::AppNav {
+                        active: UplinkRoute::ActivityLayout {},
+                    })
+                },
+            }
+        }
+    ))
+}
@@ -0,0 +1,241 @@
+use common::icons::outline::Shape as Icon;
+use common::icons::Icon as IconElement;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::call_log::{CallEntry, CallOutcome};
+use common::state::{ui, Action, State, ToastNotification};
+use dioxus::prelude::*;
+use dioxus_router::prelude::use_navigator;
+use kit::{
+    elements::{
+        button::Button,
+        tooltip::{ArrowPosition, Tooltip},
+        Appearance,
+    },
+    layout::topbar::Topbar,
+};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::components::media::calling::{offer_call, participants_to_call};
+use crate::layouts::chats::ChatSidebar;
+use crate::layouts::slimbar::SlimbarLayout;
+use crate::utils::format_timestamp::{format_duration, format_timestamp_timeago};
+use crate::UplinkRoute;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    All,
+    Missed,
+    Incoming,
+    Outgoing,
+}
+
+impl Filter {
+    fn matches(&self, entry: &CallEntry) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Missed => entry.outcome == CallOutcome::Missed,
+            Filter::Incoming => !entry.outgoing,
+            Filter::Outgoing => entry.outgoing,
+        }
+    }
+}
+
+const FILTERS: [(&str, Icon, Filter); 4] = [
+    ("calls.all", Icon::Phone, Filter::All),
+    ("calls.missed", Icon::PhoneXMark, Filter::Missed),
+    ("calls.incoming", Icon::PhoneArrowDownLeft, Filter::Incoming),
+    ("calls.outgoing", Icon::PhoneArrowUpRight, Filter::Outgoing),
+];
+
+// every call of every conversation, newest first
+#[allow(non_snake_case)]
+pub fn CallsLayout(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let filter = use_state(cx, || Filter::All);
+    let router = use_navigator(cx);
+    state.write_silent().ui.current_layout = ui::Layout::Calls;
+    log::trace!("rendering CallsLayout");
+
+    let own_id = state.read().did_key();
+    // the badge goes away once the missed calls are on screen
+    if state.read().call_log.unseen_missed(&own_id) > 0 {
+        state.write_silent().call_log.mark_seen();
+    }
+
+    let minimal = state.read().ui.is_minimal_view();
+    let show_slimbar = state.read().show_slimbar() & !minimal;
+    let language = state.read().settings.language_id();
+    let in_call = state.read().ui.call_info.active_call().is_some();
+    let entries: Vec<CallEntry> = state
+        .read()
+        .call_log
+        .entries(&own_id)
+        .into_iter()
+        .filter(|entry| filter.matches(entry))
+        .collect();
+
+    let chat_name = move |id: Uuid| {
+        let state = state.read();
+        let chat = state.get_chat_by_id(id)?;
+        Some(state.chats().title(&chat).unwrap_or_else(|| {
+            State::join_usernames(&state.remove_self(&state.chat_participants(&chat)))
+        }))
+    };
+
+    let not_found = move || {
+        state
+            .write()
+            .mutate(Action::AddToastNotification(ToastNotification::init(
+                "".into(),
+                get_local_text("calls.conversation-not-found"),
+                None,
+                2,
+            )));
+    };
+
+    let open_chat = move |conversation_id: Uuid| {
+        if state.read().get_chat_by_id(conversation_id).is_none() {
+            not_found();
+            return;
+        }
+        state
+            .write()
+            .mutate(Action::ChatWith(&conversation_id, true));
+        router.replace(UplinkRoute::ChatLayout {});
+    };
+
+    let call_back = move |conversation_id: Uuid| {
+        let Some(participants) = participants_to_call(&state.read(), conversation_id) else {
+            not_found();
+            return;
+        };
+        open_chat(conversation_id);
+        cx.spawn({
+            to_owned![state];
+            async move {
+                offer_call(state, conversation_id, participants).await;
+            }
+        });
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "calls-layout",
+            aria_label: "calls-layout",
+            class: "disable-select",
+            if show_slimbar {
+                rsx!(SlimbarLayout { active: UplinkRoute::CallsLayout {} })
+            },
+            if !minimal {
+                rsx!(ChatSidebar { active_route: UplinkRoute::CallsLayout {} })
+            },
+            div {
+                class: "calls-body",
+                aria_label: "calls-body",
+                Topbar {
+                    controls: cx.render(rsx!(
+                        FILTERS.into_iter().map(|(label, icon, value)| rsx!(Button {
+                            key: "{label}",
+                            icon: icon,
+                            text: if minimal { "".into() } else { get_local_text(label) },
+                            aria_label: format!("{label}-filter"),
+                            appearance: if *filter.get() == value { Appearance::Primary } else { Appearance::Secondary },
+                            onpress: move |_| filter.set(value),
+                        })),
+                        Button {
+                            icon: Icon::Trash,
+                            aria_label: "clear-calls-button".into(),
+                            appearance: Appearance::Secondary,
+                            tooltip: cx.render(rsx!(Tooltip {
+                                arrow_position: ArrowPosition::Top,
+                                text: get_local_text("calls.clear"),
+                            })),
+                            disabled: state.read().call_log.is_empty(),
+                            onpress: move |_| {
+                                state.write().call_log.clear();
+                            },
+                        },
+                    )),
+                },
+                div {
+                    class: "call-history",
+                    aria_label: "call-history",
+                    if entries.is_empty() {
+                        rsx!(p {
+                            class: "calls-empty",
+                            get_local_text("calls.empty")
+                        })
+                    }
+                    entries.into_iter().map(|entry| {
+                        let (icon, kind) = match (entry.outcome, entry.outgoing) {
+                            (CallOutcome::Missed, _) => (Icon::PhoneXMark, "calls.missed-call"),
+                            (CallOutcome::Declined, _) => (Icon::PhoneXMark, "calls.declined-call"),
+                            (_, true) => (Icon::PhoneArrowUpRight, "calls.outgoing-call"),
+                            (_, false) => (Icon::PhoneArrowDownLeft, "calls.incoming-call"),
+                        };
+                        let detail = match entry.outcome {
+                            CallOutcome::Ongoing => get_local_text("calls.ongoing"),
+                            CallOutcome::Ended { duration_secs } => get_local_text_with_args(
+                                "calls.duration",
+                                vec![("duration", format_duration(duration_secs))],
+                            ),
+                            CallOutcome::Missed | CallOutcome::Declined => get_local_text_with_args("calls.from", vec![(
+                                "user",
+                                state
+                                    .read()
+                                    .get_identity(&entry.caller)
+                                    .map(|id| id.username())
+                                    .unwrap_or_default(),
+                            )]),
+                        };
+                        let kind = get_local_text(kind);
+                        let name = chat_name(entry.conversation_id).unwrap_or_default();
+                        let when = format_timestamp_timeago(entry.date, &language);
+                        let conversation_id = entry.conversation_id;
+                        rsx!(div {
+                            key: "{entry.call_id}",
+                            class: format_args!("call-entry {}", if entry.outcome == CallOutcome::Missed { "missed" } else { "" }),
+                            aria_label: "call-entry",
+                            onclick: move |_| open_chat(conversation_id),
+                            IconElement {
+                                icon: icon,
+                            },
+                            div {
+                                class: "call-entry-details",
+                                p {
+                                    class: "call-entry-name",
+                                    "{name}"
+                                },
+                                p {
+                                    class: "call-entry-detail",
+                                    "{kind} · {detail}"
+                                },
+                            },
+                            p {
+                                class: "call-entry-time",
+                                "{when}"
+                            },
+                            Button {
+                                icon: Icon::Phone,
+                                aria_label: "call-back-button".into(),
+                                appearance: Appearance::Secondary,
+                                disabled: in_call,
+                                tooltip: cx.render(rsx!(Tooltip {
+                                    arrow_position: ArrowPosition::Right,
+                                    text: get_local_text("calls.call-back"),
+                                })),
+                                onpress: move |_| call_back(conversation_id),
+                            },
+                        })
+                    })
+                },
+                if minimal {
+                    rsx!(crate::AppNav {
+                        active: UplinkRoute::CallsLayout {},
+                    })
+                },
+            }
+        }
+    ))
+}
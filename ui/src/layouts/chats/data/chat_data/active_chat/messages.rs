@@ -113,6 +113,16 @@ impl Messages {
         })
     }
 
+    pub fn get_top_of_page(&self) -> Option<PartialMessage> {
+        self.all.front().and_then(|msg| {
+            let id = msg.inner.id();
+            self.times.get(&id).map(|date| PartialMessage {
+                message_id: id,
+                date: *date,
+            })
+        })
+    }
+
     pub fn get_bottom_of_page(&self) -> Option<PartialMessage> {
         self.all.back().and_then(|msg| {
             let id = msg.inner.id();
@@ -187,6 +197,10 @@ impl Messages {
         self.loaded.len() != self.all.len()
     }
 
+    pub fn contains(&self, message_id: Uuid) -> bool {
+        self.times.contains_key(&message_id)
+    }
+
     pub fn top(&self) -> Option<Uuid> {
         self.all.front().map(|x| x.inner.id())
     }
@@ -1,4 +1,7 @@
-use common::state::{self, Identity, State};
+use common::{
+    state::{self, Identity, State},
+    utils::avatars::Avatar,
+};
 use kit::components::indicator::Platform;
 use uuid::Uuid;
 use warp::{
@@ -13,11 +16,15 @@ pub struct Metadata {
     pub other_participants: Vec<Identity>,
     pub active_participant: Identity,
     pub subtext: String,
+    // the emoji of the custom status of the other participant of a direct chat
+    pub status_emoji: Option<String>,
     pub is_favorite: bool,
-    pub first_image: String,
+    pub first_image: Avatar,
     pub other_participants_names: String,
     pub platform: Platform,
     pub conversation_name: Option<String>,
+    pub title: Option<String>,
+    pub topic: Option<String>,
     pub conversation_type: Option<ConversationType>,
     pub conversation_settings: ConversationSettings,
     pub creator: Option<DID>,
@@ -37,13 +44,20 @@ impl Metadata {
             .cloned()
             .unwrap_or(s.get_own_identity());
 
-        let subtext = match chat.conversation_type {
-            ConversationType::Direct => active_participant.status_message().unwrap_or_default(),
-            _ => String::new(),
+        let custom_status = match chat.conversation_type {
+            ConversationType::Direct => active_participant.custom_status(),
+            _ => None,
         };
+        let subtext = custom_status
+            .as_ref()
+            .map(|s| s.text.clone())
+            .unwrap_or_default();
+        let status_emoji = custom_status
+            .map(|s| s.emoji)
+            .filter(|emoji| !emoji.is_empty());
         let is_favorite = s.is_favorite(chat);
 
-        let first_image = active_participant.profile_picture();
+        let first_image = active_participant.avatar();
         let other_participants_names = State::join_usernames(&other_participants);
 
         let platform = active_participant.platform().into();
@@ -54,11 +68,14 @@ impl Metadata {
             my_id: s.get_own_identity(),
             active_participant,
             subtext,
+            status_emoji,
             is_favorite,
             first_image,
             other_participants_names,
             platform,
             conversation_name: chat.conversation_name.clone(),
+            title: s.chats().title(chat),
+            topic: s.chats().topic(&chat.id),
             conversation_type: Some(chat.conversation_type),
             conversation_settings: chat.settings,
             creator: chat.creator.clone(),
@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 
 use common::{
     state::{self, Identity, State},
+    utils::avatars::Avatar,
     warp_runner::ui_adapter,
 };
 use kit::components::indicator::Platform;
@@ -26,6 +27,8 @@ pub struct ActiveChat {
     pub messages: Messages,
     pub is_initialized: bool,
     pub key: Uuid,
+    // the first message which was unread when the chat was opened. the divider goes above it
+    pub first_unread: Option<Uuid>,
 }
 
 impl ActiveChat {
@@ -34,11 +37,17 @@ impl ActiveChat {
         chat: &state::chats::Chat,
         messages: VecDeque<ui_adapter::Message>,
     ) -> Self {
+        let metadata = Metadata::new(s, chat);
+        let first_unread = (metadata.unreads > 0)
+            .then(|| messages.get(messages.len().saturating_sub(metadata.unreads)))
+            .flatten()
+            .map(|x| x.inner.id());
         Self {
-            metadata: Metadata::new(s, chat),
+            metadata,
             messages: Messages::new(s.did_key(), messages),
             is_initialized: false,
             key: Uuid::new_v4(),
+            first_unread,
         }
     }
 
@@ -97,10 +106,13 @@ impl ActiveChat {
     pub fn subtext(&self) -> String {
         self.metadata.subtext.clone()
     }
+    pub fn status_emoji(&self) -> Option<String> {
+        self.metadata.status_emoji.clone()
+    }
     pub fn is_favorite(&self) -> bool {
         self.metadata.is_favorite
     }
-    pub fn first_image(&self) -> String {
+    pub fn first_image(&self) -> Avatar {
         self.metadata.first_image.clone()
     }
     pub fn other_participants_names(&self) -> String {
@@ -112,6 +124,13 @@ impl ActiveChat {
     pub fn conversation_name(&self) -> Option<String> {
         self.metadata.conversation_name.clone()
     }
+    // the local title or the conversation name
+    pub fn title(&self) -> Option<String> {
+        self.metadata.title.clone()
+    }
+    pub fn topic(&self) -> Option<String> {
+        self.metadata.topic.clone()
+    }
     pub fn conversation_type(&self) -> ConversationType {
         self.metadata
             .conversation_type
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::layouts::chats::data::DEFAULT_MESSAGES_TO_TAKE;
 
@@ -11,6 +12,17 @@ pub struct ViewInit {
     pub msg_time: Option<DateTime<Utc>>,
     // fetch at most `limit` messages starting at `earliest_time` or now() (if it's none)
     pub limit: usize,
+    // set when a page is fetched while scrolling. the view is restored around this message instead of
+    // following `scroll_to`, so it doesn't jump when messages are added or removed at the other end
+    #[serde(default)]
+    pub anchor: Option<ScrollAnchor>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ScrollAnchor {
+    pub message_id: Uuid,
+    // pixels from the top of the message list to the top of the message
+    pub offset: i64,
 }
 
 impl Default for ViewInit {
@@ -19,6 +31,7 @@ impl Default for ViewInit {
             scroll_to: ScrollTo::MostRecent,
             msg_time: None,
             limit: DEFAULT_MESSAGES_TO_TAKE,
+            anchor: None,
         }
     }
 }
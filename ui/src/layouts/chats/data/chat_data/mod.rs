@@ -147,6 +147,15 @@ impl ChatData {
         }
     }
 
+    pub fn get_top_of_page(&self, conv_id: Uuid) -> Option<PartialMessage> {
+        if self.active_chat.id() != conv_id {
+            log::warn!("get_top_of_page wrong chat id");
+            return None;
+        }
+
+        self.active_chat.messages.get_top_of_page()
+    }
+
     pub fn get_bottom_of_page(&self, conv_id: Uuid) -> Option<PartialMessage> {
         if self.active_chat.id() != conv_id {
             log::warn!("get_bottom_of_page wrong chat id");
@@ -267,6 +276,24 @@ impl ChatData {
         self.chat_behaviors.insert(id, behavior);
     }
 
+    pub fn set_scroll_anchor(&mut self, chat_id: Uuid, anchor: Option<ScrollAnchor>) {
+        if let Some(behavior) = self.chat_behaviors.get_mut(&chat_id) {
+            behavior.view_init.anchor = anchor;
+        }
+    }
+
+    // the anchor is only used once, when the view is initialized after fetching a page
+    pub fn take_scroll_anchor(&mut self, chat_id: Uuid) -> Option<ScrollAnchor> {
+        let anchor = self
+            .chat_behaviors
+            .get_mut(&chat_id)
+            .and_then(|behavior| behavior.view_init.anchor.take())?;
+        self.active_chat
+            .messages
+            .contains(anchor.message_id)
+            .then_some(anchor)
+    }
+
     pub fn set_scroll_value(&mut self, chat_id: Uuid, val: i64) {
         if let Some(behavior) = self.chat_behaviors.get_mut(&chat_id) {
             behavior.scroll_value.replace(val);
@@ -277,6 +304,7 @@ impl ChatData {
 impl ChatData {
     fn scroll_up(&mut self, conv_id: Uuid) {
         if let Some(behavior) = self.chat_behaviors.get_mut(&conv_id) {
+            behavior.view_init.anchor.take();
             if let Some(scroll_top) = self.active_chat.messages.get_earliest_displayed() {
                 behavior.view_init.scroll_to = ScrollTo::ScrollUp {
                     view_top: scroll_top.message_id,
@@ -293,6 +321,7 @@ impl ChatData {
 
     fn scroll_down(&mut self, conv_id: Uuid) {
         if let Some(behavior) = self.chat_behaviors.get_mut(&conv_id) {
+            behavior.view_init.anchor.take();
             if let Some(scroll_bottom) = self.active_chat.messages.get_latest_displayed() {
                 let end_msg = self
                     .active_chat
@@ -4,6 +4,7 @@ mod chatbar;
 mod js_msg;
 mod misc;
 mod msg_group;
+mod participant_index;
 mod scroll_btn;
 
 pub use chat_data::*;
@@ -12,4 +13,5 @@ pub use chatbar::*;
 pub use js_msg::*;
 pub use misc::*;
 pub use msg_group::*;
+pub use participant_index::*;
 pub use scroll_btn::*;
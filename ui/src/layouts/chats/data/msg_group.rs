@@ -10,6 +10,7 @@ use common::{
     },
     warp_runner::ui_adapter,
 };
+use uuid::Uuid;
 use warp::crypto::DID;
 
 // Define a struct to represent a group of messages from the same sender.
@@ -52,10 +53,13 @@ impl MessageGroupMsg {
 /// If sender is different from the last group message, it creates a new group.
 ///
 /// if last message in a group is a reply, it creates a new group.
+///
+/// `first_unread` starts a new group too, so the unread divider can go between the groups.
 pub fn create_message_groups(
     my_id: Identity,
     other_ids: Vec<Identity>,
     mut input: VecDeque<ui_adapter::Message>,
+    first_unread: Option<Uuid>,
 ) -> Vec<MessageGroup> {
     let mut messages: Vec<MessageGroup> = vec![];
     let mut other_ids = other_ids.clone();
@@ -67,6 +71,7 @@ pub fn create_message_groups(
                 if group.sender == msg.inner.sender()
                     && last_group_message.message.in_reply_to.is_none()
                     && msg.in_reply_to.is_none()
+                    && first_unread != Some(msg.inner.id())
                 {
                     let g = MessageGroupMsg {
                         message: msg.clone(),
@@ -0,0 +1,126 @@
+use common::state::Identity;
+
+/// The participants of a chat sorted by username. Used by the participants panel and the mention
+/// autocomplete so that large groups don't have to be scanned for every keystroke.
+#[derive(Clone, Default)]
+pub struct ParticipantIndex {
+    // (lowercase username, identity), sorted by the lowercase username
+    entries: Vec<(String, Identity)>,
+}
+
+impl ParticipantIndex {
+    pub fn new(participants: Vec<Identity>) -> Self {
+        let mut entries: Vec<_> = participants
+            .into_iter()
+            .map(|id| (id.username().to_lowercase(), id))
+            .collect();
+        entries.sort_by(|(a, a_id), (b, b_id)| {
+            a.cmp(b)
+                .then_with(|| a_id.did_key().to_string().cmp(&b_id.did_key().to_string()))
+        });
+        Self { entries }
+    }
+
+    /// Participants whose username starts with `prefix`, in alphabetical order
+    pub fn starts_with(&self, prefix: &str) -> impl Iterator<Item = &Identity> {
+        let prefix = prefix.to_lowercase();
+        let start = self
+            .entries
+            .partition_point(|(name, _)| name.as_str() < prefix.as_str());
+        self.entries[start..]
+            .iter()
+            .take_while(move |(name, _)| name.starts_with(&prefix))
+            .map(|(_, id)| id)
+    }
+
+    /// All participants whose username contains `query`. Prefix matches come first.
+    pub fn search(&self, query: &str) -> Vec<Identity> {
+        if query.is_empty() {
+            return self.entries.iter().map(|(_, id)| id.clone()).collect();
+        }
+        let query = query.to_lowercase();
+        let mut matches: Vec<Identity> = self.starts_with(&query).cloned().collect();
+        matches.extend(
+            self.entries
+                .iter()
+                .filter(|(name, _)| !name.starts_with(&query) && name.contains(&query))
+                .map(|(_, id)| id.clone()),
+        );
+        matches
+    }
+
+    pub fn find_by_username(&self, username: &str) -> Option<&Identity> {
+        let username = username.to_lowercase();
+        self.starts_with(&username)
+            .find(|id| id.username().to_lowercase() == username)
+    }
+}
+
+/// Groups participants by the first letter of their username, keeping their order.
+/// Usernames which don't start with a letter are grouped under '#'.
+pub fn alphabetical_sections(participants: &[Identity]) -> Vec<(char, Vec<Identity>)> {
+    let mut sections: Vec<(char, Vec<Identity>)> = vec![];
+    for id in participants {
+        let letter = id
+            .username()
+            .chars()
+            .next()
+            .filter(|c| c.is_alphabetic())
+            .map(|c| c.to_uppercase().next().unwrap_or(c))
+            .unwrap_or('#');
+        match sections.iter_mut().find(|(l, _)| *l == letter) {
+            Some((_, list)) => list.push(id.clone()),
+            None => sections.push((letter, vec![id.clone()])),
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(username: &str) -> Identity {
+        let mut id = warp::multipass::identity::Identity::default();
+        id.set_username(username);
+        Identity::from(id)
+    }
+
+    fn usernames<'a>(ids: impl IntoIterator<Item = &'a Identity>) -> Vec<String> {
+        ids.into_iter().map(|id| id.username()).collect()
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let index = ParticipantIndex::new(vec![
+            identity("bob"),
+            identity("Alice"),
+            identity("albert"),
+            identity("carol"),
+        ]);
+        assert_eq!(usernames(index.starts_with("al")), vec!["albert", "Alice"]);
+        assert_eq!(usernames(index.starts_with("B")), vec!["bob"]);
+        assert!(index.starts_with("z").next().is_none());
+    }
+
+    #[test]
+    fn test_search_prefix_first() {
+        let index = ParticipantIndex::new(vec![identity("carla"), identity("arlo")]);
+        assert_eq!(usernames(&index.search("ar")), vec!["arlo", "carla"]);
+        assert_eq!(index.search("").len(), 2);
+    }
+
+    #[test]
+    fn test_alphabetical_sections() {
+        let index = ParticipantIndex::new(vec![
+            identity("bob"),
+            identity("alice"),
+            identity("Amy"),
+            identity("1up"),
+        ]);
+        let sections = alphabetical_sections(&index.search(""));
+        let letters: Vec<char> = sections.iter().map(|(l, _)| *l).collect();
+        assert_eq!(letters, vec!['#', 'A', 'B']);
+        assert_eq!(usernames(&sections[1].1), vec!["alice", "Amy"]);
+    }
+}
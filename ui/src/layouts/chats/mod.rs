@@ -1,26 +1,33 @@
 mod data;
 mod presentation;
 mod scripts;
+pub use presentation::chatbar::coroutines::send_text;
 pub use presentation::sidebar::Sidebar as ChatSidebar;
 use presentation::welcome::Welcome;
 
 use std::{path::PathBuf, rc::Rc};
 
 use crate::{
-    layouts::{chats::presentation::chat::Compose, slimbar::SlimbarLayout},
+    components::media::call_files::{is_over_call, share_in_call},
+    layouts::{
+        chats::presentation::{chat::Compose, split_chat::SplitChat},
+        slimbar::SlimbarLayout,
+    },
     utils::{
         clipboard::clipboard_data::get_files_path_from_clipboard,
-        get_drag_event,
+        get_drag_event, profiling,
         verify_valid_paths::{decoded_pathbufs, verify_paths},
     },
 };
 
 use common::{
     language::{get_local_text, get_local_text_with_args},
-    state::{ui, Action, State},
+    state::{data_transfer::TransferTracker, ui, Action, State},
 };
 use dioxus::prelude::*;
-use dioxus_desktop::{use_window, wry::webview::FileDropEvent, DesktopContext};
+use dioxus_desktop::{
+    tao::dpi::PhysicalPosition, use_window, wry::webview::FileDropEvent, DesktopContext,
+};
 use dioxus_html::input_data::keyboard_types::Code;
 use dioxus_html::input_data::keyboard_types::Modifiers;
 
@@ -59,7 +66,9 @@ pub const OVERLAY_SCRIPT: &str = r#"
 
 #[allow(non_snake_case)]
 pub fn ChatLayout(cx: Scope) -> Element {
+    profiling::count_render("ChatLayout");
     let state = use_shared_state::<State>(cx)?;
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
     let first_render = use_state(cx, || true);
 
     state.write_silent().ui.current_layout = ui::Layout::Welcome;
@@ -77,16 +86,37 @@ pub fn ChatLayout(cx: Scope) -> Element {
     let eval: &UseEvalFn = use_eval(cx);
 
     let show_slimbar = state.read().show_slimbar();
+    // there is no room for a second conversation in the minimal view
+    let split_chat = state
+        .read()
+        .ui
+        .split_chat
+        .filter(|_| !show_welcome && !is_minimal_view);
+    let can_drop_split = !show_welcome
+        && !is_minimal_view
+        && state
+            .read()
+            .ui
+            .dragged_chat
+            .map_or(false, |id| state.read().chats().active != Some(id));
+    let split_drag_over = use_state(cx, || false);
 
     // #[cfg(target_os = "windows")]
     use_future(cx, (), |_| {
-        to_owned![state, window, drag_event, eval];
+        to_owned![state, file_tracker, window, drag_event, eval];
         async move {
             // ondragover function from div does not work on windows
             loop {
                 tokio::time::sleep(std::time::Duration::from_millis(100)).await;
                 if let FileDropEvent::Hovered { .. } = get_drag_event::get_drag_event() {
-                    drop_and_attach_files(eval.clone(), &window, &drag_event, state.clone()).await;
+                    drop_and_attach_files(
+                        eval.clone(),
+                        &window,
+                        &drag_event,
+                        state.clone(),
+                        file_tracker.clone(),
+                    )
+                    .await;
                 }
             }
         }
@@ -142,7 +172,30 @@ pub fn ChatLayout(cx: Scope) -> Element {
                 active_route: crate::UplinkRoute::ChatLayout {},
             },
             show_welcome.then(|| rsx!(Welcome {})),
-            (!show_welcome && (sidebar_hidden  || !state.read().ui.is_minimal_view())).then(|| rsx!(Compose {}))
+            (!show_welcome && (sidebar_hidden  || !state.read().ui.is_minimal_view())).then(|| rsx!(Compose {})),
+            split_chat.map(|chat_id| rsx!(SplitChat { chat_id: chat_id })),
+            // a chat dragged out of the sidebar to the right edge opens next to the active one
+            can_drop_split.then(|| rsx!(div {
+                class: format_args!("split-drop-zone {}", if *split_drag_over.get() { "drag-over" } else { "" }),
+                aria_label: "split-drop-zone",
+                prevent_default: "ondragover ondrop",
+                ondragover: move |_| {
+                    if !*split_drag_over.get() {
+                        split_drag_over.set(true);
+                    }
+                },
+                ondragleave: move |_| split_drag_over.set(false),
+                ondrop: move |_| {
+                    split_drag_over.set(false);
+                    let dragged = state.write().ui.dragged_chat.take();
+                    if let Some(chat_id) = dragged {
+                        state.write().mutate(Action::SetSplitChat(Some(chat_id)));
+                    }
+                },
+                p {
+                    get_local_text("messages.drop-to-split")
+                },
+            }))
         }
     ))
 }
@@ -152,8 +205,29 @@ async fn drop_and_attach_files(
     window: &DesktopContext,
     drag_event: &UseRef<Option<FileDropEvent>>,
     state: UseSharedState<State>,
+    file_tracker: UseSharedState<TransferTracker>,
 ) {
-    let new_files = drag_and_drop_function(eval, window, drag_event).await;
+    // files dropped on the call of the chat are shared in it right away
+    let call_conversation = state
+        .read()
+        .ui
+        .call_info
+        .active_call()
+        .map(|call| call.call.conversation_id)
+        .filter(|id| state.read().get_active_chat().map(|c| c.id) == Some(*id));
+    let (new_files, position) = drag_and_drop_function(
+        eval.clone(),
+        window,
+        drag_event,
+        call_conversation.is_some(),
+    )
+    .await;
+    if let (Some(conv_id), Some(position)) = (call_conversation, position) {
+        if !new_files.is_empty() && is_over_call(&eval, window, position).await {
+            share_in_call(&state, &file_tracker, conv_id, new_files).await;
+            return;
+        }
+    }
     let chat_uuid = state
         .read()
         .get_active_chat()
@@ -169,19 +243,24 @@ async fn drag_and_drop_function(
     eval: UseEvalFn,
     window: &DesktopContext,
     drag_event: &UseRef<Option<FileDropEvent>>,
-) -> Vec<PathBuf> {
+    in_call: bool,
+) -> (Vec<PathBuf>, Option<PhysicalPosition<f64>>) {
     *drag_event.write_silent() = Some(get_drag_event::get_drag_event());
     let mut new_files_to_upload = Vec::new();
+    let mut drop_position = None;
     let _ = eval(ANIMATION_DASH_SCRIPT);
     loop {
         let file_drop_event = get_drag_event::get_drag_event();
         match file_drop_event {
-            FileDropEvent::Hovered { paths, .. } => {
+            FileDropEvent::Hovered { paths, position } => {
                 if verify_paths(&paths) {
                     let mut script = OVERLAY_SCRIPT.replace("$IS_DRAGGING", "true");
+                    let over_call = in_call && is_over_call(&eval, window, position).await;
                     let feedback_script = &FEEDBACK_TEXT_SCRIPT.replace(
                         "$TEXT",
-                        &(if paths.len() > 1 {
+                        &(if over_call {
+                            get_local_text("remote-controls.drop-to-share")
+                        } else if paths.len() > 1 {
                             get_local_text_with_args(
                                 "files.files-to-upload",
                                 vec![("num", paths.len())],
@@ -195,10 +274,11 @@ async fn drag_and_drop_function(
                     let _ = eval(&script);
                 }
             }
-            FileDropEvent::Dropped { paths, .. } => {
+            FileDropEvent::Dropped { paths, position } => {
                 if verify_paths(&paths) {
                     *drag_event.write_silent() = None;
                     new_files_to_upload = decoded_pathbufs(paths);
+                    drop_position = Some(position);
                     let mut script = OVERLAY_SCRIPT.replace("$IS_DRAGGING", "false");
                     script.push_str(SELECT_CHAT_BAR);
                     window.set_focus();
@@ -216,5 +296,5 @@ async fn drag_and_drop_function(
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
     }
     *drag_event.write_silent() = None;
-    new_files_to_upload
+    (new_files_to_upload, drop_position)
 }
@@ -0,0 +1,46 @@
+//! The background and bubble color chosen for a conversation, applied to its messages through CSS
+//! variables. See `#compose.custom-appearance` in the chats style.
+
+use common::state::chats::needs_dark_text;
+use common::state::State;
+use uuid::Uuid;
+
+use crate::utils::wallpaper;
+
+fn text_color(color: (u8, u8, u8)) -> &'static str {
+    if needs_dark_text(color) {
+        "var(--text-color-dark)"
+    } else {
+        "var(--text-color)"
+    }
+}
+
+/// The inline style of the conversation, empty while it uses the theme
+pub fn appearance_style(state: &State, chat_id: Uuid) -> String {
+    let appearance = state.chats().appearance(&chat_id);
+
+    let mut style = String::new();
+    if let Some((r, g, b)) = appearance.background {
+        style += &format!(
+            "--chat-background: rgb({r},{g},{b}); --chat-background-text: {};",
+            text_color((r, g, b))
+        );
+    }
+    if let Some(path) = &appearance.wallpaper {
+        // the webview loads the image itself, see utils::wallpaper
+        let url = wallpaper::url(chat_id, path);
+        // the text next to the messages is drawn on a backdrop since the image can be anything
+        let text = appearance
+            .wallpaper_color
+            .map(text_color)
+            .unwrap_or("var(--text-color)");
+        style += &format!("--chat-wallpaper: url('{url}'); --chat-background-text: {text};");
+    }
+    if let Some((r, g, b)) = appearance.bubble_color() {
+        style += &format!(
+            "--chat-bubble: rgb({r},{g},{b}); --chat-bubble-text: {};",
+            text_color((r, g, b))
+        );
+    }
+    style
+}
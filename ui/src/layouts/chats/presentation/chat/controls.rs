@@ -1,5 +1,5 @@
 use dioxus::prelude::*;
-use futures::{channel::oneshot, StreamExt};
+use futures::StreamExt;
 use kit::{
     components::invisible_closer::InvisibleCloser,
     elements::{
@@ -10,26 +10,20 @@ use kit::{
     layout::modal::Modal,
 };
 
-use super::pinned_messages::PinnedMessages;
-use crate::layouts::chats::data::{ChatData, ChatProps};
-
-use common::{
-    icons::outline::Shape as Icon,
-    state::call,
-    warp_runner::{BlinkCmd, WarpCmd},
-};
-use common::{
-    state::{Action, State},
-    WARP_CMD_CH,
+use super::{handoff::HandoffLink, pinned_messages::PinnedMessages};
+use crate::{
+    components::media::{calling::offer_call, pre_call_check::PreCallCheck},
+    layouts::chats::data::{ChatData, ChatProps},
 };
 
+use common::icons::outline::Shape as Icon;
+use common::state::{Action, State};
+
 use common::language::get_local_text;
 
 use uuid::Uuid;
 use warp::{crypto::DID, raygun::ConversationSettings};
 
-use tracing::log;
-
 enum ControlsCmd {
     VoiceCall {
         participants: Vec<DID>,
@@ -49,6 +43,8 @@ pub fn get_controls(cx: Scope<ChatProps>) -> Element {
     let call_in_progress = active_call.is_some(); // active_chat.map(|chat| chat.id) == active_call.map(|call| call.conversation_id);
 
     let show_pinned = use_state(cx, || false);
+    let show_handoff = use_state(cx, || false);
+    let show_pre_call_check = use_state(cx, || false);
 
     use_effect(cx, &minimal, |_| {
         to_owned![show_more];
@@ -61,37 +57,13 @@ pub fn get_controls(cx: Scope<ChatProps>) -> Element {
     let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<ControlsCmd>| {
         to_owned![call_pending, state];
         async move {
-            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
             while let Some(cmd) = rx.next().await {
                 match cmd {
                     ControlsCmd::VoiceCall {
                         participants,
                         conversation_id,
                     } => {
-                        let (tx, rx) = oneshot::channel();
-                        if let Err(e) = warp_cmd_tx.send(WarpCmd::Blink(BlinkCmd::OfferCall {
-                            conversation_id,
-                            participants: participants.clone(),
-                            rsp: tx,
-                        })) {
-                            log::error!("failed to send command to warp_runner: {e}");
-                            call_pending.set(false);
-                            continue;
-                        }
-
-                        let res = rx.await.expect("warp runner failed");
-                        match res {
-                            Ok(call_id) => {
-                                state.write().mutate(Action::OfferCall(call::Call::new(
-                                    call_id,
-                                    conversation_id,
-                                    participants,
-                                )));
-                            }
-                            Err(e) => {
-                                log::error!("BlinkCmd::OfferCall failed: {e}");
-                            }
-                        }
+                        offer_call(state.clone(), conversation_id, participants).await;
                         call_pending.set(false);
                     }
                 }
@@ -99,6 +71,20 @@ pub fn get_controls(cx: Scope<ChatProps>) -> Element {
         }
     });
 
+    let start_call = move || {
+        ch.send(ControlsCmd::VoiceCall {
+            participants: chat_data
+                .read()
+                .active_chat
+                .other_participants()
+                .iter()
+                .map(|x| x.did_key())
+                .collect(),
+            conversation_id: chat_data.read().active_chat.id(),
+        });
+        call_pending.set(true);
+    };
+
     let (arrow_top, arrow_top_right) = if minimal {
         (ArrowPosition::Right, ArrowPosition::Right)
     } else {
@@ -221,25 +207,56 @@ pub fn get_controls(cx: Scope<ChatProps>) -> Element {
                 show_pinned.set(true);
                 show_more.set(false);
             }
+        },
+        Button {
+            icon: Icon::DevicePhoneMobile,
+            disabled: !chat_data.read().active_chat.is_initialized,
+            aria_label: "handoff-button".into(),
+            appearance: if *show_handoff.get() { Appearance::Primary } else { Appearance::Secondary },
+            text: text_builder("messages.handoff"),
+            tooltip: tooltip_builder("messages.handoff", arrow_top),
+            onpress: move |_| {
+                show_handoff.set(true);
+                show_more.set(false);
+            }
         }
+        (!minimal).then(|| {
+            let panel_open = state.read().chats().panel(&chat_data.read().active_chat.id()).open;
+            let label = if panel_open { "conversation-panel.hide" } else { "conversation-panel.show" };
+            rsx!(Button {
+                icon: Icon::Window,
+                disabled: !chat_data.read().active_chat.is_initialized,
+                aria_label: "conversation-panel-button".into(),
+                appearance: if panel_open { Appearance::Primary } else { Appearance::Secondary },
+                text: text_builder(label),
+                tooltip: tooltip_builder(label, arrow_top),
+                onpress: move |_| {
+                    if chat_data.read().active_chat.is_initialized {
+                        state.write().mutate(Action::ToggleConversationPanel(chat_data.read().active_chat.id()));
+                    }
+                }
+            })
+        }),
         Button {
             icon: Icon::PhoneArrowUpRight,
-            disabled: !state.read().configuration.developer.experimental_features || *call_pending.current() || call_in_progress,
+            disabled: *call_pending.current() || call_in_progress,
             aria_label: "Call".into(),
             appearance: Appearance::Secondary,
-            text: text_builder(if !state.read().configuration.developer.experimental_features {"uplink.coming-soon"} else {"uplink.call"}),
-            tooltip: tooltip_builder(if !state.read().configuration.developer.experimental_features {"uplink.coming-soon"} else {"uplink.call"}, arrow_top),
+            text: text_builder("uplink.call"),
+            tooltip: tooltip_builder("uplink.call", arrow_top),
             onpress: move |_| {
-                if chat_data.read().active_chat.is_initialized {
-                    ch.send(ControlsCmd::VoiceCall{
-                        participants: chat_data.read().active_chat.other_participants().iter().map(|x| x.did_key()).collect(),
-                        conversation_id: chat_data.read().active_chat.id()
-                    });
-                    call_pending.set(true);
-                    show_more.set(false);
+                if !chat_data.read().active_chat.is_initialized {
+                    return;
+                }
+                if state.read().configuration.audiovideo.pre_call_check {
+                    show_pre_call_check.set(true);
+                } else {
+                    start_call();
                 }
+                show_more.set(false);
             }
         },
+        // blink doesn't carry video yet, the camera can only be previewed during a call
         Button {
             icon: Icon::VideoCamera,
             disabled: true,
@@ -266,6 +283,29 @@ pub fn get_controls(cx: Scope<ChatProps>) -> Element {
         }
     )),));
 
+    let handoff = cx.render(rsx!(show_handoff.then(|| rsx!(
+        Modal {
+            open: true,
+            transparent: true,
+            with_title: get_local_text("messages.handoff"),
+            onclose: move |_| {
+                show_handoff.set(false);
+            },
+            HandoffLink {}
+        }
+    )),));
+
+    let pre_call_check = cx.render(rsx!(show_pre_call_check.then(|| rsx!(PreCallCheck {
+        video: false,
+        onjoin: move |_| {
+            show_pre_call_check.set(false);
+            start_call();
+        },
+        oncancel: move |_| {
+            show_pre_call_check.set(false);
+        },
+    })),));
+
     if minimal {
         return cx.render(rsx!(
             div {
@@ -300,8 +340,10 @@ pub fn get_controls(cx: Scope<ChatProps>) -> Element {
                         buttons
                     })
             }),
-            pinned
+            pinned,
+            handoff,
+            pre_call_check
         ));
     }
-    cx.render(rsx!(buttons, pinned))
+    cx.render(rsx!(buttons, pinned, handoff, pre_call_check))
 }
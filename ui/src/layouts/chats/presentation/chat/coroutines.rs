@@ -4,7 +4,7 @@ use common::{
     state::State,
     warp_runner::{
         ui_adapter::{self, MessageEvent},
-        FetchMessagesConfig, FetchMessagesResponse, RayGunCmd, WarpCmd, WarpEvent,
+        CancelToken, FetchMessagesConfig, FetchMessagesResponse, RayGunCmd, WarpCmd, WarpEvent,
     },
     WARP_CMD_CH, WARP_EVENT_CH,
 };
@@ -21,7 +21,7 @@ pub fn handle_warp_events(
 ) {
     let active_chat_id = state.read().get_active_chat().map(|x| x.id);
     use_future(cx, &active_chat_id, |chat_id| {
-        to_owned![chat_data];
+        to_owned![state, chat_data];
         async move {
             let mut ch = WARP_EVENT_CH.tx.subscribe();
             while let Ok(evt) = ch.recv().await {
@@ -43,6 +43,8 @@ pub fn handle_warp_events(
                         conversation_id,
                         message,
                     } => {
+                        // messages from a merged duplicate are shown in the chat it was merged into
+                        let conversation_id = state.read().chats.resolve_merged(conversation_id);
                         if conversation_id != chat_id {
                             continue;
                         }
@@ -68,6 +70,7 @@ pub fn handle_warp_events(
                         message_id,
                         ..
                     } => {
+                        let conversation_id = state.read().chats.resolve_merged(conversation_id);
                         if chat_data.read().active_chat.id() != conversation_id {
                             continue;
                         }
@@ -75,7 +78,7 @@ pub fn handle_warp_events(
                             .write()
                             .delete_message(conversation_id, message_id);
                     }
-                    MessageEvent::MessageReactionAdded { message }
+                    MessageEvent::MessageReactionAdded { message, .. }
                     | MessageEvent::MessageReactionRemoved { message } => {
                         if chat_data.read().active_chat.id() != message.conversation_id() {
                             continue;
@@ -102,7 +105,8 @@ pub fn init_chat_data<'a>(
     chat_data: &'a UseSharedState<ChatData>,
 ) -> &'a UseFuture<()> {
     let active_chat_id = state.read().get_active_chat().map(|x| x.id);
-    use_future(cx, &active_chat_id, |conv_id| {
+    let handoffs_opened = state.read().ui.handoffs_opened;
+    use_future(cx, &(active_chat_id, handoffs_opened), |(conv_id, _)| {
         to_owned![state, chat_data];
         async move {
             while !state.read().initialized {
@@ -114,24 +118,47 @@ pub fn init_chat_data<'a>(
                 Some(x) => x,
             };
 
-            let behavior = chat_data.read().get_chat_behavior(conv_id);
+            // dioxus drops this future when another chat is opened, which aborts a fetch for the
+            // previous chat that is still queued or running
+            let token = CancelToken::new();
+            let _guard = token.drop_guard();
+
+            // a handoff link from another device opens the chat where it was left there
+            let handoff = state.write_silent().ui.take_handoff_position(conv_id);
+            let behavior = match handoff {
+                Some(position) => ChatBehavior {
+                    view_init: data::ViewInit {
+                        scroll_to: data::ScrollTo::ScrollUp {
+                            view_top: position.message_id,
+                        },
+                        msg_time: Some(position.date),
+                        limit: data::DEFAULT_MESSAGES_TO_TAKE,
+                        anchor: None,
+                    },
+                    ..Default::default()
+                },
+                None => chat_data.read().get_chat_behavior(conv_id),
+            };
             let config = behavior.messages_config();
+            // the offset the chat was scrolled to is restored once the messages are rendered
+            let scroll_value = behavior.scroll_value;
 
             let r = match config {
                 FetchMessagesConfig::MostRecent { limit } => {
                     log::trace!("fetching most recent messages for chat");
-                    fetch_most_recent(conv_id, limit).await
+                    fetch_most_recent(conv_id, limit, &token).await
                 }
                 FetchMessagesConfig::Window { center, half_size } => {
                     log::trace!("fetching window for chat");
-                    fetch_window(conv_id, behavior, center, half_size).await
+                    fetch_window(conv_id, behavior, center, half_size, &token).await
                 }
                 _ => unreachable!(),
             };
 
             match r {
-                Ok((messages, behavior)) => {
+                Ok((messages, mut behavior)) => {
                     log::debug!("init_chat_data");
+                    behavior.scroll_value = scroll_value;
                     chat_data
                         .write()
                         .set_active_chat(&state.read(), &conv_id, behavior, messages);
@@ -147,6 +174,7 @@ pub async fn fetch_window<'a>(
     chat_behavior: ChatBehavior,
     date: DateTime<Utc>,
     half_size: usize,
+    token: &CancelToken,
 ) -> anyhow::Result<(Vec<ui_adapter::Message>, ChatBehavior)> {
     let mut messages = vec![];
     let has_more_before: bool;
@@ -156,14 +184,17 @@ pub async fn fetch_window<'a>(
     let warp_cmd_tx = WARP_CMD_CH.tx.clone();
     let (tx, rx) = oneshot::channel();
 
-    if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::FetchMessages {
-        conv_id,
-        config: FetchMessagesConfig::Earlier {
-            start_date: date,
-            limit: half_size,
-        },
-        rsp: tx,
-    })) {
+    if let Err(e) = warp_cmd_tx.send(
+        WarpCmd::RayGun(RayGunCmd::FetchMessages {
+            conv_id,
+            config: FetchMessagesConfig::Earlier {
+                start_date: date,
+                limit: half_size,
+            },
+            rsp: tx,
+        })
+        .cancellable(token.clone()),
+    ) {
         bail!("failed to init messages: {e}");
     }
 
@@ -190,14 +221,17 @@ pub async fn fetch_window<'a>(
 
     let (tx, rx) = oneshot::channel();
 
-    if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::FetchMessages {
-        conv_id,
-        config: FetchMessagesConfig::Later {
-            start_date: date,
-            limit: half_size,
-        },
-        rsp: tx,
-    })) {
+    if let Err(e) = warp_cmd_tx.send(
+        WarpCmd::RayGun(RayGunCmd::FetchMessages {
+            conv_id,
+            config: FetchMessagesConfig::Later {
+                start_date: date,
+                limit: half_size,
+            },
+            rsp: tx,
+        })
+        .cancellable(token.clone()),
+    ) {
         bail!("failed to init messages: {e}");
     }
 
@@ -246,16 +280,20 @@ pub async fn fetch_window<'a>(
 pub async fn fetch_most_recent<'a>(
     conv_id: Uuid,
     limit: usize,
+    token: &CancelToken,
 ) -> anyhow::Result<(Vec<ui_adapter::Message>, ChatBehavior)> {
     let warp_cmd_tx = WARP_CMD_CH.tx.clone();
     let (tx, rx) = oneshot::channel();
 
     // todo: save the config during runtime
-    if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::FetchMessages {
-        conv_id,
-        config: FetchMessagesConfig::MostRecent { limit },
-        rsp: tx,
-    })) {
+    if let Err(e) = warp_cmd_tx.send(
+        WarpCmd::RayGun(RayGunCmd::FetchMessages {
+            conv_id,
+            config: FetchMessagesConfig::MostRecent { limit },
+            rsp: tx,
+        })
+        .cancellable(token.clone()),
+    ) {
         bail!("failed to init messages: {e}");
     }
 
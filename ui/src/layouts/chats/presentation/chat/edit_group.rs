@@ -4,7 +4,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use common::{
     icons::outline::Shape as Icon,
     icons::Icon as IconElement,
-    language::get_local_text,
+    language::{collation, get_local_text},
     state::{Identity, State},
     warp_runner::{RayGunCmd, WarpCmd},
     WARP_CMD_CH,
@@ -90,7 +90,7 @@ pub fn EditGroup(cx: Scope) -> Element {
         group_members
     };
 
-    friends.sort_by_key(|d| d.username());
+    collation::sort_by_name(&mut friends, |d| d.username());
 
     let add_friends = rsx!(Button {
         aria_label: "edit-group-add-members".into(),
@@ -121,7 +121,19 @@ pub fn EditGroup(cx: Scope) -> Element {
     });
 
     let creator_did2 = creator_id.clone();
-    let am_i_group_creator = creator_id == state.read().did_key();
+    // admins can remove members too
+    let am_i_group_creator = creator_id == state.read().did_key()
+        || state
+            .read()
+            .get_active_chat()
+            .map(|chat| {
+                state
+                    .read()
+                    .chats
+                    .role_of(&chat.id, &state.read().did_key())
+                    .can_manage_group()
+            })
+            .unwrap_or_default();
 
     cx.render(rsx!(
         div {
@@ -279,7 +291,7 @@ fn friend_row(cx: Scope<FriendRowProps>) -> Element {
             UserImage {
                 platform: _friend.platform().into(),
                 status: _friend.identity_status().into(),
-                image: _friend.profile_picture()
+                image: _friend.avatar()
             },
             div {
                 class: "flex-1",
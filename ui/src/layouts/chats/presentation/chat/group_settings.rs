@@ -1,12 +1,18 @@
 #[allow(unused_imports)]
 use std::collections::{BTreeMap, HashMap, HashSet};
 
-use common::warp_runner::{RayGunCmd, WarpCmd};
+use arboard::Clipboard;
+use chrono::{Duration, Utc};
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::{Action, State, ToastNotification};
+use common::warp_runner::{GroupInvite, RayGunCmd, WarpCmd};
 use common::WARP_CMD_CH;
 use dioxus::prelude::*;
 use futures::channel::oneshot;
 use futures::StreamExt;
-use kit::elements::switch::Switch;
+use kit::elements::{button::Button, select::Select, switch::Switch, Appearance};
+use uuid::Uuid;
 use warp::raygun::{ConversationSettings, GroupSettings};
 
 use crate::components::settings::SettingSectionSimple;
@@ -108,6 +114,229 @@ pub fn GroupSettings(cx: Scope) -> Element {
                     }
                 },
             }
+            GroupInvites {
+                conv_id: chat_data.read().active_chat.id(),
+            }
+        }
+    ))
+}
+
+enum InviteCmd {
+    Create {
+        expires: Option<Duration>,
+        max_uses: Option<u32>,
+    },
+    Revoke(String),
+}
+
+// (label key, duration in hours)
+const INVITE_EXPIRY_OPTIONS: [(&str, Option<i64>); 4] = [
+    ("messages.invite-expires-never", None),
+    ("messages.invite-expires-hour", Some(1)),
+    ("messages.invite-expires-day", Some(24)),
+    ("messages.invite-expires-week", Some(24 * 7)),
+];
+const INVITE_MAX_USES_OPTIONS: [Option<u32>; 5] = [None, Some(1), Some(5), Some(10), Some(25)];
+
+fn max_uses_label(max_uses: Option<u32>) -> String {
+    match max_uses {
+        Some(max) => {
+            get_local_text_with_args("messages.invite-max-uses", vec![("num", max.to_string())])
+        }
+        None => get_local_text("messages.invite-unlimited-uses"),
+    }
+}
+
+async fn list_invites(conv_id: Uuid) -> Vec<GroupInvite> {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = WARP_CMD_CH
+        .tx
+        .clone()
+        .send(WarpCmd::RayGun(RayGunCmd::ListGroupInvites {
+            conv_id,
+            rsp: tx,
+        }))
+    {
+        log::error!("failed to send warp command: {}", e);
+        return vec![];
+    }
+    rx.await.expect("command canceled")
+}
+
+#[derive(Props, PartialEq)]
+struct GroupInvitesProps {
+    conv_id: Uuid,
+}
+
+// creates and lists the invite links of the group
+#[allow(non_snake_case)]
+fn GroupInvites(cx: Scope<GroupInvitesProps>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let invites: &UseRef<Vec<GroupInvite>> = use_ref(cx, Vec::new);
+    let expiry_idx = use_state(cx, || 0);
+    let max_uses_idx = use_state(cx, || 0);
+    let conv_id = cx.props.conv_id;
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<InviteCmd>| {
+        to_owned![state, invites];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            invites.set(list_invites(conv_id).await);
+
+            while let Some(cmd) = rx.next().await {
+                match cmd {
+                    InviteCmd::Create { expires, max_uses } => {
+                        let (tx, rx) = oneshot::channel();
+                        if let Err(e) =
+                            warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::CreateGroupInvite {
+                                conv_id,
+                                expires: expires.map(|duration| Utc::now() + duration),
+                                max_uses,
+                                rsp: tx,
+                            }))
+                        {
+                            log::error!("failed to send warp command: {}", e);
+                            continue;
+                        }
+                        if let Err(e) = rx.await.expect("command canceled") {
+                            log::error!("failed to create group invite: {e}");
+                            state.write().mutate(Action::AddToastNotification(
+                                ToastNotification::init(
+                                    "".into(),
+                                    get_local_text("messages.not-allowed"),
+                                    None,
+                                    2,
+                                ),
+                            ));
+                        }
+                    }
+                    InviteCmd::Revoke(code) => {
+                        let (tx, rx) = oneshot::channel();
+                        if let Err(e) =
+                            warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::RevokeGroupInvite {
+                                code,
+                                rsp: tx,
+                            }))
+                        {
+                            log::error!("failed to send warp command: {}", e);
+                            continue;
+                        }
+                        rx.await.expect("command canceled");
+                    }
+                }
+                invites.set(list_invites(conv_id).await);
+            }
+        }
+    });
+
+    let copy_link = move |link: String| {
+        match Clipboard::new() {
+            Ok(mut c) => {
+                if let Err(e) = c.set_text(link) {
+                    log::warn!("Unable to set text to clipboard: {e}");
+                    return;
+                }
+            }
+            Err(e) => {
+                log::warn!("Unable to create clipboard reference: {e}");
+                return;
+            }
+        };
+        state
+            .write()
+            .mutate(Action::AddToastNotification(ToastNotification::init(
+                "".into(),
+                get_local_text("messages.invite-copied"),
+                None,
+                2,
+            )));
+    };
+
+    let expiry_labels: Vec<String> = INVITE_EXPIRY_OPTIONS
+        .iter()
+        .map(|(key, _)| get_local_text(key))
+        .collect();
+    let max_uses_labels: Vec<String> = INVITE_MAX_USES_OPTIONS
+        .iter()
+        .map(|max| max_uses_label(*max))
+        .collect();
+
+    cx.render(rsx!(
+        div {
+            class: "group-invites",
+            aria_label: "group-invites",
+            p {
+                class: "group-invites-label",
+                get_local_text("messages.invite-links")
+            },
+            div {
+                class: "group-invite-create",
+                Select {
+                    initial_value: expiry_labels[*expiry_idx.get()].clone(),
+                    options: expiry_labels.clone(),
+                    onselect: move |value: String| {
+                        if let Some(idx) = INVITE_EXPIRY_OPTIONS.iter().position(|(key, _)| get_local_text(key) == value) {
+                            expiry_idx.set(idx);
+                        }
+                    }
+                },
+                Select {
+                    initial_value: max_uses_labels[*max_uses_idx.get()].clone(),
+                    options: max_uses_labels.clone(),
+                    onselect: move |value: String| {
+                        if let Some(idx) = INVITE_MAX_USES_OPTIONS.iter().position(|max| max_uses_label(*max) == value) {
+                            max_uses_idx.set(idx);
+                        }
+                    }
+                },
+                Button {
+                    aria_label: "create-invite-button".into(),
+                    icon: Icon::Link,
+                    text: get_local_text("messages.create-invite"),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        ch.send(InviteCmd::Create {
+                            expires: INVITE_EXPIRY_OPTIONS[*expiry_idx.get()].1.map(Duration::hours),
+                            max_uses: INVITE_MAX_USES_OPTIONS[*max_uses_idx.get()],
+                        });
+                    }
+                }
+            },
+            invites.read().iter().map(|invite| {
+                let link = invite.link();
+                let code = invite.code.clone();
+                let uses = match invite.max_uses {
+                    Some(max) => format!("{}/{max}", invite.uses),
+                    None => invite.uses.to_string(),
+                };
+                rsx!(
+                    div {
+                        key: "{invite.code}",
+                        class: "group-invite",
+                        aria_label: "group-invite",
+                        p {
+                            class: "group-invite-link ellipsis-overflow",
+                            "{link}"
+                        },
+                        span {
+                            class: "group-invite-uses",
+                            get_local_text_with_args("messages.invite-uses", vec![("num", uses)])
+                        },
+                        Button {
+                            aria_label: "copy-invite-button".into(),
+                            icon: Icon::ClipboardDocument,
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| copy_link(link.clone()),
+                        },
+                        Button {
+                            aria_label: "revoke-invite-button".into(),
+                            icon: Icon::Trash,
+                            appearance: Appearance::Danger,
+                            onpress: move |_| ch.send(InviteCmd::Revoke(code.clone())),
+                        },
+                    }
+                )
+            })
         }
     ))
 }
@@ -1,19 +1,48 @@
-use std::collections::HashMap;
-
 use common::{
     icons::outline::Shape as Icon,
     icons::Icon as IconElement,
-    language::get_local_text,
-    state::{Chat, Identity, State},
+    language::{get_local_text, get_local_text_with_args},
+    state::{chats::GroupModeration, Action, Chat, Identity, State, ToastNotification},
+    warp_runner::{GroupRole, RayGunCmd, WarpCmd},
+    WARP_CMD_CH,
 };
 use dioxus::prelude::*;
+use futures::{channel::oneshot, StreamExt};
 
 use kit::{
     components::user_image::UserImage,
-    elements::input::{Input, Options},
+    elements::{
+        button::Button,
+        input::{Input, Options},
+        label::Label,
+        select::Select,
+        tooltip::{ArrowPosition, Tooltip},
+        Appearance,
+    },
 };
 use tracing::log;
+use uuid::Uuid;
 use warp::crypto::DID;
+
+use crate::layouts::chats::data::{alphabetical_sections, ParticipantIndex};
+
+// number of members rendered at first, and added by each "show more"
+const MEMBERS_PAGE_SIZE: usize = 50;
+
+enum ModerationCmd {
+    SetRole(DID, GroupRole),
+    SetMuted(DID, bool),
+}
+
+const ROLES: [GroupRole; 3] = [GroupRole::Member, GroupRole::Moderator, GroupRole::Admin];
+
+fn role_label(role: GroupRole) -> String {
+    match role {
+        GroupRole::Admin => get_local_text("messages.group-admin"),
+        GroupRole::Moderator => get_local_text("messages.group-moderator"),
+        GroupRole::Member => get_local_text("messages.group-member"),
+    }
+}
 #[derive(Props, PartialEq)]
 pub struct Props {
     #[props(!optional)]
@@ -27,9 +56,61 @@ pub fn GroupUsers(cx: Scope<Props>) -> Element {
     log::trace!("rendering group_users");
     let state = use_shared_state::<State>(cx)?;
     let friend_prefix = use_state(cx, String::new);
+    let visible_members = use_state(cx, || MEMBERS_PAGE_SIZE);
 
     let quickprofile_data = &cx.props.quickprofile_data;
 
+    // the warp runner checks the permissions. the state is only updated if the command succeeded.
+    let moderation_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<(Uuid, ModerationCmd)>| {
+        to_owned![state];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some((conv_id, cmd)) = rx.next().await {
+                let (tx, rx) = oneshot::channel::<Result<(), warp::error::Error>>();
+                let (warp_cmd, action) = match cmd {
+                    ModerationCmd::SetRole(member, role) => (
+                        RayGunCmd::SetGroupRole {
+                            conv_id,
+                            member: member.clone(),
+                            role,
+                            rsp: tx,
+                        },
+                        Action::SetGroupRole(conv_id, member, role),
+                    ),
+                    ModerationCmd::SetMuted(member, muted) => (
+                        RayGunCmd::SetMemberMuted {
+                            conv_id,
+                            member: member.clone(),
+                            muted,
+                            rsp: tx,
+                        },
+                        Action::SetMemberMuted(conv_id, member, muted),
+                    ),
+                };
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(warp_cmd)) {
+                    log::error!("failed to send warp command: {}", e);
+                    continue;
+                }
+
+                let res = rx.await.expect("command canceled");
+                match res {
+                    Ok(_) => state.write().mutate(action),
+                    Err(e) => {
+                        log::error!("failed to moderate group member: {e}");
+                        state.write().mutate(Action::AddToastNotification(
+                            ToastNotification::init(
+                                "".into(),
+                                get_local_text("messages.not-allowed"),
+                                None,
+                                2,
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    });
+
     let active_chat = match cx.props.active_chat.as_ref() {
         Some(r) => r,
         None => return cx.render(rsx!(div {})),
@@ -39,15 +120,13 @@ pub fn GroupUsers(cx: Scope<Props>) -> Element {
     }
 
     let participant_dids = Vec::from_iter(active_chat.participants.iter().cloned());
-    let group_participants = state.read().get_identities(&participant_dids);
-    let hash_map = HashMap::from_iter(
-        group_participants
-            .iter()
-            .map(|ident| (ident.did_key(), ident.clone())),
-    );
-    let _friends_in_group = State::get_friends_by_first_letter(hash_map);
+    let index = ParticipantIndex::new(state.read().get_identities(&participant_dids));
     let creator_id_vector = Vec::from_iter(active_chat.creator.iter().cloned());
     let creator_id = creator_id_vector.first().cloned()?;
+    let own_did = state.read().did_key();
+    let moderation = state.read().chats.moderation(&active_chat.id);
+    let my_role = moderation.role_of(Some(&creator_id), &own_did);
+    let conv_id = active_chat.id;
 
     let eval = use_eval(cx);
     use_effect(cx, (), |_| {
@@ -72,7 +151,6 @@ pub fn GroupUsers(cx: Scope<Props>) -> Element {
             div {
                 class: "search-input",
                 Input {
-                    // todo: filter friends on input
                     placeholder: get_local_text("uplink.search-placeholder"),
                     disabled: false,
                     aria_label: "friend-search-input".into(),
@@ -85,60 +163,93 @@ pub fn GroupUsers(cx: Scope<Props>) -> Element {
                     },
                     onchange: move |(v, _): (String, _)| {
                         friend_prefix.set(v);
+                        visible_members.set(MEMBERS_PAGE_SIZE);
                     },
                 }
             }
             render_friends {
-                group_participants: group_participants,
+                index: index,
                 name_prefix: friend_prefix.clone(),
+                visible: visible_members.clone(),
                 creator: creator_id,
+                moderation: moderation,
+                own_did: own_did,
+                my_role: my_role,
                 is_dev: state.read().configuration.developer.developer_mode,
                 context_data: quickprofile_data.clone(),
+                on_moderate: move |cmd| moderation_ch.send((conv_id, cmd)),
             }
         }
     ))
 }
 
-#[derive(PartialEq, Props)]
-pub struct FriendsProps {
-    group_participants: Vec<Identity>,
+#[derive(Props)]
+pub struct FriendsProps<'a> {
+    index: ParticipantIndex,
     name_prefix: UseState<String>,
+    visible: UseState<usize>,
     creator: DID,
+    moderation: GroupModeration,
+    own_did: DID,
+    my_role: GroupRole,
     is_dev: bool,
     context_data: UseRef<Option<(f64, f64, Identity, bool)>>,
+    on_moderate: EventHandler<'a, ModerationCmd>,
 }
 
-fn render_friends(cx: Scope<FriendsProps>) -> Element {
-    let name_prefix = cx.props.name_prefix.get();
-    let mut group_participants = cx.props.group_participants.clone();
-    // reduce group participants vector to just the name_prefix matched
-    group_participants.retain(|friend| {
-        friend
-            .username()
-            .to_ascii_lowercase()
-            .contains(&name_prefix.to_ascii_lowercase())
-    });
+fn render_friends<'a>(cx: Scope<'a, FriendsProps<'a>>) -> Element<'a> {
+    let matches = cx.props.index.search(cx.props.name_prefix.get());
+    let shown = matches.len().min(*cx.props.visible.get());
+    let remaining = matches.len() - shown;
+    let sections = alphabetical_sections(&matches[..shown]);
 
     cx.render(rsx!(
         div {
             class: "friend-list vertically-scrollable",
             aria_label: "friends-list",
-            if !group_participants.is_empty() {
+            if !sections.is_empty() {
                 rsx!(
-                    div {
-                        key: "friend-group",
-                        class: "friend-group",
-                        group_participants.iter().map(|_friend| {
-                            let friendid = _friend.did_key();
-                            let creator = cx.props.creator.clone();
-                            rsx!(render_friend {
-                                friend: _friend.clone(),
-                                is_creator: friendid == creator,
-                                is_dev: cx.props.is_dev,
-                                context_data: cx.props.context_data.clone(),
+                    sections.into_iter().map(|(letter, members)| {
+                        let group_letter = letter.to_string();
+                        rsx!(
+                            div {
+                                key: "friend-group-{group_letter}",
+                                class: "friend-group",
+                                Label {
+                                    text: group_letter.clone(),
+                                    aria_label: group_letter.clone(),
+                                },
+                                members.into_iter().map(|_friend| {
+                                    let friendid = _friend.did_key();
+                                    let creator = cx.props.creator.clone();
+                                    let is_creator = friendid == creator;
+                                    // nobody can change the role of the creator or moderate themselves
+                                    let can_moderate = !is_creator && friendid != cx.props.own_did;
+                                    rsx!(render_friend {
+                                        friend: _friend.clone(),
+                                        is_creator: is_creator,
+                                        role: cx.props.moderation.role_of(Some(&creator), &friendid),
+                                        is_muted: cx.props.moderation.muted.contains(&friendid),
+                                        can_assign_role: can_moderate && cx.props.my_role.can_manage_group(),
+                                        can_mute: can_moderate && cx.props.my_role.can_moderate(),
+                                        is_dev: cx.props.is_dev,
+                                        context_data: cx.props.context_data.clone(),
+                                        on_moderate: move |cmd| cx.props.on_moderate.call(cmd),
+                                    }
+                                )})
                             }
-                        )})
-                    }
+                        )
+                    }),
+                    (remaining > 0).then(|| rsx!(
+                        Button {
+                            aria_label: "show-more-members".into(),
+                            text: get_local_text_with_args("messages.show-more-members", vec![("num", remaining)]),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| {
+                                cx.props.visible.set(shown + MEMBERS_PAGE_SIZE);
+                            }
+                        }
+                    ))
                 )
             } else {
                 rsx!(
@@ -152,14 +263,20 @@ fn render_friends(cx: Scope<FriendsProps>) -> Element {
     ))
 }
 
-#[derive(PartialEq, Props)]
-pub struct FriendProps {
+#[derive(Props)]
+pub struct FriendProps<'a> {
     friend: Identity,
     is_creator: bool,
+    role: GroupRole,
+    is_muted: bool,
+    can_assign_role: bool,
+    can_mute: bool,
     is_dev: bool,
     context_data: UseRef<Option<(f64, f64, Identity, bool)>>,
+    on_moderate: EventHandler<'a, ModerationCmd>,
 }
-fn render_friend(cx: Scope<FriendProps>) -> Element {
+fn render_friend<'a>(cx: Scope<'a, FriendProps<'a>>) -> Element<'a> {
+    let role = cx.props.role;
     cx.render(rsx!(
         div {
             class: "friend-container",
@@ -171,7 +288,7 @@ fn render_friend(cx: Scope<FriendProps>) -> Element {
             UserImage {
                 platform: cx.props.friend.platform().into(),
                 status: cx.props.friend.identity_status().into(),
-                image: cx.props.friend.profile_picture(),
+                image: cx.props.friend.avatar(),
                 oncontextmenu: move |e: Event<MouseData>| {
                     cx.props
                         .context_data.set(Some((e.page_coordinates().x, e.page_coordinates().y, cx.props.friend.to_owned(), true)));
@@ -198,8 +315,51 @@ fn render_friend(cx: Scope<FriendProps>) -> Element {
                         }
                     }
                 )
-
+            } else if cx.props.can_assign_role {
+                rsx!(
+                    div {
+                        class: "group-role-select",
+                        aria_label: "group-role-select",
+                        Select {
+                            initial_value: role_label(role),
+                            options: ROLES.iter().map(|r| role_label(*r)).collect(),
+                            onselect: move |value: String| {
+                                if let Some(new_role) = ROLES.iter().find(|r| role_label(**r) == value) {
+                                    cx.props.on_moderate.call(ModerationCmd::SetRole(cx.props.friend.did_key(), *new_role));
+                                }
+                            }
+                        }
+                    }
+                )
+            } else if role != GroupRole::Member {
+                rsx!(
+                    div {
+                        class: "group-creator-container",
+                        aria_label: "group-role-badge",
+                        IconElement {
+                            icon: if role == GroupRole::Admin { Icon::ShieldCheck } else { Icon::Shield }
+                        }
+                        span {
+                            class: "group-creator-text",
+                            role_label(role)
+                        }
+                    }
+                )
             }
+            cx.props.can_mute.then(|| rsx!(
+                Button {
+                    aria_label: "group-mute-member-button".into(),
+                    icon: if cx.props.is_muted { Icon::SpeakerWave } else { Icon::NoSymbol },
+                    appearance: if cx.props.is_muted { Appearance::Danger } else { Appearance::Secondary },
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Right,
+                        text: if cx.props.is_muted { get_local_text("messages.unmute-member") } else { get_local_text("messages.mute-member") },
+                    })),
+                    onpress: move |_| {
+                        cx.props.on_moderate.call(ModerationCmd::SetMuted(cx.props.friend.did_key(), !cx.props.is_muted));
+                    }
+                }
+            ))
         }
     ))
 }
@@ -0,0 +1,82 @@
+use arboard::Clipboard;
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    state::{Action, State, ToastNotification},
+    utils::handoff::{Handoff, Position},
+};
+use dioxus::prelude::*;
+use kit::elements::{button::Button, Appearance};
+use qrcode::{render::svg, QrCode};
+use tracing::log;
+
+use crate::layouts::chats::data::ChatData;
+
+// a link to the open chat, scrolled to the message at the top of the view, to scan or paste on
+// another device of the account
+#[allow(non_snake_case)]
+pub fn HandoffLink(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let chat_data = use_shared_state::<ChatData>(cx)?;
+    // taken once, so it doesn't change while the user scans it
+    let link = use_state(cx, || {
+        let chat = &chat_data.read().active_chat;
+        Handoff {
+            conversation_id: chat.id(),
+            position: chat.messages.get_earliest_displayed().map(|m| Position {
+                message_id: m.message_id,
+                date: m.date,
+            }),
+        }
+        .to_string()
+    });
+    let qr_code = match QrCode::new(link.get().as_bytes()) {
+        Ok(code) => code
+            .render::<svg::Color>()
+            .min_dimensions(200, 200)
+            .quiet_zone(true)
+            .build(),
+        Err(e) => {
+            log::error!("failed to render the handoff link: {e}");
+            String::new()
+        }
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "handoff",
+            aria_label: "handoff",
+            p { get_local_text("messages.handoff-description") },
+            div {
+                class: "handoff-qr-code",
+                aria_label: "handoff-qr-code",
+                dangerous_inner_html: "{qr_code}",
+            },
+            code {
+                class: "handoff-link",
+                aria_label: "handoff-link",
+                "{link}"
+            },
+            Button {
+                text: get_local_text("uplink.copy-text"),
+                aria_label: "copy-handoff-link-button".into(),
+                icon: Icon::DocumentDuplicate,
+                appearance: Appearance::Secondary,
+                onpress: move |_| {
+                    let copied = Clipboard::new().and_then(|mut c| c.set_text(link.get().clone()));
+                    match copied {
+                        Ok(_) => state
+                            .write()
+                            .mutate(Action::AddToastNotification(ToastNotification::init(
+                                "".into(),
+                                get_local_text("messages.handoff-copied"),
+                                None,
+                                2,
+                            ))),
+                        Err(e) => log::warn!("Unable to set text to clipboard: {e}"),
+                    }
+                },
+            },
+        }
+    ))
+}
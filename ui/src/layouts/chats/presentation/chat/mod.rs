@@ -1,10 +1,13 @@
+mod appearance;
 mod controls;
 pub mod coroutines;
 mod edit_group;
 mod group_settings;
 mod group_users;
+mod handoff;
 mod pinned_messages;
 mod topbar;
+mod widgets;
 
 use dioxus::prelude::*;
 
@@ -24,6 +27,7 @@ use crate::{
         },
         scripts::{DISABLE_RELOAD, SHOW_CONTEXT, USER_TAG_SCRIPT},
     },
+    utils::profiling,
 };
 
 use common::state::{ui, Action, Identity, State};
@@ -37,6 +41,7 @@ use warp::crypto::DID;
 #[allow(non_snake_case)]
 pub fn Compose(cx: Scope) -> Element {
     log::trace!("rendering compose");
+    profiling::count_render("Compose");
     use_shared_state_provider(cx, ChatData::default);
     use_shared_state_provider(cx, ScrollBtn::new);
     use_shared_state_provider(cx, MessagesToSend::default);
@@ -107,8 +112,18 @@ pub fn Compose(cx: Scope) -> Element {
     let creator = chat_data.read().active_chat.creator();
 
     let chat_id = chat_data.read().active_chat.id();
+    let appearance_style = appearance::appearance_style(&state.read(), chat_id);
     let user_did: DID = state.read().did_key();
-    let is_owner = creator.map(|id| id == user_did).unwrap_or_default();
+    // group admins can do everything the creator can
+    let is_owner = creator
+        .as_ref()
+        .map(|id| id == &user_did)
+        .unwrap_or_default()
+        || state
+            .read()
+            .chats
+            .role_of(&chat_id, &user_did)
+            .can_manage_group();
 
     if init.value().is_some() {
         if let Some(chat) = state.read().get_active_chat() {
@@ -126,9 +141,20 @@ pub fn Compose(cx: Scope) -> Element {
         }
     }
 
+    // there is no room for the widgets next to the messages in the minimal view
+    let show_panel = !state.read().ui.is_minimal_view()
+        && init.value().is_some()
+        && state.read().chats().panel(&chat_id).open;
+
     cx.render(rsx!(
         div {
             id: "compose",
+            class: format_args!(
+                "{} {}",
+                if show_panel { "with-panel" } else { "" },
+                if appearance_style.is_empty() { "" } else { "custom-appearance" }
+            ),
+            style: "{appearance_style}",
             Topbar {
                 with_back_button: state.read().ui.is_minimal_view() && state.read().ui.sidebar_hidden,
                 onback: move |_| {
@@ -227,6 +253,7 @@ pub fn Compose(cx: Scope) -> Element {
             ignore_focus: should_ignore_focus,
             is_owner: is_owner,
         },
+        show_panel.then(|| rsx!(widgets::WidgetsPanel {})),
         super::quick_profile::QuickProfileContext{
             id: quick_profile_uuid,
             update_script: update_script,
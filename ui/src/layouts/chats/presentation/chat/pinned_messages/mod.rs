@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use common::{
     language::get_local_text,
     state::{Identity, State},
-    warp_runner::{thumbnail_to_base64, RayGunCmd, WarpCmd},
+    warp_runner::{thumbnail_to_base64, CancelToken, RayGunCmd, WarpCmd},
     WARP_CMD_CH,
 };
 use dioxus::prelude::*;
@@ -91,6 +91,7 @@ pub fn PinnedMessages(cx: Scope<'_, Props>) -> Element<'_> {
                             },
                             msg_time: Some(message_date),
                             limit: data::DEFAULT_MESSAGES_TO_TAKE,
+                            anchor: None,
                         };
                         let behavior = data::ChatBehavior {
                             view_init,
@@ -99,11 +100,15 @@ pub fn PinnedMessages(cx: Scope<'_, Props>) -> Element<'_> {
                             on_scroll_top: data::ScrollBehavior::FetchMore,
                             ..Default::default()
                         };
+                        // stops the fetch if the chat is closed meanwhile
+                        let token = CancelToken::new();
+                        let _guard = token.drop_guard();
                         let r = fetch_window(
                             conversation_id,
                             behavior,
                             message_date,
                             data::DEFAULT_MESSAGES_TO_TAKE / 2,
+                            &token,
                         )
                         .await;
 
@@ -214,7 +219,7 @@ pub fn PinnedMessage<'a>(cx: Scope<'a, PinnedMessageProp<'a>>) -> Element<'a> {
             aria_label: "pinned-message-wrap",
             cx.props.sender.as_ref().map(|sender| {
                 rsx!(UserImage {
-                    image: sender.profile_picture(),
+                    image: sender.avatar(),
                     platform: sender.platform().into(),
                 })
             }),
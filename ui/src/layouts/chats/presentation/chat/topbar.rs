@@ -9,13 +9,14 @@ use kit::{
     elements::{
         button::Button,
         input::{Input, Options},
+        select::Select,
         Appearance,
     },
 };
 
 use common::{
     icons::outline::Shape as Icon,
-    language::get_local_text_with_args,
+    language::{get_available_languages, get_local_text_with_args},
     state::Action,
     warp_runner::{RayGunCmd, WarpCmd},
 };
@@ -41,6 +42,9 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
     let state = use_shared_state::<State>(cx)?;
 
     let chat_data = use_shared_state::<ChatData>(cx)?;
+    let show_edit_title = use_state(cx, || false);
+    let title_value = use_ref(cx, String::new);
+    let topic_value = use_ref(cx, String::new);
 
     let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<EditGroupCmd>| async move {
         let warp_cmd_tx = WARP_CMD_CH.tx.clone();
@@ -96,6 +100,12 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
         .active_chat
         .conversation_name()
         .unwrap_or(data.active_chat.other_participants_names());
+    // the local title replaces the conversation name, but renaming a group still changes the name everyone sees
+    let display_title = data
+        .active_chat
+        .title()
+        .unwrap_or(data.active_chat.other_participants_names());
+    let topic = data.active_chat.topic();
     let show_group_list = cx
         .props
         .show_group_users
@@ -122,6 +132,31 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
 
     let conv_id = data.active_chat.id();
     let subtext = data.active_chat.subtext();
+    let status_emoji = data.active_chat.status_emoji();
+
+    // choosing the auto detect option removes the language chosen by the user
+    let auto_language = match state
+        .read()
+        .chats()
+        .all
+        .get(&conv_id)
+        .and_then(|chat| chat.detect_language())
+    {
+        Some(detected) => get_local_text_with_args(
+            "messages.language-auto-detected",
+            vec![("language", detected)],
+        ),
+        None => get_local_text("messages.language-auto"),
+    };
+    let language_label = state
+        .read()
+        .chats()
+        .languages
+        .get(&conv_id)
+        .cloned()
+        .unwrap_or_else(|| auto_language.clone());
+    let mut language_options = vec![auto_language];
+    language_options.extend(get_available_languages());
 
     let show_group_settings = || match chat_data.read().active_chat.conversation_settings() {
         ConversationSettings::Group(_) => cx.props.is_owner,
@@ -165,6 +200,7 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
                             aria_label: "rename-group-context-option".into(),
                             text: "Rename".into(),
                             onpress: move |_| {
+                                show_edit_title.set(false);
                                 cx.props.show_rename_group.set(true);
                             }
                         }
@@ -192,6 +228,18 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
                     )}
                     // TODO: `Delete` item
                 )}
+                ContextItem {
+                    icon: Icon::PencilSquare,
+                    aria_label: "edit-title-context-option".into(),
+                    text: get_local_text("messages.edit-title"),
+                    onpress: move |_| {
+                        let active_chat = &chat_data.read().active_chat;
+                        *title_value.write() = active_chat.title().unwrap_or_default();
+                        *topic_value.write() = active_chat.topic().unwrap_or_default();
+                        cx.props.show_rename_group.set(false);
+                        show_edit_title.set(true);
+                    }
+                },
             )),
             div {
                 class: "user-info",
@@ -204,7 +252,68 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
                         cx.props.show_rename_group.set(false);
                     }
                 },
-                if *cx.props.show_rename_group.get() {rsx! (
+                if *show_edit_title.get() {rsx! (
+                    div {
+                        id: "edit-chat-title",
+                        class: "edit-chat-title",
+                        onclick: move |evt| evt.stop_propagation(),
+                        Input {
+                            placeholder: get_local_text("messages.chat-title"),
+                            default_text: title_value.read().clone(),
+                            aria_label: "chat-title-input".into(),
+                            options: Options {
+                                with_clear_btn: true,
+                                ..get_input_options()
+                            },
+                            onchange: move |(v, _): (String, bool)| {
+                                *title_value.write_silent() = v;
+                            },
+                            onreturn: move |_| {
+                                save_title_and_topic(&state, conv_id, title_value, topic_value);
+                                show_edit_title.set(false);
+                            },
+                        },
+                        Input {
+                            placeholder: get_local_text("messages.chat-topic"),
+                            default_text: topic_value.read().clone(),
+                            aria_label: "chat-topic-input".into(),
+                            options: Options {
+                                with_clear_btn: true,
+                                ..get_input_options()
+                            },
+                            onchange: move |(v, _): (String, bool)| {
+                                *topic_value.write_silent() = v;
+                            },
+                            onreturn: move |_| {
+                                save_title_and_topic(&state, conv_id, title_value, topic_value);
+                                show_edit_title.set(false);
+                            },
+                        },
+                        Select {
+                            initial_value: language_label.clone(),
+                            options: language_options.clone(),
+                            onselect: move |value: String| {
+                                let language = get_available_languages().into_iter().find(|l| *l == value);
+                                state.write().mutate(Action::SetChatLanguage(conv_id, language));
+                            }
+                        },
+                        Button {
+                            icon: Icon::Check,
+                            appearance: Appearance::Primary,
+                            onpress: move |_| {
+                                save_title_and_topic(&state, conv_id, title_value, topic_value);
+                                show_edit_title.set(false);
+                            },
+                            aria_label: "save-chat-title".into(),
+                        },
+                        Button {
+                            icon: Icon::XMark,
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| show_edit_title.set(false),
+                            aria_label: "close-edit-chat-title".into(),
+                        }
+                    })
+                } else if *cx.props.show_rename_group.get() {rsx! (
                     div {
                         id: "edit-group-name",
                         class: "edit-group-name",
@@ -237,8 +346,22 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
                     p {
                         aria_label: "user-info-username",
                         class: "username",
-                        "{conversation_title}"
+                        "{display_title}",
+                        status_emoji.map(|emoji| rsx!(
+                            span {
+                                class: "status-emoji",
+                                aria_label: "user-info-status-emoji",
+                                "{emoji}"
+                            }
+                        )),
                     },
+                    topic.map(|topic| rsx!(
+                        p {
+                            aria_label: "user-info-topic",
+                            class: "topic",
+                            "{topic}"
+                        }
+                    )),
                     p {
                         aria_label: "user-info-status",
                         class: "status",
@@ -257,3 +380,14 @@ pub fn get_topbar_children(cx: Scope<ChatProps>) -> Element {
         }
     ))
 }
+
+fn save_title_and_topic(
+    state: &UseSharedState<State>,
+    conv_id: Uuid,
+    title: &UseRef<String>,
+    topic: &UseRef<String>,
+) {
+    let mut state = state.write();
+    state.mutate(Action::SetChatTitle(conv_id, Some(title.read().clone())));
+    state.mutate(Action::SetChatTopic(conv_id, Some(topic.read().clone())));
+}
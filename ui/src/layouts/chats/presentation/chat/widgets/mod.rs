@@ -0,0 +1,234 @@
+use common::{
+    icons::outline::Shape as Icon,
+    icons::Icon as IconElement,
+    language::get_local_text,
+    state::{chats::ConversationPanel, Action, State},
+    warp_runner::thumbnail_to_base64,
+};
+use dioxus::prelude::*;
+use kit::components::user_image::UserImage;
+use tracing::log;
+use uuid::Uuid;
+
+use super::pinned_messages::PinnedMessages;
+use crate::{layouts::chats::data::ChatData, utils::extension_api::ExtensionView};
+
+// the right hand panel of a conversation. extensions with the `ConversationPanel` location are
+// rendered as additional widgets below the built in ones.
+#[allow(non_snake_case)]
+pub fn WidgetsPanel(cx: Scope) -> Element {
+    log::trace!("rendering conversation widgets");
+    let state = use_shared_state::<State>(cx)?;
+    let chat_data = use_shared_state::<ChatData>(cx)?;
+    // PinnedMessages closes itself after jumping to a message, which the panel ignores
+    let show_pinned = use_state(cx, || true);
+
+    let chat_id = chat_data.read().active_chat.id();
+    let panel = state.read().chats().panel(&chat_id);
+    let pinned_count = chat_data.read().active_chat.pinned_messages().len();
+    let members = chat_data.read().active_chat.other_participants();
+    let messages = chat_data.read().active_chat.messages();
+
+    // only the loaded messages are searched, scrolling up finds older files and tasks
+    let media: Vec<_> = messages
+        .iter()
+        .flat_map(|msg| msg.inner.attachments())
+        .collect();
+    let tasks: Vec<_> = messages
+        .iter()
+        .flat_map(|msg| msg.inner.lines())
+        .filter_map(|line| parse_task(&line))
+        .collect();
+    let participants: Vec<_> = std::iter::once(chat_data.read().active_chat.my_id())
+        .chain(members)
+        .collect();
+
+    let extensions = &state.read().ui.extensions;
+    let ext_widgets = extensions
+        .values()
+        .filter(|(is_enabled, ext)| {
+            ext.details().location == extensions::Location::ConversationPanel && *is_enabled
+        })
+        .map(|(_, ext)| {
+            let meta = ext.details().meta;
+            let name = meta.name;
+            rsx!(Widget {
+                key: "{name}",
+                chat_id: chat_id,
+                id: meta.name.to_string(),
+                title: meta.pretty_name.to_string(),
+                icon: Icon::Sparkles,
+                expanded: panel.is_expanded(meta.name),
+                ExtensionView {
+                    name: name.to_string(),
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    cx.render(rsx!(
+        aside {
+            class: "conversation-panel",
+            aria_label: "conversation-panel",
+            Widget {
+                chat_id: chat_id,
+                id: ConversationPanel::PINNED.to_string(),
+                title: get_local_text("messages.pin-view"),
+                icon: Icon::Pin,
+                count: pinned_count,
+                expanded: panel.is_expanded(ConversationPanel::PINNED),
+                chat_data.read().active_chat.is_initialized.then(|| rsx!(
+                    PinnedMessages { show_pinned: show_pinned.clone() }
+                ))
+            },
+            Widget {
+                chat_id: chat_id,
+                id: ConversationPanel::MEDIA.to_string(),
+                title: get_local_text("conversation-panel.media"),
+                icon: Icon::Photo,
+                count: media.len(),
+                expanded: panel.is_expanded(ConversationPanel::MEDIA),
+                if media.is_empty() {
+                    rsx!(p {
+                        class: "widget-empty",
+                        get_local_text("conversation-panel.media-none")
+                    })
+                } else {
+                    rsx!(div {
+                        class: "widget-media",
+                        media.iter().map(|file| {
+                            let key = file.id();
+                            let thumbnail = thumbnail_to_base64(file);
+                            let name = file.name();
+                            rsx!(div {
+                                key: "{key}",
+                                class: "widget-media-item",
+                                title: "{name}",
+                                if thumbnail.is_empty() {
+                                    rsx!(
+                                        IconElement { icon: Icon::DocumentText },
+                                        span { class: "ellipsis-overflow", "{name}" }
+                                    )
+                                } else {
+                                    rsx!(img { src: "{thumbnail}", alt: "{name}" })
+                                }
+                            })
+                        })
+                    })
+                }
+            },
+            Widget {
+                chat_id: chat_id,
+                id: ConversationPanel::TASKS.to_string(),
+                title: get_local_text("conversation-panel.tasks"),
+                icon: Icon::CheckCircle,
+                count: tasks.iter().filter(|(done, _)| !done).count(),
+                expanded: panel.is_expanded(ConversationPanel::TASKS),
+                if tasks.is_empty() {
+                    rsx!(p {
+                        class: "widget-empty",
+                        get_local_text("conversation-panel.tasks-none")
+                    })
+                } else {
+                    rsx!(ul {
+                        class: "widget-tasks",
+                        tasks.iter().map(|(done, task)| rsx!(li {
+                            class: if *done { "done" } else { "" },
+                            IconElement { icon: if *done { Icon::CheckCircle } else { Icon::Circle } },
+                            span { "{task}" }
+                        }))
+                    })
+                }
+            },
+            Widget {
+                chat_id: chat_id,
+                id: ConversationPanel::MEMBERS.to_string(),
+                title: get_local_text("conversation-panel.members"),
+                icon: Icon::Users,
+                count: participants.len(),
+                expanded: panel.is_expanded(ConversationPanel::MEMBERS),
+                div {
+                    class: "widget-members",
+                    participants.iter().map(|member| {
+                        let key = member.did_key();
+                        rsx!(div {
+                            key: "{key}",
+                            class: "widget-member",
+                            UserImage {
+                                platform: member.platform().into(),
+                                status: member.identity_status().into(),
+                                image: member.avatar(),
+                            },
+                            span {
+                                class: "ellipsis-overflow",
+                                member.username()
+                            }
+                        })
+                    })
+                }
+            },
+            ext_widgets.into_iter()
+        }
+    ))
+}
+
+#[derive(Props)]
+struct WidgetProps<'a> {
+    chat_id: Uuid,
+    id: String,
+    title: String,
+    icon: Icon,
+    #[props(optional)]
+    count: Option<usize>,
+    expanded: bool,
+    children: Element<'a>,
+}
+
+#[allow(non_snake_case)]
+fn Widget<'a>(cx: Scope<'a, WidgetProps<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let id = &cx.props.id;
+
+    cx.render(rsx!(
+        section {
+            class: "conversation-widget",
+            aria_label: "widget-{id}",
+            button {
+                class: "widget-header",
+                aria_expanded: "{cx.props.expanded}",
+                onclick: move |_| {
+                    state.write().mutate(Action::ToggleConversationWidget(cx.props.chat_id, cx.props.id.clone()));
+                },
+                IconElement { icon: cx.props.icon },
+                span {
+                    class: "widget-title",
+                    "{cx.props.title}"
+                },
+                cx.props.count.filter(|count| *count > 0).map(|count| rsx!(
+                    span { class: "widget-count", "{count}" }
+                )),
+                IconElement {
+                    icon: if cx.props.expanded { Icon::ChevronDown } else { Icon::ChevronRight },
+                },
+            },
+            cx.props.expanded.then(|| rsx!(
+                div {
+                    class: "widget-body",
+                    &cx.props.children
+                }
+            ))
+        }
+    ))
+}
+
+// markdown task list items, "- [ ] todo" and "- [x] done"
+fn parse_task(line: &str) -> Option<(bool, String)> {
+    let item = line.trim_start().strip_prefix("- [")?;
+    let done = match item.get(..2)? {
+        " ]" => false,
+        "x]" | "X]" => true,
+        _ => return None,
+    };
+    let task = item[2..].trim();
+    (!task.is_empty()).then(|| (done, task.to_string()))
+}
@@ -1,14 +1,17 @@
 use std::time::{Duration, Instant};
 
 use common::{
-    state::{Action, State},
-    warp_runner::{RayGunCmd, WarpCmd},
+    state::{
+        utils::{mention_to_did_key, parse_mentions},
+        Action, State,
+    },
+    warp_runner::{CancelToken, RayGunCmd, WarpCmd},
     STATIC_ARGS, WARP_CMD_CH,
 };
 use dioxus::prelude::*;
 use futures::{channel::oneshot, StreamExt};
 use uuid::Uuid;
-use warp::raygun;
+use warp::raygun::{self, AttachmentEventStream, Location};
 
 use crate::{
     layouts::chats::data::{self, ChatProps, MsgChInput, TypingInfo, DEFAULT_MESSAGES_TO_TAKE},
@@ -22,53 +25,18 @@ pub fn get_msg_ch(cx: &ScopeState, state: &UseSharedState<State>) -> Coroutine<M
     use_coroutine(cx, |mut rx: UnboundedReceiver<MsgChInput>| {
         to_owned![state, upload_streams];
         async move {
-            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
-            while let Some(MsgChInput {
-                msg,
-                conv_id,
-                replying_to,
-            }) = rx.next().await
-            {
-                let (tx, rx) = oneshot::channel();
+            while let Some(input) = rx.next().await {
+                let conv_id = input.conv_id;
                 let attachments = state
                     .read()
                     .get_active_chat()
                     .map(|f| f.files_attached_to_send)
                     .unwrap_or_default();
-                let cmd = match replying_to {
-                    Some(reply_to) => RayGunCmd::Reply {
-                        conv_id,
-                        reply_to,
-                        msg: msg.clone(),
-                        attachments,
-                        rsp: tx,
-                    },
-                    None => RayGunCmd::SendMessage {
-                        conv_id,
-                        msg: msg.clone(),
-                        attachments,
-                        rsp: tx,
-                    },
-                };
                 state
                     .write_silent()
                     .mutate(Action::ClearChatAttachments(conv_id));
-                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd)) {
-                    log::error!("failed to send warp command: {}", e);
-                    continue;
-                }
-
-                let rsp = rx.await.expect("command canceled");
-                match rsp {
-                    Ok((id, stream)) => {
-                        state.write().increment_outgoing_messages(id, msg.clone());
-                        if let Some(stream) = stream {
-                            upload_streams.write().append((conv_id, id, stream));
-                        }
-                    }
-                    Err(e) => {
-                        log::error!("failed to send message: {}", e);
-                    }
+                if let Some((id, stream)) = send_message(&state, input, attachments).await {
+                    upload_streams.write().append((conv_id, id, stream));
                 }
             }
         }
@@ -76,6 +44,98 @@ pub fn get_msg_ch(cx: &ScopeState, state: &UseSharedState<State>) -> Coroutine<M
     .clone()
 }
 
+/// Sends `text`, typed outside of the chatbar like in the reply field of a notification, the way
+/// the chatbar would
+pub async fn send_text(state: &UseSharedState<State>, conv_id: Uuid, text: &str) {
+    let Some(msg) = message_lines(&state.read(), conv_id, text) else {
+        return;
+    };
+    if !state.read().can_use_chat(&conv_id) {
+        log::warn!("not sending a message to a chat which can't be used");
+        return;
+    }
+    let input = MsgChInput {
+        msg,
+        conv_id,
+        replying_to: None,
+    };
+    send_message(state, input, vec![]).await;
+}
+
+// the lines of the message, with the mentions of the participants replaced the way the chatbar
+// does. None if there is nothing to send
+fn message_lines(state: &State, conv_id: Uuid, text: &str) -> Option<Vec<String>> {
+    let participants: Vec<_> = state
+        .get_chat_by_id(conv_id)?
+        .participants
+        .iter()
+        .filter_map(|did| state.get_identity(did))
+        .collect();
+    let (text, _) = parse_mentions(
+        text,
+        &participants,
+        &state.did_key(),
+        true,
+        mention_to_did_key,
+    );
+    let msg: Vec<String> = text.lines().map(|x| x.trim_end().to_string()).collect();
+    msg.iter()
+        .any(|line| !line.trim().is_empty())
+        .then_some(msg)
+}
+
+/// Sends a message like the chatbar does. If it fails, most likely because the node is offline,
+/// it is queued in the outbox. Returns the stream of the attachments
+pub async fn send_message(
+    state: &UseSharedState<State>,
+    input: MsgChInput,
+    attachments: Vec<Location>,
+) -> Option<(Uuid, AttachmentEventStream)> {
+    let MsgChInput {
+        msg,
+        conv_id,
+        replying_to,
+    } = input;
+    let (tx, rx) = oneshot::channel();
+    let cmd = match replying_to {
+        Some(reply_to) => RayGunCmd::Reply {
+            conv_id,
+            reply_to,
+            msg: msg.clone(),
+            attachments: attachments.clone(),
+            rsp: tx,
+        },
+        None => RayGunCmd::SendMessage {
+            conv_id,
+            msg: msg.clone(),
+            attachments: attachments.clone(),
+            rsp: tx,
+        },
+    };
+    if let Err(e) = WARP_CMD_CH.tx.send(WarpCmd::RayGun(cmd)) {
+        log::error!("failed to send warp command: {}", e);
+        return None;
+    }
+
+    let rsp = rx.await.expect("command canceled");
+    match rsp {
+        Ok((id, stream)) => {
+            state
+                .write()
+                .increment_outgoing_messages_for(conv_id, id, msg);
+            stream.map(|stream| (id, stream))
+        }
+        Err(e) => {
+            // most likely the node is offline. the message is sent again later
+            log::warn!("failed to send message, adding it to the outbox: {}", e);
+            state
+                .write()
+                .mutate(Action::QueueMessage(conv_id, msg, attachments, replying_to));
+            None
+        }
+    }
+}
+
 pub fn get_scroll_ch(
     cx: &Scoped<'_, ChatProps>,
     chat_data: &UseSharedState<data::ChatData>,
@@ -85,9 +145,13 @@ pub fn get_scroll_ch(
         to_owned![chat_data, state];
         async move {
             while let Some(conv_id) = rx.next().await {
+                // stops the fetch if the chat is closed meanwhile
+                let token = CancelToken::new();
+                let _guard = token.drop_guard();
                 match crate::layouts::chats::presentation::chat::coroutines::fetch_most_recent(
                     conv_id,
                     DEFAULT_MESSAGES_TO_TAKE,
+                    &token,
                 )
                 .await
                 {
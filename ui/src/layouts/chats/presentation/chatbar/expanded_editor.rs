@@ -0,0 +1,123 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::{get_local_text, get_local_text_with_args},
+    state::{settings::GlobalShortcut, Action, State},
+};
+use dioxus::prelude::*;
+use dioxus_html::input_data::keyboard_types::Code;
+use kit::{
+    components::message::format_text,
+    elements::{
+        button::Button,
+        tooltip::{ArrowPosition, Tooltip},
+        Appearance,
+    },
+};
+use uuid::Uuid;
+
+use super::MAX_CHARS_LIMIT;
+
+#[derive(Props)]
+pub struct Props<'a> {
+    chat_id: Uuid,
+    // language of the conversation, for the spellchecker of the textarea
+    lang: String,
+    onsend: EventHandler<'a, ()>,
+    onclose: EventHandler<'a, ()>,
+}
+
+/// A large editor for the draft of the chatbar, with a preview of how the message will look. The
+/// draft and the attachments are the ones of the chatbar, so nothing is lost switching between them
+#[allow(non_snake_case)]
+pub fn ExpandedEditor<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let chat_id = cx.props.chat_id;
+    let text = use_state(cx, || {
+        state
+            .read()
+            .get_chat_by_id(chat_id)
+            .and_then(|chat| chat.draft)
+            .unwrap_or_default()
+    });
+
+    // sending is a keybind, see `shortcut_handlers::chat`
+    let send_keys = state
+        .read()
+        .settings
+        .keybinds
+        .iter()
+        .find(|(shortcut, _)| *shortcut == GlobalShortcut::SendMessage)
+        .map(|(_, keys)| keys.get_keys_and_modifiers_as_string().join(" + "))
+        .unwrap_or_default();
+
+    let preview = format_text(
+        text.get(),
+        state.read().ui.should_transform_markdown_text(),
+        state.read().ui.should_transform_ascii_emojis(),
+        Some((&state.read(), &chat_id, false)),
+    );
+
+    cx.render(rsx!(
+        div {
+            class: "expanded-editor",
+            aria_label: "expanded-editor",
+            div {
+                class: "expanded-editor-header",
+                span {
+                    get_local_text_with_args("messages.expanded-editor-hint", vec![("keys", send_keys)])
+                },
+                Button {
+                    icon: Icon::ArrowsPointingIn,
+                    aria_label: "collapse-editor-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| cx.props.onclose.call(()),
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Bottom,
+                        text: get_local_text("messages.collapse-editor"),
+                    })),
+                },
+                Button {
+                    icon: Icon::ChevronDoubleRight,
+                    aria_label: "expanded-editor-send-button".into(),
+                    disabled: text.trim().is_empty(),
+                    onpress: move |_| cx.props.onsend.call(()),
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Bottom,
+                        text: get_local_text("uplink.send"),
+                    })),
+                },
+            },
+            div {
+                class: "expanded-editor-panes",
+                textarea {
+                    class: "expanded-editor-input",
+                    aria_label: "expanded-editor-input",
+                    lang: "{cx.props.lang}",
+                    dir: "auto",
+                    autofocus: true,
+                    maxlength: "{MAX_CHARS_LIMIT}",
+                    placeholder: get_local_text("messages.say-something-placeholder"),
+                    value: "{text}",
+                    oninput: move |e| {
+                        // the preview is the only thing to render again while typing
+                        state
+                            .write_silent()
+                            .mutate(Action::SetChatDraft(chat_id, e.value.clone()));
+                        text.set(e.value.clone());
+                    },
+                    onkeydown: move |e| {
+                        if e.code() == Code::Escape {
+                            cx.props.onclose.call(());
+                        }
+                    },
+                },
+                div {
+                    class: "expanded-editor-preview",
+                    aria_label: "expanded-editor-preview",
+                    dir: "auto",
+                    dangerous_inner_html: "{preview}",
+                }
+            }
+        }
+    ))
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    state::{Action, State, ToastNotification},
+    warp_runner::{RayGunCmd, WarpCmd},
+    STATIC_ARGS, WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use futures::{channel::oneshot, StreamExt};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    Appearance,
+};
+use tracing::log;
+use uuid::Uuid;
+use warp::raygun::Location;
+
+use crate::utils::gifs::{self, Gif};
+
+// number of results requested from the provider
+const GIF_SEARCH_LIMIT: usize = 24;
+// the search waits this long after the last keystroke
+const GIF_SEARCH_DELAY: Duration = Duration::from_millis(300);
+
+/// Sends the GIFs picked in the `GifPicker`. Lives in the chatbar so that sending continues after the
+/// picker is closed. A GIF is either sent right away as a link, or downloaded and added to the
+/// attachments of the chat.
+pub fn use_gif_coroutine(cx: &ScopeState) -> Coroutine<(Uuid, Gif)> {
+    let state = use_shared_state::<State>(cx).unwrap();
+    use_coroutine(cx, |mut rx: UnboundedReceiver<(Uuid, Gif)>| {
+        to_owned![state];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some((chat_id, gif)) = rx.next().await {
+                if state.read().configuration.gifs.send_as_attachment {
+                    match gifs::download(&gif, &STATIC_ARGS.temp_files).await {
+                        Ok(path) => {
+                            let mut attachments = state
+                                .read()
+                                .get_chat_by_id(chat_id)
+                                .map(|chat| chat.files_attached_to_send)
+                                .unwrap_or_default();
+                            attachments.push(Location::Disk { path });
+                            state
+                                .write()
+                                .mutate(Action::SetChatAttachments(chat_id, attachments));
+                        }
+                        Err(e) => {
+                            log::error!("failed to download gif: {e}");
+                            state.write().mutate(Action::AddToastNotification(
+                                ToastNotification::init(
+                                    "".into(),
+                                    get_local_text("messages.gif-download-failed"),
+                                    None,
+                                    3,
+                                ),
+                            ));
+                        }
+                    }
+                    continue;
+                }
+
+                let msg = vec![gif.url.clone()];
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::SendMessage {
+                    conv_id: chat_id,
+                    msg: msg.clone(),
+                    attachments: vec![],
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send warp command: {}", e);
+                    continue;
+                }
+                match rx.await.expect("command canceled") {
+                    Ok((id, _)) => state
+                        .write()
+                        .increment_outgoing_messages_for(chat_id, id, msg),
+                    Err(e) => log::error!("failed to send gif: {}", e),
+                }
+            }
+        }
+    })
+    .clone()
+}
+
+#[derive(Props)]
+pub struct Props<'a> {
+    chat_id: Uuid,
+    // the search the picker opens with
+    #[props(default = "".to_owned())]
+    query: String,
+    onclose: EventHandler<'a, ()>,
+}
+
+#[allow(non_snake_case)]
+pub fn GifPicker<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let query = use_state(cx, || cx.props.query.clone());
+    let chat_id = cx.props.chat_id;
+
+    let results = use_future(cx, query.get(), |query| {
+        let config = state.read().configuration.gifs.clone();
+        async move {
+            if query.trim().is_empty() {
+                return Ok(vec![]);
+            }
+            tokio::time::sleep(GIF_SEARCH_DELAY).await;
+            match gifs::get_provider(&config) {
+                Some(provider) => gifs::search(provider.as_ref(), &query, GIF_SEARCH_LIMIT).await,
+                None => Ok(vec![]),
+            }
+        }
+    });
+
+    let ch = use_coroutine_handle::<(Uuid, Gif)>(cx)?;
+
+    cx.render(rsx!(
+        div {
+            class: "gif-picker",
+            aria_label: "gif-picker",
+            div {
+                class: "gif-picker-header",
+                Input {
+                    placeholder: get_local_text("messages.search-gifs"),
+                    aria_label: "gif-search-input".into(),
+                    focus: true,
+                    default_text: cx.props.query.clone(),
+                    options: Options {
+                        with_clear_btn: true,
+                        clear_on_submit: false,
+                        react_to_esc_key: true,
+                        ..Default::default()
+                    },
+                    onchange: move |(v, _): (String, bool)| query.set(v),
+                },
+                Button {
+                    icon: Icon::XMark,
+                    appearance: Appearance::Secondary,
+                    aria_label: "close-gif-picker".into(),
+                    onpress: move |_| cx.props.onclose.call(()),
+                }
+            },
+            div {
+                class: "gif-picker-results",
+                match results.value() {
+                    Some(Ok(gifs)) if gifs.is_empty() && !query.get().trim().is_empty() => rsx!(
+                        p { class: "gif-picker-empty", get_local_text("messages.no-gifs") }
+                    ),
+                    Some(Ok(gifs)) => rsx!(
+                        gifs.iter().cloned().map(|gif| {
+                            let preview = gif.preview_url.clone();
+                            let title = gif.title.clone();
+                            rsx!(img {
+                                key: "{gif.id}",
+                                class: "gif-picker-item",
+                                src: "{preview}",
+                                alt: "{title}",
+                                title: "{title}",
+                                onclick: move |_| {
+                                    ch.send((chat_id, gif.clone()));
+                                    cx.props.onclose.call(());
+                                }
+                            })
+                        })
+                    ),
+                    Some(Err(e)) => {
+                        log::error!("gif search failed: {e}");
+                        rsx!(p { class: "gif-picker-empty", get_local_text("messages.gif-search-failed") })
+                    }
+                    None => rsx!(""),
+                }
+            }
+        }
+    ))
+}
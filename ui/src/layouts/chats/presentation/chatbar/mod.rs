@@ -1,14 +1,20 @@
 pub mod coroutines;
+mod expanded_editor;
+mod gif_picker;
+mod spelling_menu;
 
 use std::{path::PathBuf, time::Duration};
 
 use common::{
     icons::{self},
-    language::{get_local_text, get_local_text_with_args},
+    language::{get_id_of, get_local_text, get_local_text_with_args},
+    slash_commands::SlashCommandOutput,
+    spellcheck::{self, Misspelling},
     state::{
         utils::{mention_to_did_key, parse_mentions},
-        Action, Identity, State,
+        Action, Identity, State, ToastNotification,
     },
+    utils::avatars::Avatar,
     MAX_FILES_PER_MESSAGE, STATIC_ARGS,
 };
 use dioxus::prelude::*;
@@ -21,6 +27,7 @@ use kit::{
     },
     elements::{
         button::Button,
+        textarea::MisspelledWord,
         tooltip::{ArrowPosition, Tooltip},
         Appearance,
     },
@@ -35,16 +42,18 @@ use warp::{crypto::DID, raygun::Location};
 use tracing::log;
 
 const MAX_CHARS_LIMIT: usize = 1024;
+const MAX_MENTION_SUGGESTIONS: usize = 10;
 pub static EMOJI_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(":[^:]{2,}:?$").unwrap());
 pub static TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new("@[^@ ]{2,} ?$").unwrap());
+pub static COMMAND_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^/\w*$").unwrap());
 use super::context_menus::FileLocation as FileLocationContext;
 use crate::{
     components::{files::attachments::Attachments, shortcuts},
     layouts::{
         chats::{
             data::{
-                ChatData, ChatProps, MessagesToEdit, MessagesToSend, MsgChInput, ScrollBtn,
-                TypingIndicator,
+                ChatData, ChatProps, MessagesToEdit, MessagesToSend, MsgChInput, ParticipantIndex,
+                ScrollBtn, TypingIndicator,
             },
             scripts::SHOW_CONTEXT,
         },
@@ -56,16 +65,28 @@ use crate::{
             check_if_there_is_file_or_string_in_clipboard, get_files_path_from_clipboard,
             ClipboardDataType,
         },
+        extension_api::ExtensionView,
+        gifs, profiling,
     },
 };
+use expanded_editor::ExpandedEditor;
+use gif_picker::{use_gif_coroutine, GifPicker};
+use spelling_menu::SpellingMenu;
 
 pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
     log::trace!("get_chatbar");
+    profiling::count_render("get_chatbar");
     let state = use_shared_state::<State>(cx)?;
     let chat_data = use_shared_state::<ChatData>(cx)?;
     let scroll_btn = use_shared_state::<ScrollBtn>(cx)?;
     let to_send = use_shared_state::<MessagesToSend>(cx)?;
     let edit_msg = use_shared_state::<MessagesToEdit>(cx)?;
+    let show_gif_picker = use_state(cx, || false);
+    let gif_query = use_state(cx, String::new);
+    let expanded_editor = use_state(cx, || false);
+    // the right clicked misspelling, its suggestions and where it was clicked
+    let spelling_menu = use_state(cx, || None::<(Misspelling, Vec<String>, f64, f64)>);
+    use_gif_coroutine(cx);
     state.write_silent().scope_ids.chatbar = Some(cx.scope_id().0);
 
     let active_chat_id = chat_data.read().active_chat.id();
@@ -87,6 +108,20 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
     let mentions = use_ref(cx, Vec::new);
 
     let with_scroll_btn = scroll_btn.read().get(active_chat_id) && !is_loading;
+    // messages received while scrolled up count as unread, see below
+    let new_while_scrolled = state
+        .read()
+        .get_active_chat()
+        .map(|chat| chat.unreads())
+        .unwrap_or_default();
+    let scroll_btn_text = if new_while_scrolled > 0 {
+        get_local_text_with_args(
+            "messages.scroll-bottom-new",
+            vec![("count", new_while_scrolled)],
+        )
+    } else {
+        get_local_text("messages.scroll-bottom")
+    };
 
     // if the active chat is scrolled up and a message is received, want to increment unreads
     // but the needed information isn't accessible in main.rs. so a flag was added to State
@@ -229,27 +264,79 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
                 .collect()
         })
         .unwrap_or_default();
-    let chat_participants_2 = chat_participants.clone();
     let chat_participants_3 = chat_participants.clone();
+    // same index as the participants panel, so the suggestions stay fast in large groups
+    let participant_index = ParticipantIndex::new(chat_participants);
+    let participant_index_2 = participant_index.clone();
 
     let submit_fn = move || {
         local_typing_ch.send(TypingIndicator::NotTyping);
         let active_chat_id = chat_data.read().active_chat.id();
 
-        let msg = state
+        let draft = state
             .read()
             .get_active_chat()
             .as_ref()
             .and_then(|d| d.draft.clone())
-            .map(|msg| {
-                let (txt, _) =
-                    parse_mentions(&msg, &chat_participants_3, &my_id, true, mention_to_did_key);
+            .unwrap_or_default();
+        let username = state.read().get_own_identity().username();
+        let command = state.read().ui.slash_commands.run(&draft, &username);
+        let msg = match command {
+            Some(SlashCommandOutput::Send(text)) => text,
+            Some(output) => {
+                if !active_chat_id.is_nil() {
+                    state
+                        .write()
+                        .mutate(Action::SetChatDraft(active_chat_id, String::new()));
+                }
+                suggestions.set(SuggestionType::None);
+                mentions.set(vec![]);
+                match output {
+                    SlashCommandOutput::AttachFile => {
+                        if let Some(new_files) = FileDialog::new()
+                            .set_directory(dirs::home_dir().unwrap_or_default())
+                            .pick_files()
+                        {
+                            state
+                                .write()
+                                .mutate(Action::AppendChatAttachments(active_chat_id, new_files));
+                        }
+                    }
+                    SlashCommandOutput::SendFromStorage => show_storage_modal.set(true),
+                    SlashCommandOutput::SearchGifs(query) => {
+                        if gifs::get_provider(&state.read().configuration.gifs).is_some() {
+                            gif_query.set(query);
+                            show_gif_picker.set(true);
+                        } else {
+                            state.write().mutate(Action::AddToastNotification(
+                                ToastNotification::init(
+                                    "".into(),
+                                    get_local_text("messages.gifs-disabled"),
+                                    None,
+                                    2,
+                                ),
+                            ));
+                        }
+                    }
+                    SlashCommandOutput::Send(_) => {}
+                }
+                update_send();
+                return;
+            }
+            None => {
+                let (txt, _) = parse_mentions(
+                    &draft,
+                    &chat_participants_3,
+                    &my_id,
+                    true,
+                    mention_to_did_key,
+                );
                 txt
-            })
-            .unwrap_or_default()
-            .lines()
-            .map(|x| x.trim_end().to_string())
-            .collect::<Vec<String>>();
+            }
+        }
+        .lines()
+        .map(|x| x.trim_end().to_string())
+        .collect::<Vec<String>>();
 
         if !active_chat_id.is_nil() {
             state
@@ -281,6 +368,7 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
     };
 
     let submit_fn2 = submit_fn.clone();
+    let submit_fn3 = submit_fn.clone();
 
     let extensions = &state.read().ui.extensions;
     let ext_renders = extensions
@@ -288,7 +376,13 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
         .filter(|(is_enabled, ext)| {
             ext.details().location == extensions::Location::Chatbar && *is_enabled
         })
-        .map(|(_, ext)| ext.render(cx.scope))
+        .map(|(_, ext)| {
+            let name = ext.details().meta.name;
+            rsx!(ExtensionView {
+                key: "{name}",
+                name: name.to_string(),
+            })
+        })
         .collect::<Vec<_>>();
 
     let disabled = !state.read().can_use_active_chat();
@@ -327,6 +421,25 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
     };
 
     let typing_users: Vec<String> = users_typing.iter().map(|id| (*id).username()).collect();
+    let gifs_enabled = gifs::get_provider(&state.read().configuration.gifs).is_some();
+    let chat_language = state
+        .read()
+        .chats()
+        .language(&active_chat_id)
+        .map(|language| get_id_of(&language))
+        .unwrap_or_default();
+    let chat_language_2 = chat_language.clone();
+    let spellchecker = spellcheck::for_language(&if chat_language.is_empty() {
+        state.read().settings.language_id()
+    } else {
+        chat_language.clone()
+    });
+    let misspellings = spellchecker
+        .as_ref()
+        .map(|checker| {
+            checker.misspelled(&value_chatbar, &state.read().settings.personal_dictionary)
+        })
+        .unwrap_or_default();
 
     let chatbar = cx.render(rsx!(
         Chatbar {
@@ -336,6 +449,15 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
             placeholder: placeholder_text,
             typing_users: typing_users,
             is_disabled: disabled,
+            lang: chat_language,
+            misspelled: misspellings.iter().map(|m| (m.utf16.start, m.utf16.end)).collect(),
+            on_misspelled: move |word: MisspelledWord| {
+                let Some(misspelling) = misspellings.iter().find(|m| m.utf16 == (word.from..word.to)) else {
+                    return;
+                };
+                let suggestions = spellchecker.as_ref().map(|checker| checker.suggest(&misspelling.word)).unwrap_or_default();
+                spelling_menu.set(Some((misspelling.clone(), suggestions, word.x, word.y)));
+            },
             ignore_focus: cx.props.ignore_focus,
             on_paste_keydown: move |e: Event<KeyboardData>| {
                 // HACK: Allow copy and paste files for Linux
@@ -356,7 +478,10 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
                     state.write_silent().mutate(Action::SetChatDraft(active_chat_id, v));
                     validate_max();
                     update_send();
-                    local_typing_ch2.send(TypingIndicator::Typing(active_chat_id));
+                    // typing would give away being around while invisible
+                    if state.read().configuration.privacy.typing_indicators && !state.read().is_invisible() {
+                        local_typing_ch2.send(TypingIndicator::Typing(active_chat_id));
+                    }
                 }
             },
             value: state.read().get_active_chat().as_ref().and_then(|d| d.draft.clone()).unwrap_or_default(),
@@ -366,6 +491,12 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
             oncursor_update: move |(mut v, p): (String, i64)| {
                 if !active_chat_id.is_nil() {
                     let sub: String = v.chars().take(p as usize).collect();
+                    if let Some(command) = COMMAND_REGEX.find(&sub) {
+                        let pattern = command.as_str().to_string();
+                        let commands = state.read().ui.slash_commands.matching(&pattern[1..]).map(|c| (c.usage(), c.description())).collect();
+                        suggestions.set(SuggestionType::Command(pattern, commands));
+                        return;
+                    }
                     let emoji_capture = EMOJI_REGEX.captures(&sub);
                     if let Some(emoji) = emoji_capture {
                             let emoji = &emoji[0];
@@ -397,7 +528,7 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
                             let tag = tag.replace('@', "");
                             if tag.ends_with(' ') {
                                 let name = tag.replace(' ', "").to_lowercase();
-                                let replacement = chat_participants.iter().find(|id|id.username().to_lowercase().eq(&name));
+                                let replacement = participant_index.find_by_username(&name);
                                 if let Some(id) = replacement {
                                     let username = format!("{}#{}", id.username(), id.short_id());
                                     v = v.replace(&sub, &sub.replace(&tag, &format!("{username} ")));
@@ -407,9 +538,7 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
                                 suggestions.set(SuggestionType::None);
                                 return;
                             }
-                            let lower = tag.to_lowercase();
-                            let users: Vec<_> = chat_participants.iter().filter(|id|id.username().to_lowercase().starts_with(&lower))
-                                .cloned().collect();
+                            let users: Vec<_> = participant_index.starts_with(&tag).take(MAX_MENTION_SUGGESTIONS).cloned().collect();
                             suggestions.set(SuggestionType::Tag(tag, users));
                         }
                         None => {
@@ -434,7 +563,7 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
                     if let SuggestionType::Tag(_, _) = suggestions.get() {
                         let amount = replacement.chars().count() - 9;
                         let name: String = replacement.chars().take(amount).collect(); // remove short did
-                        if let Some(participant) = chat_participants_2.iter().find(|id|id.username().eq(&name)) {
+                        if let Some(participant) = participant_index_2.find_by_username(&name) {
                             mentions.write_silent().push((participant.did_key(), replacement));
                         }
                     }
@@ -450,6 +579,33 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
             },
             controls: cx.render(
                 rsx!(
+                    gifs_enabled.then(|| rsx!(
+                        Button {
+                            icon: icons::outline::Shape::Gif,
+                            disabled: is_loading || disabled,
+                            appearance: Appearance::Secondary,
+                            aria_label: "gif-picker-button".into(),
+                            onpress: move |_| show_gif_picker.set(!show_gif_picker.get()),
+                            tooltip: cx.render(rsx!(Tooltip {
+                                arrow_position: ArrowPosition::Bottom,
+                                text: get_local_text("messages.gifs"),
+                            })),
+                        }
+                    )),
+                    Button {
+                        icon: icons::outline::Shape::ArrowsPointingOut,
+                        disabled: is_loading || disabled,
+                        appearance: Appearance::Secondary,
+                        aria_label: "expand-editor-button".into(),
+                        onpress: move |_| {
+                            suggestions.set(SuggestionType::None);
+                            expanded_editor.set(true);
+                        },
+                        tooltip: cx.render(rsx!(Tooltip {
+                            arrow_position: ArrowPosition::Bottom,
+                            text: get_local_text("messages.expand-editor"),
+                        })),
+                    },
                     Button {
                         icon: icons::outline::Shape::ChevronDoubleRight,
                         disabled: is_loading || disabled,
@@ -595,10 +751,30 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
                         // however, this is easier and seems to work well enough. 
                         scroll_ch.send(active_chat_id);
                     },
-                    get_local_text("messages.scroll-bottom"),
+                    scroll_btn_text,
                 })
             })
         },
+        spelling_menu.get().clone().map(|(misspelling, suggestions, x, y)| rsx!(
+            SpellingMenu {
+                chat_id: active_chat_id,
+                misspelling: misspelling,
+                suggestions: suggestions,
+                x: x,
+                y: y,
+                onclose: move |_| spelling_menu.set(None),
+            }
+        )),
+        (*show_gif_picker.get() && gifs_enabled).then(|| rsx!(
+            GifPicker {
+                chat_id: active_chat_id,
+                query: gif_query.get().clone(),
+                onclose: move |_| {
+                    show_gif_picker.set(false);
+                    gif_query.set(String::new());
+                },
+            }
+        )),
         Attachments {
             chat_id: active_chat_id,
             files_to_attach: state.read().get_active_chat().map(|f| f.files_attached_to_send).unwrap_or_default(),
@@ -607,14 +783,30 @@ pub fn get_chatbar<'a>(cx: &'a Scoped<'a, ChatProps>) -> Element<'a> {
                 update_send();
             }
         },
-        chatbar
+        if *expanded_editor.get() {
+            rsx!(ExpandedEditor {
+                key: "{active_chat_id}",
+                chat_id: active_chat_id,
+                lang: chat_language_2,
+                onsend: move |_| {
+                    submit_fn3();
+                    expanded_editor.set(false);
+                },
+                onclose: move |_| {
+                    update_send();
+                    expanded_editor.set(false);
+                },
+            })
+        } else {
+            rsx!(chatbar)
+        }
     ))
 }
 
-fn get_platform_and_status(msg_sender: Option<&Identity>) -> (Platform, Status, String) {
+fn get_platform_and_status(msg_sender: Option<&Identity>) -> (Platform, Status, Avatar) {
     let sender = match msg_sender {
         Some(identity) => identity,
-        None => return (Platform::Desktop, Status::Offline, String::new()),
+        None => return (Platform::Desktop, Status::Offline, Avatar::default()),
     };
     let user_sender = build_user_from_identity(sender);
     (user_sender.platform, user_sender.status, user_sender.photo)
@@ -0,0 +1,86 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    spellcheck::Misspelling,
+    state::{Action, State},
+};
+use dioxus::prelude::*;
+use kit::components::{context_menu::ContextItem, invisible_closer::InvisibleCloser};
+use uuid::Uuid;
+
+#[derive(Props)]
+pub struct Props<'a> {
+    chat_id: Uuid,
+    misspelling: Misspelling,
+    suggestions: Vec<String>,
+    // where the word was right clicked
+    x: f64,
+    y: f64,
+    onclose: EventHandler<'a, ()>,
+}
+
+/// The suggestions for a misspelled word of the draft, opened by right clicking it
+#[allow(non_snake_case)]
+pub fn SpellingMenu<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let chat_id = cx.props.chat_id;
+
+    let replace = move |suggestion: &str| {
+        let draft = state
+            .read()
+            .get_chat_by_id(chat_id)
+            .and_then(|chat| chat.draft)
+            .unwrap_or_default();
+        let range = cx.props.misspelling.bytes.clone();
+        // the draft may have changed since the menu was opened
+        if draft.get(range.clone()) == Some(cx.props.misspelling.word.as_str()) {
+            let draft = format!(
+                "{}{suggestion}{}",
+                &draft[..range.start],
+                &draft[range.end..]
+            );
+            state.write().mutate(Action::SetChatDraft(chat_id, draft));
+        }
+        cx.props.onclose.call(());
+    };
+
+    cx.render(rsx!(
+        InvisibleCloser {
+            onclose: move |_| cx.props.onclose.call(()),
+        },
+        div {
+            class: "context-menu spelling-menu",
+            aria_label: "spelling-menu",
+            top: "{cx.props.y}px",
+            left: "{cx.props.x}px",
+            cx.props.suggestions.iter().map(|suggestion| {
+                let suggestion = suggestion.clone();
+                rsx!(ContextItem {
+                    key: "{suggestion}",
+                    aria_label: "spelling-suggestion".into(),
+                    text: suggestion.clone(),
+                    onpress: move |_| replace(&suggestion),
+                })
+            }),
+            cx.props.suggestions.is_empty().then(|| rsx!(
+                ContextItem {
+                    aria_label: "spelling-no-suggestions".into(),
+                    text: get_local_text("messages.no-spelling-suggestions"),
+                    disabled: true,
+                }
+            )),
+            hr {},
+            ContextItem {
+                icon: Icon::BookOpen,
+                aria_label: "add-to-dictionary".into(),
+                text: get_local_text("messages.add-to-dictionary"),
+                onpress: move |_| {
+                    state
+                        .write()
+                        .mutate(Action::AddToDictionary(cx.props.misspelling.word.clone()));
+                    cx.props.onclose.call(());
+                },
+            },
+        }
+    ))
+}
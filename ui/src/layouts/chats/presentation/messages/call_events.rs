@@ -0,0 +1,101 @@
+use common::{
+    icons::outline::Shape as Icon,
+    icons::Icon as IconElement,
+    language::{get_local_text, get_local_text_with_args},
+    state::{
+        call::{CallEvent, CallEventKind},
+        State,
+    },
+};
+use dioxus::prelude::*;
+use kit::elements::{button::Button, Appearance};
+use uuid::Uuid;
+
+use crate::components::media::calling::{offer_call, participants_to_call};
+use crate::utils::format_timestamp::{format_duration, format_timestamp_timeago};
+
+#[derive(Props, PartialEq)]
+pub struct CallEventProps {
+    event: CallEvent,
+    conversation_id: Uuid,
+}
+
+/// A call, shown between the messages like the other events of the conversation
+#[allow(non_snake_case)]
+pub fn CallEventNotice(cx: Scope<CallEventProps>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let event = &cx.props.event;
+    let username = |did| {
+        state
+            .read()
+            .get_identity(did)
+            .map(|id| id.username())
+            .unwrap_or_default()
+    };
+    let (icon, text, unanswered) = match &event.kind {
+        CallEventKind::Started(did) if *did == state.read().did_key() => (
+            Icon::PhoneArrowUpRight,
+            get_local_text("messages.call-started-by-you"),
+            false,
+        ),
+        CallEventKind::Started(did) => (
+            Icon::PhoneArrowDownLeft,
+            get_local_text_with_args("messages.call-started", vec![("user", username(did))]),
+            false,
+        ),
+        CallEventKind::Ended { duration_secs } => (
+            Icon::Phone,
+            get_local_text_with_args(
+                "messages.call-ended",
+                vec![("duration", format_duration(*duration_secs))],
+            ),
+            false,
+        ),
+        CallEventKind::Missed(did) => (
+            Icon::PhoneXMark,
+            get_local_text_with_args("messages.call-missed", vec![("user", username(did))]),
+            true,
+        ),
+        CallEventKind::Declined(did) => (
+            Icon::PhoneXMark,
+            get_local_text_with_args("messages.call-declined", vec![("user", username(did))]),
+            true,
+        ),
+    };
+    let missed = matches!(event.kind, CallEventKind::Missed(_));
+    let can_call = unanswered && state.read().ui.call_info.active_call().is_none();
+    let timestamp = format_timestamp_timeago(event.date, &state.read().settings.language_id());
+
+    cx.render(rsx!(
+        div {
+            class: format_args!("call-event {}", if missed { "missed" } else { "" }),
+            aria_label: "call-event",
+            IconElement {
+                icon: icon,
+            },
+            span { "{text}" },
+            span {
+                class: "call-event-time",
+                "{timestamp}"
+            },
+            can_call.then(|| rsx!(Button {
+                icon: Icon::PhoneArrowUpRight,
+                text: get_local_text("calls.call-back"),
+                aria_label: "call-back-button".into(),
+                appearance: Appearance::Secondary,
+                onpress: move |_| {
+                    let conversation_id = cx.props.conversation_id;
+                    let Some(participants) = participants_to_call(&state.read(), conversation_id) else {
+                        return;
+                    };
+                    cx.spawn({
+                        to_owned![state];
+                        async move {
+                            offer_call(state, conversation_id, participants).await;
+                        }
+                    });
+                },
+            }))
+        }
+    ))
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use common::{
     language::get_local_text_with_args,
@@ -6,8 +6,8 @@ use common::{
         data_transfer::{TrackerType, TransferState, TransferTracker},
         Action, State, ToastNotification,
     },
-    warp_runner::{FetchMessagesConfig, FetchMessagesResponse, RayGunCmd, WarpCmd},
-    WARP_CMD_CH,
+    warp_runner::{CancelToken, FetchMessagesConfig, FetchMessagesResponse, RayGunCmd, WarpCmd},
+    STATIC_ARGS, WARP_CMD_CH,
 };
 
 use dioxus_core::ScopeState;
@@ -17,21 +17,29 @@ use dioxus_hooks::{
 use futures::{channel::oneshot, pin_mut, StreamExt};
 
 use uuid::Uuid;
-use warp::raygun::{PinState, ReactionState};
+use warp::raygun::{Location, PinState, ReactionState};
 
 use crate::{
     layouts::chats::{
-        data::{self, ChatBehavior, ChatData, JsMsg, ScrollBtn, DEFAULT_MESSAGES_TO_TAKE},
+        data::{
+            self, ChatBehavior, ChatData, JsMsg, ScrollAnchor, ScrollBtn, DEFAULT_MESSAGES_TO_TAKE,
+        },
         scripts,
     },
     utils::{
-        async_task_queue::{download_stream_handler, DownloadStreamData},
-        download::get_download_path,
+        async_task_queue::{
+            chat_upload_stream_handler, download_stream_handler, DownloadStreamData,
+        },
+        download::{download_attachment, get_download_path},
     },
 };
 
 use super::{DownloadTracker, MessagesCommand};
 
+// how far (in pixels) from the edge of the view the next page is fetched. the view is only a window of
+// DEFAULT_MESSAGES_TO_TAKE messages, so large chats don't keep everything mounted
+const PREFETCH_MARGIN: u32 = 800;
+
 pub fn handle_msg_scroll(
     cx: &ScopeState,
     eval_provider: &crate::utils::EvalProvider,
@@ -122,6 +130,8 @@ pub fn handle_msg_scroll(
                         observer_script.replace("$TOP_MSG_ID", &top_msg_id.to_string());
                     observer_script =
                         observer_script.replace("$BOTTOM_MSG_ID", &bottom_msg_id.to_string());
+                    observer_script =
+                        observer_script.replace("$PREFETCH_MARGIN", &PREFETCH_MARGIN.to_string());
 
                     let eval = match eval_provider(&observer_script) {
                         Ok(r) => r,
@@ -216,10 +226,12 @@ pub fn handle_msg_scroll(
                                             continue 'HANDLE_EVAL;
                                         }
 
-                                        let msg = match chat_data.read().get_top_of_view(conv_id) {
+                                        let view_top = chat_data.read().get_top_of_view(conv_id);
+                                        let page_top = chat_data.read().get_top_of_page(conv_id);
+                                        let msg = match page_top {
                                             Some(x) => x,
                                             None => {
-                                                log::error!("no messages at top of view");
+                                                log::error!("no messages at top of page");
                                                 let mut behavior = chat_data.read().get_chat_behavior(conv_id);
                                                 behavior.on_scroll_top = data::ScrollBehavior::DoNothing;
                                                 chat_data.write_silent().set_chat_behavior(conv_id, behavior);
@@ -234,7 +246,10 @@ pub fn handle_msg_scroll(
                                             rsp: tx
                                         };
 
-                                        if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd)) {
+                                        // the fetch stops if the messages go away meanwhile
+                                        let token = CancelToken::new();
+                                        let _guard = token.drop_guard();
+                                        if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd).cancellable(token.clone())) {
                                             log::error!("failed to send warp cmd: {e}");
                                             tokio::time::sleep(Duration::from_secs(1)).await;
                                             continue 'HANDLE_EVAL;
@@ -251,6 +266,11 @@ pub fn handle_msg_scroll(
 
                                         match rsp {
                                             Ok(FetchMessagesResponse{ messages, has_more, most_recent }) => {
+                                                // read right before the view changes, the user may have kept scrolling while fetching
+                                                let anchor = match view_top {
+                                                    Some(msg) => read_anchor(&eval_provider, msg.message_id).await,
+                                                    None => None,
+                                                };
                                                 let new_messages = messages.len();
                                                 chat_data.write().insert_messages(conv_id, messages);
                                                 let mut behavior = chat_data.read().get_chat_behavior(conv_id);
@@ -259,6 +279,7 @@ pub fn handle_msg_scroll(
                                                     behavior.on_scroll_end = data::ScrollBehavior::FetchMore;
                                                 }
                                                 behavior.most_recent_msg_id = most_recent;
+                                                behavior.view_init.anchor = anchor;
 
                                                 log::trace!("fetched {new_messages} messages. new behavior: {:?}", behavior);
                                                 chat_data.write().set_chat_behavior(conv_id, behavior);
@@ -281,10 +302,12 @@ pub fn handle_msg_scroll(
                                             continue 'HANDLE_EVAL;
                                         }
 
-                                        let msg = match chat_data.read().get_bottom_of_view(conv_id) {
+                                        let view_bottom = chat_data.read().active_chat.messages.get_latest_displayed();
+                                        let page_bottom = chat_data.read().get_bottom_of_page(conv_id);
+                                        let msg = match page_bottom {
                                             Some(x) => x,
                                             None => {
-                                                log::error!("no messages at bottom of view");
+                                                log::error!("no messages at bottom of page");
                                                 chat_data.write_silent().set_chat_behavior(conv_id, ChatBehavior::default());
                                                 continue 'HANDLE_EVAL;
                                             }
@@ -297,7 +320,10 @@ pub fn handle_msg_scroll(
                                             rsp: tx
                                         };
 
-                                        if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd)) {
+                                        // the fetch stops if the messages go away meanwhile
+                                        let token = CancelToken::new();
+                                        let _guard = token.drop_guard();
+                                        if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd).cancellable(token.clone())) {
                                             log::error!("failed to send warp cmd: {e}");
                                             tokio::time::sleep(Duration::from_secs(1)).await;
                                             continue 'HANDLE_EVAL;
@@ -314,6 +340,10 @@ pub fn handle_msg_scroll(
 
                                         match rsp {
                                             Ok(FetchMessagesResponse{ messages, has_more, most_recent }) => {
+                                                let anchor = match view_bottom {
+                                                    Some(msg) => read_anchor(&eval_provider, msg.message_id).await,
+                                                    None => None,
+                                                };
                                                 let new_messages = messages.len();
                                                 chat_data.write().insert_messages(conv_id, messages);
                                                 chat_data.write().active_chat.new_key();
@@ -322,10 +352,12 @@ pub fn handle_msg_scroll(
                                                 if !has_more {
                                                     // remove extra messages from the list and return to ScrollInit::MostRecent
                                                     chat_data.write().reset_messages(conv_id);
+                                                    chat_data.write_silent().set_scroll_anchor(conv_id, anchor);
                                                     scroll_btn.write().clear(conv_id);
                                                 } else {
                                                     behavior.on_scroll_top = data::ScrollBehavior::FetchMore;
                                                     behavior.on_scroll_end = if has_more { data::ScrollBehavior::FetchMore } else { data::ScrollBehavior::DoNothing };
+                                                    behavior.view_init.anchor = anchor;
                                                     chat_data.write().set_chat_behavior(conv_id, behavior.clone());
                                                 }
 
@@ -357,6 +389,17 @@ pub fn handle_msg_scroll(
     ch.clone()
 }
 
+// where the message is in the view, so it can be put back there once the new page is rendered
+async fn read_anchor(
+    eval_provider: &crate::utils::EvalProvider,
+    message_id: Uuid,
+) -> Option<ScrollAnchor> {
+    let script = scripts::READ_ANCHOR.replace("$MESSAGE_ID", &message_id.to_string());
+    let eval = eval_provider(&script).ok()?;
+    let offset = eval.join().await.ok()?.as_i64()?;
+    Some(ScrollAnchor { message_id, offset })
+}
+
 pub fn fetch_later_ch(
     cx: &ScopeState,
     chat_data: &UseSharedState<data::ChatData>,
@@ -389,7 +432,11 @@ pub fn fetch_later_ch(
                     rsp: tx,
                 };
 
-                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd)) {
+                // the fetch stops if the messages go away meanwhile
+                let token = CancelToken::new();
+                let _guard = token.drop_guard();
+                let cmd = WarpCmd::RayGun(cmd).cancellable(token.clone());
+                if let Err(e) = warp_cmd_tx.send(cmd) {
                     log::error!("failed to send warp cmd: {e}");
                     continue;
                 }
@@ -452,8 +499,15 @@ pub fn handle_warp_commands(
 ) -> Coroutine<MessagesCommand> {
     let file_tracker = use_shared_state::<TransferTracker>(cx).unwrap();
     let download_streams = download_stream_handler(cx);
+    let upload_streams = chat_upload_stream_handler(cx);
     let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<MessagesCommand>| {
-        to_owned![state, file_tracker, pending_downloads, download_streams];
+        to_owned![
+            state,
+            file_tracker,
+            pending_downloads,
+            download_streams,
+            upload_streams
+        ];
         async move {
             let warp_cmd_tx = WARP_CMD_CH.tx.clone();
             while let Some(cmd) = rx.next().await {
@@ -575,12 +629,16 @@ pub fn handle_warp_commands(
                                 continue;
                             }
                         }
-                        file_tracker.write().start_file_transfer(
-                            file_id,
-                            file.name(),
-                            file_state,
-                            TrackerType::FileDownload,
-                        );
+                        {
+                            let mut tracker = file_tracker.write();
+                            tracker.start_file_transfer(
+                                file_id,
+                                file.name(),
+                                file_state,
+                                TrackerType::FileDownload,
+                            );
+                            tracker.set_conversation(file_id, conv_id, TrackerType::FileDownload);
+                        }
                         if let Some(conv) = pending_downloads.write().get_mut(&conv_id) {
                             conv.remove(&file);
                         }
@@ -628,9 +686,150 @@ pub fn handle_warp_commands(
                             log::error!("failed to pin message: {}", e);
                         }
                     }
+                    MessagesCommand::Forward {
+                        message,
+                        convs_id,
+                        sender,
+                    } => {
+                        // attachments belong to the original conversation, so they are
+                        // downloaded first and re-attached from disk. without all of them the
+                        // message isn't forwarded at all
+                        let mut attachments = vec![];
+                        let mut forwarded_files = None;
+                        if !message.attachments().is_empty() {
+                            let files = ForwardedFiles(
+                                STATIC_ARGS
+                                    .temp_files
+                                    .join(format!("forward-{}", message.id())),
+                            );
+                            let mut failed = tokio::fs::create_dir_all(&files.0)
+                                .await
+                                .err()
+                                .map(|e| e.to_string());
+                            for file in message.attachments() {
+                                if failed.is_some() {
+                                    break;
+                                }
+                                let path = files.0.join(file.name());
+                                match download_attachment(&message, file.name(), path.clone()).await
+                                {
+                                    Ok(_) => attachments.push(Location::Disk { path }),
+                                    Err(e) => failed = Some(format!("{}: {e}", file.name())),
+                                }
+                            }
+                            if let Some(error) = failed {
+                                log::error!("failed to download an attachment to forward: {error}");
+                                state.write().mutate(Action::AddToastNotification(
+                                    ToastNotification::init(
+                                        "".into(),
+                                        get_local_text_with_args(
+                                            "messages.forward-failed",
+                                            vec![("error", error)],
+                                        ),
+                                        None,
+                                        4,
+                                    ),
+                                ));
+                                continue;
+                            }
+                            forwarded_files = Some(Arc::new(files));
+                        }
+
+                        let mut msg = vec![get_local_text_with_args(
+                            "messages.forwarded-from",
+                            vec![("user", sender)],
+                        )];
+                        msg.extend(message.lines());
+
+                        let (tx, rx) = oneshot::channel();
+                        let has_attachments = !attachments.is_empty();
+                        if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(
+                            RayGunCmd::SendMessageForSeveralChats {
+                                convs_id,
+                                msg: msg.clone(),
+                                attachments,
+                                rsp: tx,
+                            },
+                        )) {
+                            log::error!("failed to send warp command: {}", e);
+                            continue;
+                        }
+
+                        let res = rx.await.expect("command canceled");
+                        match res {
+                            Ok(results) => {
+                                let num = results.sent.len();
+                                let mut to_append = upload_streams.write();
+                                for (chat, (id, stream)) in results.sent {
+                                    if has_attachments {
+                                        state.write().increment_outgoing_messages_for(
+                                            chat,
+                                            id,
+                                            msg.clone(),
+                                        );
+                                    }
+                                    if let Some(stream) = stream {
+                                        // the files are removed once every upload of them ended
+                                        let files = forwarded_files.clone();
+                                        let stream = stream
+                                            .map(move |event| {
+                                                let _ = &files;
+                                                event
+                                            })
+                                            .boxed();
+                                        to_append.append((chat, id, stream));
+                                    }
+                                }
+                                if num > 0 {
+                                    state.write().mutate(Action::AddToastNotification(
+                                        ToastNotification::init(
+                                            "".into(),
+                                            get_local_text_with_args(
+                                                "messages.forwarded",
+                                                vec![("num", num.to_string())],
+                                            ),
+                                            None,
+                                            2,
+                                        ),
+                                    ));
+                                }
+                                if let Some((_, error)) = results.failed.first() {
+                                    state.write().mutate(Action::AddToastNotification(
+                                        ToastNotification::init(
+                                            "".into(),
+                                            get_local_text_with_args(
+                                                "messages.not-sent-to-chats",
+                                                vec![
+                                                    ("num", results.failed.len().to_string()),
+                                                    ("error", error.to_string()),
+                                                ],
+                                            ),
+                                            None,
+                                            4,
+                                        ),
+                                    ));
+                                }
+                            }
+                            Err(e) => {
+                                log::error!("failed to forward message: {}", e);
+                            }
+                        }
+                    }
                 }
             }
         }
     });
     ch.clone()
 }
+
+// the attachments of a message which is forwarded, downloaded to this directory. it is removed when
+// dropped, which is after the uploads of every chat the message was forwarded to
+struct ForwardedFiles(PathBuf);
+
+impl Drop for ForwardedFiles {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0) {
+            log::warn!("failed to remove the forwarded files: {e}");
+        }
+    }
+}
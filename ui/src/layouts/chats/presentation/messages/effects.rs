@@ -36,6 +36,8 @@ pub fn init_msg_scroll(
                     if unreads > 0 {
                         chat_data.write_silent().active_chat.clear_unreads();
                     }
+                    let has_divider = chat_data.read().active_chat.first_unread.is_some();
+                    let scroll_value = chat_behavior.scroll_value.unwrap_or_default();
                     let msg_idx = chat_data
                         .read()
                         .active_chat
@@ -51,6 +53,14 @@ pub fn init_msg_scroll(
                         .get(msg_idx)
                         .map(|x| x.inner.id());
                     match msg_id {
+                        // the divider is rendered above the first unread message
+                        Some(_) if unreads > 0 && has_divider => {
+                            scripts::SCROLL_TO_UNREAD.to_string()
+                        }
+                        // back to where the chat was scrolled to when it was left
+                        Some(_) if scroll_value < -100 => {
+                            scripts::RESTORE_SCROLL.replace("$SCROLL", &scroll_value.to_string())
+                        }
                         Some(id) => scripts::SCROLL_TO_END.replace("$MESSAGE_ID", &format!("{id}")),
                         None => {
                             log::debug!("failed to init message scroll - empty chat");
@@ -67,6 +77,14 @@ pub fn init_msg_scroll(
                 }
             };
 
+            // after fetching a page, keep the messages which were on screen where they were
+            let scroll_script = match chat_data.write_silent().take_scroll_anchor(chat_id) {
+                Some(anchor) => scripts::RESTORE_ANCHOR
+                    .replace("$MESSAGE_ID", &format!("{}", anchor.message_id))
+                    .replace("$OFFSET", &format!("{}", anchor.offset)),
+                None => scroll_script,
+            };
+
             match eval_provider(&scroll_script) {
                 Ok(eval) => {
                     if let Err(e) = eval.join().await {
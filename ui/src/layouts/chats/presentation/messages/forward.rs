@@ -0,0 +1,41 @@
+use common::{icons::outline::Shape as Icon, language::get_local_text, state::State};
+use dioxus::prelude::*;
+use warp::raygun;
+
+use crate::components::select_chats::SelectChatsModal;
+
+use super::MessagesCommand;
+
+#[derive(Props)]
+pub struct ForwardMessageProps<'a> {
+    message: raygun::Message,
+    onclose: EventHandler<'a, ()>,
+}
+
+// lets the user pick the chats a message should be forwarded to
+#[allow(non_snake_case)]
+pub fn ForwardMessageModal<'a>(cx: Scope<'a, ForwardMessageProps<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let ch = use_coroutine_handle::<MessagesCommand>(cx)?;
+
+    cx.render(rsx!(SelectChatsModal {
+        aria_label: "forward-message-modal".into(),
+        button_text: get_local_text("messages.forward"),
+        button_icon: Icon::ArrowUturnRight,
+        button_aria_label: "forward-message-button".into(),
+        excluded_chat: Some(cx.props.message.conversation_id()),
+        onclose: move |_| cx.props.onclose.call(()),
+        onsubmit: move |convs_id| {
+            let sender = state
+                .read()
+                .get_identity(&cx.props.message.sender())
+                .map(|identity| identity.username())
+                .unwrap_or_default();
+            ch.send(MessagesCommand::Forward {
+                message: cx.props.message.clone(),
+                convs_id,
+                sender,
+            });
+        },
+    }))
+}
@@ -2,15 +2,24 @@ use std::{
     collections::{HashMap, HashSet},
     ffi::OsStr,
     path::PathBuf,
+    time::Duration,
 };
 
 use arboard::Clipboard;
 use dioxus::prelude::{EventHandler, *};
 
+mod call_events;
 mod coroutines;
 mod effects;
+mod forward;
+mod quick_reactions;
+
+use call_events::CallEventNotice;
+use quick_reactions::QuickReactions;
 
 use common::state::{
+    call::CallEvent,
+    chats::CHAT_ZOOM_STEP,
     pending_message::{FileLocation, PendingMessage},
     Action, Identity, State,
 };
@@ -19,12 +28,13 @@ use common::{
     icons::Icon as IconElement,
     language::get_local_text_with_args,
     state::{ui::EmojiDestination, ToastNotification},
+    utils::incoming_webhooks,
 };
 use kit::{
     components::{
         context_menu::{ContextItem, ContextMenu},
         indicator::Status,
-        message::{Message, Order, ReactionAdapter},
+        message::{Message, Order, ReactionAdapter, Receipt},
         message_group::MessageGroup,
         message_reply::MessageReply,
         user_image::UserImage,
@@ -33,9 +43,11 @@ use kit::{
         loader::Loader,
         tooltip::{ArrowPosition, Tooltip},
     },
+    layout::modal::Modal,
 };
 
 use common::language::get_local_text;
+use extensions::context_menu::MenuContext;
 use rfd::FileDialog;
 
 use uuid::Uuid;
@@ -43,13 +55,13 @@ use warp::{
     constellation::file::File,
     crypto::DID,
     multipass::identity::IdentityStatus,
-    raygun::{self},
+    raygun::{self, ConversationType},
 };
 
 use tracing::log;
 
 use crate::{
-    components::emoji_group::EmojiGroup,
+    components::{emoji_group::EmojiGroup, extension_menu_items::ExtensionMenuItems},
     layouts::{
         chats::{
             data::{self, ChatData, MessagesToEdit, MessagesToSend, ScrollBtn},
@@ -57,7 +69,7 @@ use crate::{
         },
         storage::files_layout::file_preview::open_file_preview_modal,
     },
-    utils::format_timestamp::format_timestamp_timeago,
+    utils::{format_timestamp::format_timestamp_timeago, profiling},
 };
 
 #[allow(clippy::large_enum_variant)]
@@ -79,8 +91,17 @@ pub enum MessagesCommand {
         msg: Vec<String>,
     },
     Pin(raygun::Message),
+    // re-sends the message to the given chats, prefixed with the name of the original sender
+    Forward {
+        message: raygun::Message,
+        convs_id: Vec<Uuid>,
+        sender: String,
+    },
 }
 
+// how long a message is pressed to react to it
+const LONG_PRESS: Duration = Duration::from_millis(500);
+
 pub type DownloadTracker = HashMap<Uuid, HashSet<warp::constellation::file::File>>;
 
 #[component(no_case_check)]
@@ -89,6 +110,7 @@ pub fn get_messages(
     quickprofile_data: UseRef<Option<(f64, f64, Identity, bool)>>,
 ) -> Element {
     log::trace!("get_messages");
+    profiling::count_render("get_messages");
     use_shared_state_provider(cx, || -> DownloadTracker { HashMap::new() });
     let state = use_shared_state::<State>(cx)?;
     let chat_data = use_shared_state::<ChatData>(cx)?;
@@ -104,6 +126,26 @@ pub fn get_messages(
     let _ch = coroutines::handle_warp_commands(cx, state, pending_downloads);
 
     let active_chat_id = chat_data.read().active_chat.id();
+    use_future(cx, &active_chat_id, |chat_id| {
+        to_owned![eval, state];
+        async move {
+            let Ok(zoom) = eval(scripts::CHAT_ZOOM) else {
+                return;
+            };
+            while let Ok(zoom_in) = zoom.recv().await {
+                let step = if zoom_in.as_bool().unwrap_or_default() {
+                    CHAT_ZOOM_STEP
+                } else {
+                    -CHAT_ZOOM_STEP
+                };
+                let value = state.read().chats().zoom(&chat_id) + step;
+                state.write().mutate(Action::SetChatZoom(chat_id, value));
+            }
+        }
+    });
+    let zoom = state.read().chats().zoom(&active_chat_id);
+    let zoom_percent = (zoom * 100.).round() as u32;
+    let reset_zoom = get_local_text("messages.reset-zoom");
     // used by the intersection observer to terminate itself.
     let chat_key = chat_data.read().active_chat.key().to_string();
     let chat_behavior = chat_data.read().get_chat_behavior(active_chat_id);
@@ -137,6 +179,10 @@ pub fn get_messages(
     cx.render(rsx!(
         div {
             id: "messages",
+            // new messages are read out by the live regions of the app, this only marks the history
+            role: "log",
+            aria_live: "off",
+            style: "--chat-zoom: {zoom}",
             // this is a hack to deal with the limitations of the message paging. On the first page, if a message comes in while the page
             // is scrolled up, it won't be displayed when the user scrolls back down. need to trigger a "fetch more" response. 
             onscroll: move |_| {
@@ -175,7 +221,10 @@ pub fn get_messages(
                 rsx!(
                     msg_container_end,
                     loop_over_message_groups {
-                        groups: data::create_message_groups(chat_data.read().active_chat.my_id(), chat_data.read().active_chat.other_participants(), chat_data.read().active_chat.messages()),
+                        groups: data::create_message_groups(chat_data.read().active_chat.my_id(), chat_data.read().active_chat.other_participants(), chat_data.read().active_chat.messages(), chat_data.read().active_chat.first_unread),
+                        first_unread: chat_data.read().active_chat.first_unread,
+                        call_events: state.read().call_log.events(&active_chat_id),
+                        all_loaded: !matches!(chat_behavior.on_scroll_top, data::ScrollBehavior::FetchMore),
                         active_chat_id: chat_data.read().active_chat.id(),
                         on_context_menu_action: move |(e, mut id): (Event<MouseData>, Identity)| {
                             let own = state.read().get_own_identity().did_key().eq(&id.did_key());
@@ -196,14 +245,30 @@ pub fn get_messages(
                     }
                 )
             }
-        }
+        },
+        (zoom_percent != 100).then(|| rsx!(button {
+            class: "chat-zoom-indicator",
+            aria_label: "chat-zoom-reset",
+            title: "{reset_zoom}",
+            onclick: move |_| state.write().mutate(Action::SetChatZoom(active_chat_id, 1.0)),
+            IconElement {
+                icon: Icon::MagnifyingGlass,
+            },
+            "{zoom_percent}%"
+        }))
     ))
 }
 
 #[derive(Props)]
 pub struct AllMessageGroupsProps<'a> {
     groups: Vec<data::MessageGroup>,
+    // oldest first
+    call_events: Vec<CallEvent>,
+    // the oldest message is loaded, so are the calls from before it
+    all_loaded: bool,
     active_chat_id: Uuid,
+    // the "New Messages" divider goes above the group which starts with it
+    first_unread: Option<Uuid>,
     on_context_menu_action: EventHandler<'a, (Event<MouseData>, Identity)>,
 }
 
@@ -211,13 +276,59 @@ pub struct AllMessageGroupsProps<'a> {
 // temporary location
 pub fn loop_over_message_groups<'a>(cx: Scope<'a, AllMessageGroupsProps<'a>>) -> Element<'a> {
     log::trace!("render message groups");
-    cx.render(rsx!(cx.props.groups.iter().map(|_group| {
-        rsx!(render_message_group {
-            group: _group,
-            active_chat_id: cx.props.active_chat_id,
-            on_context_menu_action: move |e| cx.props.on_context_menu_action.call(e)
-        },)
-    })))
+    profiling::count_render("loop_over_message_groups");
+    let date_of = |msg: Option<&data::MessageGroupMsg>| msg.map(|m| m.message.inner.date());
+    // the calls which happened before each group
+    let mut events = cx.props.call_events.iter().peekable();
+    if let Some(first) = date_of(cx.props.groups.first().and_then(|g| g.messages.first())) {
+        if !cx.props.all_loaded {
+            while events.next_if(|event| event.date < first).is_some() {}
+        }
+    }
+    let groups: Vec<_> = cx
+        .props
+        .groups
+        .iter()
+        .map(|group| {
+            let start = date_of(group.messages.first());
+            let before: Vec<CallEvent> = std::iter::from_fn(|| {
+                events.next_if(|event| start.map(|start| event.date < start).unwrap_or(false))
+            })
+            .cloned()
+            .collect();
+            (before, group)
+        })
+        .collect();
+    let after: Vec<CallEvent> = events.cloned().collect();
+
+    cx.render(rsx!(
+        groups.into_iter().map(|(before, _group)| {
+            let unread_from_here = cx.props.first_unread.is_some()
+                && _group.messages.first().map(|m| m.message.inner.id()) == cx.props.first_unread;
+            rsx!(
+                before.into_iter().map(|event| rsx!(CallEventNotice {
+                    event: event,
+                    conversation_id: cx.props.active_chat_id
+                })),
+                unread_from_here.then(|| rsx!(div {
+                    id: "unread-divider",
+                    class: "unread-divider",
+                    aria_label: "unread-divider",
+                    role: "separator",
+                    get_local_text("messages.unread-divider")
+                })),
+                render_message_group {
+                    group: _group,
+                    active_chat_id: cx.props.active_chat_id,
+                    on_context_menu_action: move |e| cx.props.on_context_menu_action.call(e)
+                },
+            )
+        }),
+        after.into_iter().map(|event| rsx!(CallEventNotice {
+            event: event,
+            conversation_id: cx.props.active_chat_id
+        }))
+    ))
 }
 
 #[derive(Props)]
@@ -229,6 +340,7 @@ struct MessageGroupProps<'a> {
 }
 
 fn render_message_group<'a>(cx: Scope<'a, MessageGroupProps<'a>>) -> Element<'a> {
+    profiling::count_render("render_message_group");
     let state = use_shared_state::<State>(cx)?;
 
     let MessageGroupProps {
@@ -300,16 +412,18 @@ fn render_message_group<'a>(cx: Scope<'a, MessageGroupProps<'a>>) -> Element<'a>
     };
     let active_language = &state.read().settings.language_id();
 
-    let mut sender_status = sender.identity_status().into();
-    if !group.remote && sender_status == Status::Offline {
-        sender_status = Status::Online;
-    }
+    // appearing offline shows as invisible to ourselves
+    let sender_status = if group.remote {
+        sender.identity_status().into()
+    } else {
+        Status::own(sender.identity_status())
+    };
 
     cx.render(rsx!(
         blocked_element,
         MessageGroup {
             user_image: render!(UserImage {
-                image: sender.profile_picture(),
+                image: sender.avatar(),
                 platform: sender.platform().into(),
                 status: sender_status,
                 on_press: move |e| {
@@ -342,10 +456,22 @@ struct MessagesProps<'a> {
     pending: bool,
 }
 fn wrap_messages_in_context_menu<'a>(cx: Scope<'a, MessagesProps<'a>>) -> Element<'a> {
+    profiling::count_render("wrap_messages_in_context_menu");
     let state = use_shared_state::<State>(cx)?;
     let edit_msg = use_shared_state::<MessagesToEdit>(cx)?;
     // see comment in ContextMenu about this variable.
     let reacting_to: &UseState<Option<Uuid>> = use_state(cx, || None);
+    let show_read_by: &UseState<Option<Uuid>> = use_state(cx, || None);
+    let forwarding: &UseState<Option<raygun::Message>> = use_state(cx, || None);
+    let chat_data = use_shared_state::<ChatData>(cx)?;
+    let is_group = chat_data.read().active_chat.conversation_type() == ConversationType::Group;
+    // group moderators and admins can delete messages sent by other members
+    let can_moderate = is_group
+        && state
+            .read()
+            .chats
+            .role_of(&chat_data.read().active_chat.id(), &state.read().did_key())
+            .can_moderate();
 
     let emoji_selector_extension = "emoji_selector";
 
@@ -356,7 +482,20 @@ fn wrap_messages_in_context_menu<'a>(cx: Scope<'a, MessagesProps<'a>>) -> Elemen
         .enabled_extension(emoji_selector_extension);
 
     let ch = use_coroutine_handle::<MessagesCommand>(cx)?;
-    cx.render(rsx!(cx.props.messages.iter().map(|grouped_message| {
+    cx.render(rsx!(
+        show_read_by.get().map(|msg_id| rsx!(
+            ReadBy {
+                message_id: msg_id,
+                onclose: move |_| show_read_by.set(None),
+            }
+        )),
+        forwarding.get().as_ref().map(|message| rsx!(
+            forward::ForwardMessageModal {
+                message: message.clone(),
+                onclose: move |_| forwarding.set(None),
+            }
+        )),
+        cx.props.messages.iter().map(|grouped_message| {
         let message = &grouped_message.message;
         let sender_is_self = message.inner.sender() == state.read().did_key();
 
@@ -372,12 +511,57 @@ fn wrap_messages_in_context_menu<'a>(cx: Scope<'a, MessagesProps<'a>>) -> Elemen
         let conversation_id = message.inner.conversation_id();
 
         if cx.props.pending {
-            return rsx!(render_message {
-                message: grouped_message,
-                is_remote: cx.props.is_remote,
-                message_key: message_key,
-                edit_msg: edit_msg,
-                pending: cx.props.pending
+            if !state.read().is_queued(&msg_uuid) {
+                return rsx!(render_message {
+                    message: grouped_message,
+                    is_remote: cx.props.is_remote,
+                    message_key: message_key,
+                    edit_msg: edit_msg,
+                    pending: cx.props.pending
+                });
+            }
+            // messages in the outbox can still be changed or canceled
+            return rsx!(ContextMenu {
+                key: "{context_key}",
+                id: msg_uuid.to_string(),
+                devmode: state.read().configuration.developer.developer_mode,
+                children: cx.render(rsx!(render_message {
+                    message: grouped_message,
+                    is_remote: cx.props.is_remote,
+                    message_key: message_key,
+                    edit_msg: edit_msg,
+                    pending: cx.props.pending
+                })),
+                items: cx.render(rsx!(
+                    ContextItem {
+                        icon: Icon::Pencil,
+                        aria_label: "queued-message-edit".into(),
+                        text: get_local_text("messages.edit"),
+                        onpress: move |_| {
+                            edit_msg.write().edit = Some(msg_uuid);
+                            state.write().ui.ignore_focus = true;
+                        }
+                    },
+                    state.read().queued_message_gave_up(&msg_uuid).then(|| rsx!(
+                        ContextItem {
+                            icon: Icon::ArrowPath,
+                            aria_label: "queued-message-retry".into(),
+                            text: get_local_text("messages.retry-queued"),
+                            onpress: move |_| {
+                                state.write().mutate(Action::RetryQueuedMessage(msg_uuid));
+                            }
+                        }
+                    )),
+                    ContextItem {
+                        icon: Icon::XMark,
+                        danger: true,
+                        aria_label: "queued-message-cancel".into(),
+                        text: get_local_text("messages.cancel-queued"),
+                        onpress: move |_| {
+                            state.write().mutate(Action::CancelQueuedMessage(msg_uuid));
+                        }
+                    },
+                ))
             });
         }
 
@@ -432,6 +616,14 @@ fn wrap_messages_in_context_menu<'a>(cx: Scope<'a, MessagesProps<'a>>) -> Elemen
                             .mutate(Action::StartReplying(&cx.props.active_chat_id, message));
                     }
                 },
+                ContextItem {
+                    icon: Icon::ArrowUturnRight,
+                    aria_label: "messages-forward".into(),
+                    text: get_local_text("messages.forward"),
+                    onpress: move |_| {
+                        forwarding.set(Some(message.inner.clone()));
+                    }
+                },
                 ContextItem {
                     icon: Icon::FaceSmile,
                     aria_label: "messages-react".into(),
@@ -499,12 +691,21 @@ fn wrap_messages_in_context_menu<'a>(cx: Scope<'a, MessagesProps<'a>>) -> Elemen
                         state.write().ui.ignore_focus = false;
                     }
                 },
+                ContextItem {
+                    icon: Icon::Eye,
+                    aria_label: "messages-read-by".into(),
+                    text: get_local_text("messages.read-by"),
+                    should_render: sender_is_self && is_group && state.read().configuration.privacy.read_receipts,
+                    onpress: move |_| {
+                        show_read_by.set(Some(msg_uuid));
+                    }
+                },
                 ContextItem {
                     icon: Icon::Trash,
                     danger: true,
                     aria_label: "messages-delete".into(),
                     text: get_local_text("uplink.delete"),
-                    should_render: sender_is_self,
+                    should_render: sender_is_self || can_moderate,
                     onpress: move |_| {
                         ch.send(MessagesCommand::DeleteMessage {
                             conv_id: message.inner.conversation_id(),
@@ -512,11 +713,85 @@ fn wrap_messages_in_context_menu<'a>(cx: Scope<'a, MessagesProps<'a>>) -> Elemen
                         });
                     }
                 },
+                ExtensionMenuItems {
+                    context: MenuContext::Message { conversation_id: conversation_id, message_id: msg_uuid },
+                },
             )) // end of context menu items
         }) // end context menu
     }))) // end outer cx.render
 }
 
+#[derive(Props)]
+struct ReadByProps<'a> {
+    message_id: Uuid,
+    onclose: EventHandler<'a, ()>,
+}
+
+// lists the participants of a group chat who have read the message
+#[allow(non_snake_case)]
+fn ReadBy<'a>(cx: Scope<'a, ReadByProps<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let chat_data = use_shared_state::<ChatData>(cx)?;
+    let chat_id = chat_data.read().active_chat.id();
+    let date = chat_data
+        .read()
+        .active_chat
+        .messages
+        .times
+        .get(&cx.props.message_id)
+        .copied();
+    // only the user's own messages have the list
+    let readers: Vec<DID> = date
+        .map(|date| {
+            state
+                .read()
+                .chats
+                .read_by(&chat_id, &state.read().did_key(), date)
+                .into_iter()
+                .collect()
+        })
+        .unwrap_or_default();
+    let identities = state.read().get_identities(&readers);
+
+    cx.render(rsx!(Modal {
+        open: true,
+        transparent: true,
+        with_title: get_local_text("messages.read-by"),
+        onclose: move |_| cx.props.onclose.call(()),
+        div {
+            id: "read-by",
+            aria_label: "read-by",
+            if identities.is_empty() {
+                rsx!(
+                    p {
+                        class: "read-by-empty",
+                        get_local_text("messages.read-by-none")
+                    }
+                )
+            }
+            identities.iter().map(|identity| {
+                let did = identity.did_key().to_string();
+                rsx!(
+                    div {
+                        key: "{did}",
+                        class: "read-by-user",
+                        aria_label: "read-by-user",
+                        UserImage {
+                            platform: identity.platform().into(),
+                            status: identity.identity_status().into(),
+                            image: identity.avatar(),
+                        },
+                        p {
+                            class: "ellipsis-overflow",
+                            identity.username()
+                        }
+                    }
+                )
+            })
+        }
+    }))
+}
+
 #[derive(Props)]
 struct MessageProps<'a> {
     message: &'a data::MessageGroupMsg,
@@ -527,6 +802,7 @@ struct MessageProps<'a> {
 }
 fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
     //log::trace!("render message {}", &cx.props.message.message.key);
+    profiling::count_render("render_message");
     let state = use_shared_state::<State>(cx)?;
     let chat_data = use_shared_state::<ChatData>(cx)?;
 
@@ -567,6 +843,7 @@ fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
                 reaction_count: users.len(),
                 self_reacted: users.iter().any(|x| x == &user_did),
                 alt: user_names.join(", "),
+                reacted_by: user_names,
             }
         })
         .collect();
@@ -576,9 +853,37 @@ fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
     let pending_uploads = grouped_message.file_progress.as_ref();
     let render_markdown = state.read().ui.should_transform_markdown_text();
     let should_transform_ascii_emojis = state.read().ui.should_transform_ascii_emojis();
-    let msg_lines = message.inner.lines().join("\n");
+    let (bot, msg_lines) = match incoming_webhooks::bot_post(&message.inner.lines()) {
+        Some((bot, text)) => (Some(bot), text),
+        None => (None, message.inner.lines().join("\n")),
+    };
 
     let is_mention = message.clone().is_mention_self(&user_did);
+    let is_room_mention = state
+        .read()
+        .get_active_chat()
+        .and_then(|chat| {
+            let moderation = state.read().chats.moderation(&chat.id);
+            chat.group_mention_for(&message.inner, &user_did, &moderation)
+        })
+        .is_some();
+    // a message counts as read once every other participant has read it
+    let receipt = (!cx.props.is_remote && !cx.props.pending).then(|| {
+        let readers = state.read().chats.read_by(
+            &chat_data.read().active_chat.id(),
+            &message.inner.sender(),
+            message.inner.date(),
+        );
+        let others = chat_data.read().active_chat.other_participants().len();
+        if state.read().configuration.privacy.read_receipts
+            && !readers.is_empty()
+            && readers.len() >= others
+        {
+            Receipt::Read
+        } else {
+            Receipt::Delivered
+        }
+    });
     let preview_file_in_the_message: &UseState<(bool, Option<File>)> =
         use_state(cx, || (false, None));
 
@@ -587,10 +892,37 @@ fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
         reply_user = state.read().get_identity(&info.2).unwrap_or_default();
     }
     let to_send = use_shared_state::<MessagesToSend>(cx)?;
+    // a long press opens the quick reactions, like hovering does
+    let quick_reactions_open = use_state(cx, || false);
+    let pressing = use_ref(cx, || false);
 
     cx.render(rsx!(
         div {
-            class: "msg-wrapper",
+            class: format_args!("msg-wrapper {}", if *quick_reactions_open.get() { "reacting" } else { "" }),
+            onmousedown: move |_| {
+                *pressing.write_silent() = true;
+                cx.spawn({
+                    to_owned![pressing, quick_reactions_open];
+                    async move {
+                        tokio::time::sleep(LONG_PRESS).await;
+                        if *pressing.read() {
+                            quick_reactions_open.set(true);
+                        }
+                    }
+                });
+            },
+            onmouseup: move |_| *pressing.write_silent() = false,
+            onmouseleave: move |_| {
+                *pressing.write_silent() = false;
+                if *quick_reactions_open.get() {
+                    quick_reactions_open.set(false);
+                }
+            },
+            (!cx.props.pending && !is_editing).then(|| rsx!(QuickReactions {
+                message: &message.inner,
+                remote: cx.props.is_remote,
+                ondone: move |_| quick_reactions_open.set(false),
+            })),
             preview_file_in_the_message.0.then(|| {
                 if preview_file_in_the_message.1.is_none() {
                     preview_file_in_the_message.set((false, None));
@@ -639,7 +971,7 @@ fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
                         loading: false,
                         platform: reply_user.platform().into(),
                         status: reply_user.identity_status().into(),
-                        image: reply_user.profile_picture(),
+                        image: reply_user.avatar(),
                     }))
                 }
             )),
@@ -649,7 +981,10 @@ fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
                 editing: is_editing,
                 remote: cx.props.is_remote,
                 with_text: msg_lines,
+                bot: bot,
                 is_mention: is_mention,
+                is_room_mention: is_room_mention,
+                receipt: receipt,
                 reactions: reactions_list,
                 state: state,
                 chat: chat_data.read().active_chat.id(),
@@ -662,6 +997,8 @@ fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
                     ch.send(MessagesCommand::React((user_did.clone(), message.inner.clone(), emoji)));
                 },
                 pending: cx.props.pending,
+                queued: cx.props.pending && state.read().is_queued(&message.inner.id()),
+                send_failed: cx.props.pending && state.read().queued_message_gave_up(&message.inner.id()),
                 pinned: message.inner.pinned(),
                 attachments_pending_uploads: pending_uploads,
                 on_resend: move |(txt, file): (Option<String>, FileLocation)|{
@@ -700,6 +1037,10 @@ fn render_message<'a>(cx: Scope<'a, MessageProps<'a>>) -> Element<'a> {
                     if  message.inner.lines() == msg || !msg.iter().any(|x| !x.trim().is_empty()) {
                         return;
                     }
+                    if cx.props.pending {
+                        state.write().mutate(Action::EditQueuedMessage(message.inner.id(), msg));
+                        return;
+                    }
                     ch.send(MessagesCommand::EditMessage { conv_id: message.inner.conversation_id(), msg_id: message.inner.id(), msg})
                 }
             },
@@ -0,0 +1,76 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    state::{ui::EmojiDestination, Action, State},
+};
+use dioxus::prelude::*;
+use kit::elements::{
+    button::Button,
+    tooltip::{ArrowPosition, Tooltip},
+    Appearance,
+};
+use warp::raygun;
+
+use super::MessagesCommand;
+
+// the most used emojis offered next to a message
+const QUICK_REACTIONS: usize = 3;
+
+#[derive(Props)]
+pub struct QuickReactionsProps<'a> {
+    message: &'a raygun::Message,
+    remote: bool,
+    // called once a reaction was picked
+    ondone: EventHandler<'a, ()>,
+}
+
+// shown when hovering or long pressing a message. reacts with one of the most used emojis, or
+// opens the emoji picker for the others
+#[allow(non_snake_case)]
+pub fn QuickReactions<'a>(cx: Scope<'a, QuickReactionsProps<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let ch = use_coroutine_handle::<MessagesCommand>(cx)?;
+    let emojis = state.read().ui.emojis.get_sorted_vec(Some(QUICK_REACTIONS));
+    let has_picker = state
+        .read()
+        .ui
+        .extensions
+        .enabled_extension("emoji_selector");
+    let message = cx.props.message;
+
+    cx.render(rsx!(div {
+        class: format_args!("quick-reactions {}", if cx.props.remote { "remote" } else { "" }),
+        aria_label: "quick-reactions",
+        emojis.into_iter().map(|(emoji, _)| rsx!(Button {
+            key: "{emoji}",
+            text: emoji.clone(),
+            aria_label: "quick-reaction".into(),
+            appearance: Appearance::Transparent,
+            small: true,
+            onpress: move |_| {
+                let own_id = state.read().did_key();
+                ch.send(MessagesCommand::React((own_id, message.clone(), emoji.clone())));
+                cx.props.ondone.call(());
+            },
+        })),
+        Button {
+            icon: Icon::FaceSmile,
+            aria_label: "quick-reaction-picker".into(),
+            appearance: Appearance::Transparent,
+            small: true,
+            disabled: !has_picker,
+            tooltip: cx.render(rsx!(Tooltip {
+                arrow_position: ArrowPosition::Bottom,
+                text: if has_picker { get_local_text("messages.react") } else { get_local_text("messages.missing-emoji-picker") },
+            })),
+            onpress: move |_| {
+                state.write().ui.ignore_focus = true;
+                state.write().mutate(Action::SetEmojiDestination(Some(
+                    EmojiDestination::Message(message.conversation_id(), message.id()),
+                )));
+                state.write().mutate(Action::SetEmojiPickerVisible(true));
+                cx.props.ondone.call(());
+            },
+        },
+    }))
+}
@@ -4,4 +4,5 @@ pub mod context_menus;
 pub mod messages;
 pub mod quick_profile;
 pub mod sidebar;
+pub mod split_chat;
 pub mod welcome;
@@ -27,13 +27,15 @@ use warp::{crypto::DID, error::Error};
 use tracing::log;
 
 use crate::{
-    components::{friends::friends_list::ShareFriendsModal, settings::sidebar::Page},
+    components::{
+        friends::friends_list::ShareFriendsModal,
+        media::call_grid::{USER_VOL_MAX, USER_VOL_MIN},
+        profile_details::ProfileDetails,
+        settings::sidebar::Page,
+    },
     UplinkRoute,
 };
 
-pub const USER_VOL_MIN: f32 = 0.25;
-pub const USER_VOL_MAX: f32 = 5.0;
-
 #[derive(Props)]
 pub struct QuickProfileProps<'a> {
     id: &'a String,
@@ -97,8 +99,10 @@ pub fn QuickProfileContext<'a>(cx: Scope<'a, QuickProfileProps<'a>>) -> Element<
     let is_friend = state.read().has_friend_with_did(did);
     let in_vc = state
         .read()
-        .get_active_chat()
-        .map(|call| call.participants.contains(did))
+        .ui
+        .call_info
+        .active_call()
+        .map(|active| active.call.participants_joined.contains_key(did))
         .unwrap_or_default();
     let blocked = state.read().is_blocked(did);
     let volume = state
@@ -384,7 +388,7 @@ pub fn QuickProfileContext<'a>(cx: Scope<'a, QuickProfileProps<'a>>) -> Element<
                         format!("{}", if identity.username().is_empty() { get_local_text("uplink.unknown") } else { identity.username() })
                     }
                 }
-                identity.status_message().and_then(|s|{
+                identity.custom_status().map(|s| s.shown()).and_then(|s|{
                     cx.render(rsx!(
                         div {
                             id: "profile-status",
@@ -398,6 +402,9 @@ pub fn QuickProfileContext<'a>(cx: Scope<'a, QuickProfileProps<'a>>) -> Element<
                     ))
                 }),
             }
+            ProfileDetails {
+                did_key: identity.did_key(),
+            },
             div {
                 class: "profile-context-items",
                 if is_self {
@@ -413,7 +420,7 @@ pub fn QuickProfileContext<'a>(cx: Scope<'a, QuickProfileProps<'a>>) -> Element<
                     })
                 } else {
                     rsx!(
-                    if state.read().configuration.developer.experimental_features && in_vc {
+                    if in_vc {
                         rsx!(
                             div {
                                 class: "range-container",
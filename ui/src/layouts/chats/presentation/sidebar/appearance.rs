@@ -0,0 +1,212 @@
+use common::icons::outline::Shape as Icon;
+use common::language::get_local_text;
+use common::state::chats::ChatAppearance;
+use common::state::{Action, State, ToastNotification};
+use dioxus::prelude::*;
+use kit::components::swatch::ColorSwatch;
+use kit::elements::{
+    button::Button,
+    tooltip::{ArrowPosition, Tooltip},
+    Appearance,
+};
+use rfd::FileDialog;
+use tracing::log;
+use uuid::Uuid;
+
+use crate::components::settings::SettingSectionSimple;
+use crate::utils::wallpaper;
+
+const BACKGROUND_COLORS: [(u8, u8, u8); 6] = [
+    (24, 26, 35),    // Night
+    (33, 47, 61),    // Slate
+    (30, 61, 50),    // Forest
+    (70, 36, 56),    // Plum
+    (240, 236, 226), // Paper
+    (224, 238, 250), // Sky
+];
+
+// the accent colors offered in the general settings
+const BUBBLE_COLORS: [(u8, u8, u8); 8] = [
+    (255, 95, 87),
+    (254, 163, 127),
+    (255, 234, 167),
+    (85, 239, 196),
+    (24, 220, 255),
+    (162, 155, 254),
+    (253, 167, 223),
+    (210, 218, 226),
+];
+
+#[derive(Props, PartialEq)]
+pub struct Props {
+    chat_id: Uuid,
+}
+
+// background and bubble color of a single conversation
+#[allow(non_snake_case)]
+pub fn AppearanceSettings(cx: Scope<Props>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let chat_id = cx.props.chat_id;
+    let appearance = state.read().chats().appearance(&chat_id);
+    // the bubble is only left out while it would blend into the background
+    let blends_in = appearance.bubble.is_some() && appearance.bubble_color().is_none();
+    let wallpaper_name = appearance
+        .wallpaper
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string());
+
+    let set_appearance = move |f: &dyn Fn(&mut ChatAppearance)| {
+        let mut appearance = state.read().chats().appearance(&chat_id);
+        f(&mut appearance);
+        state
+            .write()
+            .mutate(Action::SetChatAppearance(chat_id, appearance));
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "appearance-settings",
+            aria_label: "appearance-settings",
+            div {
+                class: "settings",
+                SettingSectionSimple {
+                    aria_label: "chat-background".into(),
+                    p {
+                        get_local_text("messages.chat-background")
+                    }
+                    div {
+                        class: "color-swatches",
+                        Button {
+                            icon: Icon::NoSymbol,
+                            aria_label: "chat-background-clear".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| set_appearance(&|a| a.background = None),
+                            tooltip: cx.render(rsx!(Tooltip {
+                                arrow_position: ArrowPosition::Right,
+                                text: get_local_text("messages.theme-color"),
+                            }))
+                        },
+                        for color in BACKGROUND_COLORS {
+                            ColorSwatch {
+                                color: color,
+                                active: appearance.background == Some(color),
+                                onpress: move |_| set_appearance(&|a| a.background = Some(color)),
+                            }
+                        }
+                    }
+                },
+                SettingSectionSimple {
+                    aria_label: "chat-wallpaper".into(),
+                    p {
+                        get_local_text("messages.chat-wallpaper")
+                    }
+                    div {
+                        class: "wallpaper-picker",
+                        wallpaper_name.as_ref().map(|name| rsx!(p {
+                            class: "wallpaper-name",
+                            "{name}"
+                        })),
+                        Button {
+                            icon: Icon::Photo,
+                            text: get_local_text("messages.pick-wallpaper"),
+                            aria_label: "chat-wallpaper-pick".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| {
+                                let Some(path) = FileDialog::new()
+                                    .add_filter("image", &["jpg", "jpeg", "png", "webp", "gif"])
+                                    .pick_file() else {
+                                    return;
+                                };
+                                if wallpaper::is_too_large(&path) {
+                                    state.write().mutate(Action::AddToastNotification(ToastNotification::init(
+                                        "".into(),
+                                        get_local_text("messages.wallpaper-too-large"),
+                                        None,
+                                        3,
+                                    )));
+                                    return;
+                                }
+                                // the bubbles and the text have to stand out from the image
+                                let state = state.clone();
+                                cx.spawn(async move {
+                                    let color = {
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || wallpaper::average_color(&path)).await
+                                    };
+                                    let color = match color {
+                                        Ok(Ok(color)) => Some(color),
+                                        Ok(Err(e)) => {
+                                            log::warn!("failed to read the wallpaper {}: {e}", path.display());
+                                            None
+                                        }
+                                        Err(e) => {
+                                            log::error!("failed to read the wallpaper: {e}");
+                                            None
+                                        }
+                                    };
+                                    let mut appearance = state.read().chats().appearance(&chat_id);
+                                    appearance.wallpaper = Some(path);
+                                    appearance.wallpaper_color = color;
+                                    state.write().mutate(Action::SetChatAppearance(chat_id, appearance));
+                                });
+                            },
+                        },
+                        wallpaper_name.is_some().then(|| rsx!(Button {
+                            icon: Icon::XMark,
+                            aria_label: "chat-wallpaper-remove".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| set_appearance(&|a| {
+                                a.wallpaper = None;
+                                a.wallpaper_color = None;
+                            }),
+                        })),
+                    }
+                },
+                SettingSectionSimple {
+                    aria_label: "chat-bubble".into(),
+                    p {
+                        get_local_text("messages.chat-bubble")
+                    }
+                    div {
+                        class: "color-swatches",
+                        Button {
+                            icon: Icon::NoSymbol,
+                            aria_label: "chat-bubble-clear".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| set_appearance(&|a| a.bubble = None),
+                            tooltip: cx.render(rsx!(Tooltip {
+                                arrow_position: ArrowPosition::Right,
+                                text: get_local_text("messages.theme-color"),
+                            }))
+                        },
+                        for color in BUBBLE_COLORS {
+                            ColorSwatch {
+                                color: color,
+                                active: appearance.bubble == Some(color),
+                                onpress: move |_| set_appearance(&|a| a.bubble = Some(color)),
+                            }
+                        }
+                    }
+                },
+                blends_in.then(|| rsx!(p {
+                    class: "appearance-warning",
+                    aria_label: "chat-bubble-low-contrast",
+                    get_local_text("messages.bubble-low-contrast")
+                })),
+            },
+            Button {
+                icon: Icon::ArrowPath,
+                text: get_local_text("messages.reset-appearance"),
+                aria_label: "chat-appearance-reset".into(),
+                appearance: Appearance::Secondary,
+                disabled: appearance == ChatAppearance::default(),
+                onpress: move |_| {
+                    state
+                        .write()
+                        .mutate(Action::SetChatAppearance(chat_id, ChatAppearance::default()));
+                },
+            },
+        }
+    ))
+}
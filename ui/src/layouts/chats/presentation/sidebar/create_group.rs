@@ -1,12 +1,11 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 
 use crate::{layouts::chats::data::get_input_options, UplinkRoute};
 use common::{
     icons::outline::Shape as Icon,
     language::get_local_text,
     state::{Action, Identity, State, ToastNotification},
-    warp_runner::{RayGunCmd, WarpCmd},
-    WARP_CMD_CH,
+    warp_runner::{send_batch, BatchStep, RayGunCmd, WarpCmd},
 };
 use dioxus::prelude::*;
 use dioxus_router::prelude::use_navigator;
@@ -62,35 +61,43 @@ pub fn CreateGroup<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
     let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<()>| {
         to_owned![selected_friends, chat_with, group_name];
         async move {
-            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
             while rx.next().await.is_some() {
                 let recipients: Vec<DID> = selected_friends.current().iter().cloned().collect();
                 let group_name: Option<String> = group_name.current().as_ref().clone();
                 let group_name_string = group_name.clone().unwrap_or_default();
-
-                let (tx, rx) = oneshot::channel();
-                let cmd = RayGunCmd::CreateGroupConversation {
-                    recipients,
-                    group_name: if group_name_string.is_empty()
-                        || group_name_string.chars().all(char::is_whitespace)
-                    {
-                        None
-                    } else {
-                        group_name
-                    },
-                    settings: GroupSettings::default(),
-                    rsp: tx,
+                let group_name = if group_name_string.is_empty()
+                    || group_name_string.chars().all(char::is_whitespace)
+                {
+                    None
+                } else {
+                    group_name
                 };
 
-                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd)) {
-                    log::error!("failed to send warp command: {}", e);
-                    continue;
-                }
-
-                let rsp = rx.await.expect("command canceled");
+                // creating the group also invites the recipients. if a step is added after it, the
+                // group is deleted again when that step fails
+                let steps = vec![BatchStep::new("create-group", move |_, rsp| {
+                    Ok(WarpCmd::RayGun(RayGunCmd::CreateGroupConversation {
+                        recipients,
+                        group_name,
+                        settings: GroupSettings::default(),
+                        rsp,
+                    }))
+                })
+                .on_rollback(|ctx| {
+                    let (rsp, _) = oneshot::channel();
+                    ctx.output::<Uuid>(0).map(|conv_id| {
+                        WarpCmd::RayGun(RayGunCmd::DeleteConversation {
+                            conv_id: *conv_id,
+                            rsp,
+                        })
+                    })
+                })];
 
-                let id = match rsp {
-                    Ok(c) => c,
+                let id = match send_batch("create-group", steps, |_| {}).await {
+                    Ok(ctx) => match ctx.output::<Uuid>(0) {
+                        Some(id) => *id,
+                        None => continue,
+                    },
                     Err(e) => {
                         log::error!("failed to create conversation: {}", e);
                         continue;
@@ -191,7 +198,7 @@ pub fn CreateGroup<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
 
 #[derive(PartialEq, Props)]
 pub struct FriendsProps {
-    friends: BTreeMap<char, Vec<Identity>>,
+    friends: Vec<(char, Vec<Identity>)>,
     name_prefix: UseState<String>,
     selected_friends: UseState<HashSet<DID>>,
 }
@@ -268,7 +275,7 @@ fn render_friend(cx: Scope<FriendProps>) -> Element {
             UserImage {
                 platform: cx.props.friend.platform().into(),
                 status: cx.props.friend.identity_status().into(),
-                image: cx.props.friend.profile_picture(),
+                image: cx.props.friend.avatar(),
                 on_press: move |_| {
                     update_fn();
                 },
@@ -0,0 +1,84 @@
+use common::{icons::outline::Shape as Icon, language::get_local_text};
+use dioxus::prelude::*;
+use kit::elements::{button::Button, label::Label, select::Select, switch::Switch, Appearance};
+use rfd::FileDialog;
+use uuid::Uuid;
+
+use crate::utils::conversation_export::{ExportFormat, ExportRequest, EXPORT_FORMATS};
+
+#[derive(Props)]
+pub struct Props<'a> {
+    conv_id: Uuid,
+    title: String,
+    onclose: EventHandler<'a, ()>,
+}
+
+// lets the user choose how the conversation is exported, then asks where to save it
+#[allow(non_snake_case)]
+pub fn ExportChat<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let ch = use_coroutine_handle::<ExportRequest>(cx)?;
+    let format = use_state(cx, || ExportFormat::Json);
+    let include_attachments = use_state(cx, || false);
+
+    cx.render(rsx!(
+        div {
+            id: "export-chat",
+            aria_label: "export-chat",
+            Label {
+                aria_label: "export-format-label".into(),
+                text: get_local_text("messages.export-format"),
+            },
+            Select {
+                initial_value: format.get().label(),
+                options: EXPORT_FORMATS.iter().map(|f| f.label()).collect(),
+                onselect: move |value: String| {
+                    if let Some(f) = EXPORT_FORMATS.iter().find(|f| f.label() == value) {
+                        format.set(*f);
+                    }
+                }
+            },
+            div {
+                class: "export-chat-attachments",
+                Label {
+                    aria_label: "export-attachments-label".into(),
+                    text: get_local_text("messages.export-attachments"),
+                },
+                Switch {
+                    active: *include_attachments.get(),
+                    onflipped: move |flag| include_attachments.set(flag),
+                }
+            },
+            Button {
+                aria_label: "export-chat-button".into(),
+                icon: Icon::ArrowDownTray,
+                text: get_local_text("messages.export"),
+                appearance: Appearance::Primary,
+                onpress: move |_| {
+                    let format = *format.get();
+                    let file_name = format!(
+                        "{}.{}",
+                        cx.props.title.replace(['/', '\\', ':'], "_"),
+                        format.extension()
+                    );
+                    let path = match FileDialog::new()
+                        .set_directory(dirs::download_dir().unwrap_or_else(|| ".".into()))
+                        .set_file_name(&file_name)
+                        .add_filter(&format.label(), &[format.extension()])
+                        .save_file()
+                    {
+                        Some(path) => path,
+                        None => return,
+                    };
+                    ch.send(ExportRequest {
+                        conv_id: cx.props.conv_id,
+                        title: cx.props.title.clone(),
+                        format,
+                        include_attachments: *include_attachments.get(),
+                        path,
+                    });
+                    cx.props.onclose.call(());
+                }
+            }
+        }
+    ))
+}
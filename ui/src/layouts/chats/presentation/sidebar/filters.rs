@@ -0,0 +1,42 @@
+use common::language::get_local_text;
+use common::state::ui::SidebarFilter;
+use common::state::{Action, State};
+use dioxus::prelude::*;
+use kit::elements::{button::Button, Appearance};
+
+fn filter_label(filter: SidebarFilter) -> String {
+    get_local_text(match filter {
+        SidebarFilter::All => "sidebar.filter-all",
+        SidebarFilter::Unread => "sidebar.filter-unread",
+        SidebarFilter::Groups => "sidebar.filter-groups",
+        SidebarFilter::Direct => "sidebar.filter-direct",
+        SidebarFilter::Favorites => "sidebar.filter-favorites",
+    })
+}
+
+// chips choosing which conversations the sidebar shows
+#[allow(non_snake_case)]
+pub fn SidebarFilters(cx: Scope) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let active = state.read().ui.sidebar_filter;
+    // the unread messages which can't be seen with the filter, on the chip showing everything
+    let hidden_unreads = state.read().filtered_unread_count();
+
+    cx.render(rsx!(div {
+        class: "sidebar-filters",
+        aria_label: "sidebar-filters",
+        role: "group",
+        SidebarFilter::ALL.into_iter().map(|filter| rsx!(Button {
+            key: "{filter:?}",
+            text: filter_label(filter),
+            aria_label: format!("sidebar-filter-{filter:?}").to_lowercase(),
+            small: true,
+            pressed: filter == active,
+            appearance: if filter == active { Appearance::Primary } else { Appearance::Secondary },
+            with_badge: if filter == SidebarFilter::All && hidden_unreads > 0 { hidden_unreads.to_string() } else { String::new() },
+            onpress: move |_| {
+                state.write().mutate(Action::SetSidebarFilter(filter));
+            },
+        }))
+    }))
+}
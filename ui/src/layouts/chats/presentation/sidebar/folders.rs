@@ -0,0 +1,187 @@
+use common::icons::outline::Shape as Icon;
+use common::language::get_local_text;
+use common::state::chat_folders::ChatFolder;
+use common::state::{Action, Chat, State};
+use dioxus::prelude::*;
+use kit::components::context_menu::{ContextItem, ContextMenu};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    Appearance,
+};
+use uuid::Uuid;
+
+/// A row of the chat sidebar
+#[derive(Clone)]
+pub enum SidebarRow {
+    // the folder with the unreads of its chats
+    Folder(ChatFolder, u32),
+    // the chat with the folder it is in
+    Chat(Chat, Option<Uuid>),
+}
+
+/// The chats grouped by folder. Every folder comes with its chats unless it is collapsed, then the
+/// chats which aren't in a folder
+pub fn sidebar_rows(state: &State, chats: Vec<Chat>) -> Vec<SidebarRow> {
+    let folders = state.ui.chat_folders.all();
+    let mut rows = vec![];
+    for folder in folders {
+        let in_folder: Vec<&Chat> = chats
+            .iter()
+            .filter(|chat| folder.chats.contains(&chat.id))
+            .collect();
+        let unreads = in_folder
+            .iter()
+            .filter(|chat| !state.chats().notification_rules(&chat.id).is_muted())
+            .map(|chat| chat.unreads())
+            .sum();
+        rows.push(SidebarRow::Folder(folder.clone(), unreads));
+        if !folder.collapsed {
+            rows.extend(
+                in_folder
+                    .into_iter()
+                    .map(|chat| SidebarRow::Chat(chat.clone(), Some(folder.id))),
+            );
+        }
+    }
+    rows.extend(
+        chats
+            .into_iter()
+            .filter(|chat| !folders.iter().any(|f| f.chats.contains(&chat.id)))
+            .map(|chat| SidebarRow::Chat(chat, None)),
+    );
+    rows
+}
+
+/// What the folder name modal is for
+#[derive(Clone, PartialEq)]
+pub enum FolderEdit {
+    Create,
+    Rename(Uuid, String),
+}
+
+#[derive(Props)]
+pub struct FolderNameProps<'a> {
+    edit: FolderEdit,
+    ondone: EventHandler<'a, ()>,
+}
+
+#[allow(non_snake_case)]
+pub fn FolderName<'a>(cx: Scope<'a, FolderNameProps<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let initial = match &cx.props.edit {
+        FolderEdit::Create => String::new(),
+        FolderEdit::Rename(_, name) => name.clone(),
+    };
+    let name = use_ref(cx, || initial.clone());
+
+    let save = move || {
+        let name = name.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        state.write().mutate(match &cx.props.edit {
+            FolderEdit::Create => Action::CreateChatFolder(name),
+            FolderEdit::Rename(id, _) => Action::RenameChatFolder(*id, name),
+        });
+        cx.props.ondone.call(());
+    };
+
+    cx.render(rsx!(div {
+        id: "chat-folder-name",
+        aria_label: "chat-folder-name",
+        Input {
+            placeholder: get_local_text("messages.folder-name"),
+            default_text: initial,
+            aria_label: "chat-folder-name-input".into(),
+            focus: true,
+            options: Options {
+                with_clear_btn: true,
+                react_to_esc_key: true,
+                ..Options::default()
+            },
+            onchange: move |(v, _): (String, bool)| {
+                *name.write_silent() = v;
+            },
+            onreturn: move |_| save(),
+        },
+        Button {
+            text: get_local_text("messages.save-folder"),
+            aria_label: "chat-folder-save".into(),
+            onpress: move |_| save(),
+        },
+    }))
+}
+
+#[derive(Props)]
+pub struct FolderHeaderProps<'a> {
+    folder: ChatFolder,
+    unreads: u32,
+    // the chat being dragged in the sidebar
+    dragged: UseRef<Option<Uuid>>,
+    onrename: EventHandler<'a, ()>,
+}
+
+#[allow(non_snake_case)]
+pub fn FolderHeader<'a>(cx: Scope<'a, FolderHeaderProps<'a>>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let drag_over = use_state(cx, || false);
+    let folder = &cx.props.folder;
+    let id = folder.id;
+    let badge = (cx.props.unreads > 0).then(|| cx.props.unreads.to_string());
+
+    cx.render(rsx!(ContextMenu {
+        id: format!("{id}-folder"),
+        devmode: state.read().configuration.developer.developer_mode,
+        items: cx.render(rsx!(
+            ContextItem {
+                icon: Icon::PencilSquare,
+                aria_label: "chat-folder-rename".into(),
+                text: get_local_text("messages.rename-folder"),
+                onpress: move |_| cx.props.onrename.call(()),
+            },
+            ContextItem {
+                icon: Icon::Trash,
+                danger: true,
+                aria_label: "chat-folder-delete".into(),
+                text: get_local_text("messages.delete-folder"),
+                onpress: move |_| state.write().mutate(Action::DeleteChatFolder(id)),
+            },
+        )),
+        div {
+            class: format_args!("sidebar-folder {}", if *drag_over.get() { "drag-over" } else { "" }),
+            aria_label: "sidebar-folder",
+            aria_expanded: "{!folder.collapsed}",
+            prevent_default: "ondragover ondrop",
+            onclick: move |_| state.write().mutate(Action::ToggleChatFolder(id)),
+            ondragover: move |_| {
+                if cx.props.dragged.read().is_some() && !*drag_over.get() {
+                    drag_over.set(true);
+                }
+            },
+            ondragleave: move |_| drag_over.set(false),
+            ondrop: move |_| {
+                drag_over.set(false);
+                if let Some(chat_id) = cx.props.dragged.write_silent().take() {
+                    state.write().mutate(Action::MoveChatToFolder(chat_id, Some(id)));
+                }
+            },
+            Button {
+                icon: if folder.collapsed { Icon::ChevronRight } else { Icon::ChevronDown },
+                aria_label: "chat-folder-toggle".into(),
+                appearance: Appearance::Transparent,
+                small: true,
+                // the click goes on to the folder, which toggles it
+            },
+            p {
+                class: "sidebar-folder-name",
+                "{folder.name}"
+            },
+            badge.map(|badge| rsx!(span {
+                class: "sidebar-folder-badge",
+                aria_label: "sidebar-folder-unreads",
+                "{badge}"
+            })),
+        }
+    }))
+}
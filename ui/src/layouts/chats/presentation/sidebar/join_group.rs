@@ -0,0 +1,124 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    state::{Action, State, ToastNotification},
+    utils::handoff::Handoff,
+    warp_runner::{InviteLink, RayGunCmd, WarpCmd},
+    WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use futures::{channel::oneshot, StreamExt};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    label::Label,
+    Appearance,
+};
+use tracing::log;
+
+#[derive(Props)]
+pub struct Props<'a> {
+    onjoin: EventHandler<'a, ()>,
+    // a handoff link from another device was pasted instead of an invite
+    onopen: EventHandler<'a, Handoff>,
+}
+
+// asks the creator of an invite link to add the user to their group. also opens handoff links
+#[allow(non_snake_case)]
+pub fn JoinGroup<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    log::trace!("rendering join_group");
+    let state = use_shared_state::<State>(cx)?;
+    let link = use_state(cx, String::new);
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<String>| {
+        to_owned![state];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some(link) = rx.next().await {
+                let text = if link.parse::<InviteLink>().is_err() {
+                    get_local_text("messages.invalid-invite")
+                } else {
+                    let (tx, rx) = oneshot::channel();
+                    if let Err(e) =
+                        warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::JoinGroupViaInvite {
+                            link,
+                            rsp: tx,
+                        }))
+                    {
+                        log::error!("failed to send warp command: {}", e);
+                        continue;
+                    }
+                    match rx.await.expect("command canceled") {
+                        Ok(_) => get_local_text("messages.join-request-sent"),
+                        Err(e) => {
+                            log::error!("failed to join group: {e}");
+                            match e {
+                                warp::error::Error::IdentityDoesntExist => {
+                                    get_local_text("messages.invite-inviter-not-found")
+                                }
+                                warp::error::Error::PublicKeyIsBlocked => {
+                                    get_local_text("messages.invite-inviter-blocked")
+                                }
+                                _ => get_local_text("messages.join-request-failed"),
+                            }
+                        }
+                    }
+                };
+                state
+                    .write()
+                    .mutate(Action::AddToastNotification(ToastNotification::init(
+                        "".into(),
+                        text,
+                        None,
+                        2,
+                    )));
+            }
+        }
+    });
+
+    let submit = move |link: String| match link.parse::<Handoff>() {
+        Ok(handoff) => cx.props.onopen.call(handoff),
+        Err(_) => {
+            ch.send(link);
+            cx.props.onjoin.call(());
+        }
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "join-group",
+            aria_label: "join-group",
+            Label {
+                aria_label: "invite-link-label".into(),
+                text: get_local_text("messages.invite-link"),
+            },
+            Input {
+                placeholder: "uplink://join/...".into(),
+                aria_label: "invite-link-input".into(),
+                focus_just_on_render: true,
+                options: Options {
+                    with_clear_btn: true,
+                    ..Options::default()
+                },
+                onchange: move |(v, _): (String, _)| {
+                    link.set(v);
+                },
+                onreturn: move |(v, _, _): (String, bool, _)| {
+                    if !v.trim().is_empty() {
+                        submit(v);
+                    }
+                },
+            },
+            Button {
+                aria_label: "join-group-button".into(),
+                icon: Icon::Link,
+                text: get_local_text("messages.join-group"),
+                appearance: Appearance::Primary,
+                disabled: link.get().trim().is_empty(),
+                onpress: move |_| {
+                    submit(link.get().clone());
+                }
+            }
+        }
+    ))
+}
@@ -1,14 +1,26 @@
+mod appearance;
 mod create_group;
+mod export_chat;
+mod filters;
+mod folders;
+mod join_group;
+mod new_message;
+mod notification_settings;
 mod search;
+mod webhooks;
 
 use common::language::{get_local_text, get_local_text_with_args};
 use common::state::ui::Layout;
-use common::state::{self, identity_search_result, Action, Chat, Identity, State};
-use common::warp_runner::{RayGunCmd, WarpCmd};
+use common::state::{
+    self, identity_search_result, Action, Chat, Identity, State, ToastNotification,
+};
+use common::utils::handoff::Handoff;
+use common::warp_runner::{ui_adapter, CancelToken, RayGunCmd, WarpCmd};
 use common::{icons::outline::Shape as Icon, WARP_CMD_CH};
 use dioxus::html::input_data::keyboard_types::Code;
 use dioxus::prelude::*;
 use dioxus_router::prelude::use_navigator;
+use extensions::context_menu::MenuContext;
 use futures::channel::oneshot;
 use futures::StreamExt;
 use kit::components::message::format_text;
@@ -39,16 +51,33 @@ use warp::{
 
 use tracing::log;
 
+use crate::components::extension_menu_items::ExtensionMenuItems;
 use crate::components::file_transfer::FileTransferModal;
 use crate::components::media::calling::CallControl;
 
+use crate::layouts::chats::presentation::sidebar::appearance::AppearanceSettings;
 use crate::layouts::chats::presentation::sidebar::create_group::CreateGroup;
+use crate::layouts::chats::presentation::sidebar::export_chat::ExportChat;
+use crate::layouts::chats::presentation::sidebar::filters::SidebarFilters;
+use crate::layouts::chats::presentation::sidebar::folders::{
+    sidebar_rows, FolderEdit, FolderHeader, FolderName, SidebarRow,
+};
+use crate::layouts::chats::presentation::sidebar::join_group::JoinGroup;
+use crate::layouts::chats::presentation::sidebar::new_message::NewMessage;
+use crate::layouts::chats::presentation::sidebar::notification_settings::NotificationSettings;
+use crate::layouts::chats::presentation::sidebar::webhooks::WebhookSettings;
 use crate::utils::build_participants;
+use crate::utils::extension_api::ExtensionView;
+use crate::utils::profiling;
 use crate::UplinkRoute;
 
+// messages shown in the search dropdown
+const MESSAGE_SEARCH_RESULTS: usize = 5;
+
 #[allow(clippy::large_enum_variant)]
 enum MessagesCommand {
     CreateConversation { recipient: DID },
+    StartConversation { recipient: DID },
     DeleteConversation { conv_id: Uuid },
 }
 
@@ -60,10 +89,12 @@ pub struct SidebarProps {
 #[allow(non_snake_case)]
 pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
     log::trace!("rendering chats sidebar layout");
+    profiling::count_render("Sidebar");
     let state = use_shared_state::<State>(cx)?;
     let search_results = use_state(cx, Vec::<identity_search_result::Entry>::new);
     let search_results_friends_identities = use_state(cx, Vec::<Identity>::new);
     let search_results_chats = use_state(cx, Vec::<Chat>::new);
+    let search_results_messages = use_state(cx, Vec::<ui_adapter::Message>::new);
     let chat_with: &UseState<Option<Uuid>> = use_state(cx, || None);
     let reset_searchbar = use_state(cx, || false);
     let router = use_navigator(cx);
@@ -79,7 +110,12 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
     }
 
     let ch = use_coroutine(cx, |rx: UnboundedReceiver<MessagesCommand>| {
-        conversation_coroutine(rx, chat_with.clone(), show_delete_conversation.clone())
+        conversation_coroutine(
+            rx,
+            state.clone(),
+            chat_with.clone(),
+            show_delete_conversation.clone(),
+        )
     });
 
     let select_identifier = move |id: identity_search_result::Identifier| match id {
@@ -99,22 +135,80 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
         }
     };
 
-    // todo: display a loading page if chats is not initialized
+    let chats_loaded = state.read().chats_loaded;
     let sidebar_chats = if state.read().initialized {
         state.read().chats_sidebar()
     } else {
         vec![]
     };
+    let has_chats = !sidebar_chats.is_empty();
+    let sidebar_chats: Vec<Chat> = sidebar_chats
+        .into_iter()
+        .filter(|chat| state.read().sidebar_filter_matches(chat))
+        .collect();
+    let filtered_out = has_chats && sidebar_chats.is_empty();
+    let sidebar_rows = sidebar_rows(&state.read(), sidebar_chats);
+    // the chat being dragged onto a folder
+    let dragged_chat: &UseRef<Option<Uuid>> = use_ref(cx, || None);
+    let folder_edit: &UseState<Option<FolderEdit>> = use_state(cx, || None);
 
     let show_create_group = use_state(cx, || false);
+    let show_join_group = use_state(cx, || false);
+    let show_new_message = use_state(cx, || false);
+    // the conversation being exported and its title
+    let exporting: &UseState<Option<(Uuid, String)>> = use_state(cx, || None);
+    let notification_settings: &UseState<Option<Uuid>> = use_state(cx, || None);
+    let appearance_settings: &UseState<Option<Uuid>> = use_state(cx, || None);
+    let webhook_settings: &UseState<Option<Uuid>> = use_state(cx, || None);
 
     let extensions = &state.read().ui.extensions;
     let ext_renders = extensions
         .values()
         .filter(|(_, ext)| ext.details().location == extensions::Location::Sidebar)
-        .map(|(_, ext)| rsx!(ext.render(cx.scope)))
+        .map(|(_, ext)| {
+            let name = ext.details().meta.name;
+            rsx!(ExtensionView {
+                key: "{name}",
+                name: name.to_string(),
+            })
+        })
         .collect::<Vec<_>>();
     let search_typed_chars = use_ref(cx, String::new);
+    // the token of the message search which is running, cancelled once the search changes
+    let search_token = use_ref(cx, CancelToken::new);
+    let message_search = use_coroutine(cx, |mut rx: UnboundedReceiver<String>| {
+        to_owned![search_results_messages, search_typed_chars, search_token];
+        async move {
+            while let Some(query) = rx.next().await {
+                // something else was typed before the search started
+                if *search_typed_chars.read() != query {
+                    continue;
+                }
+                let token = CancelToken::new();
+                *search_token.write_silent() = token.clone();
+                // the search stops if the sidebar goes away as well
+                let _guard = token.drop_guard();
+                let (tx, rx) = oneshot::channel();
+                let cmd = WarpCmd::RayGun(RayGunCmd::SearchMessages {
+                    query: query.clone(),
+                    limit: MESSAGE_SEARCH_RESULTS,
+                    rsp: tx,
+                })
+                .cancellable(token.clone());
+                if let Err(e) = WARP_CMD_CH.tx.send(cmd) {
+                    log::error!("failed to send warp command: {}", e);
+                    continue;
+                }
+                let Ok(messages) = rx.await else {
+                    continue;
+                };
+                // something else was typed in the meantime
+                if *search_typed_chars.read() == query {
+                    search_results_messages.set(messages);
+                }
+            }
+        }
+    });
     let transfer = if storage {
         cx.render(rsx!(FileTransferModal { state: state }))
     } else {
@@ -154,8 +248,11 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                             }
                         },
                         onchange: move |(v, _): (String, _)| {
+                            search_token.read().cancel();
+                            *search_typed_chars.write_silent() = v.clone();
                             if v.is_empty() {
                                 search_results.set(Vec::new());
+                                search_results_messages.set(Vec::new());
                                 *search_friends_is_focused.write_silent() = false;
                             } else {
                                 let (mut friends_entries, friends_identities) = state.read().search_identities(&v);
@@ -165,7 +262,7 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                                 search_results.set(friends_entries);
                                 search_results_friends_identities.set(friends_identities);
                                 search_results_chats.set(chats);
-                                *search_typed_chars.write_silent() = v;
+                                message_search.send(v);
                                 *search_friends_is_focused.write_silent() = true;
                                 on_search_dropdown_hover.with_mut(|i| *i = false);
                             }
@@ -181,6 +278,8 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                         state::ui::Layout::Friends => UplinkRoute::FriendsLayout {},
                         state::ui::Layout::Settings => UplinkRoute::SettingsLayout {},
                         state::ui::Layout::Storage => UplinkRoute::FilesLayout {},
+                        state::ui::Layout::Activity => UplinkRoute::ActivityLayout {},
+                        state::ui::Layout::Calls => UplinkRoute::CallsLayout {},
                     },
                     onnavigate: move |_| {
                         if state.read().configuration.audiovideo.interface_sounds {
@@ -205,10 +304,12 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                     identities: search_results.clone(),
                     friends_identities: search_results_friends_identities.clone(),
                     chats: search_results_chats.clone(),
+                    messages: search_results_messages.clone(),
                     search_dropdown_hover: on_search_dropdown_hover.clone(),
                     onclick: move |identifier: identity_search_result::Identifier| {
                         select_identifier(identifier);
                         search_results.set(Vec::new());
+                        search_results_messages.set(Vec::new());
                         reset_searchbar.set(true);
                         on_search_dropdown_hover.with_mut(|i| *i = false);
                     }
@@ -221,7 +322,7 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
             div {
                 id: "chats",
                 aria_label: "Chats",
-                (!sidebar_chats.is_empty()).then(|| rsx!(
+                has_chats.then(|| rsx!(
                     div {
                         class: "sidebar-chats-header",
                         Label {
@@ -241,8 +342,178 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                             onpress: move |_| {
                                 show_create_group.set(!show_create_group.get());
                             }
+                        },
+                        Button {
+                            appearance: if *show_join_group.get() { Appearance::Primary } else { Appearance::Secondary },
+                            aria_label: "join-group-chat".into(),
+                            icon: Icon::Link,
+                            tooltip: cx.render(rsx!(
+                                Tooltip {
+                                    arrow_position: ArrowPosition::Right,
+                                    text: get_local_text("messages.join-group")
+                                }
+                            )),
+                            onpress: move |_| {
+                                show_join_group.set(!show_join_group.get());
+                            }
+                        },
+                        Button {
+                            appearance: if folder_edit.get().as_ref() == Some(&FolderEdit::Create) { Appearance::Primary } else { Appearance::Secondary },
+                            aria_label: "create-chat-folder".into(),
+                            icon: Icon::FolderPlus,
+                            tooltip: cx.render(rsx!(
+                                Tooltip {
+                                    arrow_position: ArrowPosition::Right,
+                                    text: get_local_text("messages.new-folder")
+                                }
+                            )),
+                            onpress: move |_| {
+                                folder_edit.set(Some(FolderEdit::Create));
+                            }
+                        },
+                        Button {
+                            appearance: if *show_new_message.get() { Appearance::Primary } else { Appearance::Secondary },
+                            aria_label: "new-message".into(),
+                            icon: Icon::PencilSquare,
+                            tooltip: cx.render(rsx!(
+                                Tooltip {
+                                    arrow_position: ArrowPosition::Right,
+                                    text: get_local_text("messages.new-message")
+                                }
+                            )),
+                            onpress: move |_| {
+                                show_new_message.set(!show_new_message.get());
+                            }
                         }
                     }
+                    show_new_message.then(|| rsx!(
+                        Modal {
+                            class: "create-group-modal",
+                            open: *show_new_message.clone(),
+                            with_title: get_local_text("messages.new-message"),
+                            transparent: true,
+                            onclose: move |_| {
+                                show_new_message.set(false);
+                            },
+                            NewMessage {
+                                onstart: move |did: DID| {
+                                    show_new_message.set(false);
+                                    match state.read().get_chat_with_friend(did.clone()) {
+                                        Some(c) => chat_with.set(Some(c.id)),
+                                        None => ch.send(MessagesCommand::StartConversation { recipient: did }),
+                                    }
+                                }
+                            }
+                        }
+                    )),
+                    show_join_group.then(|| rsx!(
+                        Modal {
+                            class: "create-group-modal",
+                            open: *show_join_group.clone(),
+                            with_title: get_local_text("messages.join-group"),
+                            transparent: true,
+                            onclose: move |_| {
+                                show_join_group.set(false);
+                            },
+                            JoinGroup {
+                                onjoin: move |_| {
+                                    show_join_group.set(false);
+                                },
+                                onopen: move |handoff: Handoff| {
+                                    show_join_group.set(false);
+                                    // conversations take a moment to sync to a newly linked device
+                                    if state.read().get_chat_by_id(handoff.conversation_id).is_none() {
+                                        state.write().mutate(Action::AddToastNotification(ToastNotification::init(
+                                            "".into(),
+                                            get_local_text("messages.handoff-not-found"),
+                                            None,
+                                            2,
+                                        )));
+                                        return;
+                                    }
+                                    state.write_silent().ui.open_handoff(handoff);
+                                    chat_with.set(Some(handoff.conversation_id));
+                                }
+                            }
+                        }
+                    )),
+                    exporting.get().clone().map(|(conv_id, title)| rsx!(
+                        Modal {
+                            class: "create-group-modal",
+                            open: true,
+                            with_title: get_local_text("messages.export-chat"),
+                            transparent: true,
+                            onclose: move |_| {
+                                exporting.set(None);
+                            },
+                            ExportChat {
+                                conv_id: conv_id,
+                                title: title,
+                                onclose: move |_| {
+                                    exporting.set(None);
+                                }
+                            }
+                        }
+                    )),
+                    folder_edit.get().clone().map(|edit| rsx!(
+                        Modal {
+                            class: "create-group-modal",
+                            open: true,
+                            with_title: get_local_text(if edit == FolderEdit::Create { "messages.new-folder" } else { "messages.rename-folder" }),
+                            transparent: true,
+                            onclose: move |_| {
+                                folder_edit.set(None);
+                            },
+                            FolderName {
+                                edit: edit,
+                                ondone: move |_| {
+                                    folder_edit.set(None);
+                                }
+                            }
+                        }
+                    )),
+                    notification_settings.get().map(|chat_id| rsx!(
+                        Modal {
+                            class: "create-group-modal",
+                            open: true,
+                            with_title: get_local_text("messages.notification-settings"),
+                            transparent: true,
+                            onclose: move |_| {
+                                notification_settings.set(None);
+                            },
+                            NotificationSettings {
+                                chat_id: chat_id,
+                            }
+                        }
+                    )),
+                    appearance_settings.get().map(|chat_id| rsx!(
+                        Modal {
+                            class: "create-group-modal",
+                            open: true,
+                            with_title: get_local_text("messages.appearance"),
+                            transparent: true,
+                            onclose: move |_| {
+                                appearance_settings.set(None);
+                            },
+                            AppearanceSettings {
+                                chat_id: chat_id,
+                            }
+                        }
+                    )),
+                    webhook_settings.get().map(|chat_id| rsx!(
+                        Modal {
+                            class: "create-group-modal",
+                            open: true,
+                            with_title: get_local_text("messages.webhooks"),
+                            transparent: true,
+                            onclose: move |_| {
+                                webhook_settings.set(None);
+                            },
+                            WebhookSettings {
+                                chat_id: chat_id,
+                            }
+                        }
+                    )),
                     show_create_group.then(|| {
                         let clss = format!(
                             "create-group-modal {}",
@@ -269,7 +540,26 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                         }
                     )}),
                 )),
-                sidebar_chats.iter().cloned().map(|chat| {
+                has_chats.then(|| rsx!(SidebarFilters {})),
+                filtered_out.then(|| rsx!(p {
+                    class: "sidebar-filter-empty",
+                    aria_label: "sidebar-filter-empty",
+                    get_local_text("sidebar.filter-empty")
+                })),
+                sidebar_rows.iter().cloned().map(|row| {
+                    let (chat, folder_id) = match row {
+                        SidebarRow::Folder(folder, unreads) => {
+                            let (id, name) = (folder.id, folder.name.clone());
+                            return rsx!(FolderHeader {
+                                key: "{id}-folder",
+                                folder: folder,
+                                unreads: unreads,
+                                dragged: dragged_chat.clone(),
+                                onrename: move |_| folder_edit.set(Some(FolderEdit::Rename(id, name.clone()))),
+                            });
+                        }
+                        SidebarRow::Chat(chat, folder_id) => (chat, folder_id),
+                    };
                     let users_typing = chat.typing_indicator.iter().any(|(k, _)| *k != state.read().did_key());
                     let participants = state.read().chat_participants(&chat);
                     let other_participants =  state.read().remove_self(&participants);
@@ -294,16 +584,19 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                     let key = chat.id;
 
                     let is_active = state.read().get_active_chat().map(|c| c.id) == Some(chat.id);
+                    let is_pinned = state.read().is_favorite(&chat);
                     let chat_with = chat.clone();
                     let clear_unreads = chat.clone();
+                    let export_id = chat.id;
                     let markdown = false;
                     let should_transform_ascii_emojis = state.read().ui.should_transform_ascii_emojis();
 
                     // todo: how to tell who is participating in a group chat if the chat has a conversation_name?
-                    let participants_name = match chat.conversation_name {
+                    let participants_name = match state.read().chats().title(&chat) {
                         Some(name) => name,
                         None => State::join_usernames(&other_participants)
                     };
+                    let export_title = participants_name.clone();
 
                     let subtext_val = match unwrapped_message.lines().iter().map(|x| x.trim()).find(|x| !x.is_empty()) {
                         Some(v) => {
@@ -328,12 +621,75 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                     //     .lookup(&*APP_LANG.read(), "friends.block")
                     //     .unwrap_or_default();
 
+                    // the split view needs a conversation to go next to
+                    let can_split = !is_active
+                        && !state.read().ui.is_minimal_view()
+                        && state.read().chats().active.is_some()
+                        && state.read().ui.split_chat != Some(key);
+
+                    let folders: Vec<(Uuid, String)> = state
+                        .read()
+                        .ui
+                        .chat_folders
+                        .all()
+                        .iter()
+                        .filter(|f| Some(f.id) != folder_id)
+                        .map(|f| (f.id, f.name.clone()))
+                        .collect();
+
                     rsx!(
-                        ContextMenu {
+                        div {
                             key: "{key}-chat",
+                            class: format_args!("sidebar-chat {}", if folder_id.is_some() { "in-folder" } else { "" }),
+                            draggable: "true",
+                            prevent_default: "ondragover ondrop",
+                            ondragstart: move |_| {
+                                *dragged_chat.write_silent() = Some(key);
+                                // the chat layout shows where it can be dropped to split the view
+                                state.write().ui.dragged_chat = Some(key);
+                            },
+                            ondragend: move |_| {
+                                *dragged_chat.write_silent() = None;
+                                state.write().ui.dragged_chat = None;
+                            },
+                            // dropped onto a chat, it goes where that chat is
+                            ondrop: move |_| {
+                                if let Some(chat_id) = dragged_chat.write_silent().take() {
+                                    if chat_id != key {
+                                        state.write().mutate(Action::MoveChatToFolder(chat_id, folder_id));
+                                    }
+                                }
+                            },
+                        ContextMenu {
                             id: format!("{key}-chat"),
                             devmode: state.read().configuration.developer.developer_mode,
                             items: cx.render(rsx!(
+                                folders.into_iter().map(|(id, name)| rsx!(ContextItem {
+                                    key: "{id}",
+                                    icon: Icon::Folder,
+                                    aria_label: "chats-move-to-folder".into(),
+                                    text: get_local_text_with_args("messages.move-to-folder", vec![("folder", name)]),
+                                    onpress: move |_| {
+                                        state.write().mutate(Action::MoveChatToFolder(key, Some(id)));
+                                    }
+                                })),
+                                folder_id.is_some().then(|| rsx!(ContextItem {
+                                    icon: Icon::FolderMinus,
+                                    aria_label: "chats-remove-from-folder".into(),
+                                    text: get_local_text("messages.remove-from-folder"),
+                                    onpress: move |_| {
+                                        state.write().mutate(Action::MoveChatToFolder(key, None));
+                                    }
+                                })),
+                                can_split.then(|| rsx!(ContextItem {
+                                    icon: Icon::Window,
+                                    aria_label: "chats-open-in-split-view".into(),
+                                    text: get_local_text("messages.open-in-split-view"),
+                                    onpress: move |_| {
+                                        state.write().mutate(Action::SetSplitChat(Some(key)));
+                                        router.replace(UplinkRoute::ChatLayout {});
+                                    }
+                                })),
                                 ContextItem {
                                     icon: Icon::BellSlash,
                                     aria_label: "chats-clear-unreads".into(),
@@ -343,6 +699,46 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                                         state.write().mutate(Action::ClearUnreads(clear_unreads.id));
                                     }
                                 },
+                                ContextItem {
+                                    icon: if is_pinned { Icon::HeartSlash } else { Icon::Heart },
+                                    aria_label: if is_pinned { "chats-unpin".into() } else { "chats-pin".into() },
+                                    text: get_local_text(if is_pinned { "favorites.unpin" } else { "favorites.pin" }),
+                                    onpress: move |_| {
+                                        state.write().mutate(Action::ToggleFavorite(&key));
+                                    }
+                                },
+                                ContextItem {
+                                    icon: Icon::Bell,
+                                    aria_label: "chats-notification-settings".into(),
+                                    text: get_local_text("messages.notification-settings"),
+                                    onpress: move |_| {
+                                        notification_settings.set(Some(export_id));
+                                    }
+                                },
+                                ContextItem {
+                                    icon: Icon::PaintBrush,
+                                    aria_label: "chats-appearance".into(),
+                                    text: get_local_text("messages.appearance"),
+                                    onpress: move |_| {
+                                        appearance_settings.set(Some(export_id));
+                                    }
+                                },
+                                ContextItem {
+                                    icon: Icon::CommandLine,
+                                    aria_label: "chats-webhooks".into(),
+                                    text: get_local_text("messages.webhooks"),
+                                    onpress: move |_| {
+                                        webhook_settings.set(Some(export_id));
+                                    }
+                                },
+                                ContextItem {
+                                    icon: Icon::ArrowDownTray,
+                                    aria_label: "chats-export".into(),
+                                    text: get_local_text("messages.export-chat"),
+                                    onpress: move |_| {
+                                        exporting.set(Some((export_id, export_title.clone())));
+                                    }
+                                },
                                 ContextItem {
                                     icon: Icon::EyeSlash,
                                     aria_label: "chats-hide-chat".into(),
@@ -367,7 +763,10 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                                             }
                                         },
                                     )
-                                )
+                                ),
+                                ExtensionMenuItems {
+                                    context: MenuContext::Conversation { id: export_id },
+                                },
                             )),
                             User {
                                 aria_label: participants_name.clone(),
@@ -380,7 +779,7 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                                         UserImage {
                                             platform: platform,
                                             status:  user.identity_status().into(),
-                                            image: user.profile_picture(),
+                                            image: user.avatar(),
                                             typing: users_typing,
                                         }
                                     )} else {rsx! (
@@ -402,9 +801,11 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
                                 }
                             }
                         }
+                        }
                     )}
                 ),
-                sidebar_chats.is_empty().then(|| rsx!(
+                // the rest of the chats are still loading
+                (!chats_loaded).then(|| rsx!(
                     div {
                         class: "skeletal-steady",
                         User {
@@ -455,6 +856,7 @@ pub fn Sidebar(cx: Scope<SidebarProps>) -> Element {
 
 async fn conversation_coroutine(
     mut rx: UnboundedReceiver<MessagesCommand>,
+    state: UseSharedState<State>,
     chat_with: UseState<Option<Uuid>>,
     show_delete_conversation: UseRef<bool>,
 ) {
@@ -482,6 +884,32 @@ async fn conversation_coroutine(
                     }
                 };
             }
+            MessagesCommand::StartConversation { recipient } => {
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::StartConversation {
+                    recipient,
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send warp command: {}", e);
+                    continue;
+                }
+
+                match rx.await.expect("command canceled") {
+                    Ok(c) => chat_with.set(Some(c)),
+                    Err(e) => {
+                        log::error!("failed to start conversation: {}", e);
+                        let text = match e {
+                            warp::error::Error::IdentityDoesntExist => {
+                                get_local_text("messages.recipient-not-found")
+                            }
+                            _ => get_local_text("messages.start-conversation-failed"),
+                        };
+                        state.write().mutate(Action::AddToastNotification(
+                            ToastNotification::init("".into(), text, None, 2),
+                        ));
+                    }
+                };
+            }
             MessagesCommand::DeleteConversation { conv_id } => {
                 *show_delete_conversation.write_silent() = false;
                 let (tx, rx) = futures::channel::oneshot::channel();
@@ -0,0 +1,74 @@
+use std::str::FromStr;
+
+use common::{icons::outline::Shape as Icon, language::get_local_text, state::State};
+use dioxus::prelude::*;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    label::Label,
+    Appearance,
+};
+use tracing::log;
+use warp::crypto::DID;
+
+#[derive(Props)]
+pub struct Props<'a> {
+    onstart: EventHandler<'a, DID>,
+}
+
+// starts a conversation with a pasted DID, without becoming friends first
+#[allow(non_snake_case)]
+pub fn NewMessage<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    log::trace!("rendering new_message");
+    let state = use_shared_state::<State>(cx)?;
+    let did_input = use_state(cx, String::new);
+
+    let own_did = state.read().did_key();
+    let recipient = DID::from_str(did_input.get().trim())
+        .ok()
+        .filter(|did| did != &own_did);
+
+    cx.render(rsx!(
+        div {
+            id: "new-message",
+            aria_label: "new-message",
+            Label {
+                aria_label: "recipient-did-label".into(),
+                text: get_local_text("messages.recipient-did"),
+            },
+            Input {
+                placeholder: "did:key:...".into(),
+                aria_label: "recipient-did-input".into(),
+                focus_just_on_render: true,
+                options: Options {
+                    with_clear_btn: true,
+                    ..Options::default()
+                },
+                onchange: move |(v, _): (String, _)| {
+                    did_input.set(v);
+                },
+                onreturn: move |(v, _, _): (String, bool, _)| {
+                    match DID::from_str(v.trim()) {
+                        Ok(did) if did != state.read().did_key() => cx.props.onstart.call(did),
+                        _ => {}
+                    }
+                },
+            },
+            p {
+                get_local_text("messages.new-message-description"),
+            },
+            Button {
+                aria_label: "start-conversation-button".into(),
+                icon: Icon::ChatBubbleBottomCenterText,
+                text: get_local_text("messages.start-conversation"),
+                appearance: Appearance::Primary,
+                disabled: recipient.is_none(),
+                onpress: move |_| {
+                    if let Some(did) = recipient.clone() {
+                        cx.props.onstart.call(did);
+                    }
+                }
+            }
+        }
+    ))
+}
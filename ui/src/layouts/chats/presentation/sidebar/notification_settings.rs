@@ -0,0 +1,118 @@
+use chrono::{Duration, Local};
+use common::language::{get_local_text, get_local_text_with_args};
+use common::sounds::{self, Sounds, NOTIFICATION_SOUNDS};
+use common::state::chats::{MuteUntil, NotificationRules};
+use common::state::{Action, State};
+use dioxus::prelude::*;
+use kit::elements::{select::Select, switch::Switch};
+use uuid::Uuid;
+
+use crate::components::settings::SettingSectionSimple;
+
+// (label key, mute duration in hours). None mutes until the user turns notifications back on
+const MUTE_OPTIONS: [(&str, Option<i64>); 3] = [
+    ("messages.mute-hour", Some(1)),
+    ("messages.mute-eight-hours", Some(8)),
+    ("messages.mute-forever", None),
+];
+
+fn mute_label(muted: Option<MuteUntil>) -> String {
+    match muted {
+        Some(MuteUntil::Time(time)) => get_local_text_with_args(
+            "messages.muted-until",
+            vec![(
+                "time",
+                time.with_timezone(&Local).format("%H:%M").to_string(),
+            )],
+        ),
+        Some(MuteUntil::Forever) => get_local_text("messages.mute-forever"),
+        None => get_local_text("messages.not-muted"),
+    }
+}
+
+#[derive(Props, PartialEq)]
+pub struct Props {
+    chat_id: Uuid,
+}
+
+// mute, mention only mode and sound of a single conversation
+#[allow(non_snake_case)]
+pub fn NotificationSettings(cx: Scope<Props>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let chat_id = cx.props.chat_id;
+    let rules = state.read().chats().notification_rules(&chat_id);
+    // an expired mute is shown as not muted
+    let muted = rules.muted.filter(|_| rules.is_muted());
+
+    let set_rules = move |f: &dyn Fn(&mut NotificationRules)| {
+        let mut rules = rules;
+        f(&mut rules);
+        state
+            .write()
+            .mutate(Action::SetNotificationRules(chat_id, rules));
+    };
+
+    let mut mute_options = vec![get_local_text("messages.not-muted")];
+    mute_options.extend(MUTE_OPTIONS.iter().map(|(key, _)| get_local_text(key)));
+
+    cx.render(rsx!(
+        div {
+            id: "notification-settings",
+            aria_label: "notification-settings",
+            div {
+                class: "settings",
+                SettingSectionSimple {
+                    aria_label: "mute-conversation".into(),
+                    p {
+                        get_local_text("messages.mute-conversation")
+                    }
+                    Select {
+                        initial_value: mute_label(muted),
+                        options: mute_options,
+                        onselect: move |value: String| {
+                            let muted = MUTE_OPTIONS
+                                .iter()
+                                .find(|(key, _)| get_local_text(key) == value)
+                                .map(|(_, hours)| match hours {
+                                    Some(hours) => MuteUntil::from_duration(Duration::hours(*hours)),
+                                    None => MuteUntil::Forever,
+                                });
+                            set_rules(&|rules| rules.muted = muted);
+                        }
+                    }
+                },
+                SettingSectionSimple {
+                    aria_label: "mentions-only".into(),
+                    p {
+                        get_local_text("messages.mentions-only")
+                    }
+                    Switch {
+                        active: rules.mentions_only,
+                        onflipped: move |flag| {
+                            set_rules(&|rules| rules.mentions_only = flag);
+                        }
+                    }
+                },
+                SettingSectionSimple {
+                    aria_label: "notification-sound".into(),
+                    p {
+                        get_local_text("messages.notification-sound")
+                    }
+                    Select {
+                        initial_value: rules.sound.unwrap_or(Sounds::Notification).label(),
+                        options: NOTIFICATION_SOUNDS.iter().map(|sound| sound.label()).collect(),
+                        onselect: move |value: String| {
+                            let sound = NOTIFICATION_SOUNDS
+                                .iter()
+                                .copied()
+                                .find(|sound| sound.label() == value)
+                                .filter(|sound| *sound != Sounds::Notification);
+                            sounds::Play(sound.unwrap_or(Sounds::Notification));
+                            set_rules(&|rules| rules.sound = sound);
+                        }
+                    }
+                },
+            }
+        }
+    ))
+}
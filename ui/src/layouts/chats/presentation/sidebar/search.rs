@@ -1,7 +1,8 @@
 use common::icons::outline::Shape as Icon;
 use common::icons::Icon as IconElement;
-use common::language::get_local_text;
+use common::language::{collation, get_local_text};
 use common::state::{identity_search_result, Chat, Identity, State};
+use common::warp_runner::ui_adapter;
 use dioxus::prelude::*;
 use kit::components::{user_image::UserImage, user_image_group::UserImageGroup};
 
@@ -17,19 +18,24 @@ pub struct SearchProps<'a> {
     identities: UseState<Vec<identity_search_result::Entry>>,
     friends_identities: UseState<Vec<Identity>>,
     chats: UseState<Vec<Chat>>,
+    // found in the local copy of the messages
+    messages: UseState<Vec<ui_adapter::Message>>,
     onclick: EventHandler<'a, identity_search_result::Identifier>,
 }
 
 pub fn search_friends<'a>(cx: Scope<'a, SearchProps<'a>>) -> Element<'a> {
     let state = use_shared_state::<State>(cx)?;
-    if cx.props.identities.get().is_empty() || !*cx.props.search_friends_is_focused.read() {
+    let messages = cx.props.messages.get();
+    if (cx.props.identities.get().is_empty() && messages.is_empty())
+        || !*cx.props.search_friends_is_focused.read()
+    {
         return None;
     }
 
     let mut friends_identities = cx.props.friends_identities.get().clone();
     let chats = cx.props.chats.get().clone();
 
-    friends_identities.sort_by_key(|identity| identity.username());
+    collation::sort_by_name(&mut friends_identities, |identity| identity.username());
 
     cx.render(rsx!(
         div {
@@ -85,7 +91,7 @@ pub fn search_friends<'a>(cx: Scope<'a, SearchProps<'a>>) -> Element<'a> {
                         UserImage {
                             platform: identity.platform().into(),
                             status: identity.identity_status().into(),
-                            image: identity.profile_picture()
+                            image: identity.avatar()
                         },
                         div {
                             class: "search-friends-dropdown-name",
@@ -141,10 +147,7 @@ pub fn search_friends<'a>(cx: Scope<'a, SearchProps<'a>>) -> Element<'a> {
                 let participants2 = participants.clone();
 
                 let other_participants_names = State::join_usernames(&participants);
-                let conversation_title = match chat.conversation_name.as_ref() {
-                    Some(n) => n.clone(),
-                    None => other_participants_names,
-                };
+                let conversation_title = state.read().chats().title(&chat).unwrap_or(other_participants_names);
                 let search_typed_chars = cx.props.search_typed_chars.read().clone();
                 let text_to_find = search_typed_chars.to_lowercase();
                 let search_typed_chars2 = search_typed_chars.clone();
@@ -252,7 +255,7 @@ pub fn search_friends<'a>(cx: Scope<'a, SearchProps<'a>>) -> Element<'a> {
                                 UserImage {
                                     platform: identity.platform().into(),
                                     status: identity.identity_status().into(),
-                                    image: identity.profile_picture()
+                                    image: identity.avatar()
                                 },
                                 div {
                                     class: "search-friends-dropdown-name",
@@ -289,6 +292,52 @@ pub fn search_friends<'a>(cx: Scope<'a, SearchProps<'a>>) -> Element<'a> {
                     })
                 )
             })
+            if !messages.is_empty() && !(chats.is_empty() && friends_identities.is_empty()) {
+                rsx!(div { class:"border", })
+            }
+            if !messages.is_empty() {
+                rsx!(
+                    div {
+                        id: "messages-searchdropdown-label",
+                        class: "users-groups-label",
+                        aria_label: "users-groups-label",
+                        p {
+                            get_local_text("uplink.messages")
+                        }
+                    }
+                )
+            }
+            messages.iter().map(|message| {
+                let conv_id = message.inner.conversation_id();
+                let sender = state
+                    .read()
+                    .get_identity(&message.inner.sender())
+                    .map(|identity| identity.username())
+                    .unwrap_or_default();
+                let text = message.inner.lines().join(" ");
+                rsx!(
+                    div {
+                        key: "{message.key}",
+                        class: "identity-header-sidebar",
+                        aria_label: "search-result-message",
+                        prevent_default: "onclick",
+                        onclick: move |evt| {
+                            evt.stop_propagation();
+                            *cx.props.search_friends_is_focused.write_silent() = false;
+                            cx.props.onclick.call(identity_search_result::Identifier::Uuid(conv_id));
+                        },
+                        div {
+                            class: "search-friends-dropdown-name",
+                            aria_label: "search-result-message-text",
+                            span {
+                                class: "search-message-sender",
+                                "{sender}"
+                            },
+                            span { " {text}" }
+                        }
+                    }
+                )
+            })
         }
     ))
 }
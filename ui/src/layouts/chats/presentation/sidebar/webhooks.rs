@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use arboard::Clipboard;
+use common::icons::outline::Shape as Icon;
+use common::language::{get_local_text, get_local_text_with_args};
+use common::state::chats::{ChatWebhooks, IncomingWebhook, OutgoingWebhook};
+use common::state::{Action, State, ToastNotification};
+use common::utils::{
+    incoming_webhooks::{self, Status},
+    webhooks,
+};
+use dioxus::prelude::*;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    Appearance,
+};
+use tracing::log;
+use uuid::Uuid;
+
+use crate::components::settings::SettingSectionSimple;
+
+#[derive(Props, PartialEq)]
+pub struct Props {
+    chat_id: Uuid,
+}
+
+// the bots which post into a conversation and the endpoint its new messages are posted to
+#[allow(non_snake_case)]
+pub fn WebhookSettings(cx: Scope<Props>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let chat_id = cx.props.chat_id;
+    let hooks = state.read().chats().webhooks(&chat_id);
+    let outgoing_url = hooks
+        .outgoing
+        .as_ref()
+        .map(|outgoing| outgoing.url.clone())
+        .unwrap_or_default();
+    let invalid_url = !outgoing_url.is_empty() && !webhooks::is_local_endpoint(&outgoing_url);
+    let bot_name = use_ref(cx, String::new);
+    let reset_bot_name = use_state(cx, || false);
+    let endpoint = incoming_webhooks::status();
+    let update = cx.schedule_update();
+    let restart_endpoint = move |_| {
+        incoming_webhooks::start();
+        // shown once it had time to bind
+        let update = update.clone();
+        cx.spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            update();
+        });
+    };
+
+    let set_webhooks = move |f: &dyn Fn(&mut ChatWebhooks)| {
+        let mut hooks = state.read().chats().webhooks(&chat_id);
+        f(&mut hooks);
+        state
+            .write()
+            .mutate(Action::SetChatWebhooks(chat_id, hooks));
+    };
+    let copy =
+        move |text: String, key: &str| match Clipboard::new().and_then(|mut c| c.set_text(text)) {
+            Ok(_) => state
+                .write()
+                .mutate(Action::AddToastNotification(ToastNotification::init(
+                    "".into(),
+                    get_local_text(key),
+                    None,
+                    2,
+                ))),
+            Err(e) => log::warn!("Unable to set text to clipboard: {e}"),
+        };
+    let add_bot = move || {
+        let name = bot_name.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        set_webhooks(&|hooks| hooks.incoming.push(IncomingWebhook::new(name.clone())));
+        bot_name.write_silent().clear();
+        reset_bot_name.set(true);
+    };
+
+    cx.render(rsx!(
+        div {
+            id: "webhook-settings",
+            aria_label: "webhook-settings",
+            div {
+                class: "settings",
+                SettingSectionSimple {
+                    aria_label: "chat-incoming-webhooks".into(),
+                    div {
+                        class: "webhook-description",
+                        p {
+                            get_local_text("messages.incoming-webhooks")
+                        },
+                        span {
+                            get_local_text("messages.incoming-webhooks-description")
+                        }
+                    }
+                },
+                match &endpoint {
+                    Status::Listening { port, taken: Some(taken) } => rsx!(p {
+                        class: "error",
+                        aria_label: "chat-bots-port-taken",
+                        get_local_text_with_args("messages.bot-port-taken", vec![("taken", taken.to_string()), ("port", port.to_string())])
+                    }),
+                    Status::Failed(e) => rsx!(div {
+                        class: "error",
+                        aria_label: "chat-bots-failed",
+                        p {
+                            get_local_text_with_args("messages.bots-failed", vec![("error", e.clone())])
+                        },
+                        Button {
+                            icon: Icon::ArrowPath,
+                            text: get_local_text("messages.bots-try-again"),
+                            aria_label: "chat-bots-try-again".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: restart_endpoint,
+                        },
+                    }),
+                    _ => rsx!(()),
+                },
+                hooks.incoming.iter().map(|bot| {
+                    let url = incoming_webhooks::url(&bot.token);
+                    let token = bot.token.clone();
+                    let name = &bot.name;
+                    rsx!(SettingSectionSimple {
+                        key: "{token}",
+                        aria_label: "chat-bot".into(),
+                        p {
+                            class: "bot-name",
+                            "{name}"
+                        },
+                        div {
+                            class: "button-group",
+                            url.map(|url| rsx!(Button {
+                                icon: Icon::ClipboardDocument,
+                                text: get_local_text("messages.copy-bot-url"),
+                                aria_label: "chat-bot-copy-url".into(),
+                                appearance: Appearance::Secondary,
+                                onpress: move |_| copy(url.clone(), "messages.bot-url-copied"),
+                            })),
+                            Button {
+                                icon: Icon::Trash,
+                                aria_label: "chat-bot-remove".into(),
+                                appearance: Appearance::Secondary,
+                                onpress: move |_| set_webhooks(&|hooks| hooks.incoming.retain(|bot| bot.token != token)),
+                            },
+                        }
+                    })
+                }),
+                div {
+                    class: "add-bot",
+                    Input {
+                        placeholder: get_local_text("messages.bot-name"),
+                        aria_label: "chat-bot-name-input".into(),
+                        reset: reset_bot_name.clone(),
+                        options: Options {
+                            clear_on_submit: false,
+                            ..Options::default()
+                        },
+                        onchange: move |(v, _): (String, bool)| {
+                            *bot_name.write_silent() = v;
+                        },
+                        onreturn: move |_| add_bot(),
+                    },
+                    Button {
+                        icon: Icon::Plus,
+                        text: get_local_text("messages.add-bot"),
+                        aria_label: "chat-bot-add".into(),
+                        appearance: Appearance::Secondary,
+                        onpress: move |_| add_bot(),
+                    },
+                },
+                SettingSectionSimple {
+                    aria_label: "chat-outgoing-webhook".into(),
+                    div {
+                        class: "webhook-description",
+                        p {
+                            get_local_text("messages.outgoing-webhook")
+                        },
+                        span {
+                            get_local_text("messages.outgoing-webhook-description")
+                        }
+                    }
+                },
+                div {
+                    class: "webhook-url",
+                    Input {
+                        placeholder: "http://localhost:8080/uplink".into(),
+                        default_text: outgoing_url.clone(),
+                        aria_label: "chat-webhook-url-input".into(),
+                        options: Options {
+                            clear_on_submit: false,
+                            ..Default::default()
+                        },
+                        onchange: move |(v, _): (String, bool)| {
+                            let url = v.trim().to_string();
+                            // the secret is kept while only the address changes
+                            set_webhooks(&|hooks| {
+                                hooks.outgoing = match hooks.outgoing.take() {
+                                    _ if url.is_empty() => None,
+                                    Some(outgoing) => Some(OutgoingWebhook { url: url.clone(), ..outgoing }),
+                                    None => Some(OutgoingWebhook::new(url.clone())),
+                                }
+                            });
+                        },
+                    },
+                    invalid_url.then(|| rsx!(
+                        span {
+                            class: "error",
+                            aria_label: "chat-webhook-url-error",
+                            get_local_text("settings-notifications.webhook-url-invalid")
+                        }
+                    )),
+                },
+                hooks.outgoing.as_ref().map(|outgoing| {
+                    let secret = outgoing.secret.clone();
+                    rsx!(div {
+                        class: "button-group",
+                        Button {
+                            icon: Icon::ClipboardDocument,
+                            text: get_local_text("settings-notifications.webhook-copy-secret"),
+                            aria_label: "chat-webhook-copy-secret".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| copy(secret.clone(), "settings-notifications.webhook-secret-copied"),
+                        },
+                        Button {
+                            icon: Icon::ArrowPath,
+                            text: get_local_text("settings-notifications.webhook-regenerate-secret"),
+                            aria_label: "chat-webhook-regenerate-secret".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| set_webhooks(&|hooks| {
+                                if let Some(outgoing) = hooks.outgoing.as_mut() {
+                                    outgoing.secret = webhooks::new_secret();
+                                }
+                            }),
+                        },
+                    })
+                }),
+            },
+        }
+    ))
+}
@@ -0,0 +1,263 @@
+//! A second conversation next to the active one. It loads its own messages and has a compose box
+//! of its own, so it can be followed and answered without leaving the active conversation.
+
+use common::{
+    icons::outline::Shape as Icon,
+    icons::Icon as IconElement,
+    language::get_local_text,
+    state::{Action, State},
+    warp_runner::{
+        ui_adapter::{self, MessageEvent},
+        CancelToken, RayGunCmd, WarpCmd, WarpEvent,
+    },
+    WARP_CMD_CH, WARP_EVENT_CH,
+};
+use dioxus::prelude::*;
+use futures::channel::oneshot;
+use kit::{
+    components::message::ChatText,
+    elements::{
+        button::Button,
+        input::{Input, Options},
+        tooltip::{ArrowPosition, Tooltip},
+        Appearance,
+    },
+    layout::topbar::Topbar,
+};
+use uuid::Uuid;
+
+use crate::layouts::chats::presentation::chat::coroutines::fetch_most_recent;
+
+// older messages are read in the active conversation
+const SPLIT_MESSAGES: usize = 50;
+
+#[derive(PartialEq, Props)]
+pub struct SplitChatProps {
+    chat_id: Uuid,
+}
+
+#[allow(non_snake_case)]
+pub fn SplitChat(cx: Scope<SplitChatProps>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    let messages: &UseRef<Vec<ui_adapter::Message>> = use_ref(cx, Vec::new);
+    let loaded = use_state(cx, || false);
+    let chat_id = cx.props.chat_id;
+
+    use_future(cx, &chat_id, |chat_id| {
+        to_owned![state, messages, loaded];
+        async move {
+            loaded.set(false);
+            // subscribed first so nothing sent while loading is missed
+            let mut ch = WARP_EVENT_CH.tx.subscribe();
+            // the fetch stops if the split chat is closed or shows another chat meanwhile
+            let token = CancelToken::new();
+            let _guard = token.drop_guard();
+            match fetch_most_recent(chat_id, SPLIT_MESSAGES, &token).await {
+                Ok((fetched, _)) => messages.set(fetched),
+                Err(e) => log::error!("failed to load the split chat: {e}"),
+            }
+            loaded.set(true);
+            state.write().mutate(Action::ClearUnreads(chat_id));
+
+            while let Ok(evt) = ch.recv().await {
+                let WarpEvent::Message(evt) = evt else {
+                    continue;
+                };
+                match evt {
+                    MessageEvent::Received {
+                        conversation_id,
+                        message,
+                    }
+                    | MessageEvent::Sent {
+                        conversation_id,
+                        message,
+                    } => {
+                        if state.read().chats.resolve_merged(conversation_id) != chat_id {
+                            continue;
+                        }
+                        let mut messages = messages.write();
+                        if !messages.iter().any(|m| m.inner.id() == message.inner.id()) {
+                            messages.push(message);
+                        }
+                        if messages.len() > SPLIT_MESSAGES {
+                            messages.remove(0);
+                        }
+                    }
+                    MessageEvent::Edited {
+                        conversation_id,
+                        message,
+                    } => {
+                        if state.read().chats.resolve_merged(conversation_id) != chat_id {
+                            continue;
+                        }
+                        if let Some(m) = messages
+                            .write()
+                            .iter_mut()
+                            .find(|m| m.inner.id() == message.inner.id())
+                        {
+                            *m = message;
+                        }
+                    }
+                    MessageEvent::Deleted {
+                        conversation_id,
+                        message_id,
+                        ..
+                    } => {
+                        if state.read().chats.resolve_merged(conversation_id) == chat_id {
+                            messages.write().retain(|m| m.inner.id() != message_id);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    });
+
+    let send = move |text: String| {
+        let msg: Vec<String> = text.lines().map(|line| line.to_string()).collect();
+        if msg.iter().all(|line| line.trim().is_empty()) {
+            return;
+        }
+        state.write_silent().mutate(Action::ClearChatDraft(chat_id));
+        cx.spawn({
+            to_owned![state];
+            async move {
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = WARP_CMD_CH.tx.send(WarpCmd::RayGun(RayGunCmd::SendMessage {
+                    conv_id: chat_id,
+                    msg: msg.clone(),
+                    attachments: vec![],
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send warp command: {e}");
+                    return;
+                }
+                match rx.await.expect("command canceled") {
+                    Ok((id, _)) => state
+                        .write()
+                        .increment_outgoing_messages_for(chat_id, id, msg),
+                    Err(e) => {
+                        // like in the chatbar, the outbox sends it once the node is back
+                        log::warn!("failed to send message, adding it to the outbox: {e}");
+                        state
+                            .write()
+                            .mutate(Action::QueueMessage(chat_id, msg, vec![], None));
+                    }
+                }
+            }
+        });
+    };
+
+    let Some(chat) = state.read().get_chat_by_id(chat_id) else {
+        return cx.render(rsx!(()));
+    };
+    let title = state.read().chats().title(&chat).unwrap_or_else(|| {
+        let state = state.read();
+        State::join_usernames(&state.remove_self(&state.chat_participants(&chat)))
+    });
+    let own_id = state.read().did_key();
+    let markdown = state.read().ui.should_transform_markdown_text();
+    let ascii_emoji = state.read().ui.should_transform_ascii_emojis();
+    let can_send = state.read().can_use_chat(&chat_id);
+
+    cx.render(rsx!(div {
+        id: "split-chat",
+        aria_label: "split-chat",
+        Topbar {
+            controls: cx.render(rsx!(
+                Button {
+                    icon: Icon::ArrowsPointingOut,
+                    aria_label: "split-chat-swap".into(),
+                    appearance: Appearance::Secondary,
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::Top,
+                        text: get_local_text("messages.split-swap"),
+                    })),
+                    onpress: move |_| {
+                        let active = state.read().chats().active;
+                        state.write().mutate(Action::ChatWith(&chat_id, false));
+                        state.write().mutate(Action::SetSplitChat(active));
+                    },
+                },
+                Button {
+                    icon: Icon::XMark,
+                    aria_label: "split-chat-close".into(),
+                    appearance: Appearance::Secondary,
+                    tooltip: cx.render(rsx!(Tooltip {
+                        arrow_position: ArrowPosition::TopRight,
+                        text: get_local_text("messages.split-close"),
+                    })),
+                    onpress: move |_| state.write().mutate(Action::SetSplitChat(None)),
+                },
+            )),
+            p {
+                class: "split-chat-title",
+                "{title}"
+            },
+        },
+        div {
+            // column-reverse keeps the newest message in view unless the pane is scrolled up
+            id: "split-messages",
+            aria_label: "split-messages",
+            (!*loaded.get()).then(|| rsx!(p {
+                class: "split-chat-loading",
+                get_local_text("messages.loading")
+            })),
+            messages.read().iter().rev().map(|message| {
+                let sender = message.inner.sender();
+                let remote = sender != own_id;
+                let name = state
+                    .read()
+                    .get_identity(&sender)
+                    .map(|id| id.username())
+                    .unwrap_or_default();
+                let text = message.inner.lines().join("\n");
+                let attachments: Vec<String> = message.inner.attachments().iter().map(|f| f.name()).collect();
+                rsx!(div {
+                    key: "{message.key}",
+                    class: format_args!("split-message {}", if remote { "remote" } else { "" }),
+                    aria_label: "split-message",
+                    remote.then(|| rsx!(p {
+                        class: "split-message-sender",
+                        "{name}"
+                    })),
+                    (!text.is_empty()).then(|| rsx!(ChatText {
+                        text: text,
+                        remote: remote,
+                        pending: false,
+                        markdown: markdown,
+                        ascii_emoji: ascii_emoji,
+                        state: state,
+                        chat: chat_id,
+                    })),
+                    attachments.into_iter().map(|name| rsx!(p {
+                        class: "split-message-attachment",
+                        IconElement {
+                            icon: Icon::Document,
+                        },
+                        "{name}"
+                    })),
+                })
+            }),
+        },
+        div {
+            class: "split-chatbar",
+            Input {
+                // a fresh input for every chat, with the draft of that chat
+                key: "{chat_id}",
+                placeholder: get_local_text("messages.say-something-placeholder"),
+                default_text: chat.draft.clone().unwrap_or_default(),
+                aria_label: "split-chat-input".into(),
+                disabled: !can_send,
+                options: Options {
+                    react_to_esc_key: true,
+                    ..Options::default()
+                },
+                onchange: move |(v, _): (String, bool)| {
+                    state.write_silent().mutate(Action::SetChatDraft(chat_id, v));
+                },
+                onreturn: move |(v, _, _): (String, bool, _)| send(v),
+            },
+        },
+    }))
+}
@@ -0,0 +1,18 @@
+// ctrl + scroll zooms the text of the chat instead of the whole page. the deltas are added up so
+// that a touchpad pinch, which sends many small ones, zooms about as fast as a mouse wheel
+var messages = document.getElementById("messages");
+var delta = 0;
+if (messages) {
+    // replaces the handler of the previous chat
+    messages.onwheel = (event) => {
+        if (!event.ctrlKey && !event.metaKey) {
+            return;
+        }
+        event.preventDefault();
+        delta += event.deltaY;
+        if (Math.abs(delta) >= 100) {
+            dioxus.send(delta < 0);
+            delta = 0;
+        }
+    };
+}
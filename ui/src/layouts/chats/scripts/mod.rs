@@ -9,5 +9,10 @@ pub const SCROLL_TO_BOTTOM: &str = include_str!("./scroll_to_bottom.js");
 pub const SCROLL_TO_END: &str = include_str!("./scroll_to_end.js");
 pub const OBSERVER_SCRIPT: &str = include_str!("./observer_script.js");
 pub const READ_SCROLL: &str = include_str!("./read_scroll.js");
+pub const READ_ANCHOR: &str = include_str!("./read_anchor.js");
+pub const RESTORE_ANCHOR: &str = include_str!("./restore_anchor.js");
+pub const RESTORE_SCROLL: &str = include_str!("./restore_scroll.js");
+pub const SCROLL_TO_UNREAD: &str = include_str!("./scroll_to_unread.js");
+pub const CHAT_ZOOM: &str = include_str!("./chat_zoom.js");
 pub const USER_TAG_SCRIPT: &str = include_str!("./user_tag_click_handler.js");
 pub const DISABLE_RELOAD: &str = include_str!("./disable_reload_hotkeys.js");
@@ -17,17 +17,6 @@ function observe_list() {
         entries.forEach((entry) => {
             if (entry.isIntersecting) {
                 dioxus.send("{\"Add\":{\"msg_id\":\"" + entry.target.id + "\",\"key\":\"" + conversation_key + "\"}}");
-                if (entry.target.id == bottom_msg_id && send_bottom_event) {
-                    dioxus.send("{\"Bottom\":{\"key\":\"" + conversation_key + "\"}}");
-                    observer.disconnect();
-                    observer = null;
-                    return;
-                } else if (entry.target.id == top_msg_id && send_top_event) {
-                    dioxus.send("{\"Top\":{\"key\":\"" + conversation_key + "\"}}");
-                    observer.disconnect();
-                    observer = null;
-                    return;
-                }
             } else {
                 dioxus.send("{\"Remove\":{\"msg_id\":\"" + entry.target.id + "\",\"key\":\"" + conversation_key + "\"}}");
             }
@@ -42,6 +31,49 @@ function observe_list() {
         let id = "#" + element.id;
         observer.observe(element);
     });
+
+    // the first and last loaded messages are watched with a margin around the view, so the next page is
+    // fetched before the user scrolls to the edge of what is loaded
+    var edge_observer = new IntersectionObserver( (entries) => {
+        const el = document.getElementById(conversation_key);
+        if  (!el) {
+            if (edge_observer) {
+                edge_observer.disconnect();
+                edge_observer = null;
+            }
+            return;
+        }
+        entries.forEach((entry) => {
+            if (!entry.isIntersecting || !edge_observer) {
+                return;
+            }
+            var evt = null;
+            if (entry.target.id == bottom_msg_id && send_bottom_event) {
+                evt = "Bottom";
+            } else if (entry.target.id == top_msg_id && send_top_event) {
+                evt = "Top";
+            }
+            if (evt) {
+                dioxus.send("{\"" + evt + "\":{\"key\":\"" + conversation_key + "\"}}");
+                edge_observer.disconnect();
+                edge_observer = null;
+                if (observer) {
+                    observer.disconnect();
+                    observer = null;
+                }
+            }
+        });
+    }, {
+        root: document.getElementById("messages"),
+        rootMargin: "$PREFETCH_MARGINpx 0px",
+        threshold: 0,
+    });
+    [top_msg_id, bottom_msg_id].forEach((id) => {
+        const element = document.getElementById(id);
+        if (element) {
+            edge_observer.observe(element);
+        }
+    });
 }
 
-observe_list();
\ No newline at end of file
+observe_list();
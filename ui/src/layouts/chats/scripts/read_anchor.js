@@ -0,0 +1,9 @@
+// returns for eval
+// the distance from the top of the message list to the top of the message, so it can be put back
+// in the same place after messages are added or removed around it
+var messages = document.getElementById("messages");
+var message = document.getElementById("$MESSAGE_ID");
+if (!messages || !message) {
+    return null;
+}
+return Math.round(message.getBoundingClientRect().top - messages.getBoundingClientRect().top);
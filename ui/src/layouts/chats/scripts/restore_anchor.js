@@ -0,0 +1,9 @@
+// returns for eval
+// moves the message back to where it was before the view changed. see read_anchor.js
+var messages = document.getElementById("messages");
+var message = document.getElementById("$MESSAGE_ID");
+if (messages && message) {
+    var top = message.getBoundingClientRect().top - messages.getBoundingClientRect().top;
+    messages.scrollTop += top - $OFFSET;
+}
+return "done";
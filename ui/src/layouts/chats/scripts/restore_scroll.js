@@ -0,0 +1,6 @@
+// puts the messages back where they were scrolled to when the chat was left. see read_scroll.js
+var messages = document.getElementById("messages");
+if (messages) {
+    messages.scrollTop = $SCROLL;
+}
+return "done";
@@ -0,0 +1,6 @@
+// the first unread message goes to the top, below the divider
+var divider = document.getElementById("unread-divider");
+if (divider) {
+    divider.scrollIntoView({ behavior: 'instant', block: 'start' });
+}
+return "done";// returns for eval
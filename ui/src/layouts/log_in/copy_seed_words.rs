@@ -1,13 +1,18 @@
 use std::time::Duration;
 
 use arboard::Clipboard;
-use common::{icons, language::get_local_text, state::State};
+use common::{
+    icons,
+    language::{get_local_text, get_local_text_with_args},
+    state::State,
+};
 use dioxus::prelude::*;
 use dioxus_desktop::use_window;
-use kit::elements::{button::Button, label::Label, Appearance};
+use kit::elements::{button::Button, checkbox::Checkbox, label::Label, Appearance};
 use tokio::time::sleep;
 
 use super::AuthPages;
+use crate::components::onboarding::StepDots;
 use crate::get_app_style;
 use crate::layouts::log_in::update_window_size;
 use common::state::configuration::Configuration;
@@ -22,12 +27,19 @@ use warp::multipass;
 
 // styles for this layout are in layouts/style.scss
 #[component]
-pub fn Layout(cx: Scope, page: UseState<AuthPages>, username: String, pin: String) -> Element {
+pub fn Layout(
+    cx: Scope,
+    page: UseState<AuthPages>,
+    username: String,
+    pin: String,
+    avatar: UseRef<Option<(Vec<u8>, String)>>,
+    new_account: UseRef<bool>,
+) -> Element {
     let state = use_ref(cx, State::load);
     let window = use_window(cx);
 
     if !matches!(&*page.current(), AuthPages::Success(_)) {
-        update_window_size(window, 500.0, 500.0);
+        update_window_size(window, 500.0, 560.0);
     }
 
     let words = use_future(cx, (), |_| async move {
@@ -49,6 +61,9 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, username: String, pin: Strin
         div {
             id: "copy-seed-words-layout",
             aria_label: "copy-seed-words-layout",
+            StepDots {
+                current: 1,
+            },
             div {
                 class: "instructions-important",
                 get_local_text("copy-seed-words.instructions")
@@ -58,7 +73,7 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, username: String, pin: Strin
                 text: get_local_text("copy-seed-words")
             },
             if let Some((seed_words, words)) = words.value() {
-                rsx!{ SeedWords { page: page.clone(), username: username.clone(), pin: pin.clone(), seed_words: seed_words.clone(), words: words.clone() } }
+                rsx!{ SeedWords { page: page.clone(), username: username.clone(), pin: pin.clone(), seed_words: seed_words.clone(), words: words.clone(), avatar: avatar.clone(), new_account: new_account.clone() } }
             }
         }
     ))
@@ -72,9 +87,15 @@ fn SeedWords(
     pin: String,
     seed_words: String,
     words: Vec<String>,
+    avatar: UseRef<Option<(Vec<u8>, String)>>,
+    new_account: UseRef<bool>,
 ) -> Element {
     let copied = use_ref(cx, || false);
     let loading = use_state(cx, || false);
+    // the account is only created once the user confirms the phrase was stored somewhere
+    let acknowledged = use_state(cx, || false);
+    // the account was created but its picture couldn't be set. the user is told before going on
+    let picture_failed = use_state(cx, || None::<(multipass::identity::Identity, String)>);
 
     use_future(cx, copied, |current| async move {
         if *current.read() {
@@ -84,7 +105,16 @@ fn SeedWords(
     });
 
     let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<()>| {
-        to_owned![page, loading, username, pin, seed_words];
+        to_owned![
+            page,
+            loading,
+            username,
+            pin,
+            seed_words,
+            avatar,
+            new_account,
+            picture_failed
+        ];
         async move {
             let config = Configuration::load_or_default();
             let warp_cmd_tx = WARP_CMD_CH.tx.clone();
@@ -107,9 +137,30 @@ fn SeedWords(
 
                 match res {
                     Ok(ident) => {
+                        *new_account.write_silent() = true;
                         if config.audiovideo.interface_sounds {
                             sounds::Play(sounds::Sounds::On);
                         }
+                        // the picture picked along with the username
+                        let picture = avatar.read().as_ref().map(|(image, _)| image.clone());
+                        if let Some(pfp) = picture {
+                            let (tx, rx) = oneshot::channel();
+                            let result = match warp_cmd_tx.send(WarpCmd::MultiPass(
+                                MultiPassCmd::UpdateProfilePicture { pfp, rsp: tx },
+                            )) {
+                                Ok(_) => match rx.await {
+                                    Ok(res) => res.map_err(|e| e.to_string()),
+                                    Err(e) => Err(e.to_string()),
+                                },
+                                Err(e) => Err(e.to_string()),
+                            };
+                            if let Err(e) = result {
+                                log::error!("failed to set the profile picture: {}", e);
+                                loading.set(false);
+                                picture_failed.set(Some((ident, e)));
+                                continue;
+                            }
+                        }
 
                         page.set(AuthPages::Success(ident));
                     }
@@ -177,25 +228,58 @@ fn SeedWords(
                 appearance: Appearance::Secondary
             }
         }
-        div {
-            class: "controls",
-            Button {
-                text: get_local_text("uplink.go-back"),
-                disabled: *loading.get(),
-                aria_label: "back-button".into(),
-                icon: icons::outline::Shape::ChevronLeft,
-                onpress: move |_| page.set(AuthPages::CreateOrRecover),
-                appearance: Appearance::Secondary
-            },
-            Button {
-                aria_label: "i-saved-it-button".into(),
-                disabled: *loading.get(),
-                loading: *loading.get(),
-                text: get_local_text("copy-seed-words.finished"),
-                onpress: move |_| {
-                    ch.send(());
+        if let Some((ident, error)) = picture_failed.get() {
+            // the account exists already, so it can't be created again from here
+            rsx!(
+                div {
+                    class: "instructions-important",
+                    aria_label: "picture-failed",
+                    get_local_text_with_args("copy-seed-words.picture-failed", vec![("error", error.clone())])
+                },
+                div {
+                    class: "controls",
+                    Button {
+                        aria_label: "continue-button".into(),
+                        text: get_local_text("uplink.continue"),
+                        onpress: move |_| page.set(AuthPages::Success(ident.clone())),
+                    }
                 }
-            }
+            )
+        } else {
+            rsx!(
+                div {
+                    class: "seed-acknowledgement",
+                    Checkbox {
+                        aria_label: "seed-acknowledgement-checkbox".into(),
+                        disabled: *loading.get(),
+                        is_checked: *acknowledged.get(),
+                        on_click: move |_| acknowledged.set(!acknowledged.get()),
+                    },
+                    span {
+                        get_local_text("copy-seed-words.acknowledge")
+                    }
+                }
+                div {
+                    class: "controls",
+                    Button {
+                        text: get_local_text("uplink.go-back"),
+                        disabled: *loading.get(),
+                        aria_label: "back-button".into(),
+                        icon: icons::outline::Shape::ChevronLeft,
+                        onpress: move |_| page.set(AuthPages::CreateOrRecover),
+                        appearance: Appearance::Secondary
+                    },
+                    Button {
+                        aria_label: "i-saved-it-button".into(),
+                        disabled: *loading.get() || !acknowledged.get(),
+                        loading: *loading.get(),
+                        text: get_local_text("copy-seed-words.finished"),
+                        onpress: move |_| {
+                            ch.send(());
+                        }
+                    }
+                }
+            )
         }
         copied.read().then(||{
             rsx!(div{
@@ -1,7 +1,7 @@
 use common::{language::get_local_text, state::State};
 use dioxus::prelude::*;
 use dioxus_desktop::use_window;
-use kit::elements::{button::Button, label::Label};
+use kit::elements::{button::Button, label::Label, Appearance};
 
 use crate::{get_app_style, layouts::log_in::update_window_size};
 
@@ -14,7 +14,7 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>) -> Element {
     let window = use_window(cx);
 
     if !matches!(&*page.current(), AuthPages::Success(_)) {
-        update_window_size(window, 500.0, 250.0);
+        update_window_size(window, 500.0, 300.0);
     }
     cx.render(rsx!(
         style {get_app_style(&state.read())},
@@ -46,6 +46,22 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>) -> Element {
                         page.set(AuthPages::EnterSeedWords);
                     }
                 },
+                Button {
+                    aria_label: "restore-backup-button".into(),
+                    text: get_local_text("create-or-recover.restore"),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        page.set(AuthPages::RestoreBackup);
+                    }
+                },
+                Button {
+                    aria_label: "link-device-button".into(),
+                    text: get_local_text("create-or-recover.link-device"),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        page.set(AuthPages::LinkDevice);
+                    }
+                },
             }
         }
     ))
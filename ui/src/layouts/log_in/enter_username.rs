@@ -1,3 +1,4 @@
+use base64::{engine::general_purpose, Engine};
 use common::icons::outline::Shape as Icon;
 use common::language::get_local_text;
 use dioxus::prelude::*;
@@ -6,9 +7,16 @@ use kit::elements::label::Label;
 use kit::elements::{
     button::Button,
     input::{Input, Options, Validation},
+    Appearance,
 };
+use std::path::PathBuf;
 use tracing::log;
 
+use crate::components::crop_image_tool::circle_format_tool::CropCircleImageModal;
+use crate::components::onboarding::StepDots;
+use crate::components::settings::sub_pages::profile::{
+    set_image, transform_file_into_base64_image,
+};
 use crate::layouts::log_in::update_window_size;
 use crate::AuthPages;
 
@@ -16,12 +24,24 @@ pub const MIN_USERNAME_LEN: i32 = 4;
 pub const MAX_USERNAME_LEN: i32 = 32;
 
 #[component]
-pub fn Layout(cx: Scope, page: UseState<AuthPages>, user_name: UseRef<String>) -> Element {
+pub fn Layout(
+    cx: Scope,
+    page: UseState<AuthPages>,
+    user_name: UseRef<String>,
+    avatar: UseRef<Option<(Vec<u8>, String)>>,
+) -> Element {
     log::trace!("rendering enter username layout");
     let window = use_window(cx);
+    // the picked picture, until it is cropped the way it is in the profile settings
+    let cropping = use_state(cx, || None::<(Vec<u8>, String)>);
 
     if !matches!(&*page.current(), AuthPages::Success(_)) {
-        update_window_size(window, 500.0, 250.0);
+        // the crop tool doesn't fit in the small window
+        if cropping.is_some() {
+            update_window_size(window, 800.0, 600.0);
+        } else {
+            update_window_size(window, 500.0, 360.0);
+        }
     }
 
     //let error = use_state(cx, String::new);
@@ -43,10 +63,24 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, user_name: UseRef<String>) -
         special_chars: None,
     };
 
+    let avatar_style = avatar
+        .read()
+        .as_ref()
+        .map(|(image, prefix)| {
+            format!(
+                "background-image: url('{prefix}{}')",
+                general_purpose::STANDARD.encode(image)
+            )
+        })
+        .unwrap_or_default();
+
     cx.render(rsx!(
         div {
             id: "unlock-layout",
             aria_label: "unlock-layout",
+            StepDots {
+                current: 0,
+            },
             Label {
                 text: get_local_text("auth.enter-username")
             },
@@ -82,6 +116,32 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, user_name: UseRef<String>) -
                     }
                 }
             },
+            div {
+                class: "avatar-picker",
+                div {
+                    class: "avatar-preview",
+                    aria_label: "avatar-preview",
+                    style: "{avatar_style}",
+                },
+                Button {
+                    text: get_local_text(if avatar_style.is_empty() { "auth.pick-avatar" } else { "auth.change-avatar" }),
+                    aria_label: "pick-avatar-button".into(),
+                    icon: Icon::Photo,
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        match set_image() {
+                            Ok(image) => cropping.set(Some(image)),
+                            Err(e) => log::warn!("no profile picture picked: {e}"),
+                        }
+                    }
+                },
+                (!avatar_style.is_empty()).then(|| rsx!(Button {
+                    icon: Icon::XMark,
+                    aria_label: "remove-avatar-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| avatar.set(None),
+                })),
+            },
             Button {
                 text:  get_local_text("unlock.create-account"),
                 aria_label: "create-account-button".into(),
@@ -90,7 +150,19 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, user_name: UseRef<String>) -
                 onpress: move |_| {
                     page.set(AuthPages::CopySeedWords);
                 }
-            }
+            },
+            cropping.as_ref().map(|image| rsx!(CropCircleImageModal {
+                large_thumbnail: image.clone(),
+                on_cancel: move |_| cropping.set(None),
+                on_crop: move |path: PathBuf| {
+                    // the picture isn't uploaded uncropped: it can be picked again
+                    match transform_file_into_base64_image(path) {
+                        Ok(image) => avatar.set(Some(image)),
+                        Err(e) => log::error!("failed to crop the profile picture: {e}"),
+                    }
+                    cropping.set(None);
+                }
+            })),
         }
     ))
 }
@@ -3,8 +3,10 @@ use std::fs;
 use common::{
     get_images_dir,
     language::{get_local_text, get_local_text_with_args},
+    profiles::Profiles,
     state::{configuration::Configuration, State},
-    warp_runner::TesseractCmd,
+    utils::{device_link, duress, keychain},
+    warp_runner::{DuressOutcome, TesseractCmd},
     STATIC_ARGS,
 };
 use dioxus::prelude::*;
@@ -20,6 +22,7 @@ use kit::{
         tooltip::{ArrowPosition, Tooltip},
     },
 };
+use uuid::Uuid;
 use warp::multipass;
 
 use tracing::log;
@@ -31,6 +34,7 @@ use common::{
     WARP_CMD_CH,
 };
 
+use super::profile_picker::ProfilePicker;
 use crate::{bootstrap::create_uplink_dirs, get_app_style, AuthPages};
 
 enum UnlockError {
@@ -39,6 +43,13 @@ enum UnlockError {
     Unknown,
 }
 
+struct Unlock {
+    pin: String,
+    account: Option<bool>,
+    // the pin was read from the keychain instead of being typed
+    from_keychain: bool,
+}
+
 impl UnlockError {
     fn translation(&self) -> String {
         match self {
@@ -65,13 +76,16 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
     let first_render = use_ref(cx, || true);
     let state = use_ref(cx, State::load);
     let reset_input = use_state(cx, || false);
+    let profiles = use_ref(cx, Profiles::load);
+    // a pin for the current profile is saved in the keychain
+    let saved_pin = use_state(cx, || false);
 
     // On windows, is necessary use state on topbar controls, without using use_shared_state
     // So state is loaded there to use window_maximized and offer better UX
     if cfg!(target_os = "windows") && *first_render.read() {
         *first_render.write_silent() = false;
         state.write_silent().ui.window_maximized = false;
-        let _ = state.write_silent().save();
+        let _ = state.write_silent().save_now();
     }
 
     // this will be needed later
@@ -97,17 +111,69 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
         }
     });
 
-    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<(String, Option<bool>)>| {
-        to_owned![error, page, cmd_in_progress];
+    // warp is restarted with the data of the chosen profile
+    let switch_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<Uuid>| {
+        to_owned![account_exists, state, profiles, error, shown_error];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some(profile) = rx.next().await {
+                let previous = *account_exists.current();
+                // shows the loading bar until warp is ready
+                account_exists.set(None);
+                let (tx, rx) = oneshot::channel::<Result<bool, warp::error::Error>>();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::Tesseract(TesseractCmd::SwitchProfile {
+                    profile,
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send warp command: {}", e);
+                    account_exists.set(previous);
+                    continue;
+                }
+
+                match rx.await {
+                    Ok(Ok(exists)) => {
+                        *state.write() = State::load();
+                        *profiles.write() = Profiles::load();
+                        error.set(None);
+                        shown_error.set(String::new());
+                        account_exists.set(Some(exists));
+                    }
+                    Ok(Err(e)) => {
+                        log::error!("failed to switch profile: {}", e);
+                        shown_error.set(UnlockError::Unknown.translation());
+                        account_exists.set(previous);
+                    }
+                    Err(_) => log::error!("warp_runner stopped while switching profiles"),
+                }
+            }
+        }
+    });
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<Unlock>| {
+        to_owned![
+            error,
+            page,
+            cmd_in_progress,
+            state,
+            account_exists,
+            saved_pin,
+            shown_error
+        ];
         async move {
-            let config = Configuration::load_or_default();
             let warp_cmd_tx = WARP_CMD_CH.tx.clone();
-            while let Some((password, account)) = rx.next().await {
+            while let Some(Unlock {
+                pin,
+                account,
+                from_keychain,
+            }) = rx.next().await
+            {
+                // loaded here since the profile could have changed
+                let config = Configuration::load_or_default();
                 let (tx, rx) =
                     oneshot::channel::<Result<multipass::identity::Identity, warp::error::Error>>();
 
                 if let Err(e) = warp_cmd_tx.send(WarpCmd::MultiPass(MultiPassCmd::TryLogIn {
-                    passphrase: password,
+                    passphrase: pin.clone(),
                     rsp: tx,
                 })) {
                     log::error!("failed to send warp command: {}", e);
@@ -122,10 +188,28 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
                         if config.audiovideo.interface_sounds {
                             sounds::Play(sounds::Sounds::On);
                         }
+                        if !from_keychain
+                            && state.read().configuration.privacy.keychain_unlock
+                            && keychain::available()
+                            && duress::action().is_none()
+                        {
+                            if let Err(e) = keychain::store_pin(pin).await {
+                                log::error!("failed to save the pin in the keychain: {e}");
+                            }
+                        }
 
                         page.set(AuthPages::Success(ident));
                     }
                     Err(err) => match err {
+                        warp::error::Error::DecryptionError if from_keychain => {
+                            // the pin was changed or the account was replaced
+                            log::warn!("the saved pin is outdated");
+                            if let Err(e) = keychain::forget_pin().await {
+                                log::error!("failed to remove the pin from the keychain: {e}");
+                            }
+                            saved_pin.set(false);
+                            shown_error.set(get_local_text("unlock.saved-pin-invalid"));
+                        }
                         warp::error::Error::DecryptionError => {
                             // check if account exists. can be the case when account got reset
                             if account.unwrap_or_default() {
@@ -137,6 +221,14 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
                         warp::error::Error::IdentityNotCreated => {
                             // this is supposed to fail.
                         }
+                        warp::error::Error::OtherWithContext(e) if e == device_link::REVOKED => {
+                            // the account was deleted from this device
+                            *state.write() = State::load();
+                            saved_pin.set(false);
+                            error.set(None);
+                            account_exists.set(Some(false));
+                            shown_error.set(get_local_text("unlock.device-revoked"));
+                        }
                         _ => {
                             // unexpected
                             error.set(Some(UnlockError::Unknown));
@@ -149,6 +241,107 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
         }
     });
 
+    // the duress pin is only checked once the pin is submitted, otherwise typing a real pin which
+    // starts with the duress pin would trigger it
+    let duress_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<String>| {
+        to_owned![
+            page,
+            state,
+            account_exists,
+            saved_pin,
+            error,
+            shown_error,
+            cmd_in_progress
+        ];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some(pin) = rx.next().await {
+                let checked = pin.clone();
+                let is_duress =
+                    tokio::task::spawn_blocking(move || duress::check(&checked).is_some())
+                        .await
+                        .unwrap_or(false);
+                if !is_duress {
+                    shown_error.set(UnlockError::InvalidPin.translation());
+                    continue;
+                }
+
+                cmd_in_progress.set(true);
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::Tesseract(TesseractCmd::Duress {
+                    passphrase: pin,
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send warp command: {}", e);
+                    cmd_in_progress.set(false);
+                    continue;
+                }
+
+                match rx.await {
+                    Ok(Ok(DuressOutcome::LoggedIn(ident))) => page.set(AuthPages::Success(ident)),
+                    // what couldn't be deleted isn't shown either, it would give the wipe away
+                    Ok(Ok(DuressOutcome::Wiped | DuressOutcome::PartiallyWiped)) => {
+                        // looks like another wrong pin. the profile has no account anymore
+                        *state.write() = State::load();
+                        saved_pin.set(false);
+                        error.set(None);
+                        account_exists.set(Some(false));
+                        shown_error.set(UnlockError::InvalidPin.translation());
+                    }
+                    Ok(Err(_)) => shown_error.set(UnlockError::InvalidPin.translation()),
+                    Err(_) => log::error!("warp_runner stopped during unlock"),
+                }
+                cmd_in_progress.set(false);
+            }
+        }
+    });
+
+    // unlocks with the pin from the keychain, reading it asks for Touch ID or Windows Hello. not
+    // done while a duress pin is set, it has to be typed so it can be checked
+    let keychain_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<()>| {
+        to_owned![state, ch, cmd_in_progress, account_exists, saved_pin];
+        async move {
+            while rx.next().await.is_some() {
+                let privacy = state.read().configuration.privacy;
+                if !privacy.keychain_unlock
+                    || *account_exists.current() != Some(true)
+                    || duress::action().is_some()
+                {
+                    continue;
+                }
+                let pin = match keychain::load_pin().await {
+                    Ok(Some(pin)) => pin,
+                    Ok(None) => {
+                        saved_pin.set(false);
+                        continue;
+                    }
+                    // also when the user declined, the pin can still be typed
+                    Err(e) => {
+                        log::warn!("failed to read the keychain: {e}");
+                        continue;
+                    }
+                };
+                saved_pin.set(true);
+                cmd_in_progress.set(true);
+                ch.send(Unlock {
+                    pin,
+                    account: Some(true),
+                    from_keychain: true,
+                });
+            }
+        }
+    });
+
+    // tried once the account was loaded, also after switching profiles
+    use_future(cx, account_exists.get(), |exists| {
+        to_owned![keychain_ch];
+        async move {
+            if exists == Some(true) {
+                keychain_ch.send(());
+            }
+        }
+    });
+
     // Set up validation options for the input field
     let pin_validation = Validation {
         // The input should have a maximum length of 32
@@ -228,9 +421,13 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
                             if validation_passed {
                                 let is_maximized = desktop.is_maximized();
                                 state.write_silent().ui.window_maximized = is_maximized;
-                                let _ = state.write_silent().save();
+                                let _ = state.write_silent().save_now();
                                 cmd_in_progress.set(true);
-                                ch.send((val, *account_exists.get()));
+                                ch.send(Unlock {
+                                    pin: val,
+                                    account: *account_exists.get(),
+                                    from_keychain: false,
+                                });
                                 validation_failure.set(None);
                             } else {
                                 validation_failure.set(Some(UnlockError::ValidationError));
@@ -239,6 +436,8 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
                         onreturn: move |_| {
                                 if let Some(validation_error) = validation_failure.get() {
                                     shown_error.set(validation_error.translation());
+                                } else if let Some(UnlockError::InvalidPin) = error.get() {
+                                    duress_ch.send(pin.read().clone());
                                 } else if let Some(e) = error.get() {
                                     shown_error.set(e.translation());
                                 } else if !account_exists.current().unwrap_or_default()  {
@@ -253,6 +452,20 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
                             "{shown_error}"
                         }
                     )),
+                    (*saved_pin.get() && !*cmd_in_progress.get()).then(|| rsx!(
+                        Button {
+                            text: get_local_text("unlock.use-saved-pin"),
+                            aria_label: "use-saved-pin-button".into(),
+                            icon: Icon::FingerPrint,
+                            appearance: kit::elements::Appearance::Secondary,
+                            onpress: move |_| keychain_ch.send(()),
+                        }
+                    )),
+                    ProfilePicker {
+                        profiles: profiles.read().clone(),
+                        disabled: *cmd_in_progress.get(),
+                        onswitch: move |profile| switch_ch.send(profile),
+                    },
                     div {
                         class: "unlock-details",
                         span {
@@ -276,6 +489,8 @@ pub fn Layout(cx: Scope, page: UseState<AuthPages>, pin: UseRef<String>) -> Elem
                             if let Some(validation_error) = validation_failure.get() {
                                 shown_error.set(validation_error.translation());
                                 reset_input.set(true);
+                            } else if let Some(UnlockError::InvalidPin) = error.get() {
+                                duress_ch.send(pin.read().clone());
                             } else if let Some(e) = error.get() {
                                 shown_error.set(e.translation());
                                 reset_input.set(true);
@@ -0,0 +1,191 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    state::State,
+    utils::device_link::{self, PairingCode},
+    warp_runner::{TesseractCmd, WarpCmd, BACKUP_EXTENSION},
+    STATIC_ARGS, WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use dioxus_desktop::use_window;
+use futures::{channel::oneshot, StreamExt};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    label::Label,
+    Appearance,
+};
+
+use crate::{get_app_style, layouts::log_in::update_window_size};
+
+use super::AuthPages;
+
+struct Cmd {
+    code: PairingCode,
+    device_name: String,
+}
+
+// receives the account from a device which shows a pairing code in its settings. the account is added
+// as a new profile, like a restored backup.
+// styles for this layout are in layouts/style.scss
+#[component]
+pub fn Layout(cx: Scope, page: UseState<AuthPages>) -> Element {
+    let state = use_ref(cx, State::load);
+    let code = use_state(cx, String::new);
+    let device_name = use_state(cx, || format!("Uplink ({})", std::env::consts::OS));
+    let loading = use_state(cx, || false);
+    let error: &UseState<Option<String>> = use_state(cx, || None);
+    let window = use_window(cx);
+
+    if !matches!(&*page.current(), AuthPages::Success(_)) {
+        update_window_size(window, 500.0, 350.0);
+    }
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<Cmd>| {
+        to_owned![page, loading, error];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some(Cmd { code, device_name }) = rx.next().await {
+                // the file name becomes the name of the profile
+                let bundle = STATIC_ARGS.temp_files.join(format!(
+                    "{}.{BACKUP_EXTENSION}",
+                    get_local_text("link-device.profile-name")
+                ));
+                let _ = std::fs::create_dir_all(&STATIC_ARGS.temp_files);
+                let passphrase = code.secret().to_string();
+                let device_id = match device_link::join(code, device_name, bundle.clone()).await {
+                    Ok(id) => id,
+                    Err(e) => {
+                        log::error!("failed to receive account: {e}");
+                        loading.set(false);
+                        error.set(Some(get_local_text("link-device.connect-failed")));
+                        continue;
+                    }
+                };
+
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::Tesseract(TesseractCmd::RestoreBackup {
+                    path: bundle.clone(),
+                    passphrase,
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send tesseract cmd: {e}");
+                    loading.set(false);
+                    continue;
+                }
+                let res = rx.await;
+                let _ = std::fs::remove_file(&bundle);
+                match res {
+                    Ok(Ok(_)) => {
+                        // the restored profile is the active one now
+                        if let Err(e) = device_link::set_this_device(device_id) {
+                            log::error!("failed to save the id of this device: {e}");
+                        }
+                        page.set(AuthPages::EntryPoint);
+                    }
+                    Ok(Err(e)) => {
+                        log::error!("failed to restore linked account: {e}");
+                        loading.set(false);
+                        error.set(Some(get_local_text("link-device.restore-failed")));
+                    }
+                    Err(e) => {
+                        log::error!("warp runner cmd cancelled: {e}");
+                        loading.set(false);
+                    }
+                }
+            }
+        }
+    });
+
+    let parsed_code = code.parse::<PairingCode>().ok();
+    let can_link = parsed_code.is_some() && !device_name.trim().is_empty() && !*loading.get();
+
+    cx.render(rsx!(
+        style {get_app_style(&state.read())},
+        div {
+            id: "link-device-layout",
+            aria_label: "link-device-layout",
+            Label {
+                aria_label: "link-device".into(),
+                text: get_local_text("link-device")
+            },
+            div {
+                class: "instructions",
+                aria_label: "link-device-instructions",
+                get_local_text("link-device.instructions")
+            },
+            Input {
+                id: "pairing-code-input".to_owned(),
+                focus: true,
+                disable_onblur: true,
+                aria_label: "pairing-code-input".into(),
+                icon: Icon::Link,
+                disabled: *loading.get(),
+                placeholder: get_local_text("link-device.code"),
+                options: Options {
+                    clear_on_submit: false,
+                    ..Default::default()
+                },
+                onchange: move |(val, _): (String, bool)| {
+                    code.set(val);
+                    error.set(None);
+                },
+            },
+            Input {
+                id: "device-name-input".to_owned(),
+                disable_onblur: true,
+                aria_label: "device-name-input".into(),
+                icon: Icon::DevicePhoneMobile,
+                disabled: *loading.get(),
+                default_text: device_name.get().clone(),
+                placeholder: get_local_text("link-device.device-name"),
+                options: Options {
+                    clear_on_submit: false,
+                    ..Default::default()
+                },
+                onchange: move |(val, _): (String, bool)| device_name.set(val),
+            },
+            (!code.is_empty() && parsed_code.is_none()).then(|| rsx!(
+                span {
+                    aria_label: "input-error",
+                    class: "error",
+                    get_local_text("link-device.invalid-code")
+                }
+            )),
+            error.as_ref().map(|error| rsx!(
+                span {
+                    aria_label: "input-error",
+                    class: "error",
+                    "{error}"
+                }
+            )),
+            div {
+                class: "button-container",
+                Button {
+                    aria_label: "back-button".into(),
+                    text: get_local_text("uplink.go-back"),
+                    icon: Icon::ChevronLeft,
+                    appearance: Appearance::Secondary,
+                    disabled: *loading.get(),
+                    onpress: move |_| page.set(AuthPages::CreateOrRecover),
+                },
+                Button {
+                    aria_label: "link-device-button".into(),
+                    text: get_local_text("link-device.submit"),
+                    loading: *loading.get(),
+                    disabled: !can_link,
+                    onpress: move |_| {
+                        if let Some(code) = parsed_code.clone() {
+                            loading.set(true);
+                            error.set(None);
+                            ch.send(Cmd {
+                                code,
+                                device_name: device_name.trim().to_string(),
+                            });
+                        }
+                    },
+                },
+            }
+        }
+    ))
+}
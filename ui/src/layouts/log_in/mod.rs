@@ -3,8 +3,12 @@ mod create_or_recover;
 mod enter_seed_words;
 mod enter_username;
 mod entry_point;
+mod link_device;
+mod profile_picker;
 mod recover_account;
+mod restore_backup;
 
+use common::language::text_direction;
 use dioxus::prelude::*;
 use dioxus_desktop::{use_window, DesktopService, LogicalSize};
 use kit::components::topbar_controls::TopbarControls;
@@ -15,7 +19,9 @@ pub const APP_STYLE: &str = include_str!("../../compiled_styles.css");
 // flows:
 // EntryPoint -> login
 // EntryPoint -> CreateOrRecover -> EnterSeedWords -> login or fail
-// EntryPoint -> CreateOrRecover -> CopySeedWords -> EnterUserName -> login
+// EntryPoint -> CreateOrRecover -> EnterUserName -> CopySeedWords -> login -> onboarding
+// EntryPoint -> CreateOrRecover -> RestoreBackup -> EntryPoint
+// EntryPoint -> CreateOrRecover -> LinkDevice -> EntryPoint
 // serve as a sort of router while the user logs in]
 #[allow(clippy::large_enum_variant)]
 #[derive(PartialEq, Eq)]
@@ -25,16 +31,20 @@ pub enum AuthPages {
     EnterUserName,
     EnterSeedWords,
     CopySeedWords,
+    RestoreBackup,
+    LinkDevice,
     Success(multipass::identity::Identity),
 }
 
 /// Guard the app's router with the login flow
 #[component]
-pub fn AuthGuard(cx: Scope, page: UseState<AuthPages>) -> Element {
+pub fn AuthGuard(cx: Scope, page: UseState<AuthPages>, new_account: UseRef<bool>) -> Element {
     log::trace!("rendering auth guard");
 
     let pin = use_ref(cx, String::new);
     let user_name = use_ref(cx, String::new);
+    // the picture chosen with the username, set once the account exists
+    let avatar: &UseRef<Option<(Vec<u8>, String)>> = use_ref(cx, || None);
     let theme = "";
 
     // make the window smaller while the user authenticates
@@ -48,6 +58,7 @@ pub fn AuthGuard(cx: Scope, page: UseState<AuthPages>) -> Element {
         style { "{UIKIT_STYLES} {APP_STYLE} {theme}" },
         div {
             id: "app-wrap",
+            dir: text_direction(),
             div {
                 class: "titlebar disable-select",
                 id: "lockscreen-controls",
@@ -60,10 +71,12 @@ pub fn AuthGuard(cx: Scope, page: UseState<AuthPages>) -> Element {
 
             match *page.current() {
                 AuthPages::EntryPoint => rsx!(entry_point::Layout { page: page.clone(), pin: pin.clone() }),
-                AuthPages::EnterUserName => rsx!(enter_username::Layout { page: page.clone(), user_name: user_name.clone() }),
+                AuthPages::EnterUserName => rsx!(enter_username::Layout { page: page.clone(), user_name: user_name.clone(), avatar: avatar.clone() }),
                 AuthPages::CreateOrRecover => rsx!(create_or_recover::Layout { page: page.clone() }),
                 AuthPages::EnterSeedWords => rsx!(enter_seed_words::Layout { page: page.clone(), pin: pin.clone(), }),
-                AuthPages::CopySeedWords => rsx!(copy_seed_words::Layout { page: page.clone(), username: user_name.read().clone(), pin: pin.read().clone() }),
+                AuthPages::CopySeedWords => rsx!(copy_seed_words::Layout { page: page.clone(), username: user_name.read().clone(), pin: pin.read().clone(), avatar: avatar.clone(), new_account: new_account.clone() }),
+                AuthPages::RestoreBackup => rsx!(restore_backup::Layout { page: page.clone() }),
+                AuthPages::LinkDevice => rsx!(link_device::Layout { page: page.clone() }),
                 _ => unreachable!("this view should disappear when an account is unlocked or created"),
             }
         }
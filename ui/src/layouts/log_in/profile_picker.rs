@@ -0,0 +1,145 @@
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    profiles::{Profile, Profiles},
+};
+use dioxus::prelude::*;
+use kit::elements::{
+    button::Button,
+    input::{Input, Options, Validation},
+    select::Select,
+    tooltip::{ArrowPosition, Tooltip},
+    Appearance,
+};
+use tracing::log;
+use uuid::Uuid;
+
+const MAX_PROFILE_NAME_LEN: i32 = 32;
+
+fn profile_label(profile: &Profile) -> String {
+    if profile.is_default() {
+        get_local_text("unlock.default-profile")
+    } else {
+        profile.name.clone()
+    }
+}
+
+// lets the user pick the account to unlock or add a new one. the picked profile is passed to `onswitch`
+#[component]
+pub fn ProfilePicker<'a>(
+    cx: Scope<'a>,
+    profiles: Profiles,
+    disabled: bool,
+    onswitch: EventHandler<'a, Uuid>,
+) -> Element<'a> {
+    log::trace!("rendering profile picker");
+    let adding = use_state(cx, || false);
+    let new_name = use_state(cx, String::new);
+    let error = use_state(cx, String::new);
+
+    let add_profile = move || {
+        let name = new_name.get().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        // reload in case the file changed since the picker was rendered
+        let mut profiles = Profiles::load();
+        // the select identifies profiles by their name
+        if profiles.all.iter().any(|p| profile_label(p) == name) {
+            error.set(get_local_text("unlock.profile-exists"));
+            return;
+        }
+        let profile = profiles.add(&name);
+        profiles.save();
+        adding.set(false);
+        new_name.set(String::new());
+        error.set(String::new());
+        onswitch.call(profile.id);
+    };
+
+    if *adding.get() {
+        return cx.render(rsx!(
+            div {
+                class: "profile-picker",
+                aria_label: "profile-picker",
+                Input {
+                    id: "profile-name-input".to_owned(),
+                    focus: true,
+                    aria_label: "profile-name-input".into(),
+                    disable_onblur: true,
+                    icon: Icon::UserPlus,
+                    placeholder: get_local_text("unlock.profile-name"),
+                    options: Options {
+                        with_validation: Some(Validation {
+                            max_length: Some(MAX_PROFILE_NAME_LEN),
+                            min_length: Some(1),
+                            ..Default::default()
+                        }),
+                        clear_on_submit: false,
+                        ..Default::default()
+                    },
+                    onchange: move |(val, _): (String, bool)| {
+                        new_name.set(val);
+                        error.set(String::new());
+                    },
+                    onreturn: move |_| add_profile(),
+                },
+                Button {
+                    aria_label: "add-profile-confirm".into(),
+                    icon: Icon::Check,
+                    disabled: new_name.get().trim().is_empty(),
+                    onpress: move |_| add_profile(),
+                },
+                Button {
+                    aria_label: "add-profile-cancel".into(),
+                    icon: Icon::XMark,
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| {
+                        adding.set(false);
+                        error.set(String::new());
+                    },
+                },
+            },
+            (!error.get().is_empty()).then(|| rsx!(
+                span {
+                    class: "error",
+                    "{error}"
+                }
+            ))
+        ));
+    }
+
+    let active = profiles.active;
+    cx.render(rsx!(
+        div {
+            class: "profile-picker",
+            aria_label: "profile-picker",
+            Select {
+                key: "{active}",
+                initial_value: profile_label(profiles.active()),
+                options: profiles.all.iter().map(profile_label).collect(),
+                onselect: move |value: String| {
+                    if *disabled {
+                        return;
+                    }
+                    if let Some(profile) = profiles.all.iter().find(|p| profile_label(p) == value) {
+                        if profile.id != active {
+                            onswitch.call(profile.id);
+                        }
+                    }
+                }
+            },
+            Button {
+                aria_label: "add-profile-button".into(),
+                icon: Icon::UserPlus,
+                appearance: Appearance::Secondary,
+                disabled: *disabled,
+                tooltip: cx.render(rsx!(Tooltip {
+                    arrow_position: ArrowPosition::Top,
+                    text: get_local_text("unlock.add-profile"),
+                })),
+                onpress: move |_| adding.set(true),
+            },
+        }
+    ))
+}
@@ -0,0 +1,184 @@
+use std::path::PathBuf;
+
+use common::{
+    icons::outline::Shape as Icon,
+    language::get_local_text,
+    state::State,
+    warp_runner::{TesseractCmd, WarpCmd, BACKUP_EXTENSION},
+    WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use dioxus_desktop::use_window;
+use futures::{channel::oneshot, StreamExt};
+use kit::elements::{
+    button::Button,
+    input::{Input, Options},
+    label::Label,
+    Appearance,
+};
+use rfd::FileDialog;
+
+use crate::{get_app_style, layouts::log_in::update_window_size};
+
+use super::AuthPages;
+
+struct Cmd {
+    path: PathBuf,
+    passphrase: String,
+}
+
+// restores a backup exported from the profile settings into a new profile.
+// styles for this layout are in layouts/style.scss
+#[component]
+pub fn Layout(cx: Scope, page: UseState<AuthPages>) -> Element {
+    let state = use_ref(cx, State::load);
+    let path: &UseState<Option<PathBuf>> = use_state(cx, || None);
+    let passphrase = use_state(cx, String::new);
+    let loading = use_state(cx, || false);
+    let error = use_state(cx, || false);
+    let window = use_window(cx);
+
+    if !matches!(&*page.current(), AuthPages::Success(_)) {
+        update_window_size(window, 500.0, 350.0);
+    }
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<Cmd>| {
+        to_owned![page, loading, error];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            while let Some(Cmd { path, passphrase }) = rx.next().await {
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::Tesseract(TesseractCmd::RestoreBackup {
+                    path,
+                    passphrase,
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send tesseract cmd: {e}");
+                    loading.set(false);
+                    continue;
+                }
+
+                match rx.await {
+                    // the restored profile is now active and unlocked with the pin of the backed up account
+                    Ok(Ok(_)) => page.set(AuthPages::EntryPoint),
+                    Ok(Err(e)) => {
+                        log::error!("failed to restore backup: {e}");
+                        loading.set(false);
+                        error.set(true);
+                    }
+                    Err(e) => {
+                        log::error!("warp runner cmd cancelled: {e}");
+                        loading.set(false);
+                    }
+                }
+            }
+        }
+    });
+
+    let file_name = path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| get_local_text("restore-backup.no-file"));
+    let can_restore = path.is_some() && !passphrase.is_empty() && !*loading.get();
+
+    let submit = move || {
+        if let Some(path) = path.get().clone() {
+            loading.set(true);
+            error.set(false);
+            ch.send(Cmd {
+                path,
+                passphrase: passphrase.get().clone(),
+            });
+        }
+    };
+
+    cx.render(rsx!(
+        style {get_app_style(&state.read())},
+        div {
+            id: "restore-backup-layout",
+            aria_label: "restore-backup-layout",
+            Label {
+                aria_label: "restore-backup".into(),
+                text: get_local_text("restore-backup")
+            },
+            div {
+                class: "instructions",
+                aria_label: "restore-backup-instructions",
+                get_local_text("restore-backup.instructions")
+            },
+            div {
+                class: "backup-file",
+                span {
+                    class: "ellipsis-overflow",
+                    aria_label: "backup-file-name",
+                    "{file_name}"
+                },
+                Button {
+                    aria_label: "choose-backup-button".into(),
+                    text: get_local_text("restore-backup.choose-file"),
+                    icon: Icon::FolderOpen,
+                    appearance: Appearance::Secondary,
+                    disabled: *loading.get(),
+                    onpress: move |_| {
+                        if let Some(file) = FileDialog::new()
+                            .set_directory(dirs::home_dir().unwrap_or_default())
+                            .add_filter(BACKUP_EXTENSION, &[BACKUP_EXTENSION])
+                            .pick_file()
+                        {
+                            path.set(Some(file));
+                            error.set(false);
+                        }
+                    }
+                },
+            },
+            Input {
+                id: "restore-passphrase-input".to_owned(),
+                is_password: true,
+                disable_onblur: true,
+                aria_label: "restore-passphrase-input".into(),
+                icon: Icon::Key,
+                disabled: *loading.get(),
+                placeholder: get_local_text("restore-backup.passphrase"),
+                options: Options {
+                    clear_on_submit: false,
+                    ..Default::default()
+                },
+                onchange: move |(val, _): (String, bool)| {
+                    passphrase.set(val);
+                    error.set(false);
+                },
+                onreturn: move |_| {
+                    if can_restore {
+                        submit();
+                    }
+                },
+            },
+            error.then(|| rsx!(
+                span {
+                    aria_label: "input-error",
+                    class: "error",
+                    get_local_text("restore-backup.failed")
+                }
+            )),
+            div {
+                class: "button-container",
+                Button {
+                    aria_label: "back-button".into(),
+                    text: get_local_text("uplink.go-back"),
+                    icon: Icon::ChevronLeft,
+                    appearance: Appearance::Secondary,
+                    disabled: *loading.get(),
+                    onpress: move |_| page.set(AuthPages::CreateOrRecover),
+                },
+                Button {
+                    aria_label: "restore-backup-button".into(),
+                    text: get_local_text("restore-backup.submit"),
+                    loading: *loading.get(),
+                    disabled: !can_restore,
+                    onpress: move |_| submit(),
+                },
+            }
+        }
+    ))
+}
@@ -1,3 +1,5 @@
+pub mod activity;
+pub mod calls;
 pub mod chats;
 pub mod community;
 pub mod friends;
@@ -8,15 +8,18 @@ use crate::{
             accessibility::AccessibilitySettings,
             audio::AudioSettings,
             developer::DeveloperSettings,
+            devices::LinkedDevices,
             extensions::ExtensionSettings,
             general::GeneralSettings,
             keybinds::KeybindSettings,
             licenses::Licenses,
             messages::Messages,
+            network::NetworkSettings,
             notifications::NotificationSettings,
             // files::FilesSettings,
-            // privacy::PrivacySettings,
+            privacy::PrivacySettings,
             profile::ProfileSettings,
+            storage::StorageSettings,
         },
     },
     layouts::slimbar::SlimbarLayout,
@@ -49,7 +52,10 @@ pub fn SettingsLayout(cx: Scope) -> Element {
         Page::Accessibility => rsx!(AccessibilitySettings {}),
         Page::Profile => rsx!(ProfileSettings {}),
         Page::Audio => rsx!(AudioSettings {}),
-        // Page::Privacy => rsx!(PrivacySettings {}),
+        Page::Network => rsx!(NetworkSettings {}),
+        Page::Storage => rsx!(StorageSettings {}),
+        Page::Devices => rsx!(LinkedDevices {}),
+        Page::Privacy => rsx!(PrivacySettings {}),
         // Page::Files => rsx!(FilesSettings {}),
         Page::Extensions => rsx!(ExtensionSettings {}),
         Page::Keybinds => rsx!(KeybindSettings {}),
@@ -16,6 +16,7 @@ use kit::{
     },
     layout::slimbar::Slimbar,
 };
+use uuid::Uuid;
 
 #[derive(PartialEq, Props)]
 pub struct Props {
@@ -26,6 +27,9 @@ pub struct Props {
 pub fn SlimbarLayout(cx: Scope<Props>) -> Element {
     let state = use_shared_state::<State>(cx)?;
     let router = use_navigator(cx);
+    // the favorite being dragged to another place and the one it is over
+    let dragged: &UseRef<Option<Uuid>> = use_ref(cx, || None);
+    let drag_over: &UseState<Option<Uuid>> = use_state(cx, || None);
 
     let favorites = if state.read().initialized {
         state.read().chats_favorites()
@@ -54,50 +58,79 @@ pub fn SlimbarLayout(cx: Scope<Props>) -> Element {
                             let chat_id = chat.id;
                             let participants = state.read().chat_participants(&chat);
                             let other_participants: Vec<_> = state.read().remove_self(&participants);
-                            let participants_name = match chat.conversation_name {
+                            let participants_name = match state.read().chats().title(&chat) {
                                 Some(name) => name,
                                 None => State::join_usernames(&other_participants)
                             };
+                            let unreads = if state.read().chats().notification_rules(&chat_id).is_muted() { 0 } else { chat.unreads() };
                             rsx! (
-                                ContextMenu {
+                                div {
                                     key: "{chat_id}-favorite",
-                                    id: chat_id.to_string(),
-                                    items: cx.render(rsx!(
-                                        ContextItem {
-                                            aria_label: "favorites-chat".into(),
-                                            icon: Icon::ChatBubbleBottomCenterText,
-                                            text: get_local_text("uplink.chat"),
+                                    class: format_args!("favorite {}", if *drag_over.get() == Some(chat_id) { "drag-over" } else { "" }),
+                                    draggable: "true",
+                                    prevent_default: "ondragover ondrop",
+                                    ondragstart: move |_| *dragged.write_silent() = Some(chat_id),
+                                    ondragend: move |_| {
+                                        *dragged.write_silent() = None;
+                                        drag_over.set(None);
+                                    },
+                                    ondragover: move |_| {
+                                        if dragged.read().is_some() && *drag_over.get() != Some(chat_id) {
+                                            drag_over.set(Some(chat_id));
+                                        }
+                                    },
+                                    ondrop: move |_| {
+                                        drag_over.set(None);
+                                        if let Some(moved) = dragged.write_silent().take() {
+                                            if moved != chat_id {
+                                                state.write().mutate(Action::MoveFavorite(moved, chat_id));
+                                            }
+                                        }
+                                    },
+                                    ContextMenu {
+                                        id: chat_id.to_string(),
+                                        items: cx.render(rsx!(
+                                            ContextItem {
+                                                aria_label: "favorites-chat".into(),
+                                                icon: Icon::ChatBubbleBottomCenterText,
+                                                text: get_local_text("uplink.chat"),
+                                                onpress: move |_| {
+                                                    if state.read().ui.is_minimal_view() {
+                                                        state.write().mutate(Action::SidebarHidden(true));
+                                                    }
+                                                    state.write().mutate(Action::ChatWith(&favorites_chat.id, false));
+                                                    router.replace(UplinkRoute::ChatLayout{});
+                                                }
+                                            },
+                                            ContextItem {
+                                                aria_label: "favorites-remove".into(),
+                                                icon: Icon::HeartSlash,
+                                                text: get_local_text("favorites.unpin"),
+                                                onpress: move |_| {
+                                                    state.write().mutate(Action::ToggleFavorite(&remove_favorite.id));
+                                                }
+                                            }
+                                        )),
+                                        UserImageGroup {
+                                            participants: build_participants(&other_participants),
+                                            aria_label: participants_name.clone(),
+                                            with_username: participants_name,
+                                            use_tooltip: true,
+                                            typing: users_typing,
                                             onpress: move |_| {
                                                 if state.read().ui.is_minimal_view() {
                                                     state.write().mutate(Action::SidebarHidden(true));
                                                 }
-                                                state.write().mutate(Action::ChatWith(&favorites_chat.id, false));
+                                                state.write().mutate(Action::ChatWith(&chat.id, false));
                                                 router.replace(UplinkRoute::ChatLayout{});
                                             }
-                                        },
-                                        ContextItem {
-                                            aria_label: "favorites-remove".into(),
-                                            icon: Icon::HeartSlash,
-                                            text: get_local_text("favorites.remove"),
-                                            onpress: move |_| {
-                                                state.write().mutate(Action::ToggleFavorite(&remove_favorite.id));
-                                            }
-                                        }
-                                    )),
-                                    UserImageGroup {
-                                        participants: build_participants(&other_participants),
-                                        aria_label: participants_name.clone(),
-                                        with_username: participants_name,
-                                        use_tooltip: true,
-                                        typing: users_typing,
-                                        onpress: move |_| {
-                                            if state.read().ui.is_minimal_view() {
-                                                state.write().mutate(Action::SidebarHidden(true));
-                                            }
-                                            state.write().mutate(Action::ChatWith(&chat.id, false));
-                                            router.replace(UplinkRoute::ChatLayout{});
                                         }
-                                    }
+                                    },
+                                    (unreads > 0).then(|| rsx!(span {
+                                        class: "favorite-badge",
+                                        aria_label: "favorite-unreads",
+                                        "{unreads}"
+                                    })),
                                 }
                             )
                         })
@@ -1,11 +1,16 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
 use common::{
-    state::{storage::Storage, State},
+    is_file_available_to_preview,
+    state::{
+        storage::{ItemsPage, Storage},
+        State,
+    },
     ROOT_DIR_NAME,
 };
 use dioxus_core::ScopeState;
 use dioxus_hooks::{use_ref, UseRef, UseSharedState};
+use dioxus_html::input_data::keyboard_types::Modifiers;
 use uuid::Uuid;
 use warp::{
     constellation::{directory::Directory, item::Item},
@@ -14,22 +19,64 @@ use warp::{
 
 use super::functions::{self, format_item_size};
 
+// more than that and the previews get too small to compare
+pub const MAX_FILE_PREVIEWS: usize = 3;
+
 #[derive(Clone)]
 pub struct StorageController {
     pub storage_state: Option<Storage>,
     pub directories_list: Vec<Directory>,
     pub files_list: Vec<warp::constellation::file::File>,
+    // items of the current directory, including the ones which weren't fetched yet
+    pub total_items: usize,
+    pub loading_items: bool,
+    // sizes of the folders including their subfolders, by id. computed in the background, `None` if that failed
+    pub folder_sizes: HashMap<Uuid, Option<usize>>,
+    // changes whenever the items are reloaded, since the sizes may have changed too
+    pub items_version: usize,
     pub current_dir: Directory,
     pub dirs_opened_ref: Vec<Directory>,
     pub storage_size: (String, String),
     pub is_renaming_map: Option<Uuid>,
     pub add_new_folder: bool,
     pub first_render: bool,
-    pub show_file_modal: Option<warp::constellation::file::File>,
+    // shown side by side, in the order they were opened
+    pub file_previews: Vec<warp::constellation::file::File>,
     pub files_selected_to_send: Vec<Location>,
     pub current_dir_path_as_string: String,
     pub chats_selected_to_send: Vec<Uuid>,
     pub deleting: Vec<Item>,
+    // files and folders selected with a click, when a double click opens them
+    pub selected_items: Vec<Uuid>,
+    // the item Shift+click selects from
+    pub selection_anchor: Option<Uuid>,
+    // shown in a popover with Space, see `toggle_quick_look`
+    pub quick_look: Option<warp::constellation::file::File>,
+}
+
+/// How a click changes the selection, from the keys held down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectMode {
+    Only,
+    Toggle,
+    Range,
+}
+
+impl SelectMode {
+    pub fn from_modifiers(modifiers: Modifiers) -> Self {
+        let toggle = if cfg!(target_os = "macos") {
+            Modifiers::META
+        } else {
+            Modifiers::CONTROL
+        };
+        if modifiers.contains(Modifiers::SHIFT) {
+            Self::Range
+        } else if modifiers.contains(toggle) {
+            Self::Toggle
+        } else {
+            Self::Only
+        }
+    }
 }
 
 impl StorageController {
@@ -38,6 +85,10 @@ impl StorageController {
             storage_state: None,
             directories_list: state.read().storage.directories.clone(),
             files_list: state.read().storage.files.clone(),
+            total_items: state.read().storage.total_items,
+            loading_items: false,
+            folder_sizes: HashMap::new(),
+            items_version: 0,
             current_dir: state.read().storage.current_dir.clone(),
             dirs_opened_ref: state.read().storage.directories_opened.clone(),
             storage_size: (
@@ -47,7 +98,7 @@ impl StorageController {
             is_renaming_map: None,
             add_new_folder: false,
             first_render: true,
-            show_file_modal: None,
+            file_previews: Vec::new(),
             files_selected_to_send: state
                 .read()
                 .get_active_chat()
@@ -65,6 +116,9 @@ impl StorageController {
                 .join("/"),
             chats_selected_to_send: Vec::new(),
             deleting: Vec::new(),
+            selected_items: Vec::new(),
+            selection_anchor: None,
+            quick_look: None,
         };
         use_ref(cx, || controller)
     }
@@ -85,6 +139,10 @@ impl StorageController {
         if let Some(storage) = self.storage_state.take() {
             self.directories_list = storage.directories.clone();
             self.files_list = storage.files.clone();
+            self.total_items = storage.total_items;
+            self.loading_items = false;
+            self.folder_sizes.clear();
+            self.items_version += 1;
             self.current_dir = storage.current_dir.clone();
             self.dirs_opened_ref = storage.directories_opened.clone();
             self.storage_size = (
@@ -92,12 +150,148 @@ impl StorageController {
                 format_item_size(storage.current_size),
             );
             self.storage_state = None;
+            // items which were deleted, or are in another directory now, can't stay selected
+            let ids: Vec<Uuid> = self.item_ids().collect();
+            self.selected_items.retain(|id| ids.contains(id));
+            if !self.selection_anchor.map_or(false, |id| ids.contains(&id)) {
+                self.selection_anchor = None;
+            }
+            if !self
+                .quick_look
+                .as_ref()
+                .map_or(false, |f| ids.contains(&f.id()))
+            {
+                self.quick_look = None;
+            }
             Some(storage)
         } else {
             None
         }
     }
 
+    pub fn loaded_items(&self) -> usize {
+        self.directories_list.len() + self.files_list.len()
+    }
+
+    pub fn has_more_items(&self) -> bool {
+        self.loaded_items() < self.total_items
+    }
+
+    /// Adds the items after the ones already loaded. Pages of another directory, or which were fetched twice, are dropped
+    pub fn append_page(&mut self, page: ItemsPage) {
+        self.loading_items = false;
+        if page.directory_id != self.current_dir.id() || page.offset != self.loaded_items() {
+            return;
+        }
+        self.directories_list.extend(page.directories);
+        self.files_list.extend(page.files);
+        self.total_items = page.total_items;
+    }
+
+    /// The formatted size of the folder, `None` while it is computed. Empty if it couldn't be
+    pub fn folder_size(&self, id: &Uuid) -> Option<String> {
+        self.folder_sizes
+            .get(id)
+            .map(|size| size.map(format_item_size).unwrap_or_default())
+    }
+
+    /// Opens the file on its own, closing the other previews
+    pub fn open_preview(&mut self, file: warp::constellation::file::File) {
+        self.file_previews = vec![file];
+    }
+
+    /// Opens the file next to the ones already shown. The oldest preview is closed if there are too many
+    pub fn add_preview(&mut self, file: warp::constellation::file::File) {
+        if self.file_previews.iter().any(|f| f.id() == file.id()) {
+            return;
+        }
+        if self.file_previews.len() >= MAX_FILE_PREVIEWS {
+            self.file_previews.remove(0);
+        }
+        self.file_previews.push(file);
+    }
+
+    pub fn close_preview(&mut self, id: Uuid) {
+        self.file_previews.retain(|f| f.id() != id);
+    }
+
+    // folders come before the files in the list
+    fn item_ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.directories_list
+            .iter()
+            .map(|d| d.id())
+            .chain(self.files_list.iter().map(|f| f.id()))
+    }
+
+    pub fn is_selected(&self, id: &Uuid) -> bool {
+        self.selected_items.contains(id)
+    }
+
+    /// Changes the selection the way a click on the item does in a file manager
+    pub fn select_item(&mut self, id: Uuid, mode: SelectMode) {
+        match (mode, self.selection_anchor) {
+            (SelectMode::Toggle, _) => {
+                if self.is_selected(&id) {
+                    self.selected_items.retain(|i| i != &id);
+                } else {
+                    self.selected_items.push(id);
+                }
+                self.selection_anchor = Some(id);
+            }
+            (SelectMode::Range, Some(anchor)) => {
+                let ids: Vec<Uuid> = self.item_ids().collect();
+                let (Some(from), Some(to)) = (
+                    ids.iter().position(|i| i == &anchor),
+                    ids.iter().position(|i| i == &id),
+                ) else {
+                    return;
+                };
+                self.selected_items = ids[from.min(to)..=from.max(to)].to_vec();
+            }
+            (SelectMode::Only, _) | (SelectMode::Range, None) => {
+                self.selected_items = vec![id];
+                self.selection_anchor = Some(id);
+            }
+        }
+    }
+
+    /// Shows the quick look of the file selected last, or closes it if it's already shown
+    pub fn toggle_quick_look(&mut self) {
+        if self.quick_look.take().is_some() {
+            return;
+        }
+        self.quick_look = self
+            .selection_anchor
+            .and_then(|id| self.files_list.iter().find(|f| f.id() == id))
+            .filter(|f| !f.thumbnail().is_empty() || is_file_available_to_preview(&f.name()))
+            .cloned();
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected_items.clear();
+        self.selection_anchor = None;
+    }
+
+    /// The selected items if `item` is one of them, so actions on it apply to the whole selection. Otherwise only `item`
+    pub fn items_to_act_on(&self, item: Item) -> Vec<Item> {
+        if !self.is_selected(&item.id()) {
+            return vec![item];
+        }
+        let dirs = self
+            .directories_list
+            .iter()
+            .filter(|d| self.is_selected(&d.id()))
+            .cloned()
+            .map(Item::from);
+        let files = self
+            .files_list
+            .iter()
+            .filter(|f| self.is_selected(&f.id()))
+            .cloned()
+            .map(Item::from);
+        dirs.chain(files).collect()
+    }
+
     pub fn finish_renaming_item(&mut self, should_toggle: bool) {
         self.is_renaming_map.take();
         if should_toggle {
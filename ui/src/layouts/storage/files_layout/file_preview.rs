@@ -4,15 +4,16 @@ use dioxus::prelude::*;
 
 use kit::{
     components::context_menu::{ContextItem, ContextMenu},
-    elements::loader::Loader,
+    elements::{button::Button, loader::Loader, select::Select, Appearance},
     layout::modal::Modal,
 };
+use uuid::Uuid;
 use warp::constellation::file::File;
 
 use common::{
     get_file_type,
     icons::outline::Shape as Icon,
-    is_audio, is_lang_file, is_video,
+    is_audio, is_file_available_to_preview, is_lang_file, is_video,
     language::get_local_text,
     state::{State, ToastNotification},
     utils::{
@@ -49,6 +50,111 @@ pub fn open_file_preview_modal<'a>(
     }))
 }
 
+/// Shows the files side by side to compare them. Files from `candidates` can be added from the modal
+#[component(no_case_check)]
+pub fn open_file_previews_modal<'a>(
+    cx: Scope<'a>,
+    files: Vec<File>,
+    candidates: Vec<File>,
+    on_dismiss: EventHandler<'a, ()>,
+    on_close: EventHandler<'a, Uuid>,
+    on_add: EventHandler<'a, File>,
+    on_download: EventHandler<'a, (File, Option<PathBuf>)>,
+) -> Element<'a> {
+    let compare_with = get_local_text("files.compare-with");
+    let addable: Vec<String> = candidates
+        .iter()
+        .filter(|c| !files.iter().any(|f| f.id() == c.id()))
+        .filter(|c| !c.thumbnail().is_empty() || is_file_available_to_preview(&c.name()))
+        .map(|c| c.name())
+        .collect();
+
+    cx.render(rsx!(Modal {
+        onclose: move |_| on_dismiss.call(()),
+        open: true,
+        transparent: false,
+        dont_pad: true,
+        children: cx.render(rsx!(
+            div {
+                class: "file-previews",
+                aria_label: "file-previews",
+                files.iter().map(|file| {
+                    let id = file.id();
+                    let name = file.name();
+                    rsx!(div {
+                        key: "{id}",
+                        class: "file-previews-pane",
+                        div {
+                            class: "file-previews-header",
+                            span { "{name}" },
+                            Button {
+                                aria_label: "close-file-preview".into(),
+                                icon: Icon::XMark,
+                                appearance: Appearance::Transparent,
+                                small: true,
+                                onpress: move |_| on_close.call(id),
+                            },
+                        },
+                        FilePreview {
+                            file: file,
+                            on_download: move |temp_path| on_download.call((file.clone(), temp_path)),
+                            on_dismiss: move |_| on_close.call(id),
+                        },
+                    })
+                }),
+                (!addable.is_empty()).then(|| rsx!(
+                    div {
+                        class: "file-previews-add",
+                        Select {
+                            initial_value: compare_with.clone(),
+                            options: addable.clone(),
+                            onselect: move |name: String| {
+                                if let Some(file) = candidates.iter().find(|f| f.name() == name) {
+                                    on_add.call(file.clone());
+                                }
+                            },
+                        },
+                    }
+                )),
+            }
+        ))
+    }))
+}
+
+/// A lighter preview of the file, shown over the files instead of in a modal. Space closes it
+#[component(no_case_check)]
+pub fn quick_look_popover<'a>(
+    cx: Scope<'a>,
+    file: File,
+    on_dismiss: EventHandler<'a, ()>,
+    on_download: EventHandler<'a, Option<PathBuf>>,
+) -> Element<'a> {
+    let id = file.id();
+    let name = file.name();
+    cx.render(rsx!(div {
+        key: "{id}",
+        class: "quick-look",
+        aria_label: "quick-look",
+        role: "dialog",
+        div {
+            class: "quick-look-header",
+            span { "{name}" },
+            Button {
+                aria_label: "close-quick-look".into(),
+                icon: Icon::XMark,
+                appearance: Appearance::Transparent,
+                small: true,
+                onpress: move |_| on_dismiss.call(()),
+            },
+        },
+        FilePreview {
+            file: file,
+            on_download: move |temp_path| on_download.call(temp_path),
+            on_dismiss: move |_| on_dismiss.call(()),
+        },
+    }))
+}
+
 #[derive(Props)]
 struct Props<'a> {
     file: &'a File,
@@ -4,9 +4,12 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use common::icons::outline::Shape as Icon;
-use common::language::get_local_text;
+use common::language::{get_local_text, get_local_text_with_args};
 use common::state::data_transfer::TransferTracker;
-use common::state::{ui, Action, State};
+use common::state::{
+    ui::{self, FilesIntent},
+    Action, State, ToastNotification,
+};
 use common::warp_runner::{RayGunCmd, WarpCmd};
 use common::WARP_CMD_CH;
 use dioxus::prelude::*;
@@ -17,6 +20,7 @@ use kit::elements::label::Label;
 use kit::{
     elements::{
         button::Button,
+        loader::Loader,
         tooltip::{ArrowPosition, Tooltip},
         Appearance,
     },
@@ -24,6 +28,7 @@ use kit::{
 };
 use rfd::FileDialog;
 use uuid::Uuid;
+use warp::constellation::file::File;
 use warp::raygun::Location;
 
 pub mod controller;
@@ -32,7 +37,9 @@ pub mod file_preview;
 use crate::components::files::upload_progress_bar::FileHoverHandler;
 use crate::layouts::chats::ChatSidebar;
 use crate::layouts::slimbar::SlimbarLayout;
-use crate::layouts::storage::files_layout::file_preview::open_file_preview_modal;
+use crate::layouts::storage::files_layout::file_preview::{
+    open_file_previews_modal, quick_look_popover,
+};
 use crate::layouts::storage::send_files_layout::modal::SendFilesLayoutModal;
 use crate::layouts::storage::send_files_layout::SendFilesStartLocation;
 use crate::layouts::storage::shared_component::{FilesAndFolders, FilesBreadcumbs};
@@ -94,6 +101,31 @@ pub fn FilesLayout(cx: Scope<'_>) -> Element<'_> {
 
     functions::get_items_from_current_directory(cx, ch);
 
+    // started from the quick switcher
+    match state.write_silent().ui.take_files_intent() {
+        Some(FilesIntent::NewFolder) => {
+            if !*files_been_uploaded.read() {
+                storage_controller.with_mut(|i| {
+                    i.is_renaming_map = None;
+                    i.add_new_folder = true;
+                });
+            }
+        }
+        Some(FilesIntent::OpenFolder(name)) => ch.send(ChanCmd::OpenDirectory(name)),
+        Some(FilesIntent::PreviewFile(id)) => {
+            let file = storage_controller
+                .read()
+                .files_list
+                .iter()
+                .find(|f| f.id() == id)
+                .cloned();
+            if let Some(file) = file {
+                storage_controller.with_mut(|i| i.open_preview(file));
+            }
+        }
+        None => {}
+    }
+
     #[cfg(not(target_os = "macos"))]
     functions::allow_drag_event_for_non_macos_systems(
         cx,
@@ -127,9 +159,9 @@ pub fn FilesLayout(cx: Scope<'_>) -> Element<'_> {
                         log::error!("Failed to send warp command: {}", e);
                         return;
                     }
-                    if let Ok(Ok(streams)) = rx.await {
+                    if let Ok(Ok(results)) = rx.await {
                         let mut to_append = upload_streams.write();
-                        for (chat, (id, stream)) in streams {
+                        for (chat, (id, stream)) in results.sent {
                             state
                                 .write()
                                 .increment_outgoing_messages(id, vec!["".to_owned()]);
@@ -137,6 +169,22 @@ pub fn FilesLayout(cx: Scope<'_>) -> Element<'_> {
                                 to_append.append((chat, id, stream))
                             }
                         }
+                        if let Some((_, error)) = results.failed.first() {
+                            state.write().mutate(Action::AddToastNotification(
+                                ToastNotification::init(
+                                    "".into(),
+                                    get_local_text_with_args(
+                                        "messages.not-sent-to-chats",
+                                        vec![
+                                            ("num", results.failed.len().to_string()),
+                                            ("error", error.to_string()),
+                                        ],
+                                    ),
+                                    None,
+                                    4,
+                                ),
+                            ));
+                        }
                     }
                     send_files_from_storage.set(false);
                 }
@@ -144,21 +192,43 @@ pub fn FilesLayout(cx: Scope<'_>) -> Element<'_> {
         },
     );
 
+    let current_dir = storage_controller.read().current_dir.clone();
+    let current_folder_size = storage_controller.read().folder_size(&current_dir.id());
+    functions::use_folder_sizes(cx, storage_controller, vec![current_dir]);
+    let folder_size_text = current_folder_size.clone().unwrap_or_default();
+
     cx.render(rsx!(
-        if let Some(file) = storage_controller.read().show_file_modal.as_ref() {
-            let file2 = file.clone();
-            rsx!(open_file_preview_modal {
+        if !storage_controller.read().file_previews.is_empty() {
+            rsx!(open_file_previews_modal {
+                    files: storage_controller.read().file_previews.clone(),
+                    candidates: storage_controller.read().files_list.clone(),
                     on_dismiss: |_| {
-                        storage_controller.with_mut(|i| i.show_file_modal = None);
+                        storage_controller.with_mut(|i| i.file_previews.clear());
                     },
-                    on_download: move |temp_path| {
-                        let file_name = file2.clone().name();
-                        functions::download_file(&file_name, ch, temp_path);
+                    on_close: move |id| {
+                        storage_controller.with_mut(|i| i.close_preview(id));
+                    },
+                    on_add: move |file| {
+                        storage_controller.with_mut(|i| i.add_preview(file));
+                    },
+                    on_download: move |(file, temp_path): (File, Option<PathBuf>)| {
+                        functions::download_file(&file.name(), ch, temp_path);
                     },
-                    file: file.clone()
                 }
             )
         }
+        storage_controller.read().quick_look.clone().map(|file| {
+            let name = file.name();
+            rsx!(quick_look_popover {
+                file: file,
+                on_dismiss: |_| {
+                    storage_controller.with_mut(|i| i.quick_look = None);
+                },
+                on_download: move |temp_path: Option<PathBuf>| {
+                    functions::download_file(&name, ch, temp_path);
+                },
+            })
+        }),
         div {
             id: "files-layout",
             aria_label: "files-layout",
@@ -295,6 +365,16 @@ pub fn FilesLayout(cx: Scope<'_>) -> Element<'_> {
                                             format!("{}", storage_controller.read().storage_size.1),
                                         }
                                     },
+                                    p {
+                                        class: "free-space",
+                                        aria_label: "current-folder-size",
+                                        get_local_text("files.folder-size"),
+                                        span {
+                                            class: "count",
+                                            current_folder_size.is_none().then(|| rsx!(Loader { spinning: true })),
+                                            "{folder_size_text}"
+                                        }
+                                    },
                                 )
                             }
                         }
@@ -0,0 +1,33 @@
+// reports the rows of the files grid which are in view. every item has the same size
+const list = document.getElementById("files-list")
+const parent = list.closest(".file-parent")
+let scheduled = false
+
+function report() {
+    scheduled = false
+    const item = list.querySelector(":scope > .context-wrap")
+    if (!item) return
+    const style = getComputedStyle(list)
+    const gap = parseFloat(style.rowGap) || 0
+    const padding = parseFloat(style.paddingLeft) + parseFloat(style.paddingRight)
+    const row_height = item.offsetHeight + gap
+    const columns = Math.max(1, Math.floor((list.clientWidth - padding + gap) / (item.offsetWidth + gap)))
+    const list_top = list.getBoundingClientRect().top - parent.getBoundingClientRect().top + parent.scrollTop + parseFloat(style.paddingTop)
+    dioxus.send({
+        columns: columns,
+        row_height: row_height,
+        gap: gap,
+        first_row: Math.floor(Math.max(0, parent.scrollTop - list_top) / row_height),
+        rows: Math.ceil(parent.clientHeight / row_height) + 1,
+    })
+}
+
+function schedule() {
+    if (scheduled) return
+    scheduled = true
+    requestAnimationFrame(report)
+}
+
+parent.addEventListener("scroll", schedule)
+new ResizeObserver(schedule).observe(parent)
+schedule()
@@ -1,14 +1,15 @@
 #[cfg(not(target_os = "macos"))]
 use crate::utils::get_drag_event;
 use common::{
+    control,
     language::{get_local_text, get_local_text_with_args},
     state::{
         data_transfer::{TrackerType, TransferState, TransferTracker},
-        storage::Storage,
+        storage::{ItemsPage, Storage},
         Action, State, ToastNotification,
     },
     upload_file_channel::{UploadFileAction, UPLOAD_FILE_LISTENER},
-    warp_runner::{ConstellationCmd, WarpCmd},
+    warp_runner::{CancelToken, ConstellationCmd, WarpCmd},
     WARP_CMD_CH,
 };
 use dioxus::prelude::{use_eval, EvalError, UseEval};
@@ -16,10 +17,12 @@ use dioxus_core::ScopeState;
 #[cfg(not(target_os = "macos"))]
 use dioxus_desktop::wry::webview::FileDropEvent;
 use dioxus_hooks::{
-    to_owned, use_coroutine, use_future, Coroutine, UnboundedReceiver, UseRef, UseSharedState,
+    to_owned, use_coroutine, use_effect, use_future, Coroutine, UnboundedReceiver, UseRef,
+    UseSharedState,
 };
-use futures::{channel::oneshot, StreamExt};
+use futures::{channel::oneshot, stream::FuturesUnordered, StreamExt};
 use rfd::FileDialog;
+use serde_json::json;
 use std::{ffi::OsStr, path::PathBuf, rc::Rc, time::Duration};
 use tokio::time::sleep;
 use uuid::Uuid;
@@ -69,6 +72,56 @@ pub fn run_verifications_and_update_storage(
     }
 }
 
+/// Computes the sizes of the directories which aren't known yet, in the background
+pub fn use_folder_sizes(
+    cx: &ScopeState,
+    controller: &UseRef<StorageController>,
+    directories: Vec<Directory>,
+) {
+    let ids: Vec<Uuid> = directories.iter().map(|dir| dir.id()).collect();
+    let version = controller.read().items_version;
+    use_effect(cx, (&ids, &version), |_| {
+        to_owned![controller];
+        async move {
+            // the effect is dropped once other folders are shown, which stops the sizes of these
+            let token = CancelToken::new();
+            let _guard = token.drop_guard();
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            let mut pending = FuturesUnordered::new();
+            for directory in directories {
+                let id = directory.id();
+                if controller.read().folder_sizes.contains_key(&id) {
+                    continue;
+                }
+                let (tx, rx) = oneshot::channel::<Result<usize, warp::error::Error>>();
+                let cmd = WarpCmd::Constellation(ConstellationCmd::GetDirectorySize {
+                    directory,
+                    rsp: tx,
+                })
+                .cancellable(token.clone());
+                if let Err(e) = warp_cmd_tx.send(cmd) {
+                    log::error!("failed to get folder size: {}", e);
+                    return;
+                }
+                pending.push(async move { (id, rx.await) });
+            }
+            while let Some((id, rsp)) = pending.next().await {
+                let size = match rsp {
+                    Ok(Ok(size)) => Some(size),
+                    Ok(Err(e)) => {
+                        log::error!("failed to get folder size: {}", e);
+                        None
+                    }
+                    Err(_) => continue,
+                };
+                controller.with_mut(|i| {
+                    i.folder_sizes.insert(id, size);
+                });
+            }
+        }
+    });
+}
+
 pub fn get_items_from_current_directory(cx: &ScopeState, ch: &Coroutine<ChanCmd>) {
     use_future(cx, (), |_| {
         to_owned![ch];
@@ -229,6 +282,8 @@ pub fn allow_folder_navigation(eval: &UseEvalFn, allow_navigation: bool) {
 
 pub enum ChanCmd {
     GetItemsFromCurrentDirectory,
+    // the page after the items already loaded
+    GetItemsPaged,
     CreateNewDirectory(String),
     OpenDirectory(String),
     BackToPreviousDirectory(Directory),
@@ -304,6 +359,30 @@ pub fn init_coroutine<'a>(
                             }
                         }
                     }
+                    ChanCmd::GetItemsPaged => {
+                        let offset = controller.read().loaded_items();
+                        let (tx, rx) = oneshot::channel::<Result<ItemsPage, warp::error::Error>>();
+
+                        if let Err(e) = warp_cmd_tx.send(WarpCmd::Constellation(
+                            ConstellationCmd::GetItemsPaged { offset, rsp: tx },
+                        )) {
+                            log::error!("failed to get items from {offset}: {}", e);
+                            controller.with_mut(|i| i.loading_items = false);
+                            continue;
+                        }
+
+                        let rsp = rx.await.expect("command canceled");
+                        match rsp {
+                            Ok(page) => {
+                                controller.with_mut(|i| i.append_page(page));
+                            }
+                            Err(e) => {
+                                log::error!("failed to get items from {offset}: {}", e);
+                                controller.with_mut(|i| i.loading_items = false);
+                                continue;
+                            }
+                        }
+                    }
                     ChanCmd::OpenDirectory(directory_name) => {
                         let (tx, rx) = oneshot::channel::<Result<Storage, warp::error::Error>>();
                         let directory_name2 = directory_name.clone();
@@ -543,6 +622,7 @@ pub fn start_upload_file_listener(
                         }
                     }
                     UploadFileAction::SizeNotAvailable(path, file_name) => {
+                        control::publish("files.upload_failed", json!({ "path": path }));
                         files_in_queue_to_upload.with_mut(|i| i.retain(|p| !p.eq(&path)));
                         state
                             .write()
@@ -558,6 +638,15 @@ pub fn start_upload_file_listener(
                                 ),
                             ));
                     }
+                    UploadFileAction::Queued(id, file_state, file_name) => {
+                        *files_been_uploaded.write_silent() = true;
+                        file_tracker.write().queue_file_transfer(
+                            id,
+                            file_name,
+                            file_state,
+                            TrackerType::FileUpload,
+                        );
+                    }
                     UploadFileAction::Starting(id, file_state, file_name) => {
                         *files_been_uploaded.write_silent() = true;
                         file_tracker.write().start_file_transfer(
@@ -598,6 +687,7 @@ pub fn start_upload_file_listener(
                         }
                     }
                     UploadFileAction::Finishing(path, file) => {
+                        control::publish("files.uploaded", json!({ "path": path }));
                         *files_been_uploaded.write_silent() = true;
                         if !files_in_queue_to_upload.read().is_empty() {
                             files_in_queue_to_upload.with_mut(|i| i.retain(|p| !p.eq(&path)));
@@ -623,6 +713,7 @@ pub fn start_upload_file_listener(
                     UploadFileAction::Error(path, file) => {
                         match path {
                             Some(path) => {
+                                control::publish("files.upload_failed", json!({ "path": path }));
                                 files_in_queue_to_upload.with_mut(|i| i.retain(|p| !p.eq(&path)))
                             }
                             None => files_in_queue_to_upload.with_mut(|i| i.clear()),
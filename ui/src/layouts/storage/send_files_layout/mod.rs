@@ -138,7 +138,7 @@ fn ChatsToSelect<'a>(cx: Scope<'a, ChatsToSelectProps<'a>>) -> Element<'a> {
             let user: state::Identity = other_participants.first().cloned().unwrap_or_default();
             let platform = user.platform().into();
             // todo: how to tell who is participating in a group chat if the chat has a conversation_name?
-            let participants_name = match chat.conversation_name {
+            let participants_name = match state.read().chats().title(&chat) {
                 Some(name) => name,
                 None => State::join_usernames(&other_participants)
             };
@@ -187,7 +187,7 @@ fn ChatsToSelect<'a>(cx: Scope<'a, ChatsToSelectProps<'a>>) -> Element<'a> {
                                     UserImage {
                                         platform: platform,
                                         status:  user.identity_status().into(),
-                                        image: user.profile_picture(),
+                                        image: user.avatar(),
                                         typing: false,
                                     }
                                 )} else {rsx! (
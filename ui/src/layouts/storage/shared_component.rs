@@ -1,29 +1,49 @@
+use std::ops::Range;
 use std::str::FromStr;
 
+use arboard::Clipboard;
+
+use crate::components::extension_menu_items::ExtensionMenuItems;
 use crate::layouts::storage::functions::{self, download_file, ChanCmd};
 use crate::layouts::storage::send_files_layout::send_files_components::{
     toggle_selected_file, FileCheckbox,
 };
 
-use super::files_layout::controller::StorageController;
+use super::files_layout::controller::{SelectMode, StorageController};
 use common::icons::outline::Shape as Icon;
 use common::icons::Icon as IconElement;
 use common::is_file_available_to_preview;
 use common::language::get_local_text_with_args;
-use common::state::{State, ToastNotification};
+use common::state::{
+    configuration::OpenItemsOn, storage::ITEMS_PAGE_SIZE, State, ToastNotification,
+};
 use common::warp_runner::thumbnail_to_base64;
 use common::{language::get_local_text, ROOT_DIR_NAME};
 
 use dioxus::html::input_data::keyboard_types::Code;
 use dioxus::prelude::*;
+use extensions::context_menu::MenuContext;
 use kit::components::context_menu::{ContextItem, ContextMenu};
+use kit::elements::button::Button;
 use kit::elements::file::File;
 use kit::elements::folder::Folder;
+use kit::elements::Appearance;
+use kit::layout::modal::Modal;
+use serde::Deserialize;
 use uuid::Uuid;
 use warp::constellation::directory::Directory;
 use warp::constellation::item::Item;
 use warp::raygun::{ConversationSettings, Location};
 
+// crumbs shown before the folders in the middle of the path are collapsed
+const MAX_VISIBLE_CRUMBS: usize = 4;
+// moves the focus to the crumb next to the focused one
+const FOCUS_CRUMB: &str = r##"
+var crumbs = Array.from(document.querySelectorAll("#files-breadcrumbs .crumb"));
+var crumb = crumbs[crumbs.indexOf(document.activeElement) + ($NEXT ? 1 : -1)];
+if (crumb) crumb.focus();
+"##;
+
 #[derive(Props)]
 pub struct FilesBreadcumbsProps<'a> {
     storage_controller: &'a UseRef<StorageController>,
@@ -37,6 +57,53 @@ pub fn FilesBreadcumbs<'a>(cx: Scope<'a, FilesBreadcumbsProps<'a>>) -> Element<'
     let send_files_mode = cx.props.send_files_mode;
     let storage_controller = cx.props.storage_controller;
     let ch = cx.props.ch;
+    let eval = use_eval(cx);
+    let show_hidden = use_state(cx, || false);
+
+    let dirs = storage_controller.read().dirs_opened_ref.clone();
+    // keep the root and the last folders, the ones in between go into a dropdown
+    let hidden = if dirs.len() > MAX_VISIBLE_CRUMBS {
+        1..dirs.len() - (MAX_VISIBLE_CRUMBS - 2)
+    } else {
+        0..0
+    };
+    let names: Vec<String> = dirs
+        .iter()
+        .enumerate()
+        .map(|(index, dir)| crumb_name(index, dir, &state.read()))
+        .collect();
+    let show_hidden_text = get_local_text("files.show-hidden-folders");
+    let copy_path_text = get_local_text("files.copy-path");
+
+    let open = move |dir: Directory| {
+        show_hidden.set(false);
+        ch.send(ChanCmd::BackToPreviousDirectory(dir));
+    };
+    let focus = move |code: Code| {
+        let next = match code {
+            Code::ArrowLeft => "false",
+            Code::ArrowRight => "true",
+            _ => return,
+        };
+        let _ = eval(&FOCUS_CRUMB.replace("$NEXT", next));
+    };
+    let copy_path = move || {
+        // the path as it is in Constellation, chat folders are named after their id
+        let path = format!("/{}", storage_controller.read().current_dir_path_as_string);
+        match Clipboard::new().and_then(|mut c| c.set_text(path)) {
+            Ok(_) => state
+                .write()
+                .mutate(common::state::Action::AddToastNotification(
+                    ToastNotification::init(
+                        "".into(),
+                        get_local_text("files.path-copied"),
+                        None,
+                        2,
+                    ),
+                )),
+            Err(e) => log::warn!("Unable to set text to clipboard: {e}"),
+        }
+    };
 
     cx.render(rsx!(div {
         id: "files-breadcrumbs",
@@ -44,44 +111,149 @@ pub fn FilesBreadcumbs<'a>(cx: Scope<'a, FilesBreadcumbsProps<'a>>) -> Element<'
         aria_label: "files-breadcrumbs",
         margin_top: format_args!("{}", if send_files_mode {"32px"} else {""}),
         margin: format_args!("{}", if send_files_mode {"var(--gap) 0"} else {"var(--gap)"}),
-        storage_controller.read().dirs_opened_ref.iter().enumerate().map(|(index, dir)| {
+        dirs.iter().enumerate().filter(|(index, _)| !hidden.contains(index)).map(|(index, dir)| {
             let directory = dir.clone();
-            let dir_name = dir.name();
-            if dir_name == ROOT_DIR_NAME && index == 0 {
-                let home_text = get_local_text("uplink.home");
-                rsx!(div {
+            let directory2 = dir.clone();
+            let name = &names[index];
+            let is_home = dir.name() == ROOT_DIR_NAME && index == 0;
+            let label = if is_home { "home-dir".to_string() } else { name.clone() };
+            rsx!(
+                div {
+                    key: "{index}",
                     class: "crumb",
                     aria_label: "crumb",
-                    onclick: move |_| {
-                        ch.send(ChanCmd::BackToPreviousDirectory(directory.clone()));
+                    tabindex: "0",
+                    title: "{name}",
+                    onclick: move |_| open(directory.clone()),
+                    onkeydown: move |e: Event<KeyboardData>| match e.code() {
+                        Code::Enter | Code::Space => open(directory2.clone()),
+                        code => focus(code),
                     },
-                    IconElement {
+                    is_home.then(|| rsx!(IconElement {
                         icon: Icon::Home,
-                    },
+                    })),
                     p {
-                        aria_label: "home-dir",
-                        "{home_text}",
+                        aria_label: "{label}",
+                        "{name}",
                     }
-                })
-            } else {
-                let folder_name_resolved = resolve_directory_name(dir, &state.read());
-                let folder_name_formatted = functions::format_item_name(folder_name_resolved);
-                rsx!(div {
-                    class: "crumb",
-                    onclick: move |_| {
-                        ch.send(ChanCmd::BackToPreviousDirectory(directory.clone()));
-                    },
-                    aria_label: "crumb",
-                    p {
-                        aria_label: "{folder_name_formatted}",
-                        "{folder_name_formatted}"
+                },
+                // the collapsed folders come right after the root
+                (index == 0 && !hidden.is_empty()).then(|| rsx!(
+                    div {
+                        class: "crumb-collapsed-wrap",
+                        div {
+                            class: "crumb crumb-collapsed",
+                            aria_label: "crumb-collapsed",
+                            tabindex: "0",
+                            title: "{show_hidden_text}",
+                            onclick: move |_| show_hidden.set(!show_hidden.get()),
+                            onkeydown: move |e: Event<KeyboardData>| match e.code() {
+                                Code::Enter | Code::Space | Code::ArrowDown => show_hidden.set(true),
+                                Code::Escape => show_hidden.set(false),
+                                code => focus(code),
+                            },
+                            p { "\u{2026}" },
+                        },
+                        show_hidden.get().then(|| rsx!(
+                            div {
+                                class: "crumb-dropdown",
+                                aria_label: "crumb-dropdown",
+                                dirs[hidden.clone()].iter().zip(hidden.clone()).map(|(dir, index)| {
+                                    let directory = dir.clone();
+                                    let directory2 = dir.clone();
+                                    let name = &names[index];
+                                    rsx!(div {
+                                        key: "{index}",
+                                        class: "crumb-dropdown-item",
+                                        aria_label: "crumb-dropdown-item",
+                                        tabindex: "0",
+                                        onclick: move |_| open(directory.clone()),
+                                        onkeydown: move |e: Event<KeyboardData>| match e.code() {
+                                            Code::Enter | Code::Space => open(directory2.clone()),
+                                            Code::Escape => show_hidden.set(false),
+                                            _ => {}
+                                        },
+                                        "{name}"
+                                    })
+                                })
+                            }
+                        ))
                     }
-                },)
-            }
-        })
+                ))
+            )
+        }),
+        div {
+            class: "crumb crumb-copy",
+            aria_label: "copy-path",
+            tabindex: "0",
+            title: "{copy_path_text}",
+            onclick: move |_| copy_path(),
+            onkeydown: move |e: Event<KeyboardData>| match e.code() {
+                Code::Enter | Code::Space => copy_path(),
+                code => focus(code),
+            },
+            IconElement {
+                icon: Icon::ClipboardDocument,
+            },
+        }
     },))
 }
 
+fn crumb_name(index: usize, dir: &Directory, state: &State) -> String {
+    if dir.name() == ROOT_DIR_NAME && index == 0 {
+        get_local_text("uplink.home")
+    } else {
+        functions::format_item_name(resolve_directory_name(dir, state))
+    }
+}
+
+// rows mounted above and below the ones in view, so scrolling doesn't show empty space
+const OVERSCAN_ROWS: usize = 2;
+
+const KEEP_SPACE: &str = r##"
+document.getElementById("files-list").addEventListener("keydown", (e) => {
+    if (e.code === "Space" && e.target.id === "files-list") e.preventDefault();
+});
+"##;
+
+// the part of the files grid which is in view, reported by files_viewport.js
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+struct Viewport {
+    columns: usize,
+    row_height: f64,
+    gap: f64,
+    first_row: usize,
+    rows: usize,
+}
+
+impl Viewport {
+    /// the items to mount out of `len`, and the height of the rows left out above and below them
+    fn window(&self, len: usize) -> (Range<usize>, f64, f64) {
+        let columns = self.columns.max(1);
+        let total_rows = len.div_ceil(columns);
+        let start_row = self.first_row.saturating_sub(OVERSCAN_ROWS).min(total_rows);
+        let end_row = (self.first_row + self.rows + OVERSCAN_ROWS).min(total_rows);
+        // the spacers are on a line of their own, which adds a gap
+        let height = |rows: usize| {
+            if rows == 0 {
+                0.0
+            } else {
+                rows as f64 * self.row_height - self.gap
+            }
+        };
+        (
+            start_row * columns..(end_row * columns).min(len),
+            height(start_row),
+            height(total_rows - end_row.max(start_row)),
+        )
+    }
+}
+
+// the part of `range` which falls in the items from `base` to `base + len`, relative to `base`
+fn sub_range(range: &Range<usize>, base: usize, len: usize) -> Range<usize> {
+    range.start.saturating_sub(base).min(len)..range.end.saturating_sub(base).min(len)
+}
+
 #[derive(Props)]
 pub struct FilesAndFoldersProps<'a> {
     storage_controller: &'a UseRef<StorageController>,
@@ -96,6 +268,65 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
     let send_files_mode = cx.props.send_files_mode;
     let storage_controller = cx.props.storage_controller;
     let ch = cx.props.ch;
+    // picking files to send already works by clicking them, there's nothing to open
+    let open_on_double_click = !send_files_mode
+        && state.read().configuration.files.open_items_on == OpenItemsOn::DoubleClick;
+    let eval = use_eval(cx);
+    let viewport = use_state(cx, || None::<Viewport>);
+    // the selected items the context menu deletes once the user confirmed it
+    let confirm_delete = use_state(cx, || None::<Vec<Item>>);
+
+    use_effect(cx, (), |_| {
+        to_owned![eval, viewport];
+        async move {
+            // Space opens the quick look instead of scrolling the list
+            let _ = eval(KEEP_SPACE);
+            let eval = match eval(include_str!("./files_viewport.js")) {
+                Ok(eval) => eval,
+                Err(e) => {
+                    log::error!("failed to watch the files list: {:?}", e);
+                    return;
+                }
+            };
+            while let Ok(value) = eval.recv().await {
+                match serde_json::from_value::<Viewport>(value) {
+                    Ok(value) if *viewport.current() != Some(value) => viewport.set(Some(value)),
+                    Ok(_) => {}
+                    Err(e) => log::error!("invalid files viewport: {e}"),
+                }
+            }
+        }
+    });
+
+    // only the rows in view are mounted. until they are known, the first page is
+    let new_folder = usize::from(storage_controller.read().add_new_folder);
+    let num_dirs = storage_controller.read().directories_list.len();
+    let num_files = storage_controller.read().files_list.len();
+    let len = new_folder + num_dirs + num_files;
+    let (range, space_above, space_below) = match viewport.get() {
+        Some(viewport) => viewport.window(len),
+        None => (0..len.min(ITEMS_PAGE_SIZE), 0.0, 0.0),
+    };
+    let dirs_range = sub_range(&range, new_folder, num_dirs);
+    let files_range = sub_range(&range, new_folder + num_dirs, num_files);
+
+    let dirs_in_view = storage_controller.read().directories_list[dirs_range.clone()].to_vec();
+    functions::use_folder_sizes(cx, storage_controller, dirs_in_view);
+
+    // the next page is fetched once the rows in view get close to the end of the loaded items
+    let wants_more = range.end + OVERSCAN_ROWS * viewport.get().map(|v| v.columns).unwrap_or(0)
+        >= len
+        && storage_controller.read().has_more_items();
+    use_effect(cx, (&wants_more, &len), |(wants_more, _)| {
+        to_owned![storage_controller, ch];
+        async move {
+            if wants_more && !storage_controller.read().loading_items {
+                storage_controller.with_mut(|i| i.loading_items = true);
+                ch.send(ChanCmd::GetItemsPaged);
+            }
+        }
+    });
+
     cx.render(rsx!(span {
         class: "file-parent",
         background: format_args!("{}", if send_files_mode {"var(--secondary)"} else {""}),
@@ -103,7 +334,27 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
             id: "files-list",
             class: "files-list",
             aria_label: "files-list",
-            storage_controller.read().add_new_folder.then(|| {
+            tabindex: "0",
+            onkeydown: move |e: Event<KeyboardData>| {
+                let renaming = storage_controller.with(|i| i.is_renaming_map.is_some() || i.add_new_folder);
+                if send_files_mode || renaming {
+                    return;
+                }
+                match e.code() {
+                    Code::Space => storage_controller.with_mut(|i| i.toggle_quick_look()),
+                    Code::Escape if storage_controller.read().quick_look.is_some() => {
+                        storage_controller.with_mut(|i| i.quick_look = None);
+                    }
+                    _ => {}
+                }
+            },
+            (space_above > 0.0).then(|| rsx!(
+                div {
+                    class: "files-list-spacer",
+                    height: "{space_above}px",
+                }
+            )),
+            (new_folder > 0 && range.start == 0).then(|| {
                 rsx!(
                 Folder {
                     with_rename: true,
@@ -130,13 +381,14 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                      }
                 })
             }),
-            storage_controller.read().directories_list.iter().map(|dir| {
+            storage_controller.read().directories_list[dirs_range].iter().map(|dir| {
                 let folder_name = dir.name();
                 let folder_name2 = folder_name.clone();
                 let folder_name3 = folder_name.clone();
                 let folder_name_resolved = resolve_directory_name(dir, &state.read());
                 let key = dir.id();
                 let dir2 = dir.clone();
+                let size = storage_controller.read().folder_size(&key);
                 let deleting = storage_controller.read().deleting.iter().any(|i|{
                     if let Item::Directory(d) = &i {
                         d.id().eq(&dir.id())
@@ -164,15 +416,27 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                                 aria_label: "folder-delete".into(),
                                 text: get_local_text("uplink.delete"),
                                 onpress: move |_| {
-                                    let item = Item::from(dir2.clone());
-                                    ch.send(ChanCmd::DeleteItems(item));
+                                    let items = storage_controller.read().items_to_act_on(Item::from(dir2.clone()));
+                                    // deleting several items at once is confirmed first
+                                    if items.len() > 1 {
+                                        confirm_delete.set(Some(items));
+                                        return;
+                                    }
+                                    for item in items {
+                                        ch.send(ChanCmd::DeleteItems(item));
+                                    }
                                 }
                             },
+                            ExtensionMenuItems {
+                                context: MenuContext::Folder { id: dir.id(), name: dir.name() },
+                            },
                         )),
                         Folder {
                             key: "{key}-folder",
                             text: folder_name_resolved.clone(),
                             aria_label: folder_name_resolved,
+                            size_loading: size.is_none(),
+                            size: size.unwrap_or_default(),
                             with_rename:storage_controller.with(|i| i.is_renaming_map == Some(key)),
                             onrename: move |(val, key_code)| {
                                 if val == folder_name3 {
@@ -199,6 +463,11 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                                     ch.send(ChanCmd::RenameItem{old_name: folder_name2.clone(), new_name: val});
                                 }
                             },
+                            open_on_double_click: open_on_double_click,
+                            selected: storage_controller.read().is_selected(&key),
+                            onselect: move |modifiers| {
+                                storage_controller.with_mut(|i| i.select_item(key, SelectMode::from_modifiers(modifiers)));
+                            },
                             onpress: move |_| {
                                 storage_controller.with_mut(|i| i.is_renaming_map = None);
                                 ch.send(ChanCmd::OpenDirectory(folder_name.clone()));
@@ -208,7 +477,7 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                     }
                 )
             }),
-            storage_controller.read().files_list.iter().map(|file| {
+            storage_controller.read().files_list[files_range].iter().map(|file| {
                 let file_name = file.name();
                 let file_name2 = file.name();
                 let file_name3 = file.name();
@@ -217,6 +486,8 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                 let file_path3 = format!("{}/{}", storage_controller.read().current_dir_path_as_string, file_name3);
                 let file2 = file.clone();
                 let file3 = file.clone();
+                let file5 = file.clone();
+                let can_preview = !file.thumbnail().is_empty() || is_file_available_to_preview(&file.name());
                 let key = file.id();
                 let file_id = file.id();
                 let deleting = storage_controller.read().deleting.iter().any(|i|{
@@ -262,6 +533,14 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                                         download_file(&file_name2, ch, None);
                                     },
                                 },
+                                can_preview.then(|| rsx!(ContextItem {
+                                    icon: Icon::Square2Stack,
+                                    aria_label: "files-compare".into(),
+                                    text: get_local_text("files.compare"),
+                                    onpress: move |_| {
+                                        storage_controller.with_mut(|i| i.add_preview(file5.clone()));
+                                    },
+                                })),
                                 hr {},
                                 ContextItem {
                                     icon: Icon::Trash,
@@ -269,9 +548,19 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                                     aria_label: "files-delete".into(),
                                     text: get_local_text("uplink.delete"),
                                     onpress: move |_| {
-                                        let item = Item::from(file2.clone());
-                                        ch.send(ChanCmd::DeleteItems(item));
+                                        let items = storage_controller.read().items_to_act_on(Item::from(file2.clone()));
+                                        // deleting several items at once is confirmed first
+                                        if items.len() > 1 {
+                                            confirm_delete.set(Some(items));
+                                            return;
+                                        }
+                                        for item in items {
+                                            ch.send(ChanCmd::DeleteItems(item));
+                                        }
                                     }
+                                },
+                                ExtensionMenuItems {
+                                    context: MenuContext::File { id: file.id(), name: file.name() },
                                 },)
                             }
                         )),
@@ -288,12 +577,19 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                                 text: file.name(),
                                 aria_label: file.name(),
                                 with_rename: storage_controller.with(|i| i.is_renaming_map == Some(key)),
+                                open_on_double_click: open_on_double_click,
+                                selected: storage_controller.read().is_selected(&key),
+                                onselect: move |modifiers| {
+                                    storage_controller.with_mut(|i| i.select_item(key, SelectMode::from_modifiers(modifiers)));
+                                },
                                 onpress: move |_| {
                                     if send_files_mode {
                                         toggle_selected_file(storage_controller.clone(), file_path2.clone());
                                         return;
                                     }
                                     let key = file_id;
+                                    // so Space shows it again once the preview is closed
+                                    storage_controller.with_mut(|i| i.select_item(key, SelectMode::Only));
                                     if state.read().ui.file_previews.contains_key(&key) {
                                         state
                                         .write()
@@ -321,7 +617,7 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                                         return;
                                     }
                                     let file4 = file3.clone();
-                                    storage_controller.with_mut(|i| i.show_file_modal = Some(file4));
+                                    storage_controller.with_mut(|i| i.open_preview(file4));
                                 },
                                 onrename: move |(val, key_code)| {
                                     let new_name: String = val;
@@ -355,7 +651,46 @@ pub fn FilesAndFolders<'a>(cx: Scope<'a, FilesAndFoldersProps<'a>>) -> Element<'
                     }
                 }
             }),
+            (space_below > 0.0).then(|| rsx!(
+                div {
+                    class: "files-list-spacer",
+                    height: "{space_below}px",
+                }
+            )),
         },
+        confirm_delete.get().clone().map(|items| {
+            let text = get_local_text_with_args("files.delete-items-confirm", vec![("num", items.len().to_string())]);
+            rsx!(Modal {
+                open: true,
+                with_title: get_local_text("files.delete-items"),
+                transparent: true,
+                onclose: move |_| confirm_delete.set(None),
+                div {
+                    class: "delete-items",
+                    p { "{text}" },
+                    div {
+                        class: "delete-items-buttons",
+                        Button {
+                            text: get_local_text("uplink.cancel"),
+                            aria_label: "delete-items-cancel".into(),
+                            appearance: Appearance::Secondary,
+                            onpress: move |_| confirm_delete.set(None),
+                        },
+                        Button {
+                            text: get_local_text("uplink.delete"),
+                            aria_label: "delete-items-confirm".into(),
+                            appearance: Appearance::Danger,
+                            onpress: move |_| {
+                                for item in items.clone() {
+                                    ch.send(ChanCmd::DeleteItems(item));
+                                }
+                                confirm_delete.set(None);
+                            },
+                        },
+                    }
+                }
+            })
+        }),
     }))
 }
 
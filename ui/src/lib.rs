@@ -5,16 +5,21 @@
 #![allow(non_snake_case)]
 // the above macro will make uplink be a "window" application instead of a  "console" application for Windows.
 
+use chrono::Utc;
 use clap::Parser;
 use common::icons::outline::Shape as Icon;
 use common::icons::Icon as IconElement;
-use common::language::{get_local_text, get_local_text_with_args};
+use common::language::{get_local_text, get_local_text_with_args, text_direction};
 use common::notifications::{NotificationAction, NOTIFICATION_LISTENER};
 use common::profile_update_channel::PROFILE_CHANNEL_LISTENER;
-use common::state::data_transfer::{TrackerType, TransferTracker};
+use common::state::custom_status::CustomStatus;
+use common::state::data_transfer::{TrackerType, TransferState, TransferTracker};
+use common::state::network::RelayStatus;
+use common::state::pending_message::FileProgression;
 use common::state::settings::GlobalShortcut;
-use common::state::ui::Layout;
+use common::state::ui::{EmojiStyle, Font, Layout};
 use common::state::ToastNotification;
+use common::utils::{handoff, network_check};
 use common::warp_runner::ui_adapter::MessageEvent;
 use common::warp_runner::WarpEvent;
 use common::{get_extras_dir, warp_runner, STATIC_ARGS, WARP_CMD_CH, WARP_EVENT_CH};
@@ -45,15 +50,23 @@ use std::collections::HashMap;
 
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
 use std::time::Instant;
 
 use std::sync::Arc;
 
 use crate::components::debug_logger::DebugLogger;
 use crate::components::file_transfer::FileTransferModal;
+use crate::components::live_regions::LiveRegions;
+use crate::components::lock_screen::LockScreen;
+use crate::components::onboarding::Onboarding;
+use crate::components::profiling_overlay::ProfilingOverlay;
+use crate::components::quick_switcher::QuickSwitcher;
+use crate::components::status_bar::StatusBar;
 use crate::components::toast::Toast;
+use crate::components::topbar::connection_status::ConnectionIndicator;
 use crate::components::topbar::release_info::Release_Info;
+use crate::layouts::activity::ActivityLayout;
+use crate::layouts::calls::CallsLayout;
 use crate::layouts::community::CommunityLayout;
 use crate::layouts::friends::FriendsLayout;
 use crate::layouts::loading::{use_loaded_assets, LoadingWash};
@@ -61,7 +74,8 @@ use crate::layouts::log_in::{AuthGuard, AuthPages};
 use crate::layouts::settings::SettingsLayout;
 use crate::layouts::storage::files_layout::FilesLayout;
 use crate::misc_scripts::*;
-use crate::utils::async_task_queue::{ListenerAction, ACTION_LISTENER};
+use crate::shutdown::ShutdownCmd;
+use crate::utils::async_task_queue::{chat_upload_stream_handler, ListenerAction, ACTION_LISTENER};
 use crate::utils::keyboard::shortcut_handlers::audio::ToggleType;
 use crate::utils::keyboard::KeyboardShortcuts;
 use dioxus_desktop::wry::application::event::Event as WryEvent;
@@ -69,21 +83,22 @@ use dioxus_desktop::{use_wry_event_handler, DesktopService, PhysicalSize};
 use tokio::sync::{mpsc, Mutex};
 use tokio::time::{sleep, Duration};
 use tracing::log::{self};
+use uuid::Uuid;
 
+use muda::accelerator::{Accelerator, Code, Modifiers};
 use muda::AboutMetadata;
 use muda::Menu;
+use muda::MenuItem;
 use muda::PredefinedMenuItem;
 use muda::Submenu;
 
-use crate::utils::auto_updater::{
-    DownloadProgress, DownloadState, SoftwareDownloadCmd, SoftwareUpdateCmd,
-};
+use crate::utils::auto_updater::{DownloadProgress, DownloadState, SoftwareDownloadCmd};
 
-use crate::layouts::chats::ChatLayout;
+use crate::layouts::chats::{self, ChatLayout};
 use crate::window_manager::WindowManagerCmdChannels;
 use common::{
     state::{storage, ui::WindowMeta, Action, State},
-    warp_runner::{ConstellationCmd, RayGunCmd, WarpCmd},
+    warp_runner::{ConstellationCmd, MultiPassCmd, RayGunCmd, WarpCmd},
 };
 use std::panic;
 
@@ -92,16 +107,58 @@ pub const APP_STYLE: &str = include_str!("./compiled_styles.css");
 mod bootstrap;
 mod components;
 mod extension_browser;
+mod headless;
 mod layouts;
 mod logger;
 mod misc_scripts;
 mod overlay;
+mod shutdown;
+mod tray;
 mod utils;
 mod webview_config;
 mod window_builder;
 mod window_manager;
 
 pub static OPEN_DYSLEXIC: &str = include_str!("./open-dyslexic.css");
+// for the reduced motion setting, and when the system asks for it
+pub static REDUCED_MOTION_STYLE: &str = include_str!("./reduced-motion.css");
+
+// conversations loaded from RayGun at once during startup. other commands run between the batches
+const CONVERSATIONS_PER_BATCH: usize = 10;
+
+// installed with the other assets in the extra folder, see ui/extra/fonts
+const BUNDLED_EMOJI_FONT: &str = "fonts/TwemojiMozilla.ttf";
+
+// the installed emoji font, looked up once. None if it's missing, the emojis of the system are used then
+pub static BUNDLED_EMOJI_FONT_PATH: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    let path = get_extras_dir().ok()?.join(BUNDLED_EMOJI_FONT);
+    if path.is_file() {
+        Some(path)
+    } else {
+        log::warn!("bundled emoji font not found at {}", path.display());
+        None
+    }
+});
+
+// the families from kit/src/style.scss and the custom fonts set in get_app_style
+const EMOJI_FONT_FAMILIES: [&str; 5] = [
+    "Noto",
+    "OpenDyslexic",
+    "CustomFont",
+    "CustomMessageFont",
+    "CustomMonospaceFont",
+];
+
+// emoji code points, including the joiners and modifiers used by emoji sequences
+const EMOJI_UNICODE_RANGE: &str = "U+200D, U+203C, U+2049, U+20E3, U+2139, U+2194-21AA, U+231A-23FF, \
+    U+24C2, U+25AA-25FE, U+2600-27BF, U+2934-2935, U+2B05-2B55, U+3030, U+303D, U+3297-3299, U+FE0F, \
+    U+1F000-1FAFF, U+E0020-E007F";
+
+// disables animations and transitions while in safe mode
+pub static SAFE_MODE_STYLE: &str = "*, *::before, *::after {
+    animation: none !important;
+    transition: none !important;
+}";
 
 // used to close the popout player, among other things
 pub static WINDOW_CMD_CH: Lazy<WindowManagerCmdChannels> = Lazy::new(|| {
@@ -126,6 +183,25 @@ pub fn main_lib() {
     // 4. Make sure all system dirs are ready
     bootstrap::create_uplink_dirs();
 
+    // clicking a link launches Uplink, which hands the link to the instance that already runs
+    if let Some(link) = args.link.as_deref() {
+        if !utils::links::open_launch_link(link) {
+            return;
+        }
+    }
+
+    // the account runs without a window and is driven through the control socket
+    if args.headless {
+        if let Err(e) = headless::run() {
+            log::error!("headless mode failed: {e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // 5. Detect crash loops and decide if we should start in safe mode
+    bootstrap::configure_safe_mode(args.safe_mode);
+
     // mac needs the menu built a certain way.
     // the main_menu must not be dropped before launch_cfg is called.
     let main_menu = Menu::new();
@@ -135,7 +211,13 @@ pub fn main_lib() {
 
     let _ = app_menu.append_items(&[
         &PredefinedMenuItem::about("About".into(), Some(AboutMetadata::default())),
-        &PredefinedMenuItem::quit(None),
+        // not the predefined item, it would exit without finishing the pending work
+        &MenuItem::with_id(
+            shutdown::QUIT_MENU_ID,
+            "Quit Uplink",
+            true,
+            Some(Accelerator::new(Some(Modifiers::SUPER), Code::KeyQ)),
+        ),
     ]);
     // add native shortcuts to `edit_menu` menu
     // in macOS native item are required to get keyboard shortcut
@@ -167,7 +249,7 @@ pub fn main_lib() {
         main_menu.init_for_nsapp();
     }
 
-    // 5. Finally, launch the app
+    // 6. Finally, launch the app
     dioxus_desktop::launch_cfg(app, webview_config::webview_config())
 }
 
@@ -191,6 +273,12 @@ pub enum UplinkRoute {
     #[route("/files")]
     FilesLayout {},
 
+    #[route("/activity")]
+    ActivityLayout {},
+
+    #[route("/calls")]
+    CallsLayout {},
+
     #[route("/community")]
     CommunityLayout {},
 }
@@ -198,9 +286,16 @@ pub enum UplinkRoute {
 fn app(cx: Scope) -> Element {
     // 1. Make sure the warp engine is turned on before doing anything
     bootstrap::use_warp_runner(cx);
+    utils::links::use_link_listener(cx);
 
     // 2. Guard the app with the auth
     let auth = use_state(cx, || AuthPages::EntryPoint);
+    // set when the account was created rather than unlocked or recovered
+    let new_account = use_ref(cx, || false);
+    shutdown::use_close_before_login(cx, {
+        to_owned![auth];
+        move || matches!(*auth.current(), AuthPages::Success(_))
+    });
     let AuthPages::Success(identity) = auth.get() else {
         return render! {
         KeyboardShortcuts {
@@ -214,11 +309,11 @@ fn app(cx: Scope) -> Element {
                 log::debug!("shortcut called {:?}", shortcut);
             }
         },
-        AuthGuard { page: auth.clone() }};
+        AuthGuard { page: auth.clone(), new_account: new_account.clone() }};
     };
 
     // 3. Make sure global context is setup before rendering anything downstream
-    bootstrap::use_bootstrap(cx, identity)?;
+    bootstrap::use_bootstrap(cx, identity, *new_account.read())?;
 
     // 4. Throw up a loading screen until our assets are ready
     if use_loaded_assets(cx).value().is_none() {
@@ -235,39 +330,103 @@ fn app(cx: Scope) -> Element {
 // Eventually this restriction will be lifted once global contexts in dioxus are global accessible
 fn app_layout(cx: Scope) -> Element {
     log::trace!("rendering app");
+    utils::profiling::count_render("app_layout");
 
     // terminate the logger thread when the app exits.
     cx.use_hook(|| LogDropper {});
 
     use_auto_updater(cx)?;
     use_app_coroutines(cx)?;
+    shutdown::use_shutdown_coordinator(cx)?;
     use_router_notification_listener(cx)?;
+    tray::use_tray(cx)?;
+    utils::presence::use_auto_away(cx)?;
+    utils::automation::use_control_socket(cx)?;
+    utils::conversation_export::use_export_coroutine(cx);
 
     let state = use_shared_state::<State>(cx)?;
+    let eval: &utils::EvalProvider = use_eval(cx);
+    let navigator = use_navigator(cx);
+    // renders whenever the state changes, which keeps the app icon in sync with the chats
+    let unreads = state.read().unread_count();
+    state.write_silent().ui.notifications.update_badge(unreads);
+    let show_profiling_overlay = state.read().configuration.developer.developer_mode
+        && state.read().ui.show_profiling_overlay;
+    let locked = state.read().ui.locked;
+    let show_quick_switcher = state.read().ui.show_quick_switcher;
+    // many components look their texts up once. keying the pages by the language remounts them
+    // when it changes, so switching takes effect right away
+    let language = state.read().settings.language.clone();
+    let window = use_window(cx);
+
+    // nothing but the lock screen is rendered while locked, so there is nothing to reveal by
+    // removing it. the shortcuts aren't registered either, and the devtools are closed
+    if locked {
+        if window.webview.is_devtools_open() {
+            window.webview.close_devtools();
+        }
+        return render! {
+            AppStyle {}
+            div { id: "app-wrap",
+                dir: text_direction(),
+                Titlebar {},
+                LiveRegions {},
+                LockScreen {},
+                shutdown::FinishingUpScreen {},
+            },
+        };
+    }
 
     render! {
         AppStyle {}
         div { id: "app-wrap",
+            // mirrors the layout for languages written from right to left
+            dir: text_direction(),
             Titlebar {},
             KeyboardShortcuts {
                 on_global_shortcut: move |shortcut| {
                     match shortcut {
                         GlobalShortcut::ToggleMute => utils::keyboard::shortcut_handlers::audio::toggle(state.clone(), cx, ToggleType::Mute),
                         GlobalShortcut::ToggleDeafen => utils::keyboard::shortcut_handlers::audio::toggle(state.clone(), cx, ToggleType::Deafen),
+                        GlobalShortcut::AnswerCall => utils::keyboard::shortcut_handlers::call::answer(eval),
+                        GlobalShortcut::HangUpCall => utils::keyboard::shortcut_handlers::call::hang_up(eval),
+                        GlobalShortcut::CycleCallParticipants => utils::keyboard::shortcut_handlers::call::cycle_participants(eval),
                         GlobalShortcut::IncreaseFontSize => utils::keyboard::shortcut_handlers::font::increase_size(state.clone()),
                         GlobalShortcut::DecreaseFontSize => utils::keyboard::shortcut_handlers::font::decrease_size(state.clone()),
+                        GlobalShortcut::ZoomInChat => utils::keyboard::shortcut_handlers::font::zoom_chat(state.clone(), Some(true)),
+                        GlobalShortcut::ZoomOutChat => utils::keyboard::shortcut_handlers::font::zoom_chat(state.clone(), Some(false)),
+                        GlobalShortcut::ResetChatZoom => utils::keyboard::shortcut_handlers::font::zoom_chat(state.clone(), None),
                         GlobalShortcut::OpenCloseDevTools => utils::keyboard::shortcut_handlers::dev::open_close_dev_tools(cx),
                         GlobalShortcut::ToggleDevmode => utils::keyboard::shortcut_handlers::dev::toggle_devmode(state.clone()),
+                        GlobalShortcut::ToggleProfilingOverlay => utils::keyboard::shortcut_handlers::dev::toggle_profiling_overlay(state.clone()),
                         GlobalShortcut::SetAppVisible => utils::keyboard::shortcut_handlers::navigation::set_app_visible(cx),
+                        GlobalShortcut::OpenQuickSwitcher => utils::keyboard::shortcut_handlers::navigation::toggle_quick_switcher(state.clone()),
+                        GlobalShortcut::NextChat => utils::keyboard::shortcut_handlers::navigation::cycle_chats(state.clone(), navigator, true),
+                        GlobalShortcut::PreviousChat => utils::keyboard::shortcut_handlers::navigation::cycle_chats(state.clone(), navigator, false),
+                        GlobalShortcut::OpenSettings => utils::keyboard::shortcut_handlers::navigation::open_settings(state.clone(), navigator),
+                        GlobalShortcut::LockApp => utils::keyboard::shortcut_handlers::navigation::lock(state.clone()),
+                        GlobalShortcut::SendMessage => utils::keyboard::shortcut_handlers::chat::send_message(state.clone(), eval),
+                        GlobalShortcut::FocusSearch => utils::keyboard::shortcut_handlers::chat::focus_search(state.clone(), navigator, eval),
                         GlobalShortcut::Unknown => log::error!("Unknown `Shortcut` called!")
                     }
                     log::debug!("shortcut called {:?}", shortcut);
                 }
             },
             Toasts {},
-            Outlet::<UplinkRoute>{},
+            LiveRegions {},
+            std::iter::once(language).map(|language| rsx!(
+                Fragment {
+                    key: "{language}",
+                    Outlet::<UplinkRoute>{},
+                    StatusBar {},
+                }
+            )),
             AppLogger {},
             PrismScripts {},
+            shutdown::FinishingUpScreen {},
+            show_profiling_overlay.then(|| rsx!(ProfilingOverlay {})),
+            Onboarding {},
+            show_quick_switcher.then(|| rsx!(QuickSwitcher {})),
         },
     }
 }
@@ -280,22 +439,28 @@ fn AppStyle(cx: Scope) -> Element {
 }
 
 pub fn get_app_style(state: &State) -> String {
-    let mut font_style = String::new();
-    if let Some(font) = state.ui.font.clone() {
-        font_style = format!(
-            "
-        @font-face {{
-            font-family: CustomFont;
-            src: url('{}');
-        }}
-        body,
-        html {{
-            font-family: CustomFont, sans-serif;
-        }}
-        ",
-            font.path
-        );
-    }
+    // safe mode ignores custom fonts, themes and accent colors in case they are what broke the app
+    let safe_mode = bootstrap::is_safe_mode();
+    let font_style = if safe_mode {
+        String::new()
+    } else {
+        [
+            font_face(&state.ui.font, "CustomFont", "body, html", "sans-serif"),
+            font_face(
+                &state.ui.message_font,
+                "CustomMessageFont",
+                ".message .text, .message .pending-text",
+                "sans-serif",
+            ),
+            font_face(
+                &state.ui.monospace_font,
+                "CustomMonospaceFont",
+                "pre, code",
+                "monospace",
+            ),
+        ]
+        .concat()
+    };
 
     // this gets rendered at the bottom. this way you don't have to scroll past all the use_futures to see what this function renders
 
@@ -306,16 +471,34 @@ pub fn get_app_style(state: &State) -> String {
         ""
     };
 
+    let reduced_motion = if state.configuration.general.reduced_motion {
+        REDUCED_MOTION_STYLE.to_string()
+    } else {
+        format!("@media (prefers-reduced-motion: reduce) {{ {REDUCED_MOTION_STYLE} }}")
+    };
+
     let font_scale = format!("html {{ font-size: {}rem; }}", state.settings.font_scale());
 
+    let monitor_scale = match state.ui.monitor_scale() {
+        scale if scale != 1.0 => format!("html {{ zoom: {scale}; }}"),
+        _ => String::new(),
+    };
+
     let theme = state
         .ui
         .theme
         .as_ref()
+        .filter(|_| !safe_mode)
         .map(|theme| theme.styles.clone())
         .unwrap_or_default();
+    let theme_preview = state
+        .ui
+        .theme_preview
+        .clone()
+        .filter(|_| !safe_mode)
+        .unwrap_or_default();
 
-    let accent_color = state.ui.accent_color;
+    let accent_color = state.ui.accent_color.filter(|_| !safe_mode);
     let accent_color = if let Some(color) = accent_color {
         format!(
             ":root {{
@@ -331,7 +514,56 @@ pub fn get_app_style(state: &State) -> String {
         "".into()
     };
 
-    format!("{UIKIT_STYLES} {APP_STYLE} {PRISM_STYLE} {PRISM_THEME} {theme} {accent_color} {font_style} {open_dyslexic} {font_scale}")
+    let emoji_style = match state.ui.emoji_style {
+        EmojiStyle::Native => String::new(),
+        EmojiStyle::Bundled => bundled_emoji_style(),
+    };
+
+    let safe_mode_style = if safe_mode { SAFE_MODE_STYLE } else { "" };
+
+    format!("{UIKIT_STYLES} {APP_STYLE} {PRISM_STYLE} {PRISM_THEME} {theme} {theme_preview} {accent_color} {font_style} {open_dyslexic} {reduced_motion} {emoji_style} {font_scale} {monitor_scale} {safe_mode_style}")
+}
+
+// adds the bundled emoji font to every font family used by the app. because of the unicode-range only
+// emojis are drawn with it, everything else still uses the regular font of the family.
+fn bundled_emoji_style() -> String {
+    let Some(path) = BUNDLED_EMOJI_FONT_PATH.as_ref() else {
+        return String::new();
+    };
+    // loaded like the custom fonts, from the absolute path of the installed file
+    let path = path.to_string_lossy().replace('\\', "/");
+    EMOJI_FONT_FAMILIES
+        .iter()
+        .map(|family| {
+            format!(
+                "
+                @font-face {{
+                    font-family: {family};
+                    src: url('{path}');
+                    unicode-range: {EMOJI_UNICODE_RANGE};
+                }}
+                "
+            )
+        })
+        .collect()
+}
+
+fn font_face(font: &Option<Font>, family: &str, selector: &str, fallback: &str) -> String {
+    let Some(font) = font else {
+        return String::new();
+    };
+    format!(
+        "
+        @font-face {{
+            font-family: {family};
+            src: url('{}');
+        }}
+        {selector} {{
+            font-family: {family}, {fallback};
+        }}
+        ",
+        font.path
+    )
 }
 
 // Decide if text should be dark or bright
@@ -348,33 +580,75 @@ fn get_text_color(r: u8, g: u8, b: u8) -> &'static str {
 }
 
 fn use_auto_updater(cx: &ScopeState) -> Option<()> {
+    let state = use_shared_state::<State>(cx)?;
     let download_state = use_shared_state::<DownloadState>(cx)?;
-    let updater_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<SoftwareUpdateCmd>| {
-        to_owned![download_state];
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
+    let _download_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<SoftwareDownloadCmd>| {
+        to_owned![state, download_state, file_tracker];
         async move {
-            while let Some(mut ch) = rx.next().await {
-                while let Some(percent) = ch.0.recv().await {
-                    if percent >= download_state.read().progress + 5_f32 {
-                        download_state.write().progress = percent;
+            while let Some(SoftwareDownloadCmd(dest, release)) = rx.next().await {
+                let id = Uuid::new_v4();
+                let transfer_state = TransferState::new();
+                {
+                    let mut download_state = download_state.write();
+                    download_state.stage = DownloadProgress::Pending;
+                    download_state.progress = 0_f32;
+                }
+                file_tracker.write().start_file_transfer(
+                    id,
+                    get_local_text("uplink.update-installer"),
+                    transfer_state.clone(),
+                    TrackerType::FileDownload,
+                );
+
+                let (tx, mut progress_rx) = mpsc::unbounded_channel();
+                let download = tokio::spawn(utils::auto_updater::download_installer(
+                    dest,
+                    release,
+                    transfer_state,
+                    tx,
+                ));
+                while let Some(progress) = progress_rx.recv().await {
+                    if let FileProgression::CurrentProgress {
+                        current,
+                        total: Some(total),
+                        ..
+                    } = &progress
+                    {
+                        download_state.write().progress = 100_f32 * *current as f32 / *total as f32;
                     }
+                    file_tracker.write().update_file_upload(
+                        id,
+                        progress,
+                        TrackerType::FileDownload,
+                    );
                 }
-                download_state.write().stage = DownloadProgress::Finished;
-            }
-        }
-    });
+                file_tracker
+                    .write()
+                    .remove_file_upload(id, TrackerType::FileDownload);
 
-    let _download_ch = use_coroutine(cx, |mut rx: UnboundedReceiver<SoftwareDownloadCmd>| {
-        to_owned![updater_ch];
-        async move {
-            while let Some(dest) = rx.next().await {
-                let (tx, rx) = mpsc::unbounded_channel::<f32>();
-                updater_ch.send(SoftwareUpdateCmd(rx));
-                match utils::auto_updater::download_update(dest.0.clone(), tx).await {
-                    Ok(downloaded_version) => {
-                        log::debug!("downloaded version {downloaded_version}");
+                let result = match download.await {
+                    Ok(result) => result,
+                    Err(e) => Err(e.into()),
+                };
+                match result {
+                    Ok(installer) => {
+                        log::debug!("downloaded update to {}", installer.display());
+                        let mut download_state = download_state.write();
+                        download_state.destination = Some(installer);
+                        download_state.stage = DownloadProgress::Finished;
                     }
                     Err(e) => {
                         log::error!("failed to download update: {e}");
+                        download_state.write().stage = DownloadProgress::Idle;
+                        state.write().mutate(Action::AddToastNotification(
+                            ToastNotification::init(
+                                "".into(),
+                                get_local_text("uplink.update-failed"),
+                                None,
+                                4,
+                            ),
+                        ));
                     }
                 }
             }
@@ -384,8 +658,32 @@ fn use_auto_updater(cx: &ScopeState) -> Option<()> {
     Some(())
 }
 
+/// downloads and verifies the installer of the release which was offered in the background. builds
+/// which can't verify installers show the steps to update manually instead
+pub(crate) fn start_update_download(
+    download_state: &UseSharedState<DownloadState>,
+    download_ch: &Coroutine<SoftwareDownloadCmd>,
+) {
+    let release = download_state.read().release.clone();
+    if let Some(release) = release.filter(|_| utils::auto_updater::can_install_updates()) {
+        download_state.write().stage = DownloadProgress::Pending;
+        download_ch.send(SoftwareDownloadCmd(
+            STATIC_ARGS.temp_files.join("updates"),
+            release,
+        ));
+    } else {
+        download_state.write().stage = DownloadProgress::PickFolder;
+    }
+}
+
 fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
     let desktop = use_window(cx);
+    // the taskbar overlay is drawn on the main window
+    #[cfg(target_os = "windows")]
+    cx.use_hook(|| {
+        use dioxus_desktop::tao::platform::windows::WindowExtWindows;
+        common::notifications::set_badge_window(desktop.hwnd() as isize);
+    });
     let state = use_shared_state::<State>(cx)?;
 
     // don't fetch stuff from warp when using mock data
@@ -427,11 +725,6 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
                 //log::trace!("FOCUS CHANGED {:?}", *focused);
                 if state.read().ui.metadata.focused != *focused {
                     state.write().ui.metadata.focused = *focused;
-
-                    if *focused {
-                        state.write().ui.notifications.clear_badge();
-                        let _ = state.write().save();
-                    }
                 }
             }
             WryEvent::WindowEvent {
@@ -448,8 +741,22 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
                 let position =
                     scaled_window_position(desktop.outer_position().unwrap_or_default(), &desktop);
                 state.write_silent().ui.window_position = Some((position.x, position.y));
+                update_current_monitor(&state, &desktop);
                 let _ = state.write().save();
             }
+            WryEvent::WindowEvent {
+                event: WindowEvent::ScaleFactorChanged { .. },
+                ..
+            } => {
+                // the window was moved to a display with a different DPI. keep the same logical size
+                // instead of letting the window grow or shrink with the new scale factor.
+                if let Some((width, height)) = state.read().ui.window_size {
+                    if !desktop.is_maximized() && desktop.fullscreen().is_none() {
+                        desktop.set_inner_size(LogicalSize::new(width, height));
+                    }
+                }
+                update_current_monitor(&state, &desktop);
+            }
             WryEvent::WindowEvent {
                 event: WindowEvent::Resized(_),
                 ..
@@ -472,6 +779,7 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
                     desktop.set_outer_position(LogicalPosition::new(pos_x, pos_y));
                     *first_resize.write_silent() = false;
                 }
+                update_current_monitor(&state, &desktop);
                 let size = scaled_window_size(webview.inner_size(), &desktop);
                 let metadata = state.read().ui.metadata.clone();
                 let new_metadata = WindowMeta {
@@ -505,6 +813,17 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
         }
     });
 
+    // writes the parts of the state which changed, once the changes settle down
+    use_future(cx, (), |_| {
+        to_owned![state];
+        async move {
+            loop {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                state.read().save_if_due();
+            }
+        }
+    });
+
     // update state in response to warp events
     use_future(cx, (), |_| {
         to_owned![state];
@@ -683,11 +1002,36 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
                     if let Some(v) = state.read().scope_ids.file_transfer_icon {
                         schedule(ScopeId(v))
                     }
+                    if let Some(v) = state.read().scope_ids.status_bar {
+                        schedule(ScopeId(v))
+                    }
+                    if let Some(v) = state.read().scope_ids.call_transfers {
+                        schedule(ScopeId(v))
+                    }
                 }
             }
         }
     });
 
+    // let the user know they are in safe mode, and reset the crash loop detection once the app is stable
+    use_future(cx, (), |_| {
+        to_owned![state];
+        async move {
+            if bootstrap::is_safe_mode() {
+                state
+                    .write()
+                    .mutate(Action::AddToastNotification(ToastNotification::init(
+                        get_local_text("safe-mode.title"),
+                        get_local_text("safe-mode.active"),
+                        None,
+                        10,
+                    )));
+            }
+            sleep(Duration::from_secs(bootstrap::STABLE_RUN_SECONDS)).await;
+            bootstrap::mark_startup_successful();
+        }
+    });
+
     // clear toasts
     use_future(cx, (), |_| {
         to_owned![state];
@@ -703,6 +1047,79 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
         }
     });
 
+    // send the messages in the outbox again. they are queued when sending fails, usually because the node is offline
+    let upload_streams = chat_upload_stream_handler(cx);
+    use_future(cx, (), |_| {
+        to_owned![state, upload_streams];
+        async move {
+            let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            loop {
+                sleep(Duration::from_secs(1)).await;
+                let due = state.read().due_queued_messages();
+                for queued in due {
+                    let (tx, rx) = oneshot::channel();
+                    let cmd = match queued.replying_to {
+                        Some(reply_to) => RayGunCmd::Reply {
+                            conv_id: queued.conv_id,
+                            reply_to,
+                            msg: queued.msg.clone(),
+                            attachments: queued.attachments.clone(),
+                            rsp: tx,
+                        },
+                        None => RayGunCmd::SendMessage {
+                            conv_id: queued.conv_id,
+                            msg: queued.msg.clone(),
+                            attachments: queued.attachments.clone(),
+                            rsp: tx,
+                        },
+                    };
+                    if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(cmd)) {
+                        log::error!("failed to send warp command: {}", e);
+                        break;
+                    }
+                    match rx.await.expect("command canceled") {
+                        Ok((id, stream)) => {
+                            log::debug!("sent queued message {}", queued.id);
+                            state.write().queued_message_sent(&queued, id);
+                            if let Some(stream) = stream {
+                                upload_streams.write().append((queued.conv_id, id, stream));
+                            }
+                        }
+                        Err(e) => {
+                            log::debug!("failed to send queued message again: {}", e);
+                            state.write_silent().queued_message_failed(&queued.id);
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    // keeps the connection indicator up to date. checks more often while offline so it recovers quickly
+    use_future(cx, (), |_| {
+        to_owned![state];
+        async move {
+            loop {
+                let probe = network_check::probe(Duration::from_secs(5)).await;
+                let reconnected = {
+                    let mut state = state.write();
+                    let reconnected = state.ui.network.update(probe);
+                    if reconnected {
+                        log::debug!("relay reachable again, retrying queued messages");
+                        state.retry_queued_messages();
+                    }
+                    reconnected
+                };
+                let offline = probe.relay == RelayStatus::Unreachable;
+                if offline || reconnected {
+                    sleep(Duration::from_secs(10)).await;
+                } else {
+                    sleep(Duration::from_secs(30)).await;
+                }
+            }
+        }
+    });
+
     //Update active call
     use_future(cx, (), |_| {
         to_owned![state];
@@ -743,9 +1160,50 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
         }
     });
 
-    // check for updates
+    // clears our custom status once it expires, for the clients which don't know about the expiry
     use_future(cx, (), |_| {
         to_owned![state];
+        async move {
+            loop {
+                sleep(Duration::from_secs(60)).await;
+                let identity = state.read().get_own_identity();
+                let expired = identity.status_message().map_or(false, |message| {
+                    CustomStatus::from_identity(&message, &identity.metadata())
+                        .is_expired(Utc::now())
+                });
+                if !expired {
+                    continue;
+                }
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) =
+                    WARP_CMD_CH
+                        .tx
+                        .send(WarpCmd::MultiPass(MultiPassCmd::UpdateCustomStatus {
+                            status: CustomStatus::default(),
+                            rsp: tx,
+                        }))
+                {
+                    log::error!("failed to send warp command: {}", e);
+                    continue;
+                }
+                match rx.await {
+                    Ok(Ok(mut identity)) => {
+                        let current = state.read().get_own_identity();
+                        identity.copy_profile_picture(&current);
+                        identity.set_profile_banner(&current.profile_banner());
+                        state.write().set_own_identity(identity);
+                    }
+                    Ok(Err(e)) => log::error!("failed to clear the expired status: {e}"),
+                    Err(e) => log::error!("failed to clear the expired status: {e}"),
+                }
+            }
+        }
+    });
+
+    // check for updates
+    let download_state = use_shared_state::<DownloadState>(cx)?;
+    use_future(cx, (), |_| {
+        to_owned![state, download_state];
         async move {
             loop {
                 let latest_release = match utils::auto_updater::check_for_release().await {
@@ -766,7 +1224,22 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
                     sleep(Duration::from_secs(3600 * 24)).await;
                     continue;
                 }
-                state.write().update_available(latest_release.tag_name);
+                let version = latest_release.tag_name.clone();
+                download_state.write().release = Some(latest_release);
+                if state.read().settings.update_available.as_ref() != Some(&version) {
+                    state
+                        .write()
+                        .mutate(Action::AddToastNotification(ToastNotification::init(
+                            "".into(),
+                            get_local_text_with_args(
+                                "uplink.update-available",
+                                vec![("version", version.clone())],
+                            ),
+                            None,
+                            5,
+                        )));
+                }
+                state.write().update_available(version);
                 sleep(Duration::from_secs(3600 * 24)).await;
             }
         }
@@ -808,11 +1281,16 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
             );
 
             let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+            let merged = state.read().chats.merged_by_chat();
+            let (roles, muted) = state.read().chats.group_roles();
             let res = loop {
                 let (tx, rx) = oneshot::channel();
-                if let Err(e) =
-                    warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::InitializeWarp { rsp: tx }))
-                {
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::InitializeWarp {
+                    merged: merged.clone(),
+                    roles: roles.clone(),
+                    muted: muted.clone(),
+                    rsp: tx,
+                })) {
                     log::error!("failed to send command to initialize warp {}", e);
                     tokio::time::sleep(Duration::from_secs(1)).await;
                     continue;
@@ -834,7 +1312,36 @@ fn use_app_coroutines(cx: &ScopeState) -> Option<()> {
 
             state
                 .write()
-                .init_warp(res.friends, res.chats, res.converted_identities);
+                .init_warp(res.friends, res.converted_identities);
+
+            // the chats in the sidebar first, from the top, so it fills in as they load
+            let mut conversations = res.conversations;
+            {
+                let state = state.read();
+                conversations.retain(|id| !state.chats.merged.contains_key(id));
+                let in_sidebar = &state.chats.in_sidebar;
+                conversations.sort_by_key(|id| {
+                    in_sidebar
+                        .iter()
+                        .position(|x| x == id)
+                        .unwrap_or(usize::MAX)
+                });
+            }
+            for batch in conversations.chunks(CONVERSATIONS_PER_BATCH) {
+                let (tx, rx) = oneshot::channel();
+                if let Err(e) = warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::LoadConversations {
+                    conv_ids: batch.to_vec(),
+                    rsp: tx,
+                })) {
+                    log::error!("failed to send command to load conversations {}", e);
+                    break;
+                }
+                match rx.await {
+                    Ok(chats) => state.write().load_chats(chats),
+                    Err(_) => break,
+                }
+            }
+            state.write().finish_loading_chats();
         }
     });
 
@@ -928,8 +1435,7 @@ fn get_update_icon(cx: Scope) -> Element {
     log::trace!("rendering get_update_icon");
     let state = use_shared_state::<State>(cx)?;
     let download_state = use_shared_state::<DownloadState>(cx)?;
-    let desktop = use_window(cx);
-    let _download_ch = use_coroutine_handle::<SoftwareDownloadCmd>(cx)?;
+    let download_ch = use_coroutine_handle::<SoftwareDownloadCmd>(cx)?;
 
     let new_version = match state.read().settings.update_available.as_ref() {
         Some(u) => u.clone(),
@@ -962,19 +1468,13 @@ fn get_update_icon(cx: Scope) -> Element {
                     ContextItem {
                         aria_label: "update-menu-download".into(),
                         text: get_local_text("uplink.update-menu-download"),
-                        onpress: move |_| {
-                            download_state.write().stage = DownloadProgress::PickFolder;
-
-                        }
+                        onpress: move |_| start_update_download(download_state, download_ch),
                     }
                 )),
                 div {
                     id: "update-available",
                     aria_label: "update-available",
-                    onclick: move |_| {
-                        download_state.write().stage = DownloadProgress::PickFolder;
-
-                    },
+                    onclick: move |_| start_update_download(download_state, download_ch),
                     IconElement {
                         icon: common::icons::solid::Shape::ArrowDownCircle,
                     },
@@ -993,51 +1493,93 @@ fn get_update_icon(cx: Scope) -> Element {
             //     download_ch.send(SoftwareDownloadCmd(dest));
             // }
         })),
-        DownloadProgress::_Pending => cx.render(rsx!(div {
+        DownloadProgress::Pending => cx.render(rsx!(div {
             id: "update-available",
             class: "topbar-item",
             aria_label: "update-available",
             "{downloading_msg}"
         })),
-        DownloadProgress::Finished => {
-            cx.render(rsx!(div {
-                id: "update-available",
-                class: "topbar-item",
-                aria_label: "update-available",
-                onclick: move |_| {
-                    // be sure to update this before closing the app
-                    state.write().mutate(Action::DismissUpdate);
-                    if let Some(dest) = download_state.read().destination.clone() {
-                        std::thread::spawn(move ||  {
-
-                            let cmd = if cfg!(target_os = "windows") {
-                                "explorer"
-                            } else if cfg!(target_os = "linux") {
-                                "xdg-open"
-                            } else if cfg!(target_os = "macos") {
-                                "open"
-                            } else {
-                               eprintln!("unknown OS type. failed to open files browser");
-                               return;
-                            };
-                            Command::new(cmd)
-                            .arg(dest)
-                            .spawn()
-                            .unwrap();
-                        });
-                        desktop.close();
-                    } else {
-                        log::error!("attempted to download update without download location");
-                    }
-                    download_state.write().destination = None;
-                    download_state.write().stage = DownloadProgress::Idle;
-                },
-                "{downloaded_msg}"
-            }))
-        }
+        DownloadProgress::Finished => cx.render(rsx!(div {
+            id: "update-available",
+            class: "topbar-item",
+            aria_label: "update-available",
+            onclick: move |_| {
+                download_state.write().stage = DownloadProgress::ConfirmInstall;
+            },
+            "{downloaded_msg}"
+        })),
+        DownloadProgress::ConfirmInstall => cx.render(rsx!(get_install_modal {
+            on_dismiss: move |_| {
+                download_state.write().stage = DownloadProgress::Finished;
+            },
+        })),
     }
 }
 
+/// asks before closing Uplink and starting the downloaded installer
+#[component(no_case_check)]
+pub fn get_install_modal<'a>(cx: Scope<'a>, on_dismiss: EventHandler<'a, ()>) -> Element<'a> {
+    let state = use_shared_state::<State>(cx)?;
+    let download_state = use_shared_state::<DownloadState>(cx)?;
+    let shutdown_ch = use_coroutine_handle::<ShutdownCmd>(cx)?;
+    let version = state
+        .read()
+        .settings
+        .update_available
+        .clone()
+        .unwrap_or_default();
+
+    cx.render(rsx!(Modal {
+        onclose: move |_| on_dismiss.call(()),
+        open: true,
+        transparent: false,
+        close_on_click_inside_modal: false,
+        children: cx.render(rsx!(
+            div {
+                class: "download-modal disp-flex col",
+                h1 {
+                    get_local_text_with_args("updates.install-title", vec![("version", version)])
+                },
+                p {
+                    get_local_text("updates.install-description")
+                },
+                div {
+                    class: "disp-flex row",
+                    Button {
+                        text: get_local_text("updates.install-later"),
+                        aria_label: "install-later-button".into(),
+                        appearance: Appearance::Secondary,
+                        onpress: move |_| on_dismiss.call(()),
+                    },
+                    Button {
+                        text: get_local_text("uplink.update-menu-install"),
+                        aria_label: "install-update-button".into(),
+                        onpress: move |_| {
+                            let Some(installer) = download_state.read().destination.clone() else {
+                                log::error!("attempted to install update without an installer");
+                                return;
+                            };
+                            match utils::auto_updater::launch_installer(&installer) {
+                                Ok(_) => {
+                                    // be sure to update this before closing the app
+                                    state.write().mutate(Action::DismissUpdate);
+                                    download_state.write().destination = None;
+                                    download_state.write().stage = DownloadProgress::Idle;
+                                    shutdown_ch.send(ShutdownCmd::Begin);
+                                }
+                                Err(e) => {
+                                    log::error!("failed to launch installer: {e}");
+                                    on_dismiss.call(());
+                                }
+                            }
+                        },
+                    },
+                }
+            }
+        ))
+    }))
+}
+
 #[component(no_case_check)]
 pub fn get_download_modal<'a>(
     cx: Scope<'a>,
@@ -1137,6 +1679,7 @@ fn Toasts(cx: Scope) -> Element {
 
 fn Titlebar(cx: Scope) -> Element {
     let desktop = use_window(cx);
+    let shutdown_ch = use_coroutine_handle::<ShutdownCmd>(cx)?;
 
     cx.render(rsx!(
         div {
@@ -1148,8 +1691,11 @@ fn Titlebar(cx: Scope) -> Element {
             },
             span {
                 class: "inline-controls",
+                ConnectionIndicator {},
                 get_update_icon{},
-                TopbarControls {}
+                TopbarControls {
+                    onclose: move |_| shutdown_ch.send(ShutdownCmd::Begin),
+                }
             },
         },
     ))
@@ -1159,22 +1705,30 @@ fn use_router_notification_listener(cx: &ScopeState) -> Option<()> {
     // this use_future replaces the notification_action_handler.
     let state = use_shared_state::<State>(cx)?;
     let navigator = use_navigator(cx);
+    let desktop = use_window(cx);
     use_future(cx, (), |_| {
-        to_owned![state, navigator];
+        to_owned![state, navigator, desktop];
         async move {
             let mut ch = NOTIFICATION_LISTENER.tx.subscribe();
             log::trace!("starting notification action listener");
+            // a link may have been clicked before the user logged in
+            let mut cmd = Some(NotificationAction::OpenHandoff);
             loop {
-                let cmd = match ch.recv().await {
-                    Ok(cmd) => cmd,
-                    Err(RecvError::Closed) => {
-                        log::debug!("RecvError::Closed while reading from NOTIFICATION_LISTENER");
-                        return;
-                    }
-                    _ => {
-                        tokio::time::sleep(Duration::from_millis(100)).await;
-                        continue;
-                    }
+                let cmd = match cmd.take() {
+                    Some(cmd) => cmd,
+                    None => match ch.recv().await {
+                        Ok(cmd) => cmd,
+                        Err(RecvError::Closed) => {
+                            log::debug!(
+                                "RecvError::Closed while reading from NOTIFICATION_LISTENER"
+                            );
+                            return;
+                        }
+                        _ => {
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                            continue;
+                        }
+                    },
                 };
                 log::debug!("handling notification action {:#?}", cmd);
                 match cmd {
@@ -1187,6 +1741,41 @@ fn use_router_notification_listener(cx: &ScopeState) -> Option<()> {
                         // in this case, the layout would be FriendRoute::Pending
                         navigator.replace(UplinkRoute::FriendsLayout {});
                     }
+                    NotificationAction::OpenHandoff => {
+                        // the conversation of the link may not be loaded yet
+                        while !state.read().chats_loaded {
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                        }
+                        let Some(handoff) = handoff::take_pending() else {
+                            continue;
+                        };
+                        if state
+                            .read()
+                            .get_chat_by_id(handoff.conversation_id)
+                            .is_none()
+                        {
+                            state.write().mutate(Action::AddToastNotification(
+                                ToastNotification::init(
+                                    "".into(),
+                                    get_local_text("messages.handoff-not-found"),
+                                    None,
+                                    2,
+                                ),
+                            ));
+                            continue;
+                        }
+                        state.write_silent().ui.open_handoff(handoff);
+                        state
+                            .write()
+                            .mutate(Action::ChatWith(&handoff.conversation_id, true));
+                        navigator.replace(UplinkRoute::ChatLayout {});
+                        desktop.set_visible(true);
+                        desktop.set_focus();
+                    }
+                    NotificationAction::Reply(conv_id, text) => {
+                        // sent like from the chatbar: mentions are resolved, and the outbox sends it again if it fails
+                        chats::send_text(&state, conv_id, &text).await;
+                    }
                     _ => {}
                 }
             }
@@ -1197,6 +1786,10 @@ fn use_router_notification_listener(cx: &ScopeState) -> Option<()> {
 }
 
 fn get_extensions() -> Result<HashMap<String, UplinkExtension>, Box<dyn std::error::Error>> {
+    if bootstrap::is_safe_mode() {
+        log::debug!("safe mode: not loading extensions");
+        return Ok(HashMap::new());
+    }
     fs::create_dir_all(&STATIC_ARGS.extensions_path)?;
     let mut extensions = HashMap::new();
 
@@ -1237,33 +1830,31 @@ fn get_extensions() -> Result<HashMap<String, UplinkExtension>, Box<dyn std::err
     Ok(extensions)
 }
 
+// window sizes and positions are saved in logical pixels so they are restored correctly on displays with a different DPI
 fn scaled_window_size(
     inner: PhysicalSize<u32>,
     desktop: &std::rc::Rc<DesktopService>,
 ) -> PhysicalSize<u32> {
-    if cfg!(target_os = "macos") {
-        // On Mac window sizes are kinda funky.
-        // They are scaled with the window scale factor so they dont correspond to app pixels
-        let logical: LogicalSize<f64> = (inner.width as f64, inner.height as f64).into();
-        let scale = desktop.webview.window().scale_factor();
-        logical.to_physical(1_f64 / scale)
-    } else {
-        inner
-    }
+    let scale = desktop.webview.window().scale_factor();
+    let logical: LogicalSize<u32> = inner.to_logical(scale);
+    PhysicalSize::new(logical.width, logical.height)
 }
 
 fn scaled_window_position(
     position: PhysicalPosition<i32>,
     desktop: &std::rc::Rc<DesktopService>,
 ) -> PhysicalPosition<i32> {
-    if cfg!(target_os = "macos") {
-        // On Mac window the positions are kinda funky.
-        // They are scaled with the window scale factor so they dont correspond to actual position
-        let logical: LogicalPosition<f64> = (position.x as f64, position.y as f64).into();
-        let scale = desktop.webview.window().scale_factor();
-        logical.to_physical(1_f64 / scale)
-    } else {
-        position
+    let scale = desktop.webview.window().scale_factor();
+    let logical: LogicalPosition<i32> = position.to_logical(scale);
+    PhysicalPosition::new(logical.x, logical.y)
+}
+
+// keeps track of which monitor the window is on, so the right UI scale is applied
+fn update_current_monitor(state: &UseSharedState<State>, desktop: &std::rc::Rc<DesktopService>) {
+    let monitor = desktop.current_monitor().and_then(|m| m.name());
+    if state.read().ui.current_monitor != monitor {
+        log::debug!("window moved to monitor {monitor:?}");
+        state.write().ui.current_monitor = monitor;
     }
 }
 
@@ -1281,6 +1872,7 @@ fn AppNav<'a>(
     state.write_silent().scope_ids.file_transfer_icon = Some(cx.scope_id().0);
     let navigator = use_navigator(cx);
     let pending_friends = state.read().friends().incoming_requests.len();
+    let missed_calls = state.read().call_log.unseen_missed(&state.read().did_key());
     let unreads: u32 = state
         .read()
         .chats_sidebar()
@@ -1310,10 +1902,25 @@ fn AppNav<'a>(
         }),
         ..UIRoute::default()
     };
+    let dnd = state.read().settings.do_not_disturb.enabled;
     let settings_route = UIRoute {
         to: "/settings",
         name: get_local_text("settings.settings"),
         icon: Icon::Cog6Tooth,
+        context_items: Some(cx.render(rsx!(ContextItem {
+            aria_label: "toggle-do-not-disturb".into(),
+            icon: if dnd { Icon::Bell } else { Icon::BellSlash },
+            text: get_local_text(if dnd {
+                "settings-notifications.turn-off-dnd"
+            } else {
+                "settings-notifications.turn-on-dnd"
+            }),
+            onpress: move |_| {
+                let mut dnd = state.read().settings.do_not_disturb.clone();
+                dnd.enabled = !dnd.enabled;
+                state.write().mutate(Action::SetDoNotDisturb(dnd));
+            }
+        },))),
         ..UIRoute::default()
     };
     let friends_route = UIRoute {
@@ -1341,7 +1948,31 @@ fn AppNav<'a>(
         }),
         ..UIRoute::default()
     };
-    let _routes = vec![chat_route, files_route, friends_route, settings_route];
+    let activity_route = UIRoute {
+        to: "/activity",
+        name: get_local_text("activity.activity"),
+        icon: Icon::Bell,
+        ..UIRoute::default()
+    };
+    let calls_route = UIRoute {
+        to: "/calls",
+        name: get_local_text("calls.calls"),
+        icon: Icon::Phone,
+        with_badge: if missed_calls > 0 {
+            Some(missed_calls.to_string())
+        } else {
+            None
+        },
+        ..UIRoute::default()
+    };
+    let _routes = vec![
+        chat_route,
+        files_route,
+        friends_route,
+        activity_route,
+        calls_route,
+        settings_route,
+    ];
 
     render!(kit::components::nav::Nav {
         routes: _routes,
@@ -1350,6 +1981,8 @@ fn AppNav<'a>(
             UplinkRoute::SettingsLayout {} => "/settings",
             UplinkRoute::FriendsLayout {} => "/friends",
             UplinkRoute::FilesLayout {} => "/files",
+            UplinkRoute::ActivityLayout {} => "/activity",
+            UplinkRoute::CallsLayout {} => "/calls",
             _ => "",
         },
         onnavigate: move |r| {
@@ -1362,6 +1995,8 @@ fn AppNav<'a>(
                 "/settings" => UplinkRoute::SettingsLayout {},
                 "/friends" => UplinkRoute::FriendsLayout {},
                 "/files" => UplinkRoute::FilesLayout {},
+                "/activity" => UplinkRoute::ActivityLayout {},
+                "/calls" => UplinkRoute::CallsLayout {},
                 _ => UplinkRoute::ChatLayout {},
             };
 
@@ -0,0 +1,177 @@
+//! reads the entries back from debug.log for the log viewer in the developer settings.
+//! lines which don't start with a timestamp belong to the message of the entry before them.
+//! entries written before the module was logged are read with an empty module.
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use log::Level;
+
+use super::Log;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// follows the end of the log file
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    // the end of a line which wasn't completely written yet
+    partial: String,
+}
+
+impl LogTail {
+    /// starts at most `max_bytes` before the end of the file
+    pub fn open(path: PathBuf, max_bytes: u64) -> Self {
+        let len = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            path,
+            offset: len.saturating_sub(max_bytes),
+            partial: String::new(),
+        }
+    }
+
+    /// the entries added since the last call. starts over if the file was truncated.
+    pub fn read_new(&mut self) -> io::Result<Vec<Log>> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if len == self.offset {
+            return Ok(vec![]);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut buf = Vec::with_capacity((len - self.offset) as usize);
+        file.take(len - self.offset).read_to_end(&mut buf)?;
+        self.offset = len;
+
+        let mut text = std::mem::take(&mut self.partial);
+        text.push_str(&String::from_utf8_lossy(&buf));
+        match text.rfind('\n') {
+            Some(idx) => {
+                self.partial = text.split_off(idx + 1);
+            }
+            None => {
+                self.partial = text;
+                return Ok(vec![]);
+            }
+        }
+        Ok(parse_lines(text.lines()))
+    }
+}
+
+/// writes the entries of the log file which pass `filter` to `dest`. returns how many were written.
+pub fn export(src: &Path, dest: &Path, filter: impl Fn(&Log) -> bool) -> io::Result<usize> {
+    let reader = BufReader::new(File::open(src)?);
+    let mut writer = io::BufWriter::new(File::create(dest)?);
+    let mut count = 0;
+    let mut current: Option<Log> = None;
+    let mut emit = |log: Log| -> io::Result<()> {
+        if filter(&log) {
+            writeln!(writer, "{log}")?;
+            count += 1;
+        }
+        Ok(())
+    };
+    for line in reader.lines() {
+        let line = line?;
+        match parse_line(&line) {
+            Some(log) => {
+                if let Some(previous) = current.replace(log) {
+                    emit(previous)?;
+                }
+            }
+            None => {
+                if let Some(log) = current.as_mut() {
+                    log.message.push('\n');
+                    log.message.push_str(&line);
+                }
+            }
+        }
+    }
+    if let Some(log) = current {
+        emit(log)?;
+    }
+    drop(emit);
+    writer.flush()?;
+    Ok(count)
+}
+
+fn parse_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Log> {
+    let mut logs: Vec<Log> = vec![];
+    for line in lines {
+        match parse_line(line) {
+            Some(log) => logs.push(log),
+            // the start of the entry was read before, or is before the tail
+            None => {
+                if let Some(log) = logs.last_mut() {
+                    log.message.push('\n');
+                    log.message.push_str(line);
+                }
+            }
+        }
+    }
+    logs
+}
+
+pub fn parse_line(line: &str) -> Option<Log> {
+    let mut fields = line.splitn(3, " | ");
+    let datetime = NaiveDateTime::parse_from_str(fields.next()?, DATETIME_FORMAT).ok()?;
+    let datetime = Local.from_local_datetime(&datetime).earliest()?;
+    let level = Level::from_str(fields.next()?).ok()?;
+    let rest = fields.next().unwrap_or_default();
+    let (module, message) = match rest.split_once(" | ") {
+        Some((module, message)) if is_module(module) => (module, message),
+        _ => ("", rest),
+    };
+    Some(Log {
+        level,
+        module: module.to_string(),
+        message: message.to_string(),
+        datetime,
+        colorized: false,
+    })
+}
+
+// errors and warnings start with the source file, which isn't a module
+fn is_module(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':' || c == '-')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_entries_with_and_without_module() {
+        let logs = parse_lines(
+            [
+                "2024-03-01 10:00:00 | INFO | uplink::layouts | opened chat",
+                "2024-03-01 10:00:01 | ERROR | src/lib.rs:10 | failed",
+                "second line",
+                "2024-03-01 10:00:02 | WARN | common::state | src/state.rs:3 | slow",
+            ]
+            .into_iter(),
+        );
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[0].module, "uplink::layouts");
+        assert_eq!(logs[0].message, "opened chat");
+        assert_eq!(logs[1].level, Level::Error);
+        assert_eq!(logs[1].module, "");
+        assert_eq!(logs[1].message, "src/lib.rs:10 | failed\nsecond line");
+        assert_eq!(logs[2].module, "common::state");
+        assert_eq!(logs[2].message, "src/state.rs:3 | slow");
+    }
+}
@@ -26,11 +26,15 @@ use tokio::sync::mpsc;
 use chrono::{DateTime, Local};
 use common::STATIC_ARGS;
 
+pub mod log_file;
+
 static LOGGER: Lazy<RwLock<Logger>> = Lazy::new(|| RwLock::new(Logger::load()));
 
 #[derive(Debug, Clone)]
 pub struct Log {
     pub level: Level,
+    // the target of the record, usually the module path
+    pub module: String,
     pub message: String,
     pub datetime: DateTime<Local>,
     pub colorized: bool,
@@ -40,7 +44,11 @@ impl std::fmt::Display for Log {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let datetime = &self.datetime.to_string()[0..19];
         let level = self.get_level_string();
-        write!(f, "{} | {} | {}", datetime, level, self.message)
+        write!(
+            f,
+            "{} | {} | {} | {}",
+            datetime, level, self.module, self.message
+        )
     }
 }
 
@@ -96,9 +104,13 @@ impl crate::log::Log for LogGlue {
                 .line()
                 .map(|f| f.to_string().bright_blue().to_string())
                 .unwrap_or_default();
-            LOGGER
-                .write()
-                .log(record.level(), &msg.to_string(), file, line);
+            LOGGER.write().log(
+                record.level(),
+                record.target(),
+                &msg.to_string(),
+                file,
+                line,
+            );
         }
     }
 
@@ -137,9 +149,10 @@ fn log_thread(mut file: std::fs::File, rx: std::sync::mpsc::Receiver<Log>) {
 }
 
 impl Logger {
-    fn log(&mut self, level: Level, message: &str, file: String, line: String) {
+    fn log(&mut self, level: Level, module: &str, message: &str, file: String, line: String) {
         let new_log = Log {
             level,
+            module: module.to_string(),
             message: match level {
                 Level::Error | Level::Warn => {
                     format!("{}:{} | {}", file, line, message)
@@ -261,6 +274,10 @@ pub fn get_save_to_file() -> bool {
     LOGGER.read().get_save_to_file()
 }
 
+pub fn log_file() -> PathBuf {
+    LOGGER.read().log_file.clone()
+}
+
 pub fn load_debug_log() -> Vec<Log> {
     //Note: We shouldnt read from the file since it may be too big or contain irrelevant information related to uplink
     //      unless we have a specific file related to uplink/dioxus logging, in which case we should read only the last few lines
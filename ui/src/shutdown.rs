@@ -0,0 +1,228 @@
+//! Closing the main window doesn't exit Uplink right away. Messages which are still being sent and
+//! running file transfers get a chance to finish and the state is saved before the window is closed
+//! for good. A "finishing up" screen is shown while there is something to wait for. Quitting from
+//! the menu of the app takes the same path. Transfers which didn't finish in time are reported on
+//! the next launch, see `TransferTracker::checkpoint`.
+
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use common::{
+    language::{get_local_text, get_local_text_with_args},
+    state::{data_transfer::TransferTracker, ui::ToastNotification, Action, State},
+};
+use dioxus::prelude::*;
+use dioxus_desktop::{
+    tao::event::WindowEvent, use_window, use_wry_event_handler,
+    wry::application::event::Event as WryEvent,
+};
+use futures::StreamExt;
+use kit::elements::{button::Button, label::Label, Appearance};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::log;
+
+use crate::tray;
+
+// Uplink exits after this even if it is still waiting for something
+const MAX_WAIT: Duration = Duration::from_secs(20);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// the id of the quit item of the menu of the app
+pub const QUIT_MENU_ID: &str = "app:quit";
+
+pub enum ShutdownCmd {
+    /// the main window was closed
+    Begin,
+    /// exit without waiting any longer
+    Skip,
+}
+
+/// set while Uplink waits for pending work before exiting
+#[derive(Default)]
+pub struct FinishingUp(pub bool);
+
+/// starts the shutdown when the main window is closed. the window only hides itself then (see
+/// webview_config) and is shown again if there is something to wait for
+pub(crate) fn use_shutdown_coordinator(cx: &ScopeState) -> Option<()> {
+    let state = use_shared_state::<State>(cx)?;
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
+    use_shared_state_provider(cx, FinishingUp::default);
+    let finishing = use_shared_state::<FinishingUp>(cx)?;
+    let desktop = use_window(cx);
+
+    cx.use_hook(|| {
+        let interrupted = file_tracker.write_silent().take_interrupted();
+        if !interrupted.is_empty() {
+            state
+                .write()
+                .mutate(Action::AddToastNotification(ToastNotification::init(
+                    get_local_text("shutdown.interrupted-transfers"),
+                    interrupted.join(", "),
+                    None,
+                    5,
+                )));
+        }
+    });
+
+    let ch = use_coroutine(cx, |mut rx: UnboundedReceiver<ShutdownCmd>| {
+        to_owned![state, file_tracker, finishing, desktop];
+        async move {
+            loop {
+                match rx.next().await {
+                    Some(ShutdownCmd::Begin) => break,
+                    Some(ShutdownCmd::Skip) => continue,
+                    None => return,
+                }
+            }
+            log::info!("shutting down");
+            state
+                .write()
+                .mutate(Action::ClearAllPopoutWindows(desktop.clone()));
+            desktop.set_visible(false);
+            // don't wait for the backoff of queued messages
+            state.write_silent().retry_queued_messages();
+
+            let deadline = Instant::now() + MAX_WAIT;
+            loop {
+                let messages = state.read().unsent_message_count();
+                let transfers = file_tracker.read().active_transfers().count();
+                if messages == 0 && transfers == 0 {
+                    break;
+                }
+                if !finishing.read().0 {
+                    log::debug!("waiting for {messages} messages and {transfers} transfers");
+                    finishing.write().0 = true;
+                    desktop.set_visible(true);
+                    desktop.set_focus();
+                }
+                if Instant::now() >= deadline {
+                    log::warn!(
+                        "exiting with {messages} messages and {transfers} transfers pending"
+                    );
+                    break;
+                }
+                match tokio::time::timeout(POLL_INTERVAL, rx.next()).await {
+                    Ok(Some(ShutdownCmd::Skip)) | Ok(None) => break,
+                    _ => {}
+                }
+            }
+
+            file_tracker.read().checkpoint();
+            if let Err(e) = state.read().save_now() {
+                log::error!("failed to save state before exiting: {e}");
+            }
+            desktop.close();
+        }
+    });
+
+    use_wry_event_handler(cx, {
+        to_owned![ch, desktop];
+        move |event, _| {
+            if let WryEvent::WindowEvent {
+                window_id,
+                event: WindowEvent::CloseRequested,
+                ..
+            } = event
+            {
+                if *window_id == desktop.id() {
+                    ch.send(ShutdownCmd::Begin);
+                }
+            }
+        }
+    });
+
+    use_future(cx, (), |_| {
+        to_owned![ch];
+        on_quit_from_menu(move || ch.send(ShutdownCmd::Begin))
+    });
+
+    Some(())
+}
+
+/// there is nothing to wait for on the auth pages, the window is closed right away
+pub(crate) fn use_close_before_login(cx: &ScopeState, logged_in: impl Fn() -> bool + 'static) {
+    let desktop = use_window(cx);
+    let logged_in = Rc::new(logged_in);
+    use_wry_event_handler(cx, {
+        to_owned![desktop, logged_in];
+        move |event, _| {
+            if let WryEvent::WindowEvent {
+                window_id,
+                event: WindowEvent::CloseRequested,
+                ..
+            } = event
+            {
+                if *window_id == desktop.id() && !logged_in() {
+                    desktop.close();
+                }
+            }
+        }
+    });
+    use_future(cx, (), |_| {
+        to_owned![desktop];
+        on_quit_from_menu(move || {
+            if !logged_in() {
+                desktop.close();
+            }
+        })
+    });
+}
+
+// calls `f` whenever quit is picked from the menu of the app
+async fn on_quit_from_menu(f: impl Fn()) {
+    let mut events = tray::menu_events();
+    loop {
+        match events.recv().await {
+            Ok(event) if event.id.0 == QUIT_MENU_ID => f(),
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+/// covers the app while Uplink waits before exiting
+#[allow(non_snake_case)]
+pub fn FinishingUpScreen(cx: Scope) -> Element {
+    let finishing = use_shared_state::<FinishingUp>(cx)?;
+    let state = use_shared_state::<State>(cx)?;
+    let file_tracker = use_shared_state::<TransferTracker>(cx)?;
+    let ch = use_coroutine_handle::<ShutdownCmd>(cx)?;
+
+    if !finishing.read().0 {
+        return None;
+    }
+    let messages = state.read().unsent_message_count();
+    let transfers = file_tracker.read().active_transfers().count();
+
+    cx.render(rsx!(
+        div {
+            id: "finishing-up",
+            aria_label: "finishing-up",
+            div {
+                class: "finishing-up-content",
+                Label {
+                    text: get_local_text("shutdown.finishing-up"),
+                    aria_label: "finishing-up-label".into(),
+                },
+                (messages > 0).then(|| rsx!(
+                    span {
+                        get_local_text_with_args("shutdown.unsent-messages", vec![("num", messages)])
+                    }
+                )),
+                (transfers > 0).then(|| rsx!(
+                    span {
+                        get_local_text_with_args("shutdown.active-transfers", vec![("num", transfers)])
+                    }
+                )),
+                Button {
+                    text: get_local_text("shutdown.quit-now"),
+                    aria_label: "quit-now-button".into(),
+                    appearance: Appearance::Secondary,
+                    onpress: move |_| ch.send(ShutdownCmd::Skip),
+                },
+            }
+        }
+    ))
+}
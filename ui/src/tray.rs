@@ -0,0 +1,255 @@
+//! The tray icon. Its menu has what is handy without opening the window: the online status, Do Not
+//! Disturb, the most recent conversations and locking Uplink. Menu events arrive on a thread of
+//! their own and are passed on to a future, which applies them to the state like any UI event.
+//! The menu of the app on macOS shares the channel of menu events with the tray, so both get them
+//! from `menu_events`.
+
+use common::{
+    language::get_local_text,
+    notifications::{NotificationAction, FOCUS_SCHEDULER, NOTIFICATION_LISTENER},
+    state::{Action, State},
+};
+use dioxus::prelude::*;
+use dioxus_desktop::use_window;
+use kit::components::indicator::Status;
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast;
+use tracing::log;
+use tray_icon::{
+    menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+    Icon, TrayIcon, TrayIconBuilder,
+};
+use uuid::Uuid;
+use warp::multipass::identity::IdentityStatus;
+
+use crate::utils::presence;
+
+// the ids of the tray menu items start with this
+const ID_PREFIX: &str = "tray:";
+const ICON_SIZE: u32 = 32;
+const RECENT_CONVERSATIONS: usize = 5;
+const STATUSES: [IdentityStatus; 4] = [
+    IdentityStatus::Online,
+    IdentityStatus::Away,
+    IdentityStatus::Busy,
+    IdentityStatus::Offline,
+];
+
+// the events of every menu, the thread passing them on is started when they're first needed
+static MENU_EVENTS: Lazy<broadcast::Sender<MenuEvent>> = Lazy::new(|| {
+    let (tx, _) = broadcast::channel(16);
+    let events = tx.clone();
+    std::thread::spawn(move || {
+        while let Ok(event) = MenuEvent::receiver().recv() {
+            let _ = events.send(event);
+        }
+    });
+    tx
+});
+
+/// The events of the tray and of the menu of the app
+pub fn menu_events() -> broadcast::Receiver<MenuEvent> {
+    MENU_EVENTS.subscribe()
+}
+
+enum TrayCmd {
+    Show,
+    Status(IdentityStatus),
+    ToggleDoNotDisturb,
+    OpenChat(Uuid),
+    Lock,
+}
+
+impl TrayCmd {
+    // the menu items are told apart by their ids
+    fn id(&self) -> String {
+        let id = match self {
+            Self::Show => "show".into(),
+            Self::Status(status) => format!(
+                "status:{}",
+                serde_json::to_string(status).unwrap_or_default()
+            ),
+            Self::ToggleDoNotDisturb => "dnd".into(),
+            Self::OpenChat(id) => format!("chat:{id}"),
+            Self::Lock => "lock".into(),
+        };
+        format!("{ID_PREFIX}{id}")
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        let id = id.strip_prefix(ID_PREFIX)?;
+        match id.split_once(':') {
+            Some(("status", status)) => serde_json::from_str(status).ok().map(Self::Status),
+            Some(("chat", chat)) => chat.parse().ok().map(Self::OpenChat),
+            _ => match id {
+                "show" => Some(Self::Show),
+                "dnd" => Some(Self::ToggleDoNotDisturb),
+                "lock" => Some(Self::Lock),
+                _ => None,
+            },
+        }
+    }
+}
+
+// what the menu shows. it is only rebuilt when this changes
+#[derive(PartialEq)]
+struct TrayMenu {
+    status: IdentityStatus,
+    do_not_disturb: bool,
+    // (conversation id, name)
+    recent: Vec<(Uuid, String)>,
+    locked: bool,
+}
+
+impl TrayMenu {
+    fn new(state: &State) -> Self {
+        let recent = state
+            .chats_sidebar()
+            .into_iter()
+            .take(RECENT_CONVERSATIONS)
+            .map(|chat| {
+                let name = state.chats().title(&chat).unwrap_or_else(|| {
+                    State::join_usernames(&state.remove_self(&state.chat_participants(&chat)))
+                });
+                (chat.id, name)
+            })
+            .collect();
+        Self {
+            status: state.get_own_identity().identity_status(),
+            do_not_disturb: state.settings.do_not_disturb.enabled,
+            recent,
+            locked: state.ui.locked,
+        }
+    }
+
+    fn build(&self) -> Menu {
+        let status = Submenu::new(get_local_text("tray.status"), true);
+        for option in STATUSES {
+            let label = get_local_text(&format!("settings-profile.status-{}", Status::own(option)));
+            let _ = status.append(&CheckMenuItem::with_id(
+                TrayCmd::Status(option).id(),
+                label,
+                option != self.status,
+                option == self.status,
+                None,
+            ));
+        }
+
+        // the names of the conversations stay hidden while locked
+        let recent = Submenu::new(
+            get_local_text("tray.recent"),
+            !self.locked && !self.recent.is_empty(),
+        );
+        for (id, name) in self.recent.iter() {
+            let _ = recent.append(&MenuItem::with_id(
+                TrayCmd::OpenChat(*id).id(),
+                name,
+                true,
+                None,
+            ));
+        }
+
+        let menu = Menu::new();
+        let _ = menu.append_items(&[
+            &MenuItem::with_id(TrayCmd::Show.id(), get_local_text("tray.open"), true, None),
+            &PredefinedMenuItem::separator(),
+            &status,
+            &CheckMenuItem::with_id(
+                TrayCmd::ToggleDoNotDisturb.id(),
+                get_local_text("settings-notifications.do-not-disturb"),
+                true,
+                self.do_not_disturb,
+                None,
+            ),
+            &recent,
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(
+                TrayCmd::Lock.id(),
+                get_local_text("tray.lock"),
+                !self.locked,
+                None,
+            ),
+        ]);
+        menu
+    }
+}
+
+fn icon() -> Option<Icon> {
+    let image = image::load_from_memory(include_bytes!("../extra/images/logo.png"))
+        .map_err(|e| log::error!("failed to load the tray icon: {e}"))
+        .ok()?
+        .resize(ICON_SIZE, ICON_SIZE, image::imageops::FilterType::Lanczos3)
+        .into_rgba8();
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height)
+        .map_err(|e| log::error!("failed to create the tray icon: {e}"))
+        .ok()
+}
+
+/// Shows the tray icon and applies what is picked from its menu
+pub(crate) fn use_tray(cx: &ScopeState) -> Option<()> {
+    let state = use_shared_state::<State>(cx)?;
+    let desktop = use_window(cx);
+    let tray: &Option<TrayIcon> = cx.use_hook(|| {
+        TrayIconBuilder::new()
+            .with_tooltip(get_local_text("uplink"))
+            .with_icon(icon()?)
+            .build()
+            .map_err(|e| log::error!("failed to create the tray icon: {e}"))
+            .ok()
+    });
+    let shown: &UseRef<Option<TrayMenu>> = use_ref(cx, || None);
+
+    let menu = TrayMenu::new(&state.read());
+    if shown.read().as_ref() != Some(&menu) {
+        if let Some(tray) = tray {
+            tray.set_menu(Some(Box::new(menu.build())));
+        }
+        *shown.write_silent() = Some(menu);
+    }
+
+    use_future(cx, (), |_| {
+        to_owned![state, desktop];
+        async move {
+            let mut events = menu_events();
+            loop {
+                let cmd = match events.recv().await {
+                    Ok(event) => match TrayCmd::from_id(&event.id.0) {
+                        Some(cmd) => cmd,
+                        None => continue,
+                    },
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                match cmd {
+                    TrayCmd::Show => {
+                        desktop.set_visible(true);
+                        desktop.set_minimized(false);
+                        desktop.set_focus();
+                    }
+                    TrayCmd::Status(status) => presence::set_status(&state, status).await,
+                    TrayCmd::ToggleDoNotDisturb => {
+                        let mut dnd = state.read().settings.do_not_disturb.clone();
+                        dnd.enabled = !dnd.enabled;
+                        state.write().mutate(Action::SetDoNotDisturb(dnd));
+                    }
+                    // opened the same way as the chat of a notification
+                    TrayCmd::OpenChat(id) => {
+                        desktop.set_visible(true);
+                        desktop.set_minimized(false);
+                        if let Err(e) = NOTIFICATION_LISTENER
+                            .tx
+                            .send(NotificationAction::DisplayChat(id))
+                        {
+                            log::error!("failed to send notification action {}", e);
+                        }
+                        let _ = FOCUS_SCHEDULER.tx.send(());
+                    }
+                    TrayCmd::Lock => state.write().mutate(Action::SetLocked(true)),
+                }
+            }
+        }
+    });
+
+    Some(())
+}
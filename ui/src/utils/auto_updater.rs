@@ -1,29 +1,44 @@
 use std::path::Path;
 use std::path::PathBuf;
+use std::process::Command;
 
 use anyhow::bail;
 
+use base64::{engine::general_purpose, Engine};
 use common::language::get_local_text;
+use common::state::data_transfer::{TransferState, TransferStates};
+use common::state::pending_message::FileProgression;
+use ed25519_dalek::{Signature, VerifyingKey};
 use futures::TryStreamExt;
 use reqwest::header;
 use reqwest::Client;
 
 use rfd::FileDialog;
+use semver::Version;
 use serde::Deserialize;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc;
 use tracing::log;
 
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Satellite-im/Uplink/releases/latest";
+// base64 of the ed25519 key which signs the installers of a release, embedded by the build script
+// of the release workflows. each installer has a `<name>.sig` asset next to it, made with
+// `sign_update.sh`. builds without the key can't install updates and show the manual instructions
+// instead
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("UPLINK_UPDATE_PUBLIC_KEY");
+
 // these types exist to allow different parts of the app to share the same logic for managing software updates
-pub struct SoftwareUpdateCmd(pub mpsc::UnboundedReceiver<f32>);
-pub struct SoftwareDownloadCmd(pub PathBuf);
+/// downloads the installer of the release which was offered to the user into the given directory
+pub struct SoftwareDownloadCmd(pub PathBuf, pub GitHubRelease);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum DownloadProgress {
     Idle,
     PickFolder,
-    _Pending,
+    Pending,
     Finished,
+    // the user is asked to confirm that Uplink closes for the installer
+    ConfirmInstall,
 }
 
 impl Default for DownloadProgress {
@@ -34,6 +49,9 @@ impl Default for DownloadProgress {
 #[derive(Debug, Default)]
 pub struct DownloadState {
     pub stage: DownloadProgress,
+    // the release found by the last check, which is the one downloaded
+    pub release: Option<GitHubRelease>,
+    // the verified installer once it was downloaded
     pub destination: Option<PathBuf>,
     pub progress: f32,
 }
@@ -67,20 +85,10 @@ pub fn _get_download_dest() -> Option<PathBuf> {
 }
 
 pub async fn check_for_release() -> anyhow::Result<Option<GitHubRelease>> {
-    let latest_release =
-        get_github_release("https://api.github.com/repos/Satellite-im/Uplink/releases/latest")
-            .await?;
+    let latest_release = get_github_release(LATEST_RELEASE_URL).await?;
 
-    // ensure installer is released - .deb, .msi, or .dpkg
-    let extension = if cfg!(target_os = "windows") {
-        ".msi"
-    } else if cfg!(target_os = "linux") {
-        ".deb"
-    } else if cfg!(target_os = "macos") {
-        ".dmg"
-    } else {
-        bail!("unknown OS");
-    };
+    // ensure installer is released - .deb, .msi, or .dmg
+    let extension = installer_extension()?;
 
     if !latest_release
         .assets
@@ -90,67 +98,152 @@ pub async fn check_for_release() -> anyhow::Result<Option<GitHubRelease>> {
         bail!("{extension} file not found in software release");
     }
 
-    if versions_match(&latest_release.tag_name) {
-        Ok(None)
-    } else {
+    if is_newer(&latest_release.tag_name, env!("CARGO_PKG_VERSION")) {
         Ok(Some(latest_release))
+    } else {
+        Ok(None)
     }
 }
 
-pub async fn download_update(
-    binary_dest: PathBuf,
-    ch: mpsc::UnboundedSender<f32>,
-) -> anyhow::Result<String> {
-    let latest_release =
-        get_github_release("https://api.github.com/repos/Satellite-im/Uplink/releases/latest")
-            .await?;
-    let find_asset = |name: &str| {
-        latest_release
-            .assets
-            .iter()
-            .find(|x| x.name.contains(name))
-            .cloned()
-            .ok_or(anyhow::format_err!("failed to find {name}"))
-    };
+/// whether updates can be downloaded and installed from within Uplink
+pub fn can_install_updates() -> bool {
+    UPDATE_PUBLIC_KEY.is_some()
+}
 
-    let binary_asset = if cfg!(target_os = "windows") {
-        find_asset(".msi")?
-    } else if cfg!(target_os = "linux") {
-        find_asset(".deb")?
-    } else if cfg!(target_os = "macos") {
-        find_asset("Uplink-Mac-Universal.zip")?
-    } else {
-        bail!("unknown OS type. failed to find binary");
-    };
+/// downloads the installer of `release` and verifies that it was signed for that version. the
+/// progress is reported like the progress of other file transfers. returns the path of the
+/// installer
+pub async fn download_installer(
+    dest_dir: PathBuf,
+    release: GitHubRelease,
+    transfer_state: TransferState,
+    ch: mpsc::UnboundedSender<FileProgression>,
+) -> anyhow::Result<PathBuf> {
+    let extension = installer_extension()?;
+    let find_asset =
+        |matches: &dyn Fn(&str) -> bool| release.assets.iter().find(|x| matches(&x.name)).cloned();
+    let installer = find_asset(&|name| name.ends_with(extension))
+        .ok_or(anyhow::format_err!("failed to find {extension}"))?;
+    let signature_name = format!("{}.sig", installer.name);
+    let signature_asset = find_asset(&|name| name == signature_name)
+        .ok_or(anyhow::format_err!("failed to find {signature_name}"))?;
 
-    let total_download_size = binary_asset.size as f32;
     let client = get_client()?;
+    let signature = client
+        .get(&signature_asset.browser_download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    tokio::fs::create_dir_all(&dest_dir).await?;
+    let dest = dest_dir.join(&installer.name);
     let (tx, mut rx) = mpsc::unbounded_channel::<anyhow::Result<usize>>();
-    tokio::spawn(async move {
-        if let Err(e) = download_file(
-            &client,
-            binary_dest.join(&binary_asset.name),
-            &binary_asset.browser_download_url,
-            tx.clone(),
-        )
-        .await
-        {
-            let _ = tx.send(Err(e));
-        }
-    });
+    let download = {
+        let dest = dest.clone();
+        let url = installer.browser_download_url.clone();
+        tokio::spawn(async move {
+            if let Err(e) = download_file(&client, dest, &url, tx.clone()).await {
+                let _ = tx.send(Err(e));
+            }
+        })
+    };
 
-    let mut total_bytes_downloaded = 0.0_f32;
+    let mut downloaded = 0;
+    let mut last_percent = 0;
     while let Some(x) = rx.recv().await {
-        match x {
-            Ok(b) => {
-                total_bytes_downloaded += b as f32;
-                let _ = ch.send(100_f32 * total_bytes_downloaded / total_download_size);
-            }
-            Err(e) => return Err(e),
+        if transfer_state.matches(TransferStates::Cancel).await {
+            download.abort();
+            let _ = tokio::fs::remove_file(&dest).await;
+            bail!("update download canceled by user");
         }
+        downloaded += x?;
+        // one update per percent is enough for the progress bar
+        let percent = downloaded * 100 / installer.size.max(1);
+        if percent > last_percent {
+            last_percent = percent;
+            let _ = ch.send(FileProgression::CurrentProgress {
+                name: installer.name.clone(),
+                current: downloaded,
+                total: Some(installer.size),
+            });
+        }
+    }
+
+    let verified = {
+        let dest = dest.clone();
+        let version = release.tag_name.clone();
+        tokio::task::spawn_blocking(move || verify_installer(&dest, &version, &signature)).await?
+    };
+    if let Err(e) = verified {
+        let _ = tokio::fs::remove_file(&dest).await;
+        bail!("the signature of {} is invalid: {e}", installer.name);
+    }
+    Ok(dest)
+}
+
+/// starts the installer. Uplink has to exit afterwards so it can be replaced
+pub fn launch_installer(installer: &Path) -> anyhow::Result<()> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("msiexec");
+        cmd.arg("/i");
+        cmd
+    } else if cfg!(target_os = "linux") {
+        // opens the software center of the distribution
+        Command::new("xdg-open")
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        bail!("unknown OS type. failed to launch installer");
+    };
+    cmd.arg(installer).spawn()?;
+    Ok(())
+}
+
+fn installer_extension() -> anyhow::Result<&'static str> {
+    if cfg!(target_os = "windows") {
+        Ok(".msi")
+    } else if cfg!(target_os = "linux") {
+        Ok(".deb")
+    } else if cfg!(target_os = "macos") {
+        Ok(".dmg")
+    } else {
+        bail!("unknown OS");
     }
+}
 
-    Ok(latest_release.tag_name)
+fn verify_installer(installer: &Path, version: &str, signature: &str) -> anyhow::Result<()> {
+    let Some(key) = UPDATE_PUBLIC_KEY else {
+        bail!("this build has no key to verify updates with");
+    };
+    let key: [u8; 32] = general_purpose::STANDARD
+        .decode(key)?
+        .try_into()
+        .map_err(|_| anyhow::format_err!("invalid update key"))?;
+    let key = VerifyingKey::from_bytes(&key)?;
+    let name = installer
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let data = std::fs::read(installer)?;
+    verify_signature(&key, &signed_message(version, &name, data), signature)
+}
+
+// what `sign_update.sh` signs. with the version and the name in it, the installer of an older
+// release or of another platform isn't accepted in place of the one which was offered
+fn signed_message(version: &str, name: &str, installer: Vec<u8>) -> Vec<u8> {
+    let mut message = format!("uplink-update\n{version}\n{name}\n").into_bytes();
+    message.extend(installer);
+    message
+}
+
+// the signature is base64 encoded
+fn verify_signature(key: &VerifyingKey, data: &[u8], signature: &str) -> anyhow::Result<()> {
+    let signature = general_purpose::STANDARD.decode(signature.trim())?;
+    let signature = Signature::from_slice(&signature)?;
+    key.verify_strict(data, &signature)?;
+    Ok(())
 }
 
 fn get_client() -> Result<Client, reqwest::Error> {
@@ -175,7 +268,12 @@ async fn download_file<P: AsRef<Path>>(
     url: &str,
     ch: mpsc::UnboundedSender<anyhow::Result<usize>>,
 ) -> anyhow::Result<()> {
-    let mut bytes = client.get(url).send().await?.bytes_stream();
+    let mut bytes = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes_stream();
     let mut file = tokio::fs::File::create(dest).await?;
 
     while let Some(bytes) = bytes.try_next().await? {
@@ -186,9 +284,16 @@ async fn download_file<P: AsRef<Path>>(
     Ok(())
 }
 
-fn versions_match(release_version: &str) -> bool {
-    format!("v{}", env!("CARGO_PKG_VERSION")) == release_version
-        || env!("CARGO_PKG_VERSION") == release_version
+// only newer releases are offered, so a pulled or older release isn't installed over the running one
+fn is_newer(release_version: &str, current_version: &str) -> bool {
+    let parse = |version: &str| Version::parse(version.trim().trim_start_matches('v'));
+    match (parse(release_version), parse(current_version)) {
+        (Ok(release), Ok(current)) => release > current,
+        (Err(e), _) | (_, Err(e)) => {
+            log::warn!("failed to compare {release_version} with {current_version}: {e}");
+            false
+        }
+    }
 }
 
 #[cfg(test)]
@@ -208,6 +313,34 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn only_newer_releases_are_offered() {
+        assert!(is_newer("v1.1.0", "1.0.5"));
+        assert!(is_newer("1.0.10", "1.0.9"));
+        assert!(is_newer("v1.1.0", "1.1.0-rc.1"));
+        assert!(!is_newer("v1.0.5", "1.0.5"));
+        assert!(!is_newer("v1.0.4", "1.0.5"));
+        assert!(!is_newer("v1.1.0-rc.1", "1.1.0"));
+        assert!(!is_newer("nightly", "1.0.5"));
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let installer = signed_message("v1.1.0", "uplink.deb", b"installer contents".to_vec());
+        let signature = general_purpose::STANDARD.encode(signing_key.sign(&installer).to_bytes());
+        let key = signing_key.verifying_key();
+
+        assert!(verify_signature(&key, &installer, &signature).is_ok());
+        let tampered = signed_message("v1.1.0", "uplink.deb", b"tampered contents".to_vec());
+        assert!(verify_signature(&key, &tampered, &signature).is_err());
+        let older = signed_message("v1.0.0", "uplink.deb", b"installer contents".to_vec());
+        assert!(verify_signature(&key, &older, &signature).is_err());
+        assert!(verify_signature(&key, &installer, "not a signature").is_err());
+    }
+
     // #[tokio::test]
     // async fn test_download_asset() -> Result<(), Box<dyn Error>> {
     //     let dest = "/tmp/test_download";
@@ -0,0 +1,25 @@
+//! Serves the control socket while it is enabled in the settings, so scripts can use Uplink while
+//! it runs with its window. See `common::control`.
+
+use common::{control, state::State, STATIC_ARGS};
+use dioxus::prelude::*;
+use tracing::log;
+
+pub fn use_control_socket(cx: &ScopeState) -> Option<()> {
+    let state = use_shared_state::<State>(cx)?;
+    let enabled = state.read().configuration.automation.enabled;
+    // restarted when it is turned on or off. dropping the future closes the socket
+    use_future(cx, &enabled, |enabled| async move {
+        let path = STATIC_ARGS.control_socket_path();
+        if !enabled {
+            if cfg!(unix) && path.exists() {
+                let _ = std::fs::remove_file(&path);
+            }
+            return;
+        }
+        if let Err(e) = control::serve(&path, true).await {
+            log::error!("the control socket failed: {e}");
+        }
+    });
+    Some(())
+}
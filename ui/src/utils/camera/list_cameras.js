@@ -0,0 +1,13 @@
+navigator.mediaDevices
+  .enumerateDevices()
+  .then((devices) =>
+    devices
+      .filter((device) => device.kind === "videoinput")
+      // the labels are only known once the camera was allowed
+      .map((device, i) => ({
+        id: device.deviceId,
+        label: device.label || `Camera ${i + 1}`,
+      }))
+  )
+  .then((cameras) => dioxus.send({ cameras }))
+  .catch((e) => dioxus.send({ error: e.name || String(e) }));
@@ -0,0 +1,59 @@
+//! The camera, captured by the webview. The video is shown on this device, it isn't sent to the
+//! other participants of a call by Uplink.
+
+use tracing::log;
+
+use super::EvalProvider;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Camera {
+    pub id: String,
+    pub label: String,
+}
+
+/// The cameras of this device. Their labels are generic until the camera was allowed once
+pub async fn list_cameras(eval: &EvalProvider) -> anyhow::Result<Vec<Camera>> {
+    let eval = eval(include_str!("./list_cameras.js")).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    let value = eval.recv().await.map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    if let Some(e) = value["error"].as_str() {
+        anyhow::bail!("{e}");
+    }
+    Ok(value["cameras"]
+        .as_array()
+        .map(|cameras| {
+            cameras
+                .iter()
+                .map(|camera| Camera {
+                    id: camera["id"].as_str().unwrap_or_default().to_string(),
+                    label: camera["label"].as_str().unwrap_or_default().to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Shows the camera with the id `device` in the video element `element_id`, the default camera if
+/// `device` is None. Resolves once it is showing
+pub async fn show_camera(
+    eval: &EvalProvider,
+    element_id: &str,
+    device: Option<&str>,
+) -> anyhow::Result<()> {
+    let device = serde_json::to_string(&device)?;
+    let script = include_str!("./show_camera.js")
+        .replace("$ID", element_id)
+        .replace("$DEVICE", &device);
+    let eval = eval(&script).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    let value = eval.recv().await.map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    match value["error"].as_str() {
+        Some(e) => anyhow::bail!("{e}"),
+        None => Ok(()),
+    }
+}
+
+/// Releases the camera shown in `element_id`
+pub fn stop_camera(eval: &EvalProvider, element_id: &str) {
+    if let Err(e) = eval(&include_str!("./stop_camera.js").replace("$ID", element_id)) {
+        log::error!("failed to stop the camera: {e:?}");
+    }
+}
@@ -0,0 +1,27 @@
+(async () => {
+  window.cameraStreams = window.cameraStreams || {};
+  const previous = window.cameraStreams["$ID"];
+  if (previous) {
+    previous.getTracks().forEach((track) => track.stop());
+    delete window.cameraStreams["$ID"];
+  }
+  const device = $DEVICE;
+  try {
+    const stream = await navigator.mediaDevices.getUserMedia({
+      video: device ? { deviceId: { exact: device } } : true,
+      audio: false,
+    });
+    const video = document.getElementById("$ID");
+    // the preview went away while the camera was starting
+    if (!video) {
+      stream.getTracks().forEach((track) => track.stop());
+      dioxus.send({ error: "closed" });
+      return;
+    }
+    window.cameraStreams["$ID"] = stream;
+    video.srcObject = stream;
+    dioxus.send({ started: true });
+  } catch (e) {
+    dioxus.send({ error: e.name || String(e) });
+  }
+})();
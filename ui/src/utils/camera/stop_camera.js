@@ -0,0 +1,4 @@
+if (window.cameraStreams && window.cameraStreams["$ID"]) {
+  window.cameraStreams["$ID"].getTracks().forEach((track) => track.stop());
+  delete window.cameraStreams["$ID"];
+}
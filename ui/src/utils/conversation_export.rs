@@ -0,0 +1,436 @@
+//! Formats the messages of a conversation for exporting. The export is written in chunks so that large
+//! conversations don't have to be loaded into memory at once: `header`, then `message` for every message
+//! (oldest first), then `footer`. `use_export_coroutine` runs the exports, in `app_layout` so they go on
+//! while the user moves around the app.
+
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Local, Utc};
+use common::{
+    language::{get_local_text, get_local_text_with_args},
+    state::{
+        data_transfer::{TrackerType, TransferState, TransferStates, TransferTracker},
+        pending_message::FileProgression,
+        Action, State, ToastNotification,
+    },
+    warp_runner::{RayGunCmd, WarpCmd},
+    WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use futures::{channel::oneshot, StreamExt};
+use serde_json::json;
+use tokio::{fs::File, io::AsyncWriteExt};
+use tracing::log;
+use uuid::Uuid;
+use warp::crypto::DID;
+
+use crate::utils::download::download_attachment;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    PlainText,
+    Html,
+}
+
+pub const EXPORT_FORMATS: [ExportFormat; 3] = [
+    ExportFormat::Json,
+    ExportFormat::PlainText,
+    ExportFormat::Html,
+];
+
+pub struct ExportedMessage {
+    pub id: String,
+    pub sender: String,
+    pub sender_name: String,
+    pub date: DateTime<Utc>,
+    pub edited: bool,
+    pub lines: Vec<String>,
+    // file names, or paths relative to the export if the attachments were saved
+    pub attachments: Vec<String>,
+}
+
+impl ExportFormat {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Json => get_local_text("messages.export-json"),
+            Self::PlainText => get_local_text("messages.export-plaintext"),
+            Self::Html => get_local_text("messages.export-html"),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::PlainText => "txt",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn header(&self, title: &str) -> String {
+        match self {
+            Self::Json => format!(
+                "{{\"conversation\":{},\"exported\":\"{}\",\"messages\":[",
+                json!(title),
+                Utc::now().to_rfc3339()
+            ),
+            Self::PlainText => format!("{title}\n{}\n\n", "=".repeat(title.chars().count())),
+            Self::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+                title = escape_html(title)
+            ),
+        }
+    }
+
+    /// `first` is needed to separate the entries of the JSON array
+    pub fn message(&self, message: &ExportedMessage, first: bool) -> String {
+        let local_date = message.date.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+        match self {
+            Self::Json => {
+                let entry = json!({
+                    "id": message.id,
+                    "sender": message.sender,
+                    "sender_name": message.sender_name,
+                    "date": message.date.to_rfc3339(),
+                    "edited": message.edited,
+                    "lines": message.lines,
+                    "attachments": message.attachments,
+                });
+                if first {
+                    entry.to_string()
+                } else {
+                    format!(",{entry}")
+                }
+            }
+            Self::PlainText => {
+                let mut text = format!("[{local_date}] {}:", message.sender_name);
+                for line in &message.lines {
+                    text.push_str("\n    ");
+                    text.push_str(line);
+                }
+                for attachment in &message.attachments {
+                    text.push_str(&format!("\n    [{attachment}]"));
+                }
+                text.push_str("\n\n");
+                text
+            }
+            Self::Html => {
+                let mut html = format!(
+                    "<div class=\"message\">\n<div class=\"sender\">{}<span class=\"date\">{local_date}</span></div>\n",
+                    escape_html(&message.sender_name)
+                );
+                for line in &message.lines {
+                    html.push_str(&format!("<p>{}</p>\n", escape_html(line)));
+                }
+                for attachment in &message.attachments {
+                    let attachment = escape_html(attachment);
+                    html.push_str(&format!(
+                        "<a class=\"attachment\" href=\"{attachment}\">{attachment}</a>\n"
+                    ));
+                }
+                html.push_str("</div>\n");
+                html
+            }
+        }
+    }
+
+    pub fn footer(&self) -> String {
+        match self {
+            Self::Json => "]}".into(),
+            Self::PlainText => String::new(),
+            Self::Html => "</body>\n</html>\n".into(),
+        }
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; max-width: 800px; margin: auto; } \
+    .message { padding: 8px 0; border-bottom: 1px solid #ddd; } \
+    .sender { font-weight: bold; } \
+    .date { font-weight: normal; color: #888; margin-left: 8px; } \
+    p { margin: 4px 0; white-space: pre-wrap; } \
+    .attachment { display: block; }";
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// number of messages fetched from RayGun at a time
+const EXPORT_PAGE_SIZE: usize = 100;
+
+pub struct ExportRequest {
+    pub conv_id: Uuid,
+    pub title: String,
+    pub format: ExportFormat,
+    pub include_attachments: bool,
+    pub path: PathBuf,
+}
+
+enum ExportError {
+    Cancelled,
+    Failed(String),
+}
+
+impl<E: std::fmt::Display> From<E> for ExportError {
+    fn from(e: E) -> Self {
+        ExportError::Failed(e.to_string())
+    }
+}
+
+/// Exports conversations in the background. The progress is shown in the `TransferTracker`.
+pub fn use_export_coroutine(cx: &ScopeState) -> Coroutine<ExportRequest> {
+    let state = use_shared_state::<State>(cx).unwrap();
+    let file_tracker = use_shared_state::<TransferTracker>(cx).unwrap();
+    use_coroutine(cx, |mut rx: UnboundedReceiver<ExportRequest>| {
+        to_owned![state, file_tracker];
+        async move {
+            while let Some(req) = rx.next().await {
+                let id = Uuid::new_v4();
+                let file_state = TransferState::new();
+                let file_name = req
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                file_tracker.write().start_file_transfer(
+                    id,
+                    file_name.clone(),
+                    file_state.clone(),
+                    TrackerType::FileDownload,
+                );
+                let names: HashMap<DID, String> = {
+                    let state = state.read();
+                    let participants = state
+                        .get_chat_by_id(req.conv_id)
+                        .map(|chat| chat.participants.iter().cloned().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    state
+                        .get_identities(&participants)
+                        .iter()
+                        .map(|identity| (identity.did_key(), identity.username()))
+                        .collect()
+                };
+
+                let res = export_conversation(&req, &names, &file_state, id, &file_tracker).await;
+                file_tracker
+                    .write()
+                    .remove_file_upload(id, TrackerType::FileDownload);
+                let text = match res {
+                    Ok(num) => get_local_text_with_args(
+                        "messages.export-finished",
+                        vec![("num", num.to_string()), ("file", file_name)],
+                    ),
+                    Err(ExportError::Cancelled) => {
+                        let _ = tokio::fs::remove_file(&req.path).await;
+                        continue;
+                    }
+                    Err(ExportError::Failed(e)) => {
+                        log::error!("failed to export conversation: {e}");
+                        let _ = tokio::fs::remove_file(&req.path).await;
+                        get_local_text("messages.export-failed")
+                    }
+                };
+                state
+                    .write()
+                    .mutate(Action::AddToastNotification(ToastNotification::init(
+                        "".into(),
+                        text,
+                        None,
+                        3,
+                    )));
+            }
+        }
+    })
+    .clone()
+}
+
+// walks through the conversation one page at a time, writing each page before fetching the next one.
+// returns the number of exported messages
+async fn export_conversation(
+    req: &ExportRequest,
+    names: &HashMap<DID, String>,
+    file_state: &TransferState,
+    tracker_id: Uuid,
+    file_tracker: &UseSharedState<TransferTracker>,
+) -> Result<usize, ExportError> {
+    let warp_cmd_tx = WARP_CMD_CH.tx.clone();
+    let mut file = File::create(&req.path).await?;
+    file.write_all(req.format.header(&req.title).as_bytes())
+        .await?;
+
+    // attachments are saved next to the export, in "<name>_attachments"
+    let attachments_dir_name = format!(
+        "{}_attachments",
+        req.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    );
+    let attachments_dir = req.path.with_file_name(&attachments_dir_name);
+
+    // the pages are of all the messages, including the hidden ones which aren't exported
+    let mut offset = 0;
+    let mut exported = 0;
+    let mut total = usize::MAX;
+    while offset < total {
+        loop {
+            if file_state.matches(TransferStates::Cancel).await {
+                return Err(ExportError::Cancelled);
+            }
+            if !file_state.matches(TransferStates::Pause).await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        warp_cmd_tx.send(WarpCmd::RayGun(RayGunCmd::FetchMessagePage {
+            conv_id: req.conv_id,
+            range: offset..offset + EXPORT_PAGE_SIZE,
+            rsp: tx,
+        }))?;
+        let (messages, num_messages) = rx.await.expect("command canceled")?;
+        total = num_messages;
+        offset += EXPORT_PAGE_SIZE;
+
+        for message in messages {
+            let mut attachments = vec![];
+            for attachment in message.attachments() {
+                if !req.include_attachments {
+                    attachments.push(attachment.name());
+                    continue;
+                }
+                tokio::fs::create_dir_all(&attachments_dir).await?;
+                // prefix with the message id because file names are only unique within a message
+                let name = format!("{}_{}", message.id(), attachment.name());
+                match download_attachment(&message, attachment.name(), attachments_dir.join(&name))
+                    .await
+                {
+                    Ok(_) => attachments.push(format!("{attachments_dir_name}/{name}")),
+                    Err(e) => {
+                        log::error!("failed to export attachment {}: {e}", attachment.name());
+                        attachments.push(attachment.name());
+                    }
+                }
+            }
+
+            let sender = message.sender();
+            let entry = ExportedMessage {
+                id: message.id().to_string(),
+                sender_name: names
+                    .get(&sender)
+                    .cloned()
+                    .unwrap_or_else(|| sender.to_string()),
+                sender: sender.to_string(),
+                date: message.date(),
+                edited: message.modified().is_some(),
+                lines: message.lines(),
+                attachments,
+            };
+            file.write_all(req.format.message(&entry, exported == 0).as_bytes())
+                .await?;
+            exported += 1;
+        }
+
+        file_tracker.write().update_file_upload(
+            tracker_id,
+            FileProgression::CurrentProgress {
+                name: req.title.clone(),
+                current: offset.min(total),
+                total: Some(total),
+            },
+            TrackerType::FileDownload,
+        );
+        file_tracker.write().update_file_description(
+            tracker_id,
+            get_local_text_with_args(
+                "messages.export-progress",
+                vec![
+                    ("num", offset.min(total).to_string()),
+                    ("total", total.to_string()),
+                ],
+            ),
+            TrackerType::FileDownload,
+        );
+    }
+
+    file.write_all(req.format.footer().as_bytes()).await?;
+    file.flush().await?;
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn message(id: &str, lines: &[&str], attachments: &[&str]) -> ExportedMessage {
+        ExportedMessage {
+            id: id.into(),
+            sender: "did:key:alice".into(),
+            sender_name: "<Alice>".into(),
+            date: Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            edited: false,
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            attachments: attachments.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    fn export(format: ExportFormat, messages: &[ExportedMessage]) -> String {
+        let mut export = format.header("Chat \"1\"");
+        for (i, message) in messages.iter().enumerate() {
+            export.push_str(&format.message(message, i == 0));
+        }
+        export.push_str(&format.footer());
+        export
+    }
+
+    #[test]
+    fn json_exports_are_valid() {
+        let messages = [
+            message("1", &["hi", "\"quoted\""], &[]),
+            message("2", &[], &["cat.png"]),
+        ];
+        let export: serde_json::Value =
+            serde_json::from_str(&export(ExportFormat::Json, &messages)).unwrap();
+        assert_eq!(export["conversation"], "Chat \"1\"");
+        assert_eq!(export["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(export["messages"][0]["lines"][1], "\"quoted\"");
+        assert_eq!(export["messages"][0]["date"], "2024-01-01T12:00:00+00:00");
+        assert_eq!(export["messages"][1]["attachments"][0], "cat.png");
+
+        let empty: serde_json::Value =
+            serde_json::from_str(&export(ExportFormat::Json, &[])).unwrap();
+        assert!(empty["messages"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn html_exports_are_escaped() {
+        let messages = [message("1", &["<script>alert('x')</script>"], &["a&b.png"])];
+        let export = export(ExportFormat::Html, &messages);
+        assert!(!export.contains("<script>"));
+        assert!(export.contains("&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;"));
+        assert!(export.contains("&lt;Alice&gt;"));
+        assert!(export.contains("<title>Chat &quot;1&quot;</title>"));
+        assert!(export.contains("href=\"a&amp;b.png\""));
+        assert!(export.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn plain_text_exports_list_lines_and_attachments() {
+        let messages = [message("1", &["hi", "there"], &["cat.png"])];
+        let export = export(ExportFormat::PlainText, &messages);
+        assert!(export.starts_with("Chat \"1\"\n========\n\n"));
+        assert!(export.contains("] <Alice>:\n    hi\n    there\n    [cat.png]\n\n"));
+    }
+}
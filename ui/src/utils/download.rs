@@ -1,6 +1,11 @@
 use std::path::PathBuf;
 
-use futures::{future::BoxFuture, FutureExt};
+use common::{
+    warp_runner::{RayGunCmd, WarpCmd},
+    WARP_CMD_CH,
+};
+use futures::{channel::oneshot, future::BoxFuture, FutureExt, StreamExt};
+use warp::constellation::Progression;
 
 pub type DownloadComplete = Box<dyn Send + FnOnce(bool) -> BoxFuture<'static, ()>>;
 
@@ -22,3 +27,32 @@ pub fn get_download_path(path: PathBuf) -> (PathBuf, DownloadComplete) {
     };
     (temp, Box::new(t))
 }
+
+/// Downloads an attachment of the message and waits for the download to finish
+pub async fn download_attachment(
+    message: &warp::raygun::Message,
+    file_name: String,
+    path: PathBuf,
+) -> Result<(), warp::error::Error> {
+    let (tx, rx) = oneshot::channel();
+    WARP_CMD_CH
+        .tx
+        .send(WarpCmd::RayGun(RayGunCmd::DownloadAttachment {
+            conv_id: message.conversation_id(),
+            msg_id: message.id(),
+            file_name,
+            file_path_to_download: path,
+            rsp: tx,
+        }))
+        .map_err(|e| warp::error::Error::OtherWithContext(e.to_string()))?;
+
+    let mut stream = rx.await.expect("command canceled")?;
+    while let Some(progress) = stream.next().await {
+        match progress {
+            Progression::ProgressComplete { .. } => break,
+            Progression::ProgressFailed { error, .. } => return Err(error),
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+//! Uplink's side of the read-only conversation API for extensions (see `extensions::conversations`).
+//! Conversations come from the state and messages are paged from RayGun, like for exports.
+//! Permissions are granted to the library an extension was loaded from, its name is chosen by the
+//! extension and could be the one of another extension.
+
+use std::{ops::Range, rc::Rc};
+
+use common::{
+    state::State,
+    warp_runner::{RayGunCmd, WarpCmd},
+    WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use extensions::conversations::{
+    ApiError, BoxFuture, ConversationInfo, ConversationProvider, MessageInfo, Permission,
+    ProviderHandle,
+};
+use futures::channel::oneshot;
+use uuid::Uuid;
+use warp::raygun::ConversationType;
+
+struct UplinkConversations {
+    state: UseSharedState<State>,
+    // the path of the library
+    extension: String,
+}
+
+impl ConversationProvider for UplinkConversations {
+    fn is_granted(&self, permission: Permission) -> bool {
+        self.state
+            .read()
+            .configuration
+            .extensions
+            .is_granted(&self.extension, permission)
+    }
+
+    fn conversations(&self) -> Vec<ConversationInfo> {
+        let state = self.state.read();
+        state
+            .chats()
+            .all
+            .values()
+            .map(|chat| {
+                let participants: Vec<_> = chat.participants.iter().cloned().collect();
+                ConversationInfo {
+                    id: chat.id,
+                    name: state.chats().title(chat),
+                    is_group: chat.conversation_type == ConversationType::Group,
+                    participants: state
+                        .get_identities(&participants)
+                        .iter()
+                        .map(|identity| (identity.did_key().to_string(), identity.username()))
+                        .collect(),
+                    unreads: chat.unreads(),
+                    favorite: state.is_favorite(chat),
+                }
+            })
+            .collect()
+    }
+
+    fn messages(
+        &self,
+        conversation_id: Uuid,
+        range: Range<usize>,
+    ) -> BoxFuture<Result<(Vec<MessageInfo>, usize), ApiError>> {
+        let exists = self.state.read().get_chat_by_id(conversation_id).is_some();
+        Box::pin(async move {
+            if !exists {
+                return Err(ApiError::ConversationNotFound(conversation_id));
+            }
+            let (tx, rx) = oneshot::channel();
+            WARP_CMD_CH
+                .tx
+                .send(WarpCmd::RayGun(RayGunCmd::FetchMessagePage {
+                    conv_id: conversation_id,
+                    range,
+                    rsp: tx,
+                }))
+                .map_err(|e| ApiError::Failed(e.to_string()))?;
+            let (messages, total) = rx
+                .await
+                .map_err(|e| ApiError::Failed(e.to_string()))?
+                .map_err(|e| ApiError::Failed(e.to_string()))?;
+            let messages = messages
+                .iter()
+                .map(|message| MessageInfo {
+                    id: message.id(),
+                    conversation_id,
+                    sender: message.sender().to_string(),
+                    date: message.date(),
+                    edited: message.modified().is_some(),
+                    lines: message.lines(),
+                    attachments: message.attachments().iter().map(|a| a.name()).collect(),
+                    replied_to: message.replied(),
+                    pinned: message.pinned(),
+                })
+                .collect();
+            Ok((messages, total))
+        })
+    }
+}
+
+#[derive(Props, PartialEq)]
+pub struct ExtensionViewProps {
+    // `Meta::name` of the extension
+    name: String,
+}
+
+/// Renders an extension in a scope of its own, so the API it gets from the context is the one for
+/// its library
+#[allow(non_snake_case)]
+pub fn ExtensionView(cx: Scope<ExtensionViewProps>) -> Element {
+    let state = use_shared_state::<State>(cx)?;
+    use_context_provider(cx, || {
+        // nothing is granted to an empty path
+        let extension = state
+            .read()
+            .ui
+            .extensions
+            .get(&cx.props.name)
+            .map(|ext| ext.path().to_string_lossy().to_string())
+            .unwrap_or_default();
+        ProviderHandle::new(Rc::new(UplinkConversations {
+            state: state.clone(),
+            extension,
+        }))
+    });
+    let state = state.read();
+    let element = state.ui.extensions.get(&cx.props.name)?.render(cx);
+    element
+}
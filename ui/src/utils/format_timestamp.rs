@@ -19,3 +19,13 @@ pub fn format_timestamp_timeago(datetime: DateTime<Utc>, active_language: &str)
     };
     formatter.convert(duration)
 }
+
+/// The length of a call, like 1:05 or 1:02:05
+pub fn format_duration(secs: u64) -> String {
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    }
+}
@@ -0,0 +1,246 @@
+//! GIF search for the GIF picker. Providers only differ in their search URL and the shape of the
+//! response, so a provider describes the request and parses the result while `search` does the rest.
+
+use std::path::{Path, PathBuf};
+
+use common::state::configuration::{GifProviderKind, Gifs};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+// GIFs are sent as attachments, so the larger ones aren't worth the upload
+pub const MAX_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Gif {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    // small version shown in the picker
+    pub preview_url: String,
+    pub url: String,
+}
+
+pub trait GifProvider {
+    fn search_url(&self) -> String;
+    fn query(&self, query: &str, limit: usize) -> Vec<(&'static str, String)>;
+    fn parse(&self, response: Value) -> Vec<Gif>;
+}
+
+pub struct Tenor {
+    api_key: String,
+}
+
+impl GifProvider for Tenor {
+    fn search_url(&self) -> String {
+        "https://tenor.googleapis.com/v2/search".into()
+    }
+
+    fn query(&self, query: &str, limit: usize) -> Vec<(&'static str, String)> {
+        vec![
+            ("q", query.into()),
+            ("key", self.api_key.clone()),
+            ("limit", limit.to_string()),
+            ("media_filter", "gif,tinygif".into()),
+        ]
+    }
+
+    fn parse(&self, response: Value) -> Vec<Gif> {
+        let results = response["results"].as_array().cloned().unwrap_or_default();
+        results
+            .iter()
+            .filter_map(|result| {
+                Some(Gif {
+                    id: result["id"].as_str()?.into(),
+                    title: result["content_description"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .into(),
+                    preview_url: result["media_formats"]["tinygif"]["url"].as_str()?.into(),
+                    url: result["media_formats"]["gif"]["url"].as_str()?.into(),
+                })
+            })
+            .collect()
+    }
+}
+
+pub struct Giphy {
+    api_key: String,
+}
+
+impl GifProvider for Giphy {
+    fn search_url(&self) -> String {
+        "https://api.giphy.com/v1/gifs/search".into()
+    }
+
+    fn query(&self, query: &str, limit: usize) -> Vec<(&'static str, String)> {
+        vec![
+            ("q", query.into()),
+            ("api_key", self.api_key.clone()),
+            ("limit", limit.to_string()),
+        ]
+    }
+
+    fn parse(&self, response: Value) -> Vec<Gif> {
+        let results = response["data"].as_array().cloned().unwrap_or_default();
+        results
+            .iter()
+            .filter_map(|result| {
+                Some(Gif {
+                    id: result["id"].as_str()?.into(),
+                    title: result["title"].as_str().unwrap_or_default().into(),
+                    preview_url: result["images"]["fixed_width_small"]["url"]
+                        .as_str()?
+                        .into(),
+                    url: result["images"]["original"]["url"].as_str()?.into(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A self hosted endpoint which is called with `q` and `limit` and returns a list of `Gif`s
+pub struct SelfHosted {
+    endpoint: String,
+}
+
+impl GifProvider for SelfHosted {
+    fn search_url(&self) -> String {
+        self.endpoint.clone()
+    }
+
+    fn query(&self, query: &str, limit: usize) -> Vec<(&'static str, String)> {
+        vec![("q", query.into()), ("limit", limit.to_string())]
+    }
+
+    fn parse(&self, response: Value) -> Vec<Gif> {
+        serde_json::from_value(response).unwrap_or_default()
+    }
+}
+
+/// The provider configured in the settings. None if GIFs are disabled or the provider is missing its
+/// API key or endpoint.
+pub fn get_provider(config: &Gifs) -> Option<Box<dyn GifProvider>> {
+    let api_key = config.api_key.trim().to_string();
+    let endpoint = config.endpoint.trim().to_string();
+    match config.provider {
+        GifProviderKind::Disabled => None,
+        GifProviderKind::Tenor if !api_key.is_empty() => Some(Box::new(Tenor { api_key })),
+        GifProviderKind::Giphy if !api_key.is_empty() => Some(Box::new(Giphy { api_key })),
+        GifProviderKind::SelfHosted if !endpoint.is_empty() => {
+            Some(Box::new(SelfHosted { endpoint }))
+        }
+        _ => None,
+    }
+}
+
+pub async fn search(
+    provider: &dyn GifProvider,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<Gif>, reqwest::Error> {
+    let response = Client::new()
+        .get(provider.search_url())
+        .query(&provider.query(query, limit))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Value>()
+        .await?;
+    Ok(provider.parse(response))
+}
+
+/// Downloads the GIF into `dir` so it can be sent as an attachment. GIFs larger than `MAX_SIZE` aren't
+/// downloaded
+pub async fn download(gif: &Gif, dir: &Path) -> anyhow::Result<PathBuf> {
+    let mut response = reqwest::get(&gif.url).await?.error_for_status()?;
+    if response.content_length().unwrap_or_default() > MAX_SIZE {
+        anyhow::bail!("the gif is larger than {MAX_SIZE} bytes");
+    }
+    tokio::fs::create_dir_all(dir).await?;
+    let path = dir.join(file_name(&gif.id));
+    let mut file = tokio::fs::File::create(&path).await?;
+    // the length isn't always sent, so it is checked while the gif is written as well
+    let mut size = 0;
+    while let Some(chunk) = response.chunk().await? {
+        size += chunk.len() as u64;
+        if size > MAX_SIZE {
+            drop(file);
+            let _ = tokio::fs::remove_file(&path).await;
+            anyhow::bail!("the gif is larger than {MAX_SIZE} bytes");
+        }
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    Ok(path)
+}
+
+// the id comes from the provider, keep only what is safe in a file name
+fn file_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+    if name.is_empty() {
+        format!("{}.gif", Uuid::new_v4())
+    } else {
+        format!("{name}.gif")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn responses_are_parsed() {
+        let tenor = Tenor {
+            api_key: "key".into(),
+        };
+        let response = json!({"results": [
+            {
+                "id": "1",
+                "content_description": "cat",
+                "media_formats": {"tinygif": {"url": "tiny"}, "gif": {"url": "full"}}
+            },
+            // without a preview the result is skipped
+            {"id": "2", "media_formats": {"gif": {"url": "full"}}}
+        ]});
+        let gif = Gif {
+            id: "1".into(),
+            title: "cat".into(),
+            preview_url: "tiny".into(),
+            url: "full".into(),
+        };
+        assert_eq!(tenor.parse(response), vec![gif.clone()]);
+
+        let giphy = Giphy {
+            api_key: "key".into(),
+        };
+        let response = json!({"data": [{
+            "id": "1",
+            "title": "cat",
+            "images": {"fixed_width_small": {"url": "tiny"}, "original": {"url": "full"}}
+        }]});
+        assert_eq!(giphy.parse(response), vec![gif.clone()]);
+
+        let self_hosted = SelfHosted {
+            endpoint: "https://gifs.example".into(),
+        };
+        let response = json!([{"id": "1", "title": "cat", "preview_url": "tiny", "url": "full"}]);
+        assert_eq!(self_hosted.parse(response), vec![gif]);
+        assert!(self_hosted.parse(json!({"error": "down"})).is_empty());
+        assert!(tenor.parse(json!({"error": "down"})).is_empty());
+    }
+
+    #[test]
+    fn file_names_are_safe() {
+        assert_eq!(file_name("a1-b_2"), "a1-b_2.gif");
+        assert_eq!(file_name("../../etc/passwd"), "etcpasswd.gif");
+        let name = file_name("/..");
+        assert!(name.ends_with(".gif") && name.len() > ".gif".len());
+    }
+}
@@ -0,0 +1,149 @@
+//! Hotkeys which work while Uplink is in the background, like showing the window or muting the
+//! microphone during a call. They are registered with the OS, which refuses combinations another
+//! application holds already. Those are remembered so the keybind settings can point them out.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use common::state::settings::GlobalShortcut;
+use dioxus::prelude::*;
+use dioxus_desktop::wry::application::accelerator::Accelerator;
+use dioxus_desktop::{use_window, DesktopContext, ShortcutId};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use tracing::log;
+
+static REFUSED: Lazy<RwLock<HashSet<GlobalShortcut>>> = Lazy::new(Default::default);
+// how long the keys of a hotkey are taken to be held after they were pressed
+const HELD_FOR: Duration = Duration::from_secs(1);
+
+/// True if the OS didn't register the hotkey, usually because another application uses it
+pub fn is_refused(shortcut: &GlobalShortcut) -> bool {
+    REFUSED.read().contains(shortcut)
+}
+
+// whether the keys of a hotkey are held down. some platforms report releasing the keys like pressing
+// them and others don't report it at all, the callback can't tell. so the keys count as held for a
+// moment after a press, and what is reported meanwhile is their release or the OS repeating them
+#[derive(Debug, Default)]
+struct KeyState {
+    pressed_at: Option<Instant>,
+}
+
+impl KeyState {
+    // true if the event pressed the hotkey
+    fn on_event(&mut self, now: Instant) -> bool {
+        match self.pressed_at {
+            Some(pressed_at) if now.duration_since(pressed_at) < HELD_FOR => false,
+            _ => {
+                self.pressed_at = Some(now);
+                true
+            }
+        }
+    }
+}
+
+// unregisters the hotkey when the component is dropped
+struct Registration {
+    desktop: DesktopContext,
+    id: Option<ShortcutId>,
+    shortcut: GlobalShortcut,
+}
+
+impl Registration {
+    fn new(
+        desktop: DesktopContext,
+        accelerator: &str,
+        shortcut: GlobalShortcut,
+        callback: impl FnMut() + 'static,
+    ) -> Self {
+        // keys which can't be named can't be registered either
+        if accelerator.contains("unknown") {
+            return Self {
+                desktop,
+                id: None,
+                shortcut,
+            };
+        }
+        let id = Accelerator::from_str(accelerator)
+            .ok()
+            .and_then(|accelerator| desktop.create_shortcut(accelerator, callback).ok());
+        if id.is_none() {
+            log::warn!("failed to register the hotkey {accelerator} for {shortcut}");
+            REFUSED.write().insert(shortcut.clone());
+        }
+        Self {
+            desktop,
+            id,
+            shortcut,
+        }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            self.desktop.remove_shortcut(id);
+        }
+        REFUSED.write().remove(&self.shortcut);
+    }
+}
+
+#[derive(Props)]
+pub struct Props<'a> {
+    // e.g. "Ctrl + Shift + U"
+    accelerator: String,
+    global_shortcut: GlobalShortcut,
+    on_hotkey: EventHandler<'a, GlobalShortcut>,
+}
+
+/// Registers a system-wide hotkey while rendered. Keyed by the accelerator, a changed keybind
+/// drops the old registration and makes a new one
+#[allow(non_snake_case)]
+pub fn SystemHotkey<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let desktop = use_window(cx);
+    let pressed = use_ref(cx, || false);
+    // the handlers need a scope, so the press is handled on the next render
+    if *pressed.read() {
+        *pressed.write_silent() = false;
+        cx.props.on_hotkey.call(cx.props.global_shortcut.clone());
+    }
+
+    cx.use_hook(|| {
+        let mut keys = KeyState::default();
+        Registration::new(
+            desktop.clone(),
+            &cx.props.accelerator,
+            cx.props.global_shortcut.clone(),
+            {
+                to_owned![pressed];
+                move || {
+                    if keys.on_event(Instant::now()) {
+                        pressed.with_mut(|p| *p = true);
+                    }
+                }
+            },
+        )
+    });
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counts_a_press_once() {
+        let mut keys = KeyState::default();
+        let start = Instant::now();
+        assert!(keys.on_event(start));
+        // released, or repeated by the OS
+        assert!(!keys.on_event(start + Duration::from_millis(150)));
+        assert!(!keys.on_event(start + Duration::from_millis(600)));
+        // pressed again
+        assert!(keys.on_event(start + HELD_FOR + Duration::from_millis(10)));
+        assert!(!keys.on_event(start + HELD_FOR + Duration::from_millis(100)));
+    }
+}
@@ -0,0 +1,33 @@
+// forwards the key combinations pressed in the window to the keybinds. typing without a modifier
+// is left alone, and the listeners of a previous render are replaced. a key counts once while it
+// is held down, the state is kept here since auto-repeat isn't flagged on every platform
+if (window.uplinkKeybinds) {
+    window.removeEventListener("keydown", window.uplinkKeybinds.down);
+    window.removeEventListener("keyup", window.uplinkKeybinds.up);
+    window.removeEventListener("blur", window.uplinkKeybinds.blur);
+}
+var held = new Set();
+window.uplinkKeybinds = {
+    down: (event) => {
+        if (held.has(event.code)) {
+            return;
+        }
+        held.add(event.code);
+        if (!(event.ctrlKey || event.altKey || event.metaKey)) {
+            return;
+        }
+        dioxus.send({
+            code: event.code,
+            ctrl: event.ctrlKey,
+            shift: event.shiftKey,
+            alt: event.altKey,
+            meta: event.metaKey,
+        });
+    },
+    up: (event) => held.delete(event.code),
+    // keys released in another window never send a keyup here
+    blur: () => held.clear(),
+};
+window.addEventListener("keydown", window.uplinkKeybinds.down);
+window.addEventListener("keyup", window.uplinkKeybinds.up);
+window.addEventListener("blur", window.uplinkKeybinds.blur);
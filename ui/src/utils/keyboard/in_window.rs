@@ -0,0 +1,142 @@
+//! Keybinds which only work while the window has focus. They are read from the keydown events of
+//! the webview, so nothing is registered with the OS and other applications keep their shortcuts.
+
+use common::state::settings::{GlobalShortcut, Shortcut};
+use dioxus::prelude::*;
+use dioxus_desktop::wry::application::keyboard::ModifiersState;
+use serde::Deserialize;
+
+const KEYDOWN_SCRIPT: &str = include_str!("./in_window.js");
+
+#[derive(Debug, Deserialize)]
+struct KeyDown {
+    // e.g. "KeyK", see `Shortcut::string_to_keycode_and_modifiers_state`
+    code: String,
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+}
+
+impl KeyDown {
+    fn modifiers(&self) -> ModifiersState {
+        [
+            (self.ctrl, ModifiersState::CONTROL),
+            (self.shift, ModifiersState::SHIFT),
+            (self.alt, ModifiersState::ALT),
+            (self.meta, ModifiersState::SUPER),
+        ]
+        .into_iter()
+        .filter(|(pressed, _)| *pressed)
+        .fold(ModifiersState::empty(), |all, (_, modifier)| all | modifier)
+    }
+}
+
+// the keybind made of exactly the pressed keys
+fn shortcut_for(
+    keybinds: &[(GlobalShortcut, Shortcut)],
+    key_down: &KeyDown,
+) -> Option<GlobalShortcut> {
+    let (keys, _) = Shortcut::string_to_keycode_and_modifiers_state(vec![key_down.code.clone()]);
+    if keys.is_empty() {
+        return None;
+    }
+    let modifiers = key_down.modifiers();
+    keybinds
+        .iter()
+        .find(|(_, shortcut)| {
+            shortcut.keys == keys
+                && shortcut
+                    .modifiers
+                    .iter()
+                    .fold(ModifiersState::empty(), |all, modifier| all | *modifier)
+                    == modifiers
+        })
+        .map(|(global_shortcut, _)| global_shortcut.clone())
+}
+
+#[derive(Props)]
+pub struct Props<'a> {
+    keybinds: Vec<(GlobalShortcut, Shortcut)>,
+    on_shortcut: EventHandler<'a, GlobalShortcut>,
+}
+
+/// Calls `on_shortcut` when the keys of one of the keybinds are pressed in the window
+#[allow(non_snake_case)]
+pub fn InWindowShortcuts<'a>(cx: Scope<'a, Props<'a>>) -> Element<'a> {
+    let eval = use_eval(cx);
+    let keybinds = use_ref(cx, Vec::new);
+    *keybinds.write_silent() = cx.props.keybinds.clone();
+    let pressed = use_ref(cx, || None);
+    // the handlers need a scope, so the keybind is handled on the next render
+    if let Some(global_shortcut) = pressed.write_silent().take() {
+        cx.props.on_shortcut.call(global_shortcut);
+    }
+
+    use_future(cx, (), |_| {
+        to_owned![eval, keybinds, pressed];
+        async move {
+            let Ok(keys) = eval(KEYDOWN_SCRIPT) else {
+                return;
+            };
+            while let Ok(value) = keys.recv().await {
+                let Ok(key_down) = serde_json::from_value::<KeyDown>(value) else {
+                    continue;
+                };
+                if let Some(global_shortcut) = shortcut_for(&keybinds.read(), &key_down) {
+                    pressed.set(Some(global_shortcut));
+                }
+            }
+        }
+    });
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key_down(code: &str, ctrl: bool, shift: bool) -> KeyDown {
+        KeyDown {
+            code: code.into(),
+            ctrl,
+            shift,
+            alt: false,
+            meta: false,
+        }
+    }
+
+    #[test]
+    fn matches_exactly_the_pressed_keys() {
+        let keybinds = vec![
+            (
+                GlobalShortcut::OpenQuickSwitcher,
+                Shortcut::from((vec![KeyCode::K], vec![ModifiersState::CONTROL], false)),
+            ),
+            (
+                GlobalShortcut::LockApp,
+                Shortcut::from((
+                    vec![KeyCode::L],
+                    vec![ModifiersState::SHIFT, ModifiersState::CONTROL],
+                    false,
+                )),
+            ),
+        ];
+        assert_eq!(
+            shortcut_for(&keybinds, &key_down("KeyK", true, false)),
+            Some(GlobalShortcut::OpenQuickSwitcher)
+        );
+        // the order the modifiers were recorded in doesn't matter
+        assert_eq!(
+            shortcut_for(&keybinds, &key_down("KeyL", true, true)),
+            Some(GlobalShortcut::LockApp)
+        );
+        assert_eq!(shortcut_for(&keybinds, &key_down("KeyK", true, true)), None);
+        assert_eq!(
+            shortcut_for(&keybinds, &key_down("KeyL", true, false)),
+            None
+        );
+        assert_eq!(shortcut_for(&keybinds, &key_down("F13", true, false)), None);
+    }
+}
@@ -1,15 +1,14 @@
 use common::state::settings::{key_code_to_str, modifier_state_to_string, GlobalShortcut};
 use common::state::State;
 use dioxus::prelude::*;
-use dioxus_desktop::use_global_shortcut;
 use dioxus_desktop::wry::application::keyboard::ModifiersState;
-use once_cell::sync::Lazy;
-
-use parking_lot::RwLock;
 
+pub mod hotkeys;
+pub mod in_window;
 pub mod shortcut_handlers;
 
-static CALL_COUNT: Lazy<RwLock<u32>> = Lazy::new(|| RwLock::new(0));
+use hotkeys::SystemHotkey;
+use in_window::InWindowShortcuts;
 
 const NAVIGATE_AND_HIGHLIGHT_KEYBINDS: &str = include_str!("./navigate_and_highlight_keybinds.js");
 
@@ -22,26 +21,16 @@ pub struct Props<'a> {
 
 #[allow(non_snake_case)]
 pub fn KeyboardShortcuts<'a>(cx: Scope<'a, Props>) -> Element<'a> {
-    if cfg!(target_os = "linux") {
-        return None;
-    }
+    // the hotkeys registered with the OS don't work on linux
+    let os_shortcuts = !cfg!(target_os = "linux");
 
     if cx.props.is_on_auth_pages.unwrap_or(false) {
         let state = use_ref(cx, State::load);
         let keybinds = state.read().settings.keybinds.clone();
         return cx.render(rsx! {
-            for (global_shortcut, shortcut) in keybinds {
-                rsx!{
-                    RenderGlobalShortCuts {
-                        keys: shortcut.keys,
-                        modifiers: shortcut.modifiers,
-                        on_global_shortcut: move |global_shortcut: GlobalShortcut| {
-                            // If global shortcuts are paused (for example, on the keybinds settings page) don't callback
-                            cx.props.on_global_shortcut.call(global_shortcut);
-                        },
-                        global_shortcut: global_shortcut.clone(),
-                    }
-                }
+            InWindowShortcuts {
+                keybinds: keybinds,
+                on_shortcut: move |global_shortcut| cx.props.on_global_shortcut.call(global_shortcut),
             }
         });
     }
@@ -49,99 +38,66 @@ pub fn KeyboardShortcuts<'a>(cx: Scope<'a, Props>) -> Element<'a> {
     let state = use_shared_state::<State>(cx)?;
     let eval = use_eval(cx);
 
-    if !state.read().settings.pause_global_keybinds {
-        let keybinds = state.read().settings.keybinds.clone();
-        return cx.render(rsx! {
-            for (global_shortcut, shortcut) in keybinds {
-                rsx!{
-                    RenderGlobalShortCuts {
-                        keys: shortcut.keys,
-                        modifiers: shortcut.modifiers,
-                        on_global_shortcut: move |global_shortcut: GlobalShortcut| {
-                            // If global shortcuts are paused (for example, on the keybinds settings page) don't callback
-                            cx.props.on_global_shortcut.call(global_shortcut);
-                        },
-                        global_shortcut: global_shortcut.clone(),
-                    }
-                }
-            }
-        });
-    } else if !state.read().settings.is_recording_new_keybind {
-        let keybinds = state.read().settings.keybinds.clone();
-        return cx.render(rsx! {
-            for (global_shortcut, shortcut) in keybinds {
-                rsx!{
-                    RenderGlobalShortCuts {
-                        keys: shortcut.keys,
-                        modifiers: shortcut.modifiers,
-                        on_global_shortcut: move |global_shortcut: GlobalShortcut| {
-                                let scroll_script = NAVIGATE_AND_HIGHLIGHT_KEYBINDS.to_string().replace("$SHORTCUT_PRESSED", format!("{:?}", global_shortcut).as_str());
-                                let _ = eval(&scroll_script);
-                        },
-                        global_shortcut: global_shortcut.clone(),
-                    }
-                }
-            }
-        });
-    } else {
-        println!("rendering keyboard shortcuts - 3");
-
-        None
+    // nothing is registered while a new keybind is recorded, so the keys reach the recorder
+    if state.read().settings.is_recording_new_keybind {
+        return None;
     }
-}
+    let paused = state.read().settings.pause_global_keybinds;
+    let on_shortcut = move |global_shortcut: GlobalShortcut| {
+        if paused {
+            // If global shortcuts are paused (for example, on the keybinds settings page) highlight the keybind instead
+            let scroll_script = NAVIGATE_AND_HIGHLIGHT_KEYBINDS.to_string().replace(
+                "$SHORTCUT_PRESSED",
+                format!("{:?}", global_shortcut).as_str(),
+            );
+            let _ = eval(&scroll_script);
+        } else {
+            cx.props.on_global_shortcut.call(global_shortcut);
+        }
+    };
+    // the others only work while the window has focus
+    let (system, in_window): (Vec<_>, Vec<_>) = state
+        .read()
+        .settings
+        .keybinds
+        .clone()
+        .into_iter()
+        .partition(|(_, shortcut)| shortcut.system_shortcut);
+    let system = if os_shortcuts { system } else { vec![] };
+    let system = system.into_iter().map(|(global_shortcut, shortcut)| {
+        (
+            global_shortcut,
+            accelerator(&shortcut.keys, &shortcut.modifiers),
+        )
+    });
 
-#[derive(Props)]
-struct GlobalShortcutProps<'a> {
-    keys: Vec<KeyCode>,
-    modifiers: Vec<ModifiersState>,
-    on_global_shortcut: EventHandler<'a, GlobalShortcut>,
-    global_shortcut: GlobalShortcut,
+    cx.render(rsx! {
+        InWindowShortcuts {
+            keybinds: in_window,
+            on_shortcut: move |global_shortcut| on_shortcut(global_shortcut),
+        }
+        for (global_shortcut, accelerator) in system {
+            rsx!{
+                SystemHotkey {
+                    key: "{global_shortcut}-{accelerator}",
+                    accelerator: accelerator,
+                    global_shortcut: global_shortcut.clone(),
+                    on_hotkey: move |global_shortcut| on_shortcut(global_shortcut),
+                }
+            }
+        }
+    })
 }
 
-fn RenderGlobalShortCuts<'a>(cx: Scope<'a, GlobalShortcutProps>) -> Element<'a> {
-    let command_pressed = use_ref(cx, || false);
-
-    if *command_pressed.read() {
-        *command_pressed.write_silent() = false;
-        cx.props
-            .on_global_shortcut
-            .call(cx.props.global_shortcut.clone());
-    }
-
-    let key_code_strs: Vec<String> = cx
-        .props
-        .keys
+// e.g. "Ctrl + Shift + U"
+fn accelerator(keys: &[KeyCode], modifiers: &[ModifiersState]) -> String {
+    let modifier_strs: Vec<String> = modifiers
         .iter()
-        .map(|key_code| key_code_to_str(key_code).to_string())
+        .map(|modifier| modifier_state_to_string(*modifier))
         .collect();
-
-    let modifier_strs: Vec<String> = cx
-        .props
-        .modifiers
+    let key_code_strs: Vec<String> = keys
         .iter()
-        .map(|modifier| modifier_state_to_string(*modifier))
+        .map(|key_code| key_code_to_str(key_code).to_string())
         .collect();
-
-    let modifiers_and_keys = [modifier_strs.join(" + "), key_code_strs.join(" + ")].join(" + ");
-
-    if modifiers_and_keys.contains("unknown") {
-        return None;
-    }
-
-    use_global_shortcut(cx, modifiers_and_keys.as_str(), {
-        to_owned![command_pressed];
-        move || {
-            *CALL_COUNT.write() += 1;
-
-            if *CALL_COUNT.read() == 1 {
-                command_pressed.with_mut(|i| *i = true);
-            }
-
-            if *CALL_COUNT.read() == 2 {
-                *CALL_COUNT.write() = 0;
-            }
-        }
-    });
-
-    None
+    [modifier_strs.join(" + "), key_code_strs.join(" + ")].join(" + ")
 }
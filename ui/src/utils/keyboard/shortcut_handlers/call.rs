@@ -0,0 +1,45 @@
+use tracing::log;
+
+use crate::utils::EvalProvider;
+
+// the shortcuts press the same buttons as the mouse would, so they behave the same. this includes showing the
+// pre-call check, whose join button is pressed if it is open.
+const ANSWER_CALL: &str = r##"
+(document.querySelector('button[name="pre-call-check-join"]')
+    || document.querySelector('button[name="accept-call-button"]'))?.click()
+"##;
+
+const HANG_UP_CALL: &str = r##"
+(document.querySelector('button[name="call-hangup-button"]')
+    || document.querySelector('button[name="deny-call-button"]'))?.click()
+"##;
+
+const CYCLE_PARTICIPANTS: &str = r##"
+;(() => {
+    const participants = Array.from(
+        document.querySelectorAll("#remote-controls .call-user, #remote-controls .additional-participants button")
+    )
+    if (participants.length > 0) {
+        const current = participants.indexOf(document.activeElement)
+        participants[(current + 1) % participants.length].focus()
+    }
+})()
+"##;
+
+pub fn answer(eval: &EvalProvider) {
+    run(eval, ANSWER_CALL);
+}
+
+pub fn hang_up(eval: &EvalProvider) {
+    run(eval, HANG_UP_CALL);
+}
+
+pub fn cycle_participants(eval: &EvalProvider) {
+    run(eval, CYCLE_PARTICIPANTS);
+}
+
+fn run(eval: &EvalProvider, script: &str) {
+    if let Err(e) = eval(script) {
+        log::error!("failed to run call shortcut: {:?}", e);
+    }
+}
@@ -0,0 +1,68 @@
+use common::state::{settings::GlobalShortcut, State};
+use dioxus::prelude::{KeyCode, UseSharedState};
+use dioxus_router::prelude::Navigator;
+use tracing::log;
+
+use crate::{utils::EvalProvider, UplinkRoute};
+
+// the send button of the expanded editor while it is open, else the one of the chatbar. a disabled
+// button, e.g. for an empty message, ignores the click
+const SEND_MESSAGE: &str = r##"
+(document.querySelector('button[name="expanded-editor-send-button"]')
+    || document.querySelector('button[name="send-message-button"]'))?.click()
+"##;
+
+const SEND_FROM_EDITOR: &str = r##"
+document.querySelector('button[name="expanded-editor-send-button"]')?.click()
+"##;
+
+// the chats page may still be rendering, so the search input is looked for a few times
+const FOCUS_SEARCH: &str = r##"
+;(() => {
+    let tries = 0
+    const focus = () => {
+        const input = document.querySelector('input[aria-label="chat-search-input"]')
+        if (input) {
+            input.focus()
+            input.select()
+        } else if (++tries < 10) {
+            setTimeout(focus, 50)
+        }
+    }
+    focus()
+})()
+"##;
+
+pub fn send_message(state: UseSharedState<State>, eval: &EvalProvider) {
+    // the chatbar sends on enter by itself, pressing its button too would send twice
+    let chatbar_sends = state
+        .read()
+        .settings
+        .keybinds
+        .iter()
+        .any(|(shortcut, keys)| {
+            *shortcut == GlobalShortcut::SendMessage && keys.keys.contains(&KeyCode::Enter)
+        });
+    run(
+        eval,
+        if chatbar_sends {
+            SEND_FROM_EDITOR
+        } else {
+            SEND_MESSAGE
+        },
+    );
+}
+
+pub fn focus_search(state: UseSharedState<State>, router: &Navigator, eval: &EvalProvider) {
+    if state.read().ui.locked {
+        return;
+    }
+    router.replace(UplinkRoute::ChatLayout {});
+    run(eval, FOCUS_SEARCH);
+}
+
+fn run(eval: &EvalProvider, script: &str) {
+    if let Err(e) = eval(script) {
+        log::error!("failed to run chat shortcut: {:?}", e);
+    }
+}
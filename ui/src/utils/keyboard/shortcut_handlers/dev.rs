@@ -10,6 +10,15 @@ pub fn open_close_dev_tools(cx: &ScopeState) {
         window.webview.open_devtools();
     }
 }
+// the overlay is only shown in developer mode
+pub fn toggle_profiling_overlay(state: UseSharedState<State>) {
+    if !state.read().configuration.developer.developer_mode {
+        return;
+    }
+    let show = state.read().ui.show_profiling_overlay;
+    state.write().ui.show_profiling_overlay = !show;
+}
+
 pub fn toggle_devmode(state: UseSharedState<State>) {
     let devmode = state.read().configuration.developer.developer_mode;
     state
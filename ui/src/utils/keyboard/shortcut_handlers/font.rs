@@ -1,3 +1,5 @@
+use common::state::chats::CHAT_ZOOM_STEP;
+use common::state::ui::Layout;
 use common::state::{Action, State};
 use dioxus_hooks::UseSharedState;
 
@@ -16,3 +18,19 @@ pub fn decrease_size(state: UseSharedState<State>) {
         state.write().mutate(Action::SetFontScale(value - 0.25));
     }
 }
+
+/// Zooms the text of the open chat in or out, or back to the normal size for `None`
+pub fn zoom_chat(state: UseSharedState<State>, zoom_in: Option<bool>) {
+    let Some(chat_id) = state.read().chats().active else {
+        return;
+    };
+    if state.read().ui.current_layout != Layout::Compose {
+        return;
+    }
+    let zoom = match zoom_in {
+        Some(true) => state.read().chats().zoom(&chat_id) + CHAT_ZOOM_STEP,
+        Some(false) => state.read().chats().zoom(&chat_id) - CHAT_ZOOM_STEP,
+        None => 1.0,
+    };
+    state.write().mutate(Action::SetChatZoom(chat_id, zoom));
+}
@@ -1,5 +1,10 @@
+use common::state::{Action, State};
+use dioxus::prelude::UseSharedState;
 use dioxus_core::ScopeState;
 use dioxus_desktop::use_window;
+use dioxus_router::prelude::Navigator;
+
+use crate::UplinkRoute;
 
 /// The functionality will operate correctly only when the application is not in fullscreen mode.
 ///
@@ -22,3 +27,46 @@ pub fn set_app_visible(cx: &ScopeState) {
         window.set_visible(false);
     }
 }
+
+// the switcher would show the names of the chats on the lock screen
+pub fn toggle_quick_switcher(state: UseSharedState<State>) {
+    if state.read().ui.locked {
+        return;
+    }
+    let show = state.read().ui.show_quick_switcher;
+    state.write().ui.show_quick_switcher = !show;
+}
+
+/// Opens the chat below (or above) the active one in the sidebar, wrapping around at the ends
+pub fn cycle_chats(state: UseSharedState<State>, router: &Navigator, forward: bool) {
+    if state.read().ui.locked {
+        return;
+    }
+    let chats = state.read().chats_sidebar();
+    if chats.is_empty() {
+        return;
+    }
+    let active = state.read().get_active_chat().map(|c| c.id);
+    let position = active.and_then(|id| chats.iter().position(|c| c.id == id));
+    let next = match (position, forward) {
+        (None, true) => 0,
+        (None, false) => chats.len() - 1,
+        (Some(i), true) => (i + 1) % chats.len(),
+        (Some(i), false) => (i + chats.len() - 1) % chats.len(),
+    };
+    state
+        .write()
+        .mutate(Action::ChatWith(&chats[next].id, true));
+    router.replace(UplinkRoute::ChatLayout {});
+}
+
+pub fn open_settings(state: UseSharedState<State>, router: &Navigator) {
+    if state.read().ui.locked {
+        return;
+    }
+    router.replace(UplinkRoute::SettingsLayout {});
+}
+
+pub fn lock(state: UseSharedState<State>) {
+    state.write().mutate(Action::SetLocked(true));
+}
@@ -0,0 +1,39 @@
+//! Receives the `uplink://` links the system passes to the running app, see `common::utils::handoff`.
+//! They are kept until the user is logged in, the router then opens them.
+
+use common::utils::handoff::{self, Handoff};
+use dioxus::prelude::*;
+use tracing::log;
+
+pub fn use_link_listener(cx: &ScopeState) {
+    // the links which new instances of Uplink were launched with
+    use_future(cx, (), |_| async move {
+        if let Err(e) = handoff::listen().await {
+            log::error!("failed to listen for links: {e}");
+        }
+    });
+
+    // macOS doesn't launch another instance, the running app is told about the link
+    #[cfg(target_os = "macos")]
+    dioxus_desktop::use_wry_event_handler(cx, |event, _| {
+        if let dioxus_desktop::tao::event::Event::Opened { urls } = event {
+            for url in urls {
+                match url.as_str().parse::<Handoff>() {
+                    Ok(link) => handoff::open(link),
+                    Err(e) => log::warn!("ignoring the link {url}: {e}"),
+                }
+            }
+        }
+    });
+}
+
+/// Opens the link Uplink was launched with once it runs. Returns false if it was passed to the
+/// Uplink which already runs, this one should then exit
+pub fn open_launch_link(link: &str) -> bool {
+    match link.parse::<Handoff>() {
+        Ok(link) if handoff::forward(link) => return false,
+        Ok(link) => handoff::open(link),
+        Err(e) => log::warn!("ignoring the link {link}: {e}"),
+    }
+    true
+}
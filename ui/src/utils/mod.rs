@@ -11,13 +11,23 @@ use crate::{window_manager::WindowManagerCmd, WINDOW_CMD_CH};
 
 pub mod async_task_queue;
 pub mod auto_updater;
+pub mod automation;
+pub mod camera;
 pub mod clipboard;
+pub mod conversation_export;
 pub mod download;
+pub mod extension_api;
 pub mod format_timestamp;
 pub mod get_drag_event;
 pub mod get_font_sizes;
+pub mod gifs;
 pub mod keyboard;
+pub mod links;
+pub mod presence;
+pub mod profiling;
+pub mod speech_recognition;
 pub mod verify_valid_paths;
+pub mod wallpaper;
 
 pub type EvalProvider = Rc<dyn Fn(&str) -> Result<UseEval, EvalError>>;
 
@@ -119,7 +129,7 @@ pub fn build_participants(identities: &Vec<state::Identity>) -> Vec<UserInfo> {
             platform,
             status: identity.identity_status().into(),
             username: identity.username(),
-            photo: identity.profile_picture(),
+            photo: identity.avatar(),
         })
     }
 
@@ -133,7 +143,7 @@ pub fn build_user_from_identity(identity: &state::Identity) -> UserInfo {
         platform,
         status: identity.identity_status().into(),
         username: identity.username(),
-        photo: identity.profile_picture(),
+        photo: identity.avatar(),
     }
 }
 
@@ -0,0 +1,93 @@
+//! Setting the online status, by hand or when the user steps away from the computer. Going away is
+//! noticed from the time since the keyboard or mouse was last used, as the OS reports it.
+
+use std::time::Duration;
+
+use common::{
+    state::State,
+    warp_runner::{MultiPassCmd, WarpCmd},
+    WARP_CMD_CH,
+};
+use dioxus::prelude::*;
+use futures::channel::oneshot;
+use tokio::time::sleep;
+use tracing::log;
+use user_idle::UserIdle;
+use warp::multipass::identity::IdentityStatus;
+
+const CHECK_EVERY: Duration = Duration::from_secs(15);
+
+pub async fn set_status(state: &UseSharedState<State>, status: IdentityStatus) {
+    let (tx, rx) = oneshot::channel();
+    if let Err(e) = WARP_CMD_CH
+        .tx
+        .send(WarpCmd::MultiPass(MultiPassCmd::SetStatus {
+            status,
+            rsp: tx,
+        }))
+    {
+        log::error!("failed to send warp command: {}", e);
+        return;
+    }
+    match rx.await {
+        Ok(Ok(mut identity)) => {
+            // the pictures aren't part of the returned identity
+            let current = state.read().get_own_identity();
+            identity.copy_profile_picture(&current);
+            identity.set_profile_banner(&current.profile_banner());
+            state.write().set_own_identity(identity);
+        }
+        Ok(Err(e)) => log::error!("failed to set the status: {e}"),
+        Err(e) => log::error!("failed to set the status: {e}"),
+    }
+}
+
+// None if the OS can't tell
+fn idle_time() -> Option<Duration> {
+    match UserIdle::get_time() {
+        Ok(idle) => Some(Duration::from_secs(idle.as_seconds())),
+        Err(e) => {
+            log::debug!("failed to get the idle time: {e:?}");
+            None
+        }
+    }
+}
+
+/// Sets the status to away after the configured time without input, and back to online on return.
+/// Only an online status is changed, Busy or Invisible stay as they were picked
+pub fn use_auto_away(cx: &ScopeState) -> Option<()> {
+    let state = use_shared_state::<State>(cx)?;
+
+    use_future(cx, (), |_| {
+        to_owned![state];
+        async move {
+            // true while the status is away because of this
+            let mut went_away = false;
+            loop {
+                sleep(CHECK_EVERY).await;
+                let presence = state.read().configuration.presence;
+                let status = state.read().get_own_identity().identity_status();
+                // talking without touching the keyboard isn't being away
+                let in_call = state.read().ui.call_info.active_call().is_some();
+                let threshold = Duration::from_secs(u64::from(presence.away_after_minutes) * 60);
+                let Some(idle) = idle_time() else {
+                    continue;
+                };
+                let away = presence.auto_away && !in_call && idle >= threshold;
+
+                if away && !went_away && status == IdentityStatus::Online {
+                    set_status(&state, IdentityStatus::Away).await;
+                    went_away = true;
+                } else if !away && went_away {
+                    // unless it was changed in the meantime
+                    if status == IdentityStatus::Away {
+                        set_status(&state, IdentityStatus::Online).await;
+                    }
+                    went_away = false;
+                }
+            }
+        }
+    });
+
+    Some(())
+}
@@ -0,0 +1,44 @@
+//! Numbers for the profiling overlay: how often components render and how much memory Uplink uses.
+//! Components call `count_render` at the top of their body. Renders are only counted while the
+//! overlay is open, so the calls are cheap otherwise.
+
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RENDERS: Lazy<Mutex<HashMap<&'static str, u64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn count_render(component: &'static str) {
+    if ENABLED.load(Ordering::Relaxed) {
+        *RENDERS.lock().entry(component).or_default() += 1;
+    }
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// the number of renders per component, most rendered first
+pub fn render_counts() -> Vec<(&'static str, u64)> {
+    let mut counts: Vec<_> = RENDERS
+        .lock()
+        .iter()
+        .map(|(name, count)| (*name, *count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+}
+
+pub fn reset() {
+    RENDERS.lock().clear();
+}
+
+/// the resident memory of the process in bytes
+pub fn memory_usage() -> Option<usize> {
+    memory_stats::memory_stats().map(|stats| stats.physical_mem)
+}
@@ -0,0 +1,75 @@
+//! Speech recognition for the live captions. The speech recognition of the webview is used, which
+//! sends the audio to the online service of the browser engine (Microsoft for WebView2, Apple for
+//! WKWebView) rather than recognizing it on this device. Only the own microphone is recognized, the
+//! audio of the other participants isn't captioned.
+
+use futures::future::LocalBoxFuture;
+use tracing::log;
+
+use super::EvalProvider;
+
+pub enum SpeechEvent {
+    // a finished phrase
+    Phrase(String),
+    Error(String),
+}
+
+pub trait SpeechRecognizer {
+    /// Starts recognizing speech. `language` is a BCP 47 tag, like "en-US".
+    fn start(&mut self, language: &str) -> anyhow::Result<()>;
+    /// Waits for the next phrase. Returns None if the recognizer isn't running.
+    fn recv(&mut self) -> LocalBoxFuture<'_, Option<SpeechEvent>>;
+    fn stop(&mut self);
+}
+
+/// Uses the speech recognition of the webview, which listens to the default microphone and needs a
+/// connection to the speech service of the engine
+pub struct WebSpeechRecognizer {
+    eval: EvalProvider,
+    running: Option<dioxus::prelude::UseEval>,
+}
+
+impl WebSpeechRecognizer {
+    pub fn new(eval: EvalProvider) -> Self {
+        Self {
+            eval,
+            running: None,
+        }
+    }
+}
+
+impl SpeechRecognizer for WebSpeechRecognizer {
+    fn start(&mut self, language: &str) -> anyhow::Result<()> {
+        self.stop();
+        let script = include_str!("./web_speech.js").replace("$LANGUAGE", language);
+        let eval = (self.eval)(&script).map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        self.running = Some(eval);
+        Ok(())
+    }
+
+    fn recv(&mut self) -> LocalBoxFuture<'_, Option<SpeechEvent>> {
+        Box::pin(async move {
+            let value = self.running.as_ref()?.recv().await.ok()?;
+            if let Some(text) = value["text"].as_str() {
+                return Some(SpeechEvent::Phrase(text.trim().to_string()));
+            }
+            Some(SpeechEvent::Error(
+                value["error"].as_str().unwrap_or_default().to_string(),
+            ))
+        })
+    }
+
+    fn stop(&mut self) {
+        if self.running.take().is_some() {
+            if let Err(e) = (self.eval)("if (window.stopCaptions) { window.stopCaptions(); }") {
+                log::error!("failed to stop speech recognition: {e:?}");
+            }
+        }
+    }
+}
+
+impl Drop for WebSpeechRecognizer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
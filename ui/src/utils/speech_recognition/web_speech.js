@@ -0,0 +1,34 @@
+const Recognition = window.SpeechRecognition || window.webkitSpeechRecognition;
+if (!Recognition) {
+  dioxus.send({ error: "unsupported" });
+} else {
+  const recognition = new Recognition();
+  recognition.continuous = true;
+  recognition.interimResults = false;
+  recognition.lang = "$LANGUAGE";
+  recognition.onresult = (event) => {
+    for (let i = event.resultIndex; i < event.results.length; i++) {
+      if (event.results[i].isFinal) {
+        dioxus.send({ text: event.results[i][0].transcript });
+      }
+    }
+  };
+  recognition.onerror = (event) => {
+    if (event.error !== "no-speech") {
+      window.captionsStopped = true;
+      dioxus.send({ error: event.error });
+    }
+  };
+  // the recognition ends after a pause in speech, keep restarting it until the captions are turned off
+  recognition.onend = () => {
+    if (!window.captionsStopped) {
+      recognition.start();
+    }
+  };
+  window.captionsStopped = false;
+  window.stopCaptions = () => {
+    window.captionsStopped = true;
+    recognition.stop();
+  };
+  recognition.start();
+}
@@ -0,0 +1,108 @@
+//! Serves the wallpapers of conversations to the webview through the `wallpaper` protocol, so the
+//! image is loaded by the webview once instead of being inlined in the style of the chat. Only the
+//! wallpapers which were registered with `url` are served, and none larger than `MAX_SIZE`.
+
+use std::{
+    borrow::Cow,
+    collections::hash_map::DefaultHasher,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use dioxus_desktop::wry::{
+    self,
+    http::{Request, Response, StatusCode},
+};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use tracing::log;
+use uuid::Uuid;
+
+pub const PROTOCOL: &str = "wallpaper";
+pub const MAX_SIZE: u64 = 8 * 1024 * 1024;
+
+// the wallpaper of every conversation which is shown, by conversation id
+static WALLPAPERS: Lazy<RwLock<HashMap<Uuid, PathBuf>>> = Lazy::new(Default::default);
+
+/// the URL the wallpaper of the conversation is served at. the version changes with the image, so
+/// the webview doesn't keep showing the previous one
+pub fn url(chat_id: Uuid, path: &Path) -> String {
+    WALLPAPERS.write().insert(chat_id, path.to_path_buf());
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    let version = hasher.finish();
+    // custom protocols are served from https on Windows
+    if cfg!(target_os = "windows") {
+        format!("https://{PROTOCOL}.chat/{chat_id}?v={version}")
+    } else {
+        format!("{PROTOCOL}://chat/{chat_id}?v={version}")
+    }
+}
+
+/// the image types which can be picked as wallpapers
+pub fn mime(path: &Path) -> Option<&'static str> {
+    match path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("png") => Some("image/png"),
+        Some("jpg") | Some("jpeg") => Some("image/jpeg"),
+        Some("webp") => Some("image/webp"),
+        Some("gif") => Some("image/gif"),
+        _ => None,
+    }
+}
+
+/// images which can't be read count as too large as well
+pub fn is_too_large(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len() > MAX_SIZE)
+        .unwrap_or(true)
+}
+
+/// the average color of the image, which the text and the bubbles have to stand out from
+pub fn average_color(path: &Path) -> anyhow::Result<(u8, u8, u8)> {
+    if is_too_large(path) {
+        anyhow::bail!("the wallpaper is larger than {MAX_SIZE} bytes");
+    }
+    let pixel = image::open(path)?.thumbnail_exact(1, 1).to_rgb8();
+    let [r, g, b] = pixel.get_pixel(0, 0).0;
+    Ok((r, g, b))
+}
+
+/// handles the requests of the webview for the `wallpaper` protocol
+pub fn serve(request: &Request<Vec<u8>>) -> wry::Result<Response<Cow<'static, [u8]>>> {
+    let path = request
+        .uri()
+        .path()
+        .rsplit('/')
+        .next()
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .and_then(|id| WALLPAPERS.read().get(&id).cloned());
+    let image = path.and_then(|path| {
+        let mime = mime(&path)?;
+        if is_too_large(&path) {
+            log::warn!("the wallpaper {} is too large", path.display());
+            return None;
+        }
+        match std::fs::read(&path) {
+            Ok(image) => Some((mime, image)),
+            Err(e) => {
+                // the file may have been moved since it was picked
+                log::warn!("failed to read the wallpaper {}: {e}", path.display());
+                None
+            }
+        }
+    });
+    let response = match image {
+        Some((mime, image)) => Response::builder()
+            .header("Content-Type", mime)
+            .body(Cow::from(image))?,
+        None => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Cow::from(Vec::new()))?,
+    };
+    Ok(response)
+}
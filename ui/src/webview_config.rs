@@ -1,6 +1,6 @@
 use crate::utils::get_drag_event::{BLOCK_CANCEL_DRAG_EVENT_FOR_LINUX, DRAG_EVENT};
 use common::STATIC_ARGS;
-use dioxus_desktop::{wry::webview::FileDropEvent, Config};
+use dioxus_desktop::{wry::webview::FileDropEvent, Config, WindowCloseBehaviour};
 use tracing::log;
 
 pub(crate) fn webview_config() -> Config {
@@ -46,7 +46,13 @@ pub(crate) fn webview_config() -> Config {
             }
             true
         })
-        .with_disable_context_menu(false);
+        .with_custom_protocol(
+            crate::utils::wallpaper::PROTOCOL.into(),
+            crate::utils::wallpaper::serve,
+        )
+        .with_disable_context_menu(false)
+        // uplink exits once pending work is done, see shutdown.rs
+        .with_close_behaviour(WindowCloseBehaviour::LastWindowHides);
 
     if cfg!(target_os = "windows") && STATIC_ARGS.production_mode {
         let webview_data_dir = STATIC_ARGS.dot_uplink.join("tmp");